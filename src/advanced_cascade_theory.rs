@@ -2,7 +2,7 @@
 // Based on Percolation Theory, Ising Models, and Non-Equilibrium Statistical Mechanics
 
 use nalgebra::{DMatrix, DVector, Complex};
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, BTreeMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use num_complex::Complex64;
@@ -97,7 +97,7 @@ pub enum ProposalDistribution {
 impl QuantumCascadeField {
     /// Initialize quantum field theory representation of cascade
     pub fn new(dimension: usize) -> Self {
-        let state_vector = DVector::from_fn(dimension, |i| {
+        let state_vector = DVector::from_fn(dimension, |_, _| {
             Complex64::new(1.0 / (dimension as f64).sqrt(), 0.0)
         });
         
@@ -124,7 +124,7 @@ impl QuantumCascadeField {
         let h = self.hamiltonian.read().await;
         
         // U(t) = exp(-iHt/ℏ)
-        let evolution_operator = (-Complex64::i() * time) * h.clone();
+        let evolution_operator = h.clone() * (-Complex64::i() * time);
         let u = evolution_operator.exp();
         
         u * state.clone()
@@ -153,8 +153,10 @@ impl QuantumCascadeField {
         // Linearize around fixed point
         let stability_matrix = rg.linearize_at_fixed_point(&fixed_point);
         
-        // Calculate critical exponents from eigenvalues
-        let eigenvalues = stability_matrix.eigenvalues();
+        // Calculate critical exponents from eigenvalues; none when the Schur decomposition fails
+        let eigenvalues: Vec<Complex64> = stability_matrix.eigenvalues()
+            .map(|ev| ev.iter().cloned().collect())
+            .unwrap_or_default();
         
         CriticalBehavior {
             fixed_point: fixed_point.clone(),
@@ -186,6 +188,7 @@ impl QuantumCascadeField {
         
         // ⟨f|U(t)|i⟩ = ∫ D[φ] exp(iS[φ]/ℏ)
         let paths = pi.generate_paths(initial_state, final_state, time);
+        let path_count = paths.len();
         let mut amplitude = Complex64::new(0.0, 0.0);
         
         for path in paths {
@@ -193,7 +196,7 @@ impl QuantumCascadeField {
             amplitude += Complex64::new(0.0, action).exp();
         }
         
-        amplitude / paths.len() as f64
+        amplitude / path_count as f64
     }
 }
 
@@ -248,7 +251,7 @@ impl NonEquilibriumGreensFunction {
         
         // f(ω) = -iG<(ω) / (G>(ω) - G<(ω))
         let g_l = g_less[(0, 0)];
-        let g_g = g_greater[(0, 0)];
+        let g_g = g_great[(0, 0)];
         
         (-Complex64::i() * g_l / (g_g - g_l)).re
     }
@@ -461,6 +464,153 @@ impl RenormalizationGroup {
     }
 }
 
+const PRICE_HISTORY_CAPACITY: usize = 8192;
+const MIN_RG_BLOCKS: usize = 256;          // Coarse-grained series must keep this many blocks
+const MIN_RG_SAMPLES: usize = 2 * MIN_RG_BLOCKS;
+const HILL_TAIL_FRACTION: f64 = 0.1;       // Top 10% of |returns| form the Pareto tail
+const MIN_HILL_TAIL: usize = 5;
+const RG_FIXED_POINT_TOLERANCE: f64 = 0.05;
+
+/// Eigenvalue of the stability matrix around the Gaussian fixed point.
+/// A Pareto tail with index α perturbs the Gaussian fixed point with eigenvalue y = 1 - α/2,
+/// so the Hill estimate of α stands in for the eigenvalue.
+pub type CriticalExponent = f64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowDirection {
+    TowardsOrder,  // Fluctuations shrink under coarse-graining (mean reversion)
+    TowardsChaos,  // Fluctuations grow faster than diffusive scaling (herding)
+    Fixed,
+}
+
+#[derive(Debug, Clone)]
+pub struct RgFlow {
+    pub critical_exponent: CriticalExponent,
+    pub universality_class: String,
+    pub flow_direction: FlowDirection,
+}
+
+/// Per-symbol price history feeding the renormalization group analysis
+pub struct AdvancedCascadeTheory {
+    price_history: RwLock<HashMap<String, VecDeque<f64>>>,
+}
+
+impl AdvancedCascadeTheory {
+    pub fn new() -> Self {
+        Self {
+            price_history: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn record_price(&self, symbol: &str, price: f64) {
+        let mut history = self.price_history.write().await;
+        let prices = history.entry(symbol.to_string()).or_insert_with(VecDeque::new);
+        prices.push_back(price);
+        if prices.len() > PRICE_HISTORY_CAPACITY {
+            prices.pop_front();
+        }
+    }
+
+    /// Discrete RG flow on the symbol's recorded price series
+    pub async fn compute_rg_flow(&self, symbol: &str) -> RgFlow {
+        let history = self.price_history.read().await;
+        let prices: Vec<f64> = history
+            .get(symbol)
+            .map(|p| p.iter().cloned().collect())
+            .unwrap_or_default();
+        Self::rg_flow_from_prices(&prices)
+    }
+
+    pub fn rg_flow_from_prices(prices: &[f64]) -> RgFlow {
+        let returns: Vec<f64> = prices
+            .windows(2)
+            .filter(|w| w[0] > 0.0 && w[1] > 0.0)
+            .map(|w| (w[1] / w[0]).ln())
+            .collect();
+
+        let tail_index = match hill_estimator(&returns) {
+            Some(alpha) if returns.len() >= MIN_RG_SAMPLES => alpha,
+            _ => {
+                return RgFlow {
+                    critical_exponent: 0.0,
+                    universality_class: "Undetermined".to_string(),
+                    flow_direction: FlowDirection::Fixed,
+                };
+            }
+        };
+
+        let betas = beta_function(&returns);
+        let mean_beta = if betas.is_empty() {
+            0.0
+        } else {
+            betas.iter().sum::<f64>() / betas.len() as f64
+        };
+
+        let flow_direction = if mean_beta > RG_FIXED_POINT_TOLERANCE {
+            FlowDirection::TowardsChaos
+        } else if mean_beta < -RG_FIXED_POINT_TOLERANCE {
+            FlowDirection::TowardsOrder
+        } else {
+            FlowDirection::Fixed
+        };
+
+        let universality_class = if tail_index > 2.0 {
+            "Gaussian"
+        } else if tail_index > 1.0 {
+            "Lévy-stable"
+        } else {
+            "Cauchy"
+        };
+
+        RgFlow {
+            critical_exponent: 1.0 - tail_index / 2.0,
+            universality_class: universality_class.to_string(),
+            flow_direction,
+        }
+    }
+}
+
+/// Hill estimator of the Pareto tail index of |samples|
+pub fn hill_estimator(samples: &[f64]) -> Option<f64> {
+    let mut tail: Vec<f64> = samples.iter().map(|x| x.abs()).filter(|x| *x > 0.0).collect();
+    if tail.len() <= MIN_HILL_TAIL {
+        return None;
+    }
+    tail.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    let k = ((tail.len() as f64 * HILL_TAIL_FRACTION) as usize)
+        .max(MIN_HILL_TAIL)
+        .min(tail.len() - 1);
+    let threshold = tail[k];
+    let mean_log_excess = tail[..k].iter().map(|x| (x / threshold).ln()).sum::<f64>() / k as f64;
+
+    if mean_log_excess > 0.0 {
+        Some(1.0 / mean_log_excess)
+    } else {
+        None
+    }
+}
+
+/// β(l) = dK/dl for the coupling K(l) = ln σ(2^l) - (l/2) ln 2, using block-sum coarse-graining.
+/// The Gaussian fixed point has β = 0 at every scale.
+fn beta_function(returns: &[f64]) -> Vec<f64> {
+    let mut scales = Vec::new();
+    let mut block = 1;
+    while returns.len() / block >= MIN_RG_BLOCKS {
+        let coarse: Vec<f64> = returns.chunks_exact(block).map(|c| c.iter().sum()).collect();
+        let mean = coarse.iter().sum::<f64>() / coarse.len() as f64;
+        let variance = coarse.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / coarse.len() as f64;
+        scales.push(variance.sqrt());
+        block *= 2;
+    }
+
+    scales
+        .windows(2)
+        .filter(|w| w[0] > 0.0)
+        .map(|w| (w[1] / w[0]).log2() - 0.5)
+        .collect()
+}
+
 impl PathIntegralCalculator {
     fn new() -> Self {
         Self {
@@ -597,3 +747,62 @@ impl InformationTheoreticCascade {
         0.8
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // AR(1) returns on deterministic Gaussian noise, compounded into prices
+    fn ar1_prices(phi: f64, n: usize) -> Vec<f64> {
+        let mut state: u64 = 42;
+        let mut uniform = move || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+        };
+        let mut price = 100.0;
+        let mut prev = 0.0;
+        let mut prices = vec![price];
+        for _ in 0..n {
+            let noise = (-2.0 * uniform().ln()).sqrt() * (2.0 * std::f64::consts::PI * uniform()).cos() * 0.01;
+            prev = phi * prev + noise;
+            price *= prev.exp();
+            prices.push(price);
+        }
+        prices
+    }
+
+    #[test]
+    fn test_hill_estimator_recovers_pareto_index() {
+        // Exact Pareto(α = 1.5) quantiles
+        let n = 10_000;
+        let samples: Vec<f64> = (0..n)
+            .map(|i| ((i as f64 + 0.5) / n as f64).powf(-1.0 / 1.5))
+            .collect();
+        let alpha = hill_estimator(&samples).unwrap();
+        assert!((alpha - 1.5).abs() < 0.1, "alpha {}", alpha);
+        assert!(hill_estimator(&[1.0, 2.0]).is_none());
+    }
+
+    #[test]
+    fn test_rg_flow_direction() {
+        let iid = AdvancedCascadeTheory::rg_flow_from_prices(&ar1_prices(0.0, 4096));
+        assert_eq!(iid.flow_direction, FlowDirection::Fixed);
+        assert_eq!(iid.universality_class, "Gaussian");
+        assert!(iid.critical_exponent < 0.0);
+
+        let trending = AdvancedCascadeTheory::rg_flow_from_prices(&ar1_prices(0.5, 4096));
+        assert_eq!(trending.flow_direction, FlowDirection::TowardsChaos);
+
+        let reverting = AdvancedCascadeTheory::rg_flow_from_prices(&ar1_prices(-0.5, 4096));
+        assert_eq!(reverting.flow_direction, FlowDirection::TowardsOrder);
+    }
+
+    #[tokio::test]
+    async fn test_compute_rg_flow_needs_history() {
+        let theory = AdvancedCascadeTheory::new();
+        theory.record_price("ETH", 3000.0).await;
+        let flow = theory.compute_rg_flow("ETH").await;
+        assert_eq!(flow.flow_direction, FlowDirection::Fixed);
+        assert_eq!(flow.universality_class, "Undetermined");
+    }
+}
@@ -2,19 +2,36 @@
 // 93% Success Rate through Advanced On-Chain Analytics
 // Volume, Holder Distribution, and Wallet Activity Analysis
 
-use std::collections::{HashMap, VecDeque, HashSet};
+use std::collections::{BTreeMap, HashMap, VecDeque, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc, Duration};
 use statistical::{mean, standard_deviation, correlation};
+use crate::telemetry;
+use tracing::{debug, info, info_span, warn, Instrument};
+use uuid::Uuid;
+use strike_box::bootstrap::HolderPoint;
 #[cfg(feature = "eip")]
-use ethers::types::{Address, U256, H256};
+use ethers::types::{Address, U256, H256, I256, Filter, Log};
+#[cfg(feature = "eip")]
+use ethers::abi::{ParamType, Token};
+#[cfg(feature = "eip")]
+use ethers::providers::{Http, Middleware, Provider};
 
 const TARGET_SUCCESS_RATE: f64 = 0.93; // 93% success rate target
 const MIN_CONFIDENCE_THRESHOLD: f64 = 0.93;
 const PREDICTION_WINDOW: i64 = 300; // 5 minutes prediction window
 const WALLET_ANALYSIS_DEPTH: usize = 1000; // Analyze top 1000 wallets
+const TOP_WALLET_CHANGES: usize = 10; // Largest wallet flows reported per signal
+const WHALE_SUPPLY_PCT: f64 = 0.01; // Holders with 1%+ of circulating supply count as whales
+const GINI_DELTA_WEIGHT: f64 = 10.0;
+const TOP10_DELTA_WEIGHT: f64 = 5.0;
+const HOLDER_GROWTH_WEIGHT: f64 = 10.0;
+const SQRT_IMPACT_COEFFICIENT: f64 = 0.1; // Trading 1% of pool depth costs ~1% in slippage
+const DEFAULT_MODEL_WEIGHTS_PATH: &str = "amm_fusion_weights.json"; // Overridden by AMM_MODEL_WEIGHTS
+const DEFAULT_FEE_TIER_BPS: u32 = 30; // Uniswap V3 0.3% tier, used when no pool matches
 
 // ==================== AMM PREDICTIVE ENGINE ====================
 
@@ -27,7 +44,10 @@ pub struct AMMPredictiveEngine {
     amm_bots: Vec<Arc<Mutex<AMMBot>>>,
     arbitrage_detector: ArbitrageDetector,
     success_tracker: SuccessRateTracker,
+    swap_feed: Option<Arc<dyn SwapFeed>>,
+    swap_trades: VecDeque<OnChainTrade>, // Swaps from swap_feed in the last SWAP_VOLUME_WINDOW_HOURS, oldest first
     capital: f64,
+    model_weights_path: Option<PathBuf>, // Fitted weights are loaded from and saved to here
 }
 
 impl AMMPredictiveEngine {
@@ -55,74 +75,149 @@ impl AMMPredictiveEngine {
             amm_bots,
             arbitrage_detector: ArbitrageDetector::new(),
             success_tracker: SuccessRateTracker::new(),
+            swap_feed: None,
+            swap_trades: VecDeque::new(),
             capital,
+            model_weights_path: None,
+        }
+    }
+
+    pub fn set_holder_balance_provider(&mut self, provider: Arc<dyn HolderBalanceProvider>) {
+        self.holder_analyzer = HolderDistributionAnalyzer::new().with_balance_provider(provider);
+    }
+
+    pub fn set_transfer_feed(&mut self, feed: Arc<dyn TransferFeed>) {
+        self.wallet_tracker = WalletActivityTracker::new().with_transfer_feed(feed);
+    }
+
+    /// Read traded volumes from the feed's swaps instead of simulating them
+    pub fn set_swap_feed(&mut self, feed: Arc<dyn SwapFeed>) {
+        self.swap_feed = Some(feed);
+        self.swap_trades.clear();
+    }
+
+    /// Load fitted weights from `path` if it exists, and save every later refit there
+    pub fn set_model_weights_path(&mut self, path: PathBuf) -> std::io::Result<()> {
+        let loaded = match self.predictive_model.load_weights(&path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        };
+        self.model_weights_path = Some(path);
+        loaded
+    }
+
+    pub fn fit_predictive_model(&mut self, labeled_history: &[LabeledSample]) -> bool {
+        let fitted = self.predictive_model.fit(labeled_history).is_some();
+        if fitted {
+            self.save_model_weights();
+        }
+        fitted
+    }
+
+    /// Refit on the window-labelled predictions once enough new labels have arrived
+    fn refit_predictive_model(&mut self) -> bool {
+        if !self.predictive_model.needs_refit() || self.predictive_model.refit().is_none() {
+            return false;
+        }
+        self.save_model_weights();
+        true
+    }
+
+    fn save_model_weights(&self) {
+        let Some(path) = &self.model_weights_path else {
+            return;
+        };
+        if let Err(e) = self.predictive_model.save_weights(path) {
+            warn!(path = %path.display(), error = %e, "failed to save fitted model weights");
         }
     }
 
     pub async fn execute_predictive_arbitrage(&mut self) {
-        println!("\n╔═══════════════════════════════════════════════════════════════╗");
-        println!("║         AMM PREDICTIVE ARBITRAGE SYSTEM INITIATED             ║");
-        println!("║              Target Success Rate: 93%                         ║");
-        println!("╚═══════════════════════════════════════════════════════════════╝");
+        if telemetry::pretty_banners() {
+            println!("\n╔═══════════════════════════════════════════════════════════════╗");
+            println!("║         AMM PREDICTIVE ARBITRAGE SYSTEM INITIATED             ║");
+            println!("║              Target Success Rate: 93%                         ║");
+            println!("╚═══════════════════════════════════════════════════════════════╝");
+        }
         
         loop {
-            // Phase 1: Collect on-chain data
-            let on_chain_data = self.collect_on_chain_data().await;
-            
-            // Phase 2: Analyze volume patterns
-            let volume_signal = self.volume_analyzer.predict_movement(&on_chain_data).await;
-            
-            // Phase 3: Analyze holder distribution
-            let holder_signal = self.holder_analyzer.analyze_distribution(&on_chain_data).await;
-            
-            // Phase 4: Track wallet activity
-            let wallet_signal = self.wallet_tracker.analyze_activity(&on_chain_data).await;
-            
-            // Phase 5: Generate prediction with confidence score
-            let prediction = self.predictive_model.generate_prediction(
-                &volume_signal,
-                &holder_signal,
-                &wallet_signal
-            ).await;
-            
-            // Phase 6: Only execute if confidence >= 93%
-            if prediction.confidence >= MIN_CONFIDENCE_THRESHOLD {
-                println!("\n🎯 HIGH CONFIDENCE OPPORTUNITY DETECTED!");
-                println!("   Confidence: {:.1}%", prediction.confidence * 100.0);
-                println!("   Expected Profit: {:.2}%", prediction.expected_profit * 100.0);
-                
-                // Phase 7: Detect arbitrage opportunities
-                let opportunities = self.arbitrage_detector.find_opportunities(&prediction).await;
-                
-                // Phase 8: Execute through AMM bots
-                for opportunity in opportunities {
-                    self.execute_amm_arbitrage(opportunity).await;
-                }
-                
-                // Phase 9: Track success rate
-                self.success_tracker.update(&prediction).await;
-                
-                // Print current success rate
-                let current_rate = self.success_tracker.get_success_rate();
-                println!("📊 Current Success Rate: {:.1}%", current_rate * 100.0);
-                
-                if current_rate < TARGET_SUCCESS_RATE {
-                    println!("⚠️ Adjusting model parameters to improve accuracy...");
-                    self.predictive_model.auto_calibrate(current_rate).await;
-                }
-            } else {
-                println!("⏳ Waiting for high confidence opportunity (current: {:.1}%)", 
-                    prediction.confidence * 100.0);
-            }
-            
+            let span = info_span!("strike", execution_id = %Uuid::new_v4());
+            self.run_prediction_cycle().instrument(span).await;
             tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
         }
     }
 
+    /// One predict-then-execute pass; runs inside a `strike` span so every event it raises,
+    /// including the executions it triggers, carries the prediction's execution_id
+    async fn run_prediction_cycle(&mut self) {
+        // Phase 1: Collect on-chain data
+        self.ingest_swaps(Utc::now()).await;
+        let on_chain_data = self.collect_on_chain_data().await;
+        self.predictive_model.observe_price(on_chain_data.price_usd, on_chain_data.timestamp);
+        self.refit_predictive_model();
+        
+        // Phase 2: Analyze volume patterns
+        let volume_signal = self.volume_analyzer.predict_movement(&on_chain_data).await;
+        
+        // Phase 3: Analyze holder distribution
+        let holder_signal = self.holder_analyzer.analyze_distribution(&on_chain_data).await;
+        
+        // Phase 4: Track wallet activity
+        let wallet_signal = self.wallet_tracker.analyze_activity(&on_chain_data).await;
+        
+        // Phase 5: Generate prediction with confidence score
+        let prediction = self.predictive_model.generate_prediction(
+            &volume_signal,
+            &holder_signal,
+            &wallet_signal
+        ).await;
+        self.predictive_model.track_prediction(&prediction, on_chain_data.price_usd, on_chain_data.timestamp);
+        
+        // Phase 6: Only execute if confidence >= 93%
+        if prediction.confidence < MIN_CONFIDENCE_THRESHOLD {
+            debug!(
+                confidence = prediction.confidence,
+                threshold = MIN_CONFIDENCE_THRESHOLD,
+                "waiting for a high-confidence prediction"
+            );
+            return;
+        }
+        info!(
+            confidence = prediction.confidence,
+            expected_profit_pct = prediction.expected_profit * 100.0,
+            direction = ?prediction.direction,
+            "high-confidence prediction"
+        );
+        
+        // Phase 7: Detect arbitrage opportunities
+        let opportunities = self.arbitrage_detector.find_opportunities(&prediction).await;
+        
+        // Phase 8: Execute through AMM bots
+        for opportunity in opportunities {
+            self.execute_amm_arbitrage(opportunity).await;
+        }
+        
+        // Phase 9: Track success rate
+        self.success_tracker.update(&prediction).await;
+        let current_rate = self.success_tracker.get_success_rate();
+        if current_rate < TARGET_SUCCESS_RATE {
+            info!(
+                success_rate = current_rate,
+                target = TARGET_SUCCESS_RATE,
+                "success rate below target, recalibrating"
+            );
+            self.predictive_model.auto_calibrate(current_rate).await;
+        } else {
+            debug!(success_rate = current_rate, "success rate on target");
+        }
+    }
+
     async fn collect_on_chain_data(&self) -> OnChainData {
         OnChainData {
             block_number: self.get_current_block().await,
             timestamp: Utc::now(),
+            token_address: self.get_target_token().await,
+            price_usd: self.fetch_token_price().await,
             
             // Volume data
             volume_24h: self.fetch_24h_volume().await,
@@ -154,12 +249,40 @@ impl AMMPredictiveEngine {
         }
     }
 
-    async fn execute_amm_arbitrage(&mut self, opportunity: ArbitrageOpportunity) {
-        println!("\n💰 Executing AMM Arbitrage:");
-        println!("   Type: {:?}", opportunity.arb_type);
-        println!("   Pools: {} -> {}", opportunity.source_pool, opportunity.target_pool);
-        println!("   Size: ${:.2}", opportunity.optimal_size);
-        println!("   Expected Profit: ${:.2}", opportunity.expected_profit);
+    /// Pull new swaps from the feed and drop those older than the longest volume window
+    async fn ingest_swaps(&mut self, now: DateTime<Utc>) {
+        let Some(feed) = self.swap_feed.clone() else {
+            return;
+        };
+        let cutoff = now - Duration::hours(SWAP_VOLUME_WINDOW_HOURS);
+        let since = self.swap_trades.back().map_or(cutoff, |t| t.timestamp);
+        self.swap_trades.extend(feed.fetch_swaps(since).await);
+        while self.swap_trades.front().is_some_and(|t| t.timestamp < cutoff) {
+            self.swap_trades.pop_front();
+        }
+    }
+
+    /// USD volume swapped within `window` of now; None without a swap feed
+    fn swap_volume(&self, window: Duration) -> Option<f64> {
+        self.swap_feed.as_ref()?;
+        let cutoff = Utc::now() - window;
+        Some(self.swap_trades.iter().filter(|t| t.timestamp >= cutoff).map(|t| t.volume_usd).sum())
+    }
+
+    /// Executes `opportunity` on the best-suited bot inside an `execution` span
+    async fn execute_amm_arbitrage(&mut self, opportunity: ArbitrageOpportunity) -> ArbitrageResult {
+        let span = info_span!(
+            "execution",
+            arb_type = ?opportunity.arb_type,
+            source_pool = %opportunity.source_pool,
+            target_pool = %opportunity.target_pool,
+            size_usd = opportunity.optimal_size,
+        );
+        self.run_amm_arbitrage(opportunity).instrument(span).await
+    }
+
+    async fn run_amm_arbitrage(&mut self, opportunity: ArbitrageOpportunity) -> ArbitrageResult {
+        info!(expected_profit_usd = opportunity.expected_profit, "executing arbitrage");
         
         // Select best bot for this opportunity
         let bot_index = self.select_optimal_bot(&opportunity).await;
@@ -169,13 +292,16 @@ impl AMMPredictiveEngine {
         let result = bot_guard.execute_arbitrage(opportunity).await;
         
         if result.success {
-            println!("✅ Arbitrage Successful!");
-            println!("   Actual Profit: ${:.2}", result.actual_profit);
-            println!("   Execution Time: {}ms", result.execution_time_ms);
+            info!(
+                actual_profit_usd = result.actual_profit,
+                execution_time_ms = result.execution_time_ms,
+                "arbitrage succeeded"
+            );
             self.capital += result.actual_profit;
         } else {
-            println!("❌ Arbitrage Failed: {}", result.error_msg);
+            warn!(error = %result.error_msg, "arbitrage failed");
         }
+        result
     }
 
     async fn select_optimal_bot(&self, opportunity: &ArbitrageOpportunity) -> usize {
@@ -191,9 +317,22 @@ impl AMMPredictiveEngine {
 
     // Simulated data fetching functions
     async fn get_current_block(&self) -> u64 { 18500000 }
-    async fn fetch_24h_volume(&self) -> f64 { 1_000_000.0 + rand::random::<f64>() * 500_000.0 }
-    async fn fetch_1h_volume(&self) -> f64 { 50_000.0 + rand::random::<f64>() * 25_000.0 }
-    async fn fetch_5m_volume(&self) -> f64 { 5_000.0 + rand::random::<f64>() * 2_500.0 }
+    async fn get_target_token(&self) -> Address { Address::zero() }
+    async fn fetch_token_price(&self) -> f64 {
+        self.swap_trades.back()
+            .filter(|t| t.amount0 != 0.0)
+            .map(|t| t.volume_usd / t.amount0.abs())
+            .unwrap_or_else(|| 1.0 + rand::random::<f64>() * 0.02)
+    }
+    async fn fetch_24h_volume(&self) -> f64 {
+        self.swap_volume(Duration::hours(24)).unwrap_or_else(|| 1_000_000.0 + rand::random::<f64>() * 500_000.0)
+    }
+    async fn fetch_1h_volume(&self) -> f64 {
+        self.swap_volume(Duration::hours(1)).unwrap_or_else(|| 50_000.0 + rand::random::<f64>() * 25_000.0)
+    }
+    async fn fetch_5m_volume(&self) -> f64 {
+        self.swap_volume(Duration::minutes(5)).unwrap_or_else(|| 5_000.0 + rand::random::<f64>() * 2_500.0)
+    }
     async fn calculate_volume_velocity(&self) -> f64 { 1.2 + rand::random::<f64>() * 0.5 }
     async fn fetch_holder_count(&self) -> u64 { 10000 + rand::random::<u64>() % 5000 }
     async fn fetch_whale_count(&self) -> u64 { 50 + rand::random::<u64>() % 25 }
@@ -371,105 +510,238 @@ impl VolumePredictor {
 
 // ==================== HOLDER DISTRIBUTION ANALYZER ====================
 
+#[async_trait::async_trait]
+pub trait HolderBalanceProvider: Send + Sync + std::fmt::Debug {
+    /// Top-N holder balances plus total supply for `token`
+    async fn fetch_balances(&self, token: Address) -> HolderBalances;
+}
+
+#[derive(Debug, Clone)]
+pub struct HolderBalances {
+    pub balances: Vec<(Address, f64)>,
+    pub total_supply: f64,
+    pub holder_count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HolderAnalyzerConfig {
+    pub exchange_wallets: HashMap<Address, String>, // Address -> exchange label, excluded from concentration
+    pub snapshots_per_token: usize,
+}
+
+impl Default for HolderAnalyzerConfig {
+    fn default() -> Self {
+        Self {
+            exchange_wallets: HashMap::new(),
+            snapshots_per_token: 1000,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HolderDistributionAnalyzer {
-    holder_snapshots: VecDeque<HolderSnapshot>,
+    config: HolderAnalyzerConfig,
+    holder_snapshots: HashMap<Address, VecDeque<HolderSnapshot>>,
+    balance_provider: Option<Arc<dyn HolderBalanceProvider>>,
     whale_tracker: WhaleTracker,
     distribution_model: DistributionModel,
 }
 
 impl HolderDistributionAnalyzer {
     pub fn new() -> Self {
+        Self::with_config(HolderAnalyzerConfig::default())
+    }
+
+    pub fn with_config(config: HolderAnalyzerConfig) -> Self {
         Self {
-            holder_snapshots: VecDeque::with_capacity(1000),
+            config,
+            holder_snapshots: HashMap::new(),
+            balance_provider: None,
             whale_tracker: WhaleTracker::new(),
             distribution_model: DistributionModel::new(),
         }
     }
 
+    pub fn with_balance_provider(mut self, provider: Arc<dyn HolderBalanceProvider>) -> Self {
+        self.balance_provider = Some(provider);
+        self
+    }
+
     pub async fn analyze_distribution(&mut self, data: &OnChainData) -> HolderSignal {
-        // Create holder snapshot
-        let snapshot = HolderSnapshot {
-            timestamp: data.timestamp,
-            total_holders: data.total_holders,
-            whale_holders: data.whale_holders,
-            new_holders: data.new_holders_24h,
-            concentration: data.holder_concentration,
-            gini_coefficient: self.calculate_gini_coefficient(data),
+        let mut signal = match self.balance_provider.clone() {
+            Some(provider) => {
+                let balances = provider.fetch_balances(data.token_address).await;
+                self.ingest_balances(data.token_address, &balances, data.timestamp)
+            }
+            None => {
+                // Without balances the top-10 concentration doubles as a Gini proxy
+                let snapshot = HolderSnapshot {
+                    timestamp: data.timestamp,
+                    total_holders: data.total_holders,
+                    whale_holders: data.whale_holders,
+                    new_holders: data.new_holders_24h,
+                    concentration: data.holder_concentration,
+                    top50_concentration: data.holder_concentration,
+                    gini_coefficient: data.holder_concentration,
+                };
+                self.record_snapshot(data.token_address, snapshot)
+            }
         };
 
-        self.holder_snapshots.push_back(snapshot);
-        if self.holder_snapshots.len() > 1000 {
-            self.holder_snapshots.pop_front();
-        }
-
         // Analyze whale behavior
         let whale_analysis = self.whale_tracker.analyze_whales(data).await;
-        
-        // Calculate distribution metrics
-        let decentralization_score = self.calculate_decentralization_score(&snapshot);
-        let holder_growth_rate = self.calculate_holder_growth_rate();
+        let decentralization_score = 1.0 - signal.gini;
         let smart_money_confidence = self.analyze_smart_money_holdings(data);
-        
-        // Detect unusual patterns
-        let unusual_accumulation = self.detect_unusual_accumulation();
-        let distribution_phase = self.identify_distribution_phase();
-        
-        HolderSignal {
-            whale_sentiment: whale_analysis.sentiment,
-            accumulation_strength: whale_analysis.accumulation_score,
-            distribution_risk: self.assess_distribution_risk(&snapshot),
-            holder_quality_score: self.calculate_holder_quality_score(data),
-            smart_money_flow_direction: if smart_money_confidence > 0.5 { 
-                FlowDirection::In 
-            } else { 
-                FlowDirection::Out 
-            },
-            concentration_change: self.calculate_concentration_change(),
-            retail_participation: self.estimate_retail_participation(&snapshot),
-            confidence: self.calculate_holder_confidence(&whale_analysis, decentralization_score),
-        }
+
+        signal.whale_sentiment = whale_analysis.sentiment.clone();
+        signal.accumulation_strength = whale_analysis.accumulation_score;
+        signal.holder_quality_score = self.calculate_holder_quality_score(data);
+        signal.smart_money_flow_direction = if smart_money_confidence > 0.5 {
+            FlowDirection::In
+        } else {
+            FlowDirection::Out
+        };
+        signal.confidence = self.calculate_holder_confidence(&whale_analysis, decentralization_score);
+        signal
     }
 
-    fn calculate_gini_coefficient(&self, data: &OnChainData) -> f64 {
-        // Gini coefficient for wealth distribution (0 = perfect equality, 1 = perfect inequality)
-        0.65 + rand::random::<f64>() * 0.2
+    /// Record a holder count and top-10 share where no balances are at hand; as in
+    /// analyze_distribution, the top-10 share doubles as the Gini proxy
+    pub fn record_holder_count(
+        &mut self,
+        token: Address,
+        total_holders: u64,
+        top10_concentration: f64,
+        timestamp: DateTime<Utc>,
+    ) -> HolderSignal {
+        let snapshot = HolderSnapshot {
+            timestamp,
+            total_holders,
+            whale_holders: 0,
+            new_holders: 0,
+            concentration: top10_concentration,
+            top50_concentration: top10_concentration,
+            gini_coefficient: top10_concentration,
+        };
+        self.record_snapshot(token, snapshot)
     }
 
-    fn calculate_decentralization_score(&self, snapshot: &HolderSnapshot) -> f64 {
-        // Higher score = more decentralized
-        1.0 - snapshot.gini_coefficient
+    /// Every token's snapshots, oldest first, keyed by 0x-prefixed address for a bootstrap file
+    pub fn holder_history(&self) -> BTreeMap<String, Vec<HolderPoint>> {
+        self.holder_snapshots
+            .iter()
+            .map(|(token, history)| (format!("{:?}", token), history.iter().map(HolderPoint::from).collect()))
+            .collect()
     }
 
-    fn calculate_holder_growth_rate(&self) -> f64 {
-        if self.holder_snapshots.len() < 2 {
-            return 0.0;
+    /// Replace the snapshots of each token in `history`, keeping the newest `snapshots_per_token`;
+    /// keys that aren't addresses are skipped. Returns how many tokens were installed.
+    pub fn install_holder_history(&mut self, history: &BTreeMap<String, Vec<HolderPoint>>) -> usize {
+        let mut installed = 0;
+        for (token, points) in history {
+            let Ok(address) = token.parse::<Address>() else {
+                warn!(token = %token, "bootstrap holder series has no valid address, skipped");
+                continue;
+            };
+            let skip = points.len().saturating_sub(self.config.snapshots_per_token);
+            self.holder_snapshots.insert(address, points.iter().skip(skip).map(HolderSnapshot::from).collect());
+            installed += 1;
         }
-
-        let recent = &self.holder_snapshots[self.holder_snapshots.len() - 1];
-        let previous = &self.holder_snapshots[self.holder_snapshots.len() - 2];
-        
-        (recent.total_holders as f64 - previous.total_holders as f64) / previous.total_holders as f64
+        installed
     }
 
-    fn analyze_smart_money_holdings(&self, data: &OnChainData) -> f64 {
-        // Analyze known smart money wallets
-        0.6 + rand::random::<f64>() * 0.3
-    }
+    /// Build a snapshot from raw balances (exchange wallets excluded) and compare it to the previous one
+    pub fn ingest_balances(
+        &mut self,
+        token: Address,
+        balances: &HolderBalances,
+        timestamp: DateTime<Utc>,
+    ) -> HolderSignal {
+        let mut holder_balances: Vec<f64> = balances.balances
+            .iter()
+            .filter(|(address, _)| !self.config.exchange_wallets.contains_key(address))
+            .map(|(_, balance)| *balance)
+            .collect();
+        let exchange_balance: f64 = balances.balances
+            .iter()
+            .filter(|(address, _)| self.config.exchange_wallets.contains_key(address))
+            .map(|(_, balance)| *balance)
+            .sum();
+        let circulating_supply = (balances.total_supply - exchange_balance).max(0.0);
+
+        holder_balances.sort_by(|a, b| b.total_cmp(a));
+        let top_share = |n: usize| -> f64 {
+            if circulating_supply <= 0.0 {
+                return 0.0;
+            }
+            holder_balances.iter().take(n).sum::<f64>() / circulating_supply
+        };
 
-    fn detect_unusual_accumulation(&self) -> bool {
-        rand::random::<f64>() > 0.7
+        let snapshot = HolderSnapshot {
+            timestamp,
+            total_holders: balances.holder_count,
+            whale_holders: holder_balances
+                .iter()
+                .filter(|b| circulating_supply > 0.0 && **b / circulating_supply >= WHALE_SUPPLY_PCT)
+                .count() as u64,
+            new_holders: 0,
+            concentration: top_share(10),
+            top50_concentration: top_share(50),
+            gini_coefficient: gini_coefficient(&holder_balances),
+        };
+        self.record_snapshot(token, snapshot)
     }
 
-    fn identify_distribution_phase(&self) -> DistributionPhase {
-        match rand::random::<f64>() {
-            x if x < 0.25 => DistributionPhase::Accumulation,
-            x if x < 0.5 => DistributionPhase::Markup,
-            x if x < 0.75 => DistributionPhase::Distribution,
-            _ => DistributionPhase::Markdown,
+    fn record_snapshot(&mut self, token: Address, mut snapshot: HolderSnapshot) -> HolderSignal {
+        let history = self.holder_snapshots.entry(token).or_default();
+        let previous = history.back().cloned();
+        if let Some(prev) = &previous {
+            snapshot.new_holders = snapshot.total_holders.saturating_sub(prev.total_holders);
+        }
+
+        let (gini_delta, top10_delta, holder_growth_rate) = match &previous {
+            Some(prev) => (
+                snapshot.gini_coefficient - prev.gini_coefficient,
+                snapshot.concentration - prev.concentration,
+                if prev.total_holders > 0 {
+                    (snapshot.total_holders as f64 - prev.total_holders as f64) / prev.total_holders as f64
+                } else {
+                    0.0
+                },
+            ),
+            None => (0.0, 0.0, 0.0),
+        };
+
+        history.push_back(snapshot.clone());
+        if history.len() > self.config.snapshots_per_token {
+            history.pop_front();
+        }
+
+        HolderSignal {
+            whale_sentiment: WhaleSentiment::Neutral,
+            accumulation_strength: 0.0,
+            distribution_risk: self.assess_distribution_risk(&snapshot),
+            holder_quality_score: 0.0,
+            smart_money_flow_direction: FlowDirection::Neutral,
+            concentration_change: top10_delta,
+            retail_participation: self.estimate_retail_participation(&snapshot),
+            confidence: 0.0,
+            gini: snapshot.gini_coefficient,
+            gini_delta,
+            top10_pct: snapshot.concentration,
+            top10_delta,
+            top50_pct: snapshot.top50_concentration,
+            holder_count: snapshot.total_holders,
+            holder_growth_rate,
+            score: distribution_score(gini_delta, top10_delta, holder_growth_rate),
         }
     }
 
+    fn analyze_smart_money_holdings(&self, data: &OnChainData) -> f64 {
+        // Analyze known smart money wallets
+        0.6 + rand::random::<f64>() * 0.3
+    }
+
     fn assess_distribution_risk(&self, snapshot: &HolderSnapshot) -> f64 {
         // Risk of large holders dumping
         snapshot.concentration * 0.5 + (1.0 - snapshot.gini_coefficient) * 0.5
@@ -480,20 +752,12 @@ impl HolderDistributionAnalyzer {
         0.7 + rand::random::<f64>() * 0.2
     }
 
-    fn calculate_concentration_change(&self) -> f64 {
-        if self.holder_snapshots.len() < 24 {
-            return 0.0;
-        }
-
-        let recent = &self.holder_snapshots[self.holder_snapshots.len() - 1];
-        let day_ago = &self.holder_snapshots[self.holder_snapshots.len() - 24];
-        
-        recent.concentration - day_ago.concentration
-    }
-
     fn estimate_retail_participation(&self, snapshot: &HolderSnapshot) -> f64 {
         // Estimate percentage of retail holders
-        (snapshot.total_holders - snapshot.whale_holders) as f64 / snapshot.total_holders as f64
+        if snapshot.total_holders == 0 {
+            return 0.0;
+        }
+        snapshot.total_holders.saturating_sub(snapshot.whale_holders) as f64 / snapshot.total_holders as f64
     }
 
     fn calculate_holder_confidence(&self, whale_analysis: &WhaleAnalysis, decentralization: f64) -> f64 {
@@ -501,102 +765,284 @@ impl HolderDistributionAnalyzer {
     }
 }
 
+/// Gini coefficient of balances (0 = perfect equality, 1 = perfect inequality)
+pub fn gini_coefficient(balances: &[f64]) -> f64 {
+    let n = balances.len();
+    let total: f64 = balances.iter().sum();
+    if n == 0 || total <= 0.0 {
+        return 0.0;
+    }
+
+    let mut sorted = balances.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let weighted: f64 = sorted.iter()
+        .enumerate()
+        .map(|(i, x)| (i + 1) as f64 * x)
+        .sum();
+
+    (2.0 * weighted) / (n as f64 * total) - (n as f64 + 1.0) / n as f64
+}
+
+/// Maps improving distribution (falling Gini/concentration, growing holders) to (-1, 1)
+fn distribution_score(gini_delta: f64, top10_delta: f64, holder_growth_rate: f64) -> f64 {
+    (-GINI_DELTA_WEIGHT * gini_delta - TOP10_DELTA_WEIGHT * top10_delta
+        + HOLDER_GROWTH_WEIGHT * holder_growth_rate)
+        .tanh()
+}
+
 // ==================== WALLET ACTIVITY TRACKER ====================
 
+#[async_trait::async_trait]
+pub trait TransferFeed: Send + Sync + std::fmt::Debug {
+    /// Token transfer events for `token` at or after `since`, oldest first
+    async fn fetch_transfers(&self, token: Address, since: DateTime<Utc>) -> Vec<TransferEvent>;
+}
+
+#[derive(Debug, Clone)]
+pub struct WalletTrackerConfig {
+    pub max_wallets: usize,            // Wallets kept, lowest volume evicted first
+    pub lookback: Duration,            // Window for accumulation/distribution signals
+    pub early_entry_window: Duration,  // Buys this soon after a token first trades count as early
+    pub max_single_wallet_pct: f64,    // Concentration cap for healthy accumulation
+}
+
+impl Default for WalletTrackerConfig {
+    fn default() -> Self {
+        let strike_box_validation = strike_box::TokenValidationConfig::default();
+        Self {
+            max_wallets: WALLET_ANALYSIS_DEPTH,
+            lookback: Duration::hours(1),
+            early_entry_window: Duration::hours(24),
+            max_single_wallet_pct: strike_box_validation.single_wallet_max_pct
+                .to_string()
+                .parse::<f64>()
+                .unwrap_or(0.20),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WalletActivityTracker {
-    tracked_wallets: HashMap<Address, WalletProfile>,
+    config: WalletTrackerConfig,
+    wallets: HashMap<Address, WalletLedger>,
+    token_first_seen: HashMap<Address, DateTime<Utc>>,
+    token_supply: HashMap<Address, f64>,
+    recent_transfers: VecDeque<TransferEvent>,
+    last_ingested: HashMap<Address, DateTime<Utc>>,
+    transfer_feed: Option<Arc<dyn TransferFeed>>,
     activity_history: VecDeque<WalletActivity>,
     pattern_detector: WalletPatternDetector,
 }
 
 impl WalletActivityTracker {
     pub fn new() -> Self {
+        Self::with_config(WalletTrackerConfig::default())
+    }
+
+    pub fn with_config(config: WalletTrackerConfig) -> Self {
         Self {
-            tracked_wallets: HashMap::new(),
+            config,
+            wallets: HashMap::new(),
+            token_first_seen: HashMap::new(),
+            token_supply: HashMap::new(),
+            recent_transfers: VecDeque::with_capacity(10000),
+            last_ingested: HashMap::new(),
+            transfer_feed: None,
             activity_history: VecDeque::with_capacity(10000),
             pattern_detector: WalletPatternDetector::new(),
         }
     }
 
+    pub fn with_transfer_feed(mut self, feed: Arc<dyn TransferFeed>) -> Self {
+        self.transfer_feed = Some(feed);
+        self
+    }
+
+    pub fn set_total_supply(&mut self, token: Address, supply: f64) {
+        self.token_supply.insert(token, supply);
+    }
+
     pub async fn analyze_activity(&mut self, data: &OnChainData) -> WalletSignal {
-        // Track top wallets
-        let top_wallets = self.fetch_top_wallets().await;
-        
-        // Analyze each wallet's behavior
-        let mut wallet_scores = Vec::new();
-        for wallet in top_wallets {
-            let profile = self.analyze_wallet_profile(&wallet).await;
-            wallet_scores.push(profile.predictive_score);
-            self.tracked_wallets.insert(wallet.address, profile);
+        // Pull new transfers for the token since the last ingestion
+        if let Some(feed) = self.transfer_feed.clone() {
+            let since = self.last_ingested
+                .get(&data.token_address)
+                .copied()
+                .unwrap_or(data.timestamp - self.config.lookback);
+            let first_fetch = !self.last_ingested.contains_key(&data.token_address);
+            for transfer in feed.fetch_transfers(data.token_address, since).await {
+                if first_fetch || transfer.timestamp > since {
+                    self.ingest_transfer(transfer);
+                }
+            }
         }
 
+        let mut signal = self.token_signal(data.token_address, data.timestamp);
+
         // Detect coordinated activity
         let coordination = self.detect_coordinated_activity();
         
         // Analyze transaction patterns
         let tx_patterns = self.pattern_detector.detect_patterns(&self.activity_history);
-        
-        // Calculate aggregate metrics
-        let smart_money_movement = self.track_smart_money_movement();
-        let insider_activity_score = self.detect_insider_activity();
-        let wash_trading_probability = self.detect_wash_trading();
-        
-        WalletSignal {
-            active_whale_count: data.active_wallets_1h,
-            net_flow_direction: self.calculate_net_flow_direction(),
-            smart_money_confidence: smart_money_movement.confidence,
-            coordination_detected: coordination.is_coordinated,
-            insider_probability: insider_activity_score,
-            wash_trading_risk: wash_trading_probability,
-            predictive_power: self.calculate_predictive_power(&wallet_scores),
-            confidence: self.calculate_wallet_signal_confidence(&tx_patterns),
-        }
-    }
-
-    async fn fetch_top_wallets(&self) -> Vec<WalletInfo> {
-        // Fetch top 1000 wallets by balance
-        let mut wallets = Vec::new();
-        for i in 0..100 {
-            wallets.push(WalletInfo {
-                address: Address::random(),
-                balance: 1_000_000.0 * rand::random::<f64>(),
-                transaction_count: 100 + rand::random::<u64>() % 1000,
-                first_seen: Utc::now() - Duration::days(rand::random::<i64>() % 365),
-            });
+
+        signal.active_whale_count = data.active_wallets_1h;
+        signal.coordination_detected = coordination.is_coordinated;
+        signal.insider_probability = self.detect_insider_activity();
+        signal.wash_trading_risk = self.detect_wash_trading();
+        signal.confidence = self.calculate_wallet_signal_confidence(&tx_patterns);
+        signal
+    }
+
+    /// Apply a transfer to wallet ledgers using average-cost accounting
+    pub fn ingest_transfer(&mut self, transfer: TransferEvent) {
+        let first_seen = *self.token_first_seen
+            .entry(transfer.token)
+            .or_insert(transfer.timestamp);
+        let early = transfer.timestamp - first_seen <= self.config.early_entry_window;
+        let notional = transfer.amount * transfer.price_usd;
+
+        // Seller side (mints come from the zero address)
+        if transfer.from != Address::zero() {
+            let ledger = self.touch_wallet(transfer.from, transfer.timestamp);
+            ledger.record_sell(transfer.token, transfer.amount, transfer.price_usd);
         }
-        wallets
+
+        // Buyer side (burns go to the zero address); minted tokens carry no cost basis
+        if transfer.to != Address::zero() {
+            let cost_usd = if transfer.from == Address::zero() { 0.0 } else { notional };
+            let ledger = self.touch_wallet(transfer.to, transfer.timestamp);
+            ledger.record_buy(transfer.token, transfer.amount, cost_usd, early);
+        }
+
+        self.last_ingested.insert(transfer.token, transfer.timestamp);
+        self.recent_transfers.push_back(transfer);
+        while self.recent_transfers.len() > 10000 {
+            self.recent_transfers.pop_front();
+        }
+
+        self.evict_wallets();
     }
 
-    async fn analyze_wallet_profile(&self, wallet: &WalletInfo) -> WalletProfile {
-        WalletProfile {
-            address: wallet.address,
-            wallet_type: self.classify_wallet_type(wallet),
-            trading_style: self.identify_trading_style(wallet),
-            success_rate: 0.5 + rand::random::<f64>() * 0.4,
-            avg_holding_time: Duration::hours(rand::random::<i64>() % 720),
-            profit_loss_ratio: 1.5 + rand::random::<f64>(),
-            predictive_score: 0.6 + rand::random::<f64>() * 0.35,
-            last_activity: Utc::now(),
+    /// Smart-money score in [0, 1] from realized profitability, hit rate and early entries
+    pub fn smart_money_score(&self, wallet: &Address) -> f64 {
+        self.wallets.get(wallet).map(|l| l.smart_money_score()).unwrap_or(0.0)
+    }
+
+    pub fn token_signal(&self, token: Address, as_of: DateTime<Utc>) -> WalletSignal {
+        let window_start = as_of - self.config.lookback;
+
+        // Net USD flow per wallet inside the lookback window
+        let mut net_flows: HashMap<Address, f64> = HashMap::new();
+        for transfer in self.recent_transfers.iter()
+            .filter(|t| t.token == token && t.timestamp >= window_start && t.timestamp <= as_of)
+        {
+            let notional = transfer.amount * transfer.price_usd;
+            if transfer.from != Address::zero() {
+                *net_flows.entry(transfer.from).or_insert(0.0) -= notional;
+            }
+            if transfer.to != Address::zero() {
+                *net_flows.entry(transfer.to).or_insert(0.0) += notional;
+            }
+        }
+
+        let gross_flow: f64 = net_flows.values().map(|f| f.abs()).sum();
+        let mut weighted_inflow = 0.0;
+        let mut weighted_outflow = 0.0;
+        let mut smart_money_net_flow = 0.0;
+        let mut changes = Vec::with_capacity(net_flows.len());
+
+        for (wallet, net_flow) in &net_flows {
+            let score = self.smart_money_score(wallet);
+            smart_money_net_flow += score * net_flow;
+            if *net_flow > 0.0 {
+                weighted_inflow += score * net_flow;
+            } else {
+                weighted_outflow += score * net_flow.abs();
+            }
+            changes.push(WalletChange {
+                wallet: *wallet,
+                net_flow_usd: *net_flow,
+                smart_money_score: score,
+            });
+        }
+
+        // Accumulation only counts while supply stays below the single-wallet cap
+        let accumulation_score = if gross_flow > 0.0
+            && self.max_wallet_share(token) <= self.config.max_single_wallet_pct
+        {
+            weighted_inflow / gross_flow
+        } else {
+            0.0
+        };
+        let distribution_score = if gross_flow > 0.0 {
+            weighted_outflow / gross_flow
+        } else {
+            0.0
+        };
+
+        changes.sort_by(|a, b| b.net_flow_usd.abs().total_cmp(&a.net_flow_usd.abs()));
+        changes.truncate(TOP_WALLET_CHANGES);
+
+        let scores: Vec<f64> = net_flows.keys().map(|w| self.smart_money_score(w)).collect();
+
+        WalletSignal {
+            active_whale_count: net_flows.len() as u64,
+            net_flow_direction: if smart_money_net_flow > 0.0 {
+                FlowDirection::In
+            } else if smart_money_net_flow < 0.0 {
+                FlowDirection::Out
+            } else {
+                FlowDirection::Neutral
+            },
+            smart_money_confidence: (accumulation_score - distribution_score).abs(),
+            coordination_detected: false,
+            insider_probability: 0.0,
+            wash_trading_risk: 0.0,
+            predictive_power: self.calculate_predictive_power(&scores),
+            confidence: 0.0,
+            accumulation_score,
+            distribution_score,
+            smart_money_net_flow,
+            top_wallet_changes: changes,
         }
     }
 
-    fn classify_wallet_type(&self, wallet: &WalletInfo) -> WalletType {
-        match wallet.balance {
-            b if b > 10_000_000.0 => WalletType::Whale,
-            b if b > 1_000_000.0 => WalletType::Shark,
-            b if b > 100_000.0 => WalletType::Dolphin,
-            _ => WalletType::Fish,
+    fn max_wallet_share(&self, token: Address) -> f64 {
+        let balances: Vec<f64> = self.wallets.values()
+            .filter_map(|l| l.positions.get(&token))
+            .map(|p| p.quantity)
+            .collect();
+        let supply = self.token_supply
+            .get(&token)
+            .copied()
+            .unwrap_or_else(|| balances.iter().sum());
+        if supply <= 0.0 {
+            return 0.0;
         }
+        balances.iter().fold(0.0_f64, |a, &b| a.max(b)) / supply
     }
 
-    fn identify_trading_style(&self, _wallet: &WalletInfo) -> TradingStyle {
-        match rand::random::<f64>() {
-            x if x < 0.2 => TradingStyle::Scalper,
-            x if x < 0.4 => TradingStyle::DayTrader,
-            x if x < 0.6 => TradingStyle::SwingTrader,
-            x if x < 0.8 => TradingStyle::Hodler,
-            _ => TradingStyle::Arbitrageur,
+    fn touch_wallet(&mut self, wallet: Address, timestamp: DateTime<Utc>) -> &mut WalletLedger {
+        let ledger = self.wallets.entry(wallet).or_insert_with(|| WalletLedger::new(timestamp));
+        if timestamp > ledger.last_seen {
+            ledger.last_seen = timestamp;
+        }
+        ledger
+    }
+
+    fn evict_wallets(&mut self) {
+        // Keep the top wallets by volume; among equal volumes the least recently active go first
+        while self.wallets.len() > self.config.max_wallets {
+            let smallest = self.wallets
+                .iter()
+                .min_by(|(_, a), (_, b)| {
+                    a.volume_usd.total_cmp(&b.volume_usd).then(a.last_seen.cmp(&b.last_seen))
+                })
+                .map(|(w, _)| *w);
+            match smallest {
+                Some(wallet) => { self.wallets.remove(&wallet); }
+                None => break,
+            }
         }
     }
 
@@ -608,14 +1054,6 @@ impl WalletActivityTracker {
         }
     }
 
-    fn track_smart_money_movement(&self) -> SmartMoneyFlow {
-        SmartMoneyFlow {
-            direction: if rand::random::<bool>() { FlowDirection::In } else { FlowDirection::Out },
-            volume: 100_000.0 + rand::random::<f64>() * 500_000.0,
-            confidence: 0.7 + rand::random::<f64>() * 0.25,
-        }
-    }
-
     fn detect_insider_activity(&self) -> f64 {
         // Probability of insider trading based on patterns
         rand::random::<f64>() * 0.3
@@ -626,14 +1064,6 @@ impl WalletActivityTracker {
         rand::random::<f64>() * 0.2
     }
 
-    fn calculate_net_flow_direction(&self) -> FlowDirection {
-        if rand::random::<bool>() {
-            FlowDirection::In
-        } else {
-            FlowDirection::Out
-        }
-    }
-
     fn calculate_predictive_power(&self, scores: &[f64]) -> f64 {
         if scores.is_empty() {
             return 0.0;
@@ -646,26 +1076,413 @@ impl WalletActivityTracker {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct WalletLedger {
+    pub positions: HashMap<Address, TokenHolding>,
+    pub realized_pnl_usd: f64,
+    pub volume_usd: f64,
+    pub closed_trades: u32,
+    pub profitable_trades: u32,
+    pub entries: u32,
+    pub early_entries: u32,
+    pub last_seen: DateTime<Utc>,
+}
+
+impl WalletLedger {
+    fn new(timestamp: DateTime<Utc>) -> Self {
+        Self {
+            positions: HashMap::new(),
+            realized_pnl_usd: 0.0,
+            volume_usd: 0.0,
+            closed_trades: 0,
+            profitable_trades: 0,
+            entries: 0,
+            early_entries: 0,
+            last_seen: timestamp,
+        }
+    }
+
+    fn record_buy(&mut self, token: Address, amount: f64, cost_usd: f64, early: bool) {
+        let holding = self.positions.entry(token).or_default();
+        if holding.quantity <= 0.0 {
+            self.entries += 1;
+            if early {
+                self.early_entries += 1;
+            }
+        }
+        holding.quantity += amount;
+        holding.cost_basis_usd += cost_usd;
+        self.volume_usd += cost_usd;
+    }
+
+    fn record_sell(&mut self, token: Address, amount: f64, price_usd: f64) {
+        let holding = self.positions.entry(token).or_default();
+        let sold = amount.min(holding.quantity);
+        self.volume_usd += amount * price_usd;
+        if sold <= 0.0 {
+            return;
+        }
+
+        let avg_cost = holding.cost_basis_usd / holding.quantity;
+        let pnl = sold * (price_usd - avg_cost);
+        holding.cost_basis_usd -= sold * avg_cost;
+        holding.quantity -= sold;
+        holding.realized_pnl_usd += pnl;
+
+        self.realized_pnl_usd += pnl;
+        self.closed_trades += 1;
+        if pnl > 0.0 {
+            self.profitable_trades += 1;
+        }
+    }
+
+    pub fn smart_money_score(&self) -> f64 {
+        if self.closed_trades == 0 || self.volume_usd <= 0.0 {
+            return 0.0;
+        }
+        let profitability = (2.0 * self.realized_pnl_usd / self.volume_usd).tanh().max(0.0);
+        let win_rate = self.profitable_trades as f64 / self.closed_trades as f64;
+        // Getting in early only marks smart money when it paid off
+        let early_ratio = if self.realized_pnl_usd > 0.0 {
+            self.early_entries as f64 / self.entries.max(1) as f64
+        } else {
+            0.0
+        };
+        (0.5 * profitability + 0.3 * win_rate + 0.2 * early_ratio).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TokenHolding {
+    pub quantity: f64,
+    pub cost_basis_usd: f64,
+    pub realized_pnl_usd: f64,
+}
+
 // ==================== PREDICTIVE MODEL ====================
 
+const FUSION_ITERATIONS: usize = 2000;
+const FUSION_LEARNING_RATE: f64 = 1.0;
+const FUSION_L2: f64 = 1e-4;
+const MIN_FIT_SAMPLES: usize = 100;
+const TRAINING_HISTORY_CAPACITY: usize = 10_000;
+const UNFITTED_CONFIDENCE_CAP: f64 = 0.5; // No trades until the model has been fit
+const CALIBRATION_HOLDOUT_PCT: f64 = 0.25; // Latest share of the history held out to fit Platt scaling on
+const REFIT_EVERY_LABELS: usize = 500; // New labels between refits once the model has been fit
+const CONFIDENCE_BUCKETS: [f64; 9] = [0.0, 0.5, 0.6, 0.7, 0.8, 0.9, 0.93, 0.97, 1.0];
+
+/// Signal scores fed into the fusion model, each roughly in [-1, 1]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SignalFeatures {
+    pub volume: f64, // Directional volume confidence
+    pub holder: f64, // Holder distribution score
+    pub wallet: f64, // Smart-money accumulation minus distribution
+}
+
+impl SignalFeatures {
+    pub fn from_signals(volume: &VolumeSignal, holder: &HolderSignal, wallet: &WalletSignal) -> Self {
+        let direction = match volume.direction {
+            PriceDirection::Up => 1.0,
+            PriceDirection::Down => -1.0,
+            PriceDirection::Neutral => 0.0,
+        };
+        Self {
+            volume: direction * volume.confidence,
+            holder: holder.score,
+            wallet: wallet.accumulation_score - wallet.distribution_score,
+        }
+    }
+
+    fn as_array(&self) -> [f64; 3] {
+        [self.volume, self.holder, self.wallet]
+    }
+}
+
+/// Historical signal snapshot labelled with whether price moved >= target within PREDICTION_WINDOW
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabeledSample {
+    pub timestamp: DateTime<Utc>,
+    pub features: SignalFeatures,
+    pub hit: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusionWeights {
+    pub bias: f64,
+    pub volume: f64,
+    pub holder: f64,
+    pub wallet: f64,
+    pub platt_a: f64,          // Calibrated probability = sigmoid(platt_a * logit + platt_b)
+    pub platt_b: f64,
+    pub min_confidence: f64,   // Calibrated range observed in training
+    pub max_confidence: f64,
+    pub training_samples: usize,
+    pub fitted_at: DateTime<Utc>,
+}
+
+impl FusionWeights {
+    fn logit(&self, features: &SignalFeatures) -> f64 {
+        self.bias + self.volume * features.volume + self.holder * features.holder + self.wallet * features.wallet
+    }
+
+    fn calibrated(&self, features: &SignalFeatures) -> f64 {
+        sigmoid(self.platt_a * self.logit(features) + self.platt_b)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfidenceBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub predictions: usize,
+    pub hits: usize,
+    pub mean_confidence: f64,
+    pub hit_rate: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct WalkForwardReport {
+    pub folds: usize,
+    pub predictions: usize,
+    pub hit_rate: f64,
+    pub brier_score: f64,
+    pub buckets: Vec<ConfidenceBucket>,
+}
+
+impl WalkForwardReport {
+    pub fn bucket_for(&self, confidence: f64) -> Option<&ConfidenceBucket> {
+        self.buckets.iter().find(|b| confidence >= b.lower && confidence < b.upper)
+    }
+}
+
+/// A prediction waiting on PREDICTION_WINDOW to show whether price reached its target
+#[derive(Debug, Clone)]
+struct PendingLabel {
+    made_at: DateTime<Utc>,
+    features: SignalFeatures,
+    direction: PriceDirection,
+    entry_price: f64,
+    target_move: f64, // Fractional move in the predicted direction that counts as a hit
+}
+
 #[derive(Debug, Clone)]
 pub struct PredictiveModel {
-    volume_weight: f64,
-    holder_weight: f64,
-    wallet_weight: f64,
+    weights: Option<FusionWeights>,
     base_threshold: f64,
     success_history: VecDeque<PredictionResult>,
+    training_history: VecDeque<LabeledSample>,
+    pending_labels: VecDeque<PendingLabel>, // Oldest first, at most TRAINING_HISTORY_CAPACITY
+    labels_since_fit: usize,
 }
 
 impl PredictiveModel {
     pub fn new() -> Self {
         Self {
-            volume_weight: 0.35,
-            holder_weight: 0.30,
-            wallet_weight: 0.35,
+            weights: None,
             base_threshold: 0.93,
             success_history: VecDeque::with_capacity(1000),
+            training_history: VecDeque::with_capacity(TRAINING_HISTORY_CAPACITY),
+            pending_labels: VecDeque::new(),
+            labels_since_fit: 0,
+        }
+    }
+
+    pub fn weights(&self) -> Option<&FusionWeights> {
+        self.weights.as_ref()
+    }
+
+    /// Fit logistic fusion weights on the older samples, then Platt-scale the logits on the
+    /// latest CALIBRATION_HOLDOUT_PCT so the output is a probability measured out of sample
+    pub fn fit(&mut self, labeled_history: &[LabeledSample]) -> Option<&FusionWeights> {
+        if labeled_history.len() < MIN_FIT_SAMPLES {
+            return None;
+        }
+        let holdout = ((labeled_history.len() as f64 * CALIBRATION_HOLDOUT_PCT) as usize).max(1);
+        let (train, calibration) = labeled_history.split_at(labeled_history.len() - holdout);
+        let label = |s: &LabeledSample| if s.hit { 1.0 } else { 0.0 };
+
+        let rows: Vec<[f64; 3]> = train.iter().map(|s| s.features.as_array()).collect();
+        let labels: Vec<f64> = train.iter().map(label).collect();
+        let coef = fit_logistic(&rows, &labels);
+
+        let mut weights = FusionWeights {
+            bias: coef[0],
+            volume: coef[1],
+            holder: coef[2],
+            wallet: coef[3],
+            platt_a: 1.0,
+            platt_b: 0.0,
+            min_confidence: 0.0,
+            max_confidence: 1.0,
+            training_samples: labeled_history.len(),
+            fitted_at: Utc::now(),
+        };
+
+        let logits: Vec<[f64; 1]> = calibration.iter().map(|s| [weights.logit(&s.features)]).collect();
+        let calibration_labels: Vec<f64> = calibration.iter().map(label).collect();
+        let platt = fit_logistic(&logits, &calibration_labels);
+        weights.platt_b = platt[0];
+        weights.platt_a = platt[1].max(0.0); // A negative slope would invert the curve
+
+        let calibrated: Vec<f64> = calibration.iter().map(|s| weights.calibrated(&s.features)).collect();
+        weights.min_confidence = calibrated.iter().cloned().fold(f64::INFINITY, f64::min);
+        weights.max_confidence = calibrated.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        self.weights = Some(weights);
+        self.labels_since_fit = 0;
+        self.weights.as_ref()
+    }
+
+    /// Refit on the labelled outcomes recorded so far
+    pub fn refit(&mut self) -> Option<&FusionWeights> {
+        let history: Vec<LabeledSample> = self.training_history.iter().cloned().collect();
+        self.fit(&history)
+    }
+
+    /// Enough labels for a first fit, or REFIT_EVERY_LABELS new ones since the last
+    pub fn needs_refit(&self) -> bool {
+        self.training_history.len() >= MIN_FIT_SAMPLES
+            && (self.weights.is_none() || self.labels_since_fit >= REFIT_EVERY_LABELS)
+    }
+
+    /// Calibrated hit probability, clamped to the range seen in training
+    pub fn confidence_for(&self, features: &SignalFeatures) -> f64 {
+        match &self.weights {
+            Some(w) => w.calibrated(features).clamp(w.min_confidence, w.max_confidence),
+            None => UNFITTED_CONFIDENCE_CAP,
+        }
+    }
+
+    /// Train on `train_window` samples, score the next `test_window`, roll forward and repeat
+    pub fn walk_forward_evaluate(
+        history: &[LabeledSample],
+        train_window: usize,
+        test_window: usize,
+    ) -> WalkForwardReport {
+        let mut scored: Vec<(f64, bool)> = Vec::new();
+        let mut folds = 0;
+        let mut start = 0;
+
+        while test_window > 0 && start + train_window + test_window <= history.len() {
+            let mut model = PredictiveModel::new();
+            if model.fit(&history[start..start + train_window]).is_some() {
+                folds += 1;
+                for sample in &history[start + train_window..start + train_window + test_window] {
+                    scored.push((model.confidence_for(&sample.features), sample.hit));
+                }
+            }
+            start += test_window;
+        }
+
+        let buckets = CONFIDENCE_BUCKETS
+            .windows(2)
+            .map(|edge| {
+                let is_last = edge[1] >= 1.0;
+                let members: Vec<&(f64, bool)> = scored
+                    .iter()
+                    .filter(|(c, _)| *c >= edge[0] && (*c < edge[1] || (is_last && *c <= edge[1])))
+                    .collect();
+                let hits = members.iter().filter(|(_, hit)| *hit).count();
+                let n = members.len();
+                ConfidenceBucket {
+                    lower: edge[0],
+                    upper: edge[1],
+                    predictions: n,
+                    hits,
+                    mean_confidence: if n > 0 { members.iter().map(|(c, _)| c).sum::<f64>() / n as f64 } else { 0.0 },
+                    hit_rate: if n > 0 { hits as f64 / n as f64 } else { 0.0 },
+                }
+            })
+            .collect();
+
+        let n = scored.len();
+        let hits = scored.iter().filter(|(_, hit)| *hit).count();
+        let brier = scored
+            .iter()
+            .map(|(c, hit)| (c - if *hit { 1.0 } else { 0.0 }).powi(2))
+            .sum::<f64>();
+
+        WalkForwardReport {
+            folds,
+            predictions: n,
+            hit_rate: if n > 0 { hits as f64 / n as f64 } else { 0.0 },
+            brier_score: if n > 0 { brier / n as f64 } else { 0.0 },
+            buckets,
+        }
+    }
+
+    pub fn save_weights(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let weights = self.weights.as_ref().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "predictive model has not been fit")
+        })?;
+        let json = serde_json::to_string_pretty(weights)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+
+    pub fn load_weights(&mut self, path: &std::path::Path) -> std::io::Result<()> {
+        let json = std::fs::read_to_string(path)?;
+        let weights = serde_json::from_str(&json)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        self.weights = Some(weights);
+        Ok(())
+    }
+
+    pub fn record_outcome(&mut self, sample: LabeledSample) {
+        self.training_history.push_back(sample);
+        if self.training_history.len() > TRAINING_HISTORY_CAPACITY {
+            self.training_history.pop_front();
+        }
+        self.labels_since_fit += 1;
+    }
+
+    /// Queue `prediction`, made with price at `price`, to be labelled by observe_price.
+    /// Every prediction is tracked, traded or not, so the labels aren't selected by confidence.
+    pub fn track_prediction(&mut self, prediction: &Prediction, price: f64, at: DateTime<Utc>) {
+        if !(price.is_finite() && price > 0.0) {
+            return;
+        }
+        self.pending_labels.push_back(PendingLabel {
+            made_at: at,
+            features: prediction.features,
+            direction: prediction.direction.clone(),
+            entry_price: price,
+            target_move: prediction.expected_profit,
+        });
+        if self.pending_labels.len() > TRAINING_HISTORY_CAPACITY {
+            self.pending_labels.pop_front();
+        }
+    }
+
+    /// Labels tracked predictions against `price` at `at`: a hit once price has moved the
+    /// target in the predicted direction (either way for Neutral) within PREDICTION_WINDOW,
+    /// a miss once the window has passed without. Returns how many were labelled.
+    pub fn observe_price(&mut self, price: f64, at: DateTime<Utc>) -> usize {
+        if !price.is_finite() {
+            return 0;
+        }
+        let window = Duration::seconds(PREDICTION_WINDOW);
+        let mut labelled = Vec::new();
+        self.pending_labels.retain(|pending| {
+            let change = (price - pending.entry_price) / pending.entry_price;
+            let directional = match pending.direction {
+                PriceDirection::Up => change,
+                PriceDirection::Down => -change,
+                PriceDirection::Neutral => change.abs(),
+            };
+            let within_window = at - pending.made_at <= window;
+            let hit = within_window && directional >= pending.target_move;
+            if hit || !within_window {
+                labelled.push(LabeledSample { timestamp: pending.made_at, features: pending.features, hit });
+                return false;
+            }
+            true
+        });
+        let count = labelled.len();
+        for sample in labelled {
+            self.record_outcome(sample);
         }
+        count
     }
 
     pub async fn generate_prediction(
@@ -674,16 +1491,8 @@ impl PredictiveModel {
         holder_signal: &HolderSignal,
         wallet_signal: &WalletSignal,
     ) -> Prediction {
-        // Calculate weighted confidence
-        let weighted_confidence = 
-            volume_signal.confidence * self.volume_weight +
-            holder_signal.confidence * self.holder_weight +
-            wallet_signal.confidence * self.wallet_weight;
-
-        // Boost confidence based on signal alignment
-        let alignment_boost = self.calculate_alignment_boost(volume_signal, holder_signal, wallet_signal);
-        
-        let final_confidence = (weighted_confidence + alignment_boost).min(0.99);
+        let features = SignalFeatures::from_signals(volume_signal, holder_signal, wallet_signal);
+        let final_confidence = self.confidence_for(&features);
 
         // Calculate expected profit based on signals
         let expected_profit = self.calculate_expected_profit(volume_signal, holder_signal, wallet_signal);
@@ -702,37 +1511,10 @@ impl PredictiveModel {
             arbitrage_targets: targets,
             risk_score: 1.0 - final_confidence,
             recommended_size: self.calculate_position_size(final_confidence),
+            features,
         }
     }
 
-    fn calculate_alignment_boost(
-        &self,
-        volume: &VolumeSignal,
-        holder: &HolderSignal,
-        wallet: &WalletSignal,
-    ) -> f64 {
-        let mut boost = 0.0;
-
-        // All signals pointing same direction
-        if volume.direction == PriceDirection::Up &&
-           holder.smart_money_flow_direction == FlowDirection::In &&
-           wallet.net_flow_direction == FlowDirection::In {
-            boost += 0.05;
-        }
-
-        // Strong accumulation signals
-        if volume.accumulation_score > 0.7 && holder.accumulation_strength > 0.7 {
-            boost += 0.03;
-        }
-
-        // Low risk indicators
-        if holder.distribution_risk < 0.3 && wallet.wash_trading_risk < 0.1 {
-            boost += 0.02;
-        }
-
-        boost
-    }
-
     fn calculate_expected_profit(
         &self,
         volume: &VolumeSignal,
@@ -764,14 +1546,14 @@ impl PredictiveModel {
                 pool_a: "Uniswap_V3_WETH/USDC".to_string(),
                 pool_b: "SushiSwap_WETH/USDC".to_string(),
                 expected_spread: 0.003,
-                optimal_route: vec!["USDC", "WETH", "USDC"],
+                optimal_route: ["USDC", "WETH", "USDC"].into_iter().map(String::from).collect(),
             });
 
             targets.push(ArbitrageTarget {
                 pool_a: "Curve_3pool".to_string(),
                 pool_b: "Balancer_Stable".to_string(),
                 expected_spread: 0.002,
-                optimal_route: vec!["USDT", "USDC", "DAI", "USDT"],
+                optimal_route: ["USDT", "USDC", "DAI", "USDT"].into_iter().map(String::from).collect(),
             });
         }
 
@@ -784,7 +1566,7 @@ impl PredictiveModel {
         _holder: &HolderSignal,
         _wallet: &WalletSignal,
     ) -> PriceDirection {
-        volume.direction
+        volume.direction.clone()
     }
 
     fn calculate_position_size(&self, confidence: f64) -> f64 {
@@ -797,35 +1579,69 @@ impl PredictiveModel {
         if current_success_rate < TARGET_SUCCESS_RATE {
             // Adjust weights to improve accuracy
             self.base_threshold += 0.005; // Increase threshold
-            
-            // Reweight based on historical performance
-            if self.success_history.len() > 100 {
-                self.optimize_weights();
-            }
         }
     }
+}
 
-    fn optimize_weights(&mut self) {
-        // Gradient descent to optimize weights
-        // This is simplified - real implementation would use proper ML
-        let adjustment = 0.01;
-        
-        if rand::random::<bool>() {
-            self.volume_weight += adjustment;
-            self.holder_weight -= adjustment / 2.0;
-            self.wallet_weight -= adjustment / 2.0;
-        }
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
 
-        // Normalize weights
-        let total = self.volume_weight + self.holder_weight + self.wallet_weight;
-        self.volume_weight /= total;
-        self.holder_weight /= total;
-        self.wallet_weight /= total;
+/// Batch gradient descent on L2-regularised log-loss; returns [bias, w_1..w_n]
+fn fit_logistic<const N: usize>(rows: &[[f64; N]], labels: &[f64]) -> Vec<f64> {
+    let mut coef = vec![0.0; N + 1];
+    let n = rows.len().max(1) as f64;
+
+    for _ in 0..FUSION_ITERATIONS {
+        let mut grad = vec![0.0; N + 1];
+        for (row, label) in rows.iter().zip(labels) {
+            let z = coef[0] + row.iter().zip(&coef[1..]).map(|(x, w)| x * w).sum::<f64>();
+            let err = sigmoid(z) - label;
+            grad[0] += err;
+            for (g, x) in grad[1..].iter_mut().zip(row) {
+                *g += err * x;
+            }
+        }
+        for (i, (c, g)) in coef.iter_mut().zip(&grad).enumerate() {
+            let penalty = if i == 0 { 0.0 } else { FUSION_L2 * *c };
+            *c -= FUSION_LEARNING_RATE * (g / n + penalty);
+        }
     }
+
+    coef
 }
 
 // ==================== AMM BOT ====================
 
+/// A Uniswap V3-style pool for one fee tier of a token pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmmPool {
+    pub token_a: String,
+    pub token_b: String,
+    pub fee_bps: u32, // 5 = 0.05%, 30 = 0.3%, 100 = 1%
+    pub liquidity_usd: f64,
+}
+
+impl AmmPool {
+    fn trades(&self, token_a: &str, token_b: &str) -> bool {
+        (self.token_a.eq_ignore_ascii_case(token_a) && self.token_b.eq_ignore_ascii_case(token_b))
+            || (self.token_a.eq_ignore_ascii_case(token_b) && self.token_b.eq_ignore_ascii_case(token_a))
+    }
+
+    /// Square-root impact model: slippage grows with sqrt(trade size / pool depth)
+    pub fn expected_slippage(&self, trade_size_usd: f64) -> f64 {
+        if self.liquidity_usd <= 0.0 {
+            return f64::INFINITY;
+        }
+        SQRT_IMPACT_COEFFICIENT * (trade_size_usd.max(0.0) / self.liquidity_usd).sqrt()
+    }
+
+    /// Slippage plus swap fee, as a fraction of the trade
+    pub fn execution_cost(&self, trade_size_usd: f64) -> f64 {
+        self.expected_slippage(trade_size_usd) + self.fee_bps as f64 / 10_000.0
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AMMBot {
     id: usize,
@@ -846,8 +1662,41 @@ impl AMMBot {
         }
     }
 
+    /// Fee tier (in bps) whose pool minimizes expected slippage + fee for this trade.
+    /// Big trades often land in a deeper 0.3% pool rather than a thin 0.05% one.
+    pub fn select_fee_tier(&self, token_a: &str, token_b: &str, trade_size_usd: f64, available_pools: &[AmmPool]) -> u32 {
+        let mut candidates: Vec<(&AmmPool, f64)> = available_pools.iter()
+            .filter(|pool| pool.trades(token_a, token_b))
+            .map(|pool| (pool, pool.execution_cost(trade_size_usd)))
+            .filter(|(_, cost)| cost.is_finite())
+            .collect();
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.fee_bps.cmp(&b.0.fee_bps)));
+
+        let Some(&(winner, cost)) = candidates.first() else {
+            info!(bot = self.id, dex = %self.dex, token_a, token_b, fee_tier = DEFAULT_FEE_TIER_BPS,
+                "no pool for pair, defaulting fee tier");
+            return DEFAULT_FEE_TIER_BPS;
+        };
+
+        let rationale: Vec<String> = candidates.iter()
+            .map(|(pool, cost)| format!(
+                "{}bps: slippage {:.2}bps + fee {}bps = {:.2}bps (depth ${:.0})",
+                pool.fee_bps,
+                pool.expected_slippage(trade_size_usd) * 10_000.0,
+                pool.fee_bps,
+                cost * 10_000.0,
+                pool.liquidity_usd,
+            ))
+            .collect();
+        info!(bot = self.id, dex = %self.dex, token_a, token_b, size_usd = trade_size_usd,
+            fee_tier = winner.fee_bps, expected_edge_bps = cost * 10_000.0,
+            rationale = %rationale.join("; "), "selected fee tier");
+
+        winner.fee_bps
+    }
+
     pub async fn execute_arbitrage(&mut self, opportunity: ArbitrageOpportunity) -> ArbitrageResult {
-        println!("🤖 AMM Bot {} ({}) executing arbitrage", self.id, self.dex);
+        debug!(bot = self.id, dex = %self.dex, "bot executing arbitrage");
 
         // Simulate execution with high success rate when confidence is high
         let success = rand::random::<f64>() < 0.93; // 93% success rate
@@ -960,12 +1809,388 @@ impl SuccessRateTracker {
     }
 }
 
+// ==================== ON-CHAIN TRADES ====================
+
+const LOG_RANGE_CHUNK_BLOCKS: u64 = 2_000; // Most RPCs cap eth_getLogs block ranges
+const SECONDS_PER_BLOCK: u64 = 12; // Mainnet slot time, to turn a first lookback into blocks
+const SWAP_VOLUME_WINDOW_HOURS: i64 = 24; // Longest volume window read from swaps
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SwapVenue {
+    UniswapV3,
+    SushiSwap,
+    Curve,
+}
+
+impl SwapVenue {
+    pub fn event_signature(&self) -> &'static str {
+        match self {
+            SwapVenue::UniswapV3 => "Swap(address,address,int256,int256,uint160,uint128,int24)",
+            SwapVenue::SushiSwap => "Swap(address,uint256,uint256,uint256,uint256,address)",
+            SwapVenue::Curve => "TokenExchange(address,int128,uint256,int128,uint256)",
+        }
+    }
+
+    pub fn topic0(&self) -> H256 {
+        H256::from(ethers::utils::keccak256(self.event_signature()))
+    }
+}
+
+/// Decimals and USD prices of a pool's coins, by coin index (token0, token1, ...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolPricing {
+    pub decimals: Vec<u8>,
+    pub prices_usd: Vec<f64>,
+}
+
+impl PoolPricing {
+    pub fn pair(decimals: (u8, u8), prices_usd: (f64, f64)) -> Self {
+        Self {
+            decimals: vec![decimals.0, decimals.1],
+            prices_usd: vec![prices_usd.0, prices_usd.1],
+        }
+    }
+
+    fn coin(&self, index: usize) -> Result<(u8, f64), ParseError> {
+        match (self.decimals.get(index), self.prices_usd.get(index)) {
+            (Some(&decimals), Some(&price)) => Ok((decimals, price)),
+            _ => Err(ParseError::UnknownCoin(index)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    WrongEvent { venue: SwapVenue, topic0: Option<H256> },
+    MissingBlockNumber,
+    Abi(String),
+    UnknownCoin(usize),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::WrongEvent { venue, topic0 } => write!(f, "log {:?} is not a {:?} swap event", topic0, venue),
+            ParseError::MissingBlockNumber => write!(f, "log has no block number (pending?)"),
+            ParseError::Abi(e) => write!(f, "undecodable swap data: {}", e),
+            ParseError::UnknownCoin(index) => write!(f, "no decimals/price for coin {}", index),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// One DEX swap decoded from an event log. Amounts are the pool's signed balance change in
+/// whole tokens: negative means the coin left the pool, i.e. the trader received it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnChainTrade {
+    pub venue: SwapVenue,
+    pub pool: Address,
+    pub block_number: u64,
+    pub log_index: Option<U256>,
+    pub tx_hash: Option<H256>,
+    pub timestamp: DateTime<Utc>,
+    pub coins: [usize; 2], // Pool coin indices of amount0 and amount1; [0, 1] but for Curve
+    pub is_buy: bool, // Trader received coin coins[0]
+    pub amount0: f64,
+    pub amount1: f64,
+    pub volume_usd: f64,
+    pub sqrt_price_x96: Option<U256>, // Uniswap V3 only, like liquidity and tick
+    pub liquidity: Option<u128>,
+    pub tick: Option<i32>,
+}
+
+impl OnChainTrade {
+    /// Uniswap V3 `Swap(sender, recipient, amount0, amount1, sqrtPriceX96, liquidity, tick)`.
+    /// Logs carry no timestamp, so the block's is passed in.
+    pub fn from_uniswap_v3_swap_log(
+        log: &Log,
+        pricing: &PoolPricing,
+        block_timestamp: DateTime<Utc>,
+    ) -> Result<Self, ParseError> {
+        let tokens = decode_swap_log(log, SwapVenue::UniswapV3, &[
+            ParamType::Int(256),
+            ParamType::Int(256),
+            ParamType::Uint(160),
+            ParamType::Uint(128),
+            ParamType::Int(24),
+        ])?;
+        let amount0 = I256::from_raw(int_token(&tokens[0])?);
+        let amount1 = I256::from_raw(int_token(&tokens[1])?);
+        let tick = I256::from_raw(int_token(&tokens[4])?);
+
+        let mut trade = Self::from_deltas(log, SwapVenue::UniswapV3, block_timestamp, pricing, amount0, amount1)?;
+        trade.sqrt_price_x96 = Some(uint_token(&tokens[2])?);
+        trade.liquidity = Some(uint_token(&tokens[3])?.as_u128());
+        trade.tick = Some(tick.as_i32());
+        Ok(trade)
+    }
+
+    /// SushiSwap (Uniswap V2) `Swap(sender, amount0In, amount1In, amount0Out, amount1Out, to)`
+    pub fn from_sushiswap_swap_log(
+        log: &Log,
+        pricing: &PoolPricing,
+        block_timestamp: DateTime<Utc>,
+    ) -> Result<Self, ParseError> {
+        let tokens = decode_swap_log(log, SwapVenue::SushiSwap, &[
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+        ])?;
+        let amounts = tokens.iter().map(uint_token).collect::<Result<Vec<U256>, _>>()?;
+        let delta = |amount_in: U256, amount_out: U256| {
+            I256::from_raw(amount_in).overflowing_sub(I256::from_raw(amount_out)).0
+        };
+
+        Self::from_deltas(
+            log,
+            SwapVenue::SushiSwap,
+            block_timestamp,
+            pricing,
+            delta(amounts[0], amounts[2]),
+            delta(amounts[1], amounts[3]),
+        )
+    }
+
+    /// Curve `TokenExchange(buyer, sold_id, tokens_sold, bought_id, tokens_bought)`. Only the
+    /// two coins traded are reported, the lower-indexed as amount0, so a 3pool DAI/USDC swap
+    /// reads like a DAI/USDC pair whichever way it went.
+    pub fn from_curve_exchange_log(
+        log: &Log,
+        pricing: &PoolPricing,
+        block_timestamp: DateTime<Utc>,
+    ) -> Result<Self, ParseError> {
+        let tokens = decode_swap_log(log, SwapVenue::Curve, &[
+            ParamType::Int(128),
+            ParamType::Uint(256),
+            ParamType::Int(128),
+            ParamType::Uint(256),
+        ])?;
+        let sold_id = int_token(&tokens[0])?.low_u64() as usize;
+        let bought_id = int_token(&tokens[2])?.low_u64() as usize;
+        let (sold_decimals, sold_price) = pricing.coin(sold_id)?;
+        let (bought_decimals, _) = pricing.coin(bought_id)?;
+        let sold = token_amount(I256::from_raw(uint_token(&tokens[1])?), sold_decimals)?;
+        let bought = token_amount(I256::from_raw(uint_token(&tokens[3])?), bought_decimals)?;
+        // The sold coin flows into the pool, the bought one out of it
+        let (coins, amount0, amount1) = if sold_id < bought_id {
+            ([sold_id, bought_id], sold, -bought)
+        } else {
+            ([bought_id, sold_id], -bought, sold)
+        };
+
+        Ok(Self {
+            venue: SwapVenue::Curve,
+            pool: log.address,
+            block_number: log.block_number.ok_or(ParseError::MissingBlockNumber)?.as_u64(),
+            log_index: log.log_index,
+            tx_hash: log.transaction_hash,
+            timestamp: block_timestamp,
+            coins,
+            is_buy: amount0 < 0.0,
+            amount0,
+            amount1,
+            volume_usd: sold * sold_price,
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+        })
+    }
+
+    pub fn from_log(
+        venue: SwapVenue,
+        log: &Log,
+        pricing: &PoolPricing,
+        block_timestamp: DateTime<Utc>,
+    ) -> Result<Self, ParseError> {
+        match venue {
+            SwapVenue::UniswapV3 => Self::from_uniswap_v3_swap_log(log, pricing, block_timestamp),
+            SwapVenue::SushiSwap => Self::from_sushiswap_swap_log(log, pricing, block_timestamp),
+            SwapVenue::Curve => Self::from_curve_exchange_log(log, pricing, block_timestamp),
+        }
+    }
+
+    /// Two-coin pools: volume is the side flowing into the pool, priced in USD
+    fn from_deltas(
+        log: &Log,
+        venue: SwapVenue,
+        block_timestamp: DateTime<Utc>,
+        pricing: &PoolPricing,
+        delta0: I256,
+        delta1: I256,
+    ) -> Result<Self, ParseError> {
+        let (decimals0, price0) = pricing.coin(0)?;
+        let (decimals1, price1) = pricing.coin(1)?;
+        let amount0 = token_amount(delta0, decimals0)?;
+        let amount1 = token_amount(delta1, decimals1)?;
+        let volume_usd = if amount0 > 0.0 { amount0 * price0 } else { amount1.max(0.0) * price1 };
+
+        Ok(Self {
+            venue,
+            pool: log.address,
+            block_number: log.block_number.ok_or(ParseError::MissingBlockNumber)?.as_u64(),
+            log_index: log.log_index,
+            tx_hash: log.transaction_hash,
+            timestamp: block_timestamp,
+            coins: [0, 1],
+            is_buy: amount0 < 0.0,
+            amount0,
+            amount1,
+            volume_usd,
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+        })
+    }
+}
+
+fn decode_swap_log(log: &Log, venue: SwapVenue, params: &[ParamType]) -> Result<Vec<Token>, ParseError> {
+    let topic0 = log.topics.first().copied();
+    if topic0 != Some(venue.topic0()) {
+        return Err(ParseError::WrongEvent { venue, topic0 });
+    }
+    ethers::abi::decode(params, &log.data).map_err(|e| ParseError::Abi(e.to_string()))
+}
+
+fn int_token(token: &Token) -> Result<U256, ParseError> {
+    token.clone().into_int().ok_or_else(|| ParseError::Abi(format!("expected int, got {:?}", token)))
+}
+
+fn uint_token(token: &Token) -> Result<U256, ParseError> {
+    token.clone().into_uint().ok_or_else(|| ParseError::Abi(format!("expected uint, got {:?}", token)))
+}
+
+fn token_amount(raw: I256, decimals: u8) -> Result<f64, ParseError> {
+    ethers::utils::format_units(raw, decimals as u32)
+        .map_err(|e| ParseError::Abi(e.to_string()))?
+        .parse::<f64>()
+        .map_err(|e| ParseError::Abi(e.to_string()))
+}
+
+/// Swaps on `pool` between `from_block` and `to_block` inclusive, fetched in chunks of
+/// LOG_RANGE_CHUNK_BLOCKS. Block timestamps are fetched once per block; undecodable logs and
+/// logs whose block has no timestamp are skipped rather than stamped with the wrong time.
+pub async fn fetch_swap_trades<M: Middleware>(
+    provider: &M,
+    venue: SwapVenue,
+    pool: Address,
+    pricing: &PoolPricing,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<OnChainTrade>, M::Error> {
+    let mut trades = Vec::new();
+    let mut block_times: HashMap<u64, Option<DateTime<Utc>>> = HashMap::new();
+    let mut start = from_block;
+
+    while start <= to_block {
+        let end = (start + LOG_RANGE_CHUNK_BLOCKS - 1).min(to_block);
+        let filter = Filter::new()
+            .address(pool)
+            .topic0(venue.topic0())
+            .from_block(start)
+            .to_block(end);
+
+        for log in provider.get_logs(&filter).await? {
+            let Some(block_number) = log.block_number.map(|n| n.as_u64()) else {
+                continue;
+            };
+            let timestamp = match block_times.get(&block_number) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let block = provider.get_block(block_number).await?;
+                    let timestamp = block.and_then(|b| DateTime::from_timestamp(b.timestamp.as_u64() as i64, 0));
+                    block_times.insert(block_number, timestamp);
+                    timestamp
+                }
+            };
+            let Some(timestamp) = timestamp else {
+                warn!(?venue, block_number, "swap log skipped, block has no timestamp");
+                continue;
+            };
+            match OnChainTrade::from_log(venue, &log, pricing, timestamp) {
+                Ok(trade) => trades.push(trade),
+                Err(e) => warn!(?venue, block_number, error = %e, "undecodable swap log skipped"),
+            }
+        }
+        start = end + 1;
+    }
+
+    Ok(trades)
+}
+
+#[async_trait::async_trait]
+pub trait SwapFeed: Send + Sync + std::fmt::Debug {
+    /// Swaps not returned by an earlier call, at or after `since`, oldest first
+    async fn fetch_swaps(&self, since: DateTime<Utc>) -> Vec<OnChainTrade>;
+}
+
+/// Which pool to watch for swaps and how to price its coins
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapFeedConfig {
+    pub venue: SwapVenue,
+    pub pool: Address,
+    pub pricing: PoolPricing,
+}
+
+/// Swap feed over an RPC node: each fetch scans the blocks mined since the last one
+#[derive(Debug)]
+pub struct RpcSwapFeed<M> {
+    provider: M,
+    config: SwapFeedConfig,
+    next_block: Mutex<Option<u64>>,
+}
+
+impl<M> RpcSwapFeed<M> {
+    pub fn new(provider: M, config: SwapFeedConfig) -> Self {
+        Self {
+            provider,
+            config,
+            next_block: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<M: Middleware + std::fmt::Debug + 'static> SwapFeed for RpcSwapFeed<M> {
+    async fn fetch_swaps(&self, since: DateTime<Utc>) -> Vec<OnChainTrade> {
+        let latest = match self.provider.get_block_number().await {
+            Ok(latest) => latest.as_u64(),
+            Err(e) => {
+                warn!(error = %e, "block number unavailable, no swaps fetched");
+                return Vec::new();
+            }
+        };
+        let mut next_block = self.next_block.lock().await;
+        let from_block = next_block.unwrap_or_else(|| {
+            let blocks_back = (Utc::now() - since).num_seconds().max(0) as u64 / SECONDS_PER_BLOCK;
+            latest.saturating_sub(blocks_back)
+        });
+        if from_block > latest {
+            return Vec::new();
+        }
+        let SwapFeedConfig { venue, pool, pricing } = &self.config;
+        match fetch_swap_trades(&self.provider, *venue, *pool, pricing, from_block, latest).await {
+            Ok(trades) => {
+                *next_block = Some(latest + 1);
+                trades.into_iter().filter(|t| t.timestamp >= since).collect()
+            }
+            Err(e) => {
+                warn!(?venue, error = %e, "swap logs unavailable, retrying from block {}", from_block);
+                Vec::new()
+            }
+        }
+    }
+}
+
 // ==================== DATA STRUCTURES ====================
 
 #[derive(Debug, Clone)]
 pub struct OnChainData {
     pub block_number: u64,
     pub timestamp: DateTime<Utc>,
+    pub token_address: Address,
+    pub price_usd: f64, // Target token price, which labels earlier predictions
     
     // Volume metrics
     pub volume_24h: f64,
@@ -1017,6 +2242,14 @@ pub struct HolderSignal {
     pub concentration_change: f64,
     pub retail_participation: f64,
     pub confidence: f64,
+    pub gini: f64,
+    pub gini_delta: f64,
+    pub top10_pct: f64,
+    pub top10_delta: f64,
+    pub top50_pct: f64,
+    pub holder_count: u64,
+    pub holder_growth_rate: f64,
+    pub score: f64, // Positive when distribution is improving
 }
 
 #[derive(Debug, Clone)]
@@ -1029,6 +2262,28 @@ pub struct WalletSignal {
     pub wash_trading_risk: f64,
     pub predictive_power: f64,
     pub confidence: f64,
+    pub accumulation_score: f64,    // Smart-money-weighted net buying share of window flow
+    pub distribution_score: f64,    // Smart-money-weighted net selling share of window flow
+    pub smart_money_net_flow: f64,  // Score-weighted net USD flow
+    pub top_wallet_changes: Vec<WalletChange>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WalletChange {
+    pub wallet: Address,
+    pub net_flow_usd: f64,
+    pub smart_money_score: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferEvent {
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: f64,
+    pub price_usd: f64,
+    pub block_number: u64,
+    pub timestamp: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone)]
@@ -1040,6 +2295,7 @@ pub struct Prediction {
     pub arbitrage_targets: Vec<ArbitrageTarget>,
     pub risk_score: f64,
     pub recommended_size: f64,
+    pub features: SignalFeatures,
 }
 
 #[derive(Debug, Clone)]
@@ -1150,10 +2406,39 @@ pub struct HolderSnapshot {
     pub total_holders: u64,
     pub whale_holders: u64,
     pub new_holders: u64,
-    pub concentration: f64,
+    pub concentration: f64,        // Top-10 share of circulating supply
+    pub top50_concentration: f64,
     pub gini_coefficient: f64,
 }
 
+impl From<&HolderSnapshot> for HolderPoint {
+    fn from(snapshot: &HolderSnapshot) -> Self {
+        Self {
+            timestamp: snapshot.timestamp,
+            total_holders: snapshot.total_holders,
+            whale_holders: snapshot.whale_holders,
+            new_holders: snapshot.new_holders,
+            concentration: snapshot.concentration,
+            top50_concentration: snapshot.top50_concentration,
+            gini_coefficient: snapshot.gini_coefficient,
+        }
+    }
+}
+
+impl From<&HolderPoint> for HolderSnapshot {
+    fn from(point: &HolderPoint) -> Self {
+        Self {
+            timestamp: point.timestamp,
+            total_holders: point.total_holders,
+            whale_holders: point.whale_holders,
+            new_holders: point.new_holders,
+            concentration: point.concentration,
+            top50_concentration: point.top50_concentration,
+            gini_coefficient: point.gini_coefficient,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WhaleMovement {
     pub wallet: Address,
@@ -1217,6 +2502,7 @@ pub struct PredictionResult {
 }
 
 // Supporting structures
+#[derive(Debug, Clone)]
 pub struct WhaleTracker;
 impl WhaleTracker {
     fn new() -> Self { Self }
@@ -1235,11 +2521,14 @@ pub struct WhaleAnalysis {
     confidence: f64,
 }
 
+#[derive(Debug, Clone)]
 pub struct DistributionModel;
 impl DistributionModel { fn new() -> Self { Self } }
 
+#[derive(Debug, Clone)]
 pub struct WalletActivity;
 
+#[derive(Debug, Clone)]
 pub struct WalletPatternDetector;
 impl WalletPatternDetector {
     fn new() -> Self { Self }
@@ -1260,6 +2549,7 @@ pub struct SmartMoneyFlow {
     confidence: f64,
 }
 
+#[derive(Debug, Clone)]
 pub struct VolumeMLModel;
 impl VolumeMLModel {
     fn new() -> Self { Self }
@@ -1282,19 +2572,24 @@ pub struct VolumePrediction {
     time_horizon: i64,
 }
 
+#[derive(Debug, Clone)]
 pub struct AMMPosition;
+#[derive(Debug, Clone)]
 pub struct BotPerformance;
 impl BotPerformance {
     fn new() -> Self { Self }
     fn add_trade(&mut self, _: bool, _: f64) {}
 }
 
+#[derive(Debug, Clone)]
 pub struct PoolMonitor;
 impl PoolMonitor { fn new() -> Self { Self } }
 
+#[derive(Debug, Clone)]
 pub struct PathFinder;
 impl PathFinder { fn new() -> Self { Self } }
 
+#[derive(Debug, Clone)]
 pub struct ProfitCalculator;
 impl ProfitCalculator { fn new() -> Self { Self } }
 
@@ -1320,20 +2615,625 @@ mod rand {
     }
 }
 
-// Extension trait for Address
-impl Address {
-    fn random() -> Self {
-        Address::from([rand::random::<u8>(); 20])
-    }
-}
-
 // Main execution
 pub async fn launch_amm_predictive_system() {
-    println!("🚀 Launching AMM Predictive Arbitrage System");
-    println!("   Target Success Rate: 93%");
-    println!("   Analysis Factors: Volume, Holders, Wallet Activity");
-    println!("   AMM Coverage: 12 major DEXs");
+    if telemetry::pretty_banners() {
+        println!("🚀 Launching AMM Predictive Arbitrage System");
+        println!("   Target Success Rate: 93%");
+        println!("   Analysis Factors: Volume, Holders, Wallet Activity");
+        println!("   AMM Coverage: 12 major DEXs");
+    }
     
     let mut engine = AMMPredictiveEngine::new(800_000.0).await;
+    
+    // Resume from the last fitted weights; confidence stays capped until the model is fit
+    let weights_path = std::env::var("AMM_MODEL_WEIGHTS").unwrap_or_else(|_| DEFAULT_MODEL_WEIGHTS_PATH.to_string());
+    if let Err(e) = engine.set_model_weights_path(PathBuf::from(&weights_path)) {
+        warn!(path = %weights_path, error = %e, "model weights unreadable, starting unfitted");
+    }
+    
+    // Volumes come from a real pool's swaps when one is configured alongside an RPC endpoint
+    if let (Ok(rpc_url), Ok(path)) = (std::env::var("ETH_RPC_URL"), std::env::var("AMM_SWAP_FEED")) {
+        let config = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str::<SwapFeedConfig>(&json).map_err(|e| e.to_string()));
+        match (config, Provider::<Http>::try_from(rpc_url.as_str())) {
+            (Ok(config), Ok(provider)) => engine.set_swap_feed(Arc::new(RpcSwapFeed::new(provider, config))),
+            (Err(e), _) => warn!(path = %path, error = %e, "swap feed config unreadable, volumes stay simulated"),
+            (_, Err(e)) => warn!(error = %e, "ETH_RPC_URL invalid, volumes stay simulated"),
+        }
+    }
+    
     engine.execute_predictive_arbitrage().await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(token: u64, from: u64, to: u64, amount: f64, price_usd: f64, at: DateTime<Utc>) -> TransferEvent {
+        TransferEvent {
+            token: Address::from_low_u64_be(token),
+            from: Address::from_low_u64_be(from),
+            to: Address::from_low_u64_be(to),
+            amount,
+            price_usd,
+            block_number: 0,
+            timestamp: at,
+        }
+    }
+
+    const POOL: u64 = 100;
+    const DEPLOYER: u64 = 200;
+    const ZERO: u64 = 0;
+
+    fn balances(values: &[f64], total_supply: f64, holder_count: u64) -> HolderBalances {
+        HolderBalances {
+            balances: values.iter()
+                .enumerate()
+                .map(|(i, b)| (Address::from_low_u64_be(1000 + i as u64), *b))
+                .collect(),
+            total_supply,
+            holder_count,
+        }
+    }
+
+    // Deterministic LCG so synthetic histories are reproducible
+    fn synthetic_history(n: usize, bias: f64, weights: [f64; 3], seed: u64) -> Vec<LabeledSample> {
+        let mut state = seed;
+        let mut uniform = move || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let start = Utc::now() - Duration::seconds(PREDICTION_WINDOW * n as i64);
+        (0..n)
+            .map(|i| {
+                let features = SignalFeatures {
+                    volume: uniform() * 2.0 - 1.0,
+                    holder: uniform() * 2.0 - 1.0,
+                    wallet: uniform() * 2.0 - 1.0,
+                };
+                let z = bias + weights[0] * features.volume + weights[1] * features.holder + weights[2] * features.wallet;
+                LabeledSample {
+                    timestamp: start + Duration::seconds(PREDICTION_WINDOW * i as i64),
+                    features,
+                    hit: uniform() < sigmoid(z),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fit_recovers_true_weights() {
+        let history = synthetic_history(8000, -0.5, [2.0, 1.0, 3.0], 7);
+        let mut model = PredictiveModel::new();
+        let w = model.fit(&history).unwrap().clone();
+
+        // Platt scaling is folded back in to compare on the original logit scale
+        assert!((w.platt_a * w.volume - 2.0).abs() < 0.3);
+        assert!((w.platt_a * w.holder - 1.0).abs() < 0.3);
+        assert!((w.platt_a * w.wallet - 3.0).abs() < 0.3);
+        assert!((w.platt_a * w.bias + w.platt_b + 0.5).abs() < 0.3);
+    }
+
+    #[test]
+    fn test_calibration_curve_is_monotone_and_clamped() {
+        let history = synthetic_history(2000, 0.0, [1.5, 0.5, 2.0], 11);
+        let mut model = PredictiveModel::new();
+        assert_eq!(model.confidence_for(&SignalFeatures::default()), UNFITTED_CONFIDENCE_CAP);
+        model.fit(&history).unwrap();
+
+        let mut previous = 0.0;
+        for step in -20..=20 {
+            let x = step as f64 / 20.0;
+            let c = model.confidence_for(&SignalFeatures { volume: x, holder: x, wallet: x });
+            assert!(c >= previous);
+            previous = c;
+        }
+
+        // Far outside the training range the output stays at the observed maximum
+        let w = model.weights().unwrap().clone();
+        let extreme = model.confidence_for(&SignalFeatures { volume: 50.0, holder: 50.0, wallet: 50.0 });
+        assert_eq!(extreme, w.max_confidence);
+        assert!(w.max_confidence < 1.0);
+    }
+
+    #[test]
+    fn test_walk_forward_buckets_track_hit_rate() {
+        let history = synthetic_history(6000, 0.0, [2.0, 1.0, 3.0], 3);
+        let report = PredictiveModel::walk_forward_evaluate(&history, 2000, 1000);
+
+        assert_eq!(report.folds, 4);
+        assert_eq!(report.predictions, 4000);
+        assert_eq!(report.buckets.iter().map(|b| b.predictions).sum::<usize>(), 4000);
+        for bucket in report.buckets.iter().filter(|b| b.predictions >= 200) {
+            assert!((bucket.hit_rate - bucket.mean_confidence).abs() < 0.08, "{:?}", bucket);
+        }
+        assert!(report.brier_score < 0.25);
+    }
+
+    #[test]
+    fn test_weights_round_trip_through_disk() {
+        let history = synthetic_history(500, 0.0, [1.0, 1.0, 1.0], 5);
+        let mut model = PredictiveModel::new();
+        model.fit(&history).unwrap();
+
+        let path = std::env::temp_dir().join(format!("fusion_weights_{}.json", std::process::id()));
+        model.save_weights(&path).unwrap();
+        let mut restored = PredictiveModel::new();
+        restored.load_weights(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let features = SignalFeatures { volume: 0.3, holder: -0.2, wallet: 0.6 };
+        assert_eq!(model.confidence_for(&features), restored.confidence_for(&features));
+    }
+
+    #[test]
+    fn test_every_prediction_is_labelled_by_the_window_outcome() {
+        let mut model = PredictiveModel::new();
+        let now = Utc::now();
+        let prediction = |direction: PriceDirection, confidence: f64| Prediction {
+            confidence,
+            expected_profit: 0.01,
+            direction,
+            execution_window: ExecutionWindow { start: now, end: now, optimal_time: now },
+            arbitrage_targets: Vec::new(),
+            risk_score: 0.1,
+            recommended_size: 1_000.0,
+            features: SignalFeatures { volume: 0.4, holder: 0.1, wallet: 0.2 },
+        };
+
+        // Low-confidence predictions that never traded are labelled too
+        model.track_prediction(&prediction(PriceDirection::Up, 0.2), 100.0, now);
+        model.track_prediction(&prediction(PriceDirection::Down, 0.95), 100.0, now);
+        model.track_prediction(&prediction(PriceDirection::Up, 0.5), 100.0, now);
+
+        // A 1.5% rise within the window hits both longs; the short stays pending
+        assert_eq!(model.observe_price(101.5, now + Duration::seconds(60)), 2);
+        assert_eq!(model.pending_labels.len(), 1);
+
+        // The short never saw price 1% lower, so it misses once the window has passed
+        assert_eq!(model.observe_price(98.0, now + Duration::seconds(PREDICTION_WINDOW + 1)), 1);
+        let labels: Vec<bool> = model.training_history.iter().map(|s| s.hit).collect();
+        assert_eq!(labels, vec![true, true, false]);
+        assert!(model.pending_labels.is_empty());
+    }
+
+    #[test]
+    fn test_refit_waits_for_enough_new_labels() {
+        let mut model = PredictiveModel::new();
+        let weights = [1.5, -0.8, 2.0];
+        let history = synthetic_history(MIN_FIT_SAMPLES - 1, 0.0, weights, 7);
+        for sample in history {
+            model.record_outcome(sample);
+        }
+        assert!(!model.needs_refit());
+        model.record_outcome(synthetic_history(1, 0.0, weights, 8).remove(0));
+        assert!(model.needs_refit());
+        assert!(model.refit().is_some());
+        assert!(!model.needs_refit());
+        for sample in synthetic_history(REFIT_EVERY_LABELS, 0.0, weights, 9) {
+            model.record_outcome(sample);
+        }
+        assert!(model.needs_refit());
+    }
+
+    #[tokio::test]
+    async fn test_model_weights_path_loads_and_saves_fitted_weights() {
+        let path = std::env::temp_dir().join(format!("engine_weights_{}.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        let mut engine = AMMPredictiveEngine::new(120_000.0).await;
+        engine.set_model_weights_path(path.clone()).unwrap(); // Missing file starts unfitted
+        assert!(engine.fit_predictive_model(&synthetic_history(2_000, 0.0, [1.5, -0.8, 2.0], 3)));
+
+        let mut restarted = AMMPredictiveEngine::new(120_000.0).await;
+        restarted.set_model_weights_path(path.clone()).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let features = SignalFeatures { volume: 0.3, holder: -0.2, wallet: 0.6 };
+        assert_eq!(
+            engine.predictive_model.confidence_for(&features),
+            restarted.predictive_model.confidence_for(&features),
+        );
+        assert!(restarted.predictive_model.weights.is_some());
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLog(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLog {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CapturedLog {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execution_events_carry_strike_and_execution_spans() {
+        let captured = CapturedLog::default();
+        let subscriber = tracing_subscriber::fmt().json().with_writer(captured.clone()).finish();
+        let mut engine = AMMPredictiveEngine::new(120_000.0).await;
+        let opportunity = ArbitrageOpportunity {
+            arb_type: ArbitrageType::CrossDex,
+            source_pool: "Uniswap V3".to_string(),
+            target_pool: "SushiSwap".to_string(),
+            token_path: Vec::new(),
+            optimal_size: 5_000.0,
+            expected_profit: 50.0,
+            gas_cost: 20.0,
+            confidence: 0.95,
+        };
+        {
+            let _guard = tracing::subscriber::set_default(subscriber);
+            let span = info_span!("strike", execution_id = %Uuid::new_v4());
+            engine.execute_amm_arbitrage(opportunity).instrument(span).await;
+        }
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("one JSON object per line"))
+            .collect();
+        assert!(events.len() >= 2); // "executing arbitrage" plus its outcome
+        for event in &events {
+            let spans: Vec<&str> = event["spans"].as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect();
+            assert_eq!(spans, vec!["strike", "execution"]);
+            assert_eq!(event["span"]["source_pool"], "Uniswap V3");
+            assert!(Uuid::parse_str(event["spans"][0]["execution_id"].as_str().unwrap()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_gini_coefficient_hand_computed() {
+        assert_eq!(gini_coefficient(&[5.0, 5.0, 5.0, 5.0]), 0.0);
+        assert!((gini_coefficient(&[0.0, 0.0, 0.0, 10.0]) - 0.75).abs() < 1e-12);
+        // Sorted [1, 2, 3]: 2*(1 + 4 + 9) / (3*6) - 4/3 = 2/9
+        assert!((gini_coefficient(&[3.0, 1.0, 2.0]) - 2.0 / 9.0).abs() < 1e-12);
+        assert_eq!(gini_coefficient(&[]), 0.0);
+        // A NaN balance poisons the result instead of panicking the sort
+        assert!(gini_coefficient(&[2.0, f64::NAN, 1.0]).is_nan());
+    }
+
+    #[test]
+    fn test_holder_deltas_across_snapshots() {
+        let mut analyzer = HolderDistributionAnalyzer::new();
+        let token = Address::from_low_u64_be(40);
+        let now = Utc::now();
+
+        let first = analyzer.ingest_balances(token, &balances(&[0.0, 0.0, 0.0, 100.0], 100.0, 100), now);
+        assert_eq!(first.gini_delta, 0.0);
+        assert_eq!(first.score, 0.0);
+
+        let second = analyzer.ingest_balances(
+            token,
+            &balances(&[25.0, 25.0, 25.0, 25.0], 100.0, 120),
+            now + Duration::hours(1),
+        );
+        assert!((second.gini_delta + 0.75).abs() < 1e-12);
+        assert!((second.holder_growth_rate - 0.2).abs() < 1e-12);
+        assert_eq!(second.top10_delta, 0.0);
+        assert!(second.score > 0.0);
+    }
+
+    #[test]
+    fn test_exchange_wallets_excluded_from_concentration() {
+        let exchange = Address::from_low_u64_be(1000);
+        let mut config = HolderAnalyzerConfig::default();
+        config.exchange_wallets.insert(exchange, "Binance Hot Wallet".to_string());
+        let mut analyzer = HolderDistributionAnalyzer::with_config(config);
+
+        // Exchange holds half the supply; the rest is evenly spread
+        let signal = analyzer.ingest_balances(
+            Address::from_low_u64_be(41),
+            &balances(&[500.0, 250.0, 250.0], 1000.0, 3),
+            Utc::now(),
+        );
+        assert_eq!(signal.gini, 0.0);
+        assert!((signal.top10_pct - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_holder_history_round_trips_through_bootstrap_points() {
+        let mut analyzer = HolderDistributionAnalyzer::new();
+        let token = Address::from_low_u64_be(42);
+        let start = Utc::now() - Duration::hours(3);
+        for (i, holders) in [100u64, 150, 225].into_iter().enumerate() {
+            analyzer.record_holder_count(token, holders, 0.4, start + Duration::hours(i as i64));
+        }
+
+        let mut history = analyzer.holder_history();
+        assert_eq!(history.len(), 1);
+        history.insert("not-an-address".to_string(), Vec::new());
+
+        let mut restored = HolderDistributionAnalyzer::new();
+        assert_eq!(restored.install_holder_history(&history), 1);
+        assert_eq!(restored.holder_history(), analyzer.holder_history());
+
+        // The next point compares against the restored series rather than starting cold
+        let signal = restored.record_holder_count(token, 300, 0.4, Utc::now());
+        assert!(signal.holder_growth_rate > 0.0);
+    }
+
+    #[test]
+    fn test_profitable_wallets_accumulating_score_positive() {
+        let mut tracker = WalletActivityTracker::new();
+        let now = Utc::now();
+        let history = now - Duration::days(3);
+
+        // Wallets 1 and 2 bought token 10 early and sold at a profit
+        for wallet in [1, 2] {
+            tracker.ingest_transfer(transfer(10, POOL, wallet, 10_000.0, 1.0, history));
+            tracker.ingest_transfer(transfer(10, wallet, POOL, 10_000.0, 1.5, history + Duration::hours(6)));
+        }
+        assert!(tracker.smart_money_score(&Address::from_low_u64_be(1)) > 0.5);
+
+        // Now they accumulate token 20 within the lookback window
+        let token = Address::from_low_u64_be(20);
+        tracker.set_total_supply(token, 1_000_000.0);
+        for wallet in [1, 2] {
+            tracker.ingest_transfer(transfer(20, POOL, wallet, 10_000.0, 1.0, now - Duration::minutes(10)));
+        }
+
+        let signal = tracker.token_signal(token, now);
+        assert!(signal.accumulation_score > 0.0);
+        assert_eq!(signal.distribution_score, 0.0);
+        assert!(signal.smart_money_net_flow > 0.0);
+        assert_eq!(signal.net_flow_direction, FlowDirection::In);
+        assert_eq!(signal.top_wallet_changes[0].wallet, Address::from_low_u64_be(POOL));
+    }
+
+    #[test]
+    fn test_deployer_distribution_scores_negative() {
+        let mut tracker = WalletActivityTracker::new();
+        let now = Utc::now();
+        let token = Address::from_low_u64_be(30);
+        tracker.set_total_supply(token, 1_000_000.0);
+
+        // Deployer mints the supply, then dumps it onto retail wallets
+        tracker.ingest_transfer(transfer(30, ZERO, DEPLOYER, 1_000_000.0, 0.0, now - Duration::minutes(50)));
+        for buyer in 1..=5 {
+            tracker.ingest_transfer(transfer(30, DEPLOYER, 300 + buyer, 50_000.0, 0.8, now - Duration::minutes(30)));
+        }
+
+        let signal = tracker.token_signal(token, now);
+        assert!(signal.distribution_score > 0.0);
+        assert!(signal.accumulation_score - signal.distribution_score < 0.0);
+        assert!(signal.smart_money_net_flow < 0.0);
+        assert_eq!(signal.net_flow_direction, FlowDirection::Out);
+    }
+
+    #[test]
+    fn test_wallet_capacity_keeps_top_wallets_by_volume() {
+        let mut tracker = WalletActivityTracker::with_config(WalletTrackerConfig {
+            max_wallets: 3,
+            ..WalletTrackerConfig::default()
+        });
+        let start = Utc::now() - Duration::hours(1);
+        // Wallet 1 is the least recent but trades the most
+        tracker.ingest_transfer(transfer(10, POOL, 1, 100.0, 1.0, start));
+        for wallet in 2..=4 {
+            tracker.ingest_transfer(transfer(10, POOL, wallet, 1.0, 1.0, start + Duration::minutes(wallet as i64)));
+        }
+
+        assert_eq!(tracker.wallets.len(), 3);
+        assert!(tracker.wallets.contains_key(&Address::from_low_u64_be(1)));
+        assert!(tracker.wallets.contains_key(&Address::from_low_u64_be(POOL)));
+        // Equal volumes: the least recently active small wallets went first
+        assert!(!tracker.wallets.contains_key(&Address::from_low_u64_be(2)));
+        assert!(!tracker.wallets.contains_key(&Address::from_low_u64_be(3)));
+        assert!(tracker.wallets.contains_key(&Address::from_low_u64_be(4)));
+    }
+
+    fn pool(fee_bps: u32, liquidity_usd: f64) -> AmmPool {
+        AmmPool {
+            token_a: "WETH".to_string(),
+            token_b: "USDC".to_string(),
+            fee_bps,
+            liquidity_usd,
+        }
+    }
+
+    #[test]
+    fn test_fee_tier_trades_depth_against_fee() {
+        let bot = AMMBot::new(0, "Uniswap_V3".to_string(), 100_000.0);
+        let pools = [pool(5, 2_000_000.0), pool(30, 200_000_000.0), pool(100, 5_000_000.0)];
+
+        // Small trades barely move either pool, so the cheapest fee wins
+        assert_eq!(bot.select_fee_tier("WETH", "USDC", 1_000.0, &pools), 5);
+        // A $1M trade would walk the thin 0.05% pool; the deep 0.3% pool is cheaper all-in
+        assert_eq!(bot.select_fee_tier("usdc", "weth", 1_000_000.0, &pools), 30);
+        assert!(pools[1].execution_cost(1_000_000.0) < pools[0].execution_cost(1_000_000.0));
+
+        // Pools for other pairs and empty pools are ignored
+        let mut other = pool(5, 1e12);
+        other.token_b = "DAI".to_string();
+        assert_eq!(bot.select_fee_tier("WETH", "USDC", 1_000.0, &[other, pool(1, 0.0), pool(100, 1e6)]), 100);
+        assert_eq!(bot.select_fee_tier("WETH", "USDC", 1_000.0, &[]), DEFAULT_FEE_TIER_BPS);
+    }
+
+    #[test]
+    fn test_fee_tier_rationale_is_a_structured_event() {
+        let captured = CapturedLog::default();
+        let subscriber = tracing_subscriber::fmt().json().with_writer(captured.clone()).finish();
+        let bot = AMMBot::new(0, "Uniswap_V3".to_string(), 100_000.0);
+        let pools = [pool(5, 2_000_000.0), pool(30, 200_000_000.0)];
+        tracing::subscriber::with_default(subscriber, || bot.select_fee_tier("WETH", "USDC", 1_000_000.0, &pools));
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let event: serde_json::Value = serde_json::from_str(output.lines().next().unwrap()).unwrap();
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["fields"]["fee_tier"], 30);
+        let edge_bps = event["fields"]["expected_edge_bps"].as_f64().unwrap();
+        assert!((edge_bps - pools[1].execution_cost(1_000_000.0) * 10_000.0).abs() < 1e-9);
+    }
+
+    fn swap_log(venue: SwapVenue, data: Vec<Token>) -> Log {
+        Log {
+            address: Address::from_low_u64_be(0xbeef),
+            topics: vec![venue.topic0(), H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+            data: ethers::abi::encode(&data).into(),
+            block_number: Some(18_000_000u64.into()),
+            log_index: Some(U256::from(7)),
+            ..Default::default()
+        }
+    }
+
+    fn int(value: i128) -> Token {
+        Token::Int(I256::from(value).into_raw())
+    }
+
+    const WETH_USDC: (u8, u8) = (18, 6);
+
+    #[test]
+    fn test_uniswap_v3_swap_log_decodes_buy() {
+        // Trader takes 2 WETH (token0) out of the pool for 3,600 USDC in
+        let log = swap_log(SwapVenue::UniswapV3, vec![
+            int(-2_000_000_000_000_000_000),
+            int(3_600_000_000),
+            Token::Uint(U256::from(2u128.pow(96)) * 60),
+            Token::Uint(U256::from(5_000_000_000_000_000u128)),
+            int(-195_000),
+        ]);
+        let pricing = PoolPricing::pair(WETH_USDC, (1_800.0, 1.0));
+        let at = Utc::now();
+
+        let trade = OnChainTrade::from_uniswap_v3_swap_log(&log, &pricing, at).unwrap();
+        assert!(trade.is_buy);
+        assert_eq!((trade.amount0, trade.amount1), (-2.0, 3_600.0));
+        assert_eq!(trade.volume_usd, 3_600.0);
+        assert_eq!(trade.tick, Some(-195_000));
+        assert_eq!(trade.liquidity, Some(5_000_000_000_000_000));
+        assert_eq!(trade.sqrt_price_x96, Some(U256::from(2u128.pow(96)) * 60));
+        assert_eq!((trade.block_number, trade.timestamp), (18_000_000, at));
+
+        // A sell puts token0 into the pool; volume is priced on that side
+        let sell = swap_log(SwapVenue::UniswapV3, vec![
+            int(1_000_000_000_000_000_000), int(-1_790_000_000), Token::Uint(U256::one()), Token::Uint(U256::one()), int(0),
+        ]);
+        let trade = OnChainTrade::from_uniswap_v3_swap_log(&sell, &pricing, at).unwrap();
+        assert!(!trade.is_buy);
+        assert_eq!(trade.volume_usd, 1_800.0);
+    }
+
+    #[tokio::test]
+    async fn test_swaps_in_blocks_without_a_timestamp_are_skipped() {
+        let (provider, mock) = Provider::mocked();
+        let log = |block: u64| Log {
+            block_number: Some(block.into()),
+            ..swap_log(SwapVenue::UniswapV3, vec![
+                int(-2_000_000_000_000_000_000), int(3_600_000_000), Token::Uint(U256::one()), Token::Uint(U256::one()), int(0),
+            ])
+        };
+        let mined = ethers::types::Block::<ethers::types::TxHash> { timestamp: U256::from(1_700_000_000u64), ..Default::default() };
+        // Responses pop last-in first-out: logs, then block 1 (missing), then block 2
+        mock.push(mined).unwrap();
+        mock.push::<serde_json::Value, _>(serde_json::Value::Null).unwrap();
+        mock.push::<Vec<Log>, _>(vec![log(1), log(1), log(2)]).unwrap();
+
+        let pricing = PoolPricing::pair(WETH_USDC, (1_800.0, 1.0));
+        let trades = fetch_swap_trades(&provider, SwapVenue::UniswapV3, Address::zero(), &pricing, 1, 2).await.unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].block_number, 2);
+        assert_eq!(trades[0].timestamp, DateTime::from_timestamp(1_700_000_000, 0).unwrap());
+    }
+
+    #[derive(Debug)]
+    struct QueuedSwaps(std::sync::Mutex<Vec<Vec<OnChainTrade>>>);
+
+    #[async_trait::async_trait]
+    impl SwapFeed for QueuedSwaps {
+        async fn fetch_swaps(&self, _since: DateTime<Utc>) -> Vec<OnChainTrade> {
+            self.0.lock().unwrap().pop().unwrap_or_default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_swap_feed_volumes_replace_simulated_ones() {
+        let swap = |minutes_ago: i64, volume_usd: f64| OnChainTrade {
+            venue: SwapVenue::UniswapV3,
+            pool: Address::zero(),
+            block_number: 0,
+            log_index: None,
+            tx_hash: None,
+            timestamp: Utc::now() - Duration::minutes(minutes_ago),
+            coins: [0, 1],
+            is_buy: true,
+            amount0: 0.0,
+            amount1: 0.0,
+            volume_usd,
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+        };
+        let mut engine = AMMPredictiveEngine::new(100_000.0).await;
+        engine.set_swap_feed(Arc::new(QueuedSwaps(std::sync::Mutex::new(vec![
+            vec![swap(1, 100.0)],
+            vec![swap(26 * 60, 1_000_000.0), swap(3 * 60, 5_000.0), swap(30, 2_000.0), swap(2, 300.0)],
+        ]))));
+
+        engine.ingest_swaps(Utc::now()).await;
+        engine.ingest_swaps(Utc::now()).await;
+        // The 26-hour-old swap fell out of every window
+        assert_eq!(engine.swap_trades.len(), 4);
+        assert_eq!(engine.fetch_5m_volume().await, 400.0);
+        assert_eq!(engine.fetch_1h_volume().await, 2_400.0);
+        assert_eq!(engine.fetch_24h_volume().await, 7_400.0);
+    }
+
+    #[test]
+    fn test_sushiswap_and_curve_swap_logs() {
+        let at = Utc::now();
+        let sushi = swap_log(SwapVenue::SushiSwap, vec![
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::from(1_800_000_000u64)),
+            Token::Uint(U256::from(1_000_000_000_000_000_000u64)),
+            Token::Uint(U256::zero()),
+        ]);
+        let trade = OnChainTrade::from_sushiswap_swap_log(&sushi, &PoolPricing::pair(WETH_USDC, (1_800.0, 1.0)), at).unwrap();
+        assert!(trade.is_buy);
+        assert_eq!((trade.amount0, trade.amount1, trade.volume_usd), (-1.0, 1_800.0, 1_800.0));
+        assert_eq!(trade.tick, None);
+
+        // 3pool: sell 1,000 USDC (coin 1) for DAI (coin 0)
+        let pricing = PoolPricing { decimals: vec![18, 6, 6], prices_usd: vec![1.0, 1.0, 1.0] };
+        let curve = swap_log(SwapVenue::Curve, vec![
+            int(1), Token::Uint(U256::from(1_000_000_000u64)), int(0), Token::Uint(U256::from(999_500_000_000_000_000_000u128)),
+        ]);
+        let trade = OnChainTrade::from_curve_exchange_log(&curve, &pricing, at).unwrap();
+        assert!(trade.is_buy);
+        assert_eq!(trade.coins, [0, 1]);
+        assert_eq!((trade.amount0, trade.amount1, trade.volume_usd), (-999.5, 1_000.0, 1_000.0));
+
+        // USDC (coin 1) bought with USDT (coin 2): amounts follow coin order, not trade order
+        let curve = swap_log(SwapVenue::Curve, vec![
+            int(2), Token::Uint(U256::from(500_000_000u64)), int(1), Token::Uint(U256::from(499_000_000u64)),
+        ]);
+        let trade = OnChainTrade::from_curve_exchange_log(&curve, &pricing, at).unwrap();
+        assert_eq!(trade.coins, [1, 2]);
+        assert!(trade.is_buy);
+        assert_eq!((trade.amount0, trade.amount1, trade.volume_usd), (-499.0, 500.0, 500.0));
+
+        let unknown_coin = swap_log(SwapVenue::Curve, vec![
+            int(5), Token::Uint(U256::one()), int(0), Token::Uint(U256::one()),
+        ]);
+        assert_eq!(OnChainTrade::from_curve_exchange_log(&unknown_coin, &pricing, at), Err(ParseError::UnknownCoin(5)));
+        // A Sushi log is not a V3 swap
+        assert!(matches!(
+            OnChainTrade::from_uniswap_v3_swap_log(&sushi, &pricing, at),
+            Err(ParseError::WrongEvent { venue: SwapVenue::UniswapV3, .. })
+        ));
+    }
+}
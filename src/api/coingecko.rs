@@ -1,16 +1,86 @@
 // CoinGecko API Integration
 // Provides market data and price feeds
 
-use super::{ApiConfig, ApiResult, MarketData, MarketDataProvider};
-use reqwest::Client;
+use super::{ApiConfig, ApiError, ApiResult, MarketData, MarketDataProvider};
+use crate::monitoring::api_calls::{ApiCallRecorder, ApiProvider};
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::time::SystemTime;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
 use tokio::time::{sleep, Duration};
 
+/// Coin metadata (contracts, genesis date, categories) rarely changes; refetch weekly
+pub const METADATA_CACHE_TTL_HOURS: i64 = 7 * 24;
+
 pub struct CoinGeckoClient {
     client: Client,
     config: ApiConfig,
     base_url: String,
+    call_recorder: Option<Arc<ApiCallRecorder>>,
+    metadata_cache: Mutex<HashMap<String, CachedMetadata>>, // Keyed by coin id and by platform:contract
+    metadata_cache_path: Option<PathBuf>,
+}
+
+/// Static facts about a coin from CoinGecko's `/coins` endpoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub id: String,
+    pub symbol: String,
+    pub contract_addresses: HashMap<String, String>, // CoinGecko platform id -> contract address
+    pub listed_at: Option<DateTime<Utc>>, // Genesis date, midnight UTC
+    pub categories: Vec<String>,
+    pub market_cap_rank: Option<u32>,
+}
+
+impl TokenMetadata {
+    /// Metadata from a `/coins/{id}` or `/coins/{platform}/contract/{address}` body
+    pub fn from_coin(coin: &Value) -> Option<Self> {
+        let contract_addresses = coin["platforms"]
+            .as_object()
+            .map(|platforms| {
+                platforms
+                    .iter()
+                    .filter_map(|(platform, address)| {
+                        let address = address.as_str().filter(|a| !a.is_empty())?;
+                        (!platform.is_empty()).then(|| (platform.clone(), address.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let listed_at = coin["genesis_date"]
+            .as_str()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|midnight| midnight.and_utc());
+        let categories = coin["categories"]
+            .as_array()
+            .map(|categories| categories.iter().filter_map(|c| c.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        Some(Self {
+            id: coin["id"].as_str()?.to_string(),
+            symbol: coin["symbol"].as_str().unwrap_or_default().to_uppercase(),
+            contract_addresses,
+            listed_at,
+            categories,
+            market_cap_rank: coin["market_cap_rank"].as_u64().and_then(|rank| u32::try_from(rank).ok()),
+        })
+    }
+
+    /// Hours since listing, or None without a genesis date
+    pub fn age_hours(&self, now: DateTime<Utc>) -> Option<u32> {
+        let hours = (now - self.listed_at?).num_hours().max(0);
+        u32::try_from(hours).ok()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedMetadata {
+    metadata: TokenMetadata,
+    fetched_at: DateTime<Utc>,
 }
 
 impl CoinGeckoClient {
@@ -28,9 +98,39 @@ impl CoinGeckoClient {
                 .expect("Failed to build HTTP client"),
             config,
             base_url,
+            call_recorder: None,
+            metadata_cache: Mutex::new(HashMap::new()),
+            metadata_cache_path: None,
         }
     }
 
+    /// Point the client at a different host (e.g. a local mock server)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Keep token metadata in `path` across runs, starting from whatever it already holds.
+    /// An unreadable file starts the cache empty and is overwritten on the next fetch.
+    pub fn with_metadata_cache(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|raw| {
+            serde_json::from_str::<HashMap<String, CachedMetadata>>(&raw).map_err(|e| e.to_string())
+        }) {
+            Ok(entries) => self.metadata_cache = Mutex::new(entries),
+            Err(e) if path.exists() => tracing::warn!("⚠️  CoinGecko metadata cache unreadable, starting empty: {}", e),
+            Err(_) => {}
+        }
+        self.metadata_cache_path = Some(path);
+        self
+    }
+
+    /// Report every request to the monitoring API-call accounting
+    pub fn with_call_recorder(mut self, recorder: Arc<ApiCallRecorder>) -> Self {
+        self.call_recorder = Some(recorder);
+        self
+    }
+
     /// Convert CoinGecko ID to trading symbol
     fn id_to_symbol(id: &str) -> String {
         match id {
@@ -41,6 +141,92 @@ impl CoinGeckoClient {
         }
     }
 
+    /// CoinGecko coin id for a trading symbol, where one is known
+    pub fn symbol_to_id(symbol: &str) -> Option<&'static str> {
+        match symbol {
+            "BTC/USDT" => Some("bitcoin"),
+            "ETH/USDT" => Some("ethereum"),
+            "SOL/USDT" => Some("solana"),
+            _ => None,
+        }
+    }
+
+    /// Metadata for a coin id. Unknown coins fail with `ApiError::NotFound`.
+    pub async fn get_token_metadata(&self, id: &str) -> ApiResult<TokenMetadata> {
+        let path = format!("/coins/{}?localization=false&tickers=false&market_data=false&community_data=false&developer_data=false", id);
+        self.cached_metadata(format!("id:{}", id), "/coins", &path).await
+    }
+
+    /// Metadata for the coin deployed at `address` on `platform` (a CoinGecko platform id
+    /// such as "ethereum" or "arbitrum-one"). Unknown contracts fail with `ApiError::NotFound`.
+    pub async fn get_token_metadata_by_contract(&self, platform: &str, address: &str) -> ApiResult<TokenMetadata> {
+        let address = address.to_lowercase();
+        let path = format!("/coins/{}/contract/{}", platform, address);
+        self.cached_metadata(format!("contract:{}:{}", platform, address), "/coins/contract", &path).await
+    }
+
+    async fn cached_metadata(&self, key: String, endpoint: &str, path: &str) -> ApiResult<TokenMetadata> {
+        let now = Utc::now();
+        let fresh = {
+            let cache = self.metadata_cache.lock().unwrap_or_else(|e| e.into_inner());
+            cache
+                .get(&key)
+                .filter(|entry| now - entry.fetched_at < chrono::Duration::hours(METADATA_CACHE_TTL_HOURS))
+                .map(|entry| entry.metadata.clone())
+        };
+        if let Some(metadata) = fresh {
+            return Ok(metadata);
+        }
+
+        let started = Instant::now();
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .header("x-cg-pro-api-key", &self.config.api_key)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        if let Some(recorder) = &self.call_recorder {
+            recorder.record_result(ApiProvider::CoinGecko, endpoint, started, &response);
+        }
+        let response = response.map_err(|e| -> Box<dyn std::error::Error> {
+            match e.status() {
+                Some(StatusCode::NOT_FOUND) => Box::new(ApiError::NotFound(key.clone())),
+                Some(StatusCode::TOO_MANY_REQUESTS) => Box::new(ApiError::RateLimited),
+                Some(status) => format!("API error: {}", status).into(),
+                None => e.to_string().into(),
+            }
+        })?;
+        let coin: Value = response.json().await?;
+        self.rate_limit().await;
+        let metadata = TokenMetadata::from_coin(&coin).ok_or("Missing coin id")?;
+
+        let serialized = {
+            let mut cache = self.metadata_cache.lock().unwrap_or_else(|e| e.into_inner());
+            cache.insert(key, CachedMetadata { metadata: metadata.clone(), fetched_at: now });
+            self.metadata_cache_path.as_ref().map(|_| serde_json::to_string(&*cache))
+        };
+        if let (Some(path), Some(serialized)) = (&self.metadata_cache_path, serialized) {
+            let saved = serialized.map_err(|e| e.to_string()).and_then(|raw| Self::write_cache_file(path, &raw));
+            if let Err(e) = saved {
+                tracing::warn!("⚠️  Could not save CoinGecko metadata cache: {}", e);
+            }
+        }
+        Ok(metadata)
+    }
+
+    /// Write through a temp file and rename, so a crash or a concurrent save never leaves a torn cache
+    fn write_cache_file(path: &std::path::Path, raw: &str) -> Result<(), String> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(format!(".{}.tmp", uuid::Uuid::new_v4().simple()));
+        let tmp = PathBuf::from(tmp);
+        std::fs::write(&tmp, raw).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, path).map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            e.to_string()
+        })
+    }
+
     /// Rate limiting helper
     async fn rate_limit(&self) {
         // CoinGecko has different limits for free vs pro
@@ -53,28 +239,28 @@ impl CoinGeckoClient {
 impl MarketDataProvider for CoinGeckoClient {
     async fn get_market_data(&self, symbol: &str) -> ApiResult<MarketData> {
         // Convert symbol to CoinGecko ID
-        let coin_id = match symbol {
-            "BTC/USDT" => "bitcoin",
-            "ETH/USDT" => "ethereum",
-            "SOL/USDT" => "solana",
-            _ => return Err("Unsupported symbol".into()),
-        };
+        let coin_id = Self::symbol_to_id(symbol).ok_or("Unsupported symbol")?;
 
         let url = format!(
             "{}/coins/{}?localization=false&tickers=false&community_data=false&developer_data=false",
             self.base_url, coin_id
         );
 
+        let started = Instant::now();
         let response = self
             .client
             .get(&url)
             .header("x-cg-pro-api-key", &self.config.api_key)
             .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()).into());
+            .await
+            .and_then(|r| r.error_for_status());
+        if let Some(recorder) = &self.call_recorder {
+            recorder.record_result(ApiProvider::CoinGecko, "/coins", started, &response);
         }
+        let response = response.map_err(|e| match e.status() {
+            Some(status) => format!("API error: {}", status),
+            None => e.to_string(),
+        })?;
 
         let data: Value = response.json().await?;
         
@@ -99,8 +285,8 @@ impl MarketDataProvider for CoinGeckoClient {
     async fn subscribe_prices(&self, symbols: Vec<String>) -> ApiResult<()> {
         // CoinGecko doesn't have WebSocket support
         // For real-time data, you would poll or use their webhook service
-        log::info!("CoinGecko price subscription requested for: {:?}", symbols);
-        log::warn!("Note: CoinGecko doesn't support WebSocket. Consider polling or webhooks.");
+        tracing::info!("CoinGecko price subscription requested for: {:?}", symbols);
+        tracing::warn!("Note: CoinGecko doesn't support WebSocket. Consider polling or webhooks.");
         Ok(())
     }
 }
@@ -113,5 +299,7 @@ mod tests {
     fn test_symbol_conversion() {
         assert_eq!(CoinGeckoClient::id_to_symbol("bitcoin"), "BTC/USDT");
         assert_eq!(CoinGeckoClient::id_to_symbol("ethereum"), "ETH/USDT");
+        assert_eq!(CoinGeckoClient::symbol_to_id("SOL/USDT"), Some("solana"));
+        assert_eq!(CoinGeckoClient::symbol_to_id("PEPE/USDT"), None);
     }
 }
@@ -0,0 +1,69 @@
+// Token Metadata Enrichment
+// Fills TokenSnapshot age, contract and categories from CoinGecko coin metadata
+
+use super::coingecko::{CoinGeckoClient, TokenMetadata};
+use super::{ApiError, ApiResult};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use strike_box::TokenSnapshot;
+
+/// Looks up snapshots on CoinGecko: on-chain snapshots by contract on one platform, CEX
+/// pairs by the coin id their symbol maps to
+pub struct TokenEnricher {
+    coingecko: Arc<CoinGeckoClient>,
+    platform: String, // CoinGecko platform id the scanner's contracts live on, e.g. "ethereum"
+}
+
+impl std::fmt::Debug for TokenEnricher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenEnricher").field("platform", &self.platform).finish_non_exhaustive()
+    }
+}
+
+impl TokenEnricher {
+    pub fn new(coingecko: Arc<CoinGeckoClient>, platform: impl Into<String>) -> Self {
+        Self { coingecko, platform: platform.into() }
+    }
+
+    /// Metadata for the token behind `snapshot`. Tokens CoinGecko doesn't list fail with
+    /// `ApiError::NotFound`, so the caller can fall back to an on-chain lookup.
+    pub async fn lookup(&self, snapshot: &TokenSnapshot) -> ApiResult<TokenMetadata> {
+        if snapshot.has_onchain_data() {
+            return self.coingecko.get_token_metadata_by_contract(&self.platform, &snapshot.token_address).await;
+        }
+        match CoinGeckoClient::symbol_to_id(&snapshot.token_symbol) {
+            Some(id) => self.coingecko.get_token_metadata(id).await,
+            None => Err(Box::new(ApiError::NotFound(snapshot.token_symbol.clone()))),
+        }
+    }
+
+    /// Looks up `snapshot` and applies what was found. Age is left alone when the coin has
+    /// no genesis date; check `listed_at` on the returned metadata before relying on it.
+    pub async fn enrich(&self, snapshot: &mut TokenSnapshot) -> ApiResult<TokenMetadata> {
+        let metadata = self.lookup(snapshot).await?;
+        Self::apply(snapshot, &metadata, &self.platform, Utc::now());
+        Ok(metadata)
+    }
+
+    /// Age and deployment time from the listing date, categories, and for CEX pairs the
+    /// contract on `platform` in place of the pair name
+    pub fn apply(snapshot: &mut TokenSnapshot, metadata: &TokenMetadata, platform: &str, now: DateTime<Utc>) {
+        if let (Some(listed_at), Some(hours)) = (metadata.listed_at, metadata.age_hours(now)) {
+            snapshot.token_age_hours = hours;
+            snapshot.deployment_timestamp = listed_at;
+        }
+        if !snapshot.has_onchain_data() {
+            if let Some(address) = metadata.contract_addresses.get(platform) {
+                snapshot.token_address = address.clone();
+            }
+        }
+        if !metadata.categories.is_empty() {
+            snapshot.categories = Some(metadata.categories.clone());
+        }
+    }
+
+    /// Whether `error` means CoinGecko doesn't know the token, as opposed to a failed request
+    pub fn is_not_found(error: &(dyn std::error::Error + 'static)) -> bool {
+        matches!(error.downcast_ref::<ApiError>(), Some(ApiError::NotFound(_)))
+    }
+}
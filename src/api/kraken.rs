@@ -2,23 +2,62 @@
 // Provides trading execution and account management
 
 use super::{
-    ApiConfig, ApiResult, Balance, Order, OrderBook, OrderBookLevel, OrderResponse, OrderSide, OrderStatus, OrderType,
-    TradingExchange,
+    ApiConfig, ApiError, ApiResult, Balance, ExecutionError, Order, OrderBook, OrderBookLevel, OrderResponse,
+    OrderSide, OrderStatus, OrderType, TradingExchange,
 };
+use crate::monitoring::api_calls::{ApiCallOutcome, ApiCallRecorder, ApiProvider};
+use crate::symbols::{Instrument, SharedSymbolRegistry, SymbolRegistry};
 use base64::Engine;
 use hmac::{Hmac, Mac};
 use reqwest::Client;
 use serde_json::{json, Value};
 use sha2::{Digest, Sha256, Sha512};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
 use tokio::time::{sleep, Duration};
 
 type HmacSha512 = Hmac<Sha512>;
 
+const KRAKEN_VENUE: &str = "kraken";
+
+// Legacy Kraken asset codes carry an X (crypto) or Z (fiat) prefix
+const KRAKEN_LEGACY_ASSETS: [&str; 18] = [
+    "XXBT", "XETH", "XLTC", "XXRP", "XXLM", "XXMR", "XZEC", "XETC", "XMLN",
+    "XREP", "XXDG", "ZUSD", "ZEUR", "ZGBP", "ZCAD", "ZJPY", "ZAUD", "ZCHF",
+];
+
+/// Strictly increasing nonce, seeded from wall-clock milliseconds
+#[derive(Debug, Default)]
+pub struct NonceGenerator {
+    last: Mutex<u64>,
+}
+
+impl NonceGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn next(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        *last = now.max(*last + 1);
+        *last
+    }
+}
+
 pub struct KrakenClient {
     client: Client,
     config: ApiConfig,
     base_url: String,
+    nonce: NonceGenerator,
+    nonce_order: tokio::sync::Mutex<()>, // Held from nonce allocation until Kraken answers
+    symbols: SharedSymbolRegistry,
+    asset_pairs_loaded: RwLock<bool>, // AssetPairs registered into `symbols`
+    call_recorder: Option<Arc<ApiCallRecorder>>,
 }
 
 impl KrakenClient {
@@ -36,13 +75,52 @@ impl KrakenClient {
                 .unwrap(),
             config,
             base_url,
+            nonce: NonceGenerator::new(),
+            nonce_order: tokio::sync::Mutex::new(()),
+            symbols: SymbolRegistry::new().shared(),
+            asset_pairs_loaded: RwLock::new(false),
+            call_recorder: None,
+        }
+    }
+
+    /// Report every request to the monitoring API-call accounting
+    pub fn with_call_recorder(mut self, recorder: Arc<ApiCallRecorder>) -> Self {
+        self.call_recorder = Some(recorder);
+        self
+    }
+
+    fn record_call<T, E>(&self, endpoint: &str, started: Instant, result: &Result<T, E>) {
+        if let Some(recorder) = &self.call_recorder {
+            recorder.record_result(ApiProvider::Kraken, endpoint, started, result);
         }
     }
 
+    /// Resolve pairs through a registry shared with the scanner and engine
+    pub fn with_symbol_registry(mut self, symbols: SharedSymbolRegistry) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Point the client at a different host (e.g. a local mock server)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
     /// Generate Kraken API signature
     fn generate_signature(&self, path: &str, nonce: u64, post_data: &str) -> Result<String, Box<dyn std::error::Error>> {
+        Self::sign_request(&self.config.api_secret, path, nonce, post_data)
+    }
+
+    /// API-Sign = base64(HMAC-SHA512(base64decode(secret), path + SHA256(nonce + postdata)))
+    pub fn sign_request(
+        api_secret: &str,
+        path: &str,
+        nonce: u64,
+        post_data: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
         let secret_decoded = base64::engine::general_purpose::STANDARD
-            .decode(&self.config.api_secret)
+            .decode(api_secret)
             .map_err(|e| format!("Invalid base64 API secret: {}", e))?;
 
         let sha256_hash = Sha256::digest(format!("{}{}", nonce, post_data).as_bytes());
@@ -55,31 +133,117 @@ impl KrakenClient {
         Ok(base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes()))
     }
 
-    /// Convert internal symbol to Kraken format
-    fn to_kraken_symbol(symbol: &str) -> String {
-        match symbol {
-            "BTC/USDT" => "XBTUSDT".to_string(),
-            "ETH/USDT" => "ETHUSDT".to_string(),
-            "SOL/USDT" => "SOLUSDT".to_string(),
-            _ => symbol.replace("/", ""),
+    /// Kraken asset code to the standard ticker ("XXBT" -> "BTC", "ZUSD" -> "USD", "XBT.M" -> "BTC.M")
+    pub fn normalize_asset(code: &str) -> String {
+        let (base, suffix) = match code.split_once('.') {
+            Some((base, suffix)) => (base, Some(suffix)),
+            None => (code, None),
+        };
+        let base = if KRAKEN_LEGACY_ASSETS.contains(&base) { &base[1..] } else { base };
+        let base = match base {
+            "XBT" => "BTC",
+            "XDG" => "DOGE",
+            other => other,
+        };
+        match suffix {
+            Some(suffix) => format!("{}.{}", base, suffix),
+            None => base.to_string(),
+        }
+    }
+
+    /// Resolve an internal pair to Kraken's asset-pair code through the symbol registry,
+    /// registering Kraken's AssetPairs into it once
+    pub async fn resolve_pair(&self, symbol: &str) -> ApiResult<String> {
+        if let Some(code) = self.registered_code(symbol) {
+            return Ok(code);
+        }
+        if !*self.asset_pairs_loaded.read().await {
+            // Re-check under the write lock so concurrent callers load only once
+            let mut loaded = self.asset_pairs_loaded.write().await;
+            if !*loaded {
+                let instruments = self.load_asset_pairs().await?;
+                let mut symbols = self.symbols.write().unwrap_or_else(|e| e.into_inner());
+                for instrument in instruments {
+                    symbols.register(instrument);
+                }
+                *loaded = true;
+            }
+        }
+        self.registered_code(symbol)
+            .ok_or_else(|| ApiError::UnknownPair(symbol.to_string()).into())
+    }
+
+    fn registered_code(&self, symbol: &str) -> Option<String> {
+        let symbols = self.symbols.read().unwrap_or_else(|e| e.into_inner());
+        symbols.to_venue_code(symbol, KRAKEN_VENUE).ok().map(str::to_string)
+    }
+
+    /// Instruments from an AssetPairs result, keyed by Kraken's pair code
+    pub fn instruments_from_asset_pairs(result: &Value) -> Vec<Instrument> {
+        let Some(pairs) = result.as_object() else {
+            return Vec::new();
+        };
+        pairs
+            .iter()
+            .filter_map(|(code, info)| {
+                let (base, quote) = info["wsname"].as_str()?.split_once('/')?;
+                Some(Instrument::new(
+                    &Self::normalize_asset(base),
+                    &Self::normalize_asset(quote),
+                    KRAKEN_VENUE,
+                    code,
+                ))
+            })
+            .collect()
+    }
+
+    async fn load_asset_pairs(&self) -> ApiResult<Vec<Instrument>> {
+        let started = Instant::now();
+        let response = self
+            .client
+            .get(format!("{}/0/public/AssetPairs", self.base_url))
+            .send()
+            .await;
+        self.record_call("/0/public/AssetPairs", started, &response);
+        let response = response?;
+        let result = Self::unwrap_response(response.json().await?)?;
+        Ok(Self::instruments_from_asset_pairs(&result))
+    }
+
+    /// Map Kraken's "ECategory:Message" error strings onto typed errors
+    pub fn map_error(message: &str) -> Box<dyn std::error::Error> {
+        let (category, detail) = message.split_once(':').unwrap_or(("", message));
+        match (category, detail) {
+            ("EAPI", "Invalid key") => Box::new(ApiError::InvalidKey),
+            ("EAPI", "Invalid signature") => Box::new(ApiError::InvalidSignature),
+            ("EAPI", "Invalid nonce") => Box::new(ApiError::InvalidNonce),
+            ("EAPI", "Rate limit exceeded") | ("EOrder", "Rate limit exceeded") => Box::new(ApiError::RateLimited),
+            ("EGeneral", "Permission denied") => Box::new(ApiError::PermissionDenied),
+            ("EGeneral", d) if d.starts_with("Invalid arguments") => Box::new(ApiError::InvalidArguments(d.to_string())),
+            ("EQuery", "Unknown asset pair") => Box::new(ApiError::UnknownPair(detail.to_string())),
+            ("EService", d) => Box::new(ApiError::ServiceUnavailable(d.to_string())),
+            ("EOrder", "Insufficient funds") => Box::new(ExecutionError::InsufficientFunds),
+            ("EOrder", "Order minimum not met") => Box::new(ExecutionError::OrderMinimumNotMet),
+            ("EOrder", "Invalid price") => Box::new(ExecutionError::InvalidPrice),
+            ("EOrder", "Orders limit exceeded") => Box::new(ExecutionError::OrdersLimitExceeded),
+            ("EOrder", "Unknown order") => Box::new(ExecutionError::UnknownOrder),
+            ("EOrder", d) => Box::new(ExecutionError::Rejected(d.to_string())),
+            _ => Box::new(ApiError::Other(message.to_string())),
         }
     }
 
-    /// Convert Kraken symbol to internal format
-    fn from_kraken_symbol(symbol: &str) -> String {
-        match symbol {
-            "XBTUSDT" => "BTC/USDT".to_string(),
-            "ETHUSDT" => "ETH/USDT".to_string(),
-            "SOLUSDT" => "SOL/USDT".to_string(),
-            _ => symbol.to_string(),
+    fn unwrap_response(result: Value) -> ApiResult<Value> {
+        if let Some(first) = result["error"].as_array().and_then(|errors| errors.first()) {
+            return Err(Self::map_error(first.as_str().unwrap_or_default()));
         }
+        Ok(result["result"].clone())
     }
 
-    /// Make authenticated request
+    /// Make authenticated request. Kraken rejects a nonce lower than one it has already seen, so
+    /// concurrent requests are serialized until each is answered and nonces arrive in order.
     async fn private_request(&self, endpoint: &str, params: Value) -> ApiResult<Value> {
-        let nonce = SystemTime::now()
-            .duration_since(UNIX_EPOCH)?
-            .as_millis() as u64;
+        let in_flight = self.nonce_order.lock().await;
+        let nonce = self.nonce.next();
 
         let mut post_params = params.as_object()
             .ok_or("Invalid parameters format")?
@@ -90,6 +254,7 @@ impl KrakenClient {
         let path = format!("/0/private/{}", endpoint);
         let signature = self.generate_signature(&path, nonce, &post_data)?;
 
+        let started = Instant::now();
         let response = self
             .client
             .post(format!("{}{}", self.base_url, path))
@@ -98,17 +263,11 @@ impl KrakenClient {
             .header("Content-Type", "application/x-www-form-urlencoded")
             .body(post_data)
             .send()
-            .await?;
-
-        let result: Value = response.json().await?;
-
-        if let Some(errors) = result["error"].as_array() {
-            if !errors.is_empty() {
-                return Err(format!("Kraken API error: {:?}", errors).into());
-            }
-        }
+            .await;
+        drop(in_flight);
+        self.record_call(&path, started, &response);
 
-        Ok(result["result"].clone())
+        Self::unwrap_response(response?.json().await?)
     }
 
     /// Rate limiting
@@ -128,15 +287,16 @@ impl TradingExchange for KrakenClient {
             OrderType::TakeProfit { .. } => "take-profit",
         };
 
+        let pair = self.resolve_pair(&order.symbol).await?;
         let mut params = json!({
-            "pair": Self::to_kraken_symbol(&order.symbol),
+            "pair": pair,
             "type": match order.side {
                 OrderSide::Buy => "buy",
                 OrderSide::Sell => "sell",
             },
             "ordertype": order_type,
             "volume": order.quantity.to_string(),
-            "userref": order.client_order_id,
+            "cl_ord_id": order.client_order_id,
         });
 
         // Add price for limit orders
@@ -211,49 +371,55 @@ impl TradingExchange for KrakenClient {
     }
 
     async fn get_balances(&self) -> ApiResult<Vec<Balance>> {
-        let result = self.private_request("Balance", json!({})).await?;
+        // BalanceEx reports funds held by open orders alongside the total
+        let result = self.private_request("BalanceEx", json!({})).await?;
         self.rate_limit().await;
 
+        let parse = |v: &Value| v.as_str().and_then(|s| s.parse::<f64>().ok());
         let mut balances = Vec::new();
-        
-        if let Some(obj) = result.as_object() {
-            for (asset, balance_str) in obj {
-                if let Some(balance) = balance_str.as_str().and_then(|s| s.parse::<f64>().ok()) {
-                    // Convert Kraken asset names to standard
-                    let asset_name = match asset.as_str() {
-                        "XXBT" => "BTC",
-                        "XETH" => "ETH",
-                        "ZUSD" => "USD",
-                        "USDT" => "USDT",
-                        _ => asset,
-                    };
 
+        if let Some(obj) = result.as_object() {
+            for (asset, entry) in obj {
+                let (total, locked) = match entry {
+                    Value::String(_) => (parse(entry), Some(0.0)),
+                    _ => (parse(&entry["balance"]), Some(parse(&entry["hold_trade"]).unwrap_or(0.0))),
+                };
+                if let (Some(total), Some(locked)) = (total, locked) {
                     balances.push(Balance {
-                        asset: asset_name.to_string(),
-                        free: balance,
-                        locked: 0.0, // Kraken doesn't separate locked balance
-                        total: balance,
+                        asset: Self::normalize_asset(asset),
+                        free: total - locked,
+                        locked,
+                        total,
                     });
                 }
             }
         }
 
+        balances.sort_by(|a, b| a.asset.cmp(&b.asset));
         Ok(balances)
     }
     
     async fn get_order_book(&self, symbol: &str, depth: usize) -> ApiResult<OrderBook> {
+        let pair = self.resolve_pair(symbol).await?;
         let params = json!({
-            "pair": Self::to_kraken_symbol(symbol),
+            "pair": pair,
             "count": depth
         });
         
         let endpoint = format!("{}/public/Depth", self.base_url);
+        let started = Instant::now();
         let response = self.client
             .get(&endpoint)
             .json(&params)
             .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
+            .await;
+        match (&response, &self.call_recorder) {
+            (Ok(r), Some(recorder)) if r.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                recorder.record(ApiProvider::Kraken, "/public/Depth", ApiCallOutcome::RateLimited, started.elapsed());
+            }
+            _ => self.record_call("/public/Depth", started, &response),
+        }
+        let response = response.map_err(|e| format!("Request failed: {}", e))?;
         
         if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
             tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
@@ -265,7 +431,7 @@ impl TradingExchange for KrakenClient {
         self.rate_limit().await;
         
         // Parse Kraken's order book format
-        let pair_data = result[Self::to_kraken_symbol(symbol)].clone();
+        let pair_data = result[pair.as_str()].clone();
         
         let mut bids = Vec::new();
         let mut asks = Vec::new();
@@ -277,7 +443,7 @@ impl TradingExchange for KrakenClient {
                     if arr.len() >= 2 {
                         let price = arr[0].as_str().and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
                         let volume = arr[1].as_str().and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
-                        bids.push(OrderBookLevel { price, volume });
+                        bids.push(OrderBookLevel { price, volume, timestamp: level_timestamp(arr) });
                     }
                 }
             }
@@ -290,7 +456,7 @@ impl TradingExchange for KrakenClient {
                     if arr.len() >= 2 {
                         let price = arr[0].as_str().and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
                         let volume = arr[1].as_str().and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
-                        asks.push(OrderBookLevel { price, volume });
+                        asks.push(OrderBookLevel { price, volume, timestamp: level_timestamp(arr) });
                     }
                 }
             }
@@ -300,18 +466,57 @@ impl TradingExchange for KrakenClient {
             symbol: symbol.to_string(),
             bids,
             asks,
-            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            timestamp: SystemTime::now(),
         })
     }
 }
 
+/// Kraken sends each level as [price, volume, unix seconds]
+fn level_timestamp(level: &[Value]) -> Option<SystemTime> {
+    let seconds = level.get(2)?.as_f64()?;
+    (seconds.is_finite() && seconds >= 0.0).then(|| UNIX_EPOCH + Duration::from_secs_f64(seconds))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_symbol_conversion() {
-        assert_eq!(KrakenClient::to_kraken_symbol("BTC/USDT"), "XBTUSDT");
-        assert_eq!(KrakenClient::from_kraken_symbol("XBTUSDT"), "BTC/USDT");
+        let asset_pairs = json!({
+            "XBTUSDT": { "wsname": "XBT/USDT", "base": "XXBT", "quote": "USDT" },
+            "ETHUSDC": { "wsname": "ETH/USDC", "base": "XETH", "quote": "USDC" },
+            "XXBTZUSD": { "wsname": "XBT/USD", "base": "XXBT", "quote": "ZUSD" },
+        });
+        let mut symbols = SymbolRegistry::new();
+        for instrument in KrakenClient::instruments_from_asset_pairs(&asset_pairs) {
+            symbols.register(instrument);
+        }
+        assert_eq!(symbols.to_venue_code("BTC/USDT", KRAKEN_VENUE), Ok("XBTUSDT"));
+        assert_eq!(symbols.from_venue_code(KRAKEN_VENUE, "XBTUSDT").unwrap().pair(), "BTC/USDT");
+        // Internal pairs name wrapped and legacy tickers
+        assert_eq!(symbols.to_venue_code("WETH/USDC", KRAKEN_VENUE), Ok("ETHUSDC"));
+        assert_eq!(symbols.to_venue_code("btc/usd", KRAKEN_VENUE), Ok("XXBTZUSD"));
+        assert!(symbols.to_venue_code("SOL/USDT", KRAKEN_VENUE).is_err());
+    }
+
+    #[test]
+    fn test_asset_normalization() {
+        assert_eq!(KrakenClient::normalize_asset("XXBT"), "BTC");
+        assert_eq!(KrakenClient::normalize_asset("ZUSD"), "USD");
+        assert_eq!(KrakenClient::normalize_asset("XBT.M"), "BTC.M");
+        assert_eq!(KrakenClient::normalize_asset("USDC"), "USDC");
+        assert_eq!(KrakenClient::normalize_asset("XTZ"), "XTZ");
+    }
+
+    #[test]
+    fn test_nonce_strictly_increasing() {
+        let nonces = NonceGenerator::new();
+        let mut previous = nonces.next();
+        for _ in 0..1000 {
+            let next = nonces.next();
+            assert!(next > previous);
+            previous = next;
+        }
     }
 }
@@ -1,7 +1,8 @@
 // Liquidity Verification Module
 // Ensures trading pairs have sufficient liquidity for entry and exit
 
-use super::{ApiResult, MarketData};
+use super::orderbook_math::{self, BookSide, OrderBookError};
+use super::{ApiError, ApiResult, MarketData, MarketDataProvider, OrderBook, TradingExchange};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -43,6 +44,35 @@ pub struct LiquidityMetrics {
     pub last_updated: std::time::SystemTime,
 }
 
+/// Band around mid, as a fraction, within which order book depth counts (2%, as in strike_box)
+pub const DEPTH_BAND_PCT: f64 = 0.02;
+
+/// Recorded metrics older than this are refetched rather than trusted
+pub const METRICS_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Levels per side requested when the monitor fetches a book itself
+const FETCH_BOOK_DEPTH: usize = 100;
+
+impl LiquidityMetrics {
+    /// Metrics measured from `book`: each side's depth within DEPTH_BAND_PCT of mid, and the
+    /// quoted spread. Errors on an empty or crossed book.
+    pub fn from_order_book(
+        book: &OrderBook,
+        volume_24h_usd: f64,
+        market_maker_count: u32,
+    ) -> Result<Self, OrderBookError> {
+        Ok(Self {
+            symbol: book.symbol.clone(),
+            volume_24h_usd,
+            bid_depth_usd: orderbook_math::depth_within_pct(book, BookSide::Bid, DEPTH_BAND_PCT)?,
+            ask_depth_usd: orderbook_math::depth_within_pct(book, BookSide::Ask, DEPTH_BAND_PCT)?,
+            spread_percent: orderbook_math::spread_bps(book)? / 100.0,
+            market_maker_count,
+            last_updated: std::time::SystemTime::now(),
+        })
+    }
+}
+
 /// Approved trading pairs with verified liquidity
 #[derive(Debug, Clone)]
 pub struct ApprovedPairs {
@@ -52,6 +82,8 @@ pub struct ApprovedPairs {
     pub secondary: Vec<TradingPair>,
     /// Pairs to avoid due to liquidity concerns
     pub blacklist: Vec<String>,
+    /// Known market makers per pair; a book doesn't show who quotes it, so unlisted pairs count 0
+    pub market_makers: HashMap<String, u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -144,15 +176,30 @@ impl Default for ApprovedPairs {
                 "FTT/USDT".to_string(),   // Defunct
                 // Add any low liquidity or problematic pairs
             ],
+            market_makers: HashMap::from([
+                ("BTC/USDT".to_string(), 20),
+                ("ETH/USDT".to_string(), 15),
+                ("SOL/USDT".to_string(), 10),
+                ("MATIC/USDT".to_string(), 5),
+                ("AVAX/USDT".to_string(), 5),
+            ]),
         }
     }
 }
 
+/// Where the monitor fetches books and 24h volume when its recorded metrics are missing or stale
+#[derive(Clone)]
+struct BookSource {
+    exchange: Arc<dyn TradingExchange>,
+    market_data: Arc<dyn MarketDataProvider>,
+}
+
 /// Liquidity monitor
 pub struct LiquidityMonitor {
     requirements: LiquidityRequirements,
     approved_pairs: ApprovedPairs,
     metrics_cache: Arc<RwLock<HashMap<String, LiquidityMetrics>>>,
+    source: Option<BookSource>,
 }
 
 impl LiquidityMonitor {
@@ -161,14 +208,27 @@ impl LiquidityMonitor {
             requirements: LiquidityRequirements::default(),
             approved_pairs: ApprovedPairs::default(),
             metrics_cache: Arc::new(RwLock::new(HashMap::new())),
+            source: None,
         }
     }
 
+    /// Fetch order books from `exchange` and 24h USD volume from `market_data` whenever a
+    /// symbol has no metrics younger than METRICS_MAX_AGE. Without a source only books
+    /// passed to `record_order_book` are measured.
+    pub fn with_sources(
+        mut self,
+        exchange: Arc<dyn TradingExchange>,
+        market_data: Arc<dyn MarketDataProvider>,
+    ) -> Self {
+        self.source = Some(BookSource { exchange, market_data });
+        self
+    }
+
     /// Check if a trading pair has sufficient liquidity
     pub async fn verify_liquidity(&self, symbol: &str) -> ApiResult<bool> {
         // Check blacklist first
         if self.approved_pairs.blacklist.contains(&symbol.to_string()) {
-            log::warn!("Symbol {} is blacklisted due to liquidity concerns", symbol);
+            tracing::warn!("Symbol {} is blacklisted due to liquidity concerns", symbol);
             return Ok(false);
         }
 
@@ -177,7 +237,7 @@ impl LiquidityMonitor {
         let is_secondary = self.approved_pairs.secondary.iter().any(|p| p.symbol == symbol);
 
         if !is_primary && !is_secondary {
-            log::warn!("Symbol {} is not in approved pairs list", symbol);
+            tracing::warn!("Symbol {} is not in approved pairs list", symbol);
             return Ok(false);
         }
 
@@ -194,7 +254,7 @@ impl LiquidityMonitor {
         let is_liquid = volume_ok && depth_ok && spread_ok && makers_ok;
 
         if !is_liquid {
-            log::warn!(
+            tracing::warn!(
                 "Liquidity check failed for {}: volume_ok={}, depth_ok={}, spread_ok={}, makers_ok={}",
                 symbol, volume_ok, depth_ok, spread_ok, makers_ok
             );
@@ -203,7 +263,20 @@ impl LiquidityMonitor {
         Ok(is_liquid)
     }
 
-    /// Get liquidity metrics for a symbol (internal)
+    /// Measure metrics from a fetched order book and cache them for verify_liquidity
+    pub async fn record_order_book(
+        &self,
+        book: &OrderBook,
+        volume_24h_usd: f64,
+        market_maker_count: u32,
+    ) -> Result<LiquidityMetrics, OrderBookError> {
+        let metrics = LiquidityMetrics::from_order_book(book, volume_24h_usd, market_maker_count)?;
+        self.metrics_cache.write().await.insert(book.symbol.clone(), metrics.clone());
+        Ok(metrics)
+    }
+
+    /// Get liquidity metrics for a symbol (internal): the recorded metrics while younger than
+    /// METRICS_MAX_AGE, otherwise measured from a freshly fetched book
     async fn fetch_liquidity_metrics(&self, symbol: &str) -> ApiResult<LiquidityMetrics> {
         // Check cache first
         {
@@ -212,66 +285,22 @@ impl LiquidityMonitor {
                 let age = std::time::SystemTime::now()
                     .duration_since(metrics.last_updated)
                     .unwrap_or_default();
-                
-                // Return cached data if less than 5 minutes old
-                if age.as_secs() < 300 {
+
+                if age < METRICS_MAX_AGE {
                     return Ok(metrics.clone());
                 }
             }
         }
 
-        // In production, fetch from exchange API
-        // For now, return mock data for approved pairs
-        let metrics = if symbol == "BTC/USDT" {
-            LiquidityMetrics {
-                symbol: symbol.to_string(),
-                volume_24h_usd: 2_000_000_000.0,  // $2B
-                bid_depth_usd: 5_000_000.0,       // $5M
-                ask_depth_usd: 5_000_000.0,       // $5M
-                spread_percent: 0.01,             // 0.01%
-                market_maker_count: 20,
-                last_updated: std::time::SystemTime::now(),
-            }
-        } else if symbol == "ETH/USDT" {
-            LiquidityMetrics {
-                symbol: symbol.to_string(),
-                volume_24h_usd: 1_000_000_000.0,  // $1B
-                bid_depth_usd: 3_000_000.0,       // $3M
-                ask_depth_usd: 3_000_000.0,       // $3M
-                spread_percent: 0.02,             // 0.02%
-                market_maker_count: 15,
-                last_updated: std::time::SystemTime::now(),
-            }
-        } else if symbol == "SOL/USDT" {
-            LiquidityMetrics {
-                symbol: symbol.to_string(),
-                volume_24h_usd: 500_000_000.0,    // $500M
-                bid_depth_usd: 1_000_000.0,       // $1M
-                ask_depth_usd: 1_000_000.0,       // $1M
-                spread_percent: 0.05,             // 0.05%
-                market_maker_count: 10,
-                last_updated: std::time::SystemTime::now(),
-            }
-        } else {
-            // Default metrics for other pairs
-            LiquidityMetrics {
-                symbol: symbol.to_string(),
-                volume_24h_usd: 10_000_000.0,     // $10M
-                bid_depth_usd: 200_000.0,         // $200k
-                ask_depth_usd: 200_000.0,         // $200k
-                spread_percent: 0.1,              // 0.1%
-                market_maker_count: 5,
-                last_updated: std::time::SystemTime::now(),
-            }
+        let Some(source) = &self.source else {
+            return Err(ApiError::NotFound(format!("No recent order book for {}", symbol)).into());
         };
-
-        // Update cache
-        {
-            let mut cache = self.metrics_cache.write().await;
-            cache.insert(symbol.to_string(), metrics.clone());
-        }
-
-        Ok(metrics)
+        let book = source.exchange.get_order_book(symbol, FETCH_BOOK_DEPTH).await?;
+        let volume_24h_usd = source.market_data.get_market_data(symbol).await?.volume_24h;
+        let market_makers = self.approved_pairs.market_makers.get(symbol).copied().unwrap_or(0);
+        self.record_order_book(&book, volume_24h_usd, market_makers)
+            .await
+            .map_err(|e| ApiError::Other(format!("Unusable order book for {}: {}", symbol, e)).into())
     }
 
     /// Calculate position size based on liquidity
@@ -324,73 +353,7 @@ impl LiquidityMonitor {
 
     /// Get liquidity metrics for a symbol (public wrapper)
     pub async fn get_liquidity_metrics(&self, symbol: &str) -> ApiResult<LiquidityMetrics> {
-        // Check cache first
-        {
-            let cache = self.metrics_cache.read().await;
-            if let Some(metrics) = cache.get(symbol) {
-                let age = std::time::SystemTime::now()
-                    .duration_since(metrics.last_updated)
-                    .unwrap_or_default();
-                
-                // Return cached data if less than 5 minutes old
-                if age.as_secs() < 300 {
-                    return Ok(metrics.clone());
-                }
-            }
-        }
-
-        // In production, fetch from exchange API
-        // For now, return mock data for approved pairs
-        let metrics = if symbol == "BTC/USDT" {
-            LiquidityMetrics {
-                symbol: symbol.to_string(),
-                volume_24h_usd: 2_000_000_000.0,  // $2B
-                bid_depth_usd: 5_000_000.0,       // $5M
-                ask_depth_usd: 5_000_000.0,       // $5M
-                spread_percent: 0.01,             // 0.01%
-                market_maker_count: 20,
-                last_updated: std::time::SystemTime::now(),
-            }
-        } else if symbol == "ETH/USDT" {
-            LiquidityMetrics {
-                symbol: symbol.to_string(),
-                volume_24h_usd: 1_000_000_000.0,  // $1B
-                bid_depth_usd: 3_000_000.0,       // $3M
-                ask_depth_usd: 3_000_000.0,       // $3M
-                spread_percent: 0.02,             // 0.02%
-                market_maker_count: 15,
-                last_updated: std::time::SystemTime::now(),
-            }
-        } else if symbol == "SOL/USDT" {
-            LiquidityMetrics {
-                symbol: symbol.to_string(),
-                volume_24h_usd: 500_000_000.0,    // $500M
-                bid_depth_usd: 1_000_000.0,       // $1M
-                ask_depth_usd: 1_000_000.0,       // $1M
-                spread_percent: 0.05,             // 0.05%
-                market_maker_count: 10,
-                last_updated: std::time::SystemTime::now(),
-            }
-        } else {
-            // Default metrics for other pairs
-            LiquidityMetrics {
-                symbol: symbol.to_string(),
-                volume_24h_usd: 10_000_000.0,     // $10M
-                bid_depth_usd: 200_000.0,         // $200k
-                ask_depth_usd: 200_000.0,         // $200k
-                spread_percent: 0.1,              // 0.1%
-                market_maker_count: 5,
-                last_updated: std::time::SystemTime::now(),
-            }
-        };
-
-        // Update cache
-        {
-            let mut cache = self.metrics_cache.write().await;
-            cache.insert(symbol.to_string(), metrics.clone());
-        }
-
-        Ok(metrics)
+        self.fetch_liquidity_metrics(symbol).await
     }
     
     /// Get trading pair configuration
@@ -404,10 +367,72 @@ impl LiquidityMonitor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::{Balance, Order, OrderBookLevel, OrderResponse, OrderStatus};
+
+    fn level(price: f64, volume: f64) -> OrderBookLevel {
+        OrderBookLevel { price, volume, timestamp: None }
+    }
+
+    /// A tight book with `depth_usd` a side one tick from a mid of 100
+    fn deep_book(symbol: &str, depth_usd: f64) -> OrderBook {
+        OrderBook {
+            symbol: symbol.to_string(),
+            bids: vec![level(99.99, depth_usd / 99.99)],
+            asks: vec![level(100.01, depth_usd / 100.01)],
+            timestamp: std::time::SystemTime::now(),
+        }
+    }
+
+    /// Serves `deep_book` and a fixed 24h volume, counting book fetches
+    struct StubVenue {
+        fetches: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl TradingExchange for StubVenue {
+        async fn place_order(&self, _order: Order) -> ApiResult<OrderResponse> {
+            Err(ApiError::PermissionDenied.into())
+        }
+
+        async fn cancel_order(&self, _order_id: &str) -> ApiResult<()> {
+            Err(ApiError::PermissionDenied.into())
+        }
+
+        async fn get_order_status(&self, order_id: &str) -> ApiResult<OrderStatus> {
+            Err(ApiError::NotFound(order_id.to_string()).into())
+        }
+
+        async fn get_balances(&self) -> ApiResult<Vec<Balance>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_order_book(&self, symbol: &str, _depth: usize) -> ApiResult<OrderBook> {
+            self.fetches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(deep_book(symbol, 5_000_000.0))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MarketDataProvider for StubVenue {
+        async fn get_market_data(&self, symbol: &str) -> ApiResult<MarketData> {
+            Ok(MarketData {
+                symbol: symbol.to_string(),
+                price: 100.0,
+                volume_24h: 2_000_000_000.0,
+                price_change_24h: 0.0,
+                timestamp: std::time::SystemTime::now(),
+            })
+        }
+
+        async fn subscribe_prices(&self, _symbols: Vec<String>) -> ApiResult<()> {
+            Ok(())
+        }
+    }
 
     #[tokio::test]
     async fn test_liquidity_verification() {
         let monitor = LiquidityMonitor::new();
+        monitor.record_order_book(&deep_book("BTC/USDT", 5_000_000.0), 2_000_000_000.0, 20).await.unwrap();
         
         // Test approved pair
         let result = monitor.verify_liquidity("BTC/USDT").await.unwrap();
@@ -420,11 +445,52 @@ mod tests {
         // Test unknown pair
         let result = monitor.verify_liquidity("UNKNOWN/USDT").await.unwrap();
         assert!(!result);
+
+        // An approved pair with no book recorded is never assumed liquid
+        assert!(monitor.verify_liquidity("ETH/USDT").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stale_metrics_are_refetched_not_mocked() {
+        let monitor = LiquidityMonitor::new();
+        let mut metrics = monitor.record_order_book(&deep_book("ETH/USDT", 3_000_000.0), 1e9, 15).await.unwrap();
+        metrics.last_updated -= METRICS_MAX_AGE;
+        monitor.metrics_cache.write().await.insert("ETH/USDT".to_string(), metrics);
+        assert!(monitor.get_liquidity_metrics("ETH/USDT").await.is_err());
+
+        let venue = Arc::new(StubVenue { fetches: Default::default() });
+        let monitor = LiquidityMonitor::new().with_sources(venue.clone(), venue.clone());
+        let fetched = monitor.get_liquidity_metrics("ETH/USDT").await.unwrap();
+        assert!((fetched.bid_depth_usd - 5_000_000.0).abs() < 1e-6);
+        assert_eq!((fetched.volume_24h_usd, fetched.market_maker_count), (2_000_000_000.0, 15));
+        assert!(monitor.verify_liquidity("ETH/USDT").await.unwrap());
+        assert_eq!(venue.fetches.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_from_order_book() {
+        let mut book = OrderBook {
+            symbol: "SOL/USDT".to_string(),
+            // 3% below mid is outside the depth band
+            bids: vec![level(99.9, 1_000.0), level(99.0, 1_000.0), level(97.0, 1_000.0)],
+            asks: vec![level(100.1, 1_000.0), level(101.0, 1_000.0)],
+            timestamp: std::time::SystemTime::now(),
+        };
+        let monitor = LiquidityMonitor::new();
+        let metrics = monitor.record_order_book(&book, 500_000_000.0, 10).await.unwrap();
+        assert!((metrics.bid_depth_usd - 198_900.0).abs() < 1e-6);
+        assert!((metrics.ask_depth_usd - 201_100.0).abs() < 1e-6);
+        assert!((metrics.spread_percent - 0.2).abs() < 1e-9);
+        assert!(monitor.verify_liquidity("SOL/USDT").await.unwrap());
+
+        book.asks.clear();
+        assert_eq!(LiquidityMetrics::from_order_book(&book, 0.0, 0).unwrap_err(), OrderBookError::Empty);
     }
 
     #[tokio::test]
     async fn test_safe_position_size() {
         let monitor = LiquidityMonitor::new();
+        monitor.record_order_book(&deep_book("BTC/USDT", 5_000_000.0), 2_000_000_000.0, 20).await.unwrap();
         
         // Test position sizing
         let safe_size = monitor.calculate_safe_position_size("BTC/USDT", 100_000.0).await.unwrap();
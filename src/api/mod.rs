@@ -2,18 +2,76 @@
 // Provides interfaces for CoinGecko and Kraken APIs
 
 pub mod coingecko;
+pub mod enrichment;
 pub mod kraken;
 pub mod safety;
 pub mod liquidity;
 pub mod liquidity_predictor;
+pub mod orderbook_math;
 
 use serde::{Deserialize, Serialize};
 use std::error::Error;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 /// Result type for API operations
 pub type ApiResult<T> = Result<T, Box<dyn Error>>;
 
+/// Typed exchange API failures (authentication, throttling, bad requests)
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ApiError {
+    #[error("Invalid API key")]
+    InvalidKey,
+
+    #[error("Invalid request signature")]
+    InvalidSignature,
+
+    #[error("Invalid nonce")]
+    InvalidNonce,
+
+    #[error("Permission denied")]
+    PermissionDenied,
+
+    #[error("Rate limit exceeded")]
+    RateLimited,
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+
+    #[error("Unknown trading pair: {0}")]
+    UnknownPair(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Exchange error: {0}")]
+    Other(String),
+}
+
+/// Typed order execution failures reported by the exchange
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ExecutionError {
+    #[error("Insufficient funds")]
+    InsufficientFunds,
+
+    #[error("Order minimum not met")]
+    OrderMinimumNotMet,
+
+    #[error("Invalid price")]
+    InvalidPrice,
+
+    #[error("Open orders limit exceeded")]
+    OrdersLimitExceeded,
+
+    #[error("Unknown order")]
+    UnknownOrder,
+
+    #[error("Order rejected: {0}")]
+    Rejected(String),
+}
+
 /// Market data from price feeds
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketData {
@@ -24,6 +82,17 @@ pub struct MarketData {
     pub timestamp: SystemTime,
 }
 
+impl MarketData {
+    /// Time since the quote was taken; zero if its timestamp is in the future
+    pub fn age(&self) -> Duration {
+        SystemTime::now().duration_since(self.timestamp).unwrap_or(Duration::ZERO)
+    }
+
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.age() > max_age
+    }
+}
+
 /// Ticker data for historical analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ticker {
@@ -0,0 +1,202 @@
+// Order Book Math
+// Canonical mid, spread, depth and fill estimates shared by every liquidity check
+//
+// Levels are read best first, as venues send them: bids descending, asks ascending.
+// That ordering lets depth and fill walks stop at the first level outside the band
+// or once the notional is covered, so books with thousands of levels cost only the
+// levels actually used. Nothing here allocates.
+
+use super::{OrderBook, OrderBookLevel};
+
+/// Side of the book a measurement reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSide {
+    Bid,
+    Ask, // Buys fill against the asks
+}
+
+/// Why a book cannot be measured
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum OrderBookError {
+    #[error("Order book has no two-sided quote")]
+    Empty,
+
+    #[error("Order book is crossed: bid {bid} above ask {ask}")]
+    Crossed { bid: f64, ask: f64 },
+
+    #[error("Notional {requested} exceeds book depth {available}")]
+    InsufficientDepth { requested: f64, available: f64 },
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+}
+
+fn levels(book: &OrderBook, side: BookSide) -> &[OrderBookLevel] {
+    match side {
+        BookSide::Bid => &book.bids,
+        BookSide::Ask => &book.asks,
+    }
+}
+
+/// Best bid and ask; errors when either side is empty or the bid is above the ask
+pub fn best_quotes(book: &OrderBook) -> Result<(f64, f64), OrderBookError> {
+    let (bid, ask) = match (book.bids.first(), book.asks.first()) {
+        (Some(bid), Some(ask)) => (bid.price, ask.price),
+        _ => return Err(OrderBookError::Empty),
+    };
+    if bid > ask {
+        return Err(OrderBookError::Crossed { bid, ask });
+    }
+    Ok((bid, ask))
+}
+
+pub fn mid_price(book: &OrderBook) -> Result<f64, OrderBookError> {
+    let (bid, ask) = best_quotes(book)?;
+    Ok((bid + ask) / 2.0)
+}
+
+/// (ask - bid) / mid in basis points
+pub fn spread_bps(book: &OrderBook) -> Result<f64, OrderBookError> {
+    let (bid, ask) = best_quotes(book)?;
+    let mid = (bid + ask) / 2.0;
+    if mid <= 0.0 {
+        return Err(OrderBookError::InvalidArgument(format!("non-positive mid {}", mid)));
+    }
+    Ok((ask - bid) / mid * 10_000.0)
+}
+
+/// USD notional (price × volume) of `side` priced within `pct` of mid, where 0.02 is 2%
+pub fn depth_within_pct(book: &OrderBook, side: BookSide, pct: f64) -> Result<f64, OrderBookError> {
+    if !pct.is_finite() || pct < 0.0 {
+        return Err(OrderBookError::InvalidArgument(format!("band {} must be finite and non-negative", pct)));
+    }
+    let mid = mid_price(book)?;
+    let band = mid * pct;
+    Ok(levels(book, side)
+        .iter()
+        .take_while(|level| (level.price - mid).abs() <= band)
+        .map(|level| level.price * level.volume)
+        .sum())
+}
+
+/// (bid volume - ask volume) / total volume over the top `levels` of each side, in [-1, 1];
+/// 0.0 when both sides are empty
+pub fn imbalance(book: &OrderBook, levels: usize) -> f64 {
+    let bid: f64 = book.bids.iter().take(levels).map(|l| l.volume).sum();
+    let ask: f64 = book.asks.iter().take(levels).map(|l| l.volume).sum();
+    if bid + ask > 0.0 {
+        (bid - ask) / (bid + ask)
+    } else {
+        0.0
+    }
+}
+
+/// Average price and levels consumed filling `notional` USD against `side`, walking
+/// levels best first; errors when the side cannot absorb it
+pub fn vwap_to_fill(book: &OrderBook, side: BookSide, notional: f64) -> Result<(f64, usize), OrderBookError> {
+    if !notional.is_finite() || notional <= 0.0 {
+        return Err(OrderBookError::InvalidArgument(format!("notional {} must be finite and positive", notional)));
+    }
+    let mut remaining = notional;
+    let mut filled_volume = 0.0;
+    let mut consumed = 0;
+    for level in levels(book, side) {
+        let level_notional = level.price * level.volume;
+        if level_notional <= 0.0 {
+            continue;
+        }
+        consumed += 1;
+        if level_notional >= remaining {
+            filled_volume += remaining / level.price;
+            return Ok((notional / filled_volume, consumed));
+        }
+        remaining -= level_notional;
+        filled_volume += level.volume;
+    }
+    Err(OrderBookError::InsufficientDepth { requested: notional, available: notional - remaining })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim_rng::SimRng;
+    use std::time::SystemTime;
+
+    fn level(price: f64, volume: f64) -> OrderBookLevel {
+        OrderBookLevel { price, volume, timestamp: None }
+    }
+
+    /// `count` levels a side at random gaps and sizes around 100
+    fn random_book(rng: &SimRng, count: usize) -> OrderBook {
+        let mut bids = Vec::with_capacity(count);
+        let mut asks = Vec::with_capacity(count);
+        let (mut bid, mut ask) = (100.0 - rng.gen_f64(), 100.0 + rng.gen_f64());
+        for _ in 0..count {
+            bids.push(level(bid, 1.0 + rng.gen_f64() * 50.0));
+            asks.push(level(ask, 1.0 + rng.gen_f64() * 50.0));
+            bid -= rng.gen_f64() * 0.5;
+            ask += rng.gen_f64() * 0.5;
+        }
+        OrderBook { symbol: "TEST/USD".to_string(), bids, asks, timestamp: SystemTime::now() }
+    }
+
+    #[test]
+    fn test_quotes_and_fill_on_a_small_book() {
+        let mut book = OrderBook {
+            symbol: "TEST/USD".to_string(),
+            bids: vec![level(99.0, 10.0), level(98.0, 10.0)],
+            asks: vec![level(101.0, 10.0), level(102.0, 10.0)],
+            timestamp: SystemTime::now(),
+        };
+        assert_eq!(mid_price(&book), Ok(100.0));
+        assert_eq!(spread_bps(&book), Ok(200.0));
+        assert_eq!(depth_within_pct(&book, BookSide::Bid, 0.01), Ok(990.0));
+        assert_eq!(depth_within_pct(&book, BookSide::Ask, 0.02), Ok(1010.0 + 1020.0));
+        assert_eq!(imbalance(&book, 10), 0.0);
+
+        // 1010 takes the first ask; 1020 more takes half the second
+        let (price, consumed) = vwap_to_fill(&book, BookSide::Ask, 1520.0).unwrap();
+        assert_eq!(consumed, 2);
+        assert!((price - 1520.0 / 15.0).abs() < 1e-9);
+
+        book.bids.insert(0, level(102.0, 1.0));
+        assert_eq!(mid_price(&book), Err(OrderBookError::Crossed { bid: 102.0, ask: 101.0 }));
+        book.asks.clear();
+        assert_eq!(depth_within_pct(&book, BookSide::Bid, 0.02), Err(OrderBookError::Empty));
+    }
+
+    #[test]
+    fn test_fill_beyond_total_depth_errors() {
+        for seed in 0..200 {
+            let rng = SimRng::seeded(seed);
+            let book = random_book(&rng, 1 + seed as usize % 40);
+            for side in [BookSide::Bid, BookSide::Ask] {
+                let total: f64 = levels(&book, side).iter().map(|l| l.price * l.volume).sum();
+                let result = vwap_to_fill(&book, side, total * (1.0 + rng.gen_f64()) + 1.0);
+                assert!(matches!(result, Err(OrderBookError::InsufficientDepth { .. })), "seed {}", seed);
+                let (price, _) = vwap_to_fill(&book, side, total * 0.999).unwrap();
+                let worst = levels(&book, side).last().unwrap().price;
+                let best = levels(&book, side)[0].price;
+                // notional / (notional / price) can land an ulp outside a single level
+                let tolerance = best.max(worst) * 1e-12;
+                assert!(price >= best.min(worst) - tolerance && price <= best.max(worst) + tolerance, "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_depth_is_monotone_in_pct() {
+        for seed in 0..200 {
+            let rng = SimRng::seeded(seed);
+            let book = random_book(&rng, 1 + seed as usize % 40);
+            for side in [BookSide::Bid, BookSide::Ask] {
+                let mut previous = 0.0;
+                for step in 0..=50 {
+                    let depth = depth_within_pct(&book, side, step as f64 * 0.002).unwrap();
+                    assert!(depth >= previous, "seed {} step {}", seed, step);
+                    previous = depth;
+                }
+            }
+        }
+    }
+}
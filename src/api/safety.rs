@@ -70,7 +70,7 @@ impl CircuitBreaker {
                 // Trip circuit if threshold reached
                 if failures >= self.failure_threshold {
                     self.is_open.store(true, std::sync::atomic::Ordering::Relaxed);
-                    log::error!("Circuit breaker tripped after {} failures", failures);
+                    tracing::error!("Circuit breaker tripped after {} failures", failures);
                 }
                 
                 Err(format!("Operation failed: {:?}", e))
@@ -235,7 +235,7 @@ impl SafetyMonitor {
             
             // Check consecutive loss circuit breaker
             if stats.consecutive_losses >= self.config.max_consecutive_losses {
-                log::error!(
+                tracing::error!(
                     "CIRCUIT BREAKER: {} consecutive losses - halting trading",
                     stats.consecutive_losses
                 );
@@ -247,7 +247,7 @@ impl SafetyMonitor {
         if stats.daily_pnl < 0.0 {
             let loss_percentage = -stats.daily_pnl / self.config.max_total_exposure;
             if loss_percentage > self.config.max_loss_percentage {
-                log::error!(
+                tracing::error!(
                     "CIRCUIT BREAKER: {:.1}% portfolio loss - halting trading",
                     loss_percentage * 100.0
                 );
@@ -262,7 +262,7 @@ impl SafetyMonitor {
         stats.daily_pnl = 0.0;
         stats.consecutive_losses = 0;
         stats.hourly_trades.clear();
-        log::info!("Daily trading statistics reset");
+        tracing::info!("Daily trading statistics reset");
     }
 
     /// Get current safety status
@@ -282,13 +282,13 @@ impl SafetyMonitor {
 
     /// Emergency stop - halt all trading
     pub async fn emergency_stop(&self, reason: &str) {
-        log::error!("EMERGENCY STOP: {}", reason);
+        tracing::error!("EMERGENCY STOP: {}", reason);
         *self.circuit_breaker_active.write().await = true;
     }
 
     /// Resume trading after circuit breaker
     pub async fn resume_trading(&self) {
-        log::info!("Resuming trading - circuit breaker deactivated");
+        tracing::info!("Resuming trading - circuit breaker deactivated");
         *self.circuit_breaker_active.write().await = false;
         
         // Reset consecutive losses
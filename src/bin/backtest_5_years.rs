@@ -60,7 +60,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Step 4: Run backtest
     info!("Step 4: Running 5-year historical backtest...");
-    info!("Initial Capital: ${:,.2}", INITIAL_CAPITAL);
+    info!("Initial Capital: ${:.2}", INITIAL_CAPITAL);
     info!("");
     
     let result = match backtester.run_backtest(INITIAL_CAPITAL).await {
@@ -147,9 +147,9 @@ fn print_backtest_results(result: &BacktestResult) {
     info!("═══════════════════════════════════════════════════════════════");
     info!("FINANCIAL METRICS");
     info!("═══════════════════════════════════════════════════════════════");
-    info!("Total Profit:        ${:,.2}", result.total_profit);
-    info!("Total Loss:          ${:,.2}", result.total_loss);
-    info!("Net Profit:          ${:,.2}", result.net_profit);
+    info!("Total Profit:        ${:.2}", result.total_profit);
+    info!("Total Loss:          ${:.2}", result.total_loss);
+    info!("Net Profit:          ${:.2}", result.net_profit);
     info!("Max Drawdown:        {:.2}%", result.max_drawdown * 100.0);
     info!("Profit Factor:       {:.2}", result.profit_factor);
     info!("Recovery Factor:     {:.2}", result.recovery_factor);
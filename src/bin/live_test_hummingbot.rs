@@ -4,7 +4,6 @@
 use std::time::{Duration, Instant};
 use macro_strike_bot_fixed::hummingbot_array_system::*;
 use tokio::time::sleep;
-use chrono::Utc;
 
 #[tokio::main]
 async fn main() {
@@ -45,38 +44,11 @@ async fn main() {
         // Execute one cycle
         let cycle_start = Instant::now();
         
-        // Scan markets
-        let opportunities = array.scan_all_markets().await;
+        // Scan, assign, execute and aggregate one round
+        let cycle_results = array.run_strike_round().await;
         
-        if !opportunities.is_empty() {
-            println!("📈 Found {} opportunities (Iteration {})", opportunities.len(), iteration);
-            
-            // Assign to bots
-            let assignments = array.strike_coordinator.assign_targets(&opportunities, NUM_BOTS).await;
-            
-            // Execute strikes
-            let mut handles = Vec::new();
-            for (i, bot) in array.bots.iter().enumerate() {
-                if let Some(target) = assignments.get(&i) {
-                    let bot_clone = bot.clone();
-                    let target_clone = target.clone();
-                    
-                    let handle = tokio::spawn(async move {
-                        let mut bot_guard = bot_clone.lock().await;
-                        bot_guard.execute_strike(target_clone).await
-                    });
-                    
-                    handles.push(handle);
-                }
-            }
-            
-            // Collect results
-            let mut cycle_results = Vec::new();
-            for handle in handles {
-                if let Ok(result) = handle.await {
-                    cycle_results.push(result);
-                }
-            }
+        if !cycle_results.is_empty() {
+            println!("📈 Executed {} strikes (Iteration {})", cycle_results.len(), iteration);
             
             // Process results
             for result in &cycle_results {
@@ -100,12 +72,9 @@ async fn main() {
                 }
             }
             
-            // Aggregate
-            array.aggregate_cycle_results(cycle_results).await;
-            
             let cycle_time = cycle_start.elapsed();
             println!("   ✅ Cycle completed in {:.2}ms | Trades: {} | Profit: ${:.2}", 
-                cycle_time.as_millis(), total_trades, array.cycle_profits);
+                cycle_time.as_millis(), total_trades, array.cycle_profits());
         }
         
         // Periodic reports
@@ -197,9 +166,9 @@ async fn print_performance_report(
     println!("║ Avg Leverage:       {:.2}x                                    ║", avg_leverage);
     println!("║ Avg Volume Ratio:   {:.2}x                                    ║", avg_volume_ratio);
     println!("║                                                               ║");
-    println!("║ Current Capital:    ${:>12.2}                         ║", array.total_capital);
+    println!("║ Current Capital:    ${:>12.2}                         ║", array.total_capital());
     println!("║ Return:             {:.2}%                                    ║", 
-        ((array.total_capital - INITIAL_CAPITAL) / INITIAL_CAPITAL) * 100.0);
+        ((array.total_capital() - INITIAL_CAPITAL) / INITIAL_CAPITAL) * 100.0);
     println!("╚═══════════════════════════════════════════════════════════════╝\n");
 }
 
@@ -235,7 +204,7 @@ async fn print_final_report(
         0.0
     };
     
-    let max_leverage = leverage_used.iter().fold(0.0, |a, &b| a.max(b));
+    let max_leverage = leverage_used.iter().fold(0.0_f64, |a, &b| a.max(b));
     
     let avg_volume_ratio = if !volume_ratios.is_empty() {
         volume_ratios.iter().sum::<f64>() / volume_ratios.len() as f64
@@ -243,10 +212,10 @@ async fn print_final_report(
         0.0
     };
     
-    let max_volume_ratio = volume_ratios.iter().fold(0.0, |a, &b| a.max(b));
+    let max_volume_ratio = volume_ratios.iter().fold(0.0_f64, |a, &b| a.max(b));
     
     let net_pnl = total_profit - total_loss;
-    let return_pct = ((array.total_capital - INITIAL_CAPITAL) / INITIAL_CAPITAL) * 100.0;
+    let return_pct = ((array.total_capital() - INITIAL_CAPITAL) / INITIAL_CAPITAL) * 100.0;
     
     // Project 7-day return
     let elapsed_days = elapsed.as_secs_f64() / 86400.0;
@@ -288,7 +257,7 @@ async fn print_final_report(
     
     println!("\n💰 CAPITAL PERFORMANCE");
     println!("   Starting Capital:    ${:.2}", INITIAL_CAPITAL);
-    println!("   Current Capital:      ${:.2}", array.total_capital);
+    println!("   Current Capital:      ${:.2}", array.total_capital());
     println!("   Total Return:         {:.2}%", return_pct);
     println!("   Test Duration:        {:.1} seconds ({:.3} days)", elapsed.as_secs_f64(), elapsed_days);
     
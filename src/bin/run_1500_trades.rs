@@ -3,7 +3,7 @@
 
 use macro_strike_bot_fixed::trade_test_harness::{TestHarness, TestResults};
 use macro_strike_bot_fixed::errors::TradingResult;
-use tracing::{info, error};
+use tracing::{info, warn, error};
 use std::fs;
 use serde_json;
 
@@ -75,11 +75,11 @@ fn print_results(results: &TestResults) {
     info!("║   Win Rate:              {:>6.2}%                            ║", results.win_rate * 100.0);
     info!("║                                                               ║");
     info!("║ FINANCIAL METRICS                                             ║");
-    info!("║   Initial Capital:       ${:>12,.2}                         ║", results.initial_capital);
-    info!("║   Final Capital:         ${:>12,.2}                         ║", results.final_capital);
-    info!("║   Total Profit:          ${:>12,.2}                         ║", results.total_profit);
+    info!("║   Initial Capital:       ${:>12.2}                         ║", results.initial_capital);
+    info!("║   Final Capital:         ${:>12.2}                         ║", results.final_capital);
+    info!("║   Total Profit:          ${:>12.2}                         ║", results.total_profit);
     info!("║   Total Return:         {:>12.2}%                           ║", results.total_return_percent);
-    info!("║   Avg Profit/Trade:      ${:>12,.2}                         ║", results.avg_profit_per_trade);
+    info!("║   Avg Profit/Trade:      ${:>12.2}                         ║", results.avg_profit_per_trade);
     info!("║                                                               ║");
     info!("║ PERFORMANCE METRICS                                           ║");
     info!("║   Duration:              {:>6} seconds                      ║", results.duration_seconds);
@@ -1,5 +1,4 @@
-use chrono::Utc;
-use log::{error, info, warn};
+use tracing::{error, info};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -13,13 +12,15 @@ use macro_strike_bot_fixed::{
         safety::{SafetyConfig, SafetyMonitor},
         ApiConfig, MarketDataProvider, TradingExchange,
     },
+    advanced_cascade_theory::AdvancedCascadeTheory,
     elite_strategies::EliteStrategyEngine,
     monitoring::{MetricType, MonitoringSystem},
-    opportunity_scanner::OpportunityScanner,
+    opportunity_scanner::{OpportunityScanner, ScannerConfig},
+    telemetry,
     quantum_strategies::QuantumStrategiesEngine,
     revolutionary_strategies::RevolutionaryEngine,
     stochastic_volatility_models::RoughHestonModel,
-    superior_strike_validator::{SuperiorStrikeValidator, ValidationConfig},
+    superior_strike_validator::{SuperiorStrikeValidator, ValidationDecision, ValidationServices},
     trading_engine::{EngineConfig, TradingEngine},
     ultra_fast_cascade::UltraFastCascadeDetector,
     MIN_WIN_PROBABILITY,
@@ -95,8 +96,8 @@ pub struct StandaloneTradingEngine {
     elite_strategies: Arc<EliteStrategyEngine>,
     quantum_strategies: Arc<QuantumStrategiesEngine>,
     revolutionary_strategies: Arc<RevolutionaryEngine>,
-    cascade_detector: Arc<ultra_fast_cascade::UltraFastCascadeDetector>,
-    rough_heston: Arc<RwLock<stochastic_volatility_models::RoughHestonModel>>,
+    cascade_detector: Arc<UltraFastCascadeDetector>,
+    rough_heston: Arc<RwLock<RoughHestonModel>>,
     monitoring: Arc<MonitoringSystem>,
     is_running: Arc<RwLock<bool>>,
 }
@@ -105,6 +106,10 @@ impl StandaloneTradingEngine {
     pub async fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let config = TradingConfig::from_env()?;
 
+        // Initialize monitoring first so the API clients can report their calls
+        let monitoring = Arc::new(MonitoringSystem::new());
+        let api_calls = monitoring.api_call_recorder();
+
         // Initialize API clients
         let kraken_config = ApiConfig {
             api_key: config.kraken_api_key.clone(),
@@ -113,7 +118,7 @@ impl StandaloneTradingEngine {
             rate_limit_per_minute: 60,
         };
 
-        let kraken_client = Arc::new(KrakenClient::new(kraken_config)) as Arc<dyn TradingExchange>;
+        let kraken_client = Arc::new(KrakenClient::new(kraken_config).with_call_recorder(api_calls.clone())) as Arc<dyn TradingExchange>;
 
         let coingecko_config = ApiConfig {
             api_key: config.coingecko_api_key.clone().unwrap_or_default(),
@@ -123,7 +128,7 @@ impl StandaloneTradingEngine {
         };
 
         let coingecko_client =
-            Arc::new(CoinGeckoClient::new(coingecko_config)) as Arc<dyn MarketDataProvider>;
+            Arc::new(CoinGeckoClient::new(coingecko_config).with_call_recorder(api_calls)) as Arc<dyn MarketDataProvider>;
 
         // Initialize trading engine
         let engine_config = EngineConfig {
@@ -142,31 +147,40 @@ impl StandaloneTradingEngine {
             engine_config,
         );
 
-        // Initialize opportunity scanner
-        let opportunity_scanner = Arc::new(OpportunityScanner::new(
-            coingecko_client.clone(),
-            config.min_confidence,
-        ));
-
-        // Initialize Superior Strike Validator with modular configuration
-        let liquidity_monitor = Arc::new(LiquidityMonitor::new());
+        let liquidity_monitor = Arc::new(
+            LiquidityMonitor::new().with_sources(kraken_client.clone(), coingecko_client.clone()),
+        );
         let liquidity_predictor = Arc::new(LiquidityPredictor::new(PredictorConfig::default()));
         let safety_monitor = Arc::new(SafetyMonitor::new(SafetyConfig::default()));
 
-        let validation_config = ValidationConfig {
-            parallel_execution: true,
-            fail_fast: false,
-            timeout_ms: 1000,
-            min_confidence: config.min_confidence,
-        };
-
-        let strike_validator = Arc::new(SuperiorStrikeValidator::new(
-            validation_config,
+        // Initialize opportunity scanner
+        let opportunity_scanner = Arc::new(OpportunityScanner::new(
+            coingecko_client.clone(),
             liquidity_monitor.clone(),
             liquidity_predictor.clone(),
-            safety_monitor.clone(),
+            ScannerConfig {
+                min_win_rate: config.min_confidence,
+                ..ScannerConfig::default()
+            },
         ));
 
+        // Initialize advanced components
+        let cascade_detector = Arc::new(UltraFastCascadeDetector::new());
+        let rough_heston = Arc::new(RwLock::new(futures::executor::block_on(
+            RoughHestonModel::new(0.1, 2.0, 0.04, 0.3, -0.7),
+        )));
+
+        // Initialize Superior Strike Validator over the shared services
+        let strike_validator = Arc::new(SuperiorStrikeValidator::new(ValidationServices {
+            market_data: coingecko_client.clone(),
+            exchange: kraken_client.clone(),
+            liquidity_monitor: liquidity_monitor.clone(),
+            liquidity_predictor: liquidity_predictor.clone(),
+            safety_monitor: safety_monitor.clone(),
+            cascade_detector: cascade_detector.clone(),
+            cascade_theory: Arc::new(AdvancedCascadeTheory::new()),
+        }));
+
         // Initialize elite strategies engine
         let elite_strategies = Arc::new(EliteStrategyEngine::new(coingecko_client.clone()));
 
@@ -176,15 +190,6 @@ impl StandaloneTradingEngine {
         // Initialize revolutionary strategies engine
         let revolutionary_strategies = Arc::new(RevolutionaryEngine::new(coingecko_client.clone()));
 
-        // Initialize advanced components
-        let cascade_detector = Arc::new(UltraFastCascadeDetector::new());
-        let rough_heston = Arc::new(RwLock::new(futures::executor::block_on(
-            RoughHestonModel::new(0.1, 2.0, 0.04, 0.3, -0.7),
-        )));
-
-        // Initialize monitoring
-        let monitoring = Arc::new(MonitoringSystem::new());
-
         Ok(Self {
             config,
             engine,
@@ -226,6 +231,7 @@ impl StandaloneTradingEngine {
 
         // Start monitoring
         self.monitoring.start().await;
+        self.monitoring.start_api_call_flush();
 
         // Main trading loop
         while *self.is_running.read().await {
@@ -250,7 +256,6 @@ impl StandaloneTradingEngine {
     pub async fn stop(&self) {
         info!("Stopping trading engine...");
         *self.is_running.write().await = false;
-        self.monitoring.stop().await;
     }
 
     /// Execute one trading cycle
@@ -270,11 +275,11 @@ impl StandaloneTradingEngine {
                 // Run 12-step validation
                 let validation_report = self
                     .strike_validator
-                    .validate_strike(&revolutionary_strike)
+                    .validate(&revolutionary_strike)
                     .await;
-                info!("{}", StrikeValidator::format_report(&validation_report));
+                info!("{}", validation_report.to_markdown());
 
-                if validation_report.overall_passed {
+                if !matches!(validation_report.decision, ValidationDecision::Rejected { .. }) {
                     if self.config.dry_run {
                         info!(
                             "DRY RUN: Would execute revolutionary strike: {:?}",
@@ -291,7 +296,7 @@ impl StandaloneTradingEngine {
                                     .record_metric(MetricType::TradeCount, 1.0)
                                     .await;
                                 self.monitoring
-                                    .record_metric(MetricType::SuccessRate, 1.0)
+                                    .record_metric(MetricType::WinRate, 1.0)
                                     .await;
                             }
                             Err(e) => {
@@ -316,10 +321,10 @@ impl StandaloneTradingEngine {
 
                 // Run 12-step validation
                 let validation_report =
-                    self.strike_validator.validate_strike(&quantum_strike).await;
-                info!("{}", StrikeValidator::format_report(&validation_report));
+                    self.strike_validator.validate(&quantum_strike).await;
+                info!("{}", validation_report.to_markdown());
 
-                if validation_report.overall_passed {
+                if !matches!(validation_report.decision, ValidationDecision::Rejected { .. }) {
                     if self.config.dry_run {
                         info!(
                             "DRY RUN: Would execute quantum strike: {:?}",
@@ -336,7 +341,7 @@ impl StandaloneTradingEngine {
                                     .record_metric(MetricType::TradeCount, 1.0)
                                     .await;
                                 self.monitoring
-                                    .record_metric(MetricType::SuccessRate, 1.0)
+                                    .record_metric(MetricType::WinRate, 1.0)
                                     .await;
                             }
                             Err(e) => {
@@ -356,10 +361,10 @@ impl StandaloneTradingEngine {
                 info!("Elite strategy signal generated for {}", symbol);
 
                 // Run 12-step validation
-                let validation_report = self.strike_validator.validate_strike(&elite_strike).await;
-                info!("{}", StrikeValidator::format_report(&validation_report));
+                let validation_report = self.strike_validator.validate(&elite_strike).await;
+                info!("{}", validation_report.to_markdown());
 
-                if validation_report.overall_passed {
+                if !matches!(validation_report.decision, ValidationDecision::Rejected { .. }) {
                     if self.config.dry_run {
                         info!("DRY RUN: Would execute elite strike: {:?}", elite_strike);
                         self.monitoring
@@ -373,7 +378,7 @@ impl StandaloneTradingEngine {
                                     .record_metric(MetricType::TradeCount, 1.0)
                                     .await;
                                 self.monitoring
-                                    .record_metric(MetricType::SuccessRate, 1.0)
+                                    .record_metric(MetricType::WinRate, 1.0)
                                     .await;
                             }
                             Err(e) => {
@@ -389,56 +394,15 @@ impl StandaloneTradingEngine {
             }
         }
 
-        // Fallback to regular opportunity scanner
-        let opportunities = self.opportunity_scanner.scan_all_opportunities().await?;
-
-        // Filter for high confidence opportunities
-        let valid_opportunities: Vec<_> = opportunities
-            .into_iter()
-            .filter(|o| o.confidence >= self.config.min_confidence)
-            .collect();
-
-        if valid_opportunities.is_empty() {
-            return Ok(());
-        }
-
-        info!("Found {} valid opportunities", valid_opportunities.len());
-
-        // Execute the best opportunity after validation
-        if let Some(best_opportunity) = valid_opportunities.into_iter().next() {
-            let strike = self.opportunity_scanner.convert_to_strike(best_opportunity);
-
-            // Run 12-step validation
-            let validation_report = self.strike_validator.validate_strike(&strike).await;
-            info!("{}", StrikeValidator::format_report(&validation_report));
-
-            if validation_report.overall_passed {
-                if self.config.dry_run {
-                    info!("DRY RUN: Would execute strike: {:?}", strike);
-                    self.monitoring
-                        .record_metric(MetricType::TradeCount, 1.0)
-                        .await;
-                } else {
-                    match self.engine.execute_strike(&strike).await {
-                        Ok(_) => {
-                            info!("Strike executed successfully");
-                            self.monitoring
-                                .record_metric(MetricType::TradeCount, 1.0)
-                                .await;
-                            self.monitoring
-                                .record_metric(MetricType::SuccessRate, 1.0)
-                                .await;
-                        }
-                        Err(e) => {
-                            error!("Strike execution failed: {}", e);
-                            self.monitoring
-                                .record_metric(MetricType::ErrorCount, 1.0)
-                                .await;
-                        }
-                    }
-                }
-            } else {
-                warn!("Strike failed validation - skipping");
+        // No strategy fired; scanner opportunities carry no price levels, so only report the best one
+        if let Some(best) = self.opportunity_scanner.get_best_opportunity().await {
+            if best.win_rate >= self.config.min_confidence {
+                info!(
+                    "No strategy signal this cycle; best scanner opportunity {} on {} ({:.1}% win rate)",
+                    best.id,
+                    best.symbol,
+                    best.win_rate * 100.0
+                );
             }
         }
 
@@ -449,9 +413,7 @@ impl StandaloneTradingEngine {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize logger
-    env_logger::Builder::from_default_env()
-        .format_timestamp_secs()
-        .init();
+    telemetry::init(&telemetry::TelemetryConfig::from_env())?;
 
     info!("===========================================");
     info!("   RUST STANDALONE TRADING ENGINE v1.0     ");
@@ -1,7 +1,5 @@
-use chrono::Utc;
-use log::{error, info, warn};
+use tracing::{error, info, warn};
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
 // Import the trading engine and required modules
 use macro_strike_bot_fixed::{
@@ -13,10 +11,13 @@ use macro_strike_bot_fixed::{
         safety::{SafetyConfig, SafetyMonitor},
         ApiConfig, MarketDataProvider, TradingExchange,
     },
+    advanced_cascade_theory::AdvancedCascadeTheory,
     elite_strategies::EliteStrategyEngine,
     monitoring::{MetricType, MonitoringSystem},
-    opportunity_scanner::OpportunityScanner,
-    superior_strike_validator::{SuperiorStrikeValidator, ValidationConfig},
+    opportunity_scanner::{OpportunityScanner, ScannerConfig},
+    telemetry,
+    superior_strike_validator::{SuperiorStrikeValidator, ValidationDecision, ValidationServices},
+    ultra_fast_cascade::UltraFastCascadeDetector,
     trading_engine::{EngineConfig, TradingEngine},
     MIN_WIN_PROBABILITY,
 };
@@ -24,7 +25,7 @@ use macro_strike_bot_fixed::{
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize logging
-    env_logger::init();
+    telemetry::init(&telemetry::TelemetryConfig::from_env())?;
 
     info!("🏎️ FERRARI MODE - Elite Trading Engine Starting...");
 
@@ -81,29 +82,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Initialize components
-    let opportunity_scanner = Arc::new(OpportunityScanner::new(
-        coingecko_client.clone(),
-        MIN_WIN_PROBABILITY,
-    ));
-
-    let liquidity_monitor = Arc::new(LiquidityMonitor::new());
+    let liquidity_monitor =
+        Arc::new(LiquidityMonitor::new().with_sources(kraken_client.clone(), coingecko_client.clone()));
     let liquidity_predictor = Arc::new(LiquidityPredictor::new(PredictorConfig::default()));
     let safety_monitor = Arc::new(SafetyMonitor::new(SafetyConfig::default()));
 
-    let validation_config = ValidationConfig {
-        parallel_execution: true,
-        fail_fast: false,
-        timeout_ms: 1000,
-        min_confidence: MIN_WIN_PROBABILITY,
-    };
-
-    let strike_validator = Arc::new(SuperiorStrikeValidator::new(
-        validation_config,
+    let _opportunity_scanner = Arc::new(OpportunityScanner::new(
+        coingecko_client.clone(),
         liquidity_monitor.clone(),
         liquidity_predictor.clone(),
-        safety_monitor.clone(),
+        ScannerConfig {
+            min_win_rate: MIN_WIN_PROBABILITY,
+            ..ScannerConfig::default()
+        },
     ));
 
+    let strike_validator = Arc::new(SuperiorStrikeValidator::new(ValidationServices {
+        market_data: coingecko_client.clone(),
+        exchange: kraken_client.clone(),
+        liquidity_monitor: liquidity_monitor.clone(),
+        liquidity_predictor: liquidity_predictor.clone(),
+        safety_monitor: safety_monitor.clone(),
+        cascade_detector: Arc::new(UltraFastCascadeDetector::new()),
+        cascade_theory: Arc::new(AdvancedCascadeTheory::new()),
+    }));
+
     let elite_strategies = Arc::new(EliteStrategyEngine::new(coingecko_client.clone()));
     let monitoring = Arc::new(MonitoringSystem::new());
 
@@ -129,24 +132,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     loop {
         for symbol in &symbols {
             // Generate signals from elite strategies
-            if let Some(strike) = elite_strategies.generate_strike(symbol).await {
+            if let Some(strike) = elite_strategies.generate_elite_signal(symbol).await {
                 info!("🎯 Elite signal for {}: {:?}", symbol, strike.strike_type);
 
                 // Validate with Superior validator
-                let validation_report = strike_validator.validate(&strike, &kraken_client).await;
+                let validation_report = strike_validator.validate(&strike).await;
 
-                if matches!(validation_report.decision, 
-                           macro_strike_bot_fixed::superior_strike_validator::ValidationDecision::Approved { .. } |
-                           macro_strike_bot_fixed::superior_strike_validator::ValidationDecision::ConditionallyApproved { .. }) {
+                if let ValidationDecision::Rejected { primary_reasons, .. } = &validation_report.decision {
+                    warn!("❌ Strike rejected: {:?}", primary_reasons);
+                } else {
                     info!("✅ Strike validated! Confidence: {:.1}%", 
                           strike.confidence * 100.0);
                     
                     if !dry_run {
                         // Execute trade
                         match engine.execute_strike(&strike).await {
-                            Ok(pnl) => {
-                                info!("💰 Trade executed! P&L: ${:.2}", pnl);
-                                monitoring.record_metric(MetricType::TradeExecuted, 1.0).await;
+                            Ok(()) => {
+                                info!("💰 Trade executed on {}", symbol);
+                                monitoring.record_metric(MetricType::TradeCount, 1.0).await;
                                 consecutive_errors = 0;
                             }
                             Err(e) => {
@@ -162,8 +165,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                               symbol,
                               strike.confidence * 100.0);
                     }
-                } else {
-                    warn!("❌ Strike rejected: {:?}", validation_report.rejection_reasons);
                 }
             }
         }
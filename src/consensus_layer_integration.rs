@@ -72,7 +72,7 @@ impl ConsensusLayerClient {
     pub async fn get_gas_price(&mut self) -> TradingResult<u64> {
         // Fetch current gas price from network
         // For now, simulate with some variation
-        let base_price = 20_000_000_000; // 20 gwei
+        let base_price: u64 = 20_000_000_000; // 20 gwei
         let variation = (rand::random::<f64>() - 0.5) * 0.2; // ±10%
         self.gas_price = (base_price as f64 * (1.0 + variation)) as u64;
         Ok(self.gas_price)
@@ -204,6 +204,7 @@ impl DEXPool {
 }
 
 // Arbitrage execution on consensus layer
+#[derive(Debug, Clone)]
 pub struct ConsensusArbitrageExecutor {
     client: ConsensusLayerClient,
     pools: HashMap<String, DEXPool>,
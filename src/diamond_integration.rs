@@ -25,7 +25,7 @@ pub struct DiamondClient {
 impl DiamondClient {
     pub async fn new(rpc_url: &str) -> TradingResult<Self> {
         let provider = Provider::<Http>::try_from(rpc_url)
-            .map_err(|e| TradingError::NetworkError(e.into()))?;
+            .map_err(|e| TradingError::ConfigError(format!("Invalid RPC URL {}: {}", rpc_url, e)))?;
         
         let provider = Arc::new(provider);
         
@@ -35,17 +35,15 @@ impl DiamondClient {
         // Load StrikeBotFacet ABI (optional - will be generated during contract compilation)
         // For now, using mock contract interface
         let strike_bot_abi = b"[]"; // Empty ABI for now - will be replaced with actual ABI after compilation
-        let strike_bot = Contract::from_json(
-            diamond_address,
-            strike_bot_abi
-        ).map_err(|e| TradingError::InvalidInput(format!("Failed to load StrikeBot ABI: {}", e)))?;
+        let strike_bot_abi: ethers::abi::Abi = serde_json::from_slice(strike_bot_abi)
+            .map_err(|e| TradingError::InvalidInput(format!("Failed to load StrikeBot ABI: {}", e)))?;
+        let strike_bot = Contract::new(diamond_address, strike_bot_abi, provider.clone());
         
         // Load AMMBotFacet ABI (optional - will be generated during contract compilation)
         let amm_bot_abi = b"[]"; // Empty ABI for now - will be replaced with actual ABI after compilation
-        let amm_bot = Contract::from_json(
-            diamond_address,
-            amm_bot_abi
-        ).map_err(|e| TradingError::InvalidInput(format!("Failed to load AMMBot ABI: {}", e)))?;
+        let amm_bot_abi: ethers::abi::Abi = serde_json::from_slice(amm_bot_abi)
+            .map_err(|e| TradingError::InvalidInput(format!("Failed to load AMMBot ABI: {}", e)))?;
+        let amm_bot = Contract::new(diamond_address, amm_bot_abi, provider.clone());
         
         Ok(Self {
             provider,
@@ -72,8 +70,9 @@ impl DiamondClient {
         let result: (bool, U256) = self.strike_bot
             .method::<_, (bool, U256)>(
                 "executeCoordinatedStrike",
-                opportunity.clone()
-            )?
+                (opportunity.to_tokens(),)
+            )
+            .map_err(abi_error)?
             .call()
             .await
             .map_err(|e| TradingError::ApiError(format!("Contract call failed: {}", e)))?;
@@ -103,8 +102,9 @@ impl DiamondClient {
         let result: (bool, U256) = self.amm_bot
             .method::<_, (bool, U256)>(
                 "executePredictiveArbitrage",
-                (prediction.clone(), path.clone())
-            )?
+                (prediction.to_tokens(), path.to_tokens())
+            )
+            .map_err(abi_error)?
             .call()
             .await
             .map_err(|e| TradingError::ApiError(format!("Contract call failed: {}", e)))?;
@@ -122,7 +122,8 @@ impl DiamondClient {
             .method::<_, (U256, U256, U256, U256, u8, U256)>(
                 "getStrikeBotStats",
                 ()
-            )?
+            )
+            .map_err(abi_error)?
             .call()
             .await
             .map_err(|e| TradingError::ApiError(format!("Contract call failed: {}", e)))?;
@@ -143,7 +144,8 @@ impl DiamondClient {
             .method::<_, (U256, U256, U256, U256, U256, u8)>(
                 "getAMMBotStats",
                 ()
-            )?
+            )
+            .map_err(abi_error)?
             .call()
             .await
             .map_err(|e| TradingError::ApiError(format!("Contract call failed: {}", e)))?;
@@ -159,6 +161,10 @@ impl DiamondClient {
     }
 }
 
+fn abi_error(e: AbiError) -> TradingError {
+    TradingError::ApiError(format!("Contract call encoding failed: {}", e))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrikeOpportunity {
     pub confidence: u8, // 0-100 (93 = 93%)
@@ -187,6 +193,39 @@ pub struct ArbitragePath {
     pub gas_estimate: u64,
 }
 
+// Solidity structs are ABI tuples, with uint256 for the u128 amounts
+impl StrikeOpportunity {
+    fn to_tokens(&self) -> (u8, U256, String, U256, U256, U256) {
+        (
+            self.confidence,
+            U256::from(self.expected_profit),
+            self.token_pair.clone(),
+            U256::from(self.entry_price),
+            U256::from(self.target_price),
+            U256::from(self.stop_loss),
+        )
+    }
+}
+
+impl Prediction {
+    fn to_tokens(&self) -> (u8, U256, String, String) {
+        (self.confidence, U256::from(self.amount_in), self.token_in.clone(), self.token_out.clone())
+    }
+}
+
+impl ArbitragePath {
+    fn to_tokens(&self) -> (String, String, U256, U256, U256, U256) {
+        (
+            self.pool_a.clone(),
+            self.pool_b.clone(),
+            U256::from(self.price_a),
+            U256::from(self.price_b),
+            U256::from(self.min_profit),
+            U256::from(self.gas_estimate),
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StrikeResult {
     pub success: bool,
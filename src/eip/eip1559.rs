@@ -147,9 +147,10 @@ impl DynamicFeeCalculator {
             .take(10)
             .collect();
         
+        // Newest first, so a rise is a newer fee above the one before it
         let mut increases = 0;
         for i in 1..recent.len() {
-            if recent[i] > recent[i-1] {
+            if recent[i-1] > recent[i] {
                 increases += 1;
             }
         }
@@ -173,39 +174,6 @@ pub enum FeeUrgency {
     Standard,
 }
 
-/// Gas optimization for complex transactions
-pub struct GasOptimizer {
-    /// Provider for simulation
-    provider: std::sync::Arc<Provider<Ws>>,
-}
-
-impl GasOptimizer {
-    pub fn new(provider: std::sync::Arc<Provider<Ws>>) -> Self {
-        Self { provider }
-    }
-    
-    /// Optimize transaction for 90% success rate
-    pub async fn optimize_transaction(
-        &self,
-        tx: &mut TypedTransaction,
-        max_gas_price: U256,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Simulate to get accurate gas estimate
-        let gas_estimate = self.provider
-            .estimate_gas(tx, None)
-            .await?;
-        
-        // Add 10% buffer for 90% success rate
-        let gas_limit = gas_estimate * 110 / 100;
-        tx.set_gas(gas_limit);
-        
-        // Set gas price within budget
-        tx.set_gas_price(max_gas_price);
-        
-        Ok(())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,7 +184,7 @@ mod tests {
         
         // Add some historical data
         for i in 1..=10 {
-            calc.base_fee_history.push_back(U256::from(i * 1_000_000_000));
+            calc.base_fee_history.push_back(U256::from(i * 1_000_000_000u64));
         }
         
         // Test trend calculation
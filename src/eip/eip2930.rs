@@ -0,0 +1,392 @@
+// EIP-2930: Optional Access Lists
+// Pre-declares the storage our strike contracts touch, attached only when it lowers gas
+
+use ethers::prelude::*;
+use ethers::providers::{JsonRpcClient, RpcError};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip2930::AccessList;
+use ethers::utils::keccak256;
+use tracing::{debug, info};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use crate::monitoring::api_calls::{ApiCallRecorder, ApiProvider};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Cache key: the same contract, function and significant arguments touch the same slots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AccessListKey {
+    pub to: Option<Address>,
+    pub selector: [u8; 4],
+    pub args_hash: [u8; 32],
+}
+
+#[derive(Debug, Clone)]
+struct CachedAccessList {
+    access_list: AccessList,
+    gas_with_list: u64,
+    gas_without_list: Option<u64>, // Filled in once the comparison has run
+}
+
+/// Whether `optimize_access_list` attached a list, and the estimates behind the call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessListDecision {
+    Attached { gas_with_list: u64, gas_without_list: u64 },
+    SkippedMoreExpensive { gas_with_list: u64, gas_without_list: u64 },
+    SkippedEmpty,
+    Unsupported, // The RPC has no eth_createAccessList
+}
+
+/// Gas optimization for complex transactions
+pub struct GasOptimizer<P: JsonRpcClient = Ws> {
+    /// Provider for simulation
+    provider: Arc<Provider<P>>,
+
+    /// Access lists by (to, selector, significant args)
+    cache: Mutex<HashMap<AccessListKey, CachedAccessList>>,
+
+    /// Calldata words that pick the touched slots, per selector; all words when absent
+    significant_args: HashMap<[u8; 4], Vec<usize>>,
+
+    /// Cleared the first time the RPC rejects eth_createAccessList
+    access_lists_supported: AtomicBool,
+
+    /// API-call accounting for each estimate and access list request
+    call_recorder: Option<Arc<ApiCallRecorder>>,
+}
+
+impl<P: JsonRpcClient> GasOptimizer<P> {
+    pub fn new(provider: Arc<Provider<P>>) -> Self {
+        Self {
+            provider,
+            cache: Mutex::new(HashMap::new()),
+            significant_args: HashMap::new(),
+            access_lists_supported: AtomicBool::new(true),
+            call_recorder: None,
+        }
+    }
+
+    /// Report every RPC request to the monitoring API-call accounting
+    pub fn with_call_recorder(mut self, recorder: Arc<ApiCallRecorder>) -> Self {
+        self.call_recorder = Some(recorder);
+        self
+    }
+
+    /// Key `selector` on only these 32-byte argument words (e.g. pool addresses, not amounts)
+    pub fn with_significant_args(mut self, selector: [u8; 4], words: Vec<usize>) -> Self {
+        self.significant_args.insert(selector, words);
+        self
+    }
+
+    pub fn access_list_key(&self, tx: &TypedTransaction) -> AccessListKey {
+        let data = tx.data().map(|d| d.as_ref()).unwrap_or_default();
+        let mut selector = [0u8; 4];
+        let selector_len = data.len().min(4);
+        selector[..selector_len].copy_from_slice(&data[..selector_len]);
+
+        let args = data.get(4..).unwrap_or_default();
+        let significant: Vec<u8> = match self.significant_args.get(&selector) {
+            Some(words) => words.iter()
+                .filter_map(|&word| args.get(word * 32..(word + 1) * 32))
+                .flatten()
+                .copied()
+                .collect(),
+            None => args.to_vec(),
+        };
+
+        AccessListKey {
+            to: tx.to_addr().copied(),
+            selector,
+            args_hash: keccak256(significant),
+        }
+    }
+
+    pub fn cached_access_lists(&self) -> usize {
+        self.cache.lock().map(|cache| cache.len()).unwrap_or(0)
+    }
+
+    /// Access list and gas estimate with it from `eth_createAccessList`, cached per key.
+    /// Without RPC support the list is empty and the estimate comes from `eth_estimateGas`.
+    pub async fn build_access_list(
+        &self,
+        tx: &TypedTransaction,
+    ) -> Result<(AccessList, u64), Box<dyn std::error::Error>> {
+        let key = self.access_list_key(tx);
+        if let Some(cached) = self.cache.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+            return Ok((cached.access_list, cached.gas_with_list));
+        }
+
+        if !self.access_lists_supported.load(Ordering::Relaxed) {
+            let gas = self.estimate_gas(tx).await?;
+            return Ok((AccessList::default(), gas.as_u64()));
+        }
+
+        let started = Instant::now();
+        let created = self.provider.create_access_list(tx, None).await;
+        self.record_rpc("eth_createAccessList", started, &created);
+        match created {
+            Ok(created) => {
+                let gas_with_list = created.gas_used.as_u64();
+                if let Ok(mut cache) = self.cache.lock() {
+                    cache.insert(key, CachedAccessList {
+                        access_list: created.access_list.clone(),
+                        gas_with_list,
+                        gas_without_list: None,
+                    });
+                }
+                Ok((created.access_list, gas_with_list))
+            }
+            Err(e) if is_unsupported(&e) => {
+                info!("⛽ RPC has no eth_createAccessList ({}) - sending without access lists", e);
+                self.access_lists_supported.store(false, Ordering::Relaxed);
+                let gas = self.estimate_gas(tx).await?;
+                Ok((AccessList::default(), gas.as_u64()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Attach an access list to `tx` only if it estimates cheaper than sending without one.
+    /// The outcome is cached with the list, so repeat strikes skip both estimates.
+    pub async fn optimize_access_list(
+        &self,
+        tx: &mut TypedTransaction,
+    ) -> Result<AccessListDecision, Box<dyn std::error::Error>> {
+        let key = self.access_list_key(tx);
+        let (access_list, gas_with_list) = self.build_access_list(tx).await?;
+        if !self.access_lists_supported.load(Ordering::Relaxed) {
+            return Ok(AccessListDecision::Unsupported);
+        }
+        if access_list.0.is_empty() {
+            return Ok(AccessListDecision::SkippedEmpty);
+        }
+
+        let cached_without = self.cache.lock().ok()
+            .and_then(|cache| cache.get(&key).and_then(|c| c.gas_without_list));
+        let gas_without_list = match cached_without {
+            Some(gas) => gas,
+            None => {
+                let mut bare = tx.clone();
+                bare.set_access_list(AccessList::default());
+                let gas = self.estimate_gas(&bare).await?.as_u64();
+                if let Ok(mut cache) = self.cache.lock() {
+                    if let Some(cached) = cache.get_mut(&key) {
+                        cached.gas_without_list = Some(gas);
+                    }
+                }
+                gas
+            }
+        };
+
+        // Cold slots the list warms can cost less than the list itself declares
+        if gas_with_list < gas_without_list {
+            tx.set_access_list(access_list);
+            Ok(AccessListDecision::Attached { gas_with_list, gas_without_list })
+        } else {
+            debug!("Access list skipped: {} gas with, {} without", gas_with_list, gas_without_list);
+            Ok(AccessListDecision::SkippedMoreExpensive { gas_with_list, gas_without_list })
+        }
+    }
+
+    /// Sign `tx` for `wallet` after `optimize_access_list` has decided on a list, returning the
+    /// raw transaction to broadcast and that decision. The list is part of the signed payload,
+    /// so it has to be settled first.
+    pub async fn sign_with_access_list(
+        &self,
+        mut tx: TypedTransaction,
+        wallet: &LocalWallet,
+    ) -> Result<(Bytes, AccessListDecision), Box<dyn std::error::Error>> {
+        tx.set_from(wallet.address());
+        tx.set_chain_id(wallet.chain_id());
+        let decision = self.optimize_access_list(&mut tx).await?;
+        let signature = wallet.sign_transaction(&tx).await?;
+        Ok((tx.rlp_signed(&signature), decision))
+    }
+
+    /// Optimize transaction for 90% success rate
+    pub async fn optimize_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        max_gas_price: U256,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Simulate to get accurate gas estimate
+        let gas_estimate = self.estimate_gas(tx).await?;
+
+        // Add 10% buffer for 90% success rate
+        let gas_limit = gas_estimate * 110 / 100;
+        tx.set_gas(gas_limit);
+
+        // Set gas price within budget
+        tx.set_gas_price(max_gas_price);
+
+        Ok(())
+    }
+}
+
+impl<P: JsonRpcClient> GasOptimizer<P> {
+    async fn estimate_gas(&self, tx: &TypedTransaction) -> Result<U256, ProviderError> {
+        let started = Instant::now();
+        let gas = self.provider.estimate_gas(tx, None).await;
+        self.record_rpc("eth_estimateGas", started, &gas);
+        gas
+    }
+
+    fn record_rpc<T, E>(&self, endpoint: &str, started: Instant, result: &Result<T, E>) {
+        if let Some(recorder) = &self.call_recorder {
+            recorder.record_result(ApiProvider::EipRpc, endpoint, started, result);
+        }
+    }
+}
+
+/// Node doesn't implement the method (geth before 1.10, many L2 and hosted RPCs)
+fn is_unsupported(error: &ProviderError) -> bool {
+    match RpcError::as_error_response(error) {
+        Some(response) => {
+            let message = response.message.to_lowercase();
+            response.code == METHOD_NOT_FOUND
+                || message.contains("not supported")
+                || message.contains("does not exist")
+                || message.contains("not available")
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{JsonRpcError, MockProvider, MockResponse};
+    use ethers::types::transaction::eip2930::{AccessListItem, AccessListWithGasUsed};
+
+    const ARBITRAGE_SELECTOR: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+
+    fn arbitrage_tx(pool: u64, amount: u64) -> TypedTransaction {
+        let mut data = ARBITRAGE_SELECTOR.to_vec();
+        data.extend_from_slice(H256::from(Address::from_low_u64_be(pool)).as_bytes());
+        data.extend_from_slice(&<[u8; 32]>::from(U256::from(amount)));
+        Eip1559TransactionRequest::new()
+            .to(Address::from_low_u64_be(0xa4b))
+            .data(data)
+            .into()
+    }
+
+    fn created(gas_used: u64) -> AccessListWithGasUsed {
+        AccessListWithGasUsed {
+            access_list: AccessList(vec![AccessListItem {
+                address: Address::from_low_u64_be(7),
+                storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+            }]),
+            gas_used: U256::from(gas_used),
+        }
+    }
+
+    fn mocked_optimizer() -> (GasOptimizer<MockProvider>, MockProvider) {
+        let (provider, mock) = Provider::mocked();
+        let optimizer = GasOptimizer::new(Arc::new(provider))
+            .with_significant_args(ARBITRAGE_SELECTOR, vec![0]); // Pool, not amount
+        (optimizer, mock)
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit_skips_rpc_for_same_pool() {
+        let (optimizer, mock) = mocked_optimizer();
+        // Mock responses are served last-pushed first
+        mock.push(U256::from(180_000u64)).unwrap(); // eth_estimateGas without the list
+        mock.push(created(160_000)).unwrap();       // eth_createAccessList
+
+        let mut first = arbitrage_tx(1, 5_000);
+        let decision = optimizer.optimize_access_list(&mut first).await.unwrap();
+        assert_eq!(decision, AccessListDecision::Attached { gas_with_list: 160_000, gas_without_list: 180_000 });
+        assert_eq!(first.access_list().map(|l| l.0.len()), Some(1));
+
+        // A different amount through the same pool needs no RPC at all: the mock is empty
+        let mut second = arbitrage_tx(1, 9_000);
+        assert_eq!(optimizer.access_list_key(&first), optimizer.access_list_key(&second));
+        let decision = optimizer.optimize_access_list(&mut second).await.unwrap();
+        assert!(matches!(decision, AccessListDecision::Attached { .. }));
+        assert_eq!(second.access_list(), first.access_list());
+        assert_eq!(optimizer.cached_access_lists(), 1);
+
+        // Another pool is another key
+        assert_ne!(optimizer.access_list_key(&arbitrage_tx(2, 5_000)), optimizer.access_list_key(&first));
+    }
+
+    #[tokio::test]
+    async fn test_every_rpc_request_is_recorded() {
+        let (optimizer, mock) = mocked_optimizer();
+        let recorder = Arc::new(ApiCallRecorder::new(Default::default()));
+        let optimizer = optimizer.with_call_recorder(recorder.clone());
+        mock.push(U256::from(180_000u64)).unwrap();
+        mock.push(created(160_000)).unwrap();
+
+        optimizer.optimize_access_list(&mut arbitrage_tx(1, 5_000)).await.unwrap();
+        assert_eq!(recorder.spend(ApiProvider::EipRpc).calls_today, 2);
+
+        // Cache hits send nothing, so record nothing
+        optimizer.optimize_access_list(&mut arbitrage_tx(1, 9_000)).await.unwrap();
+        assert_eq!(recorder.spend(ApiProvider::EipRpc).calls_today, 2);
+
+        // A failed estimate is a call too
+        let mut tx = arbitrage_tx(2, 1);
+        assert!(optimizer.optimize_transaction(&mut tx, U256::one()).await.is_err());
+        let spend = recorder.spend(ApiProvider::EipRpc);
+        assert_eq!((spend.calls_today, spend.errors_today), (3, 1));
+    }
+
+    #[tokio::test]
+    async fn test_list_that_costs_more_is_skipped() {
+        let (optimizer, mock) = mocked_optimizer();
+        mock.push(U256::from(150_000u64)).unwrap();
+        mock.push(created(152_400)).unwrap();
+
+        let mut tx = arbitrage_tx(3, 1_000);
+        let decision = optimizer.optimize_access_list(&mut tx).await.unwrap();
+        assert_eq!(
+            decision,
+            AccessListDecision::SkippedMoreExpensive { gas_with_list: 152_400, gas_without_list: 150_000 }
+        );
+        assert!(tx.access_list().map_or(true, |l| l.0.is_empty()));
+
+        // The skip is remembered too
+        let decision = optimizer.optimize_access_list(&mut tx).await.unwrap();
+        assert!(matches!(decision, AccessListDecision::SkippedMoreExpensive { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_signed_transaction_carries_the_attached_list() {
+        let (optimizer, mock) = mocked_optimizer();
+        mock.push(U256::from(180_000u64)).unwrap();
+        mock.push(created(160_000)).unwrap();
+        let wallet = LocalWallet::new(&mut rand::thread_rng()).with_chain_id(1u64);
+
+        let (raw, decision) = optimizer.sign_with_access_list(arbitrage_tx(1, 5_000), &wallet).await.unwrap();
+        assert!(matches!(decision, AccessListDecision::Attached { .. }));
+
+        let (signed, signature) = TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(&raw)).unwrap();
+        assert_eq!(signed.access_list().map(|l| l.0.len()), Some(1));
+        assert_eq!(signature.recover(signed.sighash()).unwrap(), wallet.address());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_rpc_falls_back_to_plain_estimate() {
+        let (optimizer, mock) = mocked_optimizer();
+        mock.push(U256::from(90_000u64)).unwrap();
+        mock.push(U256::from(95_000u64)).unwrap();
+        mock.push_response(MockResponse::Error(JsonRpcError {
+            code: METHOD_NOT_FOUND,
+            message: "the method eth_createAccessList does not exist/is not available".to_string(),
+            data: None,
+        }));
+
+        let (list, gas) = optimizer.build_access_list(&arbitrage_tx(4, 1)).await.unwrap();
+        assert!(list.0.is_empty());
+        assert_eq!(gas, 95_000);
+
+        // No second createAccessList attempt once the RPC said no
+        let mut tx = arbitrage_tx(5, 1);
+        assert_eq!(optimizer.optimize_access_list(&mut tx).await.unwrap(), AccessListDecision::Unsupported);
+        assert_eq!(optimizer.cached_access_lists(), 0);
+    }
+}
@@ -2,6 +2,7 @@
 // Protects against sandwich attacks and enables MEV extraction with 90%+ win rates
 
 use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -82,6 +83,14 @@ impl MevEngine {
         })
     }
     
+    /// Check the Flashbots relay answers; errors when none is configured
+    pub async fn ping_relay(&self) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.flashbots_provider {
+            Some(flashbots) => flashbots.ping().await,
+            None => Err("no Flashbots relay configured".into()),
+        }
+    }
+    
     /// Scan mempool for MEV opportunities with 90%+ win rate
     pub async fn scan_for_opportunities(&self) -> Vec<MevOpportunity> {
         let mut opportunities = Vec::new();
@@ -250,6 +259,20 @@ impl FlashbotsProvider {
         Ok(Self { rpc_url })
     }
     
+    /// The relay rejects unsigned requests with a 4xx, which still proves it is reachable
+    async fn ping(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let response = reqwest::Client::new()
+            .post(&self.rpc_url)
+            .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber", "params": [] }))
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?;
+        if response.status().is_server_error() {
+            return Err(format!("relay returned {}", response.status()).into());
+        }
+        Ok(())
+    }
+    
     async fn send_bundle(&self, bundle: Bundle) -> Result<H256, Box<dyn std::error::Error>> {
         // Send bundle to Flashbots relay
         todo!("Implement Flashbots bundle submission")
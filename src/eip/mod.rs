@@ -2,14 +2,19 @@
 // Integrates Ethereum standards for on-chain macro strikes
 
 use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use tracing::{info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Mutex;
+use crate::monitoring::api_calls::{ApiCallRecorder, ApiProvider};
+use eip1559::{DynamicFeeCalculator, FeeUrgency};
 
 pub mod eip1559;  // Dynamic fee market
-pub mod eip2612;  // Permit (gasless approvals)
 pub mod eip2930;  // Access lists for gas optimization
 pub mod eip4337;  // Account abstraction for smart wallets
-pub mod eip4626;  // Tokenized vault standard
 pub mod mev;      // MEV protection and extraction
 
 /// EIP Configuration for on-chain trading
@@ -35,6 +40,21 @@ pub struct EipConfig {
     
     /// Permit signatures enabled (EIP-2612)
     pub use_permit: bool,
+    
+    /// Deployed arbitrage contract that strikes call
+    #[serde(default)]
+    pub arbitrage_contract: Option<Address>,
+    
+    /// Wallet balance below which the health check fails, in wei
+    #[serde(default = "default_min_wallet_balance_wei")]
+    pub min_wallet_balance_wei: U256,
+}
+
+/// Default minimum wallet balance: gas for a handful of strikes (0.05 ETH)
+pub const MIN_WALLET_BALANCE_WEI: u64 = 50_000_000_000_000_000;
+
+fn default_min_wallet_balance_wei() -> U256 {
+    U256::from(MIN_WALLET_BALANCE_WEI)
 }
 
 /// On-chain opportunity types specific to Ethereum/EVM
@@ -83,6 +103,304 @@ pub enum OnChainOpportunity {
     },
 }
 
+impl OnChainOpportunity {
+    pub fn kind(&self) -> OpportunityKind {
+        match self {
+            OnChainOpportunity::DexArbitrage { .. } => OpportunityKind::DexArbitrage,
+            OnChainOpportunity::Liquidation { .. } => OpportunityKind::Liquidation,
+            OnChainOpportunity::MevSandwich { .. } => OpportunityKind::MevSandwich,
+            OnChainOpportunity::YieldOptimization { .. } => OpportunityKind::YieldOptimization,
+            OnChainOpportunity::FlashLoanArbitrage { .. } => OpportunityKind::FlashLoanArbitrage,
+        }
+    }
+
+    /// Lending protocol or flash-loan provider, for per-protocol gas overrides
+    pub fn protocol(&self) -> Option<&str> {
+        match self {
+            OnChainOpportunity::Liquidation { protocol, .. } => Some(protocol),
+            OnChainOpportunity::FlashLoanArbitrage { loan_provider, .. } => Some(loan_provider),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OpportunityKind {
+    DexArbitrage,
+    Liquidation,
+    MevSandwich,
+    YieldOptimization,
+    FlashLoanArbitrage,
+}
+
+impl OpportunityKind {
+    /// Fee urgency the opportunity is priced and submitted at
+    pub fn urgency(self) -> FeeUrgency {
+        match self {
+            OpportunityKind::DexArbitrage | OpportunityKind::MevSandwich => FeeUrgency::Immediate,
+            OpportunityKind::Liquidation | OpportunityKind::FlashLoanArbitrage => FeeUrgency::Fast,
+            OpportunityKind::YieldOptimization => FeeUrgency::Standard,
+        }
+    }
+}
+
+// ==================== OPPORTUNITY COSTING ====================
+
+/// ETH value of token amounts, for profits and fees not denominated in ETH
+pub trait TokenPriceSource: Send + Sync {
+    fn eth_value_wei(&self, token: Address, amount: U256) -> Option<U256>;
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TokenQuote {
+    pub eth_wei_per_token: U256, // Per whole token
+    pub decimals: u8,
+}
+
+/// Fixed quotes, e.g. refreshed from the market data feed
+#[derive(Debug, Clone, Default)]
+pub struct StaticPriceSource {
+    quotes: HashMap<Address, TokenQuote>,
+}
+
+impl StaticPriceSource {
+    pub fn with_quote(mut self, token: Address, eth_wei_per_token: U256, decimals: u8) -> Self {
+        self.quotes.insert(token, TokenQuote { eth_wei_per_token, decimals });
+        self
+    }
+}
+
+impl TokenPriceSource for StaticPriceSource {
+    fn eth_value_wei(&self, token: Address, amount: U256) -> Option<U256> {
+        let quote = self.quotes.get(&token)?;
+        amount.checked_mul(quote.eth_wei_per_token)?.checked_div(U256::exp10(quote.decimals as usize))
+    }
+}
+
+/// Gas units, fees and the profit gate applied before an on-chain strike is sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpportunityCosting {
+    pub gas_units: HashMap<OpportunityKind, u64>,
+    pub protocol_gas_units: HashMap<String, HashMap<OpportunityKind, u64>>, // Overrides gas_units
+    pub flash_loan_fee_bps: HashMap<String, u32>, // By loan provider
+    pub min_net_to_cost_ratio: f64, // Net profit must be at least this multiple of costs
+}
+
+impl Default for OpportunityCosting {
+    fn default() -> Self {
+        Self {
+            gas_units: [
+                (OpportunityKind::DexArbitrage, 250_000),
+                (OpportunityKind::Liquidation, 450_000),
+                (OpportunityKind::MevSandwich, 300_000), // Both legs
+                (OpportunityKind::YieldOptimization, 350_000),
+                (OpportunityKind::FlashLoanArbitrage, 550_000),
+            ]
+            .into_iter()
+            .collect(),
+            protocol_gas_units: [
+                ("Compound".to_string(), [(OpportunityKind::Liquidation, 600_000)].into_iter().collect()),
+                ("dYdX".to_string(), [(OpportunityKind::FlashLoanArbitrage, 650_000)].into_iter().collect()),
+            ]
+            .into_iter()
+            .collect(),
+            flash_loan_fee_bps: [("Aave".to_string(), 5), ("dYdX".to_string(), 0), ("Balancer".to_string(), 0)]
+                .into_iter()
+                .collect(),
+            min_net_to_cost_ratio: 3.0,
+        }
+    }
+}
+
+/// What an opportunity was expected to cost and earn, all in wei of ETH
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostBreakdown {
+    pub kind: OpportunityKind,
+    pub protocol: Option<String>,
+    pub gas_units: u64,
+    pub max_fee_per_gas: U256,
+    pub gas_cost_wei: U256, // At the max fee, so the worst case for the chosen urgency
+    pub flash_loan_fee_wei: U256,
+    pub total_cost_wei: U256,
+    pub gross_profit_wei: U256, // Before every cost in this breakdown
+    pub net_profit_wei: U256, // Zero when costs exceed the gross profit
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CostingRejection {
+    Unsupported(OpportunityKind),
+    NoPrice(Address),
+    Unprofitable(CostBreakdown),
+    BelowCostRatio { breakdown: CostBreakdown, min_ratio: f64 },
+}
+
+impl std::fmt::Display for CostingRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CostingRejection::Unsupported(kind) => write!(f, "{:?} opportunities are not traded", kind),
+            CostingRejection::NoPrice(token) => write!(f, "No ETH price for token {:?}", token),
+            CostingRejection::Unprofitable(b) => write!(
+                f,
+                "Costs {} wei exceed gross profit {} wei",
+                b.total_cost_wei, b.gross_profit_wei
+            ),
+            CostingRejection::BelowCostRatio { breakdown, min_ratio } => write!(
+                f,
+                "Net profit {} wei below {}x costs of {} wei",
+                breakdown.net_profit_wei, min_ratio, breakdown.total_cost_wei
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CostingRejection {}
+
+impl OpportunityCosting {
+    pub fn gas_units_for(&self, kind: OpportunityKind, protocol: Option<&str>) -> u64 {
+        protocol
+            .and_then(|p| self.protocol_gas_units.get(p))
+            .and_then(|overrides| overrides.get(&kind))
+            .or_else(|| self.gas_units.get(&kind))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Cost `opportunity` at `max_fee_per_gas` and apply the net-profit-to-cost gate. Sandwiches
+    /// front-run a user's swap and yield moves carry no position size to price, so both are
+    /// rejected outright.
+    pub fn evaluate(
+        &self,
+        opportunity: &OnChainOpportunity,
+        max_fee_per_gas: U256,
+        prices: &dyn TokenPriceSource,
+    ) -> Result<CostBreakdown, CostingRejection> {
+        let kind = opportunity.kind();
+        if matches!(kind, OpportunityKind::MevSandwich | OpportunityKind::YieldOptimization) {
+            return Err(CostingRejection::Unsupported(kind));
+        }
+        let protocol = opportunity.protocol();
+        let to_eth = |token: Address, amount: U256| {
+            prices.eth_value_wei(token, amount).ok_or(CostingRejection::NoPrice(token))
+        };
+        let bps = |amount: U256, bps: u32| amount * U256::from(bps) / U256::from(10_000u64);
+
+        let gas_units = self.gas_units_for(kind, protocol);
+        let gas_cost_wei = max_fee_per_gas * U256::from(gas_units);
+
+        // DexArbitrage and flash-loan profits are quoted from pool outputs, so swap fees are already
+        // out of them and are not charged again. `profit_after_fees` is also net of the loan fee,
+        // which is added back so the breakdown charges it exactly once.
+        let (gross_profit_wei, flash_loan_fee_wei) = match opportunity {
+            OnChainOpportunity::DexArbitrage { profit_wei, .. } => (*profit_wei, U256::zero()),
+            OnChainOpportunity::Liquidation { collateral_token, profit_estimate, .. } => {
+                (to_eth(*collateral_token, *profit_estimate)?, U256::zero())
+            }
+            OnChainOpportunity::FlashLoanArbitrage { loan_provider, loan_token, loan_amount, profit_after_fees } => {
+                let fee_bps = self.flash_loan_fee_bps.get(loan_provider).copied().unwrap_or(0);
+                let fee = to_eth(*loan_token, bps(*loan_amount, fee_bps))?;
+                (to_eth(*loan_token, *profit_after_fees)? + fee, fee)
+            }
+            OnChainOpportunity::MevSandwich { .. } | OnChainOpportunity::YieldOptimization { .. } => {
+                return Err(CostingRejection::Unsupported(kind));
+            }
+        };
+
+        let total_cost_wei = gas_cost_wei + flash_loan_fee_wei;
+        let breakdown = CostBreakdown {
+            kind,
+            protocol: protocol.map(str::to_string),
+            gas_units,
+            max_fee_per_gas,
+            gas_cost_wei,
+            flash_loan_fee_wei,
+            total_cost_wei,
+            gross_profit_wei,
+            net_profit_wei: gross_profit_wei.saturating_sub(total_cost_wei),
+        };
+
+        if gross_profit_wei <= total_cost_wei {
+            return Err(CostingRejection::Unprofitable(breakdown));
+        }
+        // Ratio in thousandths keeps the comparison in integers
+        let ratio_milli = U256::from((self.min_net_to_cost_ratio * 1_000.0).round().max(0.0) as u64);
+        if breakdown.net_profit_wei * U256::from(1_000u64) < total_cost_wei * ratio_milli {
+            return Err(CostingRejection::BelowCostRatio { breakdown, min_ratio: self.min_net_to_cost_ratio });
+        }
+        Ok(breakdown)
+    }
+}
+
+/// A sent on-chain strike with the costing it was approved on
+#[derive(Debug, Clone)]
+pub struct ExecutionAttempt {
+    pub costing: CostBreakdown,
+    pub receipt: TransactionReceipt,
+}
+
+/// A strike that passed costing but failed to send or reverted
+#[derive(Debug)]
+pub struct ExecutionFailure {
+    pub costing: CostBreakdown,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ExecutionFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} strike failed: {}", self.costing.kind, self.reason)
+    }
+}
+
+impl std::error::Error for ExecutionFailure {}
+
+// ==================== HEALTH CHECK ====================
+
+/// One pre-trade check and how long it took
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub passed: bool,
+    pub latency_ms: u64,
+    pub detail: String, // What was observed, or why it failed
+}
+
+impl HealthCheck {
+    fn timed(name: &str, started: Instant, outcome: Result<String, String>) -> Self {
+        let passed = outcome.is_ok();
+        Self {
+            name: name.to_string(),
+            passed,
+            latency_ms: started.elapsed().as_millis() as u64,
+            detail: outcome.unwrap_or_else(|e| e),
+        }
+    }
+}
+
+/// Result of `EipTradingEngine::health_check`; trade only when `all_healthy`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EipHealthReport {
+    pub all_healthy: bool,
+    pub checks: Vec<HealthCheck>,
+}
+
+impl EipHealthReport {
+    pub fn new(checks: Vec<HealthCheck>) -> Self {
+        Self { all_healthy: checks.iter().all(|c| c.passed), checks }
+    }
+    
+    pub fn failed(&self) -> impl Iterator<Item = &HealthCheck> {
+        self.checks.iter().filter(|c| !c.passed)
+    }
+}
+
+fn wallet_balance_outcome(balance: U256, min_balance: U256) -> Result<String, String> {
+    let eth = |wei: U256| ethers::utils::format_ether(wei);
+    if balance >= min_balance {
+        Ok(format!("{} ETH", eth(balance)))
+    } else {
+        Err(format!("{} ETH below minimum {} ETH", eth(balance), eth(min_balance)))
+    }
+}
+
 /// EIP-enabled trading engine
 pub struct EipTradingEngine {
     /// Web3 provider
@@ -99,6 +417,22 @@ pub struct EipTradingEngine {
     
     /// Gas optimization module
     gas_optimizer: eip2930::GasOptimizer,
+    
+    /// API-call accounting for RPC requests
+    call_recorder: Option<Arc<ApiCallRecorder>>,
+    
+    /// Gas table, fees and profit gate applied before every strike
+    costing: OpportunityCosting,
+    
+    /// EIP-1559 fee oracle used to price gas
+    fee_calculator: Mutex<DynamicFeeCalculator>,
+    
+    /// ETH conversion for non-ETH profits and fees
+    price_source: Arc<dyn TokenPriceSource>,
+    
+    /// Next wallet nonce, held locked from allocation until the node accepts the send.
+    /// `None` re-reads the pending count from the node.
+    next_nonce: Mutex<Option<U256>>,
 }
 
 impl EipTradingEngine {
@@ -131,31 +465,121 @@ impl EipTradingEngine {
             config,
             mev_engine,
             gas_optimizer,
+            call_recorder: None,
+            costing: OpportunityCosting::default(),
+            fee_calculator: Mutex::new(DynamicFeeCalculator::new()),
+            price_source: Arc::new(StaticPriceSource::default()),
+            next_nonce: Mutex::new(None),
         })
     }
     
-    /// Execute on-chain opportunity with 90% win rate
+    /// Report RPC requests to the monitoring API-call accounting
+    pub fn with_call_recorder(mut self, recorder: Arc<ApiCallRecorder>) -> Self {
+        self.gas_optimizer = self.gas_optimizer.with_call_recorder(recorder.clone());
+        self.call_recorder = Some(recorder);
+        self
+    }
+    
+    pub fn with_costing(mut self, costing: OpportunityCosting) -> Self {
+        self.costing = costing;
+        self
+    }
+    
+    pub fn with_price_source(mut self, price_source: Arc<dyn TokenPriceSource>) -> Self {
+        self.price_source = price_source;
+        self
+    }
+    
+    /// Price gas at the opportunity's urgency and run the costing gate
+    pub async fn cost_opportunity(
+        &self,
+        opportunity: &OnChainOpportunity,
+    ) -> Result<CostBreakdown, Box<dyn std::error::Error>> {
+        let started = Instant::now();
+        let fees = self.fee_calculator.lock().await
+            .calculate_optimal_fees(&self.provider, opportunity.kind().urgency())
+            .await;
+        self.record_rpc("eth_getBlockByNumber", started, &fees);
+        let (max_fee_per_gas, _priority_fee) = fees?;
+        
+        Ok(self.costing.evaluate(opportunity, max_fee_per_gas, self.price_source.as_ref())?)
+    }
+    
+    /// Execute on-chain opportunity with 90% win rate, once it clears the costing gate
     pub async fn execute_on_chain_strike(
         &self,
         opportunity: &OnChainOpportunity,
-    ) -> Result<TransactionReceipt, Box<dyn std::error::Error>> {
-        match opportunity {
+    ) -> Result<ExecutionAttempt, Box<dyn std::error::Error>> {
+        let costing = match self.cost_opportunity(opportunity).await {
+            Ok(costing) => costing,
+            Err(e) => {
+                info!("⛽ Skipping {:?}: {}", opportunity.kind(), e);
+                return Err(e);
+            }
+        };
+        
+        let result = match opportunity {
             OnChainOpportunity::DexArbitrage { .. } => {
                 self.execute_dex_arbitrage(opportunity).await
             },
-            OnChainOpportunity::Liquidation { .. } => {
-                self.execute_liquidation(opportunity).await
-            },
-            OnChainOpportunity::MevSandwich { .. } => {
-                self.execute_mev_sandwich(opportunity).await
-            },
-            OnChainOpportunity::YieldOptimization { .. } => {
-                self.execute_yield_optimization(opportunity).await
-            },
-            OnChainOpportunity::FlashLoanArbitrage { .. } => {
-                self.execute_flash_loan_arbitrage(opportunity).await
+            // No contract bindings for these yet; fail the attempt rather than send anything
+            OnChainOpportunity::Liquidation { .. }
+            | OnChainOpportunity::MevSandwich { .. }
+            | OnChainOpportunity::YieldOptimization { .. }
+            | OnChainOpportunity::FlashLoanArbitrage { .. } => {
+                Err(format!("no executor for {:?} strikes", opportunity.kind()).into())
             },
+        };
+        
+        // Failures keep the costing they were sent on so the gas table can be tuned
+        let reason = match result {
+            Ok(receipt) if receipt.status != Some(U64::zero()) => {
+                return Ok(ExecutionAttempt { costing, receipt });
+            }
+            Ok(receipt) => format!("reverted in tx {:?} using {:?} gas", receipt.transaction_hash, receipt.gas_used),
+            Err(e) => e.to_string(),
+        };
+        warn!("❌ On-chain strike failed: {} | costing {:?}", reason, costing);
+        Err(Box::new(ExecutionFailure { costing, reason }))
+    }
+    
+    /// Sign a strike transaction from the engine's wallet, with an EIP-2930 access list when
+    /// it saves gas. Strike executors go through `send_strike_transaction`, which assigns the nonce.
+    pub async fn sign_strike_transaction(&self, tx: TypedTransaction) -> Result<Bytes, Box<dyn std::error::Error>> {
+        let (raw, decision) = self.gas_optimizer.sign_with_access_list(tx, &self.wallet).await?;
+        if let eip2930::AccessListDecision::Attached { gas_with_list, gas_without_list } = decision {
+            info!("⛽ Access list saves {} gas", gas_without_list - gas_with_list);
         }
+        Ok(raw)
+    }
+    
+    /// Assign the wallet's next nonce, sign and send. Concurrent strikes queue on the nonce lock,
+    /// so two sends can never share a nonce; a failed send drops the cached nonce so the next
+    /// strike re-reads it from the node.
+    pub async fn send_strike_transaction(
+        &self,
+        mut tx: TypedTransaction,
+    ) -> Result<PendingTransaction<'_, Ws>, Box<dyn std::error::Error>> {
+        let mut next_nonce = self.next_nonce.lock().await;
+        let nonce = match *next_nonce {
+            Some(nonce) => nonce,
+            None => {
+                let started = Instant::now();
+                let count = self.provider
+                    .get_transaction_count(self.wallet.address(), Some(BlockNumber::Pending.into()))
+                    .await;
+                self.record_rpc("eth_getTransactionCount", started, &count);
+                count?
+            }
+        };
+        tx.set_nonce(nonce);
+        let raw = self.sign_strike_transaction(tx).await?;
+        
+        let started = Instant::now();
+        let sent = self.provider.send_raw_transaction(raw).await;
+        self.record_rpc("eth_sendRawTransaction", started, &sent);
+        *next_nonce = sent.is_ok().then(|| nonce + 1);
+        Ok(sent?)
     }
     
     /// Execute DEX arbitrage using smart contract
@@ -172,11 +596,12 @@ impl EipTradingEngine {
         );
         
         // Build transaction with EIP-1559 gas pricing
-        let tx = contract
+        let call = contract
             .execute_arbitrage(pool_a, pool_b, amount)
-            .gas_price(self.calculate_optimal_gas_price().await?)
-            .send()
-            .await?;
+            .gas_price(self.calculate_optimal_gas_price().await?);
+        
+        // Nonce, access list and signature - same pools, same slots
+        let tx = self.send_strike_transaction(call.tx).await?;
         
         // Wait for confirmation
         let receipt = tx.await?;
@@ -185,9 +610,90 @@ impl EipTradingEngine {
         todo!("Implement DEX arbitrage execution")
     }
     
+    /// Verify the engine can trade: RPC reachable, wallet funded above the configured minimum,
+    /// arbitrage contract deployed, gas price available and, with MEV protection, the Flashbots
+    /// relay answering. Every check runs even after one fails.
+    pub async fn health_check(&self) -> EipHealthReport {
+        let mut checks = Vec::new();
+        
+        let started = Instant::now();
+        let block = self.provider.get_block_number().await;
+        self.record_rpc("eth_blockNumber", started, &block);
+        checks.push(HealthCheck::timed(
+            "rpc_connectivity",
+            started,
+            block.map(|n| format!("block {}", n)).map_err(|e| e.to_string()),
+        ));
+        
+        let started = Instant::now();
+        let balance = self.provider.get_balance(self.wallet.address(), None).await;
+        self.record_rpc("eth_getBalance", started, &balance);
+        checks.push(HealthCheck::timed(
+            "wallet_balance",
+            started,
+            balance
+                .map_err(|e| e.to_string())
+                .and_then(|balance| wallet_balance_outcome(balance, self.config.min_wallet_balance_wei)),
+        ));
+        
+        let started = Instant::now();
+        let contract = match self.config.arbitrage_contract {
+            Some(address) => {
+                let code = self.provider.get_code(address, None).await;
+                self.record_rpc("eth_getCode", started, &code);
+                match code {
+                    Ok(code) if !code.is_empty() => Ok(format!("{} bytes at {:?}", code.len(), address)),
+                    Ok(_) => Err(format!("no code at {:?}", address)),
+                    Err(e) => Err(e.to_string()),
+                }
+            }
+            None => Err("no arbitrage contract configured".to_string()),
+        };
+        checks.push(HealthCheck::timed("arbitrage_contract", started, contract));
+        
+        let started = Instant::now();
+        let gas_price = self.provider.get_gas_price().await;
+        self.record_rpc("eth_gasPrice", started, &gas_price);
+        checks.push(HealthCheck::timed(
+            "gas_price",
+            started,
+            gas_price
+                .map(|price| format!("{} gwei", ethers::utils::format_units(price, "gwei").unwrap_or_default()))
+                .map_err(|e| e.to_string()),
+        ));
+        
+        if self.config.mev_protection {
+            let started = Instant::now();
+            let relay = match &self.mev_engine {
+                Some(mev_engine) => {
+                    let ping = mev_engine.ping_relay().await;
+                    self.record_rpc("flashbots_ping", started, &ping);
+                    ping.map(|()| "reachable".to_string()).map_err(|e| e.to_string())
+                }
+                None => Err("MEV engine not initialized".to_string()),
+            };
+            checks.push(HealthCheck::timed("flashbots_relay", started, relay));
+        }
+        
+        let report = EipHealthReport::new(checks);
+        for check in report.failed() {
+            warn!("🩺 Health check {} failed: {}", check.name, check.detail);
+        }
+        report
+    }
+    
+    fn record_rpc<T, E>(&self, endpoint: &str, started: Instant, result: &Result<T, E>) {
+        if let Some(recorder) = &self.call_recorder {
+            recorder.record_result(ApiProvider::EipRpc, endpoint, started, result);
+        }
+    }
+    
     /// Calculate optimal gas price using EIP-1559
     async fn calculate_optimal_gas_price(&self) -> Result<U256, Box<dyn std::error::Error>> {
-        let base_fee = self.provider.get_gas_price().await?;
+        let started = Instant::now();
+        let base_fee = self.provider.get_gas_price().await;
+        self.record_rpc("eth_gasPrice", started, &base_fee);
+        let base_fee = base_fee?;
         let priority_fee = U256::from(self.config.max_priority_fee_gwei) * U256::exp10(9);
         
         Ok(base_fee + priority_fee)
@@ -246,6 +752,126 @@ async fn convert_to_on_chain_opportunity(
 mod tests {
     use super::*;
     
+    fn eth(milli: u64) -> U256 {
+        U256::from(milli) * U256::exp10(15)
+    }
+    
+    fn gwei(n: u64) -> U256 {
+        U256::from(n) * U256::exp10(9)
+    }
+    
+    fn dex_arbitrage(profit_wei: U256) -> OnChainOpportunity {
+        OnChainOpportunity::DexArbitrage {
+            token_a: Address::from_low_u64_be(1),
+            token_b: Address::from_low_u64_be(2),
+            pool_a: Address::from_low_u64_be(3),
+            pool_b: Address::from_low_u64_be(4),
+            profit_wei,
+        }
+    }
+    
+    #[test]
+    fn test_gas_flips_nominal_arbitrage_to_unprofitable() {
+        let costing = OpportunityCosting::default();
+        let prices = StaticPriceSource::default();
+        
+        // 0.02 ETH gross against 250k gas at 100 gwei = 0.025 ETH
+        match costing.evaluate(&dex_arbitrage(eth(20)), gwei(100), &prices) {
+            Err(CostingRejection::Unprofitable(breakdown)) => {
+                assert_eq!(breakdown.gas_cost_wei, eth(25));
+                assert_eq!(breakdown.net_profit_wei, U256::zero());
+            }
+            other => panic!("expected unprofitable, got {:?}", other),
+        }
+        
+        // Same arb at 10 gwei clears 3x: net 0.0175 against 0.0025 costs
+        let approved = costing.evaluate(&dex_arbitrage(eth(20)), gwei(10), &prices).unwrap();
+        assert_eq!(approved.net_profit_wei, eth(20) - eth(25) / 10);
+    }
+    
+    #[test]
+    fn test_ratio_gate_rejects_thin_margin() {
+        let costing = OpportunityCosting::default();
+        // 0.04 ETH gross, 0.0125 ETH gas: profitable, but 0.0275 net is under 3x 0.0125
+        match costing.evaluate(&dex_arbitrage(eth(40)), gwei(50), &StaticPriceSource::default()) {
+            Err(CostingRejection::BelowCostRatio { breakdown, min_ratio }) => {
+                assert_eq!(breakdown.net_profit_wei, eth(40) - eth(25) / 2);
+                assert_eq!(min_ratio, 3.0);
+            }
+            other => panic!("expected ratio rejection, got {:?}", other),
+        }
+        
+        let lenient = OpportunityCosting { min_net_to_cost_ratio: 2.0, ..OpportunityCosting::default() };
+        assert!(lenient.evaluate(&dex_arbitrage(eth(40)), gwei(50), &StaticPriceSource::default()).is_ok());
+    }
+    
+    #[test]
+    fn test_token_profits_and_flash_loan_fees_convert_to_eth() {
+        let usdc = Address::from_low_u64_be(10);
+        // 1 USDC = 0.0005 ETH, 6 decimals
+        let prices = StaticPriceSource::default().with_quote(usdc, U256::from(500_000_000_000_000u64), 6);
+        let costing = OpportunityCosting::default();
+        let flash_loan = |provider: &str| OnChainOpportunity::FlashLoanArbitrage {
+            loan_provider: provider.to_string(),
+            loan_token: usdc,
+            loan_amount: U256::from(1_000_000u64) * U256::exp10(6), // 1M USDC
+            profit_after_fees: U256::from(5_000u64) * U256::exp10(6), // 5,000 USDC = 2.5 ETH
+        };
+        
+        // Aave's 5bps on 1M USDC is 500 USDC = 0.25 ETH, already out of the quoted profit;
+        // 550k gas at 20 gwei = 0.011 ETH is the only cost left to take
+        let aave = costing.evaluate(&flash_loan("Aave"), gwei(20), &prices).unwrap();
+        assert_eq!(aave.gross_profit_wei, eth(2_750));
+        assert_eq!(aave.flash_loan_fee_wei, eth(250));
+        assert_eq!(aave.gas_cost_wei, eth(11));
+        assert_eq!(aave.total_cost_wei, eth(261));
+        assert_eq!(aave.net_profit_wei, eth(2_500) - eth(11));
+        
+        // dYdX charges nothing but overrides the gas table
+        let dydx = costing.evaluate(&flash_loan("dYdX"), gwei(20), &prices).unwrap();
+        assert_eq!((dydx.gas_units, dydx.flash_loan_fee_wei), (650_000, U256::zero()));
+        
+        let unpriced = OnChainOpportunity::Liquidation {
+            protocol: "Aave".to_string(),
+            borrower: Address::from_low_u64_be(12),
+            collateral_token: Address::from_low_u64_be(11),
+            debt_token: usdc,
+            profit_estimate: U256::exp10(18),
+        };
+        assert_eq!(
+            costing.evaluate(&unpriced, gwei(20), &prices),
+            Err(CostingRejection::NoPrice(Address::from_low_u64_be(11)))
+        );
+        
+        let sandwich = OnChainOpportunity::MevSandwich {
+            target_tx: H256::zero(),
+            token_in: usdc,
+            token_out: Address::from_low_u64_be(11),
+            frontrun_amount: U256::exp10(12),
+            backrun_amount: U256::exp10(12),
+        };
+        assert_eq!(
+            costing.evaluate(&sandwich, gwei(1), &prices),
+            Err(CostingRejection::Unsupported(OpportunityKind::MevSandwich))
+        );
+    }
+    
+    #[test]
+    fn test_health_report_fails_on_any_check() {
+        let started = Instant::now();
+        let balance = wallet_balance_outcome(eth(20), U256::from(MIN_WALLET_BALANCE_WEI));
+        assert_eq!(balance, Err("0.020000000000000000 ETH below minimum 0.050000000000000000 ETH".to_string()));
+        assert!(wallet_balance_outcome(eth(50), U256::from(MIN_WALLET_BALANCE_WEI)).is_ok());
+        
+        let report = EipHealthReport::new(vec![
+            HealthCheck::timed("rpc_connectivity", started, Ok("block 1".to_string())),
+            HealthCheck::timed("wallet_balance", started, balance),
+        ]);
+        assert!(!report.all_healthy);
+        assert_eq!(report.failed().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["wallet_balance"]);
+        assert!(EipHealthReport::new(report.checks[..1].to_vec()).all_healthy);
+    }
+    
     #[tokio::test]
     async fn test_eip1559_gas_calculation() {
         // Test dynamic gas pricing
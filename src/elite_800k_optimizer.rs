@@ -387,12 +387,16 @@ impl StrategyCoordinator800K {
         }
 
         // 2. Run all active strategies
+        let mut signals = Vec::new();
         for (name, strategy) in &mut self.active_strategies {
             if let Some(signal) = strategy.generate_signal(market_data).await {
                 println!("📊 {} generated signal: {:?}", name, signal.direction);
-                self.process_signal(signal).await;
+                signals.push(signal);
             }
         }
+        for signal in signals {
+            self.process_signal(signal).await;
+        }
 
         // 3. Update existing positions
         self.update_positions(market_data).await;
@@ -458,8 +462,6 @@ impl StrategyCoordinator800K {
             // Check exit conditions
             if position.should_exit(position.current_price) {
                 positions_to_close.push(i);
-                capital_manager.update_pnl(position, position.current_price);
-                
                 println!("📈 Position closed:");
                 println!("   Symbol: {} | P&L: ${:.2}", position.symbol, position.pnl);
             }
@@ -468,6 +470,7 @@ impl StrategyCoordinator800K {
         // Remove closed positions
         for i in positions_to_close.iter().rev() {
             let position = capital_manager.current_positions.remove(*i);
+            capital_manager.update_pnl(&position, position.current_price);
             capital_manager.deployed_capital -= position.size;
         }
     }
@@ -617,7 +617,7 @@ impl UltraLowLatencyExecutor {
         let route = self.order_router.find_best_route(&order);
         
         // Select execution algorithm
-        let algo = match order.size {
+        let algo = match order.quantity {
             size if size < 1000.0 => ExecutionAlgo::Market,
             size if size < 10000.0 => ExecutionAlgo::Iceberg,
             size if size < 100000.0 => ExecutionAlgo::TWAP,
@@ -625,13 +625,14 @@ impl UltraLowLatencyExecutor {
         };
         
         // Execute with sub-200μs latency target
+        let order_id = order.id.clone();
         let result = self.execution_algos.execute(algo, order, route).await;
         
         let latency = start.elapsed();
         self.latency_monitor.record(latency);
         
         ExecutionReport {
-            order_id: order.id,
+            order_id,
             fill_price: result.avg_price,
             fill_quantity: result.filled_qty,
             latency_us: latency.as_micros() as u64,
@@ -728,7 +729,9 @@ impl EliteQuantFramework {
             
             // CTA Signals
             let futures_markets = self.extract_futures_markets(&market_data);
-            all_signals.extend(self.man_group.generate_cta_signals(&futures_markets).await);
+            all_signals.extend(self.man_group.generate_cta_signals(&futures_markets).await
+                .iter()
+                .filter_map(CTASignal::to_trading_signal));
             
             // Multi-Strategy Signals
             let pod_signals = self.millennium.run_pods(&market_data).await;
@@ -751,12 +754,12 @@ impl EliteQuantFramework {
                     let order = self.convert_to_order(signal);
                     let report = self.executor.execute_order(order).await;
                     
-                    // Track performance
-                    self.performance_tracker.record(report);
-                    
                     if report.latency_us > 200 {
                         println!("⚠️ Latency breach: {}μs", report.latency_us);
                     }
+                    
+                    // Track performance
+                    self.performance_tracker.record(report);
                 }
             }
             
@@ -892,7 +895,7 @@ pub struct ExecutionReport {
 pub struct PatternRecognizer;
 impl PatternRecognizer {
     fn new() -> Self { Self }
-    fn detect_regime(&self, _: &MarketData) -> MarketRegime { MarketRegime::Neutral }
+    fn detect_regime(&self, _: &MarketData) -> MarketRegime { MarketRegime }
 }
 
 pub struct StatisticalArbitrage;
@@ -993,16 +996,16 @@ impl CrossExchangeArbitrage {
 
 pub struct RiskManager;
 impl RiskManager {
-    fn new() -> Self { Self }
-    fn check_limits(&self, _: &[TradingSignal]) -> bool { true }
-    fn get_current_portfolio(&self) -> Portfolio { Portfolio::default() }
+    pub fn new() -> Self { Self }
+    pub fn check_limits(&self, _: &[TradingSignal]) -> bool { true }
+    pub fn get_current_portfolio(&self) -> Portfolio { Portfolio::default() }
 }
 
 pub struct PerformanceTracker;
 impl PerformanceTracker {
-    fn new() -> Self { Self }
+    pub fn new() -> Self { Self }
     fn record(&mut self, _: ExecutionReport) {}
-    fn print_stats(&self) {
+    pub fn print_stats(&self) {
         println!("📈 Performance Stats:");
         println!("  Sharpe Ratio: 2.8");
         println!("  Win Rate: 68%");
@@ -1024,7 +1027,7 @@ pub struct AlphaPrediction;
 #[derive(Default)]
 pub struct OrderBook;
 #[derive(Default)]
-pub struct Quote { price: f64, size: f64, side: Side }
+pub struct Quote { pub price: f64, pub size: f64, pub side: Side }
 #[derive(Default)]
 pub struct ArbitrageOpportunity;
 #[derive(Default)]
@@ -1034,7 +1037,7 @@ pub struct Route;
 #[derive(Default)]
 pub struct ExecutionResult;
 #[derive(Default)]
-pub struct ETF { last_price: f64 }
+pub struct ETF { pub last_price: f64 }
 #[derive(Default)]
 pub struct Basket;
 #[derive(Default)]
@@ -1135,7 +1138,7 @@ pub struct ARPOverlay;
 pub struct FactorPortfolio;
 impl FactorPortfolio {
     fn new(_: OptimalWeights, _: ARPOverlay) -> Self { Self }
-    fn to_signals(&self) -> Vec<TradingSignal> { vec![] }
+    pub fn to_signals(&self) -> Vec<TradingSignal> { vec![] }
 }
 
 // Trend following structures
@@ -1165,6 +1168,9 @@ pub struct TrendAnalysis;
 pub struct MomentumData;
 #[derive(Default)]
 pub struct CTASignal;
+impl CTASignal {
+    fn to_trading_signal(&self) -> Option<TradingSignal> { None }
+}
 
 impl FuturesMarkets {
     fn iter(&self) -> impl Iterator<Item = &FuturesMarket> {
@@ -1210,7 +1216,7 @@ impl AlphaCombiner {
 pub struct AggregatedSignals;
 
 impl AggregatedSignals {
-    fn to_trading_signals(&self) -> Vec<TradingSignal> { vec![] }
+    pub fn to_trading_signals(&self) -> Vec<TradingSignal> { vec![] }
 }
 
 // Point72/Cubist structures
@@ -1246,8 +1252,8 @@ pub struct SystematicPortfolio;
 // Leverage and risk structures
 pub struct KellyCalculator;
 impl KellyCalculator {
-    fn new() -> Self { Self }
-    fn calculate(&self, p: f64, b: f64) -> f64 {
+    pub fn new() -> Self { Self }
+    pub fn calculate(&self, p: f64, b: f64) -> f64 {
         let q = 1.0 - p;
         (p * b - q) / b
     }
@@ -1264,8 +1270,8 @@ impl DrawdownManager {
 
 pub struct VolatilityScaler;
 impl VolatilityScaler {
-    fn new() -> Self { Self }
-    fn scale(&self, volatility: f64) -> f64 {
+    pub fn new() -> Self { Self }
+    pub fn scale(&self, volatility: f64) -> f64 {
         1.0 / (1.0 + volatility)
     }
 }
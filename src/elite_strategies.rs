@@ -5,8 +5,9 @@ use crate::{MacroStrike, StrikeType};
 use crate::api::{MarketDataProvider, OrderBook, Ticker};
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use strike_box::{TokenSnapshot, TokenValidationConfig};
 use tokio::sync::RwLock;
-use log::{info, debug};
+use tracing::{info, debug};
 
 // Statistical Arbitrage Models
 #[derive(Debug, Clone)]
@@ -52,6 +53,8 @@ pub struct EliteStrategyEngine {
     // Two Sigma-style machine learning
     feature_extractors: Arc<RwLock<Vec<Box<dyn FeatureExtractor>>>>,
     ensemble_models: Arc<RwLock<Vec<Box<dyn PredictionModel>>>>,
+    token_snapshots: Arc<RwLock<HashMap<String, TokenSnapshot>>>, // Latest snapshot by symbol, the ML feature source
+    token_validation: TokenValidationConfig, // Bounds the risk features are scaled against
     
     // Jump Trading-style HFT
     latency_arbitrage_opportunities: Arc<RwLock<VecDeque<LatencyArbitrage>>>,
@@ -113,6 +116,8 @@ impl EliteStrategyEngine {
             ornstein_uhlenbeck_params: Arc::new(RwLock::new(HashMap::new())),
             feature_extractors: Arc::new(RwLock::new(Vec::new())),
             ensemble_models: Arc::new(RwLock::new(Vec::new())),
+            token_snapshots: Arc::new(RwLock::new(HashMap::new())),
+            token_validation: TokenValidationConfig::default(),
             latency_arbitrage_opportunities: Arc::new(RwLock::new(VecDeque::new())),
             order_book_dynamics: Arc::new(RwLock::new(HashMap::new())),
             strategy_allocations: Arc::new(RwLock::new(Self::default_allocations())),
@@ -120,6 +125,17 @@ impl EliteStrategyEngine {
         }
     }
     
+    /// Scale ML features against `config` rather than the default validation bounds
+    pub fn with_token_validation(mut self, config: TokenValidationConfig) -> Self {
+        self.token_validation = config;
+        self
+    }
+    
+    /// Latest on-chain snapshot for its symbol; ML alpha only trades symbols that have one
+    pub async fn update_token_snapshot(&self, snapshot: TokenSnapshot) {
+        self.token_snapshots.write().await.insert(snapshot.token_symbol.clone(), snapshot);
+    }
+    
     fn default_allocations() -> HashMap<StrategyType, f64> {
         let mut allocations = HashMap::new();
         allocations.insert(StrategyType::StatisticalArbitrage, 0.25);
@@ -218,8 +234,8 @@ impl EliteStrategyEngine {
     
     /// Two Sigma-style machine learning alpha generation
     pub async fn two_sigma_ml_alpha(&self, symbol: &str) -> Option<MacroStrike> {
-        // Extract features using ensemble of feature extractors
-        let features = self.extract_ml_features(symbol).await;
+        // Extract features from the symbol's latest snapshot
+        let features = self.extract_ml_features(symbol).await?;
         
         // Run ensemble predictions
         let predictions = self.run_ensemble_predictions(&features).await;
@@ -372,9 +388,11 @@ impl EliteStrategyEngine {
         kelly * 0.25 * (1.0 / half_life).min(2.0) * 10000.0
     }
     
-    async fn extract_ml_features(&self, symbol: &str) -> Vec<f64> {
-        // In production, use sophisticated feature engineering
-        vec![0.1, -0.2, 0.3, 0.0, 0.15, -0.05, 0.25, 0.1]
+    /// Risk features of the symbol's latest snapshot; None until one has been seen
+    async fn extract_ml_features(&self, symbol: &str) -> Option<Vec<f64>> {
+        let snapshots = self.token_snapshots.read().await;
+        let snapshot = snapshots.get(symbol)?;
+        Some(snapshot.to_risk_features(&self.token_validation).to_vec())
     }
     
     async fn run_ensemble_predictions(&self, features: &[f64]) -> Vec<f64> {
@@ -425,3 +443,65 @@ impl EliteStrategyEngine {
         self.de_shaw_multi_strategy(symbol).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use strike_box::{CONTRACT_VERIFIED_IDX, HOLDER_COUNT_IDX, RISK_FEATURE_COUNT};
+
+    struct FlatProvider;
+
+    #[async_trait::async_trait]
+    impl MarketDataProvider for FlatProvider {
+        async fn get_market_data(&self, symbol: &str) -> crate::api::ApiResult<crate::api::MarketData> {
+            Ok(crate::api::MarketData {
+                symbol: symbol.to_string(),
+                price: 100.0,
+                volume_24h: 1_000_000.0,
+                price_change_24h: 0.0,
+                timestamp: std::time::SystemTime::now(),
+            })
+        }
+
+        async fn subscribe_prices(&self, _symbols: Vec<String>) -> crate::api::ApiResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ml_features_come_from_the_latest_snapshot() {
+        let engine = EliteStrategyEngine::new(Arc::new(FlatProvider));
+        assert!(engine.extract_ml_features("PEPE").await.is_none());
+        assert!(engine.two_sigma_ml_alpha("PEPE").await.is_none());
+
+        let config = TokenValidationConfig::default();
+        let now = chrono::Utc::now();
+        let mut snapshot = TokenSnapshot {
+            token_address: "0xpepe".to_string(),
+            token_symbol: "PEPE".to_string(),
+            liquidity_usd: Decimal::new(500_000, 0),
+            bid_depth_usd: Decimal::new(100_000, 0),
+            ask_depth_usd: Decimal::new(100_000, 0),
+            holder_count: config.holder_count_preferred / 2,
+            top_10_concentration_pct: Decimal::new(20, 2),
+            largest_wallet_pct: Decimal::new(2, 2),
+            token_age_hours: 48,
+            contract_verified: true,
+            is_proxy_contract: false,
+            deployment_timestamp: now,
+            snapshot_timestamp: now,
+            source: Default::default(),
+            categories: None,
+        };
+        engine.update_token_snapshot(snapshot.clone()).await;
+        let features = engine.extract_ml_features("PEPE").await.unwrap();
+        assert_eq!(features.len(), RISK_FEATURE_COUNT);
+        assert_eq!(features, snapshot.to_risk_features(&config).to_vec());
+        assert_eq!(features[CONTRACT_VERIFIED_IDX], 1.0);
+
+        snapshot.holder_count = config.holder_count_preferred;
+        engine.update_token_snapshot(snapshot).await;
+        assert_eq!(engine.extract_ml_features("PEPE").await.unwrap()[HOLDER_COUNT_IDX], 1.0);
+    }
+}
@@ -121,8 +121,8 @@ impl HistoricalBacktester {
                 current_date = current_date + Duration::minutes(1);
             }
             
-            data_store.insert(symbol.clone(), data_points);
             info!("✅ Loaded {} data points for {}", data_points.len(), symbol);
+            data_store.insert(symbol.clone(), data_points);
         }
         
         info!("✅ Historical data loading complete");
@@ -459,5 +459,5 @@ impl HistoricalBacktester {
     }
 }
 
-use log::info;
+use tracing::info;
 
@@ -2,19 +2,38 @@
 // 8% per bot * 25 bots = 200% returns every 7 days
 // Volume-based striking with 3-5x leverage, immediate exit on win (1-minute max)
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc, Duration};
 use crate::rug_pull_detector::{RugPullDetector, RiskLevel};
+use crate::sim_rng::SimRng;
+use crate::symbols::{SharedSymbolRegistry, SymbolRegistry};
 use strike_box::{
     StrikeBoxEngine, StrikeBoxConfig, TokenSnapshot, Direction as StrikeBoxDirection,
-    RiskValidation, SafetyScore, Position as StrikeBoxPosition, PositionBook,
-    PortfolioState, SystemState as StrikeBoxSystemState,
+    RiskValidation, Position as StrikeBoxPosition, LiquidityScaler, RingBuffer,
+    StopLossConfig, TakeProfitConfig, OrderBook, BookLevel, SnapshotSource, ExitType, TpTarget, MarkToMarketResult,
+    TriggerEvent, TriggerPathAssumption, FeeModel, DEX_VENUE,
+    PositionStatus as StrikeBoxPositionStatus, LogRetentionConfig, AuditSink, JsonlAuditSink,
 };
+use crate::api::coingecko::CoinGeckoClient;
+use crate::api::enrichment::TokenEnricher;
+use crate::api::{ApiConfig, MarketDataProvider};
+#[cfg(feature = "eip")]
+use crate::amm_predictive_arbitrage::HolderDistributionAnalyzer;
+#[cfg(feature = "eip")]
+use ethers::types::Address;
+use strike_box::bootstrap::UniverseBootstrap;
+use strike_box::conv::{self, ConvError};
 use rust_decimal::Decimal;
-use log::{info, warn};
+use tracing::{debug, info, info_span, warn, Instrument};
+use crate::telemetry;
+use crate::index::{IndexConfig, MarketIndex};
+use crate::monitoring::MonitoringSystem;
+use crate::monitoring_bridge::MonitoringBridge;
 
 pub const INITIAL_CAPITAL: f64 = 800_000.0;
 pub const NUM_BOTS: usize = 25;
@@ -27,10 +46,37 @@ const MAX_POSITION_TIME_SECONDS: i64 = 60; // 1 minute maximum position time
 const MIN_VOLUME_RATIO: f64 = 2.0; // Require 2x+ volume spike
 const MIN_SAFETY_SCORE: f64 = 0.75; // Minimum safety score for non-traditional assets
 const QUICK_PROFIT_THRESHOLD: f64 = 0.005; // 0.5% quick profit exit
+const VOLATILITY_SIZE_DAMPING: f64 = 10.0; // 5% volatility halves position size
+const MAX_VOLATILITY_ADJUSTMENT: f64 = 0.9; // Never scale size down by more than 90%
+const PERFORMANCE_HISTORY_CAPACITY: usize = 10_000; // Leverage/timing samples kept for aggregate stats
+const MAX_TOKEN_EXPOSURE_PCT: f64 = 0.10; // Max 10% of array capital on one token
+const MAX_GROUP_EXPOSURE_PCT: f64 = 0.25; // Max 25% of array capital on one correlation group
+const MAX_BOTS_PER_TOKEN: usize = 3; // Max simultaneous bots on one token
+const MAX_BOT_POSITIONS: usize = 3; // Max open positions per bot
+const MAX_BOT_EXPOSURE_RATIO: f64 = MAX_LEVERAGE; // Open leveraged notional per bot, in multiples of its capital
+const MAX_BOTS_PER_TARGET: usize = 3; // Max bots assigned one (token, side) in a single round
+const LOSS_COOLDOWN_SECONDS: i64 = 600; // Bot sits out a token for 10 minutes after losing on it
+const DEX_VENUES: [&str; 3] = ["pancakeswap", "uniswap", "sushiswap"]; // Everything else is an order-book venue
+const ASSIGNMENT_HISTORY_CAPACITY: usize = 1_000; // Assignment records kept per bot
+#[cfg(feature = "trade_simulator")]
+const PAPER_FILL_RETENTION: usize = 10_000; // Paper fills kept per bot; the report's totals cover every fill
+const OPPORTUNITY_HALF_LIFE_SECONDS: f64 = 30.0; // Expected profit halves every 30s
+const MAX_OPPORTUNITY_AGE_SECONDS: f64 = 120.0; // Four half-lives: 1/16 of the original edge left
+const DIRECTION_MIN_FLOW: f64 = 0.15; // Net smart-money flow that makes the direction call on its own
+const DIRECTION_MIN_PRICE_MOVE: f64 = 0.03; // Without flow, a 3% spike move is followed
+const DIRECTION_PRICE_SCALE: f64 = 0.05; // Price move that all but saturates its conviction
+const DIRECTION_PRICE_WEIGHT: f64 = 0.3; // Most conviction the price move adds
+const DIRECTION_FLIP_MAX_CONFIDENCE: f64 = 0.3; // Calls this weak may fall back to the other side
+const CYCLE_REPORT_DIRECTION_CALLS: usize = 10; // Latest direction calls the cycle report lists with their inputs
+const DIRECTION_SPECIFIC_GATES: [&str; 5] = ["system_state", "safety_score", "book_capacity", "cross_correlation", "squeeze_risk"];
+const MAX_BOT_RESTARTS: u32 = 3; // Restarts allowed per bot within the restart window
+const BOT_RESTART_WINDOW_MINUTES: i64 = 60; // Rolling window for counting restarts
+const PROJECTION_HISTORY_CYCLES: usize = 4; // Completed cycles whose median daily return drives projections
+const CYCLE_COMPARISON_CYCLES: usize = 4; // Cycles shown side by side in the cycle report
 
 // ==================== HUMMINGBOT ARRAY CONTROLLER ====================
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct HummingbotArray {
     bots: Vec<Arc<Mutex<HummingBot>>>,
     capital_pool: Arc<RwLock<CapitalPool>>,
@@ -38,36 +84,53 @@ pub struct HummingbotArray {
     performance_aggregator: Arc<RwLock<PerformanceAggregator>>,
     rug_pull_detector: Arc<RwLock<RugPullDetector>>,
     strike_box_engine: Arc<RwLock<StrikeBoxEngine>>,
+    risk_guard: Arc<RwLock<ArrayRiskGuard>>,
+    #[cfg(feature = "eip")]
+    holder_analyzer: Arc<RwLock<HolderDistributionAnalyzer>>, // Holder trends of scanned DEX tokens
+    fault_config: FaultToleranceConfig,
+    bot_health: Vec<BotHealth>,
+    restart_log: Vec<BotRestart>,
+    rng: SimRng, // Simulated market data; each bot runs on its own fork
     cycle_start: DateTime<Utc>,
+    cycle_start_capital: f64,
     total_capital: f64,
     cycle_profits: f64,
+    cycle_store: Option<Arc<dyn CycleStore>>,
+    cycle_history: Vec<CycleRecord>, // Completed cycles, oldest first, at most log_retention.max_cycle_records
+    cycle_direction_counts: BTreeMap<String, u32>, // Scanned opportunities this cycle by side
+    cycle_direction_calls: VecDeque<DirectionReport>, // Latest CYCLE_REPORT_DIRECTION_CALLS of them, oldest first
+    symbols: SharedSymbolRegistry,
+    token_enricher: Option<Arc<TokenEnricher>>, // CoinGecko age, contract and categories for snapshots
 }
 
 impl HummingbotArray {
     pub async fn new() -> Self {
+        Self::with_rng(SimRng::from_entropy()).await
+    }
+
+    /// Array whose simulated prices, volumes and token data all come from `rng`
+    pub async fn with_rng(rng: SimRng) -> Self {
         let capital_pool = Arc::new(RwLock::new(CapitalPool::new(INITIAL_CAPITAL)));
         let strike_coordinator = Arc::new(StrikeCoordinator::new());
         let performance_aggregator = Arc::new(RwLock::new(PerformanceAggregator::new()));
-        let rug_pull_detector = Arc::new(RwLock::new(RugPullDetector::new()));
+        let rug_pull_detector = Arc::new(RwLock::new(RugPullDetector::new().with_rng(rng.fork())));
+        let risk_guard = Arc::new(RwLock::new(ArrayRiskGuard::new(ArrayRiskConfig::default(), INITIAL_CAPITAL)));
         
         // Initialize Strike Box Engine
         let strike_box_config = StrikeBoxConfig::default();
-        let strike_box_engine = Arc::new(RwLock::new(
-            StrikeBoxEngine::new(strike_box_config, Decimal::from(INITIAL_CAPITAL as i64))
-        ));
+        let mut engine = StrikeBoxEngine::new(
+            strike_box_config.clone(),
+            conv::usd_from_f64(INITIAL_CAPITAL).expect("initial capital is a finite USD amount"),
+        );
+        // Precompute sizing tiers and exposure bounds before the first scan validates anything
+        engine.warmup();
+        let strike_box_engine = Arc::new(RwLock::new(engine));
         
         let mut bots = Vec::new();
         
         // Initialize 25 specialized bots
         for i in 0..NUM_BOTS {
-            let bot_type = match i % 5 {
-                0 => BotStrategy::MarketMaking,
-                1 => BotStrategy::Arbitrage,
-                2 => BotStrategy::Momentum,
-                3 => BotStrategy::MeanReversion,
-                4 => BotStrategy::Volatility,
-                _ => BotStrategy::MarketMaking,
-            };
+            let bot_type = BotStrategy::for_bot(i);
             
             let bot = Arc::new(Mutex::new(HummingBot::new(
                 i,
@@ -75,7 +138,9 @@ impl HummingbotArray {
                 bot_type,
                 capital_pool.clone(),
                 strike_coordinator.clone(),
-            )));
+            ).with_risk_guard(risk_guard.clone())
+                .with_strike_box_config(strike_box_config.clone())
+                .with_rng(rng.fork())));
             
             bots.push(bot);
         }
@@ -87,69 +152,186 @@ impl HummingbotArray {
             performance_aggregator,
             rug_pull_detector,
             strike_box_engine,
+            risk_guard,
+            #[cfg(feature = "eip")]
+            holder_analyzer: Arc::new(RwLock::new(HolderDistributionAnalyzer::new())),
+            fault_config: FaultToleranceConfig::default(),
+            bot_health: vec![BotHealth::default(); NUM_BOTS],
+            restart_log: Vec::new(),
+            rng,
             cycle_start: Utc::now(),
+            cycle_start_capital: INITIAL_CAPITAL,
             total_capital: INITIAL_CAPITAL,
             cycle_profits: 0.0,
+            cycle_store: None,
+            cycle_history: Vec::new(),
+            cycle_direction_counts: BTreeMap::new(),
+            cycle_direction_calls: VecDeque::new(),
+            symbols: SymbolRegistry::with_default_instruments().shared(),
+            token_enricher: None,
+        }
+    }
+
+    pub fn with_fault_tolerance(mut self, fault_config: FaultToleranceConfig) -> Self {
+        self.fault_config = fault_config;
+        self
+    }
+
+    /// Resolve scanned pairs and their token addresses through a shared registry
+    pub fn with_symbol_registry(mut self, symbols: SharedSymbolRegistry) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Persist completed cycles to `store`, starting from the most recent cycles it already holds
+    pub fn with_cycle_store(mut self, store: Arc<dyn CycleStore>) -> Self {
+        match store.load() {
+            Ok(mut history) => {
+                let limit = match self.strike_box_engine.try_read() {
+                    Ok(engine) => engine.config.log_retention.max_cycle_records,
+                    Err(_) => LogRetentionConfig::default().max_cycle_records,
+                };
+                retain_recent_cycles(&mut history, limit);
+                self.cycle_history = history;
+            }
+            Err(e) => warn!("⚠️  Cycle history unreadable, starting empty: {}", e),
+        }
+        self.cycle_store = Some(store);
+        self
+    }
+
+    /// Fetch each candidate token's bytecode from `rpc_url` for the rug-pull scan
+    pub async fn with_rug_pull_rpc(self, rpc_url: impl Into<String>) -> Self {
+        let mut detector = self.rug_pull_detector.write().await;
+        *detector = detector.clone().with_rpc_url(rpc_url);
+        drop(detector);
+        self
+    }
+
+    /// Publish the strike box portfolio to `monitoring` on the bridge's interval
+    pub fn start_monitoring(&self, monitoring: Arc<MonitoringSystem>) -> Arc<MonitoringBridge> {
+        let bridge = Arc::new(MonitoringBridge::new(monitoring));
+        bridge.start(self.strike_box_engine.clone());
+        bridge
+    }
+
+    /// Track `index` from `provider`, feeding its drop events into the strike box and its
+    /// level and coverage to `monitoring`
+    pub fn start_market_index(
+        &self,
+        index: MarketIndex,
+        provider: Arc<dyn MarketDataProvider>,
+        monitoring: Arc<MonitoringSystem>,
+    ) {
+        index.start(provider, self.strike_box_engine.clone(), monitoring);
+    }
+
+    /// Hand entry, exit and rejection logs the strike box evicts for space to `sink` instead of
+    /// dropping them
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.strike_box_engine = match Arc::try_unwrap(self.strike_box_engine) {
+            Ok(engine) => Arc::new(RwLock::new(engine.into_inner().with_audit_sink(sink))),
+            Err(shared) => {
+                warn!("⚠️  Strike box engine already shared, evicted logs will not be spilled");
+                shared
+            }
+        };
+        self
+    }
+
+    /// Warm-start the strike box and holder trends from a universe bootstrap file, dropping
+    /// history older than the engine's `bootstrap.max_age_hours`; an unreadable file leaves both cold
+    pub fn with_bootstrap(mut self, path: impl AsRef<Path>) -> Self {
+        let Some(engine) = Arc::get_mut(&mut self.strike_box_engine) else {
+            warn!("⚠️  Strike box engine already shared, bootstrap not applied");
+            return self;
+        };
+        let engine = engine.get_mut();
+        match UniverseBootstrap::import(path.as_ref(), engine.config.bootstrap.max_age()) {
+            #[allow(unused_mut)]
+            Ok((mut bootstrap, report)) => {
+                let coverage = bootstrap.coverage(&engine.config);
+                #[cfg(feature = "eip")]
+                match Arc::get_mut(&mut self.holder_analyzer) {
+                    Some(holders) => {
+                        holders.get_mut().install_holder_history(&std::mem::take(&mut bootstrap.holder_history));
+                    }
+                    None => warn!("⚠️  Holder analyzer already shared, holder trends not restored"),
+                }
+                engine.install_bootstrap(bootstrap);
+                for (section, reason) in &report.skipped_sections {
+                    warn!(section = %section, reason = %reason, "bootstrap section skipped");
+                }
+                info!(
+                    tokens = coverage.tokens,
+                    volatility_ready = ?coverage.volatility_ready,
+                    correlated_pairs = coverage.correlated_pairs,
+                    holder_trends = coverage.holder_trends,
+                    stale_tokens = report.stale_tokens_dropped.len(),
+                    stale_pairs = report.stale_pairs_dropped,
+                    stale_holder_series = report.stale_holder_series_dropped,
+                    "strike box warm-started from bootstrap"
+                );
+            }
+            Err(e) => warn!("⚠️  Bootstrap unreadable, starting cold: {}", e),
         }
+        self
+    }
+
+    /// Write the strike box's price history, correlations and holder trends for the next warm start
+    pub async fn export_bootstrap(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        #[cfg(feature = "eip")]
+        let holder_history = self.holder_analyzer.read().await.holder_history();
+        #[cfg(not(feature = "eip"))]
+        let holder_history = std::collections::BTreeMap::new();
+        UniverseBootstrap::export(&*self.strike_box_engine.read().await, &holder_history, path)
+    }
+
+    /// Fill snapshot age, contract and categories from CoinGecko; on-chain age lookup remains
+    /// the fallback for tokens it doesn't list
+    pub fn with_token_enricher(mut self, enricher: Arc<TokenEnricher>) -> Self {
+        self.token_enricher = Some(enricher);
+        self
+    }
+
+    /// Completed cycles, oldest first
+    pub fn cycle_history(&self) -> &[CycleRecord] {
+        &self.cycle_history
+    }
+
+    /// The last `cycles` completed cycles side by side, with deltas from each one's predecessor
+    pub fn cycle_comparison(&self, cycles: usize) -> CycleComparison {
+        CycleComparison::from_history(&self.cycle_history, cycles)
     }
 
     pub async fn execute_coordinated_strike(&mut self) {
-        println!("\n╔══════════════════════════════════════════════════════════╗");
-        println!("║     HUMMINGBOT ARRAY - 25 BOT COORDINATED STRIKE         ║");
-        println!("╚══════════════════════════════════════════════════════════╝");
-        println!("⚡ Launching 25 parallel bots with 3-5x leverage");
-        println!("🎯 Target: 8% per bot = 200% total every 7 days");
-        println!("💰 Capital: $800,000 | Per Bot: $32,000");
-        println!("🛡️  Rug Pull Protection: ENABLED");
-        println!("📦 Strike Box Integration: ENABLED (Institutional Validation)");
-        println!("⏱️  Max Position Time: 1 minute (NO HODL)");
-        println!("📊 Volume-Based Striking: 2x+ volume spikes required");
+        info!(bots = NUM_BOTS, capital = self.total_capital, "array strike loop starting");
+        if telemetry::pretty_banners() {
+            println!("\n╔══════════════════════════════════════════════════════════╗");
+            println!("║     HUMMINGBOT ARRAY - 25 BOT COORDINATED STRIKE         ║");
+            println!("╚══════════════════════════════════════════════════════════╝");
+            println!("⚡ Launching 25 parallel bots with 3-5x leverage");
+            println!("🎯 Target: 8% per bot = 200% total every 7 days");
+            println!("💰 Capital: $800,000 | Per Bot: $32,000");
+            println!("🛡️  Rug Pull Protection: ENABLED");
+            println!("📦 Strike Box Integration: ENABLED (Institutional Validation)");
+            println!("⏱️  Max Position Time: 1 minute (NO HODL)");
+            println!("📊 Volume-Based Striking: 2x+ volume spikes required");
+        }
         
         loop {
             // Phase 1: Check and close any positions that hit targets/stops
             self.check_and_close_positions().await;
             
-            // Phase 2: Market Scanning (volume-based, non-traditional assets)
-            let opportunities = self.scan_all_markets().await;
-            
-            // Phase 2: Coordinate Strike Assignments
-            let assignments = self.strike_coordinator.assign_targets(&opportunities, NUM_BOTS).await;
-            
-            // Phase 3: Parallel Bot Execution
-            let mut handles = Vec::new();
-            
-            for (i, bot) in self.bots.iter().enumerate() {
-                if let Some(target) = assignments.get(&i) {
-                    let bot_clone = bot.clone();
-                    let target_clone = target.clone();
-                    
-                    let handle = tokio::spawn(async move {
-                        let mut bot_guard = bot_clone.lock().await;
-                        bot_guard.execute_strike(target_clone).await
-                    });
-                    
-                    handles.push(handle);
-                }
-            }
-            
-            // Wait for all bots to complete their strikes
-            let mut cycle_results = Vec::new();
-            for handle in handles {
-                if let Ok(result) = handle.await {
-                    cycle_results.push(result);
-                }
-            }
-            
-            // Phase 4: Aggregate Results
-            self.aggregate_cycle_results(cycle_results).await;
+            // Phases 2-4: scan, assign, execute and aggregate
+            self.run_strike_round().await;
             
             // Phase 5: Rebalance and Compound
             self.rebalance_capital().await;
             
             // Check cycle completion
             if self.is_cycle_complete() {
-                self.print_cycle_report().await;
-                self.reset_cycle();
+                self.complete_cycle().await;
             }
             
             // High-frequency loop - execute strikes continuously
@@ -157,6 +339,167 @@ impl HummingbotArray {
         }
     }
 
+    /// One round of the strike loop: scan markets, assign targets within the array's exposure
+    /// caps, run the assigned bots and fold their results into the cycle. Returns the round's
+    /// results.
+    pub async fn run_strike_round(&mut self) -> Vec<StrikeResult> {
+        // Market Scanning (volume-based, non-traditional assets)
+        let opportunities = self.scan_all_markets().await;
+        if opportunities.is_empty() {
+            return Vec::new();
+        }
+        self.record_direction_calls(&opportunities);
+        
+        // Coordinate Strike Assignments (within array-level exposure caps)
+        let assignments = {
+            let guard = self.risk_guard.read().await;
+            self.strike_coordinator.assign_targets(&opportunities, NUM_BOTS, &guard).await
+        };
+        
+        // Parallel Bot Execution (failed bots restarted or quarantined)
+        let cycle_results = self.fault_tolerant_execute(&assignments).await;
+        
+        // Aggregate Results
+        self.aggregate_cycle_results(cycle_results.clone()).await;
+        cycle_results
+    }
+
+    /// Counts each opportunity's side for the cycle report and keeps the latest calls' inputs
+    /// and rationale
+    fn record_direction_calls(&mut self, opportunities: &[MarketOpportunity]) {
+        for opportunity in opportunities {
+            let report = DirectionReport::new(&opportunity.pair, &opportunity.direction);
+            *self.cycle_direction_counts.entry(format!("{:?}", report.call)).or_insert(0) += 1;
+            if self.cycle_direction_calls.len() == CYCLE_REPORT_DIRECTION_CALLS {
+                self.cycle_direction_calls.pop_front();
+            }
+            self.cycle_direction_calls.push_back(report);
+        }
+    }
+
+    pub fn total_capital(&self) -> f64 {
+        self.total_capital
+    }
+
+    /// Profit booked so far in the current cycle
+    pub fn cycle_profits(&self) -> f64 {
+        self.cycle_profits
+    }
+
+    /// Run assigned strikes in parallel, skipping quarantined bots. A bot task that panics or
+    /// is cancelled is restarted with its original capital share, or quarantined once it has
+    /// used up its restarts within the window.
+    pub async fn fault_tolerant_execute(
+        &mut self,
+        assignments: &HashMap<usize, MarketOpportunity>,
+    ) -> Vec<StrikeResult> {
+        let mut handles = Vec::new();
+        for (bot_id, bot) in self.bots.iter().enumerate() {
+            if self.bot_health[bot_id].status == BotStatus::Quarantined {
+                continue;
+            }
+            if let Some(target) = assignments.get(&bot_id) {
+                let bot_clone = bot.clone();
+                let target_clone = target.clone();
+                handles.push((bot_id, tokio::spawn(async move {
+                    let mut bot_guard = bot_clone.lock().await;
+                    bot_guard.execute_strike(target_clone).await
+                })));
+            }
+        }
+        
+        let (results, failures) = join_supervised(handles).await;
+        for (bot_id, reason) in failures {
+            self.handle_bot_failure(bot_id, &reason).await;
+        }
+        results
+    }
+
+    /// Restart a failed bot, or quarantine it and hand its capital to the healthy bots
+    pub async fn handle_bot_failure(&mut self, bot_id: usize, reason: &str) -> BotStatus {
+        let now = Utc::now();
+        self.release_bot_exposure(bot_id).await;
+        
+        let window = self.fault_config.restart_window;
+        let health = &mut self.bot_health[bot_id];
+        health.recent_restarts.retain(|at| now - *at < window);
+        
+        if (health.recent_restarts.len() as u32) < self.fault_config.max_restarts {
+            health.restart_count += 1;
+            health.recent_restarts.push_back(now);
+            health.status = BotStatus::Restarted;
+            let restart_count = health.restart_count;
+            
+            // Fresh bot with its original share; the failed one's state is not trusted
+            let strike_box_config = self.strike_box_engine.read().await.config.clone();
+            self.bots[bot_id] = Arc::new(Mutex::new(HummingBot::new(
+                bot_id,
+                CAPITAL_PER_BOT,
+                BotStrategy::for_bot(bot_id),
+                self.capital_pool.clone(),
+                self.strike_coordinator.clone(),
+            ).with_risk_guard(self.risk_guard.clone())
+                .with_strike_box_config(strike_box_config)
+                .with_rng(self.rng.fork())));
+            
+            warn!("🔁 Bot {} restarted at {} (restart #{}): {}", bot_id, now, restart_count, reason);
+            self.restart_log.push(BotRestart {
+                bot_id,
+                reason: reason.to_string(),
+                at: now,
+                restart_count,
+                action: BotStatus::Restarted,
+            });
+            return BotStatus::Restarted;
+        }
+        
+        health.status = BotStatus::Quarantined;
+        let restart_count = health.restart_count;
+        let healthy: Vec<usize> = (0..self.bots.len())
+            .filter(|&id| self.bot_health[id].status != BotStatus::Quarantined)
+            .collect();
+        let stranded = self.bots[bot_id].lock().await.take_capital();
+        if !healthy.is_empty() {
+            let share = stranded / healthy.len() as f64;
+            for &id in &healthy {
+                self.bots[id].lock().await.add_capital(share);
+            }
+        }
+        self.capital_pool.write().await.reassign(bot_id, &healthy);
+        
+        warn!("⛔ Bot {} quarantined at {} after {} restarts in {} min: {} (${:.2} redistributed to {} bots)",
+            bot_id, now, self.fault_config.max_restarts, window.num_minutes(), reason, stranded, healthy.len());
+        self.restart_log.push(BotRestart {
+            bot_id,
+            reason: reason.to_string(),
+            at: now,
+            restart_count,
+            action: BotStatus::Quarantined,
+        });
+        BotStatus::Quarantined
+    }
+
+    /// Drop whatever exposure a failed bot still held in the array guard
+    async fn release_bot_exposure(&self, bot_id: usize) {
+        let stale_positions = self.bots[bot_id].lock().await.positions.clone();
+        let mut guard = self.risk_guard.write().await;
+        for position in stale_positions {
+            guard.record_close(bot_id, &position.token_address, position.leveraged_size);
+        }
+    }
+
+    pub fn bot_status(&self, bot_id: usize) -> Option<BotStatus> {
+        self.bot_health.get(bot_id).map(|h| h.status)
+    }
+
+    pub fn restart_log(&self) -> &[BotRestart] {
+        &self.restart_log
+    }
+
+    fn healthy_bot_count(&self) -> usize {
+        self.bot_health.iter().filter(|h| h.status != BotStatus::Quarantined).count()
+    }
+
     pub async fn scan_all_markets(&self) -> Vec<MarketOpportunity> {
         let mut opportunities = Vec::new();
         
@@ -193,47 +536,103 @@ impl HummingbotArray {
                     continue;
                 }
                 
-                // Extract token address for validation
-                let token_address = self.extract_token_address(&pair);
+                // Resolve the real token address, if the registry knows one for this venue
+                let dex_address = if DEX_VENUES.contains(&exchange) {
+                    self.resolve_token_address(exchange, &pair)
+                } else {
+                    None
+                };
                 
                 // Fetch real prices and token data
                 let entry_price = self.fetch_price(&pair).await;
                 let liquidity_usd = self.fetch_liquidity_usd(&pair).await;
-                let holder_count = self.fetch_holder_count(&token_address).await;
-                let token_age_hours = self.fetch_token_age_hours(&token_address).await;
                 
-                // Create TokenSnapshot for Strike Box validation
-                let token_snapshot = TokenSnapshot {
-                    token_address: token_address.clone(),
-                    token_symbol: pair.split('/').next().unwrap_or("UNKNOWN").to_string(),
-                    liquidity_usd: Decimal::from(liquidity_usd as i64),
-                    bid_depth_usd: Decimal::from((liquidity_usd * 0.5) as i64),
-                    ask_depth_usd: Decimal::from((liquidity_usd * 0.5) as i64),
-                    holder_count,
-                    top_10_concentration_pct: Decimal::new(45, 2), // Would fetch real data
-                    largest_wallet_pct: Decimal::new(12, 2), // Would fetch real data
-                    token_age_hours,
-                    contract_verified: true, // Would check real verification
-                    is_proxy_contract: false,
-                    deployment_timestamp: Utc::now() - chrono::Duration::hours(token_age_hours as i64),
-                    snapshot_timestamp: Utc::now(),
+                // Create TokenSnapshot for Strike Box validation; CEX pairs, and DEX pairs without a
+                // known contract, have no holders or contract to check, so their snapshot comes
+                // from the order book alone
+                let mut token_snapshot = if let Some(token_address) = &dex_address {
+                    let holder_count = self.fetch_holder_count(&token_address).await;
+                    let top_10_concentration_pct = Decimal::new(45, 2); // Would fetch real data
+                    #[cfg(feature = "eip")]
+                    if let Ok(address) = token_address.parse::<Address>() {
+                        self.holder_analyzer.write().await.record_holder_count(
+                            address,
+                            holder_count as u64,
+                            conv::decimal_to_f64(top_10_concentration_pct),
+                            Utc::now(),
+                        );
+                    }
+                    
+                    let (liquidity_decimal, side_depth_decimal) =
+                        match (conv::usd_from_f64(liquidity_usd), conv::usd_from_f64(liquidity_usd * 0.5)) {
+                            (Ok(total), Ok(side)) => (total, side),
+                            _ => {
+                                warn!("⚠️  Skipping {} - unusable liquidity {}", pair, liquidity_usd);
+                                continue;
+                            }
+                        };
+                    
+                    TokenSnapshot {
+                        token_address: token_address.clone(),
+                        token_symbol: pair.split('/').next().unwrap_or("UNKNOWN").to_string(),
+                        liquidity_usd: liquidity_decimal,
+                        bid_depth_usd: side_depth_decimal,
+                        ask_depth_usd: side_depth_decimal,
+                        holder_count,
+                        top_10_concentration_pct,
+                        largest_wallet_pct: Decimal::new(12, 2), // Would fetch real data
+                        token_age_hours: 0, // Filled below
+                        contract_verified: true, // Would check real verification
+                        is_proxy_contract: false,
+                        deployment_timestamp: Utc::now(),
+                        snapshot_timestamp: Utc::now(),
+                        source: SnapshotSource::Dex,
+                        categories: None,
+                    }
+                } else {
+                    let order_book = self.fetch_order_book(&pair, entry_price, liquidity_usd).await;
+                    TokenSnapshot::from_order_book(&pair, &order_book, exchange)
                 };
+                let listed = self.enrich_snapshot(&mut token_snapshot).await;
+                if token_snapshot.has_onchain_data() && !listed {
+                    let token_age_hours = self.fetch_token_age_hours(&token_snapshot.token_address).await;
+                    token_snapshot.token_age_hours = token_age_hours;
+                    token_snapshot.deployment_timestamp = Utc::now() - chrono::Duration::hours(token_age_hours as i64);
+                }
+                // Exposure and detector key: the contract where there is one, otherwise the pair
+                let token_address = token_snapshot.token_address.clone();
+                if let Some(sector) = token_snapshot.sector() {
+                    self.risk_guard.write().await.set_sector(&token_address, sector);
+                }
                 
-                // Validate with Strike Box (comprehensive institutional validation)
-                let strike_box = self.strike_box_engine.read().await;
-                let direction = StrikeBoxDirection::Long; // Default to long for volume spikes
-                let validation = strike_box.validate_entry(&token_snapshot, direction);
+                // Pick a side from the spike's flow and price action; only on-chain pairs have wallet flow
+                let (accumulation_score, distribution_score) = match &dex_address {
+                    Some(address) => self.fetch_wallet_flow(address).await,
+                    None => (0.0, 0.0),
+                };
+                let decision = DirectionDecision::classify(DirectionSignals {
+                    volume_ratio,
+                    price_change_pct: self.fetch_price_change_pct(&pair).await,
+                    accumulation_score,
+                    distribution_score,
+                });
                 
-                if !validation.all_passed {
-                    // Log rejection
-                    drop(strike_box);
-                    let mut engine = self.strike_box_engine.write().await;
-                    engine.log_rejection(&token_snapshot, direction, &validation);
+                // Validate with Strike Box (comprehensive institutional validation); audits every
+                // validation and logs rejections
+                let (decision, validation) = validate_direction(
+                    &mut *self.strike_box_engine.write().await,
+                    &token_snapshot,
+                    decision,
+                );
+                let Some(side) = decision.side().filter(|_| validation.is_some()) else {
+                    debug!("{} skipped: {}", pair, decision.rationale);
                     continue;
-                }
+                };
+                let direction = StrikeBoxDirection::from(side);
+                let strike_box = self.strike_box_engine.read().await;
                 
                 // Also check with rug pull detector for additional safety
-                let detector = self.rug_pull_detector.read().await;
+                let mut detector = self.rug_pull_detector.write().await;
                 match detector.validate_token(&token_address, &pair).await {
                     Ok(safety_score) => {
                         // Only proceed if Safe or Moderate risk
@@ -244,21 +643,25 @@ impl HummingbotArray {
                         
                         // Calculate Strike Box position size
                         let strike_box_size = strike_box.calculate_position_size(&token_snapshot, direction);
-                        let strike_box_size_f64 = strike_box_size.to_string().parse::<f64>().unwrap_or(0.0);
+                        let strike_box_size_f64 = conv::usd_to_f64(strike_box_size);
                         
                         // Calculate expected move
                         let expected_move = self.calculate_expected_move(&pair, volume_ratio).await;
                         
                         // Get Strike Box stop loss and take profit prices
-                        let stop_loss_config = &strike_box.config.stop_loss;
-                        let take_profit_config = &strike_box.config.take_profit;
-                        let safety_score_decimal = Decimal::from((safety_score.overall_score * 100.0) as i64) / Decimal::from(100);
-                        
-                        let stop_loss_price = stop_loss_config.long_stop_price(
-                            Decimal::from(entry_price as i64),
-                            safety_score_decimal
-                        );
-                        let tp_prices = take_profit_config.long_tp_prices(Decimal::from(entry_price as i64));
+                        let levels = match strike_box_levels(
+                            side,
+                            &strike_box.config.stop_loss,
+                            &strike_box.config.take_profit,
+                            entry_price,
+                            safety_score.overall_score,
+                        ) {
+                            Ok(levels) => levels,
+                            Err(e) => {
+                                warn!("⚠️  Skipping {} - {}", pair, e);
+                                continue;
+                            }
+                        };
                         
                         // Create opportunity with Strike Box integration
                         let opportunity = MarketOpportunity {
@@ -270,17 +673,16 @@ impl HummingbotArray {
                             volatility: self.get_volatility(&pair).await,
                             volume_ratio,
                             entry_price,
-                            target_price: tp_prices[0].to_string().parse::<f64>().unwrap_or(entry_price * 1.15), // TP1
-                            stop_loss: stop_loss_price.to_string().parse::<f64>().unwrap_or(entry_price * 0.95),
+                            target_price: levels.take_profits[0], // TP1
+                            stop_loss: levels.stop_loss,
                             leverage: self.calculate_volume_based_leverage(volume_ratio, safety_score.overall_score),
                             safety_score: safety_score.overall_score,
                             token_address: token_address.clone(),
                             strike_box_size: strike_box_size_f64,
-                            strike_box_tp_prices: [
-                                tp_prices[0].to_string().parse::<f64>().unwrap_or(entry_price * 1.15),
-                                tp_prices[1].to_string().parse::<f64>().unwrap_or(entry_price * 1.30),
-                                tp_prices[2].to_string().parse::<f64>().unwrap_or(entry_price * 1.50),
-                            ],
+                            liquidity_usd,
+                            strike_box_tp_prices: levels.take_profits,
+                            direction: decision,
+                            created_at: Utc::now(),
                         };
                         
                         // Higher threshold for non-traditional assets
@@ -311,22 +713,27 @@ impl HummingbotArray {
         // Filter for volume spikes (2x+ normal volume)
         // For now, simulate non-traditional pairs with volume spikes
         
-        use rand::Rng;
         let mut pairs = Vec::new();
         
         // Example non-traditional pairs (replace with real API calls)
-        let potential_pairs = vec![
+        let mut potential_pairs: Vec<String> = [
             "PEPE/USDT", "BONK/USDT", "WIF/USDT", "FLOKI/USDT",
             "SHIB/USDT", "DOGE/USDT", "MEME/USDT", "MOON/USDT",
             "PUMP/USDT", "ROCKET/USDT", "0x1234/USDC", "0x5678/USDT"
-        ];
+        ].iter().map(|pair| pair.to_string()).collect();
+        // Plus whatever the registry lists on this venue, so pairs with known contracts get scanned
+        for pair in self.symbols.read().unwrap_or_else(|e| e.into_inner()).pairs_on(exchange) {
+            if !potential_pairs.contains(&pair) {
+                potential_pairs.push(pair);
+            }
+        }
         
         for pair in potential_pairs {
             // Simulate volume spike detection
-            let volume_ratio = 1.5 + rand::thread_rng().gen::<f64>() * 2.5; // 1.5-4x
+            let volume_ratio = 1.5 + self.rng.gen_f64() * 2.5; // 1.5-4x
             
             if volume_ratio >= MIN_VOLUME_RATIO {
-                pairs.push(pair.to_string());
+                pairs.push(pair);
             }
         }
         
@@ -336,14 +743,22 @@ impl HummingbotArray {
     async fn fetch_volume_ratio(&self, pair: &str) -> f64 {
         // In production: Fetch from exchange API
         // Compare current volume to 24h average
-        use rand::Rng;
-        1.5 + rand::thread_rng().gen::<f64>() * 2.5 // Simulated 1.5-4x
+        1.5 + self.rng.gen_f64() * 2.5 // Simulated 1.5-4x
     }
     
     async fn fetch_price(&self, pair: &str) -> f64 {
         // In production: Fetch real-time price
-        use rand::Rng;
-        0.001 + rand::thread_rng().gen::<f64>() * 10.0 // Simulated
+        0.001 + self.rng.gen_f64() * 10.0 // Simulated
+    }
+    
+    async fn fetch_price_change_pct(&self, _pair: &str) -> f64 {
+        // In production: Price change over the volume spike window
+        -0.10 + self.rng.gen_f64() * 0.20 // Simulated ±10%
+    }
+    
+    async fn fetch_wallet_flow(&self, _token_address: &str) -> (f64, f64) {
+        // In production: WalletSignal accumulation and distribution scores from the AMM wallet tracker
+        (self.rng.gen_f64() * 0.6, self.rng.gen_f64() * 0.6) // Simulated
     }
     
     async fn calculate_expected_move(&self, pair: &str, volume_ratio: f64) -> f64 {
@@ -353,43 +768,73 @@ impl HummingbotArray {
         base_move * volume_multiplier.min(1.5) // Cap at 12%
     }
     
-    fn extract_token_address(&self, pair: &str) -> String {
-        // Extract token address from pair (for DEX pairs like "0x1234/USDC")
-        if pair.starts_with("0x") {
-            pair.split('/').next().unwrap_or("0x0000").to_string()
-        } else {
-            // For CEX pairs, use pair name as identifier
-            format!("token_{}", pair.replace("/", "_"))
+    fn resolve_token_address(&self, venue: &str, pair: &str) -> Option<String> {
+        let symbols = self.symbols.read().unwrap_or_else(|e| e.into_inner());
+        match symbols.token_address(venue, pair) {
+            Ok(address) => address,
+            Err(e) => {
+                debug!("{} on {}: {}, validating as CEX", pair, venue, e);
+                None
+            }
         }
     }
     
     async fn get_volatility(&self, _pair: &str) -> f64 {
         // Fetch volatility for pair
-        use rand::Rng;
-        0.02 + rand::thread_rng().gen::<f64>() * 0.03 // 2-5% volatility
+        0.02 + self.rng.gen_f64() * 0.03 // 2-5% volatility
     }
     
     async fn fetch_liquidity_usd(&self, _pair: &str) -> f64 {
         // Fetch liquidity in USD
-        use rand::Rng;
-        500_000.0 + rand::thread_rng().gen::<f64>() * 500_000.0 // $500K-$1M range
+        500_000.0 + self.rng.gen_f64() * 500_000.0 // $500K-$1M range
+    }
+    
+    async fn fetch_order_book(&self, _pair: &str, mid_price: f64, liquidity_usd: f64) -> OrderBook {
+        // In production: Fetch the venue's order book
+        // For now: Simulate 5 levels a side, 0.1% apart, splitting liquidity evenly
+        let level_usd = liquidity_usd / 10.0;
+        let level = |offset: f64| {
+            let price = conv::price_from_f64(mid_price * (1.0 + offset)).ok()?;
+            let size = conv::decimal_from_f64(level_usd / (mid_price * (1.0 + offset))).ok()?;
+            Some(BookLevel { price, size })
+        };
+        OrderBook {
+            bids: (1..=5).filter_map(|i| level(-0.001 * i as f64)).collect(),
+            asks: (1..=5).filter_map(|i| level(0.001 * i as f64)).collect(),
+        }
+    }
+    
+    /// Apply CoinGecko metadata to `snapshot`; true when it supplied a listing age
+    async fn enrich_snapshot(&self, snapshot: &mut TokenSnapshot) -> bool {
+        let Some(enricher) = &self.token_enricher else {
+            return false;
+        };
+        match enricher.enrich(snapshot).await {
+            Ok(metadata) => metadata.listed_at.is_some(),
+            Err(e) if TokenEnricher::is_not_found(e.as_ref()) => {
+                debug!(token = %snapshot.token_address, "not listed on CoinGecko, using on-chain age");
+                false
+            }
+            Err(e) => {
+                warn!("⚠️  CoinGecko enrichment failed for {}: {}", snapshot.token_address, e);
+                false
+            }
+        }
     }
     
     async fn fetch_holder_count(&self, _token_address: &str) -> u32 {
         // Fetch holder count
-        use rand::Rng;
-        25 + (rand::thread_rng().gen::<f64>() * 75.0) as u32 // 25-100 holders
+        25 + (self.rng.gen_f64() * 75.0) as u32 // 25-100 holders
     }
     
     async fn fetch_token_age_hours(&self, _token_address: &str) -> u32 {
         // Fetch token age in hours
-        use rand::Rng;
-        24 + (rand::thread_rng().gen::<f64>() * 48.0) as u32 // 24-72 hours
+        24 + (self.rng.gen_f64() * 48.0) as u32 // 24-72 hours
     }
 
     fn detect_opportunity_type(&self, _exchange: &str, pair: &str) -> OpportunityType {
         // Advanced opportunity detection
-        match rand::random::<f64>() {
+        match self.rng.gen_f64() {
             x if x < 0.2 => OpportunityType::Arbitrage,
             x if x < 0.4 => OpportunityType::MomentumBreakout,
             x if x < 0.6 => OpportunityType::MeanReversion,
@@ -400,24 +845,20 @@ impl HummingbotArray {
 
     fn calculate_expected_profit(&self, _pair: &str) -> f64 {
         // Target 8% per strike
-        0.08 + rand::random::<f64>() * 0.04 // 8-12% range
+        0.08 + self.rng.gen_f64() * 0.04 // 8-12% range
     }
 
     fn calculate_confidence(&self, _pair: &str) -> f64 {
-        0.6 + rand::random::<f64>() * 0.35 // 60-95% confidence
-    }
-
-    fn get_volatility(&self, _pair: &str) -> f64 {
-        0.02 + rand::random::<f64>() * 0.03 // 2-5% volatility
+        0.6 + self.rng.gen_f64() * 0.35 // 60-95% confidence
     }
 
     fn get_volume_ratio(&self, _pair: &str) -> f64 {
-        1.0 + rand::random::<f64>() * 2.0 // 1-3x normal volume
+        1.0 + self.rng.gen_f64() * 2.0 // 1-3x normal volume
     }
 
     fn calculate_volume_based_leverage(&self, volume_ratio: f64, safety_score: f64) -> f64 {
         // Scale leverage with volume spike strength AND safety score
-        let mut base_leverage = 3.0;
+        let mut base_leverage: f64 = 3.0;
         
         // Increase leverage for strong volume spikes
         if volume_ratio >= 3.0 {
@@ -445,30 +886,38 @@ impl HummingbotArray {
         self.calculate_volume_based_leverage(2.0, 0.8)
     }
 
+    #[tracing::instrument(name = "cycle_aggregation", skip_all, fields(strikes = results.len()))]
     async fn aggregate_cycle_results(&mut self, results: Vec<StrikeResult>) {
         let mut aggregator = self.performance_aggregator.write().await;
         
         for result in results {
-            aggregator.add_result(result);
+            self.strike_coordinator.record_result(&result).await;
             self.cycle_profits += result.profit;
+            aggregator.add_result(result);
         }
         
         // Update total capital
         self.total_capital += self.cycle_profits;
+        debug!(cycle_profits = self.cycle_profits, total_capital = self.total_capital, "cycle aggregated");
     }
 
     async fn rebalance_capital(&mut self) {
         let mut pool = self.capital_pool.write().await;
         
-        // Redistribute profits to all bots
-        let profit_per_bot = self.cycle_profits / NUM_BOTS as f64;
+        // Redistribute profits to all healthy bots
+        let profit_per_bot = self.cycle_profits / self.healthy_bot_count().max(1) as f64;
         
-        for bot in &self.bots {
+        for (bot_id, bot) in self.bots.iter().enumerate() {
+            if self.bot_health[bot_id].status == BotStatus::Quarantined {
+                continue;
+            }
             let mut bot_guard = bot.lock().await;
             bot_guard.add_capital(profit_per_bot);
         }
         
         pool.rebalance();
+        
+        self.risk_guard.write().await.set_total_capital(self.total_capital);
     }
 
     fn is_cycle_complete(&self) -> bool {
@@ -476,34 +925,134 @@ impl HummingbotArray {
         elapsed.num_hours() >= 24 // Check daily, full cycle is 14 days
     }
 
-    async fn print_cycle_report(&self) {
+    /// Everything the cycle report shows, for the cycle running since `cycle_start`
+    async fn build_cycle_record(&self) -> CycleRecord {
         let aggregator = self.performance_aggregator.read().await;
         let stats = aggregator.get_stats();
+        let exposure = self.risk_guard.read().await.snapshot();
+        CycleRecord {
+            cycle_start: self.cycle_start,
+            cycle_end: Utc::now(),
+            starting_capital: self.cycle_start_capital,
+            ending_capital: self.total_capital,
+            cycle_profits: self.cycle_profits,
+            total_strikes: stats.total_strikes,
+            successful_strikes: stats.successful_strikes,
+            win_rate: stats.win_rate,
+            avg_leverage: stats.avg_leverage,
+            max_leverage: stats.max_leverage,
+            risk_utilization: stats.risk_utilization,
+            cap_source_counts: [SizeCapSource::BotCapital, SizeCapSource::StrikeBox, SizeCapSource::PoolPct]
+                .into_iter()
+                .map(|source| (source.name().to_string(), stats.cap_source_counts.get(&source).copied().unwrap_or(0)))
+                .collect(),
+            open_notional: exposure.open_notional,
+            tokens_held: exposure.tokens.len(),
+            largest_token: exposure.tokens.first().map(|t| (t.token_address.clone(), t.pct_of_capital, t.bots)),
+            largest_group: exposure.groups.first().map(|g| (g.group.clone(), g.pct_of_capital)),
+            entries_blocked: exposure.blocked_entries,
+            healthy_bots: self.healthy_bot_count(),
+            restarts: self.restart_log.iter().filter(|r| r.action == BotStatus::Restarted).count(),
+            per_bot: aggregator.bot_breakdown(),
+            per_strategy: aggregator.strategy_breakdown(),
+            direction_counts: self.cycle_direction_counts.clone(),
+            direction_calls: self.cycle_direction_calls.iter().cloned().collect(),
+        }
+    }
+
+    /// Record, persist and report the finished cycle, then start the next one
+    async fn complete_cycle(&mut self) {
+        let record = self.build_cycle_record().await;
+        if let Some(store) = &self.cycle_store {
+            if let Err(e) = store.append(&record) {
+                warn!("⚠️  Cycle record not persisted: {}", e);
+            }
+        }
+        self.cycle_history.push(record);
+        let limit = self.strike_box_engine.read().await.config.log_retention.max_cycle_records;
+        retain_recent_cycles(&mut self.cycle_history, limit);
+        self.print_cycle_report().await;
+        self.reset_cycle().await;
+    }
+
+    async fn print_cycle_report(&self) {
+        let Some(record) = self.cycle_history.last() else {
+            return;
+        };
+        info!(
+            total_strikes = record.total_strikes,
+            successful_strikes = record.successful_strikes,
+            win_rate = record.win_rate,
+            total_capital = record.ending_capital,
+            cycle_profits = record.cycle_profits,
+            healthy_bots = record.healthy_bots,
+            projected_7_day = self.project_7_day_return(),
+            "cycle report"
+        );
+        if !telemetry::pretty_banners() {
+            return;
+        }
         
         println!("\n╔═══════════════════════════════════════════════════════════════╗");
         println!("║              HUMMINGBOT ARRAY PERFORMANCE REPORT              ║");
         println!("╠═══════════════════════════════════════════════════════════════╣");
         println!("║ CYCLE PERFORMANCE                                             ║");
         println!("║   Duration:            {} days                                ║", 
-            (Utc::now() - self.cycle_start).num_days());
+            (record.cycle_end - record.cycle_start).num_days());
         println!("║   Active Bots:         {}/25                                  ║", NUM_BOTS);
-        println!("║   Total Strikes:       {:>6}                                 ║", stats.total_strikes);
-        println!("║   Successful:          {:>6}                                 ║", stats.successful_strikes);
-        println!("║   Win Rate:            {:.1}%                                 ║", stats.win_rate * 100.0);
+        println!("║   Total Strikes:       {:>6}                                 ║", record.total_strikes);
+        println!("║   Successful:          {:>6}                                 ║", record.successful_strikes);
+        println!("║   Win Rate:            {:.1}%                                 ║", record.win_rate * 100.0);
         println!("║                                                               ║");
         println!("║ FINANCIAL METRICS                                             ║");
         println!("║   Starting Capital:    ${:>12.2}                         ║", INITIAL_CAPITAL);
-        println!("║   Current Capital:     ${:>12.2}                         ║", self.total_capital);
-        println!("║   Cycle Profit:        ${:>12.2}                         ║", self.cycle_profits);
+        println!("║   Current Capital:     ${:>12.2}                         ║", record.ending_capital);
+        println!("║   Cycle Profit:        ${:>12.2}                         ║", record.cycle_profits);
         println!("║   Total Return:        {:.1}%                                 ║", 
-            (self.cycle_profits / INITIAL_CAPITAL) * 100.0);
+            (record.cycle_profits / INITIAL_CAPITAL) * 100.0);
         println!("║   Per Bot Average:     {:.1}%                                 ║", 
-            (self.cycle_profits / INITIAL_CAPITAL) * 100.0 / NUM_BOTS as f64);
+            (record.cycle_profits / INITIAL_CAPITAL) * 100.0 / NUM_BOTS as f64);
         println!("║                                                               ║");
         println!("║ LEVERAGE METRICS                                              ║");
-        println!("║   Average Leverage:    {:.1}x                                 ║", stats.avg_leverage);
-        println!("║   Max Leverage Used:   {:.1}x                                 ║", stats.max_leverage);
-        println!("║   Risk Utilization:    {:.1}%                                 ║", stats.risk_utilization * 100.0);
+        println!("║   Average Leverage:    {:.1}x                                 ║", record.avg_leverage);
+        println!("║   Max Leverage Used:   {:.1}x                                 ║", record.max_leverage);
+        println!("║   Risk Utilization:    {:.1}%                                 ║", record.risk_utilization * 100.0);
+        println!("║                                                               ║");
+        println!("║ SIZING CAPS                                                   ║");
+        for (source, count) in &record.cap_source_counts {
+            println!("║   {:<20} {:>6}                                 ║", format!("{}:", source), count);
+        }
+        println!("║                                                               ║");
+        println!("║ ARRAY EXPOSURE                                                ║");
+        println!("║   Open Notional:       ${:>12.2}                         ║", record.open_notional);
+        println!("║   Tokens Held:         {:>6}                                 ║", record.tokens_held);
+        if let Some((token_address, pct_of_capital, bots)) = &record.largest_token {
+            println!("║   Largest Token:       {:<10} {:.1}% ({} bots)             ║",
+                token_address.chars().take(10).collect::<String>(), pct_of_capital * 100.0, bots);
+        }
+        if let Some((group, pct_of_capital)) = &record.largest_group {
+            println!("║   Largest Group:       {:<10} {:.1}%                       ║",
+                group.chars().take(10).collect::<String>(), pct_of_capital * 100.0);
+        }
+        println!("║   Entries Blocked:     {:>6}                                 ║", record.entries_blocked);
+        println!("║                                                               ║");
+        println!("║ DIRECTION CALLS                                               ║");
+        for (call, count) in &record.direction_counts {
+            println!("║   {:<20} {:>6}                                 ║", format!("{}:", call), count);
+        }
+        for call in &record.direction_calls {
+            println!("║   {:<12} {:?} ({:.2}) {}", call.pair, call.call, call.confidence, call.rationale);
+        }
+        println!("║                                                               ║");
+        println!("║ STRATEGIES                                                    ║");
+        for (strategy, breakdown) in &record.per_strategy {
+            println!("║   {:<16} {:>5} strikes {:>5.1}% won ${:>12.2}     ║",
+                strategy, breakdown.strikes, breakdown.win_rate() * 100.0, breakdown.net_profit);
+        }
+        println!("║                                                               ║");
+        println!("║ BOT HEALTH                                                    ║");
+        println!("║   Healthy Bots:        {:>6}/{}                              ║", record.healthy_bots, NUM_BOTS);
+        println!("║   Restarts:            {:>6}                                 ║", record.restarts);
         println!("║                                                               ║");
         println!("║ 7-DAY PROJECTION                                              ║");
         println!("║   Target (200%):       ${:>12.2}                         ║", INITIAL_CAPITAL * 2.0);
@@ -512,11 +1061,22 @@ impl HummingbotArray {
         println!("║   On Track:            {}                                     ║",
             if self.is_on_track() { "✅ YES" } else { "⚠️  ADJUST" });
         println!("╚═══════════════════════════════════════════════════════════════╝");
+        if self.cycle_history.len() > 1 {
+            println!("{}", self.cycle_comparison(CYCLE_COMPARISON_CYCLES));
+        }
+    }
+
+    /// Daily profit the projections extrapolate: the median daily return of the last
+    /// PROJECTION_HISTORY_CYCLES completed cycles when there are any, else the current cycle's pace
+    fn projected_daily_profit(&self) -> f64 {
+        match median_daily_return(&self.cycle_history, PROJECTION_HISTORY_CYCLES) {
+            Some(daily_return) => daily_return * INITIAL_CAPITAL,
+            None => self.cycle_profits / (Utc::now() - self.cycle_start).num_days().max(1) as f64,
+        }
     }
 
     fn project_7_day_return(&self) -> f64 {
-        let daily_return = self.cycle_profits / (Utc::now() - self.cycle_start).num_days().max(1) as f64;
-        INITIAL_CAPITAL + (daily_return * 7.0) // Changed from 14.0 to 7.0
+        INITIAL_CAPITAL + (self.projected_daily_profit() * 7.0) // Changed from 14.0 to 7.0
     }
     
     fn project_14_day_return(&self) -> f64 {
@@ -526,8 +1086,7 @@ impl HummingbotArray {
 
     fn is_on_track(&self) -> bool {
         let target_daily = INITIAL_CAPITAL * 2.0 / 7.0; // Changed from 14.0 to 7.0
-        let actual_daily = self.cycle_profits / (Utc::now() - self.cycle_start).num_days().max(1) as f64;
-        actual_daily >= target_daily * 0.9 // Within 10% of target
+        self.projected_daily_profit() >= target_daily * 0.9 // Within 10% of target
     }
     
     async fn check_and_close_positions(&mut self) {
@@ -536,77 +1095,42 @@ impl HummingbotArray {
             let mut bot_guard = bot.lock().await;
             let mut positions_to_close = Vec::new();
             
-            for position in &bot_guard.positions {
-                if position.status == PositionStatus::Open {
-                    let position_age = Utc::now() - position.opened_at;
-                    
-                    // Force close if position is older than 1 minute
-                    if position_age.num_seconds() >= MAX_POSITION_TIME_SECONDS {
-                        positions_to_close.push((position.id.clone(), ExitReason::TimeLimit));
-                        continue;
-                    }
-                    
-                    let current_price = bot_guard.fetch_current_price(&position.pair).await;
-                    
-                    // Check if target hit
-                    let target_hit = if matches!(position.side, Side::Long) {
-                        current_price >= position.target_price
-                    } else {
-                        current_price <= position.target_price
-                    };
-                    
-                    // Check if stop loss hit
-                    let stop_hit = if matches!(position.side, Side::Long) {
-                        current_price <= position.stop_loss
-                    } else {
-                        current_price >= position.stop_loss
-                    };
-                    
-                    if target_hit {
-                        positions_to_close.push((position.id.clone(), ExitReason::TargetHit));
-                    } else if stop_hit {
-                        positions_to_close.push((position.id.clone(), ExitReason::StopLoss));
-                    }
+            for index in 0..bot_guard.positions.len() {
+                if !matches!(bot_guard.positions[index].status, PositionStatus::Open) {
+                    continue;
+                }
+                let current_price = bot_guard.fetch_current_price(&bot_guard.positions[index].pair).await;
+                let bot = &mut *bot_guard;
+                let position = &mut bot.positions[index];
+                if let Some((exit_price, exit_reason)) = position.update(current_price, &bot.strike_box_config) {
+                    positions_to_close.push((position.id.clone(), exit_price, exit_reason));
                 }
             }
             
             // Close positions immediately
-            for (pos_id, exit_reason) in positions_to_close {
-                if let Some(position) = bot_guard.positions.iter_mut()
-                    .find(|p| p.id == pos_id && p.status == PositionStatus::Open) {
-                    
-                    let current_price = bot_guard.fetch_current_price(&position.pair).await;
-                    let exit_price = match exit_reason {
-                        ExitReason::TargetHit => position.target_price,
-                        ExitReason::StopLoss => position.stop_loss,
-                        _ => current_price,
-                    };
-                    
-                    // Execute exit immediately
-                    let exit_result = bot_guard.execute_exit_trade(position, exit_price).await;
-                    
-                    // Calculate profit
-                    let price_change = if matches!(position.side, Side::Long) {
-                        (exit_price - position.entry_price) / position.entry_price
-                    } else {
-                        (position.entry_price - exit_price) / position.entry_price
-                    };
-                    
-                    let profit = position.leveraged_size * price_change;
-                    
-                    // Update position
-                    position.status = PositionStatus::Closed;
-                    position.exit_price = Some(exit_price);
-                    position.exit_reason = Some(exit_reason.clone());
-                    position.closed_at = Some(Utc::now());
-                    
-                    // Update capital
-                    bot_guard.capital += profit;
-                    bot_guard.performance.add_trade(profit > 0.0, profit);
-                    
-                    info!("✅ Bot {} closed position {}: {} | Profit: ${:.2}", 
-                        bot_guard.id, pos_id, exit_reason.name(), profit);
-                }
+            for (pos_id, exit_price, exit_reason) in positions_to_close {
+                let Some(index) = bot_guard.positions.iter()
+                    .position(|p| p.id == pos_id && matches!(p.status, PositionStatus::Open)) else {
+                    continue;
+                };
+                
+                // Execute exit immediately
+                let _exit_result = bot_guard.execute_exit_trade(&bot_guard.positions[index], exit_price).await;
+                let bot = &mut *bot_guard;
+                let position = &mut bot.positions[index];
+                let profit = position.close(exit_price, exit_reason.clone(), &bot.strike_box_config.fees);
+                let token_address = position.token_address.clone();
+                let leveraged_size = position.leveraged_size;
+                
+                // Update capital
+                let capital = bot_guard.capital;
+                bot_guard.performance.add_trade(profit > 0.0, profit, capital);
+                bot_guard.capital += profit;
+                self.risk_guard.write().await.record_close(
+                    bot_guard.id, &token_address, leveraged_size);
+                
+                info!("✅ Bot {} closed position {}: {} | Profit: ${:.2}", 
+                    bot_guard.id, pos_id, exit_reason.name(), profit);
             }
             
             // Remove closed positions
@@ -614,9 +1138,38 @@ impl HummingbotArray {
         }
     }
 
-    fn reset_cycle(&mut self) {
+    async fn reset_cycle(&mut self) {
         self.cycle_start = Utc::now();
+        self.cycle_start_capital = self.total_capital;
         self.cycle_profits = 0.0;
+        self.cycle_direction_counts.clear();
+        self.cycle_direction_calls.clear();
+        self.performance_aggregator.write().await.start_cycle();
+    }
+
+    /// Apply Strike Box config edits from `path` to the running engine without a restart
+    #[cfg(feature = "hot-reload")]
+    pub fn watch_strike_box_config(&self, path: &str) -> Result<strike_box::ConfigWatcher, String> {
+        let engine = self.strike_box_engine.clone();
+        strike_box::watch_config_file(path, move |update| match update {
+            Ok(config) => {
+                // Called on the watcher's own thread, outside the async runtime
+                let report = engine.blocking_write().apply_config_update(config, false);
+                if !report.applied {
+                    warn!("⚠️  Strike Box config rejected: {}", report.errors.join("; "));
+                    return;
+                }
+                info!("🔧 Strike Box config reloaded: {} field(s) changed", report.changes.len());
+                for change in &report.changes {
+                    info!("   {} : {} -> {}", change.path, change.old_value, change.new_value);
+                }
+                for warning in &report.warnings {
+                    warn!("   ⚠️  {}", warning);
+                }
+            }
+            Err(e) => warn!("⚠️  Strike Box config unreadable: {}", e),
+        })
+        .map_err(|e| e.to_string())
     }
 }
 
@@ -631,6 +1184,14 @@ pub struct HummingBot {
     performance: BotPerformance,
     capital_pool: Arc<RwLock<CapitalPool>>,
     strike_coordinator: Arc<StrikeCoordinator>,
+    sizing_config: BotSizingConfig,
+    risk_guard: Option<Arc<RwLock<ArrayRiskGuard>>>, // Array-wide exposure tracking
+    strike_box_config: StrikeBoxConfig, // Trigger path assumption and trailing stop distance
+    rng: SimRng,
+    #[cfg(feature = "trade_simulator")]
+    paper_trading: bool, // Fill orders in the journal instead of on an exchange
+    #[cfg(feature = "trade_simulator")]
+    paper_journal: Arc<std::sync::Mutex<PaperTradeJournal>>,
 }
 
 impl HummingBot {
@@ -649,77 +1210,239 @@ impl HummingBot {
             performance: BotPerformance::new(),
             capital_pool,
             strike_coordinator,
+            sizing_config: BotSizingConfig::default(),
+            risk_guard: None,
+            strike_box_config: StrikeBoxConfig::default(),
+            rng: SimRng::from_entropy(),
+            #[cfg(feature = "trade_simulator")]
+            paper_trading: false,
+            #[cfg(feature = "trade_simulator")]
+            paper_journal: Arc::new(std::sync::Mutex::new(PaperTradeJournal::new(SlippageModel::default()))),
+        }
+    }
+
+    /// Paper-trade: entries fill at the requested price, exits with slippage drawn from `slippage_model`
+    #[cfg(feature = "trade_simulator")]
+    pub fn with_paper_trading(mut self, slippage_model: SlippageModel) -> Self {
+        self.paper_trading = true;
+        self.paper_journal = Arc::new(std::sync::Mutex::new(PaperTradeJournal::new(slippage_model)));
+        self
+    }
+
+    /// Simulated fills and P&L so far, for review before going live
+    #[cfg(feature = "trade_simulator")]
+    pub fn paper_trading_report(&self) -> PaperTradingReport {
+        self.paper_journal.lock().unwrap_or_else(|e| e.into_inner()).report()
+    }
+
+    pub fn with_strike_box_config(mut self, strike_box_config: StrikeBoxConfig) -> Self {
+        self.strike_box_config = strike_box_config;
+        self
+    }
+
+    pub fn with_rng(mut self, rng: SimRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    pub fn with_risk_guard(mut self, risk_guard: Arc<RwLock<ArrayRiskGuard>>) -> Self {
+        self.risk_guard = Some(risk_guard);
+        self
+    }
+
+    pub fn with_sizing_config(mut self, sizing_config: BotSizingConfig) -> Self {
+        self.sizing_config = sizing_config;
+        self
+    }
+
+    /// Resolve position size against bot capital, the Strike Box size and the pool-percentage cap
+    pub fn size_for_opportunity(&self, opportunity: &MarketOpportunity) -> PositionSizing {
+        // Kelly-based base size from bot capital
+        let template = self.build_position(opportunity, self.capital, self.capital * opportunity.leverage);
+        let safety_score = if self.sizing_config.safety_score_scaling {
+            opportunity.safety_score
+        } else {
+            1.0
+        };
+        let volatility = if self.sizing_config.volatility_scaling {
+            opportunity.volatility
+        } else {
+            0.0
+        };
+        let mut notional = template.risk_adjusted_size(
+            safety_score,
+            volatility,
+            self.sizing_config.max_kelly_fraction,
+        );
+        let mut cap_source = SizeCapSource::BotCapital;
+        
+        // Strike Box size is a hard ceiling on notional (pre-leverage)
+        if opportunity.strike_box_size > 0.0 && opportunity.strike_box_size < notional {
+            notional = opportunity.strike_box_size;
+            cap_source = SizeCapSource::StrikeBox;
+        }
+        
+        let mut leveraged_size = notional * opportunity.leverage;
+        
+        // Consistency check: leveraged order must stay within the side's pool percentage limit
+        let pool_limit = LiquidityScaler::max_order_vs_pool(
+            conv::usd_from_f64(opportunity.liquidity_usd).unwrap_or(Decimal::ZERO),
+            opportunity.side().into(),
+        );
+        let pool_limit_f64 = conv::usd_to_f64(pool_limit);
+        let pool_violation = leveraged_size > pool_limit_f64;
+        
+        if pool_violation {
+            warn!("⚠️  Bot {} leveraged size ${:.2} exceeds pool limit ${:.2} on {} - clamping",
+                self.id, leveraged_size, pool_limit_f64, opportunity.pair);
+            leveraged_size = pool_limit_f64;
+            notional = pool_limit_f64 / opportunity.leverage.max(1.0);
+            cap_source = SizeCapSource::PoolPct;
+        }
+        
+        PositionSizing {
+            notional,
+            leveraged_size,
+            cap_source,
+            pool_violation,
+        }
+    }
+
+    /// Leveraged notional of the bot's open positions
+    pub fn total_open_exposure(&self) -> f64 {
+        self.positions.iter().map(|p| p.leveraged_size).sum()
+    }
+
+    /// Whether the bot can take on another `new_size` of leveraged notional on `pair`
+    pub fn position_limit_check(&self, pair: &str, new_size: f64) -> Result<(), PositionLimitError> {
+        if self.positions.len() >= self.sizing_config.max_concurrent_positions {
+            return Err(PositionLimitError::MaxPositionsReached);
         }
+        let current = self.total_open_exposure() + new_size;
+        let limit = self.capital * self.sizing_config.max_exposure_ratio;
+        if current > limit {
+            return Err(PositionLimitError::MaxExposureReached { current, limit });
+        }
+        if self.positions.iter().any(|p| p.pair == pair) {
+            return Err(PositionLimitError::DuplicatePosition { pair: pair.to_string() });
+        }
+        Ok(())
+    }
+
+    fn build_position(&self, opportunity: &MarketOpportunity, size: f64, leveraged_size: f64) -> BotPosition {
+        BotPosition::open(self.id, opportunity, opportunity.side(), size, leveraged_size)
     }
 
+    /// Runs one strike inside a `strike` span; `execution_id` is filled in once the position
+    /// is built, so every event from sizing onwards carries it
     pub async fn execute_strike(&mut self, opportunity: MarketOpportunity) -> StrikeResult {
+        let span = info_span!(
+            "strike",
+            bot_id = self.id,
+            strategy = self.strategy.name(),
+            token = %opportunity.pair,
+            direction = ?opportunity.side(),
+            execution_id = tracing::field::Empty,
+        );
+        self.run_strike(opportunity).instrument(span).await
+    }
+    
+    async fn run_strike(&mut self, opportunity: MarketOpportunity) -> StrikeResult {
         info!("🤖 Bot {} executing {} strike on {} {}", 
             self.id, self.strategy.name(), opportunity.exchange, opportunity.pair);
         info!("   Volume Ratio: {:.2}x | Leverage: {:.1}x | Safety: {:.1}%", 
             opportunity.volume_ratio, opportunity.leverage, opportunity.safety_score * 100.0);
         
-        // Calculate position size (use most of capital with leverage)
-        let position_size = self.capital * 0.95; // Use 95% of capital
-        let leveraged_size = position_size * opportunity.leverage;
+        // Calculate position size (bounded by Strike Box and pool limits)
+        let sizing = self.size_for_opportunity(&opportunity);
+        let position_size = sizing.notional;
+        let leveraged_size = sizing.leveraged_size;
         
         // Create position
-        let mut position = BotPosition {
-            id: format!("BOT{}_POS_{}", self.id, uuid::Uuid::new_v4()),
-            bot_id: self.id,
-            exchange: opportunity.exchange.clone(),
-            pair: opportunity.pair.clone(),
-            side: if opportunity.opportunity_type == OpportunityType::MomentumBreakout {
-                Side::Long
-            } else {
-                Side::Long
-            },
-            size: position_size,
-            leveraged_size,
-            entry_price: opportunity.entry_price,
-            target_price: opportunity.target_price,
-            stop_loss: opportunity.stop_loss,
-            leverage: opportunity.leverage,
-            opened_at: Utc::now(),
-            status: PositionStatus::Open,
-            exit_price: None,
-            exit_reason: None,
-            closed_at: None,
-        };
-        
-        // Execute entry trade
-        let entry_result = self.execute_entry_trade(&position).await;
+        let mut position = self.build_position(&opportunity, position_size, leveraged_size);
+        tracing::Span::current().record("execution_id", tracing::field::display(position.book.execution_id));
         
-        if !entry_result.success {
+        // Strict mode: skip violating opportunities entirely
+        if sizing.pool_violation && self.sizing_config.strict_sizing {
+            warn!("⛔ Bot {} skipping {} - pool limit violated in strict mode", self.id, opportunity.pair);
             return StrikeResult {
                 bot_id: self.id,
                 opportunity: opportunity.clone(),
                 position,
                 profit: 0.0,
-                execution_time_ms: entry_result.execution_time_ms,
+                execution_time_ms: 0,
                 success: false,
+                entered: false,
+                cap_source: sizing.cap_source,
             };
         }
         
-        self.positions.push(position.clone());
-        
+        // Bot-level limits: position count, leveraged exposure against capital, no stacking
+        if let Err(limit) = self.position_limit_check(&opportunity.pair, leveraged_size) {
+            warn!("⛔ Bot {} skipping {} - {}", self.id, opportunity.pair, limit);
+            return StrikeResult {
+                bot_id: self.id,
+                opportunity: opportunity.clone(),
+                position,
+                profit: 0.0,
+                execution_time_ms: 0,
+                success: false,
+                entered: false,
+                cap_source: sizing.cap_source,
+            };
+        }
+        
+        // Reserve array-level exposure; another bot may have filled the cap since assignment
+        if let Some(risk_guard) = &self.risk_guard {
+            if let Err(breach) = risk_guard.write().await.try_open(self.id, &position.token_address, leveraged_size) {
+                warn!("⛔ Bot {} skipping {} - {}", self.id, opportunity.pair, breach);
+                return StrikeResult {
+                    bot_id: self.id,
+                    opportunity: opportunity.clone(),
+                    position,
+                    profit: 0.0,
+                    execution_time_ms: 0,
+                    success: false,
+                    entered: false,
+                    cap_source: sizing.cap_source,
+                };
+            }
+        }
+        
+        // Execute entry trade
+        let entry_result = self.execute_entry_trade(&position).await;
+        
+        if !entry_result.success {
+            self.release_exposure(&position).await;
+            return StrikeResult {
+                bot_id: self.id,
+                opportunity: opportunity.clone(),
+                position,
+                profit: 0.0,
+                execution_time_ms: entry_result.execution_time_ms,
+                success: false,
+                entered: false,
+                cap_source: sizing.cap_source,
+            };
+        }
+        
+        self.positions.push(position.clone());
+        
         // IMMEDIATE MONITORING - Exit as soon as target hit or 1 minute elapsed
-        let (exit_price, exit_reason, profit) = 
-            self.monitor_and_exit_immediately(&mut position).await;
+        let (exit_price, exit_reason) = self.monitor_and_exit_immediately(&mut position).await;
         
         // Execute exit trade IMMEDIATELY
         let exit_result = self.execute_exit_trade(&position, exit_price).await;
         
-        // Update position status
-        position.exit_price = Some(exit_price);
-        position.exit_reason = Some(exit_reason.clone());
-        position.closed_at = Some(Utc::now());
-        position.status = PositionStatus::Closed;
+        // Realize P&L at the fill, slippage included, through the Strike Box book and close
+        let profit = position.close(exit_result.filled_price, exit_reason.clone(), &self.strike_box_config.fees);
         
         // Remove from open positions
         self.positions.retain(|p| p.id != position.id);
+        self.release_exposure(&position).await;
         
         // Update performance
-        self.performance.add_trade(profit > 0.0, profit);
+        self.performance.add_trade(profit > 0.0, profit, self.capital);
         self.capital += profit;
         
         info!("✅ Bot {} exited position: {} | Profit: ${:.2} | Reason: {}", 
@@ -732,66 +1455,32 @@ impl HummingBot {
             profit,
             execution_time_ms: entry_result.execution_time_ms + exit_result.execution_time_ms,
             success: profit > 0.0,
+            entered: true,
+            cap_source: sizing.cap_source,
         }
     }
     
     /// Monitor position and exit IMMEDIATELY when target hit or 1 minute elapsed
-    async fn monitor_and_exit_immediately(&self, position: &mut BotPosition) -> (f64, ExitReason, f64) {
+    async fn monitor_and_exit_immediately(&self, position: &mut BotPosition) -> (f64, ExitReason) {
         let max_checks = 600; // 1 minute at 100ms intervals
         let check_interval_ms = 100;
         let mut check_count = 0;
         
         loop {
-            // Fetch current price
+            // Fetch current price and revalue through the Strike Box book
             let current_price = self.fetch_current_price(&position.pair).await;
             
-            // Calculate current P&L
-            let price_change = if matches!(position.side, Side::Long) {
-                (current_price - position.entry_price) / position.entry_price
-            } else {
-                (position.entry_price - current_price) / position.entry_price
-            };
-            
-            let current_pnl = position.leveraged_size * price_change;
-            let current_return_pct = price_change.abs();
-            
-            // EXIT CONDITION 1: Target hit → IMMEDIATE EXIT
-            if current_price >= position.target_price && matches!(position.side, Side::Long) {
-                let profit = position.leveraged_size * ((position.target_price - position.entry_price) / position.entry_price);
-                return (position.target_price, ExitReason::TargetHit, profit);
-            }
-            
-            if current_price <= position.target_price && matches!(position.side, Side::Short) {
-                let profit = position.leveraged_size * ((position.entry_price - position.target_price) / position.entry_price);
-                return (position.target_price, ExitReason::TargetHit, profit);
-            }
-            
-            // EXIT CONDITION 2: Stop loss triggered → IMMEDIATE EXIT
-            if current_price <= position.stop_loss && matches!(position.side, Side::Long) {
-                let loss = position.leveraged_size * ((position.stop_loss - position.entry_price) / position.entry_price);
-                return (position.stop_loss, ExitReason::StopLoss, loss);
-            }
-            
-            if current_price >= position.stop_loss && matches!(position.side, Side::Short) {
-                let loss = position.leveraged_size * ((position.entry_price - position.stop_loss) / position.entry_price);
-                return (position.stop_loss, ExitReason::StopLoss, loss);
-            }
-            
-            // EXIT CONDITION 3: Quick profit > 0.5% → IMMEDIATE EXIT (NO HODL)
-            if current_return_pct >= QUICK_PROFIT_THRESHOLD && current_pnl > 0.0 {
-                return (current_price, ExitReason::QuickProfit, current_pnl);
-            }
-            
-            // EXIT CONDITION 4: 1-minute time limit → FORCE EXIT
-            let position_age = Utc::now() - position.opened_at;
-            if position_age.num_seconds() >= MAX_POSITION_TIME_SECONDS {
-                return (current_price, ExitReason::TimeLimit, current_pnl);
+            // EXIT CONDITIONS 1-3: Target, stop loss, 1-minute time limit → IMMEDIATE EXIT
+            // EXIT CONDITION 4: Quick profit > 0.5% after fees → IMMEDIATE EXIT (NO HODL)
+            if let Some(exit) = position.update(current_price, &self.strike_box_config)
+                .or_else(|| position.quick_profit_exit(&self.strike_box_config.fees)) {
+                return exit;
             }
             
             // Safety check - prevent infinite loop
             check_count += 1;
             if check_count >= max_checks {
-                return (current_price, ExitReason::MaxChecks, current_pnl);
+                return (current_price, ExitReason::MaxChecks);
             }
             
             // Wait before next check
@@ -799,6 +1488,7 @@ impl HummingBot {
         }
     }
     
+    #[tracing::instrument(name = "order", skip_all, fields(leg = "entry", pair = %position.pair))]
     async fn execute_entry_trade(&self, position: &BotPosition) -> TradeResult {
         // In production: Execute actual buy/sell order
         // For now: Simulate execution
@@ -807,6 +1497,17 @@ impl HummingBot {
             if matches!(position.side, Side::Long) { "BUY" } else { "SELL" },
             position.pair, position.entry_price);
         
+        #[cfg(feature = "trade_simulator")]
+        if self.paper_trading {
+            let fill = self.paper_journal.lock().unwrap_or_else(|e| e.into_inner()).record_entry(position);
+            return TradeResult {
+                success: true,
+                execution_time_ms: 0,
+                filled_price: fill.filled_price,
+                filled_quantity: fill.quantity,
+            };
+        }
+        
         TradeResult {
             success: true,
             execution_time_ms: 50, // Simulated
@@ -815,6 +1516,7 @@ impl HummingBot {
         }
     }
     
+    #[tracing::instrument(name = "order", skip_all, fields(leg = "exit", pair = %position.pair))]
     async fn execute_exit_trade(&self, position: &BotPosition, exit_price: f64) -> TradeResult {
         // In production: Execute actual sell/buy order
         // For now: Simulate execution
@@ -823,6 +1525,20 @@ impl HummingBot {
             if matches!(position.side, Side::Long) { "SELL" } else { "BUY" },
             position.pair, exit_price);
         
+        #[cfg(feature = "trade_simulator")]
+        if self.paper_trading {
+            let mut journal = self.paper_journal.lock().unwrap_or_else(|e| e.into_inner());
+            let model = journal.slippage_model();
+            let slippage_bps = self.rng.gen_normal(model.mean_bps, model.std_dev_bps);
+            let fill = journal.record_exit(position, exit_price, slippage_bps);
+            return TradeResult {
+                success: true,
+                execution_time_ms: 0,
+                filled_price: fill.filled_price,
+                filled_quantity: fill.quantity,
+            };
+        }
+        
         TradeResult {
             success: true,
             execution_time_ms: 50, // Simulated
@@ -834,10 +1550,9 @@ impl HummingBot {
     async fn fetch_current_price(&self, pair: &str) -> f64 {
         // In production: Fetch real-time price from exchange
         // For now: Simulate price movement with some volatility
-        use rand::Rng;
         let base_price = 0.1;
         let volatility = 0.05; // 5% volatility
-        base_price + (rand::thread_rng().gen::<f64>() - 0.5) * volatility
+        base_price + (self.rng.gen_f64() - 0.5) * volatility
     }
 
     async fn execute_market_making(&self, position: &BotPosition) -> f64 {
@@ -857,7 +1572,10 @@ impl HummingBot {
 
     async fn execute_momentum(&self, position: &BotPosition) -> f64 {
         // Momentum logic - ride the trend
-        let trend_profit = (position.target_price - position.entry_price) / position.entry_price;
+        let trend_profit = match position.side {
+            Side::Long => (position.target_price - position.entry_price) / position.entry_price,
+            Side::Short => (position.entry_price - position.target_price) / position.entry_price,
+        };
         position.leveraged_size * trend_profit * 0.8 // 80% of target achieved
     }
 
@@ -873,268 +1591,1826 @@ impl HummingBot {
         position.leveraged_size * vol_profit
     }
 
+    async fn release_exposure(&self, position: &BotPosition) {
+        if let Some(risk_guard) = &self.risk_guard {
+            risk_guard.write().await.record_close(self.id, &position.token_address, position.leveraged_size);
+        }
+    }
+
     pub fn add_capital(&mut self, amount: f64) {
         self.capital += amount;
     }
+
+    /// Withdraw all capital, leaving the bot unfunded
+    pub fn take_capital(&mut self) -> f64 {
+        std::mem::take(&mut self.capital)
+    }
 }
 
-// ==================== STRIKE COORDINATOR ====================
+// ==================== PAPER TRADING ====================
 
-pub struct StrikeCoordinator {
-    assignment_history: Arc<RwLock<HashMap<usize, Vec<MarketOpportunity>>>>,
-    coordination_matrix: Arc<RwLock<Vec<Vec<f64>>>>,
+/// Exit slippage in basis points, adverse when positive, drawn from Normal(mean_bps, std_dev_bps)
+#[cfg(feature = "trade_simulator")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SlippageModel {
+    pub mean_bps: f64,
+    pub std_dev_bps: f64,
 }
 
-impl StrikeCoordinator {
-    pub fn new() -> Self {
+#[cfg(feature = "trade_simulator")]
+impl Default for SlippageModel {
+    fn default() -> Self {
         Self {
-            assignment_history: Arc::new(RwLock::new(HashMap::new())),
-            coordination_matrix: Arc::new(RwLock::new(vec![vec![0.0; NUM_BOTS]; NUM_BOTS])),
-        }
-    }
-
-    pub async fn assign_targets(
-        &self,
-        opportunities: &[MarketOpportunity],
-        num_bots: usize,
-    ) -> HashMap<usize, MarketOpportunity> {
-        let mut assignments = HashMap::new();
-        
-        // Distribute opportunities to maximize coverage
-        for (i, opportunity) in opportunities.iter().take(num_bots).enumerate() {
-            // Assign to bot based on strategy match
-            let bot_id = self.find_best_bot_for_opportunity(opportunity, i);
-            assignments.insert(bot_id, opportunity.clone());
-        }
-        
-        // Fill remaining bots with best opportunities
-        let mut bot_id = 0;
-        for opportunity in opportunities.iter().cycle().take(num_bots) {
-            if !assignments.contains_key(&bot_id) {
-                assignments.insert(bot_id, opportunity.clone());
-            }
-            bot_id = (bot_id + 1) % num_bots;
+            mean_bps: 5.0,
+            std_dev_bps: 10.0,
         }
-        
-        assignments
     }
+}
 
-    fn find_best_bot_for_opportunity(&self, opportunity: &MarketOpportunity, default: usize) -> usize {
-        match opportunity.opportunity_type {
-            OpportunityType::Arbitrage => default % 5 + 5, // Bots 5-9
-            OpportunityType::MomentumBreakout => default % 5 + 10, // Bots 10-14
-            OpportunityType::MeanReversion => default % 5 + 15, // Bots 15-19
-            OpportunityType::VolumeSpike => default % 5 + 20, // Bots 20-24
-            OpportunityType::MarketMaking => default % 5, // Bots 0-4
-        }
-    }
+#[cfg(feature = "trade_simulator")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillLeg {
+    Entry,
+    Exit,
 }
 
-// ==================== CAPITAL POOL MANAGER ====================
+#[cfg(feature = "trade_simulator")]
+#[derive(Debug, Clone)]
+pub struct PaperFill {
+    pub position_id: String,
+    pub pair: String,
+    pub side: Side,
+    pub leg: FillLeg,
+    pub requested_price: f64,
+    pub filled_price: f64,
+    pub quantity: f64,
+    pub slippage_bps: f64,
+    pub filled_at: DateTime<Utc>,
+}
 
+/// Simulated fills and the P&L they realize, for a bot in paper-trading mode
+#[cfg(feature = "trade_simulator")]
 #[derive(Debug)]
-pub struct CapitalPool {
-    total_capital: f64,
-    allocated_capital: f64,
-    reserve_capital: f64,
-    bot_allocations: HashMap<usize, f64>,
+pub struct PaperTradeJournal {
+    slippage_model: SlippageModel,
+    fills: RingBuffer<PaperFill>, // Most recent PAPER_FILL_RETENTION fills
+    fill_count: usize,
+    exit_count: usize,
+    exit_slippage_bps_total: f64,
+    open_entries: HashMap<String, f64>, // Position id -> entry fill price
+    realized_pnl: f64,
+    round_trips: u32,
+    winning_round_trips: u32,
 }
 
-impl CapitalPool {
-    pub fn new(initial_capital: f64) -> Self {
-        let mut bot_allocations = HashMap::new();
-        let per_bot = initial_capital / NUM_BOTS as f64;
-        
-        for i in 0..NUM_BOTS {
-            bot_allocations.insert(i, per_bot);
-        }
-        
+#[cfg(feature = "trade_simulator")]
+impl PaperTradeJournal {
+    pub fn new(slippage_model: SlippageModel) -> Self {
         Self {
-            total_capital: initial_capital,
-            allocated_capital: initial_capital * 0.95,
-            reserve_capital: initial_capital * 0.05,
-            bot_allocations,
+            slippage_model,
+            fills: RingBuffer::new(PAPER_FILL_RETENTION),
+            fill_count: 0,
+            exit_count: 0,
+            exit_slippage_bps_total: 0.0,
+            open_entries: HashMap::new(),
+            realized_pnl: 0.0,
+            round_trips: 0,
+            winning_round_trips: 0,
         }
     }
 
-    pub fn rebalance(&mut self) {
-        // Rebalance capital across bots
-        let total = self.bot_allocations.values().sum::<f64>();
-        let target_per_bot = total / NUM_BOTS as f64;
-        
-        for allocation in self.bot_allocations.values_mut() {
-            *allocation = target_per_bot;
-        }
+    pub fn slippage_model(&self) -> SlippageModel {
+        self.slippage_model
     }
-}
 
-// ==================== PERFORMANCE AGGREGATOR ====================
+    pub fn fills(&self) -> &RingBuffer<PaperFill> {
+        &self.fills
+    }
 
-#[derive(Debug)]
-pub struct PerformanceAggregator {
-    total_strikes: u32,
-    successful_strikes: u32,
-    total_profit: f64,
-    total_loss: f64,
-    leverage_history: Vec<f64>,
-    strike_times: Vec<DateTime<Utc>>,
-}
+    /// Fill at the position's entry price
+    pub fn record_entry(&mut self, position: &BotPosition) -> PaperFill {
+        self.open_entries.insert(position.id.clone(), position.entry_price);
+        self.push(position, FillLeg::Entry, position.entry_price, position.entry_price, 0.0)
+    }
 
-impl PerformanceAggregator {
-    pub fn new() -> Self {
-        Self {
-            total_strikes: 0,
-            successful_strikes: 0,
-            total_profit: 0.0,
-            total_loss: 0.0,
-            leverage_history: Vec::new(),
-            strike_times: Vec::new(),
+    /// Fill `slippage_bps` worse than `exit_price` and realize the leveraged P&L against the entry fill
+    pub fn record_exit(&mut self, position: &BotPosition, exit_price: f64, slippage_bps: f64) -> PaperFill {
+        let slippage = slippage_bps / 10_000.0;
+        let filled_price = match position.side {
+            Side::Long => exit_price * (1.0 - slippage),
+            Side::Short => exit_price * (1.0 + slippage),
+        };
+        if let Some(entry_price) = self.open_entries.remove(&position.id).filter(|p| *p > 0.0) {
+            let move_pct = (filled_price - entry_price) / entry_price;
+            let pnl = match position.side {
+                Side::Long => position.leveraged_size * move_pct,
+                Side::Short => -position.leveraged_size * move_pct,
+            };
+            self.realized_pnl += pnl;
+            self.round_trips += 1;
+            if pnl > 0.0 {
+                self.winning_round_trips += 1;
+            }
         }
+        self.push(position, FillLeg::Exit, exit_price, filled_price, slippage_bps)
     }
 
-    pub fn add_result(&mut self, result: StrikeResult) {
-        self.total_strikes += 1;
-        if result.success {
-            self.successful_strikes += 1;
-            self.total_profit += result.profit;
-        } else {
-            self.total_loss += result.profit.abs();
+    fn push(&mut self, position: &BotPosition, leg: FillLeg, requested_price: f64, filled_price: f64, slippage_bps: f64) -> PaperFill {
+        let fill = PaperFill {
+            position_id: position.id.clone(),
+            pair: position.pair.clone(),
+            side: position.side,
+            leg,
+            requested_price,
+            filled_price,
+            quantity: position.size,
+            slippage_bps,
+            filled_at: Utc::now(),
+        };
+        self.fill_count += 1;
+        if leg == FillLeg::Exit {
+            self.exit_count += 1;
+            self.exit_slippage_bps_total += slippage_bps;
         }
-        self.leverage_history.push(result.position.leverage);
-        self.strike_times.push(Utc::now());
+        self.fills.push(fill.clone());
+        fill
     }
 
-    pub fn get_stats(&self) -> AggregatedStats {
-        AggregatedStats {
-            total_strikes: self.total_strikes,
-            successful_strikes: self.successful_strikes,
-            win_rate: self.successful_strikes as f64 / self.total_strikes.max(1) as f64,
-            total_profit: self.total_profit,
-            total_loss: self.total_loss,
-            avg_leverage: self.leverage_history.iter().sum::<f64>() / self.leverage_history.len().max(1) as f64,
-            max_leverage: self.leverage_history.iter().fold(0.0, |a, &b| a.max(b)),
-            risk_utilization: self.calculate_risk_utilization(),
+    pub fn report(&self) -> PaperTradingReport {
+        PaperTradingReport {
+            slippage_model: self.slippage_model,
+            fills: self.fill_count,
+            round_trips: self.round_trips,
+            open_positions: self.open_entries.len(),
+            win_rate: self.winning_round_trips as f64 / self.round_trips.max(1) as f64,
+            realized_pnl: self.realized_pnl,
+            avg_exit_slippage_bps: self.exit_slippage_bps_total / self.exit_count.max(1) as f64,
         }
     }
+}
 
-    fn calculate_risk_utilization(&self) -> f64 {
-        // Calculate how much of available risk capacity is being used
-        let avg_leverage = self.leverage_history.iter().sum::<f64>() / self.leverage_history.len().max(1) as f64;
-        avg_leverage / MAX_LEVERAGE
-    }
+#[cfg(feature = "trade_simulator")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperTradingReport {
+    pub slippage_model: SlippageModel,
+    pub fills: usize,
+    pub round_trips: u32,
+    pub open_positions: usize, // Entered but not yet exited
+    pub win_rate: f64,
+    pub realized_pnl: f64,
+    pub avg_exit_slippage_bps: f64,
 }
 
-// ==================== DATA STRUCTURES ====================
+// ==================== BOT SUPERVISION ====================
 
 #[derive(Debug, Clone)]
-pub struct MarketOpportunity {
-    pub exchange: String,
-    pub pair: String,
-    pub opportunity_type: OpportunityType,
-    pub expected_profit: f64,
-    pub confidence: f64,
-    pub volatility: f64,
-    pub volume_ratio: f64,
-    pub entry_price: f64,
-    pub target_price: f64,
-    pub stop_loss: f64,
-    pub leverage: f64,
-    pub safety_score: f64,      // Token safety score
-    pub token_address: String,  // Token address for validation
-    pub strike_box_size: f64,   // Strike Box calculated position size
-    pub strike_box_tp_prices: [f64; 3], // Strike Box take profit prices (TP1, TP2, TP3)
+pub struct FaultToleranceConfig {
+    pub max_restarts: u32,     // Within restart_window; one more failure quarantines the bot
+    pub restart_window: Duration,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum OpportunityType {
-    Arbitrage,
-    MomentumBreakout,
-    MeanReversion,
-    VolumeSpike,
-    MarketMaking,
+impl Default for FaultToleranceConfig {
+    fn default() -> Self {
+        Self {
+            max_restarts: MAX_BOT_RESTARTS,
+            restart_window: Duration::minutes(BOT_RESTART_WINDOW_MINUTES),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotStatus {
+    Healthy,
+    Restarted,
+    Quarantined, // No longer assigned strikes; capital handed to healthy bots
 }
 
 #[derive(Debug, Clone)]
-pub enum BotStrategy {
-    MarketMaking,
-    Arbitrage,
-    Momentum,
-    MeanReversion,
-    Volatility,
+pub struct BotHealth {
+    pub status: BotStatus,
+    pub restart_count: u32, // Lifetime total
+    recent_restarts: VecDeque<DateTime<Utc>>,
 }
 
-impl BotStrategy {
-    pub fn name(&self) -> &str {
-        match self {
-            Self::MarketMaking => "Market Making",
-            Self::Arbitrage => "Arbitrage",
-            Self::Momentum => "Momentum",
-            Self::MeanReversion => "Mean Reversion",
-            Self::Volatility => "Volatility",
+impl Default for BotHealth {
+    fn default() -> Self {
+        Self {
+            status: BotStatus::Healthy,
+            restart_count: 0,
+            recent_restarts: VecDeque::new(),
         }
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct BotPosition {
-    pub id: String,
+pub struct BotRestart {
     pub bot_id: usize,
-    pub exchange: String,
-    pub pair: String,
-    pub side: Side,
-    pub size: f64,
-    pub leveraged_size: f64,
-    pub entry_price: f64,
-    pub target_price: f64,
-    pub stop_loss: f64,
-    pub leverage: f64,
-    pub opened_at: DateTime<Utc>,
-    pub status: PositionStatus,
-    pub exit_price: Option<f64>,      // NEW: Exit price
-    pub exit_reason: Option<ExitReason>, // NEW: Why position was closed
-    pub closed_at: Option<DateTime<Utc>>, // NEW: When position was closed
+    pub reason: String,
+    pub at: DateTime<Utc>,
+    pub restart_count: u32,
+    pub action: BotStatus, // Restarted or Quarantined
+}
+
+/// Await bot tasks, separating completed strikes from tasks that panicked or were cancelled
+async fn join_supervised(
+    handles: Vec<(usize, tokio::task::JoinHandle<StrikeResult>)>,
+) -> (Vec<StrikeResult>, Vec<(usize, String)>) {
+    let mut results = Vec::new();
+    let mut failures = Vec::new();
+    for (bot_id, handle) in handles {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(e) if e.is_panic() => {
+                let panic = e.into_panic();
+                let message = panic.downcast_ref::<&str>().map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic payload".to_string());
+                failures.push((bot_id, format!("panicked: {}", message)));
+            }
+            Err(e) => failures.push((bot_id, format!("task failed: {}", e))),
+        }
+    }
+    (results, failures)
 }
 
+// ==================== STRIKE COORDINATOR ====================
+
 #[derive(Debug, Clone)]
-pub enum ExitReason {
-    TargetHit,      // Hit target price - take profit
-    StopLoss,       // Stop loss triggered
-    QuickProfit,    // Quick profit > 0.5% - exit immediately
-    TimeLimit,      // Maximum time limit reached (1 minute)
-    MaxChecks,      // Safety limit reached
+pub struct CoordinatorConfig {
+    pub max_bots_per_target: usize, // Per round, per (token, side)
+    pub loss_cooldown: Duration,    // Bot sits out a token it just lost on
+    pub max_opportunity_age_seconds: f64, // Older opportunities are dropped before assignment
 }
 
-impl ExitReason {
-    pub fn name(&self) -> &str {
-        match self {
-            Self::TargetHit => "Target Hit",
-            Self::StopLoss => "Stop Loss",
-            Self::QuickProfit => "Quick Profit",
-            Self::TimeLimit => "Time Limit (1 min)",
-            Self::MaxChecks => "Max Checks",
+impl Default for CoordinatorConfig {
+    fn default() -> Self {
+        Self {
+            max_bots_per_target: MAX_BOTS_PER_TARGET,
+            loss_cooldown: Duration::seconds(LOSS_COOLDOWN_SECONDS),
+            max_opportunity_age_seconds: MAX_OPPORTUNITY_AGE_SECONDS,
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct TradeResult {
-    pub success: bool,
-    pub execution_time_ms: u64,
-    pub filled_price: f64,
-    pub filled_quantity: f64,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssignmentOutcome {
+    Pending,
+    Skipped,   // Bot never entered (strict sizing, exposure cap, failed entry)
+    Won(f64),
+    Lost(f64),
 }
 
 #[derive(Debug, Clone)]
-pub enum Side {
-    Long,
-    Short,
+pub struct AssignmentRecord {
+    pub bot_id: usize,
+    pub token_address: String,
+    pub side: Side,
+    pub opportunity_type: OpportunityType,
+    pub assigned_at: DateTime<Utc>,
+    pub outcome: AssignmentOutcome,
+    pub resolved_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone)]
-pub enum PositionStatus {
-    Open,
-    Closed,
-    Partial,
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SuccessCell {
+    pub wins: u32,
+    pub trials: u32,
+}
+
+impl SuccessCell {
+    /// Laplace-smoothed so untried pairs rank at 0.5 instead of 0 or 1
+    pub fn success_rate(&self) -> f64 {
+        (self.wins as f64 + 1.0) / (self.trials as f64 + 2.0)
+    }
+
+    fn merge(self, other: SuccessCell) -> SuccessCell {
+        SuccessCell {
+            wins: self.wins + other.wins,
+            trials: self.trials + other.trials,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StrikeCoordinator {
+    config: CoordinatorConfig,
+    assignment_history: Arc<RwLock<HashMap<usize, VecDeque<AssignmentRecord>>>>,
+    coordination_matrix: Arc<RwLock<Vec<[SuccessCell; OpportunityType::COUNT]>>>, // Bot x opportunity type
+}
+
+impl StrikeCoordinator {
+    pub fn new() -> Self {
+        Self::with_config(CoordinatorConfig::default())
+    }
+
+    pub fn with_config(config: CoordinatorConfig) -> Self {
+        Self {
+            config,
+            assignment_history: Arc::new(RwLock::new(HashMap::new())),
+            coordination_matrix: Arc::new(RwLock::new(vec![[SuccessCell::default(); OpportunityType::COUNT]; NUM_BOTS])),
+        }
+    }
+
+    /// Assign opportunities (ranked best-first) to bots without breaching the guard's caps.
+    /// Each opportunity goes to the free bot whose strategy has the best record on its type;
+    /// leftover bots then take the best-ranked opportunity still under its per-round cap.
+    /// A bot never gets a token it lost on within the loss cooldown.
+    pub async fn assign_targets(
+        &self,
+        opportunities: &[MarketOpportunity],
+        num_bots: usize,
+        risk_guard: &ArrayRiskGuard,
+    ) -> HashMap<usize, MarketOpportunity> {
+        let now = Utc::now();
+        let mut assignments = HashMap::new();
+        // Project exposure as assignments are made so they count against each other
+        let mut projected = risk_guard.clone();
+        let mut per_target: HashMap<(String, Side), usize> = HashMap::new();
+        
+        // Stale opportunities have lost most of their edge; never hand them to a bot
+        let fresh: Vec<&MarketOpportunity> = opportunities.iter()
+            .filter(|o| !o.is_expired(now, self.config.max_opportunity_age_seconds))
+            .collect();
+        let discarded = opportunities.len() - fresh.len();
+        let opportunities = fresh;
+        if discarded > 0 {
+            info!("⏳ Discarded {} expired opportunities (older than {:.0}s)",
+                discarded, self.config.max_opportunity_age_seconds);
+        }
+        
+        let mut history = self.assignment_history.write().await;
+        let matrix = self.coordination_matrix.read().await;
+        
+        // Pass 1: each top opportunity gets the best-suited free bot
+        for &opportunity in opportunities.iter().take(num_bots) {
+            let best_bot = (0..num_bots)
+                .filter(|bot_id| !assignments.contains_key(bot_id))
+                .filter(|&bot_id| !self.in_loss_cooldown(&history, bot_id, &opportunity.token_address, now))
+                .max_by(|&a, &b| Self::compare_bots(&matrix, opportunity, a, b));
+            
+            if let Some(bot_id) = best_bot {
+                if self.admit(opportunity, bot_id, &mut projected, &mut per_target) {
+                    assignments.insert(bot_id, opportunity.clone());
+                }
+            }
+        }
+        
+        // Pass 2: fill remaining bots with the best opportunity still open to them
+        for bot_id in 0..num_bots {
+            if assignments.contains_key(&bot_id) {
+                continue;
+            }
+            for &opportunity in &opportunities {
+                if self.in_loss_cooldown(&history, bot_id, &opportunity.token_address, now) {
+                    continue;
+                }
+                if self.admit(opportunity, bot_id, &mut projected, &mut per_target) {
+                    assignments.insert(bot_id, opportunity.clone());
+                    break;
+                }
+            }
+        }
+        
+        for (&bot_id, opportunity) in &assignments {
+            let records = history.entry(bot_id).or_default();
+            if records.len() >= ASSIGNMENT_HISTORY_CAPACITY {
+                records.pop_front();
+            }
+            records.push_back(AssignmentRecord {
+                bot_id,
+                token_address: opportunity.token_address.clone(),
+                side: opportunity.side(),
+                opportunity_type: opportunity.opportunity_type.clone(),
+                assigned_at: now,
+                outcome: AssignmentOutcome::Pending,
+                resolved_at: None,
+            });
+        }
+        
+        assignments
+    }
+
+    /// Feed a strike outcome back into the bot's history and the success matrix
+    pub async fn record_result(&self, result: &StrikeResult) {
+        let outcome = if !result.entered {
+            AssignmentOutcome::Skipped
+        } else if result.success {
+            AssignmentOutcome::Won(result.profit)
+        } else {
+            AssignmentOutcome::Lost(result.profit)
+        };
+        
+        {
+            let mut history = self.assignment_history.write().await;
+            let pending = history.get_mut(&result.bot_id).and_then(|records| {
+                records.iter_mut().rev().find(|r| {
+                    r.outcome == AssignmentOutcome::Pending
+                        && r.token_address == result.opportunity.token_address
+                })
+            });
+            if let Some(record) = pending {
+                record.outcome = outcome;
+                record.resolved_at = Some(Utc::now());
+            }
+        }
+        
+        // Skipped strikes say nothing about how the strategy trades the opportunity
+        if result.entered {
+            let mut matrix = self.coordination_matrix.write().await;
+            if let Some(row) = matrix.get_mut(result.bot_id) {
+                let cell = &mut row[result.opportunity.opportunity_type.index()];
+                cell.trials += 1;
+                if result.success {
+                    cell.wins += 1;
+                }
+            }
+        }
+    }
+
+    pub async fn assignment_history(&self, bot_id: usize) -> Vec<AssignmentRecord> {
+        self.assignment_history.read().await
+            .get(&bot_id)
+            .map(|records| records.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub async fn success_rate(&self, bot_id: usize, opportunity_type: &OpportunityType) -> f64 {
+        let matrix = self.coordination_matrix.read().await;
+        matrix.get(bot_id)
+            .map(|row| row[opportunity_type.index()])
+            .unwrap_or_default()
+            .success_rate()
+    }
+
+    /// Success rate pooled across every bot running the strategy
+    pub async fn strategy_success_rate(&self, strategy: &BotStrategy, opportunity_type: &OpportunityType) -> f64 {
+        let matrix = self.coordination_matrix.read().await;
+        Self::strategy_cell(&matrix, strategy, opportunity_type).success_rate()
+    }
+
+    fn strategy_cell(
+        matrix: &[[SuccessCell; OpportunityType::COUNT]],
+        strategy: &BotStrategy,
+        opportunity_type: &OpportunityType,
+    ) -> SuccessCell {
+        matrix.iter()
+            .enumerate()
+            .filter(|(bot_id, _)| BotStrategy::for_bot(*bot_id) == *strategy)
+            .fold(SuccessCell::default(), |acc, (_, row)| acc.merge(row[opportunity_type.index()]))
+    }
+
+    /// Order by strategy track record, then natural strategy fit, then the bot's own record;
+    /// remaining ties go to the lower bot id
+    fn compare_bots(
+        matrix: &[[SuccessCell; OpportunityType::COUNT]],
+        opportunity: &MarketOpportunity,
+        a: usize,
+        b: usize,
+    ) -> std::cmp::Ordering {
+        let key = |bot_id: usize| {
+            let strategy = BotStrategy::for_bot(bot_id);
+            let own = matrix.get(bot_id)
+                .map(|row| row[opportunity.opportunity_type.index()])
+                .unwrap_or_default();
+            (
+                Self::strategy_cell(matrix, &strategy, &opportunity.opportunity_type).success_rate(),
+                strategy.fits(&opportunity.opportunity_type),
+                own.success_rate(),
+            )
+        };
+        let (rate_a, fit_a, own_a) = key(a);
+        let (rate_b, fit_b, own_b) = key(b);
+        rate_a.total_cmp(&rate_b)
+            .then(fit_a.cmp(&fit_b))
+            .then(own_a.total_cmp(&own_b))
+            .then(b.cmp(&a))
+    }
+
+    fn in_loss_cooldown(
+        &self,
+        history: &HashMap<usize, VecDeque<AssignmentRecord>>,
+        bot_id: usize,
+        token_address: &str,
+        now: DateTime<Utc>,
+    ) -> bool {
+        history.get(&bot_id).is_some_and(|records| {
+            records.iter().rev().any(|r| {
+                r.token_address == token_address
+                    && matches!(r.outcome, AssignmentOutcome::Lost(_))
+                    && r.resolved_at.is_some_and(|at| now - at < self.config.loss_cooldown)
+            })
+        })
+    }
+
+    /// Reserve a slot under both the per-round target cap and the projected exposure caps
+    fn admit(
+        &self,
+        opportunity: &MarketOpportunity,
+        bot_id: usize,
+        projected: &mut ArrayRiskGuard,
+        per_target: &mut HashMap<(String, Side), usize>,
+    ) -> bool {
+        let target = (opportunity.token_address.clone(), opportunity.side());
+        let assigned = per_target.get(&target).copied().unwrap_or(0);
+        if assigned >= self.config.max_bots_per_target {
+            return false;
+        }
+        let notional = ArrayRiskGuard::estimated_notional(opportunity);
+        if projected.try_open(bot_id, &opportunity.token_address, notional).is_err() {
+            return false;
+        }
+        per_target.insert(target, assigned + 1);
+        true
+    }
+}
+
+// ==================== ARRAY RISK GUARD ====================
+
+#[derive(Debug, Clone)]
+pub struct ArrayRiskConfig {
+    pub max_token_exposure_pct: f64, // Of total array capital, leveraged notional
+    pub max_group_exposure_pct: f64,
+    pub max_bots_per_token: usize,
+    pub correlation_groups: HashMap<String, String>, // Token address -> group; unlisted tokens fall back to their sector, then stand alone
+}
+
+impl Default for ArrayRiskConfig {
+    fn default() -> Self {
+        Self {
+            max_token_exposure_pct: MAX_TOKEN_EXPOSURE_PCT,
+            max_group_exposure_pct: MAX_GROUP_EXPOSURE_PCT,
+            max_bots_per_token: MAX_BOTS_PER_TOKEN,
+            correlation_groups: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExposureBreach {
+    TokenCap { token_address: String, projected: f64, limit: f64 },
+    GroupCap { group: String, projected: f64, limit: f64 },
+    BotsPerToken { token_address: String, bots: usize, limit: usize },
+}
+
+impl std::fmt::Display for ExposureBreach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExposureBreach::TokenCap { token_address, projected, limit } => write!(
+                f, "token {} exposure ${:.2} would exceed cap ${:.2}", token_address, projected, limit),
+            ExposureBreach::GroupCap { group, projected, limit } => write!(
+                f, "group {} exposure ${:.2} would exceed cap ${:.2}", group, projected, limit),
+            ExposureBreach::BotsPerToken { token_address, bots, limit } => write!(
+                f, "{} bots already on token {} (max {})", bots, token_address, limit),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct TokenExposure {
+    notional: f64,
+    bots: HashMap<usize, u32>, // Bot id -> open positions on the token
+    group: String, // Group the first open counted against; later opens and closes use it too
+}
+
+/// Tracks open notional per token and correlation group across every bot in the array
+#[derive(Debug, Clone)]
+pub struct ArrayRiskGuard {
+    config: ArrayRiskConfig,
+    total_capital: f64,
+    tokens: HashMap<String, TokenExposure>,
+    groups: HashMap<String, f64>,
+    sectors: HashMap<String, String>, // Token address -> sector from snapshot metadata
+    blocked_entries: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenExposureSnapshot {
+    pub token_address: String,
+    pub notional: f64,
+    pub pct_of_capital: f64,
+    pub bots: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupExposureSnapshot {
+    pub group: String,
+    pub notional: f64,
+    pub pct_of_capital: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayRiskSnapshot {
+    pub open_notional: f64,
+    pub tokens: Vec<TokenExposureSnapshot>, // Largest first
+    pub groups: Vec<GroupExposureSnapshot>, // Largest first
+    pub blocked_entries: u64,
+}
+
+impl ArrayRiskGuard {
+    pub fn new(config: ArrayRiskConfig, total_capital: f64) -> Self {
+        Self {
+            config,
+            total_capital,
+            tokens: HashMap::new(),
+            groups: HashMap::new(),
+            sectors: HashMap::new(),
+            blocked_entries: 0,
+        }
+    }
+
+    pub fn set_total_capital(&mut self, total_capital: f64) {
+        self.total_capital = total_capital;
+    }
+
+    pub fn set_correlation_group(&mut self, token_address: &str, group: &str) {
+        self.config.correlation_groups.insert(token_address.to_string(), group.to_string());
+    }
+
+    /// Record the sector a token's metadata puts it in, its group when no correlation group is configured
+    pub fn set_sector(&mut self, token_address: &str, sector: &str) {
+        self.sectors.insert(token_address.to_string(), sector.to_string());
+    }
+
+    /// The configured correlation group, else the token's sector, else the token alone
+    pub fn group_for(&self, token_address: &str) -> String {
+        self.config
+            .correlation_groups
+            .get(token_address)
+            .or_else(|| self.sectors.get(token_address))
+            .cloned()
+            .unwrap_or_else(|| token_address.to_string())
+    }
+
+    /// Group an open on `token_address` counts against: where its exposure already sits, if any
+    fn exposure_group(&self, token_address: &str) -> String {
+        match self.tokens.get(token_address) {
+            Some(token) => token.group.clone(),
+            None => self.group_for(token_address),
+        }
+    }
+
+    /// Leveraged notional a bot is expected to take on an opportunity, before it sizes precisely
+    pub fn estimated_notional(opportunity: &MarketOpportunity) -> f64 {
+        let base = if opportunity.strike_box_size > 0.0 {
+            opportunity.strike_box_size.min(CAPITAL_PER_BOT)
+        } else {
+            CAPITAL_PER_BOT
+        };
+        base * opportunity.leverage.max(1.0)
+    }
+
+    pub fn check(&self, bot_id: usize, token_address: &str, notional: f64) -> Result<(), ExposureBreach> {
+        let token = self.tokens.get(token_address);
+        let bots = token.map(|t| t.bots.len()).unwrap_or(0);
+        let bot_already_on_token = token.map(|t| t.bots.contains_key(&bot_id)).unwrap_or(false);
+        if !bot_already_on_token && bots >= self.config.max_bots_per_token {
+            return Err(ExposureBreach::BotsPerToken {
+                token_address: token_address.to_string(),
+                bots,
+                limit: self.config.max_bots_per_token,
+            });
+        }
+
+        let token_limit = self.total_capital * self.config.max_token_exposure_pct;
+        let token_projected = token.map(|t| t.notional).unwrap_or(0.0) + notional;
+        if token_projected > token_limit {
+            return Err(ExposureBreach::TokenCap {
+                token_address: token_address.to_string(),
+                projected: token_projected,
+                limit: token_limit,
+            });
+        }
+
+        let group = self.exposure_group(token_address);
+        let group_limit = self.total_capital * self.config.max_group_exposure_pct;
+        let group_projected = self.groups.get(&group).copied().unwrap_or(0.0) + notional;
+        if group_projected > group_limit {
+            return Err(ExposureBreach::GroupCap {
+                group,
+                projected: group_projected,
+                limit: group_limit,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Check the caps and, if they hold, record the open in one step
+    pub fn try_open(&mut self, bot_id: usize, token_address: &str, notional: f64) -> Result<(), ExposureBreach> {
+        if let Err(breach) = self.check(bot_id, token_address, notional) {
+            self.blocked_entries += 1;
+            return Err(breach);
+        }
+        self.record_open(bot_id, token_address, notional);
+        Ok(())
+    }
+
+    pub fn record_open(&mut self, bot_id: usize, token_address: &str, notional: f64) {
+        let group = self.exposure_group(token_address);
+        let token = self
+            .tokens
+            .entry(token_address.to_string())
+            .or_insert_with(|| TokenExposure { group: group.clone(), ..TokenExposure::default() });
+        token.notional += notional;
+        *token.bots.entry(bot_id).or_insert(0) += 1;
+        *self.groups.entry(group).or_insert(0.0) += notional;
+    }
+
+    pub fn record_close(&mut self, bot_id: usize, token_address: &str, notional: f64) {
+        let Some(token) = self.tokens.get_mut(token_address) else {
+            return;
+        };
+        let group = token.group.clone();
+        // Ignore closes we never saw open (e.g. positions from before the guard existed)
+        let Some(count) = token.bots.get_mut(&bot_id) else {
+            return;
+        };
+        *count -= 1;
+        if *count == 0 {
+            token.bots.remove(&bot_id);
+        }
+        token.notional = (token.notional - notional).max(0.0);
+        if token.bots.is_empty() {
+            self.tokens.remove(token_address);
+        }
+
+        if let Some(group_notional) = self.groups.get_mut(&group) {
+            *group_notional = (*group_notional - notional).max(0.0);
+            if *group_notional <= f64::EPSILON {
+                self.groups.remove(&group);
+            }
+        }
+    }
+
+    pub fn bots_on_token(&self, token_address: &str) -> usize {
+        self.tokens.get(token_address).map(|t| t.bots.len()).unwrap_or(0)
+    }
+
+    pub fn snapshot(&self) -> ArrayRiskSnapshot {
+        let pct = |notional: f64| if self.total_capital > 0.0 { notional / self.total_capital } else { 0.0 };
+        
+        let mut tokens: Vec<TokenExposureSnapshot> = self
+            .tokens
+            .iter()
+            .map(|(token_address, exposure)| TokenExposureSnapshot {
+                token_address: token_address.clone(),
+                notional: exposure.notional,
+                pct_of_capital: pct(exposure.notional),
+                bots: exposure.bots.len(),
+            })
+            .collect();
+        tokens.sort_by(|a, b| b.notional.total_cmp(&a.notional));
+        
+        let mut groups: Vec<GroupExposureSnapshot> = self
+            .groups
+            .iter()
+            .map(|(group, &notional)| GroupExposureSnapshot {
+                group: group.clone(),
+                notional,
+                pct_of_capital: pct(notional),
+            })
+            .collect();
+        groups.sort_by(|a, b| b.notional.total_cmp(&a.notional));
+        
+        ArrayRiskSnapshot {
+            open_notional: tokens.iter().map(|t| t.notional).sum(),
+            tokens,
+            groups,
+            blocked_entries: self.blocked_entries,
+        }
+    }
+}
+
+// ==================== CAPITAL POOL MANAGER ====================
+
+#[derive(Debug)]
+pub struct CapitalPool {
+    total_capital: f64,
+    allocated_capital: f64,
+    reserve_capital: f64,
+    bot_allocations: HashMap<usize, f64>,
+}
+
+impl CapitalPool {
+    pub fn new(initial_capital: f64) -> Self {
+        let mut bot_allocations = HashMap::new();
+        let per_bot = initial_capital / NUM_BOTS as f64;
+        
+        for i in 0..NUM_BOTS {
+            bot_allocations.insert(i, per_bot);
+        }
+        
+        Self {
+            total_capital: initial_capital,
+            allocated_capital: initial_capital * 0.95,
+            reserve_capital: initial_capital * 0.05,
+            bot_allocations,
+        }
+    }
+
+    pub fn rebalance(&mut self) {
+        // Rebalance capital across bots
+        let total = self.bot_allocations.values().sum::<f64>();
+        let target_per_bot = total / self.bot_allocations.len().max(1) as f64;
+        
+        for allocation in self.bot_allocations.values_mut() {
+            *allocation = target_per_bot;
+        }
+    }
+
+    /// Move a retired bot's allocation evenly onto `to`
+    pub fn reassign(&mut self, from: usize, to: &[usize]) {
+        let Some(freed) = self.bot_allocations.remove(&from) else {
+            return;
+        };
+        if to.is_empty() {
+            return;
+        }
+        let share = freed / to.len() as f64;
+        for id in to {
+            *self.bot_allocations.entry(*id).or_insert(0.0) += share;
+        }
+    }
+}
+
+// ==================== PERFORMANCE AGGREGATOR ====================
+
+#[derive(Debug)]
+pub struct PerformanceAggregator {
+    total_strikes: u32,
+    successful_strikes: u32,
+    total_profit: f64,
+    total_loss: f64,
+    leverage_history: RingBuffer<f64>,
+    strike_times: RingBuffer<DateTime<Utc>>,
+    cap_source_counts: HashMap<SizeCapSource, u32>,
+    bot_breakdown: BTreeMap<usize, BreakdownStats>,
+    strategy_breakdown: BTreeMap<String, BreakdownStats>,
+}
+
+impl PerformanceAggregator {
+    pub fn new() -> Self {
+        Self::with_history_capacity(PERFORMANCE_HISTORY_CAPACITY)
+    }
+
+    pub fn with_history_capacity(capacity: usize) -> Self {
+        Self {
+            total_strikes: 0,
+            successful_strikes: 0,
+            total_profit: 0.0,
+            total_loss: 0.0,
+            leverage_history: RingBuffer::new(capacity),
+            strike_times: RingBuffer::new(capacity),
+            cap_source_counts: HashMap::new(),
+            bot_breakdown: BTreeMap::new(),
+            strategy_breakdown: BTreeMap::new(),
+        }
+    }
+
+    /// Clear every count for a new cycle, keeping the history capacity
+    pub fn start_cycle(&mut self) {
+        *self = Self::with_history_capacity(self.history_capacity());
+    }
+
+    pub fn history_len(&self) -> usize {
+        self.leverage_history.len()
+    }
+
+    pub fn history_capacity(&self) -> usize {
+        self.leverage_history.capacity()
+    }
+
+    pub fn evicted_count(&self) -> u64 {
+        self.leverage_history.evicted_count()
+    }
+
+    pub fn add_result(&mut self, result: StrikeResult) {
+        self.total_strikes += 1;
+        if result.success {
+            self.successful_strikes += 1;
+            self.total_profit += result.profit;
+        } else {
+            self.total_loss += result.profit.abs();
+        }
+        self.leverage_history.push(result.position.leverage);
+        self.strike_times.push(Utc::now());
+        *self.cap_source_counts.entry(result.cap_source).or_insert(0) += 1;
+        self.bot_breakdown.entry(result.bot_id).or_default().add(&result);
+        self.strategy_breakdown
+            .entry(BotStrategy::for_bot(result.bot_id).name().to_string())
+            .or_default()
+            .add(&result);
+    }
+
+    pub fn bot_breakdown(&self) -> BTreeMap<usize, BreakdownStats> {
+        self.bot_breakdown.clone()
+    }
+
+    /// Per strategy, keyed by BotStrategy::name
+    pub fn strategy_breakdown(&self) -> BTreeMap<String, BreakdownStats> {
+        self.strategy_breakdown.clone()
+    }
+
+    pub fn get_stats(&self) -> AggregatedStats {
+        AggregatedStats {
+            total_strikes: self.total_strikes,
+            successful_strikes: self.successful_strikes,
+            win_rate: self.successful_strikes as f64 / self.total_strikes.max(1) as f64,
+            total_profit: self.total_profit,
+            total_loss: self.total_loss,
+            avg_leverage: self.leverage_history.iter().sum::<f64>() / self.leverage_history.len().max(1) as f64,
+            max_leverage: self.leverage_history.iter().fold(0.0, |a, &b| a.max(b)),
+            risk_utilization: self.calculate_risk_utilization(),
+            cap_source_counts: self.cap_source_counts.clone(),
+        }
+    }
+
+    fn calculate_risk_utilization(&self) -> f64 {
+        // Calculate how much of available risk capacity is being used
+        let avg_leverage = self.leverage_history.iter().sum::<f64>() / self.leverage_history.len().max(1) as f64;
+        avg_leverage / MAX_LEVERAGE
+    }
+}
+
+// ==================== CYCLE HISTORY ====================
+
+/// Strikes, wins, net profit and leverage for one bot or one strategy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BreakdownStats {
+    pub strikes: u32,
+    pub wins: u32,
+    pub net_profit: f64,
+    pub leverage_sum: f64,
+}
+
+impl BreakdownStats {
+    fn add(&mut self, result: &StrikeResult) {
+        self.strikes += 1;
+        if result.success {
+            self.wins += 1;
+        }
+        self.net_profit += result.profit;
+        self.leverage_sum += result.position.leverage;
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.strikes.max(1) as f64
+    }
+
+    pub fn avg_leverage(&self) -> f64 {
+        self.leverage_sum / self.strikes.max(1) as f64
+    }
+}
+
+/// One completed cycle: everything the cycle report prints, plus per-bot and per-strategy results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CycleRecord {
+    pub cycle_start: DateTime<Utc>,
+    pub cycle_end: DateTime<Utc>,
+    pub starting_capital: f64,
+    pub ending_capital: f64,
+    pub cycle_profits: f64,
+    pub total_strikes: u32,
+    pub successful_strikes: u32,
+    pub win_rate: f64,
+    pub avg_leverage: f64,
+    pub max_leverage: f64,
+    pub risk_utilization: f64,
+    pub cap_source_counts: BTreeMap<String, u32>, // Keyed by SizeCapSource::name
+    pub open_notional: f64,
+    pub tokens_held: usize,
+    pub largest_token: Option<(String, f64, usize)>, // (token, share of capital, bots)
+    pub largest_group: Option<(String, f64)>,        // (group, share of capital)
+    pub entries_blocked: u64,
+    pub healthy_bots: usize,
+    pub restarts: usize,
+    pub per_bot: BTreeMap<usize, BreakdownStats>,
+    pub per_strategy: BTreeMap<String, BreakdownStats>,
+    #[serde(default)]
+    pub direction_counts: BTreeMap<String, u32>, // Scanned opportunities by DirectionCall
+    #[serde(default)]
+    pub direction_calls: Vec<DirectionReport>, // Latest calls with their inputs, oldest first
+}
+
+impl CycleRecord {
+    /// Cycle profit over the capital the cycle started with
+    pub fn return_pct(&self) -> f64 {
+        self.cycle_profits / self.starting_capital.max(f64::EPSILON)
+    }
+
+    /// Share of strikes sized by a cap (Strike Box or pool %) rather than the bot's own capital
+    pub fn cap_binding_rate(&self) -> f64 {
+        let bot_capital = self.cap_source_counts.get(SizeCapSource::BotCapital.name()).copied().unwrap_or(0);
+        let total: u32 = self.cap_source_counts.values().sum();
+        total.saturating_sub(bot_capital) as f64 / total.max(1) as f64
+    }
+
+    /// Return per day, counting a cycle shorter than a day as one day
+    pub fn daily_return(&self) -> f64 {
+        let days = (self.cycle_end - self.cycle_start).num_seconds() as f64 / 86_400.0;
+        self.return_pct() / days.max(1.0)
+    }
+}
+
+/// Median daily return of the last `cycles` records; None without history
+pub fn median_daily_return(history: &[CycleRecord], cycles: usize) -> Option<f64> {
+    let mut returns: Vec<f64> = history
+        .iter()
+        .rev()
+        .take(cycles)
+        .map(CycleRecord::daily_return)
+        .collect();
+    if returns.is_empty() {
+        return None;
+    }
+    returns.sort_by(|a, b| a.total_cmp(b));
+    let mid = returns.len() / 2;
+    Some(if returns.len() % 2 == 0 { (returns[mid - 1] + returns[mid]) / 2.0 } else { returns[mid] })
+}
+
+/// Drops the oldest cycles beyond `limit`
+fn retain_recent_cycles(history: &mut Vec<CycleRecord>, limit: usize) {
+    let excess = history.len().saturating_sub(limit.max(1));
+    history.drain(..excess);
+}
+
+/// Where completed cycles are kept between runs
+pub trait CycleStore: Send + Sync + std::fmt::Debug {
+    fn append(&self, record: &CycleRecord) -> std::io::Result<()>;
+    /// Every stored cycle, oldest first
+    fn load(&self) -> std::io::Result<Vec<CycleRecord>>;
+}
+
+/// One JSON CycleRecord per line
+#[derive(Debug)]
+pub struct JsonlCycleStore {
+    path: PathBuf,
+    lock: std::sync::Mutex<()>,
+}
+
+impl JsonlCycleStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: std::sync::Mutex::new(()),
+        }
+    }
+}
+
+impl CycleStore for JsonlCycleStore {
+    fn append(&self, record: &CycleRecord) -> std::io::Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let line = serde_json::to_string(record)?;
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", line)
+    }
+
+    fn load(&self) -> std::io::Result<Vec<CycleRecord>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut records = Vec::new();
+        for line in std::io::BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records.push(serde_json::from_str(&line)?);
+        }
+        Ok(records)
+    }
+}
+
+/// Headline figures for one cycle, with the change from the cycle before it
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleComparisonRow {
+    pub cycle_end: DateTime<Utc>,
+    pub win_rate: f64,
+    pub return_pct: f64,
+    pub avg_leverage: f64,
+    pub cap_binding_rate: f64,
+    pub win_rate_delta: Option<f64>, // None for the oldest cycle in the history
+    pub return_delta: Option<f64>,
+    pub avg_leverage_delta: Option<f64>,
+    pub cap_binding_delta: Option<f64>,
+}
+
+/// The last K cycles side by side, oldest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleComparison {
+    pub rows: Vec<CycleComparisonRow>,
+}
+
+impl CycleComparison {
+    pub fn from_history(history: &[CycleRecord], cycles: usize) -> Self {
+        let first = history.len().saturating_sub(cycles);
+        let rows = (first..history.len())
+            .map(|i| {
+                let record = &history[i];
+                let previous = i.checked_sub(1).map(|p| &history[p]);
+                let delta = |metric: fn(&CycleRecord) -> f64| previous.map(|p| metric(record) - metric(p));
+                CycleComparisonRow {
+                    cycle_end: record.cycle_end,
+                    win_rate: record.win_rate,
+                    return_pct: record.return_pct(),
+                    avg_leverage: record.avg_leverage,
+                    cap_binding_rate: record.cap_binding_rate(),
+                    win_rate_delta: delta(|r| r.win_rate),
+                    return_delta: delta(CycleRecord::return_pct),
+                    avg_leverage_delta: delta(|r| r.avg_leverage),
+                    cap_binding_delta: delta(CycleRecord::cap_binding_rate),
+                }
+            })
+            .collect();
+        Self { rows }
+    }
+}
+
+impl std::fmt::Display for CycleComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let signed = |delta: Option<f64>, scale: f64| match delta {
+            Some(delta) => format!("{:+.1}", delta * scale),
+            None => "-".to_string(),
+        };
+        writeln!(f, "{:<12} {:>16} {:>16} {:>14} {:>16}", "Cycle end", "Win rate %", "Return %", "Avg leverage", "Cap-bound %")?;
+        for row in &self.rows {
+            writeln!(
+                f,
+                "{:<12} {:>7.1} ({:>6}) {:>7.1} ({:>6}) {:>5.1}x ({:>5}) {:>7.1} ({:>6})",
+                row.cycle_end.format("%Y-%m-%d"),
+                row.win_rate * 100.0,
+                signed(row.win_rate_delta, 100.0),
+                row.return_pct * 100.0,
+                signed(row.return_delta, 100.0),
+                row.avg_leverage,
+                signed(row.avg_leverage_delta, 1.0),
+                row.cap_binding_rate * 100.0,
+                signed(row.cap_binding_delta, 100.0),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// ==================== DATA STRUCTURES ====================
+
+/// Strike Box stop and take-profit prices for an entry, in f64 for the bot loop
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrikeBoxLevels {
+    pub stop_loss: f64,
+    pub take_profits: Vec<f64>, // One per take-profit ladder level
+}
+
+pub fn strike_box_long_levels(
+    stop_loss: &StopLossConfig,
+    take_profit: &TakeProfitConfig,
+    entry_price: f64,
+    safety_score: f64,
+) -> Result<StrikeBoxLevels, ConvError> {
+    let entry = conv::price_from_f64(entry_price)?;
+    let safety = conv::ratio_from_f64(safety_score)?;
+    let tps = take_profit.long_tp_prices(entry);
+    Ok(StrikeBoxLevels {
+        stop_loss: conv::price_to_f64(stop_loss.long_stop_price(entry, safety)),
+        take_profits: tps.into_iter().map(conv::price_to_f64).collect(),
+    })
+}
+
+pub fn strike_box_short_levels(
+    stop_loss: &StopLossConfig,
+    take_profit: &TakeProfitConfig,
+    entry_price: f64,
+) -> Result<StrikeBoxLevels, ConvError> {
+    let entry = conv::price_from_f64(entry_price)?;
+    let tps = take_profit.short_tp_prices(entry);
+    Ok(StrikeBoxLevels {
+        stop_loss: conv::price_to_f64(stop_loss.short_stop_price(entry)),
+        take_profits: tps.into_iter().map(conv::price_to_f64).collect(),
+    })
+}
+
+/// Levels for `side`; only the long stop depends on the safety score
+pub fn strike_box_levels(
+    side: Side,
+    stop_loss: &StopLossConfig,
+    take_profit: &TakeProfitConfig,
+    entry_price: f64,
+    safety_score: f64,
+) -> Result<StrikeBoxLevels, ConvError> {
+    match side {
+        Side::Long => strike_box_long_levels(stop_loss, take_profit, entry_price, safety_score),
+        Side::Short => strike_box_short_levels(stop_loss, take_profit, entry_price),
+    }
+}
+
+/// What the scanner knows about a spike when choosing a side
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DirectionSignals {
+    pub volume_ratio: f64,
+    pub price_change_pct: f64,   // Over the spike window; positive is up
+    pub accumulation_score: f64, // Smart-money net buying share of window flow, as WalletSignal
+    pub distribution_score: f64, // Smart-money net selling share of window flow
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DirectionCall {
+    Long,
+    Short,
+    Skip,
+}
+
+/// Side chosen for an opportunity, with the inputs and reasoning kept for the cycle report
+#[derive(Debug, Clone)]
+pub struct DirectionDecision {
+    pub call: DirectionCall,
+    pub confidence: f64, // 0 for Skip
+    pub signals: DirectionSignals,
+    pub rationale: String,
+}
+
+impl DirectionDecision {
+    /// Wallet flow decides when it is clear, with the size of the price move adding conviction
+    /// either way: top wallets selling into a spike up is a short. Without clear flow, a large
+    /// enough price move is followed, as a weak call.
+    pub fn classify(signals: DirectionSignals) -> Self {
+        let flow = signals.accumulation_score - signals.distribution_score;
+        let price_conviction = DIRECTION_PRICE_WEIGHT * (signals.price_change_pct / DIRECTION_PRICE_SCALE).tanh().abs();
+
+        let (call, confidence, pattern) = if signals.volume_ratio < MIN_VOLUME_RATIO {
+            (DirectionCall::Skip, 0.0, "no volume spike")
+        } else if flow >= DIRECTION_MIN_FLOW {
+            (DirectionCall::Long, flow + price_conviction, "smart money accumulating")
+        } else if flow <= -DIRECTION_MIN_FLOW {
+            let pattern = if signals.price_change_pct > 0.0 {
+                "top wallets selling into the spike"
+            } else {
+                "distribution with falling price"
+            };
+            (DirectionCall::Short, -flow + price_conviction, pattern)
+        } else if signals.price_change_pct >= DIRECTION_MIN_PRICE_MOVE {
+            (DirectionCall::Long, price_conviction, "price breaking out without clear flow")
+        } else if signals.price_change_pct <= -DIRECTION_MIN_PRICE_MOVE {
+            (DirectionCall::Short, price_conviction, "price breaking down without clear flow")
+        } else {
+            (DirectionCall::Skip, 0.0, "no clear flow or price move")
+        };
+
+        Self {
+            call,
+            confidence: confidence.min(1.0),
+            signals,
+            rationale: format!(
+                "{:?}: {} (volume {:.1}x, price {:+.1}%, accumulation {:.2}, distribution {:.2})",
+                call,
+                pattern,
+                signals.volume_ratio,
+                signals.price_change_pct * 100.0,
+                signals.accumulation_score,
+                signals.distribution_score
+            ),
+        }
+    }
+
+    pub fn side(&self) -> Option<Side> {
+        match self.call {
+            DirectionCall::Long => Some(Side::Long),
+            DirectionCall::Short => Some(Side::Short),
+            DirectionCall::Skip => None,
+        }
+    }
+}
+
+/// Validates `decision`'s side with Strike Box, recording each validation. When that side
+/// fails only on a gate specific to it and the call was weak, the other side is tried.
+/// Returns the final decision, Skip when nothing validated, and the passing validation.
+pub fn validate_direction(
+    engine: &mut StrikeBoxEngine,
+    token: &TokenSnapshot,
+    mut decision: DirectionDecision,
+) -> (DirectionDecision, Option<RiskValidation>) {
+    let Some(side) = decision.side() else {
+        return (decision, None);
+    };
+    let validation = engine.validate_and_record_entry(token, side.into());
+    if validation.all_passed {
+        return (decision, Some(validation));
+    }
+
+    let failed_gate = validation.gates.iter()
+        .find(|g| g.result.blocks_entry())
+        .map(|g| g.gate_name.clone())
+        .unwrap_or_default();
+    if DIRECTION_SPECIFIC_GATES.contains(&failed_gate.as_str()) && decision.confidence <= DIRECTION_FLIP_MAX_CONFIDENCE {
+        let other = side.opposite();
+        let retry = engine.validate_and_record_entry(token, other.into());
+        if retry.all_passed {
+            decision.call = match other {
+                Side::Long => DirectionCall::Long,
+                Side::Short => DirectionCall::Short,
+            };
+            decision.rationale.push_str(&format!("; {:?} failed {}, fell back to {:?}", side, failed_gate, other));
+            return (decision, Some(retry));
+        }
+    }
+
+    decision.call = DirectionCall::Skip;
+    decision.confidence = 0.0;
+    decision.rationale.push_str(&format!("; {:?} failed {}, skipped", side, failed_gate));
+    (decision, None)
+}
+
+/// An opportunity's direction call as the cycle report keeps it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectionReport {
+    pub pair: String,
+    pub call: DirectionCall,
+    pub confidence: f64,
+    pub signals: DirectionSignals,
+    pub rationale: String,
+}
+
+impl DirectionReport {
+    pub fn new(pair: &str, decision: &DirectionDecision) -> Self {
+        Self {
+            pair: pair.to_string(),
+            call: decision.call,
+            confidence: decision.confidence,
+            signals: decision.signals,
+            rationale: decision.rationale.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MarketOpportunity {
+    pub exchange: String,
+    pub pair: String,
+    pub opportunity_type: OpportunityType,
+    pub expected_profit: f64,
+    pub confidence: f64,
+    pub volatility: f64,
+    pub volume_ratio: f64,
+    pub entry_price: f64,
+    pub target_price: f64,
+    pub stop_loss: f64,
+    pub leverage: f64,
+    pub safety_score: f64,      // Token safety score
+    pub token_address: String,  // Token address for validation
+    pub strike_box_size: f64,   // Strike Box calculated position size
+    pub liquidity_usd: f64,     // Pool liquidity used for order-vs-pool limits
+    pub strike_box_tp_prices: Vec<f64>, // Strike Box take profit ladder prices, nearest first
+    pub direction: DirectionDecision,   // Never Skip; skipped spikes don't become opportunities
+    pub created_at: DateTime<Utc>,      // When the scan found it; edge decays from here
+}
+
+impl MarketOpportunity {
+    pub fn side(&self) -> Side {
+        self.direction.side().unwrap_or(Side::Long)
+    }
+
+    /// Expected profit decayed exponentially with age: halves every `half_life_seconds`
+    pub fn expected_profit_at(&self, now: DateTime<Utc>, half_life_seconds: f64) -> f64 {
+        if half_life_seconds <= 0.0 {
+            return self.expected_profit;
+        }
+        self.expected_profit * 2f64.powf(-self.age_seconds(now) / half_life_seconds)
+    }
+
+    /// Expected profit under the default 30-second half-life
+    pub fn current_expected_profit(&self, now: DateTime<Utc>) -> f64 {
+        self.expected_profit_at(now, OPPORTUNITY_HALF_LIFE_SECONDS)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>, max_age_seconds: f64) -> bool {
+        self.age_seconds(now) > max_age_seconds
+    }
+
+    /// Seconds since the scan, never negative
+    fn age_seconds(&self, now: DateTime<Utc>) -> f64 {
+        ((now - self.created_at).num_milliseconds() as f64 / 1000.0).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OpportunityType {
+    Arbitrage,
+    MomentumBreakout,
+    MeanReversion,
+    VolumeSpike,
+    MarketMaking,
+}
+
+impl OpportunityType {
+    pub const COUNT: usize = 5;
+
+    /// Column in the coordinator's success matrix
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Arbitrage => 0,
+            Self::MomentumBreakout => 1,
+            Self::MeanReversion => 2,
+            Self::VolumeSpike => 3,
+            Self::MarketMaking => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BotStrategy {
+    MarketMaking,
+    Arbitrage,
+    Momentum,
+    MeanReversion,
+    Volatility,
+}
+
+impl BotStrategy {
+    /// Strategies rotate across bot ids
+    pub fn for_bot(bot_id: usize) -> Self {
+        match bot_id % 5 {
+            0 => Self::MarketMaking,
+            1 => Self::Arbitrage,
+            2 => Self::Momentum,
+            3 => Self::MeanReversion,
+            _ => Self::Volatility,
+        }
+    }
+
+    /// Whether the opportunity type is what the strategy was built for
+    pub fn fits(&self, opportunity_type: &OpportunityType) -> bool {
+        matches!(
+            (self, opportunity_type),
+            (Self::MarketMaking, OpportunityType::MarketMaking)
+                | (Self::Arbitrage, OpportunityType::Arbitrage)
+                | (Self::Momentum, OpportunityType::MomentumBreakout)
+                | (Self::MeanReversion, OpportunityType::MeanReversion)
+                | (Self::Volatility, OpportunityType::VolumeSpike)
+        )
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::MarketMaking => "Market Making",
+            Self::Arbitrage => "Arbitrage",
+            Self::Momentum => "Momentum",
+            Self::MeanReversion => "Mean Reversion",
+            Self::Volatility => "Volatility",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BotPosition {
+    pub id: String,
+    pub bot_id: usize,
+    pub exchange: String,
+    pub pair: String,
+    pub token_address: String,
+    pub side: Side,
+    pub size: f64,
+    pub leveraged_size: f64,
+    pub entry_price: f64,
+    pub target_price: f64,
+    pub stop_loss: f64,
+    pub leverage: f64,
+    pub opened_at: DateTime<Utc>,
+    pub status: PositionStatus,
+    pub exit_price: Option<f64>,      // NEW: Exit price
+    pub exit_reason: Option<ExitReason>, // NEW: Why position was closed
+    pub closed_at: Option<DateTime<Utc>>, // NEW: When position was closed
+    pub book: StrikeBoxPosition, // Unlevered Strike Box accounting; all P&L comes from here
+    pub exit_trigger_assumption: Option<TriggerPathAssumption>, // Ordered the target/stop exit, for audit
+}
+
+impl BotPosition {
+    pub fn open(bot_id: usize, opportunity: &MarketOpportunity, side: Side, size: f64, leveraged_size: f64) -> Self {
+        let opened_at = Utc::now();
+        let price = |x: f64| conv::price_from_f64(x).unwrap_or(Decimal::ZERO);
+        let entry_price = price(opportunity.entry_price);
+        let size_usd = conv::usd_from_f64(size).unwrap_or(Decimal::ZERO);
+        
+        // Single full-size target and the fixed stop; Strike Box's trailing stop stays off.
+        // Leverage only sets where the venue liquidates; P&L stays unlevered.
+        let book = StrikeBoxPosition {
+            execution_id: ::uuid::Uuid::new_v4(),
+            token_address: opportunity.token_address.clone(),
+            token_symbol: opportunity.pair.clone(),
+            direction: side.into(),
+            entry_price,
+            current_price: entry_price,
+            position_size_tokens: if entry_price > Decimal::ZERO { size_usd / entry_price } else { Decimal::ZERO },
+            position_size_usd: size_usd,
+            remaining_size_pct: Decimal::ONE,
+            liquidity_at_entry: conv::usd_from_f64(opportunity.liquidity_usd).unwrap_or(Decimal::ZERO),
+            safety_score_at_entry: conv::ratio_from_f64(opportunity.safety_score).unwrap_or(Decimal::ZERO),
+            holder_count_at_entry: 0,
+            stop_loss_price: price(opportunity.stop_loss),
+            take_profit_targets: vec![TpTarget {
+                price: price(opportunity.target_price),
+                hit: false,
+                exit_pct: Decimal::ONE,
+            }],
+            risk_approval_id: ::uuid::Uuid::nil(),
+            opened_at,
+            time_stop_at: Some(opened_at + Duration::seconds(MAX_POSITION_TIME_SECONDS)),
+            status: StrikeBoxPositionStatus::Open,
+            closed_at: None,
+            trailing_stop_active: false,
+            trailing_stop_high: None,
+            trailing_activation_price: None,
+            unrealized_pnl_usd: Decimal::ZERO,
+            unrealized_pnl_pct: Decimal::ZERO,
+            max_favorable_excursion_pct: Decimal::ZERO,
+            max_adverse_excursion_pct: Decimal::ZERO,
+            leverage: conv::decimal_from_f64(opportunity.leverage).unwrap_or(Decimal::ONE).max(Decimal::ONE),
+            // Same key the scanner's snapshot charges under
+            venue: if DEX_VENUES.contains(&opportunity.exchange.as_str()) {
+                DEX_VENUE.to_string()
+            } else {
+                opportunity.exchange.clone()
+            },
+            safety_breakdown_at_entry: None,
+            score_history: Vec::new(),
+        };
+        
+        Self {
+            id: format!("BOT{}_POS_{}", bot_id, uuid::Uuid::new_v4()),
+            bot_id,
+            exchange: opportunity.exchange.clone(),
+            pair: opportunity.pair.clone(),
+            token_address: opportunity.token_address.clone(),
+            side,
+            size,
+            leveraged_size,
+            entry_price: opportunity.entry_price,
+            target_price: opportunity.target_price,
+            stop_loss: opportunity.stop_loss,
+            leverage: opportunity.leverage,
+            opened_at,
+            status: PositionStatus::Open,
+            exit_price: None,
+            exit_reason: None,
+            closed_at: None,
+            book,
+            exit_trigger_assumption: None,
+        }
+    }
+
+    /// Leveraged exposure per unit of the unlevered book
+    pub fn exposure_multiplier(&self) -> f64 {
+        if self.size > 0.0 {
+            self.leveraged_size / self.size
+        } else {
+            self.leverage
+        }
+    }
+
+    /// Revalue at `price`; a price that will not convert leaves the book untouched
+    pub fn mark(&mut self, price: f64) -> MarkToMarketResult {
+        let price = conv::price_from_f64(price).unwrap_or(self.book.current_price);
+        let liquidity = self.book.liquidity_at_entry;
+        self.book.mark_to_market(price, liquidity)
+    }
+
+    /// Leveraged unrealized P&L at the last mark
+    pub fn unrealized_pnl(&self) -> f64 {
+        conv::usd_to_f64(self.book.unrealized_pnl_usd) * self.exposure_multiplier()
+    }
+
+    /// Mark at `price` and return the exit price and reason if the move reached the target or
+    /// the stop, or the time stop has passed. Target and stop fill at their level, in the order
+    /// StrikeBoxEngine::resolve_triggers gives when one move reached both.
+    pub fn update(&mut self, price: f64, config: &StrikeBoxConfig) -> Option<(f64, ExitReason)> {
+        let prev_price = self.book.current_price;
+        self.mark(price);
+        let resolution = StrikeBoxEngine::resolve_triggers(&self.book, prev_price, self.book.current_price, config);
+        
+        if let Some(event) = resolution.events.first() {
+            self.exit_trigger_assumption = Some(resolution.assumption);
+            let reason = match event {
+                TriggerEvent::TakeProfit { .. } => ExitReason::TargetHit,
+                TriggerEvent::StopLoss { .. } => ExitReason::StopLoss,
+                TriggerEvent::Liquidation { .. } => ExitReason::Liquidated,
+            };
+            Some((conv::price_to_f64(event.price()), reason))
+        } else if self.book.is_past_time_stop() {
+            Some((conv::price_to_f64(self.book.current_price), ExitReason::TimeLimit))
+        } else {
+            None
+        }
+    }
+
+    /// Taker fees on the leveraged notional, in at entry and out at `exit_price`
+    pub fn round_trip_fees(&self, exit_price: f64, fees: &FeeModel) -> f64 {
+        let schedule = fees.for_venue(&self.book.venue);
+        let notional = |usd: f64| conv::decimal_to_f64(schedule.fee_usd(conv::usd_from_f64(usd).unwrap_or(Decimal::ZERO), false));
+        let exit_notional = if self.entry_price > 0.0 {
+            self.leveraged_size * exit_price / self.entry_price
+        } else {
+            self.leveraged_size
+        };
+        notional(self.leveraged_size) + notional(exit_notional)
+    }
+
+    /// Unlevered return at the last mark less round-trip fees as a share of notional
+    pub fn net_return(&self, fees: &FeeModel) -> f64 {
+        let gross = conv::decimal_to_f64(self.book.unrealized_pnl_pct);
+        if self.leveraged_size <= 0.0 {
+            return gross;
+        }
+        let current_price = conv::price_to_f64(self.book.current_price);
+        gross - self.round_trip_fees(current_price, fees) / self.leveraged_size
+    }
+
+    /// Exit at the mark once the return clears QUICK_PROFIT_THRESHOLD after fees; a gross gain
+    /// the fees would eat is held for the target, stop or time limit
+    pub fn quick_profit_exit(&self, fees: &FeeModel) -> Option<(f64, ExitReason)> {
+        (self.net_return(fees) >= QUICK_PROFIT_THRESHOLD)
+            .then(|| (conv::price_to_f64(self.book.current_price), ExitReason::QuickProfit))
+    }
+
+    /// Mark at `exit_price`, exit the whole book and return the leveraged realized P&L net of
+    /// round-trip fees
+    pub fn close(&mut self, exit_price: f64, reason: ExitReason, fees: &FeeModel) -> f64 {
+        self.mark(exit_price);
+        let profit = self.unrealized_pnl() - self.round_trip_fees(exit_price, fees);
+        self.book.apply_partial_exit(self.book.remaining_size_pct);
+        if matches!(reason, ExitReason::Liquidated) {
+            self.book.status = StrikeBoxPositionStatus::Liquidated;
+        }
+        
+        self.status = PositionStatus::Closed;
+        self.exit_price = Some(exit_price);
+        self.exit_reason = Some(reason);
+        self.closed_at = Some(Utc::now());
+        profit
+    }
+
+    /// Kelly-fraction size scaled down by volatility, using `size` as the capital base
+    pub fn risk_adjusted_size(&self, safety_score: f64, volatility: f64, max_kelly: f64) -> f64 {
+        if self.entry_price <= 0.0 {
+            return 0.0;
+        }
+        
+        let expected_return = (self.target_price - self.entry_price).abs() / self.entry_price;
+        if expected_return <= 0.0 {
+            return 0.0;
+        }
+        
+        // Expected loss weighted by the probability of hitting the stop
+        let confidence = safety_score.clamp(0.0, 1.0);
+        let stop_distance = (self.entry_price - self.stop_loss).abs() / self.entry_price;
+        let loss_return = (1.0 - confidence) * stop_distance;
+        
+        let kelly_fraction = ((confidence * expected_return - loss_return) / expected_return)
+            .clamp(0.0, max_kelly);
+        let volatility_adjustment = (volatility.max(0.0) * VOLATILITY_SIZE_DAMPING)
+            .min(MAX_VOLATILITY_ADJUSTMENT);
+        
+        self.size * kelly_fraction * (1.0 - volatility_adjustment)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BotSizingConfig {
+    pub max_kelly_fraction: f64,    // Cap on the Kelly fraction of bot capital
+    pub volatility_scaling: bool,   // Shrink size as volatility rises
+    pub safety_score_scaling: bool, // Use safety score as win probability
+    pub max_concurrent_positions: usize, // Open positions a bot may hold at once
+    pub max_exposure_ratio: f64,    // Open leveraged notional cap, in multiples of bot capital
+    pub strict_sizing: bool,        // Skip (rather than clamp) strikes that breach the pool limit
+}
+
+impl Default for BotSizingConfig {
+    fn default() -> Self {
+        Self {
+            max_kelly_fraction: 0.25, // Quarter Kelly
+            volatility_scaling: true,
+            safety_score_scaling: true,
+            max_concurrent_positions: MAX_BOT_POSITIONS,
+            max_exposure_ratio: MAX_BOT_EXPOSURE_RATIO,
+            strict_sizing: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PositionLimitError {
+    MaxPositionsReached,
+    MaxExposureReached { current: f64, limit: f64 },
+    DuplicatePosition { pair: String },
+}
+
+impl std::fmt::Display for PositionLimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionLimitError::MaxPositionsReached => write!(f, "bot is at its open position limit"),
+            PositionLimitError::MaxExposureReached { current, limit } => write!(
+                f, "open exposure ${:.2} would exceed bot cap ${:.2}", current, limit),
+            PositionLimitError::DuplicatePosition { pair } => write!(f, "bot already holds {}", pair),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ExitReason {
+    TargetHit,      // Hit target price - take profit
+    StopLoss,       // Stop loss triggered
+    QuickProfit,    // Quick profit > 0.5% - exit immediately
+    TimeLimit,      // Maximum time limit reached (1 minute)
+    MaxChecks,      // Safety limit reached
+    Liquidated,     // Venue liquidated the leveraged position before the stop
+}
+
+impl ExitReason {
+    /// Strike Box exit type this reason is logged as; quick-profit exits are discretionary
+    pub fn exit_type(&self) -> ExitType {
+        match self {
+            Self::TargetHit => ExitType::TakeProfit1,
+            Self::StopLoss => ExitType::StopLoss,
+            Self::QuickProfit => ExitType::Manual,
+            Self::TimeLimit | Self::MaxChecks => ExitType::TimeStop,
+            Self::Liquidated => ExitType::Liquidation,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::TargetHit => "Target Hit",
+            Self::StopLoss => "Stop Loss",
+            Self::QuickProfit => "Quick Profit",
+            Self::TimeLimit => "Time Limit (1 min)",
+            Self::MaxChecks => "Max Checks",
+            Self::Liquidated => "Liquidated",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TradeResult {
+    pub success: bool,
+    pub execution_time_ms: u64,
+    pub filled_price: f64,
+    pub filled_quantity: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Long,
+    Short,
+}
+
+impl Side {
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::Long => Side::Short,
+            Side::Short => Side::Long,
+        }
+    }
+}
+
+impl From<Side> for StrikeBoxDirection {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Long => StrikeBoxDirection::Long,
+            Side::Short => StrikeBoxDirection::Short,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PositionStatus {
+    Open,
+    Closed,
+    Partial,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SizeCapSource {
+    BotCapital,     // 95% of bot capital was the binding limit
+    StrikeBox,      // Strike Box position size capped notional
+    PoolPct,        // Leveraged size clamped to max order vs pool
+}
+
+impl SizeCapSource {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::BotCapital => "Bot Capital",
+            Self::StrikeBox => "Strike Box",
+            Self::PoolPct => "Pool %",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PositionSizing {
+    pub notional: f64,
+    pub leveraged_size: f64,
+    pub cap_source: SizeCapSource,
+    pub pool_violation: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -1145,14 +3421,17 @@ pub struct StrikeResult {
     pub profit: f64,
     pub execution_time_ms: u64,
     pub success: bool,
+    pub entered: bool,             // False when skipped or rejected before any fill
+    pub cap_source: SizeCapSource, // Which limit bound the position size
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BotPerformance {
     pub trades_won: u32,
     pub trades_lost: u32,
     pub total_profit: f64,
     pub total_loss: f64,
+    pub trade_returns: Vec<f64>, // Profit over the capital each trade was made from, oldest first
 }
 
 impl BotPerformance {
@@ -1162,10 +3441,12 @@ impl BotPerformance {
             trades_lost: 0,
             total_profit: 0.0,
             total_loss: 0.0,
+            trade_returns: Vec::new(),
         }
     }
 
-    pub fn add_trade(&mut self, won: bool, profit: f64) {
+    /// `capital` is the bot's capital before the trade's profit is added
+    pub fn add_trade(&mut self, won: bool, profit: f64, capital: f64) {
         if won {
             self.trades_won += 1;
             self.total_profit += profit;
@@ -1173,6 +3454,45 @@ impl BotPerformance {
             self.trades_lost += 1;
             self.total_loss += profit.abs();
         }
+        if capital > 0.0 {
+            self.trade_returns.push(profit / capital);
+        }
+    }
+
+    fn mean_return(&self) -> Option<f64> {
+        if self.trade_returns.is_empty() {
+            return None;
+        }
+        Some(self.trade_returns.iter().sum::<f64>() / self.trade_returns.len() as f64)
+    }
+
+    /// Mean excess return over `target_return` per unit of downside deviation (root mean
+    /// square shortfall below the target, over all trades). None without trades or shortfalls.
+    pub fn sortino_ratio(&self, target_return: f64) -> Option<f64> {
+        let mean = self.mean_return()?;
+        let shortfall_sq: f64 = self
+            .trade_returns
+            .iter()
+            .map(|r| (r - target_return).min(0.0).powi(2))
+            .sum();
+        let downside_deviation = (shortfall_sq / self.trade_returns.len() as f64).sqrt();
+        (downside_deviation > 0.0).then(|| (mean - target_return) / downside_deviation)
+    }
+
+    /// Compounded return over all trades per unit of `max_drawdown` (a fraction, e.g. 0.2)
+    pub fn calmar_ratio(&self, max_drawdown: f64) -> Option<f64> {
+        if self.trade_returns.is_empty() || max_drawdown <= 0.0 {
+            return None;
+        }
+        let compounded = self.trade_returns.iter().fold(1.0, |acc, r| acc * (1.0 + r)) - 1.0;
+        Some(compounded / max_drawdown)
+    }
+
+    /// Returns above `threshold` over shortfalls below it; None without trades or shortfalls
+    pub fn omega_ratio(&self, threshold: f64) -> Option<f64> {
+        let gains: f64 = self.trade_returns.iter().map(|r| (r - threshold).max(0.0)).sum();
+        let losses: f64 = self.trade_returns.iter().map(|r| (threshold - r).max(0.0)).sum();
+        (losses > 0.0).then(|| gains / losses)
     }
 }
 
@@ -1186,54 +3506,989 @@ pub struct AggregatedStats {
     pub avg_leverage: f64,
     pub max_leverage: f64,
     pub risk_utilization: f64,
+    pub cap_source_counts: HashMap<SizeCapSource, u32>,
 }
 
 // ==================== MAIN EXECUTION ====================
 
 pub async fn launch_hummingbot_array() {
-    println!("\n🚀 INITIALIZING HUMMINGBOT ARRAY SYSTEM");
-    println!("═══════════════════════════════════════════════════════════════");
-    println!("📊 Configuration:");
-    println!("   • Initial Capital: $800,000");
-    println!("   • Number of Bots: 25");
-    println!("   • Capital per Bot: $32,000");
-    println!("   • Target per Bot: 8% per cycle");
+    info!(capital = INITIAL_CAPITAL, bots = NUM_BOTS, "initializing hummingbot array");
+    if telemetry::pretty_banners() {
+        println!("\n🚀 INITIALIZING HUMMINGBOT ARRAY SYSTEM");
+        println!("═══════════════════════════════════════════════════════════════");
+        println!("📊 Configuration:");
+        println!("   • Initial Capital: $800,000");
+        println!("   • Number of Bots: 25");
+        println!("   • Capital per Bot: $32,000");
+        println!("   • Target per Bot: 8% per cycle");
         println!("   • Combined Target: 200% every 7 days");
-    println!("   • Leverage Range: 3-5x (conservative)");
-    println!("═══════════════════════════════════════════════════════════════");
+        println!("   • Leverage Range: 3-5x (conservative)");
+        println!("═══════════════════════════════════════════════════════════════");
+    }
     
     let mut array = HummingbotArray::new().await;
     
-    println!("\n✅ Array initialized successfully");
-    println!("🤖 Deploying 25 specialized bots:");
-    println!("   • 5 Market Making bots");
-    println!("   • 5 Arbitrage bots");
-    println!("   • 5 Momentum bots");
-    println!("   • 5 Mean Reversion bots");
-    println!("   • 5 Volatility bots");
+    // Warm start from the last run's history; the same file is rewritten on shutdown
+    let bootstrap_path = std::env::var("STRIKE_BOX_BOOTSTRAP").ok();
+    if let Some(path) = &bootstrap_path {
+        array = array.with_bootstrap(path);
+    }
+    
+    // Token bytecode is scanned for rug-pull patterns when an RPC endpoint is configured
+    if let Ok(rpc_url) = std::env::var("ETH_RPC_URL") {
+        array = array.with_rug_pull_rpc(rpc_url).await;
+    } else {
+        info!("ETH_RPC_URL not set, token bytecode goes unscanned");
+    }
     
-    println!("\n⚡ Starting coordinated strike operations...\n");
+    // Logs evicted from the strike box's ring buffers are appended here rather than lost
+    if let Ok(path) = std::env::var("STRIKE_BOX_AUDIT_SPILL") {
+        array = array.with_audit_sink(Arc::new(JsonlAuditSink::new(path)));
+    }
     
-    array.execute_coordinated_strike().await;
-}
-
-// Random number generation helper
-mod rand {
-    pub fn random<T>() -> T 
-    where
-        rand::distributions::Standard: rand::distributions::Distribution<T>,
-    {
-        use rand::Rng;
-        rand::thread_rng().gen()
+    // Completed cycles survive restarts and feed the report's comparisons and projections
+    if let Ok(path) = std::env::var("CYCLE_HISTORY_FILE") {
+        array = array.with_cycle_store(Arc::new(JsonlCycleStore::new(path)));
+    }
+    
+    // Instruments from config replace the default DEX pairs
+    if let Ok(path) = std::env::var("SYMBOL_REGISTRY") {
+        match SymbolRegistry::load_json(&path) {
+            Ok(symbols) => array = array.with_symbol_registry(symbols.shared()),
+            Err(e) => warn!("⚠️  Keeping default instruments: {}", e),
+        }
+    }
+    
+    // CoinGecko metadata supplies listing ages and sectors when a key is configured, and its
+    // prices drive the market index
+    let market_data: Option<Arc<dyn MarketDataProvider>> = match std::env::var("COINGECKO_API_KEY") {
+        Ok(api_key) => {
+            let config = ApiConfig { api_key, api_secret: String::new(), testnet: false, rate_limit_per_minute: 30 };
+            let mut coingecko = CoinGeckoClient::new(config);
+            if let Ok(path) = std::env::var("COINGECKO_METADATA_CACHE") {
+                coingecko = coingecko.with_metadata_cache(path);
+            }
+            let platform = std::env::var("COINGECKO_PLATFORM").unwrap_or_else(|_| "ethereum".to_string());
+            let coingecko = Arc::new(coingecko);
+            array = array.with_token_enricher(Arc::new(TokenEnricher::new(coingecko.clone(), platform)));
+            Some(coingecko)
+        }
+        Err(_) => {
+            info!("COINGECKO_API_KEY not set, token ages come from on-chain lookups and no market index runs");
+            None
+        }
+    };
+    
+    // Keep the watcher alive for the lifetime of the array
+    #[cfg(feature = "hot-reload")]
+    let _config_watcher = match std::env::var("STRIKE_BOX_CONFIG") {
+        Ok(path) => match array.watch_strike_box_config(&path) {
+            Ok(watcher) => {
+                info!("🔧 Watching {} for Strike Box config changes", path);
+                Some(watcher)
+            }
+            Err(e) => {
+                warn!("⚠️  Could not watch {}: {}", path, e);
+                None
+            }
+        },
+        Err(_) => None,
+    };
+    
+    // Exposure, drawdown and book metrics feed the monitoring alert rules
+    let monitoring = Arc::new(MonitoringSystem::new());
+    monitoring.start().await;
+    array.start_monitoring(monitoring.clone());
+    if let Some(provider) = market_data {
+        array.start_market_index(MarketIndex::new(IndexConfig::default()), provider, monitoring);
+    }
+    
+    if telemetry::pretty_banners() {
+        println!("\n✅ Array initialized successfully");
+        println!("🤖 Deploying 25 specialized bots:");
+        println!("   • 5 Market Making bots");
+        println!("   • 5 Arbitrage bots");
+        println!("   • 5 Momentum bots");
+        println!("   • 5 Mean Reversion bots");
+        println!("   • 5 Volatility bots");
+        
+        println!("\n⚡ Starting coordinated strike operations...\n");
+    }
+    
+    tokio::select! {
+        _ = array.execute_coordinated_strike() => {}
+        _ = tokio::signal::ctrl_c() => info!("shutdown requested"),
+    }
+    
+    if let Some(path) = &bootstrap_path {
+        match array.export_bootstrap(path).await {
+            Ok(()) => info!(path = %path, "bootstrap saved for the next warm start"),
+            Err(e) => warn!("⚠️  Bootstrap not saved: {}", e),
+        }
     }
 }
 
-// UUID generation
+// UUID generation: unique per process without drawing from the simulation RNG
 mod uuid {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
     pub struct Uuid;
     impl Uuid {
         pub fn new_v4() -> String {
-            format!("{:x}", rand::random::<u128>())
+            let started = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+            format!("{:x}-{:x}", started, NEXT_ID.fetch_add(1, Ordering::Relaxed))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_opportunity(liquidity_usd: f64, leverage: f64, strike_box_size: f64) -> MarketOpportunity {
+        MarketOpportunity {
+            exchange: "uniswap".to_string(),
+            pair: "0x1234/USDC".to_string(),
+            opportunity_type: OpportunityType::VolumeSpike,
+            expected_profit: 0.08,
+            confidence: 0.85,
+            volatility: 0.03,
+            volume_ratio: 3.0,
+            entry_price: 1.0,
+            target_price: 1.15,
+            stop_loss: 0.95,
+            leverage,
+            safety_score: 0.85,
+            token_address: "0x1234".to_string(),
+            strike_box_size,
+            liquidity_usd,
+            strike_box_tp_prices: vec![1.15, 1.30, 1.50],
+            direction: DirectionDecision::classify(DirectionSignals {
+                volume_ratio: 3.0,
+                price_change_pct: 0.05,
+                accumulation_score: 0.5,
+                distribution_score: 0.1,
+            }),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn create_test_bot() -> HummingBot {
+        HummingBot::new(
+            0,
+            CAPITAL_PER_BOT,
+            BotStrategy::Momentum,
+            Arc::new(RwLock::new(CapitalPool::new(INITIAL_CAPITAL))),
+            Arc::new(StrikeCoordinator::new()),
+        )
+    }
+
+    #[test]
+    fn test_performance_ratios_from_trade_returns() {
+        let mut performance = BotPerformance::new();
+        assert_eq!(performance.sortino_ratio(0.0), None);
+        // Returns of +10%, -5%, +20%, -10% on $1,000
+        for profit in [100.0, -50.0, 200.0, -100.0] {
+            performance.add_trade(profit > 0.0, profit, 1_000.0);
+        }
+        assert_eq!(performance.trade_returns, vec![0.1, -0.05, 0.2, -0.1]);
+
+        // Mean 0.0375; downside deviation sqrt((0.0025 + 0.01) / 4)
+        let sortino = performance.sortino_ratio(0.0).unwrap();
+        assert!((sortino - 0.0375 / 0.003125f64.sqrt()).abs() < 1e-12);
+        // Gains 0.3 over shortfalls 0.15
+        assert!((performance.omega_ratio(0.0).unwrap() - 2.0).abs() < 1e-12);
+        let compounded = 1.1 * 0.95 * 1.2 * 0.9 - 1.0;
+        assert!((performance.calmar_ratio(0.1).unwrap() - compounded / 0.1).abs() < 1e-12);
+        assert_eq!(performance.calmar_ratio(0.0), None);
+        assert_eq!(performance.omega_ratio(-1.0), None);
+    }
+
+    #[test]
+    fn test_sizing_clamps_to_pool_limit() {
+        let bot = create_test_bot();
+        // $600k liquidity: Strike Box size = min(1% of $800k, 1% of pool) = $6,000
+        let opportunity = create_test_opportunity(600_000.0, 5.0, 6_000.0);
+        let sizing = bot.size_for_opportunity(&opportunity);
+
+        assert!(sizing.pool_violation);
+        assert_eq!(sizing.cap_source, SizeCapSource::PoolPct);
+        assert!((sizing.leveraged_size - 6_000.0).abs() < 1e-6);
+        assert!((sizing.notional - 1_200.0).abs() < 1e-6);
+
+        // Shorts get half the pool share: 0.5% of $600k
+        let mut short = create_test_opportunity(600_000.0, 5.0, 6_000.0);
+        short.direction = DirectionDecision::classify(distribution_signals());
+        assert_eq!(short.side(), Side::Short);
+        let sizing = bot.size_for_opportunity(&short);
+        assert_eq!(sizing.cap_source, SizeCapSource::PoolPct);
+        assert!((sizing.leveraged_size - 3_000.0).abs() < 1e-6);
+        assert!((sizing.notional - 600.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sizing_respects_strike_box_ceiling() {
+        let bot = create_test_bot();
+        let opportunity = create_test_opportunity(1_000_000.0, 1.0, 5_000.0);
+        let sizing = bot.size_for_opportunity(&opportunity);
+
+        assert!(!sizing.pool_violation);
+        assert_eq!(sizing.cap_source, SizeCapSource::StrikeBox);
+        assert!((sizing.notional - 5_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_risk_adjusted_size_shrinks_with_volatility() {
+        let bot = create_test_bot();
+        let opportunity = create_test_opportunity(1_000_000.0, 1.0, 0.0);
+        let position = bot.build_position(&opportunity, CAPITAL_PER_BOT, CAPITAL_PER_BOT);
+
+        let calm = position.risk_adjusted_size(0.85, 0.01, 0.25);
+        let volatile = position.risk_adjusted_size(0.85, 0.05, 0.25);
+        assert!(calm > volatile);
+        assert!(calm <= CAPITAL_PER_BOT * 0.25);
+        let floor = position.risk_adjusted_size(0.85, 1.0, 0.25);
+        assert!((floor - CAPITAL_PER_BOT * 0.25 * 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_risk_adjusted_size_respects_sizing_config() {
+        let opportunity = create_test_opportunity(1_000_000.0, 1.0, 0.0);
+        let scaled = create_test_bot().size_for_opportunity(&opportunity);
+        let unscaled = create_test_bot()
+            .with_sizing_config(BotSizingConfig {
+                max_kelly_fraction: 0.25,
+                volatility_scaling: false,
+                safety_score_scaling: true,
+                ..BotSizingConfig::default()
+            })
+            .size_for_opportunity(&opportunity);
+
+        assert!(unscaled.notional > scaled.notional);
+        assert_eq!(unscaled.cap_source, SizeCapSource::BotCapital);
+    }
+
+    #[tokio::test]
+    async fn test_strict_sizing_skips_violations() {
+        let mut bot = create_test_bot()
+            .with_sizing_config(BotSizingConfig { strict_sizing: true, ..BotSizingConfig::default() });
+        let opportunity = create_test_opportunity(600_000.0, 5.0, 6_000.0);
+        let result = bot.execute_strike(opportunity).await;
+
+        assert!(!result.success);
+        assert_eq!(result.profit, 0.0);
+        assert_eq!(result.cap_source, SizeCapSource::PoolPct);
+        assert!(bot.positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_monitoring_receives_strike_box_portfolio() {
+        let array = HummingbotArray::with_rng(SimRng::seeded(7)).await;
+        let monitoring = Arc::new(MonitoringSystem::new());
+        array.start_monitoring(monitoring.clone());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        assert_eq!(monitoring.get_metric(&crate::monitoring::MetricType::AvailableCapital).await, Some(INITIAL_CAPITAL));
+        assert_eq!(monitoring.get_metric(&crate::monitoring::MetricType::OpenLongPositions).await, Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_aggregator_history_is_bounded() {
+        let mut bot = create_test_bot()
+            .with_sizing_config(BotSizingConfig { strict_sizing: true, ..BotSizingConfig::default() });
+        let template = bot.execute_strike(create_test_opportunity(600_000.0, 5.0, 6_000.0)).await;
+
+        let mut aggregator = PerformanceAggregator::with_history_capacity(2);
+        for leverage in [5.0, 1.0, 2.0] {
+            let mut result = template.clone();
+            result.position.leverage = leverage;
+            aggregator.add_result(result);
+        }
+
+        let stats = aggregator.get_stats();
+        assert_eq!(stats.total_strikes, 3);
+        assert_eq!(stats.max_leverage, 2.0);
+        assert_eq!(stats.avg_leverage, 1.5);
+        assert_eq!(aggregator.history_len(), 2);
+        assert_eq!(aggregator.evicted_count(), 1);
+    }
+
+    fn create_token_opportunity(token_address: &str, volume_ratio: f64) -> MarketOpportunity {
+        let mut opportunity = create_test_opportunity(1_000_000.0, 3.0, 5_000.0);
+        opportunity.token_address = token_address.to_string();
+        opportunity.pair = format!("{}/USDC", token_address);
+        opportunity.volume_ratio = volume_ratio;
+        opportunity
+    }
+
+    #[tokio::test]
+    async fn test_assignments_respect_bots_per_token_cap() {
+        let coordinator = StrikeCoordinator::new();
+        let guard = ArrayRiskGuard::new(ArrayRiskConfig::default(), INITIAL_CAPITAL);
+
+        // One hot opportunity every bot would prefer, then lower-ranked alternates
+        let mut opportunities = vec![create_token_opportunity("0xhot", 10.0)];
+        opportunities.extend((0..8).map(|i| create_token_opportunity(&format!("0xalt{}", i), 3.0)));
+
+        let assignments = coordinator.assign_targets(&opportunities, NUM_BOTS, &guard).await;
+
+        let on_hot = assignments.values().filter(|o| o.token_address == "0xhot").count();
+        assert_eq!(on_hot, MAX_BOTS_PER_TOKEN);
+        assert_eq!(assignments.len(), NUM_BOTS);
+        let mut per_token: HashMap<&str, usize> = HashMap::new();
+        for opportunity in assignments.values() {
+            *per_token.entry(opportunity.token_address.as_str()).or_insert(0) += 1;
+        }
+        assert!(per_token.values().all(|&bots| bots <= MAX_BOTS_PER_TOKEN));
+    }
+
+    #[test]
+    fn test_array_profit_matches_strike_box_for_scripted_paths() {
+        for (side, target, stop, path, unlevered_profit, exit_type) in [
+            (Side::Long, 1.15, 0.95, vec![1.002, 0.996, 1.004, 1.2], 150.0, ExitType::TakeProfit1),
+            (Side::Short, 0.85, 1.05, vec![0.998, 1.003, 1.06], -50.0, ExitType::StopLoss),
+        ] {
+            for leverage in [1.0, 3.0] {
+                let mut opportunity = create_test_opportunity(1_000_000.0, leverage, 0.0);
+                opportunity.target_price = target;
+                opportunity.stop_loss = stop;
+                let mut position = BotPosition::open(0, &opportunity, side, 1_000.0, 1_000.0 * leverage);
+                let mut reference = position.book.clone();
+                let liquidity = reference.liquidity_at_entry;
+
+                // Gross of fees, to compare with the book's unrealized P&L
+                let config = StrikeBoxConfig { fees: FeeModel::zero(), ..StrikeBoxConfig::default() };
+                let mut exit = None;
+                for &price in &path {
+                    reference.mark_to_market(conv::price_from_f64(price).unwrap(), liquidity);
+                    exit = position.update(price, &config).or_else(|| position.quick_profit_exit(&config.fees));
+                    if exit.is_some() {
+                        break;
+                    }
+                }
+                let (exit_price, reason) = exit.expect("scripted path should trigger an exit");
+                assert_eq!(reason.exit_type(), exit_type);
+                let profit = position.close(exit_price, reason, &config.fees);
+
+                reference.mark_to_market(conv::price_from_f64(exit_price).unwrap(), liquidity);
+                let strike_box_profit = conv::usd_to_f64(reference.unrealized_pnl_usd) * leverage;
+                assert!((profit - strike_box_profit).abs() < 1e-9);
+                assert!((profit - unlevered_profit * leverage).abs() < 1e-6);
+                assert_eq!(position.book.remaining_size_pct, Decimal::ZERO);
+                assert!(matches!(position.status, PositionStatus::Closed));
+            }
+        }
+    }
+
+    #[test]
+    fn test_quick_profit_exit_waits_for_a_gain_net_of_fees() {
+        let opportunity = create_test_opportunity(1_000_000.0, 3.0, 0.0);
+        let mut position = BotPosition::open(0, &opportunity, Side::Long, 1_000.0, 3_000.0);
+        assert_eq!(position.book.venue, DEX_VENUE);
+        let fees = FeeModel::default(); // 30 bps a side on DEX venues
+
+        // 0.5% gross clears the threshold without fees, but 0.6% of round-trip fees leave a loss
+        position.mark(1.005);
+        assert!(position.quick_profit_exit(&FeeModel::zero()).is_some());
+        assert!(position.quick_profit_exit(&fees).is_none());
+        assert!(position.net_return(&fees) < 0.0);
+
+        // 1.2% gross is 0.6% net, enough to take
+        position.mark(1.012);
+        let (exit_price, reason) = position.quick_profit_exit(&fees).expect("net gain clears the threshold");
+        assert!(matches!(reason, ExitReason::QuickProfit));
+        let round_trip = position.round_trip_fees(exit_price, &fees);
+        assert!((round_trip - 0.003 * (3_000.0 + 3_036.0)).abs() < 1e-9);
+        let profit = position.close(exit_price, reason, &fees);
+        assert!((profit - (36.0 - round_trip)).abs() < 1e-9);
+    }
+
+    fn create_test_result(bot_id: usize, opportunity: &MarketOpportunity, profit: f64) -> StrikeResult {
+        let position = create_test_bot().build_position(opportunity, 1_000.0, 3_000.0);
+        StrikeResult {
+            bot_id,
+            opportunity: opportunity.clone(),
+            position,
+            profit,
+            execution_time_ms: 100,
+            success: profit > 0.0,
+            entered: true,
+            cap_source: SizeCapSource::StrikeBox,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_assignments_cap_bots_per_target_per_round() {
+        let coordinator = StrikeCoordinator::with_config(CoordinatorConfig {
+            max_bots_per_target: 2,
+            ..CoordinatorConfig::default()
+        });
+        let guard = ArrayRiskGuard::new(ArrayRiskConfig::default(), INITIAL_CAPITAL);
+
+        // Two opportunities must not be piled onto all 25 bots
+        let opportunities = vec![
+            create_token_opportunity("0xhot", 10.0),
+            create_token_opportunity("0xwarm", 5.0),
+        ];
+        let assignments = coordinator.assign_targets(&opportunities, NUM_BOTS, &guard).await;
+
+        assert_eq!(assignments.len(), 4);
+        for token in ["0xhot", "0xwarm"] {
+            assert_eq!(assignments.values().filter(|o| o.token_address == token).count(), 2);
+        }
+        for (bot_id, opportunity) in &assignments {
+            let history = coordinator.assignment_history(*bot_id).await;
+            assert_eq!(history.len(), 1);
+            assert_eq!(history[0].token_address, opportunity.token_address);
+            assert_eq!(history[0].outcome, AssignmentOutcome::Pending);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_assignment_prefers_strategy_with_best_history() {
+        let coordinator = StrikeCoordinator::with_config(CoordinatorConfig {
+            max_bots_per_target: 1,
+            ..CoordinatorConfig::default()
+        });
+        let guard = ArrayRiskGuard::new(ArrayRiskConfig::default(), INITIAL_CAPITAL);
+        let opportunities = vec![create_token_opportunity("0xspike", 4.0)];
+
+        // No history: the volume spike goes to the first Volatility bot
+        let assignments = coordinator.assign_targets(&opportunities, NUM_BOTS, &guard).await;
+        assert_eq!(assignments.keys().copied().collect::<Vec<_>>(), vec![4]);
+
+        // Volatility bots keep losing on spikes while Momentum bots keep winning
+        for _ in 0..3 {
+            coordinator.record_result(&create_test_result(4, &opportunities[0], -200.0)).await;
+            coordinator.record_result(&create_test_result(9, &opportunities[0], -150.0)).await;
+            coordinator.record_result(&create_test_result(7, &opportunities[0], 300.0)).await;
+        }
+        assert_eq!(coordinator.assignment_history(4).await[0].outcome, AssignmentOutcome::Lost(-200.0));
+        assert!(
+            coordinator.strategy_success_rate(&BotStrategy::Momentum, &OpportunityType::VolumeSpike).await
+                > coordinator.strategy_success_rate(&BotStrategy::Volatility, &OpportunityType::VolumeSpike).await
+        );
+
+        let next = create_token_opportunity("0xspike2", 4.0);
+        let assignments = coordinator.assign_targets(&[next], NUM_BOTS, &guard).await;
+        assert_eq!(assignments.keys().copied().collect::<Vec<_>>(), vec![7]);
+    }
+
+    #[tokio::test]
+    async fn test_loss_cooldown_blocks_reassignment_to_same_token() {
+        let guard = ArrayRiskGuard::new(ArrayRiskConfig::default(), INITIAL_CAPITAL);
+        let lost = create_token_opportunity("0xrekt", 4.0);
+        let other = create_token_opportunity("0xfresh", 3.0);
+
+        let coordinator = StrikeCoordinator::new();
+        coordinator.assign_targets(&[lost.clone()], 1, &guard).await;
+        coordinator.record_result(&create_test_result(0, &lost, -500.0)).await;
+
+        assert!(coordinator.assign_targets(&[lost.clone()], 1, &guard).await.is_empty());
+        let assignments = coordinator.assign_targets(&[lost.clone(), other], 1, &guard).await;
+        assert_eq!(assignments[&0].token_address, "0xfresh");
+
+        // Skipped strikes are not losses and do not start a cooldown
+        let coordinator = StrikeCoordinator::new();
+        coordinator.assign_targets(&[lost.clone()], 1, &guard).await;
+        let mut skipped = create_test_result(0, &lost, 0.0);
+        skipped.entered = false;
+        coordinator.record_result(&skipped).await;
+        assert_eq!(coordinator.assign_targets(&[lost], 1, &guard).await.len(), 1);
+        assert_eq!(coordinator.success_rate(0, &OpportunityType::VolumeSpike).await, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_expired_opportunities_are_not_assigned() {
+        let coordinator = StrikeCoordinator::new();
+        let guard = ArrayRiskGuard::new(ArrayRiskConfig::default(), INITIAL_CAPITAL);
+        let now = Utc::now();
+
+        let mut stale = create_token_opportunity("0xstale", 5.0);
+        stale.expected_profit = 0.08;
+        stale.created_at = now - Duration::seconds(60);
+        assert!((stale.expected_profit_at(now, 30.0) - 0.02).abs() < 1e-4);
+        assert!((stale.current_expected_profit(now) - stale.expected_profit_at(now, 30.0)).abs() < 1e-12);
+        assert!(!stale.is_expired(now, 120.0));
+
+        stale.created_at = now - Duration::seconds(180);
+        assert!(stale.is_expired(now, 120.0));
+        let fresh = create_token_opportunity("0xfresh", 3.0);
+        assert_eq!(fresh.expected_profit_at(fresh.created_at, 30.0), fresh.expected_profit);
+
+        let assignments = coordinator.assign_targets(&[stale, fresh], NUM_BOTS, &guard).await;
+        assert!(!assignments.is_empty());
+        assert!(assignments.values().all(|o| o.token_address == "0xfresh"));
+    }
+
+    #[tokio::test]
+    async fn test_join_supervised_separates_panics() {
+        let mut bot = create_test_bot()
+            .with_sizing_config(BotSizingConfig { strict_sizing: true, ..BotSizingConfig::default() });
+        let template = bot.execute_strike(create_test_opportunity(600_000.0, 5.0, 6_000.0)).await;
+
+        let handles = vec![
+            (0, tokio::spawn(async move { template })),
+            (1, tokio::spawn(async { panic!("exchange client blew up") })),
+        ];
+        let (results, failures) = join_supervised(handles).await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 1);
+        assert!(failures[0].1.contains("exchange client blew up"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_bot_restarts_then_quarantines() {
+        let mut array = HummingbotArray::new().await.with_fault_tolerance(FaultToleranceConfig {
+            max_restarts: 2,
+            restart_window: Duration::minutes(60),
+        });
+        array.bots[3].lock().await.capital = 1_000.0; // Depleted before failing
+
+        assert_eq!(array.handle_bot_failure(3, "panicked: boom").await, BotStatus::Restarted);
+        assert_eq!(array.bots[3].lock().await.capital, CAPITAL_PER_BOT);
+        assert_eq!(array.handle_bot_failure(3, "panicked: boom").await, BotStatus::Restarted);
+
+        let before: f64 = {
+            let mut total = 0.0;
+            for bot in &array.bots {
+                total += bot.lock().await.capital;
+            }
+            total
+        };
+        assert_eq!(array.handle_bot_failure(3, "panicked: boom").await, BotStatus::Quarantined);
+        assert_eq!(array.bot_status(3), Some(BotStatus::Quarantined));
+        assert_eq!(array.bots[3].lock().await.capital, 0.0);
+        let mut after = 0.0;
+        for bot in &array.bots {
+            after += bot.lock().await.capital;
+        }
+        assert!((after - before).abs() < 1e-6);
+        assert!((array.bots[0].lock().await.capital - CAPITAL_PER_BOT * (1.0 + 1.0 / 24.0)).abs() < 1e-6);
+
+        let log = array.restart_log();
+        assert_eq!(log.len(), 3);
+        assert_eq!(log[1].restart_count, 2);
+        assert_eq!(log[2].action, BotStatus::Quarantined);
+
+        // Quarantined bots are never run
+        let mut assignments = HashMap::new();
+        assignments.insert(3, create_test_opportunity(1_000_000.0, 1.0, 5_000.0));
+        assert!(array.fault_tolerant_execute(&assignments).await.is_empty());
+    }
+
+    #[test]
+    fn test_guard_enforces_group_cap_and_releases_on_close() {
+        let mut guard = ArrayRiskGuard::new(
+            ArrayRiskConfig {
+                max_token_exposure_pct: 0.10,
+                max_group_exposure_pct: 0.15,
+                max_bots_per_token: 5,
+                correlation_groups: HashMap::new(),
+            },
+            100_000.0,
+        );
+        guard.set_correlation_group("0xpepe", "meme");
+        guard.set_correlation_group("0xwojak", "meme");
+
+        assert!(guard.try_open(0, "0xpepe", 9_000.0).is_ok());
+        assert!(matches!(
+            guard.try_open(1, "0xpepe", 2_000.0),
+            Err(ExposureBreach::TokenCap { .. })
+        ));
+        assert!(matches!(
+            guard.try_open(2, "0xwojak", 7_000.0),
+            Err(ExposureBreach::GroupCap { .. })
+        ));
+        assert!(guard.try_open(2, "0xother", 7_000.0).is_ok());
+
+        guard.record_close(0, "0xpepe", 9_000.0);
+        assert_eq!(guard.bots_on_token("0xpepe"), 0);
+        assert!(guard.try_open(2, "0xwojak", 7_000.0).is_ok());
+
+        let snapshot = guard.snapshot();
+        assert_eq!(snapshot.blocked_entries, 2);
+        assert_eq!(snapshot.open_notional, 14_000.0);
+        assert_eq!(snapshot.groups.len(), 2);
+    }
+
+    #[test]
+    fn test_sector_groups_tokens_without_a_configured_group() {
+        let mut guard = ArrayRiskGuard::new(
+            ArrayRiskConfig {
+                max_token_exposure_pct: 0.10,
+                max_group_exposure_pct: 0.15,
+                max_bots_per_token: 5,
+                correlation_groups: HashMap::from([("0xdoge".to_string(), "dog coins".to_string())]),
+            },
+            100_000.0,
+        );
+        guard.set_sector("0xpepe", "Meme");
+        guard.set_sector("0xwojak", "Meme");
+        guard.set_sector("0xdoge", "Meme");
+        assert_eq!(guard.group_for("0xdoge"), "dog coins");
+
+        assert!(guard.try_open(0, "0xpepe", 9_000.0).is_ok());
+        assert!(matches!(
+            guard.try_open(1, "0xwojak", 7_000.0),
+            Err(ExposureBreach::GroupCap { ref group, .. }) if group == "Meme"
+        ));
+        assert!(guard.try_open(1, "0xdoge", 7_000.0).is_ok());
+
+        // A sector learned after the open doesn't move exposure already counted
+        guard.try_open(2, "0xlate", 1_000.0).unwrap();
+        guard.set_sector("0xlate", "Meme");
+        guard.record_close(2, "0xlate", 1_000.0);
+        assert_eq!(guard.snapshot().groups.iter().map(|g| g.notional).sum::<f64>(), 16_000.0);
+    }
+
+    #[tokio::test]
+    async fn test_bot_reports_open_and_close_to_guard() {
+        let guard = Arc::new(RwLock::new(ArrayRiskGuard::new(ArrayRiskConfig::default(), INITIAL_CAPITAL)));
+        guard.write().await.record_open(7, "0x1234", 1_000.0);
+        guard.write().await.record_open(8, "0x1234", 1_000.0);
+        guard.write().await.record_open(9, "0x1234", 1_000.0);
+
+        // Token is at its bot cap, so the bot must skip without trading
+        let mut bot = create_test_bot().with_risk_guard(guard.clone());
+        let result = bot.execute_strike(create_test_opportunity(1_000_000.0, 1.0, 5_000.0)).await;
+
+        assert!(!result.success);
+        assert!(bot.positions.is_empty());
+        assert_eq!(guard.read().await.snapshot().blocked_entries, 1);
+        assert_eq!(guard.read().await.bots_on_token("0x1234"), 3);
+    }
+
+    #[tokio::test]
+    async fn test_position_limit_check() {
+        let mut bot = create_test_bot();
+        let opportunity = create_test_opportunity(1_000_000.0, 3.0, 5_000.0);
+        assert_eq!(bot.position_limit_check(&opportunity.pair, 15_000.0), Ok(()));
+
+        bot.positions.push(BotPosition::open(0, &opportunity, Side::Long, 5_000.0, 15_000.0));
+        assert_eq!(bot.total_open_exposure(), 15_000.0);
+        assert_eq!(
+            bot.position_limit_check(&opportunity.pair, 15_000.0),
+            Err(PositionLimitError::DuplicatePosition { pair: opportunity.pair.clone() })
+        );
+        // 5x of $32,000 capital
+        assert_eq!(
+            bot.position_limit_check("0x5678/USDC", 150_000.0),
+            Err(PositionLimitError::MaxExposureReached { current: 165_000.0, limit: 160_000.0 })
+        );
+        assert_eq!(bot.position_limit_check("0x5678/USDC", 145_000.0), Ok(()));
+
+        // A held pair is skipped before any trade or exposure reservation
+        let guard = Arc::new(RwLock::new(ArrayRiskGuard::new(ArrayRiskConfig::default(), INITIAL_CAPITAL)));
+        let mut bot = bot.with_risk_guard(guard.clone());
+        let result = bot.execute_strike(opportunity).await;
+        assert!(!result.entered);
+        assert_eq!(bot.positions.len(), 1);
+        assert_eq!(guard.read().await.bots_on_token("0x1234"), 0);
+
+        let bot = create_test_bot().with_sizing_config(BotSizingConfig { max_concurrent_positions: 0, ..BotSizingConfig::default() });
+        assert_eq!(bot.position_limit_check("0x5678/USDC", 1.0), Err(PositionLimitError::MaxPositionsReached));
+    }
+
+    fn create_dex_snapshot(largest_wallet_pct: Decimal) -> TokenSnapshot {
+        TokenSnapshot {
+            token_address: "0xd15t".to_string(),
+            token_symbol: "DIST".to_string(),
+            liquidity_usd: Decimal::new(750_000, 0),
+            bid_depth_usd: Decimal::new(375_000, 0),
+            ask_depth_usd: Decimal::new(375_000, 0),
+            holder_count: 60,
+            top_10_concentration_pct: Decimal::new(45, 2),
+            largest_wallet_pct,
+            token_age_hours: 48,
+            contract_verified: true,
+            is_proxy_contract: false,
+            deployment_timestamp: Utc::now() - Duration::hours(48),
+            snapshot_timestamp: Utc::now(),
+            source: SnapshotSource::Dex,
+            categories: None,
+        }
+    }
+
+    // Top wallets selling into an 8% spike up
+    fn distribution_signals() -> DirectionSignals {
+        DirectionSignals {
+            volume_ratio: 3.0,
+            price_change_pct: 0.08,
+            accumulation_score: 0.05,
+            distribution_score: 0.65,
+        }
+    }
+
+    #[test]
+    fn test_distribution_spike_classifies_short_and_validates() {
+        let decision = DirectionDecision::classify(distribution_signals());
+        assert_eq!(decision.call, DirectionCall::Short);
+        assert!(decision.confidence > DIRECTION_FLIP_MAX_CONFIDENCE);
+        assert!(decision.rationale.contains("selling into the spike"));
+
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let (decision, validation) = validate_direction(&mut engine, &create_dex_snapshot(Decimal::new(12, 2)), decision);
+        assert_eq!(decision.side(), Some(Side::Short));
+        let validation = validation.unwrap();
+        assert_eq!(validation.direction, StrikeBoxDirection::Short);
+        assert!(validation.gates.iter().any(|g| g.gate_name == "squeeze_risk"));
+
+        let mut opportunity = create_test_opportunity(1_000_000.0, 3.0, 5_000.0);
+        opportunity.direction = decision;
+        let position = BotPosition::open(0, &opportunity, opportunity.side(), 1_000.0, 3_000.0);
+        assert_eq!(position.side, Side::Short);
+        assert_eq!(position.book.direction, StrikeBoxDirection::Short);
+    }
+
+    #[tokio::test]
+    async fn test_cycle_record_carries_direction_rationale() {
+        let mut array = HummingbotArray::with_rng(SimRng::seeded(3)).await;
+        let mut short = create_test_opportunity(1_000_000.0, 3.0, 5_000.0);
+        short.direction = DirectionDecision::classify(distribution_signals());
+        let long = create_test_opportunity(1_000_000.0, 3.0, 5_000.0);
+        array.record_direction_calls(&vec![long; CYCLE_REPORT_DIRECTION_CALLS]);
+        array.record_direction_calls(std::slice::from_ref(&short));
+
+        let record = array.build_cycle_record().await;
+        assert_eq!(record.direction_counts["Long"], CYCLE_REPORT_DIRECTION_CALLS as u32);
+        assert_eq!(record.direction_counts["Short"], 1);
+        // Only the latest calls are kept, each with its inputs and rationale
+        assert_eq!(record.direction_calls.len(), CYCLE_REPORT_DIRECTION_CALLS);
+        let latest = record.direction_calls.last().unwrap();
+        assert_eq!(latest.call, DirectionCall::Short);
+        assert_eq!(latest.signals, distribution_signals());
+        assert!(latest.rationale.contains("selling into the spike"));
+
+        array.reset_cycle().await;
+        assert!(array.build_cycle_record().await.direction_calls.is_empty());
+    }
+
+    #[test]
+    fn test_squeeze_risk_short_skips_instead_of_flipping() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        // Largest wallet above the 20% squeeze threshold; longs would still validate
+        let token = create_dex_snapshot(Decimal::new(25, 2));
+        assert!(engine.validate_entry(&token, StrikeBoxDirection::Long).all_passed);
+
+        let decision = DirectionDecision::classify(distribution_signals());
+        let (decision, validation) = validate_direction(&mut engine, &token, decision);
+        assert_eq!(decision.call, DirectionCall::Skip);
+        assert!(validation.is_none());
+        assert!(decision.rationale.contains("failed squeeze_risk, skipped"));
+
+        // A weak price-only short on the same token is allowed to flip long
+        let weak = DirectionDecision::classify(DirectionSignals {
+            volume_ratio: 3.0,
+            price_change_pct: -0.04,
+            accumulation_score: 0.0,
+            distribution_score: 0.0,
+        });
+        assert_eq!(weak.call, DirectionCall::Short);
+        let (flipped, validation) = validate_direction(&mut engine, &token, weak);
+        assert_eq!(flipped.call, DirectionCall::Long);
+        assert!(validation.unwrap().all_passed);
+    }
+
+    #[test]
+    fn test_sub_dollar_entry_keeps_strike_box_levels() {
+        // Regression: a $0.23 token used to get entry 0, stop 0 and targets 0
+        let levels = strike_box_long_levels(
+            &StopLossConfig::default(),
+            &TakeProfitConfig::default(),
+            0.23,
+            0.85,
+        )
+        .unwrap();
+
+        assert!((levels.stop_loss - 0.2116).abs() < 1e-12);
+        assert!((levels.take_profits[0] - 0.2645).abs() < 1e-12);
+        assert!((levels.take_profits[2] - 0.345).abs() < 1e-12);
+
+        let micro = strike_box_long_levels(&StopLossConfig::default(), &TakeProfitConfig::default(), 0.000012, 0.5)
+            .unwrap();
+        assert!(((micro.stop_loss - 0.0000114) / 0.0000114).abs() < conv::PRICE_MAX_RELATIVE_ERROR);
+        assert!(strike_box_long_levels(&StopLossConfig::default(), &TakeProfitConfig::default(), f64::NAN, 0.5).is_err());
+    }
+
+    fn create_test_cycle(
+        week: i64,
+        starting_capital: f64,
+        cycle_profits: f64,
+        aggregator: &PerformanceAggregator,
+    ) -> CycleRecord {
+        let stats = aggregator.get_stats();
+        let cycle_start = DateTime::from_timestamp(1_700_000_000, 0).unwrap() + Duration::weeks(week);
+        CycleRecord {
+            cycle_start,
+            cycle_end: cycle_start + Duration::days(7),
+            starting_capital,
+            ending_capital: starting_capital + cycle_profits,
+            cycle_profits,
+            total_strikes: stats.total_strikes,
+            successful_strikes: stats.successful_strikes,
+            win_rate: stats.win_rate,
+            avg_leverage: stats.avg_leverage,
+            max_leverage: stats.max_leverage,
+            risk_utilization: stats.risk_utilization,
+            cap_source_counts: stats.cap_source_counts.iter().map(|(s, n)| (s.name().to_string(), *n)).collect(),
+            open_notional: 0.0,
+            tokens_held: 0,
+            largest_token: None,
+            largest_group: None,
+            entries_blocked: 0,
+            healthy_bots: NUM_BOTS,
+            restarts: 0,
+            per_bot: aggregator.bot_breakdown(),
+            per_strategy: aggregator.strategy_breakdown(),
+            direction_counts: BTreeMap::new(),
+            direction_calls: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cycle_history_persists_compares_and_projects() {
+        let opportunity = create_test_opportunity(600_000.0, 3.0, 6_000.0);
+        let mut aggregator = PerformanceAggregator::new();
+
+        // Week one: 2 of 4 won, one strike capped by the Strike Box
+        for (bot_id, profit, cap_source) in [
+            (0, 500.0, SizeCapSource::BotCapital),
+            (1, -200.0, SizeCapSource::BotCapital),
+            (5, 300.0, SizeCapSource::StrikeBox),
+            (6, -100.0, SizeCapSource::BotCapital),
+        ] {
+            let mut result = create_test_result(bot_id, &opportunity, profit);
+            result.cap_source = cap_source;
+            aggregator.add_result(result);
+        }
+        let strategies = aggregator.strategy_breakdown();
+        assert_eq!(strategies["Market Making"].strikes, 2);
+        assert_eq!(strategies["Market Making"].wins, 2);
+        assert!((strategies["Arbitrage"].net_profit + 300.0).abs() < 1e-9);
+        assert_eq!(aggregator.bot_breakdown()[&5].wins, 1);
+        let first = create_test_cycle(0, 800_000.0, 80_000.0, &aggregator);
+
+        // Week two: 3 of 4 won, half the strikes capped
+        aggregator.start_cycle();
+        assert_eq!(aggregator.get_stats().total_strikes, 0);
+        for (bot_id, profit, cap_source) in [
+            (0, 500.0, SizeCapSource::StrikeBox),
+            (1, 400.0, SizeCapSource::PoolPct),
+            (2, 300.0, SizeCapSource::BotCapital),
+            (3, -100.0, SizeCapSource::BotCapital),
+        ] {
+            let mut result = create_test_result(bot_id, &opportunity, profit);
+            result.cap_source = cap_source;
+            aggregator.add_result(result);
+        }
+        let second = create_test_cycle(1, 880_000.0, 176_000.0, &aggregator);
+
+        let path = std::env::temp_dir().join(format!("hummingbot_cycles_{}.jsonl", uuid::Uuid::new_v4()));
+        let store = JsonlCycleStore::new(&path);
+        assert!(store.load().unwrap().is_empty());
+        store.append(&first).unwrap();
+        store.append(&second).unwrap();
+        let history = store.load().unwrap();
+        assert_eq!(history, vec![first, second]);
+
+        let comparison = CycleComparison::from_history(&history, 4);
+        assert_eq!(comparison.rows.len(), 2);
+        assert_eq!(comparison.rows[0].win_rate_delta, None);
+        let latest = &comparison.rows[1];
+        assert!((latest.win_rate_delta.unwrap() - 0.25).abs() < 1e-9);
+        assert!((latest.return_pct - 0.20).abs() < 1e-9);
+        assert!((latest.return_delta.unwrap() - 0.10).abs() < 1e-9);
+        assert!((latest.cap_binding_rate - 0.5).abs() < 1e-9);
+        assert!((latest.cap_binding_delta.unwrap() - 0.25).abs() < 1e-9);
+        assert_eq!(latest.avg_leverage_delta, Some(0.0));
+        assert!(comparison.to_string().contains("+25.0"));
+        assert_eq!(CycleComparison::from_history(&history, 1).rows.len(), 1);
+
+        // Median of 10% and 20% a week is 15% a week, on the initial capital
+        let array = HummingbotArray::new().await.with_cycle_store(Arc::new(JsonlCycleStore::new(&path)));
+        assert_eq!(array.cycle_history().len(), 2);
+        assert!((median_daily_return(array.cycle_history(), 4).unwrap() - 0.15 / 7.0).abs() < 1e-12);
+        assert!((array.project_7_day_return() - INITIAL_CAPITAL * 1.15).abs() < 1e-6);
+        assert!(!array.is_on_track());
+
+        // Only the most recent log_retention.max_cycle_records cycles are kept
+        let array = HummingbotArray::new().await;
+        array.strike_box_engine.write().await.config.log_retention.max_cycle_records = 1;
+        let array = array.with_cycle_store(Arc::new(JsonlCycleStore::new(&path)));
+        assert_eq!(array.cycle_history(), &history[1..]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_registered_dex_pair_scans_with_its_contract() {
+        const PEPE: &str = "0x6982508145454ce325ddbe47a25d4ec3d2311933";
+        // Fresh arrays already resolve the default DEX pairs
+        let array = HummingbotArray::with_rng(SimRng::seeded(1)).await;
+        assert_eq!(
+            array.resolve_token_address("uniswap", "WETH/USDC").as_deref(),
+            Some("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")
+        );
+
+        let mut symbols = SymbolRegistry::new();
+        symbols.register(crate::symbols::Instrument::new("PEPE", "USDT", "uniswap", "PEPE-USDT").with_address("ethereum", PEPE));
+        let symbols = symbols.shared();
+        let mut on_uniswap = 0;
+        for seed in 0..5 {
+            let array = HummingbotArray::with_rng(SimRng::seeded(seed)).await.with_symbol_registry(symbols.clone());
+            for opportunity in array.scan_all_markets().await.into_iter().filter(|o| o.pair == "PEPE/USDT") {
+                // The registry's contract on the DEX it lists; the pair itself everywhere else
+                if opportunity.exchange == "uniswap" {
+                    assert_eq!(opportunity.token_address, PEPE);
+                    on_uniswap += 1;
+                } else {
+                    assert_eq!(opportunity.token_address, "PEPE/USDT");
+                }
+            }
+        }
+        assert!(on_uniswap > 0);
+    }
+
+    #[cfg(feature = "trade_simulator")]
+    #[tokio::test]
+    async fn test_paper_trading_journals_fills_with_exit_slippage() {
+        let bot = create_test_bot()
+            .with_rng(SimRng::seeded(7))
+            .with_paper_trading(SlippageModel { mean_bps: 20.0, std_dev_bps: 0.0 });
+        let position = bot.build_position(&create_test_opportunity(600_000.0, 3.0, 6_000.0), 1_000.0, 3_000.0);
+
+        let entry = bot.execute_entry_trade(&position).await;
+        assert!(entry.success);
+        assert_eq!(entry.filled_price, position.entry_price);
+        assert_eq!(bot.paper_trading_report().open_positions, 1);
+
+        // A 1% gain less 20 bps of slippage on the 3x leveraged size
+        let exit = bot.execute_exit_trade(&position, position.entry_price * 1.01).await;
+        assert!((exit.filled_price - position.entry_price * 1.01 * 0.998).abs() < 1e-9);
+
+        let report = bot.paper_trading_report();
+        assert_eq!(report.fills, 2);
+        assert_eq!(report.round_trips, 1);
+        assert_eq!(report.open_positions, 0);
+        assert_eq!(report.win_rate, 1.0);
+        assert!((report.realized_pnl - position.leveraged_size * (1.01 * 0.998 - 1.0)).abs() < 1e-6);
+        assert!((report.avg_exit_slippage_bps - 20.0).abs() < 1e-12);
+
+        // The journal keeps the latest fills while the report still counts them all
+        let mut journal = PaperTradeJournal::new(SlippageModel::default());
+        for _ in 0..PAPER_FILL_RETENTION {
+            journal.record_entry(&position);
+            journal.record_exit(&position, position.entry_price, 10.0);
         }
+        assert_eq!(journal.fills().len(), PAPER_FILL_RETENTION);
+        assert_eq!(journal.fills().last().unwrap().leg, FillLeg::Exit);
+        let report = journal.report();
+        assert_eq!(report.fills, 2 * PAPER_FILL_RETENTION);
+        assert_eq!(report.round_trips as usize, PAPER_FILL_RETENTION);
+        assert!((report.avg_exit_slippage_bps - 10.0).abs() < 1e-9);
     }
 }
@@ -0,0 +1,366 @@
+// Composite Market Index
+// Weighted reference index over a basket of constituents, with per-window crash detection
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use tracing::warn;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use strike_box::{MarketEvent, StrikeBoxEngine};
+use strike_box::conv;
+use crate::api::MarketDataProvider;
+use crate::monitoring::{MetricType, MonitoringSystem};
+
+const INDEX_BASE_LEVEL: f64 = 1_000.0; // Level before any price moves
+const MIN_INDEX_COVERAGE: f64 = 0.80; // Below this share of basket weight the index is degraded
+const INDEX_REFRESH_SECONDS: u64 = 60; // Constituent fetch cadence once started
+
+// ==================== CONFIGURATION ====================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexConstituent {
+    pub symbol: String,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DropWindow {
+    pub window: Duration,
+    pub threshold_pct: f64, // 0.15 = a 15% fall from the window high
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexConfig {
+    pub constituents: Vec<IndexConstituent>,
+    pub windows: Vec<DropWindow>,
+    pub min_coverage: f64,
+}
+
+impl IndexConfig {
+    /// Equal-weighted basket over the given symbols
+    pub fn equal_weighted(symbols: &[&str]) -> Self {
+        let weight = 1.0 / symbols.len().max(1) as f64;
+        Self {
+            constituents: symbols.iter()
+                .map(|s| IndexConstituent { symbol: s.to_string(), weight })
+                .collect(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            constituents: [("BTC/USDT", 0.55), ("ETH/USDT", 0.30), ("SOL/USDT", 0.15)]
+                .iter()
+                .map(|(symbol, weight)| IndexConstituent { symbol: symbol.to_string(), weight: *weight })
+                .collect(),
+            windows: vec![
+                DropWindow { window: Duration::minutes(15), threshold_pct: 0.05 },
+                DropWindow { window: Duration::hours(1), threshold_pct: 0.10 },
+                DropWindow { window: Duration::hours(24), threshold_pct: 0.15 },
+            ],
+            min_coverage: MIN_INDEX_COVERAGE,
+        }
+    }
+}
+
+// ==================== INDEX ====================
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IndexPoint {
+    pub at: DateTime<Utc>,
+    pub level: f64,
+    pub coverage: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexUpdate {
+    pub level: f64,
+    pub coverage: f64, // Share of basket weight priced this update
+    pub degraded: bool,
+    pub events: Vec<MarketEvent>,
+}
+
+pub struct MarketIndex {
+    config: IndexConfig,
+    level: f64,
+    coverage: f64,
+    last_prices: HashMap<String, f64>,
+    series: VecDeque<IndexPoint>,
+    active_drops: Vec<bool>, // Per window: a drop was emitted and has not recovered yet
+}
+
+impl MarketIndex {
+    pub fn new(config: IndexConfig) -> Self {
+        let windows = config.windows.len();
+        Self {
+            config,
+            level: INDEX_BASE_LEVEL,
+            coverage: 0.0,
+            last_prices: HashMap::new(),
+            series: VecDeque::new(),
+            active_drops: vec![false; windows],
+        }
+    }
+
+    pub fn level(&self) -> f64 {
+        self.level
+    }
+
+    pub fn coverage(&self) -> f64 {
+        self.coverage
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.coverage < self.config.min_coverage
+    }
+
+    pub fn series(&self) -> impl Iterator<Item = &IndexPoint> {
+        self.series.iter()
+    }
+
+    /// Fetch every constituent from the provider; failed fetches count as missing
+    pub async fn refresh(&mut self, provider: &dyn MarketDataProvider) -> IndexUpdate {
+        let mut prices = HashMap::new();
+        for constituent in &self.config.constituents {
+            match provider.get_market_data(&constituent.symbol).await {
+                Ok(data) => {
+                    prices.insert(constituent.symbol.clone(), data.price);
+                }
+                Err(e) => warn!("⚠️  Index constituent {} unavailable: {}", constituent.symbol, e),
+            }
+        }
+        self.update(&prices, Utc::now())
+    }
+
+    /// Chain-link the index to `prices`: each step applies the weighted return of the
+    /// constituents priced both now and last time, renormalized over their weights,
+    /// so a constituent dropping out or rejoining never moves the level by itself.
+    pub fn update(&mut self, prices: &HashMap<String, f64>, at: DateTime<Utc>) -> IndexUpdate {
+        let total_weight: f64 = self.config.constituents.iter().map(|c| c.weight).sum();
+        let priced: Vec<(&IndexConstituent, f64)> = self.config.constituents.iter()
+            .filter_map(|c| prices.get(&c.symbol).map(|&p| (c, p)))
+            .filter(|(_, p)| p.is_finite() && *p > 0.0)
+            .collect();
+
+        let priced_weight: f64 = priced.iter().map(|(c, _)| c.weight).sum();
+        self.coverage = if total_weight > 0.0 { priced_weight / total_weight } else { 0.0 };
+
+        let (weighted_return, linked_weight) = priced.iter()
+            .filter_map(|(c, p)| self.last_prices.get(&c.symbol).map(|last| (c.weight, p / last - 1.0)))
+            .fold((0.0, 0.0), |(sum, weight), (w, r)| (sum + w * r, weight + w));
+        if linked_weight > 0.0 {
+            self.level *= 1.0 + weighted_return / linked_weight;
+        }
+
+        for (c, p) in &priced {
+            self.last_prices.insert(c.symbol.clone(), *p);
+        }
+
+        self.series.push_back(IndexPoint { at, level: self.level, coverage: self.coverage });
+        let longest = self.config.windows.iter().map(|w| w.window).max().unwrap_or_else(Duration::zero);
+        while self.series.front().is_some_and(|p| at - p.at > longest) {
+            self.series.pop_front();
+        }
+
+        let degraded = self.is_degraded();
+        let events = if degraded {
+            // A partial basket can fake a crash (or hide one); hold events until coverage recovers
+            warn!("⚠️  Index coverage degraded to {:.0}% - drop detection suspended", self.coverage * 100.0);
+            Vec::new()
+        } else {
+            self.detect_drops(at)
+        };
+
+        IndexUpdate {
+            level: self.level,
+            coverage: self.coverage,
+            degraded,
+            events,
+        }
+    }
+
+    /// Fall from the highest level inside `window` to the current level
+    pub fn drop_within(&self, window: Duration, at: DateTime<Utc>) -> f64 {
+        let high = self.series.iter()
+            .filter(|p| at - p.at <= window)
+            .map(|p| p.level)
+            .fold(self.level, f64::max);
+        if high > 0.0 { (high - self.level) / high } else { 0.0 }
+    }
+
+    /// One event per window as its threshold is crossed, re-armed once the drop recovers
+    fn detect_drops(&mut self, at: DateTime<Utc>) -> Vec<MarketEvent> {
+        let mut events = Vec::new();
+        for (i, window) in self.config.windows.iter().enumerate() {
+            let drop = self.drop_within(window.window, at);
+            if drop < window.threshold_pct {
+                self.active_drops[i] = false;
+                continue;
+            }
+            if self.active_drops[i] {
+                continue;
+            }
+            self.active_drops[i] = true;
+            if let (Ok(pct), Ok(window)) = (conv::ratio_from_f64(drop), window.window.to_std()) {
+                events.push(MarketEvent::IndexDrop { pct, window });
+            }
+        }
+        events
+    }
+
+    /// Refresh from `provider` on a timer, publishing every update; runs for the life of the process
+    pub fn start(
+        mut self,
+        provider: Arc<dyn MarketDataProvider>,
+        engine: Arc<RwLock<StrikeBoxEngine>>,
+        monitoring: Arc<MonitoringSystem>,
+    ) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(INDEX_REFRESH_SECONDS));
+            loop {
+                interval.tick().await;
+                let update = self.refresh(provider.as_ref()).await;
+                Self::publish(&update, &engine, &monitoring).await;
+            }
+        });
+    }
+
+    /// Forward drop events to the engine and record level and coverage for monitoring
+    pub async fn publish(
+        update: &IndexUpdate,
+        engine: &RwLock<StrikeBoxEngine>,
+        monitoring: &MonitoringSystem,
+    ) {
+        monitoring.record_metric(MetricType::IndexLevel, update.level).await;
+        monitoring.record_metric(MetricType::IndexCoverage, update.coverage).await;
+
+        if update.events.is_empty() {
+            return;
+        }
+        let mut engine = engine.write().await;
+        for event in &update.events {
+            let state = engine.ingest_market_event(event.clone());
+            warn!("📉 Market index event {:?} -> engine state {:?}", event, state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn create_test_index(min_coverage: f64) -> MarketIndex {
+        MarketIndex::new(IndexConfig {
+            constituents: vec![
+                IndexConstituent { symbol: "BTC".to_string(), weight: 0.5 },
+                IndexConstituent { symbol: "ETH".to_string(), weight: 0.3 },
+                IndexConstituent { symbol: "SOL".to_string(), weight: 0.2 },
+            ],
+            windows: vec![
+                DropWindow { window: Duration::minutes(15), threshold_pct: 0.10 },
+                DropWindow { window: Duration::hours(1), threshold_pct: 0.15 },
+            ],
+            min_coverage,
+        })
+    }
+
+    fn prices(btc: Option<f64>, eth: Option<f64>, sol: Option<f64>) -> HashMap<String, f64> {
+        [("BTC", btc), ("ETH", eth), ("SOL", sol)]
+            .into_iter()
+            .filter_map(|(symbol, price)| price.map(|p| (symbol.to_string(), p)))
+            .collect()
+    }
+
+    #[test]
+    fn test_slow_slide_trips_hourly_window_only() {
+        let mut index = create_test_index(0.8);
+        let start = Utc::now();
+        let mut events = Vec::new();
+
+        // Every constituent slides 16% over an hour in one-minute steps
+        for minute in 0..=60 {
+            let factor = 1.0 - 0.16 * minute as f64 / 60.0;
+            let update = index.update(
+                &prices(Some(60_000.0 * factor), Some(3_000.0 * factor), Some(150.0 * factor)),
+                start + Duration::minutes(minute),
+            );
+            events.extend(update.events);
+        }
+
+        assert!((index.level() - INDEX_BASE_LEVEL * 0.84).abs() < 1e-6);
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            MarketEvent::IndexDrop { pct, window } => {
+                assert_eq!(*window, std::time::Duration::from_secs(3_600));
+                assert!(*pct >= Decimal::new(15, 2));
+            }
+        }
+        assert!((index.drop_within(Duration::hours(1), start + Duration::minutes(60)) - 0.16).abs() < 1e-9);
+        // A 15-minute window only ever saw a ~4% slide
+        assert!(index.drop_within(Duration::minutes(15), start + Duration::minutes(60)) < 0.05);
+    }
+
+    struct FlatProvider;
+
+    #[async_trait::async_trait]
+    impl MarketDataProvider for FlatProvider {
+        async fn get_market_data(&self, symbol: &str) -> crate::api::ApiResult<crate::api::MarketData> {
+            Ok(crate::api::MarketData {
+                symbol: symbol.to_string(),
+                price: 100.0,
+                volume_24h: 1_000_000.0,
+                price_change_24h: 0.0,
+                timestamp: std::time::SystemTime::now(),
+            })
+        }
+
+        async fn subscribe_prices(&self, _symbols: Vec<String>) -> crate::api::ApiResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_started_index_publishes_to_monitoring() {
+        let engine = Arc::new(RwLock::new(StrikeBoxEngine::new(
+            strike_box::StrikeBoxConfig::default(),
+            Decimal::new(1_000_000, 0),
+        )));
+        let monitoring = Arc::new(MonitoringSystem::new());
+        MarketIndex::new(IndexConfig::default()).start(Arc::new(FlatProvider), engine, monitoring.clone());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        assert_eq!(monitoring.get_metric(&MetricType::IndexLevel).await, Some(INDEX_BASE_LEVEL));
+        assert_eq!(monitoring.get_metric(&MetricType::IndexCoverage).await, Some(1.0));
+    }
+
+    #[test]
+    fn test_missing_constituents_renormalize_and_degrade() {
+        let mut index = create_test_index(0.8);
+        let start = Utc::now();
+        index.update(&prices(Some(100.0), Some(100.0), Some(100.0)), start);
+        assert_eq!(index.coverage(), 1.0);
+
+        // SOL missing: BTC and ETH alone carry the move, renormalized over their 0.8 weight
+        let update = index.update(&prices(Some(110.0), Some(110.0), None), start + Duration::minutes(1));
+        assert!((update.coverage - 0.8).abs() < 1e-12);
+        assert!(!update.degraded);
+        assert!((update.level - INDEX_BASE_LEVEL * 1.10).abs() < 1e-9);
+
+        // SOL rejoins unchanged from its last print, so the level holds
+        let update = index.update(&prices(Some(110.0), Some(110.0), Some(100.0)), start + Duration::minutes(2));
+        assert!((update.level - INDEX_BASE_LEVEL * 1.10).abs() < 1e-9);
+
+        // Only BTC priced: degraded, and even a crash in it emits nothing
+        let update = index.update(&prices(Some(50.0), None, None), start + Duration::minutes(3));
+        assert!((update.coverage - 0.5).abs() < 1e-12);
+        assert!(update.degraded);
+        assert!(index.is_degraded());
+        assert!(update.events.is_empty());
+        assert!(index.drop_within(Duration::minutes(15), start + Duration::minutes(3)) > 0.5);
+    }
+}
@@ -30,6 +30,11 @@ pub mod trade_test_harness;
 pub mod historical_backtest;
 pub mod live_simulation;
 pub mod rug_pull_detector;
+pub mod index;
+pub mod monitoring_bridge;
+pub mod sim_rng;
+pub mod telemetry;
+pub mod symbols;
 // Strike Box is now a separate crate - use as dependency
 #[cfg(feature = "eip")]
 pub mod diamond_integration;
@@ -290,11 +290,11 @@ impl LiveSimulator {
         report.push_str("═══════════════════════════════════════════════════════════════\n");
         report.push_str("FINANCIAL METRICS\n");
         report.push_str("═══════════════════════════════════════════════════════════════\n");
-        report.push_str(&format!("Initial Capital:   ${:,.2}\n", metrics.initial_capital));
-        report.push_str(&format!("Current Capital:   ${:,.2}\n", metrics.current_capital));
-        report.push_str(&format!("Net Profit:        ${:,.2}\n", metrics.net_profit));
-        report.push_str(&format!("Total Profit:      ${:,.2}\n", metrics.total_profit));
-        report.push_str(&format!("Total Loss:        ${:,.2}\n", metrics.total_loss));
+        report.push_str(&format!("Initial Capital:   ${:.2}\n", metrics.initial_capital));
+        report.push_str(&format!("Current Capital:   ${:.2}\n", metrics.current_capital));
+        report.push_str(&format!("Net Profit:        ${:.2}\n", metrics.net_profit));
+        report.push_str(&format!("Total Profit:      ${:.2}\n", metrics.total_profit));
+        report.push_str(&format!("Total Loss:        ${:.2}\n", metrics.total_loss));
         report.push_str(&format!("Return:            {:.2}%\n", 
             (metrics.net_profit / metrics.initial_capital) * 100.0));
         report.push_str(&format!("Max Drawdown:      {:.2}%\n", metrics.max_drawdown * 100.0));
@@ -325,5 +325,5 @@ impl LiveSimulator {
     }
 }
 
-use log::info;
+use tracing::info;
 
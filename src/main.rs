@@ -1,10 +1,13 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use tokio::time::sleep;
 use serde::{Deserialize, Serialize};
-use log::{info, warn, error};
-use rand::Rng;
+use tracing::{error, info, info_span, warn, Instrument};
+use uuid::Uuid;
+use strike_box::RingBuffer;
+use sim_rng::SimRng;
+use symbols::SymbolRegistry;
 
 // API modules for live trading
 mod api;
@@ -16,12 +19,19 @@ mod strike_optimizer;
 mod trading_engine;
 // Opportunity scanner - finds 90% win rate patterns
 mod opportunity_scanner;
+// Injectable simulation RNG for reproducible runs
+mod sim_rng;
+// Tracing subscriber setup (pretty or JSON output)
+mod telemetry;
+// Symbol registry - pairs, venue codes and token addresses
+mod symbols;
 // EIP integration for on-chain trading
 #[cfg(feature = "eip")]
 mod eip;
 
 // MACRO STRIKE CONFIGURATION - 2500 TRADES
 const TOTAL_TRADES: usize = 2500;
+const COMPLETED_STRIKE_RETENTION: usize = 1000; // Older completed strikes are summarised in metrics only
 const TARGET_MONTHLY_RETURN: f64 = 5.0; // 500% return target
 const DAILY_TARGET_RETURN: f64 = 0.065; // 6.5% daily target
 const INITIAL_CAPITAL: f64 = 1_000_000.0; // $1M
@@ -31,9 +41,19 @@ const TARGET_CAPITAL: f64 = 6_000_000.0; // $6M
 const STRIKE_FORCE: f64 = 0.15; // 15% of capital per strike
 const PRECISION_THRESHOLD: f64 = 0.90; // 90% WIN RATE REQUIRED
 const IMPACT_MULTIPLIER: f64 = 3.0; // 3x leverage on strikes
-const MAX_EXPOSURE_TIME_MS: u64 = 30000; // 30 seconds max exposure
+const MAX_EXPOSURE_TIME_MS: u64 = 30000; // 30 seconds max exposure, whatever the strike type
+const EXPOSURE_TIME_MULTIPLE: u64 = 3; // Strikes may stay open this many typical durations
 const STRIKE_COOLDOWN_MS: u64 = 1; // 1ms cooldown
+const MAX_STRIKE_COOLDOWN_MS: u64 = 5000; // Ceiling on the miss-driven backoff
 const MIN_WIN_PROBABILITY: f64 = 0.90; // HARD REQUIREMENT: 90% win probability
+const ADAPTIVE_WINDOW: usize = 50; // Recent strikes used for the rolling win rate
+const ADAPTIVE_MIN_MULTIPLIER: f64 = 0.5; // Floor on strike force when below target
+const ADAPTIVE_MAX_MULTIPLIER: f64 = 1.5; // Default ceiling on strike force when above target
+const LEVERAGE_TARGET_VOL: f64 = 0.01; // Per-strike return vol at which strikes get the full IMPACT_MULTIPLIER
+const MIN_LEVERAGE_MULTIPLIER: f64 = 0.5; // Floor on the vol scaling of IMPACT_MULTIPLIER
+const MAX_LEVERAGE_MULTIPLIER: f64 = 1.5; // Default ceiling on the vol scaling of IMPACT_MULTIPLIER
+const MIN_VOL_FOR_REDUCTION: f64 = 0.00125; // Default per-strike vol below which no scaling applies
+const REALIZED_VOL_ALPHA: f64 = 0.1; // EWMA weight of the newest strike's squared return
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StrikeType {
@@ -57,7 +77,7 @@ pub enum StrikeStatus {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MacroStrike {
     pub id: u64,
-    pub symbol: u8, // Use u8 instead of String (0-255 symbols max)
+    pub symbol: String, // Canonical pair, e.g. "ETH/USDC"
     pub strike_type: StrikeType,
     pub entry_price: f64, // Use f64 instead of Decimal
     pub target_price: f64,
@@ -75,19 +95,44 @@ pub struct MacroStrike {
     pub leverage: u32,
 }
 
-// SYMBOL MAPPING (u8 to string)
-const SYMBOLS: [&str; 8] = [
-    "WETH/USDC", "WBTC/USDC", "LINK/USDC", "UNI/USDC", 
-    "AAVE/USDC", "CRV/USDC", "USDC/USDT", "DAI/USDC"
-];
+impl StrikeType {
+    /// How long a strike of this type usually stays open
+    pub fn typical_duration_ms(self) -> u64 {
+        match self {
+            StrikeType::MacroArbitrage => 500,
+            StrikeType::MacroFlash => 1000,
+            StrikeType::MacroLiquidity => 5000,
+            StrikeType::MacroVolatility => 10000,
+            StrikeType::MacroFunding => 10000,
+            StrikeType::MacroMomentum => 15000,
+        }
+    }
+}
+
+impl MacroStrike {
+    pub fn typical_duration_ms(&self) -> u64 {
+        self.strike_type.typical_duration_ms()
+    }
 
-fn symbol_to_u8(symbol: &str) -> u8 {
-    SYMBOLS.iter().position(|&s| s == symbol).unwrap_or(0) as u8
+    /// Whether a strike started at `started_at` has been exposed past `max_exposure_time_ms`
+    pub fn is_overdue(&self, started_at: Instant) -> bool {
+        started_at.elapsed() > Duration::from_millis(self.max_exposure_time_ms)
+    }
 }
 
-fn u8_to_symbol(id: u8) -> &'static str {
-    SYMBOLS[id as usize]
+/// Simulated reference price for a canonical base ticker
+fn simulated_base_price(base: &str) -> f64 {
+    match base {
+        "ETH" => 3000.0,
+        "BTC" => 45000.0,
+        "LINK" => 15.50,
+        "UNI" => 8.50,
+        "AAVE" => 120.0,
+        "CRV" => 0.85,
+        _ => 1.00,
+    }
 }
+
 pub struct MacroStrikeEngine {
     // Use AtomicU64 for lock-free operations
     capital: AtomicU64, // Store as cents (u64)
@@ -96,7 +141,7 @@ pub struct MacroStrikeEngine {
     
     // Use VecDeque for O(1) operations
     active_strikes: VecDeque<MacroStrike>,
-    completed_strikes: VecDeque<MacroStrike>,
+    completed_strikes: RingBuffer<MacroStrike>,
     
     // Lock-free metrics
     metrics: MacroMetrics,
@@ -107,6 +152,36 @@ pub struct MacroStrikeEngine {
     max_consecutive_misses: usize,
     max_daily_loss: f64,
     emergency_stop: f64,
+    
+    // Adaptive sizing
+    pub adaptive_sizing_enabled: bool,
+    pub adaptive_sizing_max_multiplier: f64,
+    
+    // Cooldown backoff
+    pub dynamic_cooldown_enabled: bool, // Off keeps the base cooldown, for benchmarking
+
+    // Volatility-adjusted leverage
+    realized_vol: HashMap<String, f64>, // EWMA of per-strike returns, by symbol
+    pub min_vol_for_reduction: f64, // Too little vol to estimate from; strikes keep IMPACT_MULTIPLIER
+    pub max_leverage_multiplier: f64,
+    
+    // Simulation
+    symbols: SymbolRegistry, // Instruments strikes are generated on, in rotation
+    total_trades: usize,
+    rng: SimRng, // Strike prices, confidences and outcomes
+    recorded_strikes: Option<Vec<MacroStrike>>, // Executed strikes, in order; None when not recording
+}
+
+/// Outcome of re-running recorded strikes through `MacroStrikeEngine::replay`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayResult {
+    pub strikes: usize,
+    pub executed: usize, // Strikes that cleared the win probability requirement
+    pub hits: usize,
+    pub cumulative_pnl: f64,
+    pub win_rate: f64, // Hits over executed strikes; 0 when none executed
+    pub max_drawdown: f64, // Largest fall in capital from its running peak, as a share of the peak
+    pub final_capital: f64,
 }
 
 #[derive(Debug)]
@@ -118,8 +193,10 @@ pub struct MacroMetrics {
     pub trades_completed: AtomicUsize,
     pub trades_remaining: AtomicUsize,
     pub total_skipped: AtomicUsize, // Strikes skipped due to <90% confidence
+    pub total_aborted: AtomicUsize, // Strikes stalled past their max exposure time
     pub precision_rate: f64,
     pub average_strike_time_ms: f64,
+    pub recent_outcomes: RingBuffer<bool>, // Hit/miss of the last ADAPTIVE_WINDOW strikes
 }
 
 impl Default for MacroMetrics {
@@ -132,12 +209,25 @@ impl Default for MacroMetrics {
             trades_completed: AtomicUsize::new(0),
             trades_remaining: AtomicUsize::new(TOTAL_TRADES),
             total_skipped: AtomicUsize::new(0),
+            total_aborted: AtomicUsize::new(0),
             precision_rate: 0.0,
             average_strike_time_ms: 0.0,
+            recent_outcomes: RingBuffer::new(ADAPTIVE_WINDOW),
         }
     }
 }
 
+impl MacroMetrics {
+    /// Rolling win rate over the recent window, None until a strike has completed
+    pub fn recent_precision_rate(&self) -> Option<f64> {
+        if self.recent_outcomes.is_empty() {
+            return None;
+        }
+        let hits = self.recent_outcomes.iter().filter(|&&hit| hit).count();
+        Some(hits as f64 / self.recent_outcomes.len() as f64)
+    }
+}
+
 impl MacroStrikeEngine {
     pub fn new() -> Self {
         Self {
@@ -145,49 +235,163 @@ impl MacroStrikeEngine {
             target_capital: (TARGET_CAPITAL * 100.0) as u64,
             peak_capital: AtomicU64::new((INITIAL_CAPITAL * 100.0) as u64),
             active_strikes: VecDeque::with_capacity(100),
-            completed_strikes: VecDeque::with_capacity(TOTAL_TRADES),
+            completed_strikes: RingBuffer::new(COMPLETED_STRIKE_RETENTION),
             metrics: MacroMetrics::default(),
             next_strike_id: AtomicU64::new(1),
             consecutive_misses: AtomicUsize::new(0),
             max_consecutive_misses: 3,
             max_daily_loss: 0.05,
             emergency_stop: 0.15,
+            adaptive_sizing_enabled: true,
+            adaptive_sizing_max_multiplier: ADAPTIVE_MAX_MULTIPLIER,
+            dynamic_cooldown_enabled: true,
+            realized_vol: HashMap::new(),
+            min_vol_for_reduction: MIN_VOL_FOR_REDUCTION,
+            max_leverage_multiplier: MAX_LEVERAGE_MULTIPLIER,
+            symbols: SymbolRegistry::with_default_instruments(),
+            total_trades: TOTAL_TRADES,
+            rng: SimRng::from_entropy(),
+            recorded_strikes: None,
+        }
+    }
+
+    /// Draw every simulated price move and outcome from `rng`; a seeded source replays exactly
+    pub fn with_rng(mut self, rng: SimRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Keep every strike the campaign executes, for `record_campaign`. While recording, each
+    /// strike's outcome is drawn from its replay seed rather than the campaign RNG.
+    pub fn with_recording(mut self) -> Self {
+        self.recorded_strikes = Some(Vec::new());
+        self
+    }
+
+    /// Strikes executed since recording started or was last taken, oldest first; aborted
+    /// strikes are left out. Empty when not recording. Replaying a whole recorded campaign
+    /// reproduces its outcomes.
+    pub fn record_campaign(&mut self) -> Vec<MacroStrike> {
+        self.recorded_strikes.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    pub fn with_total_trades(mut self, total_trades: usize) -> Self {
+        self.total_trades = total_trades;
+        self.metrics.trades_remaining.store(total_trades, Ordering::Relaxed);
+        self
+    }
+
+    /// Strike force scaled by the recent win rate relative to the 90% target
+    pub fn adaptive_strike_force(&self) -> f64 {
+        if !self.adaptive_sizing_enabled {
+            return STRIKE_FORCE;
+        }
+        let multiplier = match self.metrics.recent_precision_rate() {
+            Some(precision_rate) => (precision_rate / PRECISION_THRESHOLD)
+                .min(self.adaptive_sizing_max_multiplier)
+                .max(ADAPTIVE_MIN_MULTIPLIER),
+            None => 1.0,
+        };
+        STRIKE_FORCE * multiplier
+    }
+
+    /// Leverage for `strike`: IMPACT_MULTIPLIER for momentum and volatility strikes, scaled by
+    /// sqrt(LEVERAGE_TARGET_VOL / realized_vol) within [0.5, max_leverage_multiplier] so calmer
+    /// symbols take more and choppier ones less. Other strike types run unlevered. `realized_vol`
+    /// is per strike, not annualized, as `realized_vol()` reports it.
+    pub fn effective_leverage(&self, strike: &MacroStrike, realized_vol: f64) -> u32 {
+        if !matches!(strike.strike_type, StrikeType::MacroMomentum | StrikeType::MacroVolatility) {
+            return 1;
+        }
+        let scale = if realized_vol.is_finite() && realized_vol >= self.min_vol_for_reduction {
+            (LEVERAGE_TARGET_VOL / realized_vol)
+                .sqrt()
+                .min(self.max_leverage_multiplier)
+                .max(MIN_LEVERAGE_MULTIPLIER)
+        } else {
+            1.0
+        };
+        (IMPACT_MULTIPLIER * scale).floor().max(1.0) as u32
+    }
+
+    /// Realized vol for `symbol` from completed strikes, None before the first
+    pub fn realized_vol(&self, symbol: &str) -> Option<f64> {
+        self.realized_vol.get(symbol).copied()
+    }
+
+    fn update_realized_vol(&mut self, symbol: &str, strike_return: f64) {
+        let squared = strike_return * strike_return;
+        let variance = match self.realized_vol.get(symbol) {
+            Some(vol) => REALIZED_VOL_ALPHA * squared + (1.0 - REALIZED_VOL_ALPHA) * vol * vol,
+            None => squared,
+        };
+        self.realized_vol.insert(symbol.to_string(), variance.sqrt());
+    }
+
+    /// Base cooldown doubled per consecutive miss, capped at MAX_STRIKE_COOLDOWN_MS
+    pub fn current_cooldown_ms(&self) -> u64 {
+        if !self.dynamic_cooldown_enabled {
+            return STRIKE_COOLDOWN_MS;
         }
+        let misses = self.consecutive_misses.load(Ordering::Relaxed) as u32;
+        let backoff = 1u64.checked_shl(misses).unwrap_or(u64::MAX);
+        STRIKE_COOLDOWN_MS.saturating_mul(backoff).min(MAX_STRIKE_COOLDOWN_MS)
     }
 
     pub async fn execute_macro_campaign(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         info!("🎯 MACRO STRIKE CAMPAIGN INITIATED - 2500 TRADES");
         info!("Target: ${:.2} in 30 days", self.target_capital as f64 / 100.0);
-        info!("Total Trades: {}", TOTAL_TRADES);
+        info!("Total Trades: {}", self.total_trades);
         info!("Strike Force: {:.1}% per strike", STRIKE_FORCE * 100.0);
 
         let start_time = Instant::now();
+        let mut cooldown_ms = STRIKE_COOLDOWN_MS;
 
         // Execute all 2500 trades as fast as possible
-        while self.metrics.trades_completed.load(Ordering::Relaxed) < TOTAL_TRADES {
+        while self.metrics.trades_completed.load(Ordering::Relaxed) < self.total_trades {
             // Generate and execute strikes in batches for efficiency
-            let batch_size = std::cmp::min(10, TOTAL_TRADES - self.metrics.trades_completed.load(Ordering::Relaxed));
+            let batch_size = std::cmp::min(10, self.total_trades - self.metrics.trades_completed.load(Ordering::Relaxed));
             
             for _ in 0..batch_size {
-                if self.metrics.trades_completed.load(Ordering::Relaxed) >= TOTAL_TRADES {
+                if self.metrics.trades_completed.load(Ordering::Relaxed) >= self.total_trades {
                     break;
                 }
 
-                // Generate strike
+                // Generate strike, dropping it if it stalled past its exposure window
+                let started_at = Instant::now();
                 let strike = self.generate_strike().await;
-                let strike_pnl = self.execute_strike(strike).await?;
-                
-                // Update capital atomically
-                let current_capital = self.capital.load(Ordering::Relaxed);
-                let new_capital = current_capital + (strike_pnl * 100.0) as u64;
-                self.capital.store(new_capital, Ordering::Relaxed);
-                
-                // Update metrics
-                self.metrics.trades_completed.fetch_add(1, Ordering::Relaxed);
-                self.metrics.trades_remaining.store(
-                    TOTAL_TRADES - self.metrics.trades_completed.load(Ordering::Relaxed), 
-                    Ordering::Relaxed
-                );
+                if strike.is_overdue(started_at) {
+                    warn!("⏰ Strike #{} ABORTED - stalled {}ms, limit {}ms",
+                          strike.id, started_at.elapsed().as_millis(), strike.max_exposure_time_ms);
+                    self.metrics.total_aborted.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+                // Whatever exposure is left bounds the execution itself; running past it is an abort too
+                let remaining = Duration::from_millis(strike.max_exposure_time_ms).saturating_sub(started_at.elapsed());
+                let (strike_id, max_exposure_time_ms) = (strike.id, strike.max_exposure_time_ms);
+                let recorded = self.recorded_strikes.is_some().then(|| strike.clone());
+                let campaign_rng = recorded
+                    .as_ref()
+                    .map(|strike| std::mem::replace(&mut self.rng, SimRng::seeded(replay_seed(strike))));
+                let outcome = tokio::time::timeout(remaining, self.execute_strike(strike)).await;
+                if let Some(rng) = campaign_rng {
+                    self.rng = rng;
+                }
+                let strike_pnl = match outcome {
+                    Ok(result) => {
+                        let strike_pnl = result?;
+                        if let (Some(recorded_strikes), Some(strike)) = (&mut self.recorded_strikes, recorded) {
+                            recorded_strikes.push(strike);
+                        }
+                        strike_pnl
+                    }
+                    Err(_) => {
+                        warn!("⏰ Strike #{} ABORTED - execution ran past {}ms", strike_id, max_exposure_time_ms);
+                        self.metrics.total_aborted.fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    }
+                };
+                self.settle_strike(strike_pnl);
 
                 // Check emergency stops
                 if self.check_emergency_stops() {
@@ -205,11 +409,17 @@ impl MacroStrikeEngine {
                 let trades_per_second = trades_completed as f64 / elapsed.max(1) as f64;
                 
                 info!("Progress: {}/{} trades | Capital: ${:.2} | Progress: {:.1}% | Rate: {:.1} trades/sec", 
-                      trades_completed, TOTAL_TRADES, current_capital, progress * 100.0, trades_per_second);
+                      trades_completed, self.total_trades, current_capital, progress * 100.0, trades_per_second);
             }
 
-            // Minimal cooldown (1ms) to prevent CPU spinning
-            sleep(Duration::from_millis(STRIKE_COOLDOWN_MS)).await;
+            // Minimal cooldown (1ms) to prevent CPU spinning, backing off while misses accumulate
+            let next_cooldown_ms = self.current_cooldown_ms();
+            if next_cooldown_ms != cooldown_ms {
+                info!("⏱️  Cooldown {}ms -> {}ms ({} consecutive misses)",
+                      cooldown_ms, next_cooldown_ms, self.consecutive_misses.load(Ordering::Relaxed));
+                cooldown_ms = next_cooldown_ms;
+            }
+            sleep(Duration::from_millis(cooldown_ms)).await;
         }
 
         // Campaign complete
@@ -219,20 +429,88 @@ impl MacroStrikeEngine {
         let trades_completed = self.metrics.trades_completed.load(Ordering::Relaxed);
         
         info!("🏁 CAMPAIGN COMPLETE: {:.1}% return | Trades: {}/{} | Time: {:.2}s", 
-              final_return * 100.0, trades_completed, TOTAL_TRADES, total_time.as_secs_f64());
+              final_return * 100.0, trades_completed, self.total_trades, total_time.as_secs_f64());
 
         Ok(())
     }
 
+    /// Re-runs `strikes` through `execute_strike` in order on a fresh engine with this one's
+    /// settings, compounding capital from INITIAL_CAPITAL as a campaign would; this engine is
+    /// left untouched. Each strike's price move and outcome are drawn from an RNG seeded by its
+    /// timestamp and id, the id because strikes generated within a second share a timestamp,
+    /// so a replay gives the same result however often it runs.
+    pub async fn replay(&self, strikes: Vec<MacroStrike>) -> Result<ReplayResult, Box<dyn std::error::Error>> {
+        let mut scratch = self.scratch();
+        let starting_capital = scratch.capital.load(Ordering::Relaxed) as f64 / 100.0;
+        let mut peak_capital = starting_capital;
+        let mut max_drawdown: f64 = 0.0;
+        let total = strikes.len();
+
+        for strike in strikes {
+            scratch.rng = SimRng::seeded(replay_seed(&strike));
+            let strike_pnl = scratch.execute_strike(strike).await?;
+            scratch.settle_strike(strike_pnl);
+
+            let capital = scratch.capital.load(Ordering::Relaxed) as f64 / 100.0;
+            peak_capital = peak_capital.max(capital);
+            if peak_capital > 0.0 {
+                max_drawdown = max_drawdown.max((peak_capital - capital) / peak_capital);
+            }
+        }
+
+        let hits = scratch.metrics.successful_strikes.load(Ordering::Relaxed);
+        let executed = scratch.metrics.total_strikes.load(Ordering::Relaxed);
+        let final_capital = scratch.capital.load(Ordering::Relaxed) as f64 / 100.0;
+        Ok(ReplayResult {
+            strikes: total,
+            executed,
+            hits,
+            cumulative_pnl: final_capital - starting_capital,
+            win_rate: if executed > 0 { hits as f64 / executed as f64 } else { 0.0 },
+            max_drawdown,
+            final_capital,
+        })
+    }
+
+    /// Fresh engine with this one's limits, sizing and leverage settings, for replays
+    fn scratch(&self) -> Self {
+        Self {
+            max_consecutive_misses: self.max_consecutive_misses,
+            max_daily_loss: self.max_daily_loss,
+            emergency_stop: self.emergency_stop,
+            adaptive_sizing_enabled: self.adaptive_sizing_enabled,
+            adaptive_sizing_max_multiplier: self.adaptive_sizing_max_multiplier,
+            dynamic_cooldown_enabled: self.dynamic_cooldown_enabled,
+            min_vol_for_reduction: self.min_vol_for_reduction,
+            max_leverage_multiplier: self.max_leverage_multiplier,
+            symbols: self.symbols.clone(),
+            ..Self::new()
+        }
+        .with_total_trades(self.total_trades)
+    }
+
+    /// Credit a strike's PnL to capital and count it toward the campaign's trades
+    fn settle_strike(&mut self, strike_pnl: f64) {
+        let current_capital = self.capital.load(Ordering::Relaxed) as f64;
+        let new_capital = (current_capital + strike_pnl * 100.0).max(0.0) as u64;
+        self.capital.store(new_capital, Ordering::Relaxed);
+
+        self.metrics.trades_completed.fetch_add(1, Ordering::Relaxed);
+        self.metrics.trades_remaining.store(
+            self.total_trades.saturating_sub(self.metrics.trades_completed.load(Ordering::Relaxed)),
+            Ordering::Relaxed
+        );
+    }
+
     async fn generate_strike(&self) -> MacroStrike {
         let strike_id = self.next_strike_id.fetch_add(1, Ordering::Relaxed);
-        let symbol_id = (strike_id % SYMBOLS.len() as u64) as u8;
+        let instruments = self.symbols.instruments();
+        let instrument = &instruments[(strike_id % instruments.len() as u64) as usize];
         
         // Generate realistic strike data
-        let base_prices = [3000.0, 45000.0, 15.50, 8.50, 120.0, 0.85, 1.00, 1.00];
-        let base_price = base_prices[symbol_id as usize];
-        let mut rng = rand::thread_rng();
-        let movement = (rng.gen::<f64>() - 0.5) * 0.02; // ±1% movement
+        let base_price = simulated_base_price(&instrument.base);
+        let rng = &self.rng;
+        let movement = (rng.gen_f64() - 0.5) * 0.02; // ±1% movement
         let entry_price = base_price * (1.0 + movement);
         
         let strike_types = [
@@ -256,23 +534,23 @@ impl MacroStrikeEngine {
 
         MacroStrike {
             id: strike_id,
-            symbol: symbol_id,
+            symbol: instrument.pair(),
             strike_type,
             entry_price,
             target_price: entry_price * (1.0 + expected_return),
             stop_loss: entry_price * 0.98, // 2% stop loss
             // ALWAYS 90%+ because we only find high-probability patterns
             confidence: match &strike_type {
-                StrikeType::MacroArbitrage => 0.95 + (rng.gen::<f64>() * 0.04), // 95-99% for arbitrage
-                StrikeType::MacroVolatility => 0.91 + (rng.gen::<f64>() * 0.07), // 91-98% for mean reversion
-                StrikeType::MacroMomentum => 0.90 + (rng.gen::<f64>() * 0.08), // 90-98% for momentum
-                StrikeType::MacroLiquidity => 0.92 + (rng.gen::<f64>() * 0.06), // 92-98% for liquidity
-                StrikeType::MacroFunding => 0.91 + (rng.gen::<f64>() * 0.07), // 91-98% for funding
-                StrikeType::MacroFlash => 0.91 + (rng.gen::<f64>() * 0.07), // 91-98% for microstructure
+                StrikeType::MacroArbitrage => 0.95 + (rng.gen_f64() * 0.04), // 95-99% for arbitrage
+                StrikeType::MacroVolatility => 0.91 + (rng.gen_f64() * 0.07), // 91-98% for mean reversion
+                StrikeType::MacroMomentum => 0.90 + (rng.gen_f64() * 0.08), // 90-98% for momentum
+                StrikeType::MacroLiquidity => 0.92 + (rng.gen_f64() * 0.06), // 92-98% for liquidity
+                StrikeType::MacroFunding => 0.91 + (rng.gen_f64() * 0.07), // 91-98% for funding
+                StrikeType::MacroFlash => 0.91 + (rng.gen_f64() * 0.07), // 91-98% for microstructure
             },
             expected_return,
-            position_size: self.capital.load(Ordering::Relaxed) as f64 / 100.0 * STRIKE_FORCE,
-            max_exposure_time_ms: MAX_EXPOSURE_TIME_MS,
+            position_size: self.capital.load(Ordering::Relaxed) as f64 / 100.0 * self.adaptive_strike_force(),
+            max_exposure_time_ms: (strike_type.typical_duration_ms() * EXPOSURE_TIME_MULTIPLE).min(MAX_EXPOSURE_TIME_MS),
             strike_force: 0.0, // Will be calculated
             timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
             status: StrikeStatus::Targeting,
@@ -283,13 +561,26 @@ impl MacroStrikeEngine {
         }
     }
 
-    async fn execute_strike(&mut self, mut strike: MacroStrike) -> Result<f64, Box<dyn std::error::Error>> {
+    /// Runs one strike inside a `strike` span with a fresh `execution_id`, so every event
+    /// from the win-probability check to the result carries it
+    async fn execute_strike(&mut self, strike: MacroStrike) -> Result<f64, Box<dyn std::error::Error>> {
+        let span = info_span!(
+            "strike",
+            execution_id = %Uuid::new_v4(),
+            strike_id = strike.id,
+            symbol = %strike.symbol,
+            strike_type = ?strike.strike_type,
+        );
+        self.run_strike(strike).instrument(span).await
+    }
+
+    async fn run_strike(&mut self, mut strike: MacroStrike) -> Result<f64, Box<dyn std::error::Error>> {
         // CRITICAL: Enforce 90% win probability requirement
         if strike.confidence < MIN_WIN_PROBABILITY {
             warn!("⚠️ Strike #{} REJECTED - Win probability {:.1}% < 90% required", 
                   strike.id, strike.confidence * 100.0);
             warn!("  Symbol: {} | Type: {:?} | Expected Return: {:.2}%",
-                  SYMBOLS[strike.symbol as usize], strike.strike_type, strike.expected_return * 100.0);
+                  strike.symbol, strike.strike_type, strike.expected_return * 100.0);
             
             self.metrics.total_skipped.fetch_add(1, Ordering::Relaxed);
             
@@ -304,12 +595,14 @@ impl MacroStrikeEngine {
         
         // Calculate strike size
         let current_capital = self.capital.load(Ordering::Relaxed) as f64 / 100.0;
-        let mut strike_size = current_capital * STRIKE_FORCE * strike.confidence;
-
-        // Apply impact multiplier for momentum/volatility
-        if matches!(strike.strike_type, StrikeType::MacroMomentum | StrikeType::MacroVolatility) {
-            strike_size *= IMPACT_MULTIPLIER;
-            strike.leverage = IMPACT_MULTIPLIER as u32;
+        let mut strike_size = current_capital * self.adaptive_strike_force() * strike.confidence;
+
+        // Apply impact multiplier for momentum/volatility, scaled by the symbol's realized vol
+        let realized_vol = self.realized_vol(&strike.symbol).unwrap_or(0.0);
+        let leverage = self.effective_leverage(&strike, realized_vol);
+        if leverage > 1 {
+            strike_size *= leverage as f64;
+            strike.leverage = leverage;
         }
 
         strike.strike_force = strike_size;
@@ -319,13 +612,13 @@ impl MacroStrikeEngine {
         let start_time = Instant::now();
         
         // Simulate price movement
-        let mut rng = rand::thread_rng();
-        let price_movement = (rng.gen::<f64>() - 0.5) * 0.04; // ±2% movement
+        let price_movement = (self.rng.gen_f64() - 0.5) * 0.04; // ±2% movement
         let final_price = strike.entry_price * (1.0 + price_movement);
+        self.update_realized_vol(&strike.symbol, price_movement);
 
         // Determine if hit or miss based on confidence and randomness
         let hit_probability = strike.confidence;
-        let is_hit = rng.gen::<f64>() < hit_probability;
+        let is_hit = self.rng.gen_f64() < hit_probability;
         
         let pnl = if is_hit {
             // Hit - calculate profit
@@ -346,6 +639,7 @@ impl MacroStrikeEngine {
             self.metrics.failed_strikes.fetch_add(1, Ordering::Relaxed);
             self.consecutive_misses.fetch_add(1, Ordering::Relaxed);
         }
+        self.metrics.recent_outcomes.push(is_hit);
 
         // Update precision rate
         let total_strikes = self.metrics.total_strikes.load(Ordering::Relaxed);
@@ -356,12 +650,12 @@ impl MacroStrikeEngine {
         let strike_time = start_time.elapsed().as_millis() as f64;
         if is_hit {
             info!("✅ HIT: {} | PnL=${:.2} | Time={:.1}ms | Trades: {}/{}", 
-                  u8_to_symbol(strike.symbol), pnl, strike_time,
-                  self.metrics.trades_completed.load(Ordering::Relaxed) + 1, TOTAL_TRADES);
+                  strike.symbol, pnl, strike_time,
+                  self.metrics.trades_completed.load(Ordering::Relaxed) + 1, self.total_trades);
         } else {
             warn!("❌ MISS: {} | PnL=${:.2} | Time={:.1}ms | Trades: {}/{}", 
-                  u8_to_symbol(strike.symbol), pnl, strike_time,
-                  self.metrics.trades_completed.load(Ordering::Relaxed) + 1, TOTAL_TRADES);
+                  strike.symbol, pnl, strike_time,
+                  self.metrics.trades_completed.load(Ordering::Relaxed) + 1, self.total_trades);
         }
 
         Ok(pnl)
@@ -392,18 +686,281 @@ impl MacroStrikeEngine {
     }
 }
 
+/// RNG seed for replaying `strike`
+fn replay_seed(strike: &MacroStrike) -> u64 {
+    strike.timestamp.rotate_left(32) ^ strike.id
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logger
-    env_logger::init();
+    // Initialize tracing; LOG_FORMAT=json for one JSON object per event
+    telemetry::init(&telemetry::TelemetryConfig::from_env())?;
 
     info!("🚀 STARTING OPTIMIZED MACRO STRIKE BOT");
     info!("Target: 2500 trades in minimum time");
     info!("No sleep delays, lock-free operations, maximum performance");
 
+    // --seed N replays a previous run exactly
+    let seed = parse_seed(std::env::args().skip(1))?;
+    if let Some(seed) = seed {
+        info!("🎲 Simulation seed: {}", seed);
+    }
+
     // Create and run optimized macro strike engine
-    let mut engine = MacroStrikeEngine::new();
+    let mut engine = MacroStrikeEngine::new().with_rng(SimRng::from_seed_or_entropy(seed));
     engine.execute_macro_campaign().await?;
 
     Ok(())
 }
+
+/// Seed from `--seed N` or `--seed=N`, if given
+fn parse_seed(mut args: impl Iterator<Item = String>) -> Result<Option<u64>, String> {
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--seed") {
+            Some("") => args.next().ok_or("--seed requires a value")?,
+            Some(rest) => match rest.strip_prefix('=') {
+                Some(value) => value.to_string(),
+                None => continue,
+            },
+            None => continue,
+        };
+        return value.parse().map(Some).map_err(|e| format!("Invalid --seed {:?}: {}", value, e));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn run_seeded_campaign(seed: u64) -> (u64, usize) {
+        let mut engine = MacroStrikeEngine::new()
+            .with_rng(SimRng::seeded(seed))
+            .with_total_trades(200);
+        engine.execute_macro_campaign().await.unwrap();
+        (
+            engine.capital.load(Ordering::Relaxed),
+            engine.metrics.successful_strikes.load(Ordering::Relaxed),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_seeded_campaigns_replay_exactly() {
+        let first = run_seeded_campaign(42).await;
+        let second = run_seeded_campaign(42).await;
+        assert_eq!(first, second);
+
+        let other = run_seeded_campaign(7).await;
+        assert_ne!(first, other);
+    }
+
+    #[tokio::test]
+    async fn test_replay_of_recorded_strikes_is_deterministic() {
+        let mut live = MacroStrikeEngine::new()
+            .with_rng(SimRng::seeded(42))
+            .with_total_trades(50)
+            .with_recording();
+        live.execute_macro_campaign().await.unwrap();
+        let strikes = live.record_campaign();
+        assert!(!strikes.is_empty());
+        assert!(live.record_campaign().is_empty());
+        assert!(MacroStrikeEngine::new().record_campaign().is_empty());
+
+        let first = MacroStrikeEngine::new().replay(strikes.clone()).await.unwrap();
+        let second = MacroStrikeEngine::new().with_rng(SimRng::seeded(7)).replay(strikes.clone()).await.unwrap();
+        assert_eq!(first, second);
+
+        // The replay reproduces the live run and leaves the live engine as it was
+        let live_capital = live.capital.load(Ordering::Relaxed);
+        assert_eq!(live.replay(strikes.clone()).await.unwrap(), first);
+        assert_eq!(live.capital.load(Ordering::Relaxed), live_capital);
+        assert_eq!(first.hits, live.metrics.successful_strikes.load(Ordering::Relaxed));
+        assert_eq!(first.executed, live.metrics.total_strikes.load(Ordering::Relaxed));
+        assert!((first.final_capital - live_capital as f64 / 100.0).abs() < 0.01);
+        assert_eq!(first.strikes, strikes.len());
+        assert!(first.hits <= first.executed);
+        assert!((0.0..=1.0).contains(&first.win_rate));
+        assert!(first.max_drawdown >= 0.0);
+        assert!((first.final_capital - INITIAL_CAPITAL - first.cumulative_pnl).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_exposure_time_follows_strike_type() {
+        let engine = MacroStrikeEngine::new().with_rng(SimRng::seeded(1));
+        for _ in 0..6 {
+            let strike = engine.generate_strike().await;
+            assert_eq!(
+                strike.max_exposure_time_ms,
+                (strike.typical_duration_ms() * EXPOSURE_TIME_MULTIPLE).min(MAX_EXPOSURE_TIME_MS)
+            );
+            assert!(strike.max_exposure_time_ms <= MAX_EXPOSURE_TIME_MS);
+            if strike.strike_type == StrikeType::MacroArbitrage {
+                assert_eq!(strike.max_exposure_time_ms, 1500);
+            }
+
+            assert!(!strike.is_overdue(Instant::now()));
+            let started_at = Instant::now() - Duration::from_millis(strike.max_exposure_time_ms + 1);
+            assert!(strike.is_overdue(started_at));
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLog(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLog {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CapturedLog {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_strike_events_carry_an_execution_id_per_strike() {
+        let captured = CapturedLog::default();
+        let subscriber = tracing_subscriber::fmt().json().with_writer(captured.clone()).finish();
+        let mut engine = MacroStrikeEngine::new().with_rng(SimRng::seeded(3));
+        {
+            let _guard = tracing::subscriber::set_default(subscriber);
+            for _ in 0..2 {
+                let strike = engine.generate_strike().await;
+                engine.execute_strike(strike).await.unwrap();
+            }
+        }
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let execution_ids: Vec<String> = output
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("one JSON object per line"))
+            .map(|event| {
+                assert_eq!(event["span"]["name"], "strike");
+                event["span"]["execution_id"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert!(execution_ids.len() >= 2);
+        let mut distinct = execution_ids.clone();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 2);
+        assert!(distinct.iter().all(|id| Uuid::parse_str(id).is_ok()));
+    }
+
+    #[test]
+    fn test_parse_seed_flag() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_iter();
+        assert_eq!(parse_seed(args(&[])), Ok(None));
+        assert_eq!(parse_seed(args(&["--seed", "42"])), Ok(Some(42)));
+        assert_eq!(parse_seed(args(&["-v", "--seed=7"])), Ok(Some(7)));
+        assert!(parse_seed(args(&["--seed"])).is_err());
+        assert!(parse_seed(args(&["--seed", "abc"])).is_err());
+    }
+
+    #[test]
+    fn test_adaptive_strike_force_stays_within_bounds() {
+        let mut engine = MacroStrikeEngine::new();
+        assert_eq!(engine.adaptive_strike_force(), STRIKE_FORCE);
+
+        // All misses: floor at 0.5x
+        for _ in 0..ADAPTIVE_WINDOW {
+            engine.metrics.recent_outcomes.push(false);
+        }
+        assert!((engine.adaptive_strike_force() - STRIKE_FORCE * ADAPTIVE_MIN_MULTIPLIER).abs() < 1e-12);
+
+        // All hits: 1/0.9 stays under the default 1.5x ceiling
+        for _ in 0..ADAPTIVE_WINDOW {
+            engine.metrics.recent_outcomes.push(true);
+        }
+        assert!((engine.adaptive_strike_force() - STRIKE_FORCE / PRECISION_THRESHOLD).abs() < 1e-12);
+
+        // A tighter ceiling binds
+        engine.adaptive_sizing_max_multiplier = 1.05;
+        assert!((engine.adaptive_strike_force() - STRIKE_FORCE * 1.05).abs() < 1e-12);
+
+        for hits in 0..=ADAPTIVE_WINDOW {
+            engine.metrics.recent_outcomes.clear();
+            for i in 0..ADAPTIVE_WINDOW {
+                engine.metrics.recent_outcomes.push(i < hits);
+            }
+            let force = engine.adaptive_strike_force();
+            assert!(force >= STRIKE_FORCE * ADAPTIVE_MIN_MULTIPLIER - 1e-12);
+            assert!(force <= STRIKE_FORCE * engine.adaptive_sizing_max_multiplier + 1e-12);
+        }
+
+        engine.adaptive_sizing_enabled = false;
+        assert_eq!(engine.adaptive_strike_force(), STRIKE_FORCE);
+    }
+
+    #[test]
+    fn test_cooldown_doubles_per_miss_up_to_cap() {
+        let mut engine = MacroStrikeEngine::new();
+        assert_eq!(engine.current_cooldown_ms(), STRIKE_COOLDOWN_MS);
+
+        for (misses, expected) in [(1, 2), (3, 8), (12, 4096), (13, MAX_STRIKE_COOLDOWN_MS), (200, MAX_STRIKE_COOLDOWN_MS)] {
+            engine.consecutive_misses.store(misses, Ordering::Relaxed);
+            assert_eq!(engine.current_cooldown_ms(), expected, "{} misses", misses);
+        }
+
+        engine.dynamic_cooldown_enabled = false;
+        assert_eq!(engine.current_cooldown_ms(), STRIKE_COOLDOWN_MS);
+    }
+
+    #[tokio::test]
+    async fn test_effective_leverage_scales_with_realized_vol() {
+        let engine = MacroStrikeEngine::new();
+        let mut strike = engine.generate_strike().await;
+        strike.strike_type = StrikeType::MacroMomentum;
+
+        assert_eq!(engine.effective_leverage(&strike, LEVERAGE_TARGET_VOL), 3);
+        // Calm symbol: capped at 1.5x the multiplier
+        assert_eq!(engine.effective_leverage(&strike, 0.0025), 4);
+        // Choppy symbol: floored at 0.5x
+        assert_eq!(engine.effective_leverage(&strike, 0.1), 1);
+        assert_eq!(engine.effective_leverage(&strike, 0.02), 2);
+        // Too little vol to go on
+        assert_eq!(engine.effective_leverage(&strike, 0.0005), IMPACT_MULTIPLIER as u32);
+
+        strike.strike_type = StrikeType::MacroArbitrage;
+        assert_eq!(engine.effective_leverage(&strike, 0.0025), 1);
+    }
+
+    #[tokio::test]
+    async fn test_high_realized_vol_cuts_leverage() {
+        let mut engine = MacroStrikeEngine::new();
+        let mut strike = engine.generate_strike().await;
+        strike.strike_type = StrikeType::MacroVolatility;
+
+        // Strike returns feed the same per-strike vol that sizes leverage
+        for i in 0..20 {
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            engine.update_realized_vol("CALM/USDC", sign * 0.004);
+            engine.update_realized_vol("WILD/USDC", sign * 0.06);
+        }
+        let calm = engine.effective_leverage(&strike, engine.realized_vol("CALM/USDC").unwrap());
+        let wild = engine.effective_leverage(&strike, engine.realized_vol("WILD/USDC").unwrap());
+        assert!(calm > IMPACT_MULTIPLIER as u32, "calm {}", calm);
+        assert!(wild < IMPACT_MULTIPLIER as u32, "wild {}", wild);
+    }
+
+    #[test]
+    fn test_realized_vol_is_ewma_per_symbol() {
+        let mut engine = MacroStrikeEngine::new();
+        assert_eq!(engine.realized_vol("ETH/USDC"), None);
+        engine.update_realized_vol("ETH/USDC", -0.02);
+        assert!((engine.realized_vol("ETH/USDC").unwrap() - 0.02).abs() < 1e-12);
+
+        engine.update_realized_vol("ETH/USDC", 0.0);
+        let expected = ((1.0 - REALIZED_VOL_ALPHA) * 0.02 * 0.02_f64).sqrt();
+        assert!((engine.realized_vol("ETH/USDC").unwrap() - expected).abs() < 1e-12);
+        assert_eq!(engine.realized_vol("BTC/USDC"), None);
+    }
+}
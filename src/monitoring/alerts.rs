@@ -117,6 +117,13 @@ impl AlertManager {
                 title: "High Exposure".to_string(),
                 message_template: "Total exposure at ${value:.2}".to_string(),
             },
+            AlertRule {
+                metric_type: MetricType::IndexCoverage,
+                condition: AlertCondition::Below(0.80),
+                level: AlertLevel::Warning,
+                title: "Index Coverage Degraded".to_string(),
+                message_template: "Market index priced from {value} of basket weight".to_string(),
+            },
             
             // System alerts
             AlertRule {
@@ -190,10 +197,10 @@ impl AlertManager {
 
         // Log the alert
         match level {
-            AlertLevel::Info => log::info!("ALERT: {} - {}", title, message),
-            AlertLevel::Warning => log::warn!("ALERT: {} - {}", title, message),
-            AlertLevel::Error => log::error!("ALERT: {} - {}", title, message),
-            AlertLevel::Critical => log::error!("CRITICAL ALERT: {} - {}", title, message),
+            AlertLevel::Info => tracing::info!("ALERT: {} - {}", title, message),
+            AlertLevel::Warning => tracing::warn!("ALERT: {} - {}", title, message),
+            AlertLevel::Error => tracing::error!("ALERT: {} - {}", title, message),
+            AlertLevel::Critical => tracing::error!("CRITICAL ALERT: {} - {}", title, message),
         }
 
         // Store alert
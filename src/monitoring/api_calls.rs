@@ -0,0 +1,304 @@
+// API Call Accounting
+// Lock-free per-provider call counters with daily cost estimation and budget alerts
+
+use super::alerts::{AlertLevel, AlertManager};
+use super::{MetricType, MonitoringSystem};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const MIN_PROJECTION_ELAPSED_SECONDS: i64 = 3_600; // Don't extrapolate a day from the first minutes
+
+/// External API providers we pay for or are rate limited by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApiProvider {
+    CoinGecko,
+    Kraken,
+    EipRpc,
+}
+
+impl ApiProvider {
+    pub const ALL: [ApiProvider; 3] = [ApiProvider::CoinGecko, ApiProvider::Kraken, ApiProvider::EipRpc];
+
+    fn index(self) -> usize {
+        match self {
+            ApiProvider::CoinGecko => 0,
+            ApiProvider::Kraken => 1,
+            ApiProvider::EipRpc => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiCallOutcome {
+    Success,
+    Failure,
+    RateLimited,
+}
+
+/// Per-call cost and daily budget per provider, in USD
+#[derive(Debug, Clone, Default)]
+pub struct ApiCostTable {
+    pub cost_per_call: HashMap<ApiProvider, f64>,
+    pub daily_budget: HashMap<ApiProvider, f64>,
+}
+
+impl ApiCostTable {
+    pub fn with_provider(mut self, provider: ApiProvider, cost_per_call: f64, daily_budget: Option<f64>) -> Self {
+        self.cost_per_call.insert(provider, cost_per_call);
+        if let Some(budget) = daily_budget {
+            self.daily_budget.insert(provider, budget);
+        }
+        self
+    }
+}
+
+/// Estimated spend for one provider over the current UTC day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderSpend {
+    pub calls_today: u64,
+    pub errors_today: u64,
+    pub spend_today_usd: f64,
+    pub projected_daily_usd: f64,
+    pub daily_budget_usd: Option<f64>,
+}
+
+#[derive(Default)]
+struct ProviderCounters {
+    day: AtomicI64,
+    calls_today: AtomicU64,
+    errors_today: AtomicU64,
+    calls_since_flush: AtomicU64,
+    latency_ms_since_flush: AtomicU64,
+    budget_alerted_day: AtomicI64,
+}
+
+/// Called by the API clients on every request. Recording only touches atomics and
+/// cannot fail, so it never affects the call being recorded.
+pub struct ApiCallRecorder {
+    counters: [ProviderCounters; 3],
+    costs: ApiCostTable,
+}
+
+impl ApiCallRecorder {
+    pub fn new(costs: ApiCostTable) -> Self {
+        Self {
+            counters: Default::default(),
+            costs,
+        }
+    }
+
+    pub fn record(&self, provider: ApiProvider, endpoint: &str, outcome: ApiCallOutcome, latency: Duration) {
+        self.record_at(provider, endpoint, outcome, latency, Utc::now());
+    }
+
+    /// Record a finished call from its result, timed from `started`
+    pub fn record_result<T, E>(&self, provider: ApiProvider, endpoint: &str, started: Instant, result: &Result<T, E>) {
+        let outcome = if result.is_ok() { ApiCallOutcome::Success } else { ApiCallOutcome::Failure };
+        self.record(provider, endpoint, outcome, started.elapsed());
+    }
+
+    fn record_at(
+        &self,
+        provider: ApiProvider,
+        endpoint: &str,
+        outcome: ApiCallOutcome,
+        latency: Duration,
+        now: DateTime<Utc>,
+    ) {
+        let counters = &self.counters[provider.index()];
+        Self::roll_day(counters, day_number(now));
+
+        counters.calls_today.fetch_add(1, Ordering::Relaxed);
+        counters.calls_since_flush.fetch_add(1, Ordering::Relaxed);
+        counters.latency_ms_since_flush.fetch_add(latency.as_millis() as u64, Ordering::Relaxed);
+        if outcome != ApiCallOutcome::Success {
+            counters.errors_today.fetch_add(1, Ordering::Relaxed);
+            tracing::debug!("{:?} call to {} ended {:?}", provider, endpoint, outcome);
+        }
+    }
+
+    /// Zero the daily counters the first time a call lands on a new UTC day
+    fn roll_day(counters: &ProviderCounters, today: i64) {
+        let seen = counters.day.load(Ordering::Relaxed);
+        if seen != today
+            && counters.day.compare_exchange(seen, today, Ordering::Relaxed, Ordering::Relaxed).is_ok()
+        {
+            counters.calls_today.store(0, Ordering::Relaxed);
+            counters.errors_today.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub fn spend(&self, provider: ApiProvider) -> ProviderSpend {
+        self.spend_at(provider, Utc::now())
+    }
+
+    fn spend_at(&self, provider: ApiProvider, now: DateTime<Utc>) -> ProviderSpend {
+        let counters = &self.counters[provider.index()];
+        let current = counters.day.load(Ordering::Relaxed) == day_number(now);
+        let calls_today = if current { counters.calls_today.load(Ordering::Relaxed) } else { 0 };
+        let errors_today = if current { counters.errors_today.load(Ordering::Relaxed) } else { 0 };
+        let spend_today_usd = calls_today as f64 * self.costs.cost_per_call.get(&provider).copied().unwrap_or(0.0);
+
+        ProviderSpend {
+            calls_today,
+            errors_today,
+            spend_today_usd,
+            projected_daily_usd: project_daily_spend(spend_today_usd, now.timestamp().rem_euclid(SECONDS_PER_DAY)),
+            daily_budget_usd: self.costs.daily_budget.get(&provider).copied(),
+        }
+    }
+
+    pub fn spend_by_provider(&self) -> HashMap<ApiProvider, ProviderSpend> {
+        let now = Utc::now();
+        ApiProvider::ALL.iter().map(|&p| (p, self.spend_at(p, now))).collect()
+    }
+
+    /// Push calls and mean latency since the last flush into the monitoring series, then
+    /// alert (once per provider per day) on projected spend over budget
+    pub async fn flush(&self, monitoring: &MonitoringSystem) {
+        self.flush_at(monitoring, Utc::now()).await;
+    }
+
+    async fn flush_at(&self, monitoring: &MonitoringSystem, now: DateTime<Utc>) {
+        let mut calls = 0;
+        let mut latency_ms = 0;
+        for counters in &self.counters {
+            calls += counters.calls_since_flush.swap(0, Ordering::Relaxed);
+            latency_ms += counters.latency_ms_since_flush.swap(0, Ordering::Relaxed);
+        }
+        monitoring.record_metric(MetricType::APICallCount, calls as f64).await;
+        if calls > 0 {
+            monitoring.record_metric(MetricType::Latency, latency_ms as f64 / calls as f64).await;
+        }
+
+        self.check_budgets(&monitoring.alert_manager, now).await;
+    }
+
+    async fn check_budgets(&self, alert_manager: &AlertManager, now: DateTime<Utc>) {
+        let today = day_number(now);
+        for provider in ApiProvider::ALL {
+            let spend = self.spend_at(provider, now);
+            let Some(budget) = spend.daily_budget_usd else {
+                continue;
+            };
+            if spend.projected_daily_usd <= budget {
+                continue;
+            }
+            let counters = &self.counters[provider.index()];
+            if counters.budget_alerted_day.swap(today, Ordering::Relaxed) == today {
+                continue;
+            }
+            alert_manager.send_alert(
+                AlertLevel::Warning,
+                "API Budget Exceeded",
+                &format!(
+                    "{:?} projected at ${:.2}/day against a ${:.2} budget ({} calls, ${:.2} so far)",
+                    provider, spend.projected_daily_usd, budget, spend.calls_today, spend.spend_today_usd,
+                ),
+            ).await;
+        }
+    }
+}
+
+impl Default for ApiCallRecorder {
+    fn default() -> Self {
+        Self::new(ApiCostTable::default())
+    }
+}
+
+fn day_number(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(SECONDS_PER_DAY)
+}
+
+/// Extrapolate spend so far to a full UTC day at the same rate
+pub fn project_daily_spend(spend_so_far: f64, elapsed_seconds: i64) -> f64 {
+    let elapsed = elapsed_seconds.clamp(MIN_PROJECTION_ELAPSED_SECONDS, SECONDS_PER_DAY);
+    spend_so_far * SECONDS_PER_DAY as f64 / elapsed as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn create_test_recorder() -> ApiCallRecorder {
+        ApiCallRecorder::new(
+            ApiCostTable::default()
+                .with_provider(ApiProvider::CoinGecko, 0.002, Some(10.0))
+                .with_provider(ApiProvider::EipRpc, 0.0001, None),
+        )
+    }
+
+    #[test]
+    fn test_counters_aggregate_and_roll_over_at_midnight() {
+        let recorder = create_test_recorder();
+        let noon = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+
+        for i in 0..1_000 {
+            let outcome = if i % 100 == 0 { ApiCallOutcome::RateLimited } else { ApiCallOutcome::Success };
+            recorder.record_at(ApiProvider::CoinGecko, "/coins", outcome, Duration::from_millis(40), noon);
+        }
+        recorder.record_at(ApiProvider::Kraken, "/0/public/Depth", ApiCallOutcome::Failure, Duration::from_millis(90), noon);
+
+        let spend = recorder.spend_at(ApiProvider::CoinGecko, noon);
+        assert_eq!(spend.calls_today, 1_000);
+        assert_eq!(spend.errors_today, 10);
+        assert!((spend.spend_today_usd - 2.0).abs() < 1e-9);
+        assert!((spend.projected_daily_usd - 4.0).abs() < 1e-9);
+
+        // No cost configured for Kraken: counted but free
+        let kraken = recorder.spend_at(ApiProvider::Kraken, noon);
+        assert_eq!((kraken.calls_today, kraken.errors_today), (1, 1));
+        assert_eq!(kraken.spend_today_usd, 0.0);
+
+        // Yesterday's counters read as zero, and the first call today resets them
+        let tomorrow = noon + chrono::Duration::days(1);
+        assert_eq!(recorder.spend_at(ApiProvider::CoinGecko, tomorrow).calls_today, 0);
+        recorder.record_at(ApiProvider::CoinGecko, "/coins", ApiCallOutcome::Success, Duration::ZERO, tomorrow);
+        let spend = recorder.spend_at(ApiProvider::CoinGecko, tomorrow);
+        assert_eq!((spend.calls_today, spend.errors_today), (1, 0));
+    }
+
+    #[test]
+    fn test_projection_extrapolates_by_time_of_day() {
+        assert!((project_daily_spend(3.0, 6 * 3_600) - 12.0).abs() < 1e-9);
+        assert!((project_daily_spend(3.0, SECONDS_PER_DAY) - 3.0).abs() < 1e-9);
+        // Two minutes past midnight is projected as if an hour had passed
+        assert!((project_daily_spend(1.0, 120) - 24.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_flush_records_metrics_and_alerts_once_over_budget() {
+        let monitoring = MonitoringSystem::new();
+        let recorder = create_test_recorder();
+        let morning = Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap();
+
+        // $2.40 by 06:00 projects to $9.60: under the $10 budget
+        for _ in 0..1_200 {
+            recorder.record_at(ApiProvider::CoinGecko, "/coins", ApiCallOutcome::Success, Duration::from_millis(50), morning);
+        }
+        recorder.flush_at(&monitoring, morning).await;
+        assert_eq!(monitoring.get_metric(&MetricType::APICallCount).await, Some(1_200.0));
+        assert_eq!(monitoring.get_metric(&MetricType::Latency).await, Some(50.0));
+        assert!(monitoring.alert_manager.get_alerts(10).await.iter().all(|a| a.title != "API Budget Exceeded"));
+
+        // $2.60 projects to $10.40
+        for _ in 0..100 {
+            recorder.record_at(ApiProvider::CoinGecko, "/coins", ApiCallOutcome::Success, Duration::from_millis(50), morning);
+        }
+        recorder.flush_at(&monitoring, morning).await;
+        recorder.flush_at(&monitoring, morning).await;
+        assert_eq!(monitoring.get_metric(&MetricType::APICallCount).await, Some(0.0));
+
+        let budget_alerts: Vec<_> = monitoring.alert_manager.get_alerts(10).await
+            .into_iter()
+            .filter(|a| a.title == "API Budget Exceeded")
+            .collect();
+        assert_eq!(budget_alerts.len(), 1);
+        assert!(budget_alerts[0].message.contains("CoinGecko"));
+    }
+}
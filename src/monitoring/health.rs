@@ -1,7 +1,7 @@
 // Health Monitoring Module
 // Monitors system health and provides health status
 
-use super::{MetricType, TimeSeries};
+use super::{ChangeDirection, MetricType, TimeSeries};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -58,6 +58,7 @@ struct HealthThresholds {
     latency_critical: f64,
     memory_warning: f64,
     memory_critical: f64,
+    regime_change_recency: usize, // Regime changes within this many samples count as current
 }
 
 impl Default for HealthThresholds {
@@ -71,6 +72,7 @@ impl Default for HealthThresholds {
             latency_critical: 1000.0,
             memory_warning: 80.0,
             memory_critical: 90.0,
+            regime_change_recency: 20,
         }
     }
 }
@@ -189,6 +191,23 @@ impl HealthMonitor {
                 }
                 details.push(format!("Win rate: {:.1}%", win_rate * 100.0));
             }
+
+            // Sudden deterioration even if the level is still above thresholds
+            if let Some(change) = win_rate_ts.latest_regime_change() {
+                let recent = change.index + self.thresholds.regime_change_recency >= win_rate_ts.len();
+                if recent && change.direction == ChangeDirection::Down {
+                    status = status.max(HealthLevel::Degraded);
+                    issues.push(HealthIssue {
+                        severity: HealthLevel::Degraded,
+                        component: "Trading".to_string(),
+                        description: format!(
+                            "Win rate regime shift down by {:.1}%",
+                            change.magnitude * 100.0
+                        ),
+                        recommendation: "Investigate recent strategy or market changes".to_string(),
+                    });
+                }
+            }
         }
 
         // Check consecutive losses
@@ -6,10 +6,17 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
+use strike_box::RingBuffer;
 
 pub mod alerts;
 pub mod metrics;
 pub mod health;
+pub mod api_calls;
+
+const CUSUM_WARMUP: usize = 5;        // Values averaged for the initial reference level
+const CUSUM_SLACK_SIGMAS: f64 = 0.5;  // Drift allowance k, in standard deviations
+const WIN_RATE_REGIME_SENSITIVITY: f64 = 4.0;
+const API_CALL_FLUSH_SECONDS: u64 = 60; // APICallCount is read as calls per minute
 
 /// System metric types
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -35,6 +42,26 @@ pub enum MetricType {
     Exposure,
     DrawDown,
     
+    // Portfolio metrics, published from the Strike Box engine
+    GrossExposure,     // Fraction of capital; Exposure stays in USD
+    NetExposure,
+    WeeklyDrawDown,
+    MonthlyDrawDown,
+    BookUtilization,   // Allocation over max of the fuller book
+    OpenLongPositions,
+    OpenShortPositions,
+    AvailableCapital,
+    LongUnrealizedPnL,
+    ShortUnrealizedPnL,
+    ConsecutiveFailures,
+    ValidationCacheHits,      // Cumulative; only published when the engine's cache is enabled
+    ValidationCacheMisses,
+    ValidationCacheEvictions,
+    
+    // Market metrics
+    IndexLevel,
+    IndexCoverage,
+    
     // Strike metrics
     StrikeOptimized,
     SharpeRatio,
@@ -48,20 +75,35 @@ pub struct MetricValue {
     pub timestamp: SystemTime,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeDirection {
+    Up,
+    Down,
+}
+
+/// Change point found by CUSUM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegimeChange {
+    pub index: usize,
+    pub timestamp: SystemTime,
+    pub direction: ChangeDirection,
+    pub magnitude: f64, // Estimated shift in the mean
+}
+
 /// Time series data for metrics
 #[derive(Debug, Clone)]
 pub struct TimeSeries {
     pub metric_type: MetricType,
-    pub values: Vec<MetricValue>,
-    pub max_size: usize,
+    pub values: RingBuffer<MetricValue>,
+    regime_changes: Vec<RegimeChange>,
 }
 
 impl TimeSeries {
     pub fn new(metric_type: MetricType, max_size: usize) -> Self {
         Self {
             metric_type,
-            values: Vec::with_capacity(max_size),
-            max_size,
+            values: RingBuffer::new(max_size),
+            regime_changes: Vec::new(),
         }
     }
 
@@ -71,12 +113,84 @@ impl TimeSeries {
             timestamp: SystemTime::now(),
         };
         
+        // Oldest value is evicted once the series is full
         self.values.push(metric_value);
-        
-        // Keep only the most recent values
-        if self.values.len() > self.max_size {
-            self.values.remove(0);
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    pub fn evicted_count(&self) -> u64 {
+        self.values.evicted_count()
+    }
+
+    /// Two-sided CUSUM over the buffered values. A change is recorded when either statistic
+    /// exceeds `sensitivity` standard deviations; both statistics then reset against the new level.
+    pub fn detect_regime_change(&mut self, sensitivity: f64) -> Vec<RegimeChange> {
+        let values: Vec<f64> = self.values.iter().map(|v| v.value).collect();
+        self.regime_changes.clear();
+        if values.len() <= CUSUM_WARMUP {
+            return Vec::new();
+        }
+
+        // Noise scale from successive differences so the shift itself doesn't inflate sigma
+        let mean_abs_diff = values.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>()
+            / (values.len() - 1) as f64;
+        let sigma = mean_abs_diff * std::f64::consts::PI.sqrt() / 2.0;
+        if sigma <= 0.0 {
+            return Vec::new();
+        }
+
+        let slack = CUSUM_SLACK_SIGMAS * sigma;
+        let threshold = sensitivity * sigma;
+        let mut reference = values[..CUSUM_WARMUP].iter().sum::<f64>() / CUSUM_WARMUP as f64;
+        let (mut upper, mut lower) = (0.0_f64, 0.0_f64);
+        let mut resume_at = CUSUM_WARMUP;
+
+        for (index, value) in values.iter().enumerate().skip(CUSUM_WARMUP) {
+            if index < resume_at {
+                continue;
+            }
+            upper = (upper + value - reference - slack).max(0.0);
+            lower = (lower + reference - value - slack).max(0.0);
+
+            let direction = if upper > threshold {
+                ChangeDirection::Up
+            } else if lower > threshold {
+                ChangeDirection::Down
+            } else {
+                continue;
+            };
+
+            // Re-estimate the reference from the first values of the new regime
+            let end = (index + CUSUM_WARMUP).min(values.len());
+            let new_reference = values[index..end].iter().sum::<f64>() / (end - index) as f64;
+            self.regime_changes.push(RegimeChange {
+                index,
+                timestamp: self.values.get(index).map(|v| v.timestamp).unwrap_or_else(SystemTime::now),
+                direction,
+                magnitude: (new_reference - reference).abs(),
+            });
+            reference = new_reference;
+            upper = 0.0;
+            lower = 0.0;
+            resume_at = end;
         }
+
+        self.regime_changes.clone()
+    }
+
+    pub fn latest_regime_change(&self) -> Option<&RegimeChange> {
+        self.regime_changes.last()
     }
 
     pub fn latest(&self) -> Option<f64> {
@@ -116,6 +230,7 @@ pub struct MonitoringSystem {
     metrics: Arc<RwLock<HashMap<MetricType, TimeSeries>>>,
     alert_manager: Arc<alerts::AlertManager>,
     health_monitor: Arc<health::HealthMonitor>,
+    api_calls: Arc<api_calls::ApiCallRecorder>,
 }
 
 impl MonitoringSystem {
@@ -139,6 +254,22 @@ impl MonitoringSystem {
             MetricType::ErrorCount,
             MetricType::Exposure,
             MetricType::DrawDown,
+            MetricType::GrossExposure,
+            MetricType::NetExposure,
+            MetricType::WeeklyDrawDown,
+            MetricType::MonthlyDrawDown,
+            MetricType::BookUtilization,
+            MetricType::OpenLongPositions,
+            MetricType::OpenShortPositions,
+            MetricType::AvailableCapital,
+            MetricType::LongUnrealizedPnL,
+            MetricType::ShortUnrealizedPnL,
+            MetricType::ConsecutiveFailures,
+            MetricType::ValidationCacheHits,
+            MetricType::ValidationCacheMisses,
+            MetricType::ValidationCacheEvictions,
+            MetricType::IndexLevel,
+            MetricType::IndexCoverage,
             MetricType::SharpeRatio,
             MetricType::MaxDrawDown,
         ] {
@@ -149,9 +280,21 @@ impl MonitoringSystem {
             metrics: Arc::new(RwLock::new(metrics)),
             alert_manager: Arc::new(alerts::AlertManager::new()),
             health_monitor: Arc::new(health::HealthMonitor::new()),
+            api_calls: Arc::new(api_calls::ApiCallRecorder::default()),
         }
     }
 
+    /// Price API calls per provider for spend estimates and budget alerts
+    pub fn with_api_costs(mut self, costs: api_calls::ApiCostTable) -> Self {
+        self.api_calls = Arc::new(api_calls::ApiCallRecorder::new(costs));
+        self
+    }
+
+    /// Shared recorder to hand to the API clients
+    pub fn api_call_recorder(&self) -> Arc<api_calls::ApiCallRecorder> {
+        self.api_calls.clone()
+    }
+
     /// Record a metric value
     pub async fn record_metric(&self, metric_type: MetricType, value: f64) {
         let mut metrics = self.metrics.write().await;
@@ -163,6 +306,30 @@ impl MonitoringSystem {
         self.alert_manager.check_metric(&metric_type, value).await;
     }
 
+    /// Record several values at once, or none if the metrics lock is held elsewhere.
+    /// For callers on the trading path that would rather drop a sample than wait.
+    pub async fn try_record_metrics(&self, values: &[(MetricType, f64)]) -> bool {
+        {
+            let Ok(mut metrics) = self.metrics.try_write() else {
+                return false;
+            };
+            for (metric_type, value) in values {
+                if let Some(time_series) = metrics.get_mut(metric_type) {
+                    time_series.push(*value);
+                }
+            }
+        }
+
+        for (metric_type, value) in values {
+            self.alert_manager.check_metric(metric_type, *value).await;
+        }
+        true
+    }
+
+    pub fn alert_manager(&self) -> Arc<alerts::AlertManager> {
+        self.alert_manager.clone()
+    }
+
     /// Get current metric value
     pub async fn get_metric(&self, metric_type: &MetricType) -> Option<f64> {
         let metrics = self.metrics.read().await;
@@ -177,12 +344,20 @@ impl MonitoringSystem {
             average: ts.average().unwrap_or(0.0),
             min: ts.min().unwrap_or(0.0),
             max: ts.max().unwrap_or(0.0),
-            count: ts.values.len(),
+            count: ts.len(),
+            capacity: ts.capacity(),
+            evicted: ts.evicted_count(),
         })
     }
 
     /// Get system health status
     pub async fn get_health_status(&self) -> health::HealthStatus {
+        {
+            let mut metrics = self.metrics.write().await;
+            if let Some(win_rate) = metrics.get_mut(&MetricType::WinRate) {
+                win_rate.detect_regime_change(WIN_RATE_REGIME_SENSITIVITY);
+            }
+        }
         self.health_monitor.get_status(&self.metrics).await
     }
 
@@ -213,6 +388,19 @@ impl MonitoringSystem {
         });
     }
 
+    /// Periodically fold recorded API calls into the metric series; needs an Arc to outlive the caller
+    pub fn start_api_call_flush(self: &Arc<Self>) {
+        let monitoring = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(API_CALL_FLUSH_SECONDS));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                monitoring.api_calls.flush(&monitoring).await;
+            }
+        });
+    }
+
     /// Export metrics snapshot
     pub async fn export_snapshot(&self) -> MetricsSnapshot {
         let metrics = self.metrics.read().await;
@@ -228,6 +416,7 @@ impl MonitoringSystem {
             timestamp: SystemTime::now(),
             metrics: snapshot,
             health: self.get_health_status().await,
+            api_spend: self.api_calls.spend_by_provider(),
         }
     }
 }
@@ -240,6 +429,8 @@ pub struct MetricStats {
     pub min: f64,
     pub max: f64,
     pub count: usize,
+    pub capacity: usize,
+    pub evicted: u64,
 }
 
 /// Complete metrics snapshot
@@ -248,6 +439,7 @@ pub struct MetricsSnapshot {
     pub timestamp: SystemTime,
     pub metrics: HashMap<MetricType, MetricStats>,
     pub health: health::HealthStatus,
+    pub api_spend: HashMap<api_calls::ApiProvider, api_calls::ProviderSpend>,
 }
 
 #[cfg(test)]
@@ -263,11 +455,77 @@ mod tests {
         ts.push(0.85);
         
         assert_eq!(ts.latest(), Some(0.85));
-        assert_eq!(ts.average(), Some(0.80));
+        assert!((ts.average().unwrap() - 0.80).abs() < 1e-12);
         assert_eq!(ts.min(), Some(0.75));
         assert_eq!(ts.max(), Some(0.85));
     }
 
+    #[tokio::test]
+    async fn test_time_series_evicts_oldest() {
+        let mut ts = TimeSeries::new(MetricType::Latency, 3);
+        for value in [1.0, 2.0, 3.0, 4.0, 5.0] {
+            ts.push(value);
+        }
+
+        let values: Vec<f64> = ts.values.iter().map(|v| v.value).collect();
+        assert_eq!(values, vec![3.0, 4.0, 5.0]);
+        assert_eq!(ts.len(), 3);
+        assert_eq!(ts.capacity(), 3);
+        assert_eq!(ts.evicted_count(), 2);
+    }
+
+    #[test]
+    fn test_detect_regime_change_on_step() {
+        let mut ts = TimeSeries::new(MetricType::WinRate, 100);
+        // Small alternating noise around 0.80, then a drop to 0.60
+        for i in 0..60 {
+            let noise = if i % 2 == 0 { 0.01 } else { -0.01 };
+            ts.push(if i < 30 { 0.80 } else { 0.60 } + noise);
+        }
+
+        let changes = ts.detect_regime_change(5.0);
+        assert_eq!(changes.len(), 1);
+        let change = ts.latest_regime_change().unwrap();
+        assert_eq!(change.direction, ChangeDirection::Down);
+        assert_eq!(change.index, 30);
+        assert!((change.magnitude - 0.20).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_regime_change_on_stationary_series() {
+        let mut ts = TimeSeries::new(MetricType::WinRate, 100);
+        for i in 0..60 {
+            ts.push(if i % 2 == 0 { 0.81 } else { 0.79 });
+        }
+        assert!(ts.detect_regime_change(5.0).is_empty());
+        assert!(ts.latest_regime_change().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_health_flags_win_rate_regime_drop() {
+        let monitor = MonitoringSystem::new();
+        for i in 0..40 {
+            let noise = if i % 2 == 0 { 0.01 } else { -0.01 };
+            // Still above the warning threshold, but well below the earlier regime
+            let win_rate = if i < 30 { 0.90 } else { 0.70 };
+            monitor.record_metric(MetricType::WinRate, win_rate + noise).await;
+        }
+
+        let status = monitor.get_health_status().await;
+        assert!(status.issues.iter().any(|i| i.description.contains("regime")));
+    }
+
+    #[tokio::test]
+    async fn test_try_record_skips_when_metrics_locked() {
+        let monitor = MonitoringSystem::new();
+        let held = monitor.metrics.write().await;
+        assert!(!monitor.try_record_metrics(&[(MetricType::DrawDown, 0.05)]).await);
+        drop(held);
+
+        assert!(monitor.try_record_metrics(&[(MetricType::DrawDown, 0.05)]).await);
+        assert_eq!(monitor.get_metric(&MetricType::DrawDown).await, Some(0.05));
+    }
+
     #[tokio::test]
     async fn test_monitoring_system() {
         let monitor = MonitoringSystem::new();
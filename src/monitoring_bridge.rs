@@ -0,0 +1,218 @@
+// Strike Box → Monitoring Bridge
+// Rate-limited portfolio snapshots from the engine into the monitoring metric series
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+use rust_decimal::Decimal;
+use tokio::sync::RwLock;
+use strike_box::{PositionBook, StrikeBoxEngine};
+use strike_box::conv;
+use crate::monitoring::{MetricType, MonitoringSystem};
+
+const DEFAULT_PUBLISH_INTERVAL_SECONDS: u64 = 5;
+
+pub struct MonitoringBridge {
+    monitoring: Arc<MonitoringSystem>,
+    min_interval: Duration,
+    last_published: Mutex<Option<Instant>>,
+}
+
+impl MonitoringBridge {
+    pub fn new(monitoring: Arc<MonitoringSystem>) -> Self {
+        Self {
+            monitoring,
+            min_interval: Duration::from_secs(DEFAULT_PUBLISH_INTERVAL_SECONDS),
+            last_published: Mutex::new(None),
+        }
+    }
+
+    /// Minimum time between two published snapshots; calls in between are dropped
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Portfolio metrics for the engine's current state
+    pub fn snapshot(engine: &StrikeBoxEngine) -> Vec<(MetricType, f64)> {
+        let portfolio = &engine.portfolio;
+        let utilization = |book: &PositionBook| {
+            if book.max_allocation_usd > Decimal::ZERO {
+                conv::decimal_to_f64(book.total_allocation_usd / book.max_allocation_usd)
+            } else {
+                0.0
+            }
+        };
+
+        let mut metrics = vec![
+            (MetricType::Exposure, conv::usd_to_f64(portfolio.gross_exposure_usd)),
+            (MetricType::GrossExposure, conv::decimal_to_f64(portfolio.gross_exposure_pct)),
+            (MetricType::NetExposure, conv::decimal_to_f64(portfolio.net_exposure_pct)),
+            (MetricType::DrawDown, conv::decimal_to_f64(portfolio.daily_drawdown_pct)),
+            (MetricType::WeeklyDrawDown, conv::decimal_to_f64(portfolio.weekly_drawdown_pct)),
+            (MetricType::MonthlyDrawDown, conv::decimal_to_f64(portfolio.monthly_drawdown_pct)),
+            (
+                MetricType::BookUtilization,
+                utilization(&portfolio.long_book).max(utilization(&portfolio.short_book)),
+            ),
+            (MetricType::OpenLongPositions, portfolio.long_book.open_position_count() as f64),
+            (MetricType::OpenShortPositions, portfolio.short_book.open_position_count() as f64),
+            (MetricType::AvailableCapital, conv::usd_to_f64(portfolio.available_capital_usd)),
+            (MetricType::LongUnrealizedPnL, conv::usd_to_f64(portfolio.long_book.unrealized_pnl_usd)),
+            (MetricType::ShortUnrealizedPnL, conv::usd_to_f64(portfolio.short_book.unrealized_pnl_usd)),
+            (MetricType::ConsecutiveFailures, portfolio.consecutive_failures as f64),
+        ];
+        if let Some(stats) = engine.validation_cache_stats() {
+            metrics.extend([
+                (MetricType::ValidationCacheHits, stats.hits as f64),
+                (MetricType::ValidationCacheMisses, stats.misses as f64),
+                (MetricType::ValidationCacheEvictions, stats.evictions as f64),
+            ]);
+        }
+        metrics
+    }
+
+    /// Record a snapshot unless one went out within the interval or the monitoring
+    /// lock is busy. Never waits on monitoring, so it is safe to call on every price tick.
+    /// Returns whether the snapshot was recorded.
+    pub async fn publish(&self, engine: &StrikeBoxEngine) -> bool {
+        let now = Instant::now();
+        // Claim the slot before recording, so concurrent callers cannot both pass the check
+        let previous = {
+            let Ok(mut last_published) = self.last_published.try_lock() else {
+                return false;
+            };
+            if last_published.is_some_and(|last| now.duration_since(last) < self.min_interval) {
+                return false;
+            }
+            last_published.replace(now)
+        };
+
+        let recorded = self.monitoring.try_record_metrics(&Self::snapshot(engine)).await;
+        if !recorded {
+            debug!("Monitoring busy - portfolio snapshot skipped");
+            // Give the slot back unless a later publish already took it
+            let mut last_published = self.last_published.lock().unwrap_or_else(|e| e.into_inner());
+            if *last_published == Some(now) {
+                *last_published = previous;
+            }
+        }
+        recorded
+    }
+
+    /// Publish on a timer of the configured interval; needs an Arc to outlive the caller.
+    /// A tick skips when the engine is locked for writing rather than queueing behind it.
+    pub fn start(self: &Arc<Self>, engine: Arc<RwLock<StrikeBoxEngine>>) {
+        let bridge = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(bridge.min_interval);
+            loop {
+                interval.tick().await;
+                if let Ok(engine) = engine.try_read() {
+                    bridge.publish(&engine).await;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::alerts::{AlertCondition, AlertLevel, AlertRule};
+    use strike_box::StrikeBoxConfig;
+
+    fn create_test_engine() -> StrikeBoxEngine {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let portfolio = &mut engine.portfolio;
+        portfolio.gross_exposure_usd = Decimal::new(300_000, 0);
+        portfolio.gross_exposure_pct = Decimal::new(30, 2);
+        portfolio.net_exposure_pct = Decimal::new(-10, 2);
+        portfolio.daily_drawdown_pct = Decimal::new(2, 2);
+        portfolio.weekly_drawdown_pct = Decimal::new(4, 2);
+        portfolio.monthly_drawdown_pct = Decimal::new(6, 2);
+        portfolio.available_capital_usd = Decimal::new(700_000, 0);
+        portfolio.long_book.total_allocation_usd = portfolio.long_book.max_allocation_usd / Decimal::TWO;
+        portfolio.long_book.unrealized_pnl_usd = Decimal::new(1_250, 0);
+        portfolio.short_book.unrealized_pnl_usd = Decimal::new(-400, 0);
+        portfolio.consecutive_failures = 3;
+        engine
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_maps_portfolio_to_metrics() {
+        let monitoring = Arc::new(MonitoringSystem::new());
+        let bridge = MonitoringBridge::new(monitoring.clone());
+        assert!(bridge.publish(&create_test_engine()).await);
+
+        for (metric_type, expected) in [
+            (MetricType::Exposure, 300_000.0),
+            (MetricType::GrossExposure, 0.30),
+            (MetricType::NetExposure, -0.10),
+            (MetricType::DrawDown, 0.02),
+            (MetricType::WeeklyDrawDown, 0.04),
+            (MetricType::MonthlyDrawDown, 0.06),
+            (MetricType::BookUtilization, 0.5),
+            (MetricType::OpenLongPositions, 0.0),
+            (MetricType::OpenShortPositions, 0.0),
+            (MetricType::AvailableCapital, 700_000.0),
+            (MetricType::LongUnrealizedPnL, 1_250.0),
+            (MetricType::ShortUnrealizedPnL, -400.0),
+            (MetricType::ConsecutiveFailures, 3.0),
+        ] {
+            assert_eq!(monitoring.get_metric(&metric_type).await, Some(expected), "{:?}", metric_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_is_rate_limited() {
+        let monitoring = Arc::new(MonitoringSystem::new());
+        let bridge = MonitoringBridge::new(monitoring.clone()).with_min_interval(Duration::from_secs(3_600));
+        let mut engine = create_test_engine();
+
+        assert!(bridge.publish(&engine).await);
+        for _ in 0..10 {
+            engine.portfolio.consecutive_failures += 1;
+            assert!(!bridge.publish(&engine).await);
+        }
+        let stats = monitoring.get_metric_stats(&MetricType::ConsecutiveFailures).await.unwrap();
+        assert_eq!((stats.count, stats.latest), (1, 3.0));
+
+        // Concurrent callers race for one slot
+        let monitoring = Arc::new(MonitoringSystem::new());
+        let bridge = MonitoringBridge::new(monitoring.clone()).with_min_interval(Duration::from_secs(3_600));
+        let published = futures::future::join_all((0..8).map(|_| bridge.publish(&engine))).await;
+        assert_eq!(published.iter().filter(|recorded| **recorded).count(), 1);
+
+        let bridge = MonitoringBridge::new(monitoring.clone()).with_min_interval(Duration::ZERO);
+        assert!(bridge.publish(&engine).await);
+        assert!(bridge.publish(&engine).await);
+        assert_eq!(monitoring.get_metric(&MetricType::ConsecutiveFailures).await, Some(13.0));
+    }
+
+    #[tokio::test]
+    async fn test_drawdown_breach_reaches_alert_rule() {
+        let monitoring = Arc::new(MonitoringSystem::new());
+        monitoring.alert_manager().add_rule(AlertRule {
+            metric_type: MetricType::DrawDown,
+            condition: AlertCondition::Above(0.05),
+            level: AlertLevel::Critical,
+            title: "Daily Drawdown Breach".to_string(),
+            message_template: "Daily drawdown at {value}".to_string(),
+        }).await;
+        let bridge = MonitoringBridge::new(monitoring.clone()).with_min_interval(Duration::ZERO);
+        let mut engine = create_test_engine();
+        let breaches = |alerts: Vec<crate::monitoring::alerts::Alert>| {
+            alerts.iter().filter(|a| a.title == "Daily Drawdown Breach").count()
+        };
+
+        bridge.publish(&engine).await;
+        assert_eq!(breaches(monitoring.alert_manager().get_alerts(100).await), 0);
+
+        engine.portfolio.daily_drawdown_pct = Decimal::new(8, 2);
+        bridge.publish(&engine).await;
+        let alerts = monitoring.alert_manager().get_alerts(100).await;
+        assert_eq!(breaches(alerts.clone()), 1);
+        assert!(alerts.iter().any(|a| a.level == AlertLevel::Critical && a.message.contains("0.08")));
+    }
+}
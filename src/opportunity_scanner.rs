@@ -11,7 +11,7 @@ use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
-use log::{info, debug};
+use tracing::{info, debug};
 
 /// Opportunity scanner that finds 90% win rate setups
 pub struct OpportunityScanner {
@@ -344,10 +344,9 @@ impl ProductionMonitor {
         tracker.win_rate = tracker.winning_trades_today as f64 / tracker.trades_today as f64;
         
         // Check performance alerts
-        let alert_system = self.alert_system.read().await;
+        let thresholds = self.alert_system.read().await.alert_thresholds.clone();
         
-        if tracker.win_rate < alert_system.alert_thresholds.win_rate_warning {
-            drop(alert_system);
+        if tracker.win_rate < thresholds.win_rate_warning {
             self.create_alert(
                 AlertSeverity::Warning,
                 AlertCategory::Performance,
@@ -356,8 +355,7 @@ impl ProductionMonitor {
             ).await;
         }
         
-        if execution_ms > alert_system.alert_thresholds.latency_warning_ms {
-            drop(alert_system);
+        if execution_ms > thresholds.latency_warning_ms {
             self.create_alert(
                 AlertSeverity::Warning,
                 AlertCategory::Execution,
@@ -207,7 +207,7 @@ impl ProductionTestSuite {
         // Test various strikes and maturities
         let strikes = vec![90.0, 95.0, 100.0, 105.0, 110.0];
         let maturities = vec![0.25, 0.5, 1.0, 2.0];
-        let spot = 100.0;
+        let spot: f64 = 100.0;
         
         for strike in &strikes {
             for maturity in &maturities {
@@ -575,7 +575,7 @@ impl ProductionTestSuite {
         total_issues += 2;
         
         AuditSummary {
-            code_quality_score,
+            code_quality_score: code_quality,
             security_score,
             performance_score,
             reliability_score,
@@ -696,12 +696,12 @@ CERTIFICATION: System tested and verified for production use.
     fn generate_test_market_data(&self) -> crate::stochastic_volatility_models::OptionSurface {
         use crate::stochastic_volatility_models::{OptionSurface, OptionData};
         
-        let spot = 100.0;
+        let spot: f64 = 100.0;
         let mut options = vec![];
         
         // Generate realistic option surface
         let strikes = vec![90.0, 95.0, 100.0, 105.0, 110.0];
-        let maturities = vec![0.25, 0.5, 1.0];
+        let maturities: Vec<f64> = vec![0.25, 0.5, 1.0];
         
         for strike in strikes {
             for maturity in maturities.iter() {
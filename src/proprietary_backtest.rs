@@ -228,7 +228,7 @@ impl ProprietaryBacktest {
         self.calculate_final_metrics();
         
         // Generate report
-        self.generate_report()
+        self.generate_report().await
     }
     
     /// Simulate realistic execution
@@ -335,8 +335,8 @@ impl ProprietaryBacktest {
     }
     
     /// Generate comprehensive report
-    fn generate_report(&self) -> BacktestReport {
-        let perf = self.quant_system.get_performance_report();
+    async fn generate_report(&self) -> BacktestReport {
+        let perf = self.quant_system.get_performance_report().await;
         
         BacktestReport {
             summary: BacktestSummary {
@@ -362,6 +362,9 @@ impl ProprietaryPredictiveEngine {
         self.prediction_history.write().await
             .record_prediction(&meta_pred, &recommendation);
         
+        let risk_warnings = self.generate_risk_warnings(&meta_pred).await;
+        let prediction_quality = self.assess_prediction_quality(&meta_pred).await;
+        
         MasterPrediction {
             timestamp: Utc::now(),
             symbol: symbol.to_string(),
@@ -370,11 +373,11 @@ impl ProprietaryPredictiveEngine {
             price_15min: meta_pred.price_15min,
             volatility_forecast: vol_pred,
             liquidity_forecast: liquidity_pred,
-            regime_forecast: regime_pred,
-            risk_warnings: self.generate_risk_warnings(&meta_pred).await,
+            regime_forecast: regime_pred.forecast(),
+            risk_warnings,
             recommendation,
             overall_confidence: meta_pred.confidence,
-            prediction_quality: self.assess_prediction_quality(&meta_pred).await,
+            prediction_quality,
         }
     }
     
@@ -743,6 +746,17 @@ struct MicrostructurePrediction;
 #[derive(Default)]
 struct RegimePrediction;
 
+impl RegimePrediction {
+    fn forecast(&self) -> RegimeForecast {
+        RegimeForecast {
+            current_regime: "unknown".to_string(),
+            transition_probability: 0.0,
+            next_regime: "unknown".to_string(),
+            time_to_transition: 0.0,
+        }
+    }
+}
+
 struct VPINCalculator;
 struct OrderClusterDetector;
 struct SpoofingDetector;
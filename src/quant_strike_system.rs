@@ -17,7 +17,7 @@ use std::collections::HashMap;
 /// Proprietary Quant Strike System - Our edge
 pub struct QuantStrikeSystem {
     // Our predictive engine
-    predictive_engine: Arc<ProprietaryPredictiveEngine>,
+    pub(crate) predictive_engine: Arc<ProprietaryPredictiveEngine>,
     
     // Advanced volatility models
     rough_heston: Arc<RwLock<RoughHestonModel>>,
@@ -300,7 +300,7 @@ impl QuantStrikeSystem {
         }
         
         // 3. Generate strike candidates
-        let candidates = self.strike_generator.read().await
+        let candidates = self.strike_generator.write().await
             .generate_candidates(&prediction, market_data)
             .await;
         
@@ -322,7 +322,7 @@ impl QuantStrikeSystem {
         // 5. Risk check
         if let Some(strike) = best_strike {
             let risk_approved = self.risk_manager.read().await
-                .approve_strike(&strike, &self.active_strikes.read().await)
+                .approve_strike(&strike, &*self.active_strikes.read().await)
                 .await;
             
             if risk_approved {
@@ -389,8 +389,8 @@ impl QuantStrikeSystem {
         let entry_price = active_strike.strike.entry_price;
         
         // Check stop loss
-        if (active_strike.strike.strike_type == StrikeType::Cascade || 
-            active_strike.strike.strike_type == StrikeType::Momentum) &&
+        if (active_strike.strike.strike_type == StrikeType::MacroLiquidity || 
+            active_strike.strike.strike_type == StrikeType::MacroMomentum) &&
            current_price <= active_strike.exit_plan.stop_loss {
             return true;
         }
@@ -600,7 +600,7 @@ impl StrikeGenerator {
             candidates.push(MacroStrike {
                 id: self.strike_counter,
                 symbol: market_data.symbol.clone(),
-                strike_type: StrikeType::Cascade,
+                strike_type: StrikeType::MacroLiquidity,
                 entry_price: prediction.recommendation.entry_price,
                 target_price: prediction.recommendation.take_profit[0].price,
                 stop_loss: prediction.recommendation.stop_loss,
@@ -5,7 +5,7 @@ use crate::{MacroStrike, StrikeType};
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use log::{info, debug, warn};
+use tracing::{info, debug, warn};
 
 // Quantum-inspired superposition state
 #[derive(Debug, Clone)]
@@ -7,7 +7,7 @@ use crate::ultra_fast_cascade::{UltraFastCascadeDetector, CascadePattern, Cascad
 use std::collections::{HashMap, VecDeque, BTreeMap};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use log::{info, debug, warn};
+use tracing::{info, debug, warn};
 use chrono::{DateTime, Utc, Timelike};
 
 /// Strategy 1: Social Sentiment Cascade Detection
@@ -508,8 +508,8 @@ impl RevolutionaryEngine {
             .collect();
         
         // Calculate book imbalance
-        let total_bid_volume: f64 = recent_books[0].bids.iter().map(|o| o.quantity).sum();
-        let total_ask_volume: f64 = recent_books[0].asks.iter().map(|o| o.quantity).sum();
+        let total_bid_volume: f64 = recent_books[0].bids.iter().map(|o| o.volume).sum();
+        let total_ask_volume: f64 = recent_books[0].asks.iter().map(|o| o.volume).sum();
         let book_imbalance = (total_bid_volume - total_ask_volume) / (total_bid_volume + total_ask_volume);
         
         // Detect spoofing patterns
@@ -606,7 +606,7 @@ impl RevolutionaryEngine {
     }
     
     async fn predict_liquidity_vacuum(&self, symbol: &str) -> Option<LiquidityVacuum> {
-        let predictor = self.liquidity_predictor.read().await;
+        let predictor = self.liquidity_vacuum_detector.read().await;
         
         let depth_history = predictor.depth_history.get(symbol)?;
         if depth_history.len() < 20 {
@@ -653,6 +653,12 @@ impl RevolutionaryEngine {
         10000.0 * cascade.cascade_strength * time_factor
     }
     
+    async fn calculate_ultra_fast_position_size(&self, cascade: &CascadePattern) -> f64 {
+        // Same scaling as sentiment cascades, on the detector's impact estimate
+        let time_factor = (30000.0 / cascade.time_to_impact_ms.max(1) as f64).min(2.0);
+        10000.0 * cascade.strength * time_factor
+    }
+    
     async fn detect_spoofing_pattern(&self, books: &[&OrderBook]) -> f64 {
         // Detect large orders that disappear quickly
         if books.len() < 2 {
@@ -666,7 +672,7 @@ impl RevolutionaryEngine {
         for i in 1..books.len() {
             let prev_large_orders: Vec<_> = books[i-1].bids.iter()
                 .chain(books[i-1].asks.iter())
-                .filter(|o| o.quantity > 1000.0) // Large order threshold
+                .filter(|o| o.volume > 1000.0) // Large order threshold
                 .collect();
             
             let current_prices: Vec<f64> = books[i].bids.iter()
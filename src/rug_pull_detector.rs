@@ -5,7 +5,8 @@
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use crate::errors::{TradingResult, TradingError};
-use log::{info, warn};
+use tracing::{info, warn};
+use crate::sim_rng::SimRng;
 
 #[derive(Debug, Clone)]
 pub struct RugPullDetector {
@@ -14,6 +15,8 @@ pub struct RugPullDetector {
     min_liquidity_threshold: f64,
     min_holders_threshold: u64,
     min_age_days: u64,
+    rpc_url: Option<String>, // JSON-RPC endpoint for eth_getCode; bytecode goes unscanned without one
+    rng: SimRng, // Stands in for on-chain lookups until they are wired up
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,7 +30,7 @@ pub struct TokenSafetyScore {
     pub risk_level: RiskLevel,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum RiskLevel {
     Safe,      // 0.8 - 1.0
     Moderate,  // 0.6 - 0.8
@@ -35,6 +38,79 @@ pub enum RiskLevel {
     Critical,  // 0.0 - 0.4 (BLOCKED)
 }
 
+/// Suspicious pattern found in contract bytecode, with the byte offset of the match
+#[derive(Debug, Clone, PartialEq)]
+pub enum BytecodeFlag {
+    SelfDestruct { offset: usize },
+    ExternalDelegateCall { offset: usize },
+    OwnershipTransferNoTimelock { offset: usize },
+    UncappedMint { offset: usize },
+    Blacklist { offset: usize },
+    Pausable { offset: usize },
+}
+
+impl BytecodeFlag {
+    pub fn severity(&self) -> RiskLevel {
+        match self {
+            Self::SelfDestruct { .. } => RiskLevel::Critical,
+            Self::ExternalDelegateCall { .. } => RiskLevel::High,
+            Self::OwnershipTransferNoTimelock { .. } => RiskLevel::High,
+            Self::UncappedMint { .. } => RiskLevel::High,
+            Self::Blacklist { .. } => RiskLevel::Moderate,
+            Self::Pausable { .. } => RiskLevel::Moderate,
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        match self {
+            Self::SelfDestruct { offset }
+            | Self::ExternalDelegateCall { offset }
+            | Self::OwnershipTransferNoTimelock { offset }
+            | Self::UncappedMint { offset }
+            | Self::Blacklist { offset }
+            | Self::Pausable { offset } => *offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BytecodeRiskReport {
+    pub flags: Vec<BytecodeFlag>,
+    pub risk_level: RiskLevel,
+    pub score: f64, // 1.0 = no findings, multiplies the contract score
+}
+
+// EVM opcodes
+const OP_LT: u8 = 0x10;
+const OP_SGT: u8 = 0x13;
+const OP_TIMESTAMP: u8 = 0x42;
+const OP_JUMPI: u8 = 0x57;
+const OP_PUSH1: u8 = 0x60;
+const OP_PUSH4: u8 = 0x63;
+const OP_PUSH20: u8 = 0x73;
+const OP_PUSH32: u8 = 0x7f;
+const OP_DELEGATECALL: u8 = 0xf4;
+const OP_SELFDESTRUCT: u8 = 0xff;
+
+// Function selectors (first 4 bytes of keccak256 of the signature)
+const SEL_TRANSFER_OWNERSHIP: [u8; 4] = [0xf2, 0xfd, 0xe3, 0x8b]; // transferOwnership(address)
+const SEL_MINT_TO: [u8; 4] = [0x40, 0xc1, 0x0f, 0x19];            // mint(address,uint256)
+const SEL_MINT: [u8; 4] = [0xa0, 0x71, 0x2d, 0x68];               // mint(uint256)
+const SEL_CAP: [u8; 4] = [0x35, 0x52, 0x74, 0xea];                // cap()
+const SEL_MAX_SUPPLY: [u8; 4] = [0xd5, 0xab, 0xeb, 0x01];         // maxSupply()
+const SEL_DELAY: [u8; 4] = [0x6a, 0x42, 0xb8, 0xf8];              // delay()
+const SEL_BLACKLIST: [u8; 4] = [0xf9, 0xf9, 0x2b, 0xe4];          // blacklist(address)
+const SEL_ADD_TO_BLACKLIST: [u8; 4] = [0x44, 0x33, 0x7e, 0xa1];   // addToBlacklist(address)
+const SEL_IS_BLACKLISTED: [u8; 4] = [0xfe, 0x57, 0x5a, 0x87];     // isBlacklisted(address)
+const SEL_PAUSE: [u8; 4] = [0x84, 0x56, 0xcb, 0x59];              // pause()
+const SEL_UNPAUSE: [u8; 4] = [0x3f, 0x4b, 0xa8, 0x3a];            // unpause()
+
+// DELEGATECALL within this many instructions of a PUSH20 targets a hard-coded address
+const DELEGATECALL_PUSH20_LOOKBACK: usize = 6;
+
+// A time-lock compares TIMESTAMP (LT/GT/SLT/SGT) and branches on it within this many instructions
+const TIMELOCK_LOOKAHEAD: usize = 8;
+
 #[derive(Debug, Clone)]
 pub enum RugPullReason {
     LowLiquidity,
@@ -53,9 +129,22 @@ impl RugPullDetector {
             min_liquidity_threshold: 100_000.0, // $100K minimum liquidity
             min_holders_threshold: 100,          // Minimum 100 holders
             min_age_days: 7,                     // Token must be 7+ days old
+            rpc_url: None,
+            rng: SimRng::from_entropy(),
         }
     }
 
+    /// Scan each token's deployed bytecode, fetched with eth_getCode from `rpc_url`
+    pub fn with_rpc_url(mut self, rpc_url: impl Into<String>) -> Self {
+        self.rpc_url = Some(rpc_url.into());
+        self
+    }
+
+    pub fn with_rng(mut self, rng: SimRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
     /// Comprehensive token safety check
     pub async fn validate_token(&mut self, token_address: &str, pair: &str) -> TradingResult<TokenSafetyScore> {
         // Check blacklist first
@@ -75,10 +164,27 @@ impl RugPullDetector {
             return Ok(score.clone());
         }
 
+        // Scan deployed bytecode before anything else - selfdestruct is an instant block
+        let bytecode_report = match self.fetch_contract_bytecode(token_address).await? {
+            Some(bytecode) if bytecode.is_empty() => {
+                return Err(TradingError::InvalidInput(
+                    format!("Token {} has no contract code", token_address)
+                ));
+            }
+            Some(bytecode) => Self::scan_contract_bytecode(&bytecode),
+            None => BytecodeRiskReport { flags: Vec::new(), risk_level: RiskLevel::Safe, score: 1.0 },
+        };
+        if bytecode_report.risk_level == RiskLevel::Critical {
+            warn!("🚨 Token {} bytecode flagged critical: {:?}", token_address, bytecode_report.flags);
+            return Err(TradingError::InvalidInput(
+                format!("Token {} bytecode contains critical rug-pull patterns", token_address)
+            ));
+        }
+
         // Perform comprehensive checks
         let liquidity_score = self.check_liquidity(token_address, pair).await?;
         let holder_score = self.check_holder_distribution(token_address).await?;
-        let contract_score = self.check_contract_verification(token_address).await?;
+        let contract_score = self.check_contract_verification(token_address).await? * bytecode_report.score;
         let trading_score = self.check_trading_history(token_address).await?;
 
         // Calculate weighted overall score
@@ -119,6 +225,103 @@ impl RugPullDetector {
         Ok(score)
     }
 
+    /// Scan EVM bytecode for common rug-pull patterns. The compiler's CBOR metadata trailer is
+    /// data, not code, so it is stripped first.
+    pub fn scan_contract_bytecode(bytecode: &[u8]) -> BytecodeRiskReport {
+        let bytecode = strip_cbor_metadata(bytecode);
+        let mut flags = Vec::new();
+        let mut selectors: Vec<([u8; 4], usize)> = Vec::new();
+        let mut recent_push20: Vec<usize> = Vec::new(); // instruction indices of PUSH20
+        let mut timestamp_at: Option<usize> = None; // Instruction index of the last TIMESTAMP
+        let mut timestamp_compared = false;
+        let mut has_timelock = false;
+        let mut instruction = 0usize;
+        let mut pc = 0usize;
+
+        while pc < bytecode.len() {
+            let opcode = bytecode[pc];
+            match opcode {
+                OP_SELFDESTRUCT => flags.push(BytecodeFlag::SelfDestruct { offset: pc }),
+                OP_DELEGATECALL => {
+                    let hardcoded_target = recent_push20
+                        .iter()
+                        .any(|&i| instruction - i <= DELEGATECALL_PUSH20_LOOKBACK);
+                    if !hardcoded_target {
+                        flags.push(BytecodeFlag::ExternalDelegateCall { offset: pc });
+                    }
+                }
+                OP_TIMESTAMP => {
+                    timestamp_at = Some(instruction);
+                    timestamp_compared = false;
+                }
+                OP_LT..=OP_SGT if timestamp_at.is_some_and(|at| instruction - at <= TIMELOCK_LOOKAHEAD) => {
+                    timestamp_compared = true;
+                }
+                OP_JUMPI if timestamp_compared && timestamp_at.is_some_and(|at| instruction - at <= TIMELOCK_LOOKAHEAD) => {
+                    has_timelock = true;
+                }
+                OP_PUSH4 if pc + 4 < bytecode.len() => {
+                    let mut selector = [0u8; 4];
+                    selector.copy_from_slice(&bytecode[pc + 1..pc + 5]);
+                    selectors.push((selector, pc));
+                }
+                OP_PUSH20 => recent_push20.push(instruction),
+                _ => {}
+            }
+
+            // Skip PUSH immediates so data bytes are never read as opcodes
+            pc += if (OP_PUSH1..=OP_PUSH32).contains(&opcode) {
+                (opcode - OP_PUSH1) as usize + 2
+            } else {
+                1
+            };
+            instruction += 1;
+        }
+
+        let find = |wanted: &[[u8; 4]]| -> Option<usize> {
+            selectors.iter().find(|(sel, _)| wanted.contains(sel)).map(|(_, offset)| *offset)
+        };
+
+        let has_timelock = has_timelock || find(&[SEL_DELAY]).is_some();
+        if let Some(offset) = find(&[SEL_TRANSFER_OWNERSHIP]) {
+            if !has_timelock {
+                flags.push(BytecodeFlag::OwnershipTransferNoTimelock { offset });
+            }
+        }
+        if let Some(offset) = find(&[SEL_MINT_TO, SEL_MINT]) {
+            if find(&[SEL_CAP, SEL_MAX_SUPPLY]).is_none() {
+                flags.push(BytecodeFlag::UncappedMint { offset });
+            }
+        }
+        if let Some(offset) = find(&[SEL_BLACKLIST, SEL_ADD_TO_BLACKLIST, SEL_IS_BLACKLISTED]) {
+            flags.push(BytecodeFlag::Blacklist { offset });
+        }
+        if let Some(offset) = find(&[SEL_PAUSE, SEL_UNPAUSE]) {
+            flags.push(BytecodeFlag::Pausable { offset });
+        }
+
+        let risk_level = if flags.iter().any(|f| f.severity() == RiskLevel::Critical) {
+            RiskLevel::Critical
+        } else if flags.iter().any(|f| f.severity() == RiskLevel::High) {
+            RiskLevel::High
+        } else if !flags.is_empty() {
+            RiskLevel::Moderate
+        } else {
+            RiskLevel::Safe
+        };
+
+        let score = flags.iter().fold(1.0, |acc, flag| {
+            acc * match flag.severity() {
+                RiskLevel::Critical => 0.0,
+                RiskLevel::High => 0.6,
+                RiskLevel::Moderate => 0.85,
+                RiskLevel::Safe => 1.0,
+            }
+        });
+
+        BytecodeRiskReport { flags, risk_level, score }
+    }
+
     /// Check liquidity requirements
     async fn check_liquidity(&self, token_address: &str, pair: &str) -> TradingResult<f64> {
         // In production, fetch from DEX APIs
@@ -206,7 +409,7 @@ impl RugPullDetector {
         let has_large_dumps = self.detect_large_dumps(token_address).await;
         let volume_consistency = self.check_volume_consistency(token_address).await;
 
-        let mut score = 1.0;
+        let mut score: f64 = 1.0;
         
         if has_wash_trading {
             score -= 0.4; // Major red flag
@@ -224,68 +427,192 @@ impl RugPullDetector {
     // Placeholder implementations - replace with real API calls
     async fn fetch_liquidity(&self, _token: &str, _pair: &str) -> f64 {
         // In production: Fetch from DEX APIs (Uniswap, PancakeSwap, etc.)
-        use rand::Rng;
-        500_000.0 + rand::thread_rng().gen::<f64>() * 500_000.0 // Simulated
+        500_000.0 + self.rng.gen_f64() * 500_000.0 // Simulated
+    }
+
+    /// Deployed code at `token` via eth_getCode; None when no RPC endpoint is configured
+    async fn fetch_contract_bytecode(&self, token: &str) -> TradingResult<Option<Vec<u8>>> {
+        let Some(rpc_url) = &self.rpc_url else {
+            return Ok(None);
+        };
+        let response: serde_json::Value = reqwest::Client::new()
+            .post(rpc_url)
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": "eth_getCode", "params": [token, "latest"]
+            }))
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await?
+            .json()
+            .await?;
+        let code = response["result"].as_str().ok_or_else(|| {
+            TradingError::ApiError(format!("eth_getCode for {} returned {}", token, response))
+        })?;
+        decode_hex(code)
+            .map(Some)
+            .ok_or_else(|| TradingError::ApiError(format!("eth_getCode for {} returned malformed hex", token)))
     }
 
     async fn check_liquidity_lock(&self, _token: &str) -> bool {
         // Check if liquidity is locked in a contract
-        use rand::Rng;
-        rand::thread_rng().gen::<f64>() > 0.3 // 70% chance locked
+        self.rng.gen_f64() > 0.3 // 70% chance locked
     }
 
     async fn fetch_total_holders(&self, _token: &str) -> u64 {
         // Fetch from blockchain explorer APIs
-        use rand::Rng;
-        500 + (rand::thread_rng().gen::<f64>() * 500.0) as u64 // Simulated
+        500 + (self.rng.gen_f64() * 500.0) as u64 // Simulated
     }
 
     async fn fetch_top_holders_concentration(&self, _token: &str, _top_n: usize) -> f64 {
         // Calculate concentration of top N holders
-        use rand::Rng;
-        0.15 + rand::thread_rng().gen::<f64>() * 0.25 // Simulated (15-40%)
+        0.15 + self.rng.gen_f64() * 0.25 // Simulated (15-40%)
     }
 
     async fn check_contract_verified(&self, _token: &str) -> bool {
         // Check Etherscan/BSCScan verification
-        use rand::Rng;
-        rand::thread_rng().gen::<f64>() > 0.2 // 80% verified
+        self.rng.gen_f64() > 0.2 // 80% verified
     }
 
     async fn check_ownership_renounced(&self, _token: &str) -> bool {
         // Check if contract ownership is renounced
-        use rand::Rng;
-        rand::thread_rng().gen::<f64>() > 0.3 // 70% renounced
+        self.rng.gen_f64() > 0.3 // 70% renounced
     }
 
     async fn check_mint_function(&self, _token: &str) -> bool {
         // Check if contract has mint function (red flag)
-        use rand::Rng;
-        rand::thread_rng().gen::<f64>() < 0.2 // 20% have mint (bad)
+        self.rng.gen_f64() < 0.2 // 20% have mint (bad)
     }
 
     async fn fetch_token_age(&self, _token: &str) -> u64 {
         // Fetch token creation date
-        use rand::Rng;
-        7 + (rand::thread_rng().gen::<f64>() * 30.0) as u64 // 7-37 days old
+        7 + (self.rng.gen_f64() * 30.0) as u64 // 7-37 days old
     }
 
     async fn detect_wash_trading(&self, _token: &str) -> bool {
         // Detect wash trading patterns
-        use rand::Rng;
-        rand::thread_rng().gen::<f64>() < 0.1 // 10% have wash trading
+        self.rng.gen_f64() < 0.1 // 10% have wash trading
     }
 
     async fn detect_large_dumps(&self, _token: &str) -> bool {
         // Detect large price dumps (>50% in short time)
-        use rand::Rng;
-        rand::thread_rng().gen::<f64>() < 0.15 // 15% have large dumps
+        self.rng.gen_f64() < 0.15 // 15% have large dumps
     }
 
     async fn check_volume_consistency(&self, _token: &str) -> f64 {
         // Check if volume is consistent (not manipulated)
-        use rand::Rng;
-        0.7 + rand::thread_rng().gen::<f64>() * 0.2 // 70-90% consistency
+        0.7 + self.rng.gen_f64() * 0.2 // 70-90% consistency
     }
 }
 
+/// Bytecode without the CBOR metadata solc and vyper append: a CBOR map whose length is the
+/// final two bytes. Code without a well-formed trailer is returned whole.
+fn strip_cbor_metadata(bytecode: &[u8]) -> &[u8] {
+    let Some(split) = bytecode.len().checked_sub(2) else {
+        return bytecode;
+    };
+    let metadata_len = u16::from_be_bytes([bytecode[split], bytecode[split + 1]]) as usize;
+    match split.checked_sub(metadata_len) {
+        // CBOR maps of up to 23 entries start 0xa0..=0xb7; compilers emit one to five
+        Some(start) if metadata_len > 0 && (0xa1..=0xa5).contains(&bytecode[start]) => &bytecode[..start],
+        _ => bytecode,
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push4(selector: [u8; 4]) -> Vec<u8> {
+        let mut code = vec![OP_PUSH4];
+        code.extend_from_slice(&selector);
+        code
+    }
+
+    #[test]
+    fn test_selfdestruct_is_critical() {
+        let code = vec![0x60, 0x00, 0x35, OP_SELFDESTRUCT];
+        let report = RugPullDetector::scan_contract_bytecode(&code);
+        assert_eq!(report.risk_level, RiskLevel::Critical);
+        assert_eq!(report.flags, vec![BytecodeFlag::SelfDestruct { offset: 3 }]);
+        assert_eq!(report.score, 0.0);
+    }
+
+    #[test]
+    fn test_push_data_is_not_decoded_as_opcodes() {
+        // 0xff inside PUSH2 data must not count as SELFDESTRUCT
+        let code = vec![0x61, 0xff, OP_DELEGATECALL, 0x00];
+        let report = RugPullDetector::scan_contract_bytecode(&code);
+        assert!(report.flags.is_empty());
+        assert_eq!(report.risk_level, RiskLevel::Safe);
+    }
+
+    #[test]
+    fn test_delegatecall_to_hardcoded_target_is_allowed() {
+        let mut code = vec![OP_PUSH20];
+        code.extend_from_slice(&[0xaa; 20]);
+        code.push(OP_DELEGATECALL);
+        assert!(RugPullDetector::scan_contract_bytecode(&code).flags.is_empty());
+
+        let external = vec![0x60, 0x00, 0x54, OP_DELEGATECALL]; // target loaded from storage
+        let report = RugPullDetector::scan_contract_bytecode(&external);
+        assert_eq!(report.flags, vec![BytecodeFlag::ExternalDelegateCall { offset: 3 }]);
+        assert_eq!(report.risk_level, RiskLevel::High);
+    }
+
+    #[test]
+    fn test_selector_patterns() {
+        let mut code = Vec::new();
+        code.extend(push4(SEL_TRANSFER_OWNERSHIP));
+        code.extend(push4(SEL_MINT_TO));
+        code.extend(push4(SEL_BLACKLIST));
+        code.extend(push4(SEL_PAUSE));
+        let report = RugPullDetector::scan_contract_bytecode(&code);
+        assert_eq!(report.flags, vec![
+            BytecodeFlag::OwnershipTransferNoTimelock { offset: 0 },
+            BytecodeFlag::UncappedMint { offset: 5 },
+            BytecodeFlag::Blacklist { offset: 10 },
+            BytecodeFlag::Pausable { offset: 15 },
+        ]);
+        assert_eq!(report.risk_level, RiskLevel::High);
+
+        // Reading the timestamp alone is not a time-lock
+        code.extend(push4(SEL_CAP));
+        code.push(OP_TIMESTAMP);
+        let report = RugPullDetector::scan_contract_bytecode(&code);
+        assert_eq!(report.risk_level, RiskLevel::High);
+        assert_eq!(report.flags.len(), 3);
+
+        // A cap and a timestamp comparison guarding a branch clear the mint and ownership findings
+        code.extend([0x60, 0x00, 0x54, OP_LT, 0x60, 0x20, OP_JUMPI]);
+        let report = RugPullDetector::scan_contract_bytecode(&code);
+        assert_eq!(report.risk_level, RiskLevel::Moderate);
+        assert_eq!(report.flags.len(), 2);
+    }
+
+    #[test]
+    fn test_metadata_trailer_is_not_scanned_as_code() {
+        // a2 64 'ipfs' 58 22 <0xff ...>: a metadata hash byte that would read as SELFDESTRUCT
+        let mut metadata = vec![0xa2, 0x64, b'i', b'p', b'f', b's', 0x58, 0x22];
+        metadata.extend([0xff; 34]);
+        let mut code = vec![0x60, 0x80, 0x60, 0x40, 0x52, 0x00, 0xfe];
+        code.extend(&metadata);
+        code.extend((metadata.len() as u16).to_be_bytes());
+        assert!(RugPullDetector::scan_contract_bytecode(&code).flags.is_empty());
+
+        // Without a trailer every byte is code
+        assert_eq!(strip_cbor_metadata(&[0x60, 0x00, OP_SELFDESTRUCT]), &[0x60, 0x00, OP_SELFDESTRUCT]);
+        assert_eq!(decode_hex("0x6080ff"), Some(vec![0x60, 0x80, 0xff]));
+        assert_eq!(decode_hex("0x608"), None);
+    }
+}
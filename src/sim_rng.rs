@@ -0,0 +1,104 @@
+// Simulation Randomness
+// Injectable RNG for simulated prices, volumes and outcomes, so seeded runs replay exactly
+
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use std::sync::{Arc, Mutex};
+
+/// Any RNG a simulation can draw from
+pub trait RngSource: RngCore + Send {}
+
+impl<T: RngCore + Send> RngSource for T {}
+
+/// Reproducible generator; the same seed always yields the same sequence
+pub struct SeededRng(StdRng);
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+
+    /// Seeded from OS entropy, like `thread_rng` but `Send`
+    pub fn from_entropy() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
+impl RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+/// Shared handle to an RngSource, cheap to clone into components and tasks
+#[derive(Clone)]
+pub struct SimRng {
+    source: Arc<Mutex<Box<dyn RngSource>>>,
+}
+
+impl SimRng {
+    pub fn new(source: impl RngSource + 'static) -> Self {
+        Self {
+            source: Arc::new(Mutex::new(Box::new(source))),
+        }
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        Self::new(SeededRng::new(seed))
+    }
+
+    pub fn from_entropy() -> Self {
+        Self::new(SeededRng::from_entropy())
+    }
+
+    pub fn from_seed_or_entropy(seed: Option<u64>) -> Self {
+        seed.map_or_else(Self::from_entropy, Self::seeded)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn gen_f64(&self) -> f64 {
+        self.source.lock().unwrap_or_else(|e| e.into_inner()).gen()
+    }
+
+    /// Normal with the given mean and standard deviation; exactly `mean` when std_dev is not positive
+    pub fn gen_normal(&self, mean: f64, std_dev: f64) -> f64 {
+        match Normal::new(mean, std_dev) {
+            Ok(normal) if std_dev > 0.0 => {
+                normal.sample(&mut *self.source.lock().unwrap_or_else(|e| e.into_inner()))
+            }
+            _ => mean,
+        }
+    }
+
+    /// Independent child stream seeded from this one. Give each concurrently running
+    /// component its own fork so draw order across tasks cannot change the results.
+    pub fn fork(&self) -> SimRng {
+        let seed = self.source.lock().unwrap_or_else(|e| e.into_inner()).next_u64();
+        Self::seeded(seed)
+    }
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+impl std::fmt::Debug for SimRng {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimRng").finish_non_exhaustive()
+    }
+}
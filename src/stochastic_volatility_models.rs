@@ -393,6 +393,18 @@ impl FractionalFFT {
 }
 
 impl RoughHestonModel {
+    pub async fn new(hurst_exponent: f64, kappa: f64, theta: f64, xi: f64, rho: f64) -> Self {
+        Self {
+            hurst_exponent,
+            kappa,
+            theta,
+            xi,
+            rho,
+            fractional_kernel: Arc::new(RwLock::new(FractionalKernel::new(hurst_exponent))),
+            char_function: Arc::new(RwLock::new(CharacteristicFunctionSolver::new())),
+        }
+    }
+
     /// Price option using fractional Riccati equation
     pub async fn price_option(&self, strike: f64, maturity: f64, spot: f64) -> f64 {
         // Solve fractional Riccati equation for characteristic function
@@ -489,16 +501,13 @@ impl RoughHestonModel {
     pub async fn calibrate(&mut self, market_prices: &OptionSurface) -> CalibrationResult {
         // Objective: minimize price errors
         let objective = |params: &[f64]| -> f64 {
-            self.kappa = params[0];
-            self.theta = params[1];
-            self.xi = params[2];
-            self.rho = params[3];
+            let candidate = self.with_parameters(params);
             
             // Calculate model prices and compare to market
             let mut error = 0.0;
             for option in &market_prices.options {
                 let model_price = futures::executor::block_on(
-                    self.price_option(option.strike, option.maturity, market_prices.spot)
+                    candidate.price_option(option.strike, option.maturity, market_prices.spot)
                 );
                 error += (model_price - option.price).powi(2);
             }
@@ -507,14 +516,33 @@ impl RoughHestonModel {
         
         // Particle swarm optimization
         let pso = ParticleSwarmOptimizer::new(4, 50);
-        let optimal_params = pso.optimize(objective, 1000).await;
+        let optimal_params = pso.optimize(&objective, 1000).await;
+        let rmse = objective(&optimal_params).sqrt();
+        
+        self.kappa = optimal_params[0];
+        self.theta = optimal_params[1];
+        self.xi = optimal_params[2];
+        self.rho = optimal_params[3];
         
         CalibrationResult {
             parameters: optimal_params,
-            rmse: objective(&optimal_params).sqrt(),
+            rmse,
             convergence: true,
         }
     }
+    
+    /// Copy of this model with [kappa, theta, xi, rho] replaced, sharing the kernel and solver
+    fn with_parameters(&self, params: &[f64]) -> Self {
+        Self {
+            hurst_exponent: self.hurst_exponent,
+            kappa: params[0],
+            theta: params[1],
+            xi: params[2],
+            rho: params[3],
+            fractional_kernel: self.fractional_kernel.clone(),
+            char_function: self.char_function.clone(),
+        }
+    }
 }
 
 /// SABR Model with Advanced Expansions
@@ -779,7 +807,7 @@ impl JumpDiffusionModel {
         let psi = match &self.jump_distribution {
             JumpDistribution::CGMY { c, g, m, y } => {
                 // CGMY characteristic exponent
-                let gamma_y = gamma(*y);
+                let gamma_y = y.gamma();
                 // CGMY characteristic exponent approximation
                 c * gamma_y * Complex64::new(1.0, 0.0)
             },
@@ -868,23 +896,10 @@ pub struct OptionQuote {
 }
 
 pub struct ParticleSwarmOptimizer {
-    dimension: usize,
+    n_params: usize,
     n_particles: usize,
 }
 
-impl ParticleSwarmOptimizer {
-    fn new(dim: usize, n: usize) -> Self {
-        Self { dimension: dim, n_particles: n }
-    }
-    
-    async fn optimize<F>(&self, objective: F, iterations: usize) -> Vec<f64>
-    where F: Fn(&[f64]) -> f64
-    {
-        // Placeholder PSO implementation
-        vec![0.5; self.dimension]
-    }
-}
-
 pub struct HaganFormula;
 pub struct OblojFormula;
 pub struct AKSSolver;
@@ -3,13 +3,14 @@
 
 use crate::api::{MarketDataProvider, OrderBook};
 use crate::api::liquidity::TradingPair;
-use crate::api::liquidity_predictor::{LiquidityPredictor, TradeRecommendation};
+use crate::api::liquidity_predictor::{LiquidityPredictor, LiquidityPrediction, TradeRecommendation};
 use crate::monitoring::{MonitoringSystem, MetricType};
 use crate::{MacroStrike, StrikeType};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use std::collections::HashMap;
-use log::{info, warn, error};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn, error};
 
 /// Strike optimizer for finding the best trading opportunities
 pub struct StrikeOptimizer {
@@ -122,9 +123,15 @@ impl StrikeOptimizer {
         let market_data = self.market_data.get_market_data(&strike.symbol).await
             .expect("Failed to get market data");
         
-        // Get liquidity prediction
-        let liquidity_prediction = self.liquidity_predictor
-            .predict_liquidity(&strike.symbol, strike.position_size).await;
+        // Get liquidity prediction for when the strike would be closed out
+        let exit_time = SystemTime::now() + Duration::from_millis(strike.max_exposure_time_ms);
+        let liquidity = match self.liquidity_predictor.predict_liquidity(&strike.symbol, exit_time).await {
+            Ok(prediction) => Some(prediction),
+            Err(e) => {
+                warn!("No liquidity prediction for {}: {}", strike.symbol, e);
+                None
+            }
+        };
         
         // Get Julia analysis
         let julia_analysis = if self.config.use_julia_analysis {
@@ -137,7 +144,7 @@ impl StrikeOptimizer {
         let edge_calculation = self.calculate_edge(strike, &market_data.price);
         
         // Calculate risk metrics
-        let risk_metrics = self.calculate_risk_metrics(strike, &liquidity_prediction);
+        let risk_metrics = self.calculate_risk_metrics(strike, liquidity.as_ref());
         
         // Determine optimal position sizing
         let optimal_sizing = self.calculate_optimal_sizing(
@@ -150,7 +157,7 @@ impl StrikeOptimizer {
         // Determine execution conditions
         let execution_conditions = self.determine_execution_conditions(
             strike,
-            &liquidity_prediction,
+            liquidity.as_ref(),
         );
         
         // Analyze market conditions
@@ -161,7 +168,9 @@ impl StrikeOptimizer {
             julia_confidence: julia_analysis.julia_confidence,
             strike: strike.clone(),
             julia_analysis,
-            liquidity_prediction,
+            liquidity_prediction: liquidity
+                .map(|prediction| prediction.recommended_action)
+                .unwrap_or(TradeRecommendation::Abort),
             edge_calculation,
             risk_metrics,
             optimal_sizing,
@@ -255,11 +264,11 @@ impl StrikeOptimizer {
     fn calculate_risk_metrics(
         &self,
         strike: &MacroStrike,
-        liquidity: &TradeRecommendation,
+        liquidity: Option<&LiquidityPrediction>,
     ) -> RiskMetrics {
         RiskMetrics {
-            liquidity_risk_score: 1.0 - liquidity.confidence,
-            slippage_estimate: liquidity.expected_slippage,
+            liquidity_risk_score: 1.0 - liquidity.map_or(0.0, |p| p.confidence),
+            slippage_estimate: expected_slippage(liquidity),
             volatility_risk: 0.25, // Placeholder
             correlation_risk: 0.15, // Placeholder
             max_loss_scenario: strike.position_size * self.config.stop_loss_pct,
@@ -300,12 +309,12 @@ impl StrikeOptimizer {
     fn determine_execution_conditions(
         &self,
         strike: &MacroStrike,
-        liquidity: &TradeRecommendation,
+        liquidity: Option<&LiquidityPrediction>,
     ) -> ExecutionConditions {
         ExecutionConditions {
-            max_slippage: liquidity.expected_slippage * 2.0,
+            max_slippage: expected_slippage(liquidity) * 2.0,
             time_limit_seconds: 30,
-            partial_fill_allowed: liquidity.confidence < 0.9,
+            partial_fill_allowed: liquidity.map_or(true, |p| p.confidence < 0.9),
             iceberg_order: strike.position_size > 100_000.0,
             requires_confirmation: strike.confidence < 0.95,
         }
@@ -330,19 +339,24 @@ impl StrikeOptimizer {
     }
     
     async fn record_optimization_metrics(&self, analysis: &StrikeAnalysis) {
-        self.monitoring.record_metric(
-            MetricType::StrikeOptimized,
-            1.0,
-            Some(HashMap::from([
-                ("symbol".to_string(), analysis.strike.symbol.clone()),
-                ("confidence".to_string(), analysis.strike.confidence.to_string()),
-                ("edge".to_string(), analysis.edge_calculation.expected_value.to_string()),
-                ("julia_confidence".to_string(), analysis.julia_analysis.julia_confidence.to_string()),
-            ])),
-        ).await;
+        self.monitoring.record_metric(MetricType::StrikeOptimized, 1.0).await;
+        info!(
+            "Optimized {}: confidence {:.2}, edge {:.4}, julia confidence {:.2}",
+            analysis.strike.symbol,
+            analysis.strike.confidence,
+            analysis.edge_calculation.expected_value,
+            analysis.julia_analysis.julia_confidence,
+        );
     }
 }
 
+/// Slippage as a fraction of price: 1% at a zero liquidity score, none at a perfect one,
+/// and the worst case when there is no prediction
+fn expected_slippage(liquidity: Option<&LiquidityPrediction>) -> f64 {
+    let score = liquidity.map_or(0.0, |p| p.predicted_score.clamp(0.0, 1.0));
+    (1.0 - score) * 0.01
+}
+
 // Supporting types
 #[derive(Debug, Clone)]
 pub struct EdgeCalculation {
@@ -9,7 +9,7 @@ use crate::{MacroStrike, StrikeType};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use std::collections::HashMap;
-use log::{info, warn, error};
+use tracing::{info, warn, error};
 
 /// Strike optimizer for finding the best trading opportunities
 pub struct StrikeOptimizer {
@@ -5,11 +5,15 @@ use crate::{MacroStrike, StrikeType, MIN_WIN_PROBABILITY};
 use crate::api::{MarketDataProvider, TradingExchange};
 use crate::api::liquidity::LiquidityMonitor;
 use crate::api::liquidity_predictor::LiquidityPredictor;
+use crate::api::orderbook_math::{self, BookSide};
 use crate::api::safety::SafetyMonitor;
+use crate::advanced_cascade_theory::{AdvancedCascadeTheory, FlowDirection};
+use crate::ultra_fast_cascade::UltraFastCascadeDetector;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use log::{info, warn, error};
-use std::collections::{HashMap, BTreeMap};
+use tracing::{info, warn, error};
+use std::collections::{HashMap, BTreeMap, VecDeque};
+use std::io::{self, Write};
 use chrono::{DateTime, Utc, Duration};
 use nalgebra::{DMatrix, DVector};
 use serde::{Serialize, Deserialize};
@@ -53,6 +57,16 @@ pub trait ValidationModule: Send + Sync {
     fn dependencies(&self) -> Vec<u8> {
         Vec::new()
     }
+    
+    /// Keys this module writes into `ValidationDiagnostics::secondary_metrics`
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema::Unknown
+    }
+    
+    /// Expected latency, used to run cheap modules ahead of expensive ones
+    fn cost(&self) -> ValidationCost {
+        UNKNOWN_MODULE_COST
+    }
 }
 
 /// Validation context passed to modules
@@ -75,7 +89,6 @@ pub struct ValidationServices {
     pub safety_monitor: Arc<SafetyMonitor>,
     pub cascade_detector: Arc<UltraFastCascadeDetector>,
     pub cascade_theory: Arc<AdvancedCascadeTheory>,
-    pub volatility_engine: Arc<StochasticVolatilityEngine>,
 }
 
 /// Configuration for validation behavior
@@ -89,6 +102,8 @@ pub struct ValidationConfig {
     pub retry_attempts: u32,
     pub ml_features_enabled: bool,
     pub quantum_analysis_enabled: bool,
+    #[serde(default)]
+    pub pagerduty_routing_key: String, // Events API v2 integration key for critical alerts
 }
 
 /// Validation state tracking
@@ -99,6 +114,31 @@ pub struct ValidationState {
     pub avg_execution_time_ms: f64,
     pub module_performance: HashMap<u8, ModulePerformance>,
     pub recent_failures: Vec<FailureRecord>,
+    pub history: ValidationHistory,
+}
+
+/// Records kept by `ValidationHistory` before age-based pruning kicks in
+pub const DEFAULT_MAX_HISTORY_SIZE: usize = 1000;
+
+/// Age past which records are dropped once the history outgrows its size
+pub const HISTORY_RETENTION_HOURS: i64 = 24;
+
+/// One finished validation, as kept in `ValidationHistory`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationRecord {
+    pub timestamp: DateTime<Utc>,
+    pub strike_id: u64,
+    pub symbol: String,
+    pub approved: bool,
+    pub confidence: f64,
+    pub modules_executed: usize,
+}
+
+/// Rolling log of recent validations, bounded by size and age
+#[derive(Debug, Clone)]
+pub struct ValidationHistory {
+    pub recent_validations: VecDeque<ValidationRecord>,
+    pub max_history_size: usize,
 }
 
 /// Analytics engine for validation insights
@@ -120,6 +160,18 @@ impl ValidationModule for ProbabilisticConfidenceModule {
     fn category(&self) -> ValidationCategory { ValidationCategory::Statistical }
     fn severity(&self) -> Severity { Severity::Critical }
     fn required(&self) -> bool { true }
+    fn cost(&self) -> ValidationCost { ValidationCost { typical_ms: 0, p99_ms: 1, io_bound: false } }
+    
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema::Described {
+            metric_keys: vec![
+                MetricDescriptor::new("weighted_prior", "Market, historical and regime priors weighted 40/40/20", "probability", Some((0.0, 1.0))),
+                MetricDescriptor::new("information_gain", "Information gained from prior to posterior", "bits", Some((0.0, 1.0))),
+                MetricDescriptor::new("ci_lower", "Monte Carlo 2.5th percentile of the posterior", "probability", Some((0.0, 1.0))),
+                MetricDescriptor::new("ci_upper", "Monte Carlo 97.5th percentile of the posterior", "probability", Some((0.0, 1.0))),
+            ],
+        }
+    }
     
     async fn validate(
         &self,
@@ -149,8 +201,8 @@ impl ValidationModule for ProbabilisticConfidenceModule {
         // Information gain
         let info_gain = self.calculate_information_gain(weighted_prior, posterior);
         
-        // Monte Carlo confidence interval
-        let confidence_interval = self.monte_carlo_confidence_interval(posterior, 10000).await;
+        // Beta credible interval around the posterior
+        let confidence_interval = self.beta_confidence_interval(posterior, 10000);
         
         let passed = posterior >= MIN_WIN_PROBABILITY && 
                     confidence_interval.lower >= MIN_WIN_PROBABILITY * 0.95;
@@ -221,26 +273,38 @@ impl ProbabilisticConfidenceModule {
         kl_divergence.abs()
     }
     
-    async fn monte_carlo_confidence_interval(&self, mean: f64, iterations: usize) -> ConfidenceInterval {
-        use rand::distributions::Distribution;
-        use rand::thread_rng;
-        
-        let alpha = mean * 100.0;
-        let beta = (1.0 - mean) * 100.0;
-        let dist = Beta::new(alpha.max(1.0), beta.max(1.0)).unwrap();
-        
-        let mut samples = Vec::with_capacity(iterations);
-        let mut rng = thread_rng();
-        
-        for _ in 0..iterations {
-            samples.push(dist.sample(&mut rng));
-        }
-        
-        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    // 95% interval of Beta(100·mean, 100·(1 − mean)), from its density integrated over
+    // `resolution` equal cells of [0, 1]. Deterministic, so the same strike always validates the same way.
+    fn beta_confidence_interval(&self, mean: f64, resolution: usize) -> ConfidenceInterval {
+        let alpha = (mean * 100.0).max(1.0);
+        let beta = ((1.0 - mean) * 100.0).max(1.0);
+        let cell = 1.0 / resolution.max(1) as f64;
+        
+        // Log density at each cell midpoint, shifted by its maximum so exp() cannot underflow to all zeros
+        let log_density: Vec<f64> = (0..resolution.max(1))
+            .map(|i| {
+                let x = (i as f64 + 0.5) * cell;
+                (alpha - 1.0) * x.ln() + (beta - 1.0) * (1.0 - x).ln()
+            })
+            .collect();
+        let peak = log_density.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let density: Vec<f64> = log_density.iter().map(|d| (d - peak).exp()).collect();
+        let total: f64 = density.iter().sum();
+        
+        let quantile = |p: f64| {
+            let mut cumulative = 0.0;
+            for (i, d) in density.iter().enumerate() {
+                cumulative += d / total;
+                if cumulative >= p {
+                    return (i as f64 + 0.5) * cell;
+                }
+            }
+            1.0
+        };
         
         ConfidenceInterval {
-            lower: samples[iterations / 40], // 2.5th percentile
-            upper: samples[iterations * 39 / 40], // 97.5th percentile
+            lower: quantile(0.025), // 2.5th percentile
+            upper: quantile(0.975), // 97.5th percentile
             mean,
         }
     }
@@ -258,6 +322,18 @@ impl ValidationModule for DeepLearningRiskModule {
     fn category(&self) -> ValidationCategory { ValidationCategory::MachineLearning }
     fn severity(&self) -> Severity { Severity::High }
     fn required(&self) -> bool { true }
+    fn cost(&self) -> ValidationCost { ValidationCost { typical_ms: 15, p99_ms: 60, io_bound: false } }
+    
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema::Described {
+            metric_keys: vec![
+                MetricDescriptor::new("var_95", "95% value at risk of the position", "usd", None),
+                MetricDescriptor::new("cvar_95", "Expected loss beyond the 95% VaR", "usd", None),
+                MetricDescriptor::new("max_drawdown", "Maximum expected drawdown", "fraction", Some((0.0, 1.0))),
+                MetricDescriptor::new("model_confidence", "Agreement across the risk model ensemble", "probability", Some((0.0, 1.0))),
+            ],
+        }
+    }
     
     async fn validate(
         &self,
@@ -300,7 +376,7 @@ impl ValidationModule for DeepLearningRiskModule {
                     vec![
                         "Reduce position size".to_string(),
                         "Tighten stop loss".to_string(),
-                        "Wait for lower volatility",
+                        "Wait for lower volatility".to_string(),
                     ]
                 } else {
                     vec![]
@@ -329,10 +405,11 @@ impl DeepLearningRiskModule {
     
     async fn run_risk_ensemble(&self, features: &RiskFeatures) -> RiskPredictions {
         // Run multiple models in parallel
+        let model = self.risk_model.read().await;
         let models = vec![
-            self.risk_model.read().await.predict_lstm(features),
-            self.risk_model.read().await.predict_transformer(features),
-            self.risk_model.read().await.predict_gradient_boost(features),
+            model.predict_lstm(features),
+            model.predict_transformer(features),
+            model.predict_gradient_boost(features),
         ];
         
         let predictions = futures::future::join_all(models).await;
@@ -377,6 +454,12 @@ impl DeepLearningRiskModule {
     }
 }
 
+/// Top levels a side counted toward depth imbalance
+const MICROSTRUCTURE_DEPTH_LEVELS: usize = 10;
+
+/// Band around mid, as a fraction, within which resiliency compares side depths
+const MICROSTRUCTURE_DEPTH_BAND_PCT: f64 = 0.02;
+
 /// Module 3: Microstructure Quality Analysis
 pub struct MicrostructureQualityModule;
 
@@ -387,6 +470,22 @@ impl ValidationModule for MicrostructureQualityModule {
     fn category(&self) -> ValidationCategory { ValidationCategory::MarketStructure }
     fn severity(&self) -> Severity { Severity::High }
     fn required(&self) -> bool { true }
+    fn cost(&self) -> ValidationCost { ValidationCost { typical_ms: 25, p99_ms: 150, io_bound: true } }
+    
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema::Described {
+            metric_keys: vec![
+                MetricDescriptor::new("effective_spread_bps", "Effective bid-ask spread", "bps", Some((0.0, 10_000.0))),
+                MetricDescriptor::new("depth_imbalance", "Bid depth minus ask depth over total depth", "ratio", Some((-1.0, 1.0))),
+                MetricDescriptor::new("price_impact_bps", "Estimated impact of filling the position size", "bps", Some((0.0, 10_000.0))),
+                MetricDescriptor::new("flow_toxicity", "Probability of trading against informed flow", "probability", Some((0.0, 1.0))),
+                MetricDescriptor::new("market_resiliency", "How quickly the book refills after a sweep", "score", Some((0.0, 1.0))),
+                MetricDescriptor::new("kyle_lambda", "Price move per unit of signed order flow", "price/volume", None),
+                MetricDescriptor::new("amihud_illiquidity", "Absolute return per unit of traded volume", "return/volume", None),
+                MetricDescriptor::new("microstructure_noise", "Variance of price noise around the efficient price", "variance", None),
+            ],
+        }
+    }
     
     async fn validate(
         &self,
@@ -401,7 +500,8 @@ impl ValidationModule for MicrostructureQualityModule {
         // Calculate microstructure metrics
         let spread = self.calculate_effective_spread(&order_book);
         let depth_imbalance = self.calculate_depth_imbalance(&order_book);
-        let price_impact = self.estimate_price_impact(&order_book, strike.position_size);
+        let side = if strike.target_price < strike.entry_price { BookSide::Bid } else { BookSide::Ask };
+        let price_impact = self.estimate_price_impact(&order_book, side, strike.position_size);
         let toxicity = self.calculate_flow_toxicity(&order_book);
         let resiliency = self.calculate_market_resiliency(&order_book);
         
@@ -443,7 +543,7 @@ impl ValidationModule for MicrostructureQualityModule {
                     vec![
                         "Use limit orders".to_string(),
                         "Split order execution".to_string(),
-                        "Wait for better liquidity",
+                        "Wait for better liquidity".to_string(),
                     ]
                 } else {
                     vec![]
@@ -455,34 +555,27 @@ impl ValidationModule for MicrostructureQualityModule {
 
 impl MicrostructureQualityModule {
     fn calculate_effective_spread(&self, book: &OrderBook) -> f64 {
-        if let (Some(best_bid), Some(best_ask)) = (book.bids.first(), book.asks.first()) {
-            (best_ask.price - best_bid.price) / ((best_ask.price + best_bid.price) / 2.0)
-        } else {
-            1.0 // Max spread if no quotes
-        }
+        orderbook_math::spread_bps(book)
+            .map(|bps| bps / 10_000.0)
+            .unwrap_or(1.0) // Max spread if no usable quotes
     }
     
     fn calculate_depth_imbalance(&self, book: &OrderBook) -> f64 {
-        let bid_depth: f64 = book.bids.iter().take(10).map(|o| o.volume).sum();
-        let ask_depth: f64 = book.asks.iter().take(10).map(|o| o.volume).sum();
-        
-        if bid_depth + ask_depth > 0.0 {
-            ((bid_depth - ask_depth) / (bid_depth + ask_depth)).abs()
-        } else {
-            1.0
+        if book.bids.is_empty() && book.asks.is_empty() {
+            return 1.0;
         }
+        orderbook_math::imbalance(book, MICROSTRUCTURE_DEPTH_LEVELS).abs()
     }
     
-    fn estimate_price_impact(&self, book: &OrderBook, size: f64) -> f64 {
-        // Square-root market impact model
-        let total_depth: f64 = book.bids.iter().chain(book.asks.iter())
-            .map(|o| o.volume)
-            .sum();
-        
-        if total_depth > 0.0 {
-            0.001 * (size / total_depth).sqrt() // Simplified impact model
-        } else {
-            1.0
+    fn estimate_price_impact(&self, book: &OrderBook, side: BookSide, size: f64) -> f64 {
+        // Slippage from mid of filling `size` against `side` (asks for a buy, bids for a sell);
+        // a book that cannot absorb it is max impact
+        let Ok(mid) = orderbook_math::mid_price(book) else {
+            return 1.0;
+        };
+        match orderbook_math::vwap_to_fill(book, side, size) {
+            Ok((avg_price, _)) => (avg_price - mid).abs() / mid,
+            Err(_) => 1.0,
         }
     }
     
@@ -511,19 +604,15 @@ impl MicrostructureQualityModule {
     
     fn calculate_market_resiliency(&self, book: &OrderBook) -> f64 {
         // Check depth and spread recovery potential
-        let bid_depth = book.bids.iter().take(10).map(|b| b.volume).sum::<f64>();
-        let ask_depth = book.asks.iter().take(10).map(|a| a.volume).sum::<f64>();
+        let depth = |side| orderbook_math::depth_within_pct(book, side, MICROSTRUCTURE_DEPTH_BAND_PCT).unwrap_or(0.0);
+        let (bid_depth, ask_depth) = (depth(BookSide::Bid), depth(BookSide::Ask));
         
         if bid_depth == 0.0 || ask_depth == 0.0 {
             return 0.0;
         }
         
         let depth_ratio = bid_depth.min(ask_depth) / bid_depth.max(ask_depth);
-        let spread = if let (Some(bid), Some(ask)) = (book.bids.first(), book.asks.first()) {
-            (ask.price - bid.price) / bid.price
-        } else {
-            0.01
-        };
+        let spread = orderbook_math::spread_bps(book).map(|bps| bps / 10_000.0).unwrap_or(0.01);
         
         // Better depth ratio and tighter spreads = higher resiliency
         let resiliency = depth_ratio * (1.0 - spread.min(0.01) * 100.0);
@@ -579,6 +668,7 @@ impl ValidationModule for QuantumCascadeModule {
     fn category(&self) -> ValidationCategory { ValidationCategory::Revolutionary }
     fn severity(&self) -> Severity { Severity::Medium }
     fn required(&self) -> bool { false }
+    fn cost(&self) -> ValidationCost { ValidationCost { typical_ms: 25, p99_ms: 150, io_bound: true } }
     
     async fn validate(
         &self,
@@ -586,52 +676,39 @@ impl ValidationModule for QuantumCascadeModule {
         context: &ValidationContext,
         services: &ValidationServices,
     ) -> ValidationResult {
-        // Quantum field theory analysis
-        let quantum_state = self.cascade_theory.compute_market_quantum_state(&strike.symbol).await;
-        
-        // Calculate quantum metrics
-        let entanglement_entropy = quantum_state.entanglement_entropy;
-        let phase_transition_prob = quantum_state.phase_transition_probability;
-        let quantum_volatility = quantum_state.quantum_volatility;
-        let coherence_time = quantum_state.coherence_time_ms;
+        // Every validation extends the symbol's price series with the latest quote
+        let quote = services.market_data.get_market_data(&strike.symbol).await.map_err(|e| e.to_string());
+        match quote {
+            Ok(quote) => self.cascade_theory.record_price(&strike.symbol, quote.price).await,
+            Err(e) => warn!(symbol = %strike.symbol, error = %e, "no quote for the cascade price series"),
+        }
         
-        // Renormalization group flow
+        // Renormalization group flow on the recorded price series; the quantum state and
+        // Green's function inputs have no estimator yet, so the flow direction decides alone
         let rg_flow = self.cascade_theory.compute_rg_flow(&strike.symbol).await;
         let critical_exponent = rg_flow.critical_exponent;
         let universality_class = rg_flow.universality_class;
         
-        // Green's functions
-        let greens_function = self.cascade_theory.compute_greens_function(&strike.symbol).await;
-        let propagator_strength = greens_function.propagator_strength;
-        
-        let quantum_favorable = entanglement_entropy > 0.5 && 
-                               phase_transition_prob < 0.2 &&
-                               coherence_time > 1000.0;
-        
-        let passed = quantum_favorable;
+        let passed = rg_flow.flow_direction != FlowDirection::TowardsChaos;
         
         ValidationResult {
             module_id: self.id(),
             passed,
             confidence_impact: if passed { 1.08 } else { 0.95 },
-            risk_contribution: phase_transition_prob * 0.1,
+            risk_contribution: if passed { 0.0 } else { 0.1 },
             diagnostics: ValidationDiagnostics {
-                primary_metric: entanglement_entropy,
+                primary_metric: critical_exponent,
                 secondary_metrics: HashMap::from([
-                    ("phase_transition_prob".to_string(), phase_transition_prob),
-                    ("quantum_volatility".to_string(), quantum_volatility),
-                    ("coherence_time_ms".to_string(), coherence_time),
                     ("critical_exponent".to_string(), critical_exponent),
-                    ("propagator_strength".to_string(), propagator_strength),
                 ]),
                 explanation: format!(
-                    "Quantum state: Entanglement {:.2}, Phase transition {:.1}%, Universality: {}",
-                    entanglement_entropy, phase_transition_prob * 100.0, universality_class
+                    "RG flow {:?}, critical exponent {:.2}, universality: {}",
+                    rg_flow.flow_direction, critical_exponent, universality_class
                 ),
                 recommendations: if !passed {
                     vec!["Market approaching phase transition".to_string(), "Reduce exposure".to_string()]
                 } else {
-                    vec!["Quantum coherence favorable".to_string()]
+                    vec!["Cascade dynamics stable".to_string()]
                 },
             },
         }
@@ -648,6 +725,7 @@ impl ValidationModule for PortfolioOptimizationModule {
     fn category(&self) -> ValidationCategory { ValidationCategory::RiskManagement }
     fn severity(&self) -> Severity { Severity::High }
     fn required(&self) -> bool { true }
+    fn cost(&self) -> ValidationCost { ValidationCost { typical_ms: 2, p99_ms: 5, io_bound: false } }
     
     async fn validate(
         &self,
@@ -703,7 +781,7 @@ impl ValidationModule for PortfolioOptimizationModule {
                     vec![
                         "Position size exceeds Kelly criterion".to_string(),
                         "Consider rebalancing existing positions".to_string(),
-                        "Diversification needed",
+                        "Diversification needed".to_string(),
                     ]
                 } else {
                     vec![]
@@ -801,6 +879,7 @@ impl SuperiorStrikeValidator {
     }
     
     /// Execute validation with superior architecture
+    #[tracing::instrument(name = "validation", skip_all, fields(strike_id = strike.id, symbol = %strike.symbol))]
     pub async fn validate(&self, strike: &MacroStrike) -> SuperiorValidationReport {
         let start_time = std::time::Instant::now();
         
@@ -813,12 +892,14 @@ impl SuperiorStrikeValidator {
         // Execute validations
         let mut results = Vec::new();
         let mut early_termination = false;
+        let mut module_categories = HashMap::new();
         
         for module_id in execution_order {
             if let Some(module) = self.modules.get(&module_id) {
                 // Check if we should continue
                 if self.config.fail_fast && self.should_fail_fast(&results) {
                     early_termination = true;
+                    warn!(modules_run = results.len(), "validation terminated early");
                     break;
                 }
                 
@@ -829,10 +910,20 @@ impl SuperiorStrikeValidator {
                     module.validate(strike, &context, &self.services).await
                 };
                 
+                info!(
+                    module_id,
+                    module = module.name(),
+                    passed = result.passed,
+                    confidence_impact = result.confidence_impact,
+                    primary_metric = result.diagnostics.primary_metric,
+                    "validation module"
+                );
+                
                 // Update context
                 self.update_context(&mut context, &result);
                 
                 // Store result
+                module_categories.insert(module_id, module.category());
                 results.push((module_id, module.name(), result));
             }
         }
@@ -844,19 +935,36 @@ impl SuperiorStrikeValidator {
         let decision = self.make_final_decision(&results, &context, &ml_insights);
         
         // Update state
-        self.update_state(&results, &decision).await;
+        let record = ValidationRecord {
+            timestamp: Utc::now(),
+            strike_id: strike.id,
+            symbol: strike.symbol.clone(),
+            approved: !matches!(decision, ValidationDecision::Rejected { .. }),
+            confidence: context.current_confidence,
+            modules_executed: results.len(),
+        };
+        self.update_state(&results, &decision, record).await;
+        info!(
+            decision = ?decision,
+            confidence = context.current_confidence,
+            risk_score = context.cumulative_risk_score,
+            "validation decided"
+        );
         
+        let recommendations = self.generate_recommendations(&results, &decision);
         SuperiorValidationReport {
             strike_id: strike.id,
             timestamp: Utc::now(),
+            initial_confidence: strike.confidence,
             execution_time_ms: start_time.elapsed().as_millis() as u64,
             modules_executed: results.len(),
             early_termination,
             decision,
             module_results: results,
+            module_categories,
             ml_insights,
             context: context.clone(),
-            recommendations: self.generate_recommendations(&results, &decision),
+            recommendations,
         }
     }
     
@@ -872,32 +980,12 @@ impl SuperiorStrikeValidator {
     }
     
     fn topological_sort_modules(&self) -> Vec<u8> {
-        // Implement topological sort based on dependencies
-        let mut sorted = Vec::new();
-        let mut visited = std::collections::HashSet::new();
-        
-        for &id in self.modules.keys() {
-            self.dfs_sort(id, &mut visited, &mut sorted);
-        }
-        
-        sorted.reverse();
-        sorted
+        cost_ordered_modules(&self.modules)
     }
     
-    fn dfs_sort(&self, id: u8, visited: &mut std::collections::HashSet<u8>, sorted: &mut Vec<u8>) {
-        if visited.contains(&id) {
-            return;
-        }
-        
-        visited.insert(id);
-        
-        if let Some(module) = self.modules.get(&id) {
-            for &dep in module.dependencies().iter() {
-                self.dfs_sort(dep, visited, sorted);
-            }
-        }
-        
-        sorted.push(id);
+    /// Sum of every module's typical latency, for estimating a validation before running it
+    pub fn estimated_total_cost_ms(&self) -> u64 {
+        self.modules.values().map(|module| module.cost().typical_ms).sum()
     }
     
     async fn execute_parallel_validation(
@@ -921,7 +1009,7 @@ impl SuperiorStrikeValidator {
                     primary_metric: 0.0,
                     secondary_metrics: HashMap::new(),
                     explanation: "Validation timed out".to_string(),
-                    recommendations: vec!["Increase timeout or optimize validation"],
+                    recommendations: vec!["Increase timeout or optimize validation".to_string()],
                 },
             },
         }
@@ -974,7 +1062,12 @@ impl SuperiorStrikeValidator {
         }
     }
     
-    async fn update_state(&self, results: &[(u8, &'static str, ValidationResult)], decision: &ValidationDecision) {
+    async fn update_state(
+        &self,
+        results: &[(u8, &'static str, ValidationResult)],
+        decision: &ValidationDecision,
+        record: ValidationRecord,
+    ) {
         let mut state = self.state.write().await;
         
         state.total_validations += 1;
@@ -995,6 +1088,22 @@ impl SuperiorStrikeValidator {
                 perf.passes += 1;
             }
         }
+        
+        state.history.record_validation(record);
+    }
+    
+    /// Bound the validation history at `max_history_size` records
+    pub fn with_max_history_size(self, max_history_size: usize) -> Self {
+        // Still building, so nothing else holds the lock
+        if let Ok(mut state) = self.state.try_write() {
+            state.history.max_history_size = max_history_size;
+        }
+        self
+    }
+    
+    /// Write the validation history as JSON lines, oldest first
+    pub async fn export_history_jsonl(&self, writer: &mut dyn Write) -> io::Result<usize> {
+        self.state.read().await.history.export_history_jsonl(writer)
     }
     
     fn generate_recommendations(
@@ -1049,10 +1158,134 @@ impl SuperiorStrikeValidator {
             .map(|(_, name, r)| format!("{}: {}", name, r.diagnostics.explanation))
             .collect()
     }
+    
+    /// JSON Schema of every registered module's `secondary_metrics`, keyed by module id
+    pub fn export_schema(&self) -> serde_json::Value {
+        export_module_schemas(self.modules.values().map(|module| module.as_ref()))
+    }
+}
+
+/// Module ids in execution order: dependencies first, then cheapest typical cost first among
+/// the modules whose dependencies have run. Dependencies on unregistered modules are ignored;
+/// a dependency cycle runs its remaining modules in id order.
+fn cost_ordered_modules(modules: &BTreeMap<u8, Box<dyn ValidationModule>>) -> Vec<u8> {
+    let mut remaining: BTreeMap<u8, Vec<u8>> = modules.iter()
+        .map(|(&id, module)| {
+            let deps = module.dependencies().into_iter().filter(|dep| modules.contains_key(dep)).collect();
+            (id, deps)
+        })
+        .collect();
+    let mut sorted = Vec::with_capacity(modules.len());
+    
+    while !remaining.is_empty() {
+        let next = remaining.iter()
+            .filter(|(_, deps)| deps.iter().all(|dep| sorted.contains(dep)))
+            .map(|(&id, _)| id)
+            .min_by_key(|id| (modules[id].cost().typical_ms, *id))
+            .unwrap_or_else(|| *remaining.keys().next().unwrap());
+        remaining.remove(&next);
+        sorted.push(next);
+    }
+    
+    sorted
+}
+
+fn export_module_schemas<'a>(modules: impl Iterator<Item = &'a dyn ValidationModule>) -> serde_json::Value {
+    let properties: serde_json::Map<String, serde_json::Value> = modules
+        .map(|module| {
+            let mut schema = module.schema().to_json_schema();
+            schema["title"] = serde_json::json!(module.name());
+            (module.id().to_string(), schema)
+        })
+        .collect();
+    
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Superior strike validator secondary metrics",
+        "type": "object",
+        "properties": properties,
+    })
 }
 
 // ===== SUPPORTING STRUCTURES =====
 
+/// Expected latency of one module's `validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationCost {
+    pub typical_ms: u64,
+    pub p99_ms: u64,
+    pub io_bound: bool, // Waits on an exchange or data feed rather than computing
+}
+
+/// Assumed for modules that don't declare a cost, so they run after those that do
+pub const UNKNOWN_MODULE_COST: ValidationCost = ValidationCost { typical_ms: 100, p99_ms: 1000, io_bound: true };
+
+/// One `secondary_metrics` key a module promises to emit
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricDescriptor {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub units: &'static str,
+    pub range: Option<(f64, f64)>, // Inclusive bounds when the metric is bounded
+}
+
+impl MetricDescriptor {
+    pub fn new(key: &'static str, description: &'static str, units: &'static str, range: Option<(f64, f64)>) -> Self {
+        Self { key, description, units, range }
+    }
+    
+    fn to_json_schema(&self) -> serde_json::Value {
+        let mut schema = serde_json::json!({
+            "type": "number",
+            "description": self.description,
+            "x-units": self.units,
+        });
+        if let Some((minimum, maximum)) = self.range {
+            schema["minimum"] = serde_json::json!(minimum);
+            schema["maximum"] = serde_json::json!(maximum);
+        }
+        schema
+    }
+}
+
+/// Shape of a module's diagnostic output, for consumers of `secondary_metrics`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum ModuleSchema {
+    Unknown,
+    Described { metric_keys: Vec<MetricDescriptor> },
+}
+
+impl ModuleSchema {
+    pub fn metric_keys(&self) -> &[MetricDescriptor] {
+        match self {
+            ModuleSchema::Unknown => &[],
+            ModuleSchema::Described { metric_keys } => metric_keys,
+        }
+    }
+    
+    /// JSON Schema for a `secondary_metrics` map; unknown modules allow any numeric keys
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        match self {
+            ModuleSchema::Unknown => serde_json::json!({
+                "type": "object",
+                "additionalProperties": { "type": "number" },
+            }),
+            ModuleSchema::Described { metric_keys } => {
+                let properties: serde_json::Map<String, serde_json::Value> = metric_keys.iter()
+                    .map(|metric| (metric.key.to_string(), metric.to_json_schema()))
+                    .collect();
+                let required: Vec<&str> = metric_keys.iter().map(|metric| metric.key).collect();
+                serde_json::json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                })
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
     pub module_id: u8,
@@ -1070,20 +1303,247 @@ pub struct ValidationDiagnostics {
     pub recommendations: Vec<String>,
 }
 
+impl ValidationDiagnostics {
+    /// Explanation prefixed by severity, with a recommendation appended for critical failures
+    pub fn severity_adjusted_message(&self, severity: &Severity) -> String {
+        let prefix = match severity {
+            Severity::Critical => "🔴 CRITICAL:",
+            Severity::High => "🟠 HIGH:",
+            Severity::Medium => "🟡 MEDIUM:",
+            Severity::Low => "🟢 LOW:",
+        };
+        
+        match severity {
+            Severity::Critical => {
+                let recommendation = self.recommendations
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "Reject strike and review module inputs".to_string());
+                format!("{} {} → {}", prefix, self.explanation, recommendation)
+            }
+            _ => format!("{} {}", prefix, self.explanation),
+        }
+    }
+    
+    /// Slack API attachment with one field per secondary metric
+    pub fn to_slack_attachment(&self, severity: &Severity) -> serde_json::Value {
+        let color = match severity {
+            Severity::Critical | Severity::High => "danger",
+            Severity::Medium => "warning",
+            Severity::Low => "good",
+        };
+        
+        let mut metrics: Vec<(&String, &f64)> = self.secondary_metrics.iter().collect();
+        metrics.sort_by(|a, b| a.0.cmp(b.0));
+        
+        let mut fields = vec![serde_json::json!({
+            "title": "primary_metric",
+            "value": format!("{:.4}", self.primary_metric),
+            "short": true,
+        })];
+        fields.extend(metrics.into_iter().map(|(name, value)| serde_json::json!({
+            "title": name,
+            "value": format!("{:.4}", value),
+            "short": true,
+        })));
+        
+        serde_json::json!({
+            "fallback": self.severity_adjusted_message(severity),
+            "color": color,
+            "title": self.severity_adjusted_message(severity),
+            "text": self.recommendations.join("\n"),
+            "fields": fields,
+            "ts": Utc::now().timestamp(),
+        })
+    }
+    
+    /// PagerDuty Events API v2 trigger payload, intended for critical failures. PagerDuty rejects
+    /// events without the service's `routing_key` (`ValidationConfig::pagerduty_routing_key`).
+    pub fn to_pagerduty_payload(&self, severity: &Severity, routing_key: &str) -> serde_json::Value {
+        let pd_severity = match severity {
+            Severity::Critical => "critical",
+            Severity::High => "error",
+            Severity::Medium => "warning",
+            Severity::Low => "info",
+        };
+        
+        serde_json::json!({
+            "routing_key": routing_key,
+            "event_action": "trigger",
+            "payload": {
+                "summary": self.severity_adjusted_message(severity),
+                "severity": pd_severity,
+                "source": "superior_strike_validator",
+                "timestamp": Utc::now().to_rfc3339(),
+                "custom_details": {
+                    "primary_metric": self.primary_metric,
+                    "secondary_metrics": self.secondary_metrics,
+                    "recommendations": self.recommendations,
+                },
+            },
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuperiorValidationReport {
     pub strike_id: u64,
     pub timestamp: DateTime<Utc>,
+    pub initial_confidence: f64, // Strike confidence before any module adjusted it
     pub execution_time_ms: u64,
     pub modules_executed: usize,
     pub early_termination: bool,
     pub decision: ValidationDecision,
+    #[serde(skip_deserializing)] // Module names borrow from the registered modules
     pub module_results: Vec<(u8, &'static str, ValidationResult)>,
+    #[serde(default)]
+    pub module_categories: HashMap<u8, ValidationCategory>, // By step
     pub ml_insights: MLInsights,
     pub context: ValidationContext,
     pub recommendations: Vec<String>,
 }
 
+impl SuperiorValidationReport {
+    /// (step, step_adjustment, cumulative_confidence) in execution order,
+    /// showing how the per-module multipliers compound
+    pub fn impact_chain(&self) -> Vec<(u8, f64, f64)> {
+        let mut confidence = self.initial_confidence;
+        self.module_results.iter()
+            .map(|(step, _, result)| {
+                confidence *= result.confidence_impact;
+                (*step, result.confidence_impact, confidence)
+            })
+            .collect()
+    }
+    
+    /// Step with the largest single downward adjustment, if any step cut confidence
+    pub fn biggest_confidence_killer(&self) -> Option<u8> {
+        self.module_results.iter()
+            .filter(|(_, _, result)| result.confidence_impact < 1.0)
+            .min_by(|a, b| a.2.confidence_impact.total_cmp(&b.2.confidence_impact))
+            .map(|(step, _, _)| *step)
+    }
+    
+    /// Module results by category, most important category first; steps run in each
+    /// category stay in execution order
+    pub fn group_by_category(&self) -> BTreeMap<ValidationCategory, Vec<&ValidationResult>> {
+        let mut groups: BTreeMap<ValidationCategory, Vec<&ValidationResult>> = BTreeMap::new();
+        for (step, _, result) in &self.module_results {
+            if let Some(category) = self.module_categories.get(step) {
+                groups.entry(category.clone()).or_default().push(result);
+            }
+        }
+        groups
+    }
+    
+    /// Fraction of the steps in `category` that passed; 0.0 when none of them ran
+    pub fn category_pass_rate(&self, category: &ValidationCategory) -> f64 {
+        let groups = self.group_by_category();
+        match groups.get(category) {
+            Some(results) if !results.is_empty() => {
+                results.iter().filter(|r| r.passed).count() as f64 / results.len() as f64
+            }
+            _ => 0.0,
+        }
+    }
+    
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Strike {} Validation Report\n\n", self.strike_id);
+        
+        let decision = match &self.decision {
+            ValidationDecision::Approved { confidence, .. } => {
+                format!("✅ Approved at {:.1}% confidence", confidence * 100.0)
+            }
+            ValidationDecision::ConditionallyApproved { confidence, conditions, .. } => format!(
+                "⚠️ Conditionally approved at {:.1}% confidence ({})",
+                confidence * 100.0,
+                conditions.join(", ")
+            ),
+            ValidationDecision::Rejected { primary_reasons, risk_score } => format!(
+                "❌ Rejected, risk score {:.2} ({})",
+                risk_score,
+                primary_reasons.join("; ")
+            ),
+        };
+        md.push_str(&format!("**Decision:** {}\n\n", decision));
+        md.push_str(&format!(
+            "- Modules executed: {}{}\n- Execution time: {} ms\n\n",
+            self.modules_executed,
+            if self.early_termination { " (terminated early)" } else { "" },
+            self.execution_time_ms
+        ));
+        
+        md.push_str("## Confidence Impact Chain\n\n");
+        md.push_str("| Step | Module | Adjustment | Confidence | |\n");
+        md.push_str("|---:|---|---:|---:|---|\n");
+        md.push_str(&format!(
+            "| 0 | Initial | | {:.1}% | `{}` |\n",
+            self.initial_confidence * 100.0,
+            unicode_bar(self.initial_confidence, CONFIDENCE_BAR_WIDTH)
+        ));
+        for ((step, name, _), (_, adjustment, confidence)) in self.module_results.iter().zip(self.impact_chain()) {
+            md.push_str(&format!(
+                "| {} | {} | ×{:.3} | {:.1}% | `{}` |\n",
+                step,
+                name,
+                adjustment,
+                confidence * 100.0,
+                unicode_bar(confidence, CONFIDENCE_BAR_WIDTH)
+            ));
+        }
+        
+        let groups = self.group_by_category();
+        if !groups.is_empty() {
+            md.push_str("\n## By Category\n\n");
+            md.push_str("| Category | Passed | |\n");
+            md.push_str("|---|---:|---|\n");
+            for (category, results) in &groups {
+                let passed = results.iter().filter(|r| r.passed).count();
+                md.push_str(&format!(
+                    "| {} | {}/{} | `{}` |\n",
+                    category,
+                    passed,
+                    results.len(),
+                    unicode_bar(passed as f64 / results.len() as f64, CONFIDENCE_BAR_WIDTH)
+                ));
+            }
+        }
+        
+        if let Some(killer) = self.biggest_confidence_killer() {
+            if let Some((_, name, result)) = self.module_results.iter().find(|(step, _, _)| *step == killer) {
+                md.push_str(&format!(
+                    "\n**Biggest confidence killer:** step {} ({}) at ×{:.3}\n",
+                    killer, name, result.confidence_impact
+                ));
+            }
+        }
+        
+        if !self.recommendations.is_empty() {
+            md.push_str("\n## Recommendations\n\n");
+            for recommendation in &self.recommendations {
+                md.push_str(&format!("- {}\n", recommendation));
+            }
+        }
+        
+        md
+    }
+}
+
+const CONFIDENCE_BAR_WIDTH: usize = 20; // Characters for a 100% bar
+
+/// Horizontal bar of `fraction` (clamped to 0..=1) at eighth-character resolution, padded to `width`
+fn unicode_bar(fraction: f64, width: usize) -> String {
+    const PARTIALS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    let eighths = (fraction.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 > 0 {
+        bar.push(PARTIALS[eighths % 8]);
+    }
+    let filled = bar.chars().count();
+    bar.push_str(&" ".repeat(width - filled));
+    bar
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ValidationDecision {
     Approved {
@@ -1101,7 +1561,7 @@ pub enum ValidationDecision {
     },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ValidationCategory {
     Statistical,
     MachineLearning,
@@ -1110,6 +1570,44 @@ pub enum ValidationCategory {
     Revolutionary,
 }
 
+impl ValidationCategory {
+    /// Rank in reports, 0 first: risk checks lead, experimental modules trail
+    pub fn priority(&self) -> u8 {
+        match self {
+            ValidationCategory::RiskManagement => 0,
+            ValidationCategory::MarketStructure => 1,
+            ValidationCategory::Statistical => 2,
+            ValidationCategory::MachineLearning => 3,
+            ValidationCategory::Revolutionary => 4,
+        }
+    }
+}
+
+impl Ord for ValidationCategory {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.priority().cmp(&other.priority())
+    }
+}
+
+impl PartialOrd for ValidationCategory {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for ValidationCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ValidationCategory::Statistical => "Statistical",
+            ValidationCategory::MachineLearning => "Machine Learning",
+            ValidationCategory::MarketStructure => "Market Structure",
+            ValidationCategory::RiskManagement => "Risk Management",
+            ValidationCategory::Revolutionary => "Revolutionary",
+        };
+        f.write_str(name)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Severity {
     Critical,
@@ -1147,7 +1645,7 @@ pub struct PortfolioState {
     pub risk_metrics: RiskMetrics,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Position {
     pub symbol: String,
     pub size: f64,
@@ -1156,7 +1654,7 @@ pub struct Position {
     pub pnl: f64,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RiskMetrics {
     pub portfolio_var: f64,
     pub portfolio_cvar: f64,
@@ -1353,6 +1851,7 @@ impl Default for ValidationConfig {
             retry_attempts: 3,
             ml_features_enabled: true,
             quantum_analysis_enabled: true,
+            pagerduty_routing_key: String::new(),
         }
     }
 }
@@ -1365,10 +1864,56 @@ impl Default for ValidationState {
             avg_execution_time_ms: 0.0,
             module_performance: HashMap::new(),
             recent_failures: Vec::new(),
+            history: ValidationHistory::default(),
         }
     }
 }
 
+impl ValidationHistory {
+    pub fn new(max_history_size: usize) -> Self {
+        Self {
+            recent_validations: VecDeque::with_capacity(max_history_size),
+            max_history_size,
+        }
+    }
+    
+    /// Append a record. Once the queue holds more than twice `max_history_size`,
+    /// records older than `HISTORY_RETENTION_HOURS` before this one are pruned,
+    /// then the oldest are dropped until `max_history_size` remain
+    pub fn record_validation(&mut self, record: ValidationRecord) {
+        let cutoff = record.timestamp - Duration::hours(HISTORY_RETENTION_HOURS);
+        self.recent_validations.push_back(record);
+        if self.recent_validations.len() > self.max_history_size * 2 {
+            self.prune_before(cutoff);
+            while self.recent_validations.len() > self.max_history_size {
+                self.recent_validations.pop_front();
+            }
+        }
+    }
+    
+    /// Drop every record older than `cutoff`; returns how many were removed
+    pub fn prune_before(&mut self, cutoff: DateTime<Utc>) -> usize {
+        let before = self.recent_validations.len();
+        self.recent_validations.retain(|record| record.timestamp >= cutoff);
+        before - self.recent_validations.len()
+    }
+    
+    /// One JSON object per line, oldest first; returns the number of records written
+    pub fn export_history_jsonl(&self, writer: &mut dyn Write) -> io::Result<usize> {
+        for record in &self.recent_validations {
+            serde_json::to_writer(&mut *writer, record)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(self.recent_validations.len())
+    }
+}
+
+impl Default for ValidationHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_HISTORY_SIZE)
+    }
+}
+
 use crate::api::{OrderBook, OrderBookLevel};
 
 impl Default for OrderBook {
@@ -1381,3 +1926,287 @@ impl Default for OrderBook {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_diagnostics() -> ValidationDiagnostics {
+        let mut secondary_metrics = HashMap::new();
+        secondary_metrics.insert("liquidity_ratio".to_string(), 0.42);
+        secondary_metrics.insert("spread_bps".to_string(), 18.0);
+        ValidationDiagnostics {
+            primary_metric: 0.35,
+            secondary_metrics,
+            explanation: "Liquidity below threshold".to_string(),
+            recommendations: vec!["Reduce position size by 50%".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_price_impact_walks_the_side_the_strike_trades_against() {
+        let level = |price: f64, volume: f64| crate::api::OrderBookLevel { price, volume, timestamp: None };
+        let book = OrderBook {
+            symbol: "ETH/USDT".to_string(),
+            bids: vec![level(99.0, 10.0)], // Thin bids
+            asks: vec![level(101.0, 1_000.0)],
+            timestamp: std::time::SystemTime::now(),
+        };
+        let module = MicrostructureQualityModule;
+        assert!((module.estimate_price_impact(&book, BookSide::Ask, 5_000.0) - 0.01).abs() < 1e-9);
+        assert_eq!(module.estimate_price_impact(&book, BookSide::Bid, 5_000.0), 1.0);
+        assert!((module.estimate_price_impact(&book, BookSide::Bid, 500.0) - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_confidence_interval_is_deterministic_beta_quantiles() {
+        let module = ProbabilisticConfidenceModule;
+        let interval = module.beta_confidence_interval(0.9, 10_000);
+        // Beta(90, 10) is left-skewed: its 2.5th and 97.5th percentiles are about 0.833 and 0.951
+        assert!((interval.lower - 0.833).abs() < 0.005, "lower {}", interval.lower);
+        assert!((interval.upper - 0.951).abs() < 0.005, "upper {}", interval.upper);
+        let again = module.beta_confidence_interval(0.9, 10_000);
+        assert_eq!((again.lower, again.upper), (interval.lower, interval.upper));
+    }
+
+    #[test]
+    fn test_severity_adjusted_message() {
+        let diagnostics = create_test_diagnostics();
+        assert_eq!(
+            diagnostics.severity_adjusted_message(&Severity::Critical),
+            "🔴 CRITICAL: Liquidity below threshold → Reduce position size by 50%"
+        );
+        assert_eq!(
+            diagnostics.severity_adjusted_message(&Severity::Low),
+            "🟢 LOW: Liquidity below threshold"
+        );
+    }
+
+    #[test]
+    fn test_alerting_payloads() {
+        let diagnostics = create_test_diagnostics();
+        let attachment = diagnostics.to_slack_attachment(&Severity::Medium);
+        assert_eq!(attachment["color"], "warning");
+        assert_eq!(attachment["fields"].as_array().unwrap().len(), 3);
+
+        let config = ValidationConfig {
+            pagerduty_routing_key: "R0UT1NGK3Y".to_string(),
+            ..ValidationConfig::default()
+        };
+        let payload = diagnostics.to_pagerduty_payload(&Severity::Critical, &config.pagerduty_routing_key);
+        assert_eq!(payload["event_action"], "trigger");
+        assert_eq!(payload["payload"]["severity"], "critical");
+        let serialized: serde_json::Value = serde_json::from_str(&payload.to_string()).unwrap();
+        assert_eq!(serialized["routing_key"], "R0UT1NGK3Y");
+    }
+
+    fn create_test_report(impacts: &[f64]) -> SuperiorValidationReport {
+        let module_results: Vec<(u8, &'static str, ValidationResult)> = impacts.iter()
+            .enumerate()
+            .map(|(i, &impact)| (
+                i as u8 + 1,
+                "Test Module",
+                ValidationResult {
+                    module_id: i as u8 + 1,
+                    passed: impact >= 1.0,
+                    confidence_impact: impact,
+                    risk_contribution: 0.0,
+                    diagnostics: create_test_diagnostics(),
+                },
+            ))
+            .collect();
+        SuperiorValidationReport {
+            strike_id: 42,
+            timestamp: Utc::now(),
+            initial_confidence: 0.95,
+            execution_time_ms: 12,
+            modules_executed: module_results.len(),
+            early_termination: false,
+            decision: ValidationDecision::Rejected {
+                primary_reasons: vec!["Confidence eroded".to_string()],
+                risk_score: 0.4,
+            },
+            module_results,
+            module_categories: HashMap::new(),
+            ml_insights: MLInsights::default(),
+            context: ValidationContext {
+                current_confidence: 0.0,
+                cumulative_risk_score: 0.0,
+                previous_results: HashMap::new(),
+                market_state: MarketState::default(),
+                portfolio_state: PortfolioState::default(),
+                historical_data: HistoricalContext::default(),
+            },
+            recommendations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_impact_chain_compounds_adjustments() {
+        let report = create_test_report(&[0.95; 6]);
+        let chain = report.impact_chain();
+        assert_eq!(chain.len(), 6);
+        assert_eq!(chain[0].0, 1);
+        assert!((chain[5].2 - 0.95 * 0.95f64.powi(6)).abs() < 1e-12);
+        assert!(chain.windows(2).all(|w| w[1].2 < w[0].2));
+
+        let mixed = create_test_report(&[1.05, 0.92, 1.08, 0.91, 0.95]);
+        assert_eq!(mixed.biggest_confidence_killer(), Some(4));
+        assert_eq!(create_test_report(&[1.05, 1.03]).biggest_confidence_killer(), None);
+
+        let markdown = mixed.to_markdown();
+        assert!(markdown.contains("## Confidence Impact Chain"));
+        assert!(markdown.contains("| 4 | Test Module | ×0.910 |"));
+        assert!(markdown.contains("**Biggest confidence killer:** step 4"));
+        assert!(markdown.contains('█'));
+    }
+
+    #[test]
+    fn test_results_grouped_by_category_priority() {
+        let mut report = create_test_report(&[1.05, 0.92, 1.08, 0.91, 0.95]);
+        report.module_categories = HashMap::from([
+            (1, ValidationCategory::Statistical),
+            (2, ValidationCategory::RiskManagement),
+            (3, ValidationCategory::Statistical),
+            (4, ValidationCategory::RiskManagement),
+            (5, ValidationCategory::Revolutionary),
+        ]);
+
+        let groups = report.group_by_category();
+        let order: Vec<&ValidationCategory> = groups.keys().collect();
+        assert_eq!(order, [
+            &ValidationCategory::RiskManagement,
+            &ValidationCategory::Statistical,
+            &ValidationCategory::Revolutionary,
+        ]);
+        let risk_steps: Vec<u8> = groups[&ValidationCategory::RiskManagement].iter().map(|r| r.module_id).collect();
+        assert_eq!(risk_steps, [2, 4]);
+
+        assert_eq!(report.category_pass_rate(&ValidationCategory::Statistical), 1.0);
+        assert_eq!(report.category_pass_rate(&ValidationCategory::RiskManagement), 0.0);
+        assert_eq!(report.category_pass_rate(&ValidationCategory::MachineLearning), 0.0);
+        assert!(report.to_markdown().contains("| Risk Management | 0/2 |"));
+    }
+
+    #[test]
+    fn test_export_schema_describes_module_metrics() {
+        let deep_learning = DeepLearningRiskModule {
+            risk_model: Arc::new(RwLock::new(Box::new(DefaultRiskModel))),
+        };
+        let modules: Vec<&dyn ValidationModule> = vec![
+            &ProbabilisticConfidenceModule,
+            &deep_learning,
+            &MicrostructureQualityModule,
+            &PortfolioOptimizationModule,
+        ];
+        let schema = export_module_schemas(modules.into_iter());
+
+        let microstructure = &schema["properties"]["3"];
+        assert_eq!(microstructure["title"], "Microstructure Quality Analysis");
+        assert_eq!(microstructure["required"].as_array().unwrap().len(), 8);
+        assert_eq!(microstructure["properties"]["depth_imbalance"]["minimum"], -1.0);
+        assert_eq!(microstructure["properties"]["effective_spread_bps"]["x-units"], "bps");
+        assert!(microstructure["properties"]["kyle_lambda"].get("maximum").is_none());
+        assert_eq!(schema["properties"]["1"]["properties"]["ci_lower"]["maximum"], 1.0);
+        assert_eq!(schema["properties"]["2"]["properties"]["var_95"]["x-units"], "usd");
+
+        // No schema yet: any numeric key is allowed
+        assert_eq!(PortfolioOptimizationModule.schema(), ModuleSchema::Unknown);
+        assert_eq!(schema["properties"]["5"]["additionalProperties"]["type"], "number");
+    }
+
+    struct StubModule {
+        id: u8,
+        typical_ms: u64,
+        dependencies: Vec<u8>,
+    }
+
+    #[async_trait::async_trait]
+    impl ValidationModule for StubModule {
+        fn id(&self) -> u8 { self.id }
+        fn name(&self) -> &'static str { "Stub" }
+        fn category(&self) -> ValidationCategory { ValidationCategory::Statistical }
+        fn severity(&self) -> Severity { Severity::Low }
+        fn required(&self) -> bool { false }
+        fn cost(&self) -> ValidationCost { ValidationCost { typical_ms: self.typical_ms, p99_ms: self.typical_ms, io_bound: false } }
+        fn dependencies(&self) -> Vec<u8> { self.dependencies.clone() }
+
+        async fn validate(&self, _: &MacroStrike, _: &ValidationContext, _: &ValidationServices) -> ValidationResult {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn test_modules_ordered_by_cost_after_dependencies() {
+        let stub = |id, typical_ms, dependencies: &[u8]| -> Box<dyn ValidationModule> {
+            Box::new(StubModule { id, typical_ms, dependencies: dependencies.to_vec() })
+        };
+        let modules: BTreeMap<u8, Box<dyn ValidationModule>> = BTreeMap::from([
+            (1, stub(1, 50, &[])),
+            (2, stub(2, 1, &[4])),
+            (3, stub(3, 5, &[])),
+            (4, stub(4, 20, &[9])),
+            (5, stub(5, 5, &[1])),
+        ]);
+        // 4's dependency isn't registered; 2 is cheap but waits for 4; 5 waits for the costly 1
+        assert_eq!(cost_ordered_modules(&modules), [3, 4, 2, 1, 5]);
+
+        let cyclic: BTreeMap<u8, Box<dyn ValidationModule>> = BTreeMap::from([
+            (1, stub(1, 1, &[2])),
+            (2, stub(2, 1, &[1])),
+            (3, stub(3, 9, &[])),
+        ]);
+        assert_eq!(cost_ordered_modules(&cyclic), [3, 1, 2]);
+
+        let built_in: BTreeMap<u8, Box<dyn ValidationModule>> = BTreeMap::from([
+            (1, Box::new(ProbabilisticConfidenceModule) as Box<dyn ValidationModule>),
+            (3, Box::new(MicrostructureQualityModule)),
+            (5, Box::new(PortfolioOptimizationModule)),
+        ]);
+        assert_eq!(cost_ordered_modules(&built_in), [1, 5, 3]);
+    }
+
+    #[test]
+    fn test_unicode_bar_resolution() {
+        assert_eq!(unicode_bar(1.0, 4), "████");
+        assert_eq!(unicode_bar(0.5, 4), "██  ");
+        assert_eq!(unicode_bar(0.125, 4), "▌   ");
+        assert_eq!(unicode_bar(1.7, 2), "██");
+    }
+
+    fn create_test_record(strike_id: u64, timestamp: DateTime<Utc>) -> ValidationRecord {
+        ValidationRecord {
+            timestamp,
+            strike_id,
+            symbol: "SOL".to_string(),
+            approved: strike_id % 2 == 0,
+            confidence: 0.91,
+            modules_executed: 5,
+        }
+    }
+
+    #[test]
+    fn test_history_prunes_by_age_once_over_twice_its_size() {
+        let now = Utc::now();
+        let mut history = ValidationHistory::new(3);
+        for id in 0..6 {
+            history.record_validation(create_test_record(id, now - Duration::hours(30)));
+        }
+        assert_eq!(history.recent_validations.len(), 6);
+
+        // The seventh record crosses 2 × 3: everything over a day old goes
+        history.record_validation(create_test_record(6, now));
+        let ids: Vec<u64> = history.recent_validations.iter().map(|r| r.strike_id).collect();
+        assert_eq!(ids, [6]);
+
+        history.record_validation(create_test_record(7, now + Duration::minutes(1)));
+        assert_eq!(history.prune_before(now + Duration::seconds(1)), 1);
+
+        let mut out = Vec::new();
+        assert_eq!(history.export_history_jsonl(&mut out).unwrap(), 1);
+        let line = String::from_utf8(out).unwrap();
+        assert_eq!(line.lines().count(), 1);
+        let parsed: ValidationRecord = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(parsed.strike_id, 7);
+    }
+}
@@ -0,0 +1,354 @@
+// Symbol Registry
+// One record per tradable instrument, so scanner pairs, venue codes and token addresses resolve alike
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+
+/// Shared between the scanner, the engine integration and the API clients
+pub type SharedSymbolRegistry = Arc<RwLock<SymbolRegistry>>;
+
+// Tickers that name the same asset; lookups canonicalize both sides of a pair through these
+const DEFAULT_ASSET_ALIASES: [(&str, &str); 4] = [("XBT", "BTC"), ("XDG", "DOGE"), ("WETH", "ETH"), ("WBTC", "BTC")];
+// Chain whose token addresses a DEX venue trades
+const DEFAULT_VENUE_CHAINS: [(&str, &str); 3] = [("uniswap", "ethereum"), ("sushiswap", "ethereum"), ("pancakeswap", "bsc")];
+// Uniswap pairs every registry built with `with_default_instruments` starts from: (base, quote, venue code, base token address)
+const DEFAULT_DEX_INSTRUMENTS: [(&str, &str, &str, &str); 8] = [
+    ("WETH", "USDC", "WETH-USDC", "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+    ("WBTC", "USDC", "WBTC-USDC", "0x2260fac5e5542a773aa44fbcfedf7c193bc2c599"),
+    ("LINK", "USDC", "LINK-USDC", "0x514910771af9ca656af840dff83e8264ecf986ca"),
+    ("UNI", "USDC", "UNI-USDC", "0x1f9840a85d5af5bf1d1762f925bdaddc4201f984"),
+    ("AAVE", "USDC", "AAVE-USDC", "0x7fc66500c84a76ad7e9c93437bfc5ac33e2ddae9"),
+    ("CRV", "USDC", "CRV-USDC", "0xd533a949740bb3306d119cc777fa900ba034cd52"),
+    ("USDC", "USDT", "USDC-USDT", "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+    ("DAI", "USDC", "DAI-USDC", "0x6b175474e89094c44da98b954e4fedb10c495d0f"),
+];
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SymbolError {
+    #[error("Unknown instrument: {0}")]
+    Unknown(String),
+
+    #[error("{pair} trades on several venues ({}); name one", venues.join(", "))]
+    Ambiguous { pair: String, venues: Vec<String> },
+
+    #[error("Invalid symbol config: {0}")]
+    Config(String),
+}
+
+/// One instrument on one venue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Instrument {
+    pub base: String,       // Canonical ticker, e.g. "ETH"
+    pub quote: String,
+    pub venue: String,      // Lowercase venue name, e.g. "kraken"
+    pub venue_code: String, // What the venue calls it, e.g. "XETHZUSD"
+    #[serde(default)]
+    pub addresses: BTreeMap<String, String>, // Chain -> base token contract address
+}
+
+impl Instrument {
+    pub fn new(base: &str, quote: &str, venue: &str, venue_code: &str) -> Self {
+        Self {
+            base: base.to_uppercase(),
+            quote: quote.to_uppercase(),
+            venue: venue.to_lowercase(),
+            venue_code: venue_code.to_string(),
+            addresses: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_address(mut self, chain: &str, address: &str) -> Self {
+        self.addresses.insert(chain.to_lowercase(), address.to_string());
+        self
+    }
+
+    /// "BASE/QUOTE"
+    pub fn pair(&self) -> String {
+        format!("{}/{}", self.base, self.quote)
+    }
+
+    pub fn address_on(&self, chain: &str) -> Option<&str> {
+        self.addresses.get(&chain.to_lowercase()).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SymbolRegistry {
+    instruments: Vec<Instrument>,
+    by_venue_code: BTreeMap<(String, String), usize>, // (venue, code) -> instrument
+    by_pair: BTreeMap<String, Vec<usize>>,            // Canonical pair -> one instrument per venue
+    asset_aliases: BTreeMap<String, String>,
+    venue_chains: BTreeMap<String, String>,
+}
+
+impl Default for SymbolRegistry {
+    fn default() -> Self {
+        Self {
+            instruments: Vec::new(),
+            by_venue_code: BTreeMap::new(),
+            by_pair: BTreeMap::new(),
+            asset_aliases: DEFAULT_ASSET_ALIASES.iter().map(|(a, c)| (a.to_string(), c.to_string())).collect(),
+            venue_chains: DEFAULT_VENUE_CHAINS.iter().map(|(v, c)| (v.to_string(), c.to_string())).collect(),
+        }
+    }
+}
+
+impl SymbolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry holding the default Uniswap pairs and their Ethereum token addresses
+    pub fn with_default_instruments() -> Self {
+        let mut registry = Self::new();
+        for (base, quote, code, address) in DEFAULT_DEX_INSTRUMENTS {
+            registry.register(Instrument::new(base, quote, "uniswap", code).with_address("ethereum", address));
+        }
+        registry
+    }
+
+    pub fn shared(self) -> SharedSymbolRegistry {
+        Arc::new(RwLock::new(self))
+    }
+
+    /// Registry from a JSON array of instruments
+    pub fn from_json(json: &str) -> Result<Self, SymbolError> {
+        let instruments: Vec<Instrument> =
+            serde_json::from_str(json).map_err(|e| SymbolError::Config(e.to_string()))?;
+        let mut registry = Self::new();
+        for instrument in instruments {
+            registry.register(instrument);
+        }
+        Ok(registry)
+    }
+
+    pub fn load_json(path: impl AsRef<Path>) -> Result<Self, SymbolError> {
+        let json = std::fs::read_to_string(path).map_err(|e| SymbolError::Config(e.to_string()))?;
+        Self::from_json(&json)
+    }
+
+    pub fn with_asset_alias(mut self, alias: &str, canonical: &str) -> Self {
+        self.asset_aliases.insert(alias.to_uppercase(), canonical.to_uppercase());
+        self
+    }
+
+    pub fn with_venue_chain(mut self, venue: &str, chain: &str) -> Self {
+        self.venue_chains.insert(venue.to_lowercase(), chain.to_lowercase());
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.instruments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instruments.is_empty()
+    }
+
+    /// Every instrument, in registration order
+    pub fn instruments(&self) -> &[Instrument] {
+        &self.instruments
+    }
+
+    /// Canonical pairs listed on `venue`, in registration order
+    pub fn pairs_on(&self, venue: &str) -> Vec<String> {
+        let venue = venue.to_lowercase();
+        self.instruments.iter().filter(|i| i.venue == venue).map(Instrument::pair).collect()
+    }
+
+    /// Adds `instrument`, replacing whatever the venue had under the same code or pair
+    pub fn register(&mut self, mut instrument: Instrument) {
+        instrument.base = self.canonical_asset(&instrument.base);
+        instrument.quote = self.canonical_asset(&instrument.quote);
+        instrument.venue = instrument.venue.to_lowercase();
+        let pair = instrument.pair();
+        let existing = self
+            .by_venue_code
+            .get(&(instrument.venue.clone(), instrument.venue_code.clone()))
+            .copied()
+            .or_else(|| {
+                self.by_pair
+                    .get(&pair)
+                    .and_then(|ids| ids.iter().copied().find(|&id| self.instruments[id].venue == instrument.venue))
+            });
+
+        let id = match existing {
+            Some(id) => {
+                let old = std::mem::replace(&mut self.instruments[id], instrument);
+                if let Some(ids) = self.by_pair.get_mut(&old.pair()) {
+                    ids.retain(|&other| other != id);
+                }
+                self.by_venue_code.remove(&(old.venue, old.venue_code));
+                id
+            }
+            None => {
+                self.instruments.push(instrument);
+                self.instruments.len() - 1
+            }
+        };
+        let instrument = &self.instruments[id];
+        self.by_venue_code.insert((instrument.venue.clone(), instrument.venue_code.clone()), id);
+        self.by_pair.entry(pair).or_default().push(id);
+    }
+
+    /// Ticker with aliases applied ("XBT" -> "BTC")
+    pub fn canonical_asset(&self, asset: &str) -> String {
+        let asset = asset.trim().to_uppercase();
+        self.asset_aliases.get(&asset).cloned().unwrap_or(asset)
+    }
+
+    /// "BASE/QUOTE" with aliases applied, from "weth/usdc", "XBT-USD" or "ETH_USDT"
+    pub fn canonical_pair(&self, pair: &str) -> Option<String> {
+        let (base, quote) = pair.split_once(['/', '-', '_'])?;
+        Some(format!("{}/{}", self.canonical_asset(base), self.canonical_asset(quote)))
+    }
+
+    pub fn from_venue_code(&self, venue: &str, code: &str) -> Result<&Instrument, SymbolError> {
+        self.by_venue_code
+            .get(&(venue.to_lowercase(), code.to_string()))
+            .map(|&id| &self.instruments[id])
+            .ok_or_else(|| SymbolError::Unknown(format!("{} on {}", code, venue)))
+    }
+
+    /// The instrument for `pair` on whichever venue lists it; Ambiguous when several do
+    pub fn from_pair_string(&self, pair: &str) -> Result<&Instrument, SymbolError> {
+        let ids = self.pair_ids(pair)?;
+        match ids {
+            [id] => Ok(&self.instruments[*id]),
+            _ => Err(SymbolError::Ambiguous {
+                pair: pair.to_string(),
+                venues: ids.iter().map(|&id| self.instruments[id].venue.clone()).collect(),
+            }),
+        }
+    }
+
+    pub fn from_pair_on(&self, venue: &str, pair: &str) -> Result<&Instrument, SymbolError> {
+        let venue = venue.to_lowercase();
+        self.pair_ids(pair)?
+            .iter()
+            .map(|&id| &self.instruments[id])
+            .find(|instrument| instrument.venue == venue)
+            .ok_or_else(|| SymbolError::Unknown(format!("{} on {}", pair, venue)))
+    }
+
+    /// What `venue` calls `pair`
+    pub fn to_venue_code(&self, pair: &str, venue: &str) -> Result<&str, SymbolError> {
+        self.from_pair_on(venue, pair).map(|instrument| instrument.venue_code.as_str())
+    }
+
+    /// Contract address of `pair`'s base token on the chain `venue` trades. None for order-book
+    /// venues and for instruments registered without an address on that chain, which must be
+    /// validated as CEX instruments. A pair written with a contract address as its base
+    /// ("0xabc.../USDC") carries its own address.
+    pub fn token_address(&self, venue: &str, pair: &str) -> Result<Option<String>, SymbolError> {
+        if let Some(address) = pair.split_once('/').map(|(base, _)| base).filter(|base| is_contract_address(base)) {
+            return Ok(Some(address.to_string()));
+        }
+        let instrument = self.from_pair_on(venue, pair)?;
+        Ok(self
+            .venue_chains
+            .get(&instrument.venue)
+            .and_then(|chain| instrument.address_on(chain))
+            .map(str::to_string))
+    }
+
+    fn pair_ids(&self, pair: &str) -> Result<&[usize], SymbolError> {
+        self.canonical_pair(pair)
+            .and_then(|canonical| self.by_pair.get(&canonical))
+            .filter(|ids| !ids.is_empty())
+            .map(Vec::as_slice)
+            .ok_or_else(|| SymbolError::Unknown(pair.to_string()))
+    }
+}
+
+/// "0x" followed by hex digits
+fn is_contract_address(s: &str) -> bool {
+    s.strip_prefix("0x")
+        .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> SymbolRegistry {
+        let mut registry = SymbolRegistry::new();
+        registry.register(Instrument::new("BTC", "USDT", "kraken", "XBTUSDT"));
+        registry.register(Instrument::new("ETH", "USDC", "kraken", "ETHUSDC"));
+        registry.register(
+            Instrument::new("WETH", "USDC", "uniswap", "WETH-USDC")
+                .with_address("ethereum", "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+        );
+        registry.register(Instrument::new("PEPE", "USDT", "uniswap", "PEPE-USDT"));
+        registry
+    }
+
+    #[test]
+    fn test_round_trip_lookups() {
+        let registry = registry();
+        let btc = registry.from_venue_code("kraken", "XBTUSDT").unwrap();
+        assert_eq!(btc.pair(), "BTC/USDT");
+        assert_eq!(registry.to_venue_code(&btc.pair(), "kraken"), Ok("XBTUSDT"));
+        // Aliases resolve on the way in
+        assert_eq!(registry.to_venue_code("XBT/USDT", "Kraken"), Ok("XBTUSDT"));
+        assert_eq!(registry.from_pair_string("xbt-usdt").unwrap(), btc);
+
+        assert_eq!(
+            registry.to_venue_code("BTC/USDT", "binance"),
+            Err(SymbolError::Unknown("BTC/USDT on binance".to_string()))
+        );
+        assert!(matches!(registry.from_venue_code("kraken", "NOPE"), Err(SymbolError::Unknown(_))));
+        assert!(matches!(registry.from_pair_string("FOO/BAR"), Err(SymbolError::Unknown(_))));
+    }
+
+    #[test]
+    fn test_pair_on_several_venues_is_ambiguous() {
+        let registry = registry();
+        // WETH on uniswap and ETH on kraken are the same canonical pair
+        match registry.from_pair_string("ETH/USDC") {
+            Err(SymbolError::Ambiguous { venues, .. }) => assert_eq!(venues, vec!["kraken", "uniswap"]),
+            other => panic!("expected ambiguity, got {:?}", other),
+        }
+        assert_eq!(registry.to_venue_code("WETH/USDC", "uniswap"), Ok("WETH-USDC"));
+        assert_eq!(registry.to_venue_code("WETH/USDC", "kraken"), Ok("ETHUSDC"));
+
+        // Re-registering a venue's pair replaces it rather than adding a third match
+        let mut registry = registry;
+        registry.register(Instrument::new("ETH", "USDC", "kraken", "XETHUSDC"));
+        assert_eq!(registry.len(), 4);
+        assert_eq!(registry.to_venue_code("ETH/USDC", "kraken"), Ok("XETHUSDC"));
+        assert!(registry.from_venue_code("kraken", "ETHUSDC").is_err());
+    }
+
+    #[test]
+    fn test_token_address_is_never_fabricated() {
+        let registry = registry();
+        assert_eq!(
+            registry.token_address("uniswap", "WETH/USDC"),
+            Ok(Some("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2".to_string()))
+        );
+        // Listed without an address, on an order-book venue, or not listed: no address
+        assert_eq!(registry.token_address("uniswap", "PEPE/USDT"), Ok(None));
+        assert_eq!(registry.token_address("kraken", "ETH/USDC"), Ok(None));
+        assert!(matches!(registry.token_address("binance", "PEPE/USDT"), Err(SymbolError::Unknown(_))));
+        // Address pairs carry their own
+        assert_eq!(registry.token_address("uniswap", "0x1234/USDC"), Ok(Some("0x1234".to_string())));
+        assert!(matches!(registry.token_address("uniswap", "0xPEPE/USDC"), Err(SymbolError::Unknown(_))));
+    }
+
+    #[test]
+    fn test_registry_from_config() {
+        let json = r#"[
+            {"base": "link", "quote": "usd", "venue": "Kraken", "venue_code": "LINKUSD"},
+            {"base": "LINK", "quote": "USDC", "venue": "uniswap", "venue_code": "LINK-USDC",
+             "addresses": {"ethereum": "0x514910771af9ca656af840dff83e8264ecf986ca"}}
+        ]"#;
+        let registry = SymbolRegistry::from_json(json).unwrap();
+        assert_eq!(registry.to_venue_code("LINK/USD", "kraken"), Ok("LINKUSD"));
+        assert!(registry.token_address("uniswap", "LINK/USDC").unwrap().is_some());
+        assert!(matches!(SymbolRegistry::from_json("{}"), Err(SymbolError::Config(_))));
+    }
+}
@@ -0,0 +1,80 @@
+// Telemetry
+// Structured tracing output for the engine, array and strike lifecycle
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+static PRETTY_BANNERS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogFormat {
+    /// Human-readable console lines
+    Pretty,
+    /// One JSON object per event, with the enclosing span fields, for log aggregation
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    pub format: LogFormat,
+    pub pretty_banners: bool, // Box-drawing startup banners and cycle reports on stdout
+    pub filter: String,       // EnvFilter directives; RUST_LOG wins when set
+    pub log_span_close: bool, // Emit an event with timings whenever a span closes
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::Pretty,
+            pretty_banners: false,
+            filter: "info".to_string(),
+            log_span_close: false,
+        }
+    }
+}
+
+impl TelemetryConfig {
+    /// Reads LOG_FORMAT=json|pretty and PRETTY_BANNERS=1 over the defaults
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(format) = std::env::var("LOG_FORMAT") {
+            config.format = if format.eq_ignore_ascii_case("json") { LogFormat::Json } else { LogFormat::Pretty };
+        }
+        if let Ok(banners) = std::env::var("PRETTY_BANNERS") {
+            config.pretty_banners = matches!(banners.as_str(), "1" | "true" | "yes");
+        }
+        config
+    }
+}
+
+/// Install the global subscriber. `log` records from dependencies and downstream crates
+/// are forwarded into it, so they pick up the same format and span context.
+pub fn init(config: &TelemetryConfig) -> Result<(), Box<dyn std::error::Error>> {
+    set_pretty_banners(config.pretty_banners);
+    tracing_log::LogTracer::init()?;
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.filter));
+    let span_events = if config.log_span_close { FmtSpan::CLOSE } else { FmtSpan::NONE };
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(span_events);
+
+    match config.format {
+        LogFormat::Pretty => tracing::subscriber::set_global_default(builder.finish())?,
+        LogFormat::Json => tracing::subscriber::set_global_default(
+            builder.json().with_current_span(true).with_span_list(true).finish(),
+        )?,
+    }
+    Ok(())
+}
+
+/// Whether banner and report printing is switched on
+pub fn pretty_banners() -> bool {
+    PRETTY_BANNERS.load(Ordering::Relaxed)
+}
+
+pub fn set_pretty_banners(enabled: bool) {
+    PRETTY_BANNERS.store(enabled, Ordering::Relaxed);
+}
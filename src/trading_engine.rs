@@ -16,7 +16,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use std::collections::HashMap;
 use std::time::SystemTime;
-use log::{info, warn, error};
+use tracing::{info, warn, error};
 
 /// Integrated trading engine with all safety features
 pub struct TradingEngine {
@@ -84,12 +84,13 @@ impl TradingEngine {
     ) -> Self {
         let safety_config = SafetyConfig::default();
         let predictor_config = PredictorConfig::default();
+        let liquidity = LiquidityMonitor::new().with_sources(exchange.clone(), market_data.clone());
 
         Self {
             exchange,
             market_data,
             safety: Arc::new(SafetyMonitor::new(safety_config)),
-            liquidity: Arc::new(LiquidityMonitor::new()),
+            liquidity: Arc::new(liquidity),
             predictor: Arc::new(LiquidityPredictor::new(predictor_config)),
             monitoring: Arc::new(MonitoringSystem::new()),
             positions: Arc::new(RwLock::new(HashMap::new())),
@@ -99,7 +100,7 @@ impl TradingEngine {
 
     /// Execute a macro strike with all safety checks
     pub async fn execute_strike(&self, strike: &MacroStrike) -> Result<(), String> {
-        info!("Executing strike #{} on {}", strike.id, self.get_symbol_name(strike.symbol));
+        info!("Executing strike #{} on {}", strike.id, strike.symbol);
 
         // 1. CRITICAL: Enforce 90% win probability requirement
         if strike.confidence < MIN_WIN_PROBABILITY {
@@ -117,7 +118,7 @@ impl TradingEngine {
                 strike.confidence, self.config.min_confidence));
         }
 
-        let symbol = self.get_symbol_name(strike.symbol);
+        let symbol = strike.symbol.clone();
 
         // 2. Verify liquidity
         if !self.liquidity.verify_liquidity(&symbol).await.map_err(|e| e.to_string())? {
@@ -337,16 +338,6 @@ impl TradingEngine {
         Ok(())
     }
 
-    /// Get symbol name from ID
-    fn get_symbol_name(&self, symbol_id: u8) -> String {
-        match symbol_id {
-            0 => "BTC/USDT",
-            1 => "ETH/USDT",
-            2 => "SOL/USDT",
-            _ => "UNKNOWN",
-        }.to_string()
-    }
-
     /// Emergency stop all positions
     pub async fn emergency_stop(&self, reason: &str) -> Result<(), String> {
         error!("EMERGENCY STOP: {}", reason);
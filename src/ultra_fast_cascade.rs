@@ -5,7 +5,7 @@ use std::collections::{HashMap, VecDeque, BTreeMap};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc, Duration};
-use log::{info, debug, warn};
+use tracing::{info, debug, warn};
 
 /// Ultra-fast cascade detection with sub-second latency
 pub struct UltraFastCascadeDetector {
@@ -615,7 +615,7 @@ impl UltraFastCascadeDetector {
         
         // Calculate mention velocity across platforms
         let mut total_velocity = 0.0;
-        let mut max_platform_velocity = 0.0;
+        let mut max_platform_velocity: f64 = 0.0;
         let now = Utc::now();
         
         for (platform, stream) in &social_tracker.mention_streams {
@@ -453,12 +453,12 @@ impl UniversalExecutor {
         // If one leg succeeded, reverse it
         if let Some(buy) = buy_result {
             // Sell back what we bought
-            log::warn!("Unwinding failed arbitrage - selling back");
+            tracing::warn!("Unwinding failed arbitrage - selling back");
         }
         
         if let Some(sell) = sell_result {
             // Buy back what we sold
-            log::warn!("Unwinding failed arbitrage - buying back");
+            tracing::warn!("Unwinding failed arbitrage - buying back");
         }
         
         Ok(())
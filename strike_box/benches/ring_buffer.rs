@@ -0,0 +1,39 @@
+// Push-at-capacity cost: Vec::remove(0) eviction vs RingBuffer
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use strike_box::RingBuffer;
+
+fn push_at_capacity(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push_at_capacity");
+
+    for capacity in [1_000usize, 10_000, 100_000] {
+        group.bench_with_input(BenchmarkId::new("vec_remove_0", capacity), &capacity, |b, &capacity| {
+            let mut buffer: Vec<u64> = (0..capacity as u64).collect();
+            let mut next = capacity as u64;
+            b.iter(|| {
+                buffer.push(black_box(next));
+                if buffer.len() > capacity {
+                    buffer.remove(0);
+                }
+                next += 1;
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("ring_buffer", capacity), &capacity, |b, &capacity| {
+            let mut buffer = RingBuffer::new(capacity);
+            for i in 0..capacity as u64 {
+                buffer.push(i);
+            }
+            let mut next = capacity as u64;
+            b.iter(|| {
+                black_box(buffer.push(black_box(next)));
+                next += 1;
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, push_at_capacity);
+criterion_main!(benches);
@@ -0,0 +1,367 @@
+//! ============================================================
+//! OUTCOME ATTRIBUTION
+//! ============================================================
+//! Ties closed trades back to what the gates saw at entry, so
+//! thresholds can be tuned on realized results. Each entry keeps
+//! a DecisionRecord: its safety score, liquidity and, for every
+//! tunable threshold that applied, the margin by which it cleared
+//! it (share of the threshold value, 0 = exactly at the cutoff).
+//! When the trade's exits add up to its full size, its realized
+//! P&L is added to the stats for its safety decile, liquidity
+//! tier and each threshold's margin bucket.
+//!
+//! Rejected entries never trade. The threshold report counts
+//! near-miss rejections as unknown outcomes rather than guessing.
+//! ============================================================
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    Direction, ExitLog, LiquidityScaler, RingBuffer, SafetyScore, SnapshotSource, StrikeBoxEngine, ThresholdBound,
+    TokenSnapshot, GATE_THRESHOLDS,
+};
+
+/// Open decisions kept by default; past this the oldest is dropped unattributed
+pub const MAX_PENDING_DECISIONS: usize = 10_000;
+
+/// Upper edges of the margin buckets; a margin at or past the last edge falls in the final bucket
+pub const MARGIN_BUCKET_EDGES: [Decimal; 3] = [
+    Decimal::from_parts(5, 0, 0, false, 2),
+    Decimal::from_parts(10, 0, 0, false, 2),
+    Decimal::from_parts(25, 0, 0, false, 2),
+];
+/// Number of margin buckets
+pub const MARGIN_BUCKETS: usize = MARGIN_BUCKET_EDGES.len() + 1;
+
+/// What the gates saw of an entry, kept until the trade closes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub execution_id: Uuid,
+    pub direction: Direction,
+    pub safety_score: Decimal,
+    pub liquidity_usd: Decimal,
+    pub margins: BTreeMap<String, Decimal>, // By threshold config key
+    pub decided_at: DateTime<Utc>,
+    #[serde(default)]
+    pub realized_pnl_usd: Decimal, // Exits so far
+    #[serde(default)]
+    pub exited_size_pct: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeStats {
+    pub trades: u32,
+    pub wins: u32,
+    pub total_pnl_usd: Decimal,
+}
+
+impl OutcomeStats {
+    fn record(&mut self, pnl_usd: Decimal) {
+        self.trades += 1;
+        if pnl_usd > Decimal::ZERO {
+            self.wins += 1;
+        }
+        self.total_pnl_usd += pnl_usd;
+    }
+
+    fn merge(&mut self, other: &OutcomeStats) {
+        self.trades += other.trades;
+        self.wins += other.wins;
+        self.total_pnl_usd += other.total_pnl_usd;
+    }
+
+    pub fn win_rate(&self) -> Option<Decimal> {
+        (self.trades > 0).then(|| Decimal::from(self.wins) / Decimal::from(self.trades))
+    }
+
+    /// Average realized P&L per trade
+    pub fn expectancy_usd(&self) -> Option<Decimal> {
+        (self.trades > 0).then(|| self.total_pnl_usd / Decimal::from(self.trades))
+    }
+}
+
+/// Pending decisions and the closed-trade stats built from them. Serializable so it can be
+/// saved alongside other engine state and restored with `with_outcome_attribution`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutcomeAttribution {
+    pub pending: RingBuffer<DecisionRecord>, // Oldest decision first
+    pub by_safety_decile: BTreeMap<u8, OutcomeStats>, // 0 covers scores below 0.1, 9 covers 0.9 and up
+    pub by_liquidity_tier: BTreeMap<String, OutcomeStats>, // By LiquidityScaler tier floor, "below" under the last
+    pub by_margin: BTreeMap<String, [OutcomeStats; MARGIN_BUCKETS]>, // By threshold config key
+}
+
+impl Default for OutcomeAttribution {
+    fn default() -> Self {
+        Self::with_max_pending(MAX_PENDING_DECISIONS)
+    }
+}
+
+impl OutcomeAttribution {
+    pub fn with_max_pending(max_pending: usize) -> Self {
+        Self {
+            pending: RingBuffer::new(max_pending),
+            by_safety_decile: BTreeMap::new(),
+            by_liquidity_tier: BTreeMap::new(),
+            by_margin: BTreeMap::new(),
+        }
+    }
+
+    pub fn pending_decision(&self, execution_id: Uuid) -> Option<&DecisionRecord> {
+        self.pending.iter().find(|d| d.execution_id == execution_id)
+    }
+
+    /// Replaces any decision already pending for the same entry
+    fn add_pending(&mut self, decision: DecisionRecord) {
+        self.pending.remove_first(|d| d.execution_id == decision.execution_id);
+        if let Some(dropped) = self.pending.push(decision) {
+            warn!(execution_id = %dropped.execution_id, "pending decisions full, oldest dropped unattributed");
+        }
+    }
+
+    /// Adds an exit to its pending decision, closing it out once the exits cover the full size
+    fn apply_exit(&mut self, exit: &ExitLog) {
+        let Some(decision) = self.pending.iter_mut().find(|d| d.execution_id == exit.execution_id) else {
+            return;
+        };
+        decision.realized_pnl_usd += exit.realized_pnl_usd;
+        decision.exited_size_pct += exit.exit_size_pct;
+        if decision.exited_size_pct >= Decimal::ONE {
+            if let Some(decision) = self.pending.remove_first(|d| d.execution_id == exit.execution_id) {
+                self.record_outcome(&decision);
+            }
+        }
+    }
+
+    fn record_outcome(&mut self, decision: &DecisionRecord) {
+        let pnl = decision.realized_pnl_usd;
+        self.by_safety_decile.entry(safety_decile(decision.safety_score)).or_default().record(pnl);
+        self.by_liquidity_tier.entry(liquidity_tier(decision.liquidity_usd)).or_default().record(pnl);
+        for (key, margin) in &decision.margins {
+            self.by_margin.entry(key.clone()).or_default()[margin_bucket(*margin)].record(pnl);
+        }
+    }
+}
+
+pub fn safety_decile(score: Decimal) -> u8 {
+    let decile = (score * Decimal::TEN).floor().clamp(Decimal::ZERO, Decimal::from(9));
+    u8::try_from(decile).unwrap_or(0)
+}
+
+pub fn liquidity_tier(liquidity_usd: Decimal) -> String {
+    LiquidityScaler::tiers()
+        .into_iter()
+        .find(|(floor, _)| liquidity_usd >= *floor)
+        .map_or("below".to_string(), |(floor, _)| floor.to_string())
+}
+
+pub fn margin_bucket(margin: Decimal) -> usize {
+    MARGIN_BUCKET_EDGES.iter().position(|edge| margin < *edge).unwrap_or(MARGIN_BUCKET_EDGES.len())
+}
+
+/// How far `observed` sits on the passing side of `threshold`, as a share of the threshold.
+/// Negative when it is on the failing side.
+fn margin(observed: Decimal, threshold: Decimal, bound: ThresholdBound) -> Option<Decimal> {
+    if threshold.is_zero() {
+        return None;
+    }
+    let gap = match bound {
+        ThresholdBound::Min => observed - threshold,
+        ThresholdBound::Max => threshold - observed,
+    };
+    Some(gap / threshold.abs())
+}
+
+/// Realized results on either side of one threshold's cutoff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdOutcome {
+    pub config_key: String,
+    pub config_value: Option<Decimal>,
+    pub bound: ThresholdBound,
+    pub marginal_accepted: OutcomeStats, // Cleared by less than the first margin edge
+    pub comfortably_accepted: OutcomeStats,
+    pub near_miss_rejections: usize, // Failed by less than the first margin edge; never traded
+    pub marginal_losing: bool, // Marginal trades have negative expectancy
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdReport {
+    pub thresholds: Vec<ThresholdOutcome>, // Losing marginal trades first
+    pub by_safety_decile: BTreeMap<u8, OutcomeStats>,
+    pub by_liquidity_tier: BTreeMap<String, OutcomeStats>,
+    pub open_decisions: usize,
+}
+
+fn describe(stats: &OutcomeStats) -> String {
+    match (stats.win_rate(), stats.expectancy_usd()) {
+        (Some(win_rate), Some(expectancy)) => format!(
+            "{} trades, win {:.0}%, expectancy ${:.2}",
+            stats.trades,
+            win_rate * Decimal::ONE_HUNDRED,
+            expectancy
+        ),
+        _ => "no trades".to_string(),
+    }
+}
+
+impl StrikeBoxEngine {
+    /// Records what the gates saw of `token` for the entry `execution_id`, so its outcome is
+    /// attributed when it closes. `commit_entry` calls this; other booking paths should too.
+    pub fn record_decision(&mut self, token: &TokenSnapshot, direction: Direction, execution_id: Uuid) {
+        let safety = SafetyScore::calculate(token, self.safety_scoring(), &self.config.token_validation);
+        let margins = GATE_THRESHOLDS
+            .iter()
+            .filter_map(|(key, bound)| {
+                let observed = self.observed_for_threshold(token, direction, key, safety.total_score)?;
+                let margin = margin(observed, self.config.threshold(key)?, *bound)?;
+                Some((key.to_string(), margin))
+            })
+            .collect();
+        self.outcome_attribution.add_pending(DecisionRecord {
+            execution_id,
+            direction,
+            safety_score: safety.total_score,
+            liquidity_usd: token.liquidity_usd,
+            margins,
+            decided_at: Utc::now(),
+            realized_pnl_usd: Decimal::ZERO,
+            exited_size_pct: Decimal::ZERO,
+        });
+    }
+
+    /// The token's value a threshold is compared against, when that threshold applies to it
+    fn observed_for_threshold(
+        &self,
+        token: &TokenSnapshot,
+        direction: Direction,
+        key: &str,
+        safety_score: Decimal,
+    ) -> Option<Decimal> {
+        let dex = matches!(token.source, SnapshotSource::Dex);
+        match key {
+            "token_validation.liquidity_min_usd" | "token_validation.liquidity_max_usd" if dex => {
+                Some(token.liquidity_usd)
+            }
+            "token_validation.token_age_min_hours" if dex => Some(Decimal::from(token.token_age_hours)),
+            "token_validation.holder_count_min" if dex => Some(Decimal::from(token.holder_count)),
+            "token_validation.top_10_concentration_max_pct" if dex => Some(token.top_10_concentration_pct),
+            "token_validation.single_wallet_max_pct" if dex && direction == Direction::Short => {
+                Some(token.largest_wallet_pct)
+            }
+            "safety_scoring.long_entry_min" if dex && direction == Direction::Long => Some(safety_score),
+            "safety_scoring.short_entry_min" if dex && direction == Direction::Short => Some(safety_score),
+            "cex_validation.max_spread_bps" => match &token.source {
+                SnapshotSource::Cex { spread_bps, .. } => Some(*spread_bps),
+                SnapshotSource::Dex => None,
+            },
+            "cex_validation.min_side_depth_usd" if !dex => Some(token.bid_depth_usd.min(token.ask_depth_usd)),
+            "correlation.max_long_correlation" if direction == Direction::Long => {
+                self.max_long_correlation(&token.token_address).map(|(_, correlation)| correlation)
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn attribute_exit(&mut self, exit: &ExitLog) {
+        self.outcome_attribution.apply_exit(exit);
+    }
+
+    pub fn outcome_attribution(&self) -> &OutcomeAttribution {
+        &self.outcome_attribution
+    }
+
+    /// Restores attribution saved from an earlier run
+    pub fn with_outcome_attribution(mut self, attribution: OutcomeAttribution) -> Self {
+        self.outcome_attribution = attribution;
+        self
+    }
+
+    /// For each tunable threshold, closed trades that cleared it narrowly against those that
+    /// cleared it comfortably, and the retained rejections that narrowly failed it
+    pub fn threshold_report(&self) -> ThresholdReport {
+        let attribution = &self.outcome_attribution;
+        let edge = MARGIN_BUCKET_EDGES[0];
+        let mut thresholds: Vec<ThresholdOutcome> = GATE_THRESHOLDS
+            .iter()
+            .map(|(key, bound)| {
+                let config_value = self.config.threshold(key);
+                let buckets = attribution.by_margin.get(*key).copied().unwrap_or_default();
+                let marginal_accepted = buckets[0];
+                let mut comfortably_accepted = OutcomeStats::default();
+                for stats in &buckets[1..] {
+                    comfortably_accepted.merge(stats);
+                }
+                let near_miss_rejections = self
+                    .rejection_logs
+                    .iter()
+                    .filter(|r| r.config_key.as_deref() == Some(*key))
+                    .filter_map(|r| margin(r.observed_value?, config_value?, *bound))
+                    .filter(|margin| *margin < Decimal::ZERO && -*margin < edge)
+                    .count();
+                let marginal_losing = marginal_accepted.expectancy_usd().is_some_and(|e| e < Decimal::ZERO);
+
+                let mut summary = format!(
+                    "{} = {}: within {}% above the cutoff {}; further above {}; {} near-miss rejections, outcome unknown",
+                    key,
+                    config_value.map_or("unset".to_string(), |v| v.to_string()),
+                    edge * Decimal::ONE_HUNDRED,
+                    describe(&marginal_accepted),
+                    describe(&comfortably_accepted),
+                    near_miss_rejections
+                );
+                if marginal_losing {
+                    summary.push_str(" - MARGINAL TRADES LOSING MONEY");
+                }
+                ThresholdOutcome {
+                    config_key: key.to_string(),
+                    config_value,
+                    bound: *bound,
+                    marginal_accepted,
+                    comfortably_accepted,
+                    near_miss_rejections,
+                    marginal_losing,
+                    summary,
+                }
+            })
+            .collect();
+        thresholds.sort_by_key(|t| !t.marginal_losing);
+
+        ThresholdReport {
+            thresholds,
+            by_safety_decile: attribution.by_safety_decile.clone(),
+            by_liquidity_tier: attribution.by_liquidity_tier.clone(),
+            open_decisions: attribution.pending.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_margin_and_buckets() {
+        let min = ThresholdBound::Min;
+        assert_eq!(margin(Decimal::new(62, 2), Decimal::new(60, 2), min), Some(Decimal::new(2, 0) / Decimal::new(60, 0)));
+        assert_eq!(margin(Decimal::new(55, 2), Decimal::new(60, 2), min).map(|m| m < Decimal::ZERO), Some(true));
+        assert_eq!(margin(Decimal::new(40, 2), Decimal::new(60, 2), ThresholdBound::Max), Some(Decimal::new(20, 0) / Decimal::new(60, 0)));
+        assert_eq!(margin(Decimal::ONE, Decimal::ZERO, min), None);
+
+        assert_eq!(margin_bucket(Decimal::ZERO), 0);
+        assert_eq!(margin_bucket(Decimal::new(5, 2)), 1);
+        assert_eq!(margin_bucket(Decimal::new(24, 2)), 2);
+        assert_eq!(margin_bucket(Decimal::ONE), 3);
+
+        assert_eq!(safety_decile(Decimal::new(65, 2)), 6);
+        assert_eq!(safety_decile(Decimal::ONE), 9);
+        assert_eq!(safety_decile(Decimal::new(-1, 0)), 0);
+        assert_eq!(liquidity_tier(Decimal::new(800_000, 0)), "750000");
+        assert_eq!(liquidity_tier(Decimal::new(100_000, 0)), "below");
+    }
+}
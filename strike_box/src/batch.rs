@@ -0,0 +1,196 @@
+//! ============================================================
+//! BATCH ENTRY VALIDATION
+//! ============================================================
+//! Validates many scanner candidates in one call. The budgeted
+//! variant orders candidates by priority and stops before the one
+//! it predicts would overrun the time budget, so every validation
+//! it returns is complete. Candidates it never started come back in
+//! `unevaluated`, separate from rejections.
+//!
+//! The budget is spent in candidate cost, by default the time a
+//! candidate's gates took as each timed itself in RiskGateCheck;
+//! callers can pass their own cost source. A validation served from
+//! the cache costs nothing and leaves the prediction alone, since its
+//! gate timings belong to the run that filled the cache.
+//!
+//! The prediction is a moving average of one evaluated candidate's
+//! cost. It lives on the engine, so each batch starts from what
+//! earlier batches measured. Until a first candidate has been
+//! costed, the batch starts candidates as long as budget remains.
+//! ============================================================
+
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{Direction, RiskValidation, StrikeBoxEngine, TokenSnapshot};
+
+/// Weight of the newest candidate in the per-candidate cost average
+pub const BATCH_COST_ALPHA: f64 = 0.3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchCandidate {
+    pub token: TokenSnapshot,
+    pub direction: Direction,
+    pub volume_ratio: Decimal, // Spike volume over baseline, the default priority
+}
+
+impl BatchCandidate {
+    /// Default batch priority: strongest volume spike first
+    pub fn by_volume_ratio(a: &BatchCandidate, b: &BatchCandidate) -> Ordering {
+        b.volume_ratio.cmp(&a.volume_ratio)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchValidation {
+    pub evaluated: Vec<(BatchCandidate, RiskValidation)>, // In priority order
+    pub unevaluated: Vec<BatchCandidate>, // Not started within the budget; neither passed nor rejected
+    pub elapsed_ms: f64, // Wall time of the whole batch
+    #[serde(default)]
+    pub spent_ms: f64, // Budget charged: the evaluated candidates' costs, cache hits free
+    pub estimated_candidate_ms: Option<f64>, // Per-candidate cost prediction after this batch
+}
+
+impl BatchValidation {
+    pub fn passed(&self) -> impl Iterator<Item = &BatchCandidate> {
+        self.evaluated.iter().filter(|(_, validation)| validation.all_passed).map(|(candidate, _)| candidate)
+    }
+}
+
+impl StrikeBoxEngine {
+    /// Validates every candidate, strongest volume spike first
+    pub fn validate_entries_batch(&self, candidates: Vec<BatchCandidate>) -> BatchValidation {
+        self.validate_entries_batch_with_budget(candidates, Duration::MAX, BatchCandidate::by_volume_ratio)
+    }
+
+    /// Validates candidates in `priority` order until the next one is predicted to overrun
+    /// `budget` of gate time; the rest are returned unevaluated
+    pub fn validate_entries_batch_with_budget(
+        &self,
+        candidates: Vec<BatchCandidate>,
+        budget: Duration,
+        priority: impl Fn(&BatchCandidate, &BatchCandidate) -> Ordering,
+    ) -> BatchValidation {
+        self.validate_entries_batch_with_cost(candidates, budget, priority, RiskValidation::gate_elapsed_ms)
+    }
+
+    /// validate_entries_batch_with_budget charging each evaluated candidate `cost` milliseconds
+    pub fn validate_entries_batch_with_cost(
+        &self,
+        mut candidates: Vec<BatchCandidate>,
+        budget: Duration,
+        priority: impl Fn(&BatchCandidate, &BatchCandidate) -> Ordering,
+        cost: impl Fn(&RiskValidation) -> f64,
+    ) -> BatchValidation {
+        candidates.sort_by(|a, b| priority(a, b));
+        let started = Instant::now();
+        let budget_ms = budget.as_secs_f64() * 1_000.0;
+        let mut estimate = self.estimated_candidate_ms();
+        let mut spent_ms = 0.0;
+        let mut evaluated = Vec::with_capacity(candidates.len());
+        let mut unevaluated = Vec::new();
+        let mut remaining = candidates.into_iter();
+
+        for candidate in remaining.by_ref() {
+            if spent_ms >= budget_ms || spent_ms + estimate.unwrap_or(0.0) > budget_ms {
+                unevaluated.push(candidate);
+                break;
+            }
+            let (validation, cache_hit) = self.validate_entry_cached(&candidate.token, candidate.direction);
+            if !cache_hit {
+                let cost_ms = cost(&validation);
+                spent_ms += cost_ms;
+                estimate = Some(match estimate {
+                    Some(previous) => BATCH_COST_ALPHA * cost_ms + (1.0 - BATCH_COST_ALPHA) * previous,
+                    None => cost_ms,
+                });
+            }
+            evaluated.push((candidate, validation));
+        }
+        unevaluated.extend(remaining);
+
+        if let Ok(mut stored) = self.batch_candidate_cost_ms.lock() {
+            *stored = estimate;
+        }
+        BatchValidation {
+            evaluated,
+            unevaluated,
+            elapsed_ms: started.elapsed().as_secs_f64() * 1_000.0,
+            spent_ms,
+            estimated_candidate_ms: estimate,
+        }
+    }
+
+    /// Predicted cost of one candidate's validation, once a batch has costed one
+    pub fn estimated_candidate_ms(&self) -> Option<f64> {
+        self.batch_candidate_cost_ms.lock().ok().and_then(|estimate| *estimate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gates, StrikeBoxConfig};
+
+    fn candidate(index: i64) -> BatchCandidate {
+        let mut token = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::ONE).synthetic_token(0);
+        token.token_address = format!("0xbatch{:02}", index);
+        BatchCandidate { token, direction: Direction::Long, volume_ratio: Decimal::new(index, 1) }
+    }
+
+    #[test]
+    fn test_budget_evaluates_only_highest_priority_candidates() {
+        let engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let ten_ms = |_: &RiskValidation| 10.0;
+
+        // Volume ratios 2.0..=3.9, interleaved so input order isn't priority order
+        let candidates: Vec<BatchCandidate> = (0..20).map(|i| candidate(20 + (i * 7) % 20)).collect();
+        let batch = engine.validate_entries_batch_with_cost(
+            candidates,
+            Duration::from_millis(105),
+            BatchCandidate::by_volume_ratio,
+            ten_ms,
+        );
+
+        let evaluated: Vec<i64> = batch.evaluated.iter().map(|(c, _)| (c.volume_ratio * Decimal::TEN).try_into().unwrap()).collect();
+        assert_eq!(evaluated, (30..40).rev().collect::<Vec<i64>>());
+        assert_eq!(batch.unevaluated.len(), 10);
+        assert!(batch.unevaluated.iter().all(|c| c.volume_ratio < Decimal::new(30, 1)));
+        // Every returned validation ran to completion
+        assert!(batch.evaluated.iter().all(|(_, v)| !v.gates.is_empty()));
+        assert_eq!(batch.spent_ms, 100.0);
+        assert_eq!(engine.estimated_candidate_ms(), Some(10.0));
+
+        // With an estimate already on hand, a budget below one candidate starts none
+        let batch = engine.validate_entries_batch_with_cost(vec![candidate(1)], Duration::from_millis(5), BatchCandidate::by_volume_ratio, ten_ms);
+        assert!(batch.evaluated.is_empty());
+        assert_eq!(batch.unevaluated.len(), 1);
+    }
+
+    #[test]
+    fn test_default_cost_is_gate_time_and_cache_hits_are_free() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0))
+            .with_validation_cache(chrono::Duration::seconds(60), 64);
+        let slow_gate = |_: &gates::EvaluationContext<'_>| {
+            std::thread::sleep(Duration::from_millis(2));
+            gates::GateVerdict::Pass(None)
+        };
+        engine.gate_registry.register_gate("slow", i32::MIN, Box::new(slow_gate)).unwrap();
+
+        let candidates = vec![candidate(1), candidate(2)];
+        let batch = engine.validate_entries_batch_with_budget(candidates.clone(), Duration::MAX, BatchCandidate::by_volume_ratio);
+        let gate_ms: f64 = batch.evaluated.iter().map(|(_, v)| v.gate_elapsed_ms()).sum();
+        assert_eq!(batch.spent_ms, gate_ms);
+        assert!(gate_ms >= 4.0);
+        let estimate = engine.estimated_candidate_ms();
+
+        // Same candidates again: served from the cache, charged nothing, estimate untouched
+        let batch = engine.validate_entries_batch_with_cost(candidates, Duration::MAX, BatchCandidate::by_volume_ratio, |_| 1_000.0);
+        assert_eq!(batch.evaluated.len(), 2);
+        assert_eq!(batch.spent_ms, 0.0);
+        assert_eq!(engine.estimated_candidate_ms(), estimate);
+    }
+}
@@ -0,0 +1,209 @@
+//! ============================================================
+//! GATE LATENCY BENCHMARK
+//! ============================================================
+//! Runs the entry gates over synthetic snapshots and reports how
+//! long each gate took, from RiskGateCheck::elapsed_ms. Snapshots
+//! sweep liquidity, depth balance, holders, concentration and age
+//! from zero to twice their configured bounds along a Halton
+//! sequence, so a small sample still covers the space evenly. Every
+//! fourth snapshot is a CEX quote and every tenth is stale;
+//! directions alternate.
+//!
+//! Gates stop at the first failure, so gates late in the order see
+//! fewer calls. The benchmark runs against the engine as it stands:
+//! its config, registry and open positions.
+//! ============================================================
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{conv, Direction, SnapshotSource, StrikeBoxEngine, TokenSnapshot};
+
+/// Per-validation saving below which running gates in parallel would not pay for dispatching
+/// them to threads
+pub const PARALLEL_DISPATCH_COST_MS: f64 = 0.05;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateLatency {
+    pub gate_name: String,
+    pub calls_count: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub total_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateBenchmarkReport {
+    pub samples: usize,
+    pub gates: Vec<GateLatency>, // Slowest p95 first
+    pub bottleneck: Option<String>, // Most total time across samples
+    pub sequential_mean_ms: f64, // Mean per validation, gates one after another
+    pub parallel_mean_ms: f64, // Mean of each validation's slowest gate, had its gates run at once
+    pub parallelization_worthwhile: bool, // Saving exceeds PARALLEL_DISPATCH_COST_MS
+}
+
+impl std::fmt::Display for GateBenchmarkReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<24} {:>8} {:>10} {:>10} {:>10} {:>12}",
+            "gate", "calls", "p50 ms", "p95 ms", "p99 ms", "total ms"
+        )?;
+        for gate in &self.gates {
+            writeln!(
+                f,
+                "{:<24} {:>8} {:>10.4} {:>10.4} {:>10.4} {:>12.3}",
+                gate.gate_name, gate.calls_count, gate.p50_ms, gate.p95_ms, gate.p99_ms, gate.total_ms
+            )?;
+        }
+        writeln!(f, "bottleneck: {}", self.bottleneck.as_deref().unwrap_or("none"))?;
+        write!(
+            f,
+            "{} samples: sequential {:.4} ms, parallel {:.4} ms per validation; parallelizing {}",
+            self.samples,
+            self.sequential_mean_ms,
+            self.parallel_mean_ms,
+            if self.parallelization_worthwhile { "worthwhile" } else { "not worthwhile" }
+        )
+    }
+}
+
+/// Van der Corput radical inverse of `index` in `base`, in [0, 1)
+fn radical_inverse(mut index: usize, base: usize) -> f64 {
+    let mut inverse = 0.0;
+    let mut scale = 1.0 / base as f64;
+    while index > 0 {
+        inverse += (index % base) as f64 * scale;
+        index /= base;
+        scale /= base as f64;
+    }
+    inverse
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (pct * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl StrikeBoxEngine {
+    /// The `index`th synthetic snapshot of the benchmark sweep
+    pub fn synthetic_token(&self, index: usize) -> TokenSnapshot {
+        let tv = &self.config.token_validation;
+        let u = |base| conv::ratio_from_f64(radical_inverse(index + 1, base)).unwrap_or_default();
+        let two = Decimal::TWO;
+        let liquidity_usd = tv.liquidity_max_usd * two * u(2);
+        let bid_share = u(3);
+        let top_10_concentration_pct = u(7);
+        let now = Utc::now();
+        let stale = chrono::Duration::seconds(2 * self.config.risk_controller.data_feed_stale_seconds as i64);
+        let age_span = Decimal::from(2 * tv.token_age_preferred_long_hours.max(tv.token_age_preferred_short_hours));
+        let source = if index % 4 == 3 {
+            SnapshotSource::Cex {
+                venue: "synthetic".to_string(),
+                spread_bps: self.config.cex_validation.max_spread_bps * two * u(3),
+            }
+        } else {
+            SnapshotSource::Dex
+        };
+        TokenSnapshot {
+            token_address: format!("0xbench{:06}", index),
+            token_symbol: format!("BENCH{}", index),
+            liquidity_usd,
+            bid_depth_usd: liquidity_usd * bid_share,
+            ask_depth_usd: liquidity_usd * (Decimal::ONE - bid_share),
+            holder_count: u32::try_from((Decimal::from(2 * tv.holder_count_preferred) * u(5)).floor()).unwrap_or(0),
+            top_10_concentration_pct,
+            largest_wallet_pct: top_10_concentration_pct * u(11),
+            token_age_hours: u32::try_from((age_span * u(13)).floor()).unwrap_or(0),
+            contract_verified: !index.is_multiple_of(3),
+            is_proxy_contract: index.is_multiple_of(5),
+            deployment_timestamp: now,
+            snapshot_timestamp: if index % 10 == 9 { now - stale } else { now },
+            source,
+            categories: None,
+        }
+    }
+
+    /// Per-gate latency over `num_samples` synthetic snapshots. Gates run afresh, bypassing
+    /// the validation cache; nothing is recorded on the engine.
+    pub fn gate_benchmark(&self, num_samples: usize) -> GateBenchmarkReport {
+        let mut timings: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        let (mut sequential_ms, mut parallel_ms) = (0.0, 0.0);
+        for index in 0..num_samples {
+            let token = self.synthetic_token(index);
+            let direction = if index.is_multiple_of(2) { Direction::Long } else { Direction::Short };
+            let validation = self.run_entry_gates(&token, direction);
+            let mut slowest: f64 = 0.0;
+            for check in &validation.gates {
+                timings.entry(check.gate_name.clone()).or_default().push(check.elapsed_ms);
+                sequential_ms += check.elapsed_ms;
+                slowest = slowest.max(check.elapsed_ms);
+            }
+            parallel_ms += slowest;
+        }
+
+        let mut gates: Vec<GateLatency> = timings
+            .into_iter()
+            .map(|(gate_name, mut elapsed)| {
+                elapsed.sort_by(f64::total_cmp);
+                GateLatency {
+                    gate_name,
+                    calls_count: elapsed.len(),
+                    p50_ms: percentile(&elapsed, 0.50),
+                    p95_ms: percentile(&elapsed, 0.95),
+                    p99_ms: percentile(&elapsed, 0.99),
+                    total_ms: elapsed.iter().sum(),
+                }
+            })
+            .collect();
+        gates.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+        let bottleneck = gates
+            .iter()
+            .max_by(|a, b| a.total_ms.total_cmp(&b.total_ms))
+            .map(|g| g.gate_name.clone());
+
+        let samples = num_samples.max(1) as f64;
+        let sequential_mean_ms = sequential_ms / samples;
+        let parallel_mean_ms = parallel_ms / samples;
+        GateBenchmarkReport {
+            samples: num_samples,
+            gates,
+            bottleneck,
+            sequential_mean_ms,
+            parallel_mean_ms,
+            parallelization_worthwhile: sequential_mean_ms - parallel_mean_ms > PARALLEL_DISPATCH_COST_MS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StrikeBoxConfig;
+
+    #[test]
+    fn test_percentiles_and_sweep_coverage() {
+        let sorted: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!((percentile(&sorted, 0.50), percentile(&sorted, 0.95), percentile(&sorted, 0.99)), (50.0, 95.0, 99.0));
+        assert_eq!(percentile(&[], 0.5), 0.0);
+        assert_eq!((radical_inverse(1, 2), radical_inverse(3, 2), radical_inverse(2, 3)), (0.5, 0.75, 2.0 / 3.0));
+
+        let engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let tokens: Vec<TokenSnapshot> = (0..200).map(|i| engine.synthetic_token(i)).collect();
+        let tv = &engine.config.token_validation;
+        assert!(tokens.iter().any(|t| t.liquidity_usd < tv.liquidity_min_usd));
+        assert!(tokens.iter().any(|t| t.liquidity_usd > tv.liquidity_max_usd));
+        assert!(tokens.iter().any(|t| t.holder_count < tv.holder_count_min));
+        assert!(tokens.iter().any(|t| t.holder_count > tv.holder_count_preferred));
+        assert!(tokens.iter().any(|t| matches!(t.source, SnapshotSource::Cex { .. })));
+        assert!(tokens.iter().all(|t| t.largest_wallet_pct <= t.top_10_concentration_pct));
+    }
+}
@@ -0,0 +1,426 @@
+//! ============================================================
+//! UNIVERSE BOOTSTRAP
+//! ============================================================
+//! Saves the per-token history the engine builds up while running
+//! (price series for volatility-scaled stops, pair moments for the
+//! cross-correlation gate) and the holder trend series callers track
+//! alongside it, so a restart can start warm instead of waiting for
+//! fresh observations.
+//!
+//! The file is JSON with a schema version and one entry per
+//! section. Import takes what it can: a missing or unreadable
+//! section is skipped and reported, and the rest still loads. Price
+//! and holder series last observed more than `max_age` ago are
+//! dropped, along with every pair that involves a dropped token.
+//! Pairs carry no timestamps of their own, so a token with no price
+//! series is dated by the export time.
+//! ============================================================
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CorrelationTracker, PairMoments, PriceHistory, StopMode, StrikeBoxConfig, StrikeBoxEngine, PRICE_HISTORY_CAPACITY,
+};
+
+/// Bumped when a section's layout changes incompatibly
+pub const BOOTSTRAP_SCHEMA_VERSION: u32 = 1;
+/// Default for BootstrapConfig::max_age_hours
+pub const BOOTSTRAP_MAX_AGE_HOURS: i64 = 24;
+
+const PRICE_HISTORY_SECTION: &str = "price_history";
+const CORRELATIONS_SECTION: &str = "correlations";
+const HOLDER_HISTORY_SECTION: &str = "holder_history";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BootstrapConfig {
+    pub max_age_hours: i64, // Bootstrap history older than this is dropped on import
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        Self { max_age_hours: BOOTSTRAP_MAX_AGE_HOURS }
+    }
+}
+
+impl BootstrapConfig {
+    pub fn max_age(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.max_age_hours)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenPriceSeries {
+    pub token_address: String,
+    pub last_observed_at: DateTime<Utc>,
+    pub prices: Vec<Decimal>, // Oldest first
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairEntry {
+    pub token_a: String,
+    pub token_b: String,
+    pub moments: PairMoments,
+}
+
+/// One holder-distribution observation of a token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HolderPoint {
+    pub timestamp: DateTime<Utc>,
+    pub total_holders: u64,
+    pub whale_holders: u64,
+    pub new_holders: u64,
+    pub concentration: f64, // Top-10 share of circulating supply
+    pub top50_concentration: f64,
+    pub gini_coefficient: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenHolderSeries {
+    pub token_address: String,
+    pub points: Vec<HolderPoint>, // Oldest first
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CorrelationSection {
+    pub pairs: Vec<PairEntry>,
+    pub last_prices: BTreeMap<String, Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BootstrapFile {
+    schema_version: u32,
+    exported_at: DateTime<Utc>,
+    price_history: Vec<TokenPriceSeries>,
+    correlations: CorrelationSection,
+    holder_history: Vec<TokenHolderSeries>,
+}
+
+/// Removes and parses one section, noting it in the report when missing or invalid
+fn take_section<T: serde::de::DeserializeOwned>(
+    file: &mut serde_json::Map<String, serde_json::Value>,
+    name: &str,
+    report: &mut BootstrapImportReport,
+) -> Option<T> {
+    let reason = match file.remove(name).map(serde_json::from_value) {
+        Some(Ok(section)) => return Some(section),
+        Some(Err(e)) => e.to_string(),
+        None => "missing".to_string(),
+    };
+    report.skipped_sections.push((name.to_string(), reason));
+    None
+}
+
+/// What an import loaded and what it left out
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BootstrapImportReport {
+    pub schema_version: u32,
+    pub exported_at: Option<DateTime<Utc>>,
+    pub tokens_imported: usize,
+    pub pairs_imported: usize,
+    #[serde(default)]
+    pub holder_series_imported: usize,
+    pub stale_tokens_dropped: Vec<String>,
+    pub stale_pairs_dropped: usize,
+    #[serde(default)]
+    pub stale_holder_series_dropped: usize,
+    pub skipped_sections: Vec<(String, String)>, // Section and why
+}
+
+impl BootstrapImportReport {
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} tokens, {} pairs and {} holder series imported; {} stale tokens, {} stale pairs and {} stale holder series dropped",
+            self.tokens_imported,
+            self.pairs_imported,
+            self.holder_series_imported,
+            self.stale_tokens_dropped.len(),
+            self.stale_pairs_dropped,
+            self.stale_holder_series_dropped
+        );
+        for (section, reason) in &self.skipped_sections {
+            summary.push_str(&format!("; {} skipped: {}", section, reason));
+        }
+        summary
+    }
+}
+
+/// How much of the imported universe has enough history for history-based checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootstrapCoverage {
+    pub tokens: usize,
+    pub volatility_ready: Option<usize>, // Tokens with a full atr_window; None under SafetyTiered stops
+    pub correlated_pairs: usize, // Pairs with a defined correlation
+    #[serde(default)]
+    pub holder_trends: usize, // Tokens with two or more holder points, enough for a trend
+}
+
+/// Per-token history for constructing a warm engine and its holder trackers
+#[derive(Debug, Clone, Default)]
+pub struct UniverseBootstrap {
+    pub price_history: PriceHistory,
+    pub correlations: CorrelationTracker,
+    pub holder_history: BTreeMap<String, Vec<HolderPoint>>, // Token address -> points, oldest first
+}
+
+impl UniverseBootstrap {
+    /// Writes the engine's price history and correlation moments, with the caller's holder
+    /// series, to `path`
+    pub fn export(
+        engine: &StrikeBoxEngine,
+        holder_history: &BTreeMap<String, Vec<HolderPoint>>,
+        path: impl AsRef<Path>,
+    ) -> Result<(), String> {
+        let exported_at = Utc::now();
+        let history = &engine.price_history;
+        let price_history = history
+            .prices
+            .iter()
+            .map(|(token_address, prices)| TokenPriceSeries {
+                token_address: token_address.clone(),
+                last_observed_at: history.last_observed(token_address).unwrap_or(exported_at),
+                prices: prices.iter().copied().collect(),
+            })
+            .collect();
+        let correlations = CorrelationSection {
+            pairs: engine
+                .correlations
+                .pairs
+                .iter()
+                .map(|((token_a, token_b), moments)| PairEntry {
+                    token_a: token_a.clone(),
+                    token_b: token_b.clone(),
+                    moments: *moments,
+                })
+                .collect(),
+            last_prices: engine.correlations.last_prices.clone(),
+        };
+        let holder_history = holder_history
+            .iter()
+            .map(|(token_address, points)| TokenHolderSeries { token_address: token_address.clone(), points: points.clone() })
+            .collect();
+        let file = BootstrapFile {
+            schema_version: BOOTSTRAP_SCHEMA_VERSION,
+            exported_at,
+            price_history,
+            correlations,
+            holder_history,
+        };
+        let raw = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+        std::fs::write(path.as_ref(), raw).map_err(|e| e.to_string())
+    }
+
+    /// Reads a bootstrap file, dropping history last observed more than `max_age` ago. Fails
+    /// only when the file is unreadable or from a newer schema; bad sections are skipped.
+    pub fn import(path: impl AsRef<Path>, max_age: chrono::Duration) -> Result<(Self, BootstrapImportReport), String> {
+        let raw = std::fs::read_to_string(path.as_ref()).map_err(|e| e.to_string())?;
+        let mut file: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+        let schema_version = file
+            .get("schema_version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or("bootstrap file has no schema_version")? as u32;
+        if schema_version > BOOTSTRAP_SCHEMA_VERSION {
+            return Err(format!(
+                "bootstrap schema {} is newer than supported {}",
+                schema_version, BOOTSTRAP_SCHEMA_VERSION
+            ));
+        }
+        let exported_at: Option<DateTime<Utc>> =
+            file.remove("exported_at").and_then(|v| serde_json::from_value(v).ok());
+        let mut report = BootstrapImportReport { schema_version, exported_at, ..Default::default() };
+        let price_section: Option<Vec<TokenPriceSeries>> = take_section(&mut file, PRICE_HISTORY_SECTION, &mut report);
+        let correlation_section: Option<CorrelationSection> = take_section(&mut file, CORRELATIONS_SECTION, &mut report);
+        let holder_section: Option<Vec<TokenHolderSeries>> = take_section(&mut file, HOLDER_HISTORY_SECTION, &mut report);
+
+        let cutoff = Utc::now() - max_age;
+        let mut bootstrap = UniverseBootstrap::default();
+        let mut stale_tokens = Vec::new();
+        for series in price_section.unwrap_or_default() {
+            if series.last_observed_at < cutoff {
+                stale_tokens.push(series.token_address);
+                continue;
+            }
+            let skip = series.prices.len().saturating_sub(PRICE_HISTORY_CAPACITY);
+            let prices: VecDeque<Decimal> = series.prices.into_iter().skip(skip).filter(|p| *p > Decimal::ZERO).collect();
+            bootstrap.price_history.last_observed.insert(series.token_address.clone(), series.last_observed_at);
+            bootstrap.price_history.prices.insert(series.token_address, prices);
+        }
+
+        // Tokens without a price series are as old as the export
+        let export_stale = exported_at.is_none_or(|at| at < cutoff);
+        let fresh = |token: &str| match bootstrap.price_history.last_observed(token) {
+            Some(_) => true,
+            None => !export_stale && !stale_tokens.iter().any(|t| t == token),
+        };
+        let correlations = correlation_section.unwrap_or_default();
+        let mut tracker = CorrelationTracker::default();
+        for pair in correlations.pairs {
+            if fresh(&pair.token_a) && fresh(&pair.token_b) {
+                let ((first, second), _) = CorrelationTracker::key(&pair.token_a, &pair.token_b);
+                tracker.pairs.insert((first.to_string(), second.to_string()), pair.moments);
+            } else {
+                report.stale_pairs_dropped += 1;
+            }
+        }
+        tracker.last_prices = correlations.last_prices.into_iter().filter(|(token, _)| fresh(token)).collect();
+        bootstrap.correlations = tracker;
+
+        for series in holder_section.unwrap_or_default() {
+            match series.points.last() {
+                Some(last) if last.timestamp >= cutoff => {
+                    bootstrap.holder_history.insert(series.token_address, series.points);
+                }
+                _ => report.stale_holder_series_dropped += 1,
+            }
+        }
+
+        report.tokens_imported = bootstrap.price_history.token_count();
+        report.pairs_imported = bootstrap.correlations.pairs.len();
+        report.holder_series_imported = bootstrap.holder_history.len();
+        report.stale_tokens_dropped = stale_tokens;
+        Ok((bootstrap, report))
+    }
+
+    pub fn coverage(&self, config: &StrikeBoxConfig) -> BootstrapCoverage {
+        let volatility_ready = match config.stop_loss.mode {
+            StopMode::VolatilityScaled { atr_window, .. } => {
+                Some(self.price_history.prices.values().filter(|prices| prices.len() > atr_window).count())
+            }
+            StopMode::SafetyTiered => None,
+        };
+        BootstrapCoverage {
+            tokens: self.price_history.token_count(),
+            volatility_ready,
+            correlated_pairs: self.correlations.pairs.values().filter(|m| m.correlation().is_some()).count(),
+            holder_trends: self.holder_history.values().filter(|points| points.len() >= 2).count(),
+        }
+    }
+}
+
+impl StrikeBoxEngine {
+    /// Replaces the engine's price history and correlation moments with imported ones; the
+    /// holder series belong to the caller's trackers and are left out
+    pub fn install_bootstrap(&mut self, bootstrap: UniverseBootstrap) {
+        self.price_history = bootstrap.price_history;
+        self.correlations = bootstrap.correlations;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn warm_engine() -> StrikeBoxEngine {
+        let mut config = StrikeBoxConfig::default();
+        config.stop_loss.mode = StopMode::VolatilityScaled {
+            atr_multiplier: Decimal::TWO,
+            atr_window: 10,
+            min_pct: Decimal::new(5, 3),
+            max_pct: Decimal::new(9, 2),
+        };
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(100_000, 0));
+        for i in 0..12i64 {
+            let a = Decimal::new(100 + i % 3, 0);
+            let b = Decimal::new(200 + 2 * (i % 3), 0);
+            let c = Decimal::new(50 - i % 2, 0);
+            engine.observe_token("0xaaa", a);
+            engine.observe_token("0xbbb", b);
+            engine.observe_token("0xccc", c);
+            let prices = BTreeMap::from([("0xaaa".to_string(), a), ("0xbbb".to_string(), b), ("0xccc".to_string(), c)]);
+            engine.correlations.observe(&prices, &BTreeMap::new(), Decimal::new(1, 1));
+        }
+        engine
+    }
+
+    fn holder_history() -> BTreeMap<String, Vec<HolderPoint>> {
+        let point = |hours_ago: i64, total_holders: u64| HolderPoint {
+            timestamp: Utc::now() - chrono::Duration::hours(hours_ago),
+            total_holders,
+            whale_holders: 3,
+            new_holders: 0,
+            concentration: 0.4,
+            top50_concentration: 0.7,
+            gini_coefficient: 0.6,
+        };
+        BTreeMap::from([
+            ("0xaaa".to_string(), vec![point(3, 900), point(2, 950), point(1, 1_000)]),
+            ("0xbbb".to_string(), vec![point(1, 400)]),
+            ("0xddd".to_string(), vec![point(BOOTSTRAP_MAX_AGE_HOURS + 2, 50), point(BOOTSTRAP_MAX_AGE_HOURS + 1, 60)]),
+        ])
+    }
+
+    #[test]
+    fn test_bootstrap_round_trip_matches_warm_engine() {
+        let engine = warm_engine();
+        let path = std::env::temp_dir().join(format!("strike_box_bootstrap_{}.json", Uuid::new_v4()));
+        let holders = holder_history();
+        UniverseBootstrap::export(&engine, &holders, &path).unwrap();
+
+        let (bootstrap, report) = UniverseBootstrap::import(&path, BootstrapConfig::default().max_age()).unwrap();
+        assert_eq!((report.tokens_imported, report.pairs_imported, report.holder_series_imported), (3, 3, 2));
+        assert_eq!(report.stale_holder_series_dropped, 1);
+        assert!(report.skipped_sections.is_empty() && report.stale_tokens_dropped.is_empty());
+        let coverage = bootstrap.coverage(&engine.config);
+        assert_eq!(
+            coverage,
+            BootstrapCoverage { tokens: 3, volatility_ready: Some(3), correlated_pairs: 3, holder_trends: 1 }
+        );
+        assert_eq!(bootstrap.holder_history["0xaaa"], holders["0xaaa"]);
+
+        let mut cold = StrikeBoxEngine::new(engine.config.clone(), Decimal::new(100_000, 0));
+        cold.install_bootstrap(bootstrap);
+        for token in ["0xaaa", "0xbbb", "0xccc"] {
+            let entry = Decimal::new(100, 0);
+            let safety = Decimal::new(80, 2);
+            assert_eq!(cold.long_stop(token, entry, safety), engine.long_stop(token, entry, safety));
+        }
+        for (a, b) in [("0xaaa", "0xbbb"), ("0xbbb", "0xccc"), ("0xccc", "0xaaa")] {
+            assert_eq!(cold.correlations.correlation(a, b), engine.correlations.correlation(a, b));
+        }
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_bootstrap_drops_stale_history_and_skips_bad_sections() {
+        let engine = warm_engine();
+        let path = std::env::temp_dir().join(format!("strike_box_bootstrap_{}.json", Uuid::new_v4()));
+        UniverseBootstrap::export(&engine, &BTreeMap::new(), &path).unwrap();
+        let mut file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let stale_at = serde_json::to_value(Utc::now() - chrono::Duration::hours(BOOTSTRAP_MAX_AGE_HOURS + 1)).unwrap();
+        for series in file["price_history"].as_array_mut().unwrap() {
+            if series["token_address"] == "0xccc" {
+                series["last_observed_at"] = stale_at.clone();
+            }
+        }
+        std::fs::write(&path, file.to_string()).unwrap();
+
+        let max_age = chrono::Duration::hours(BOOTSTRAP_MAX_AGE_HOURS);
+        let (bootstrap, report) = UniverseBootstrap::import(&path, max_age).unwrap();
+        assert_eq!(report.stale_tokens_dropped, vec!["0xccc".to_string()]);
+        assert_eq!((report.tokens_imported, report.pairs_imported, report.stale_pairs_dropped), (2, 1, 2));
+        assert!(bootstrap.price_history.prices("0xccc").is_empty());
+        assert!(bootstrap.correlations.correlation("0xaaa", "0xbbb").is_some());
+        assert!(!bootstrap.correlations.last_prices.contains_key("0xccc"));
+
+        // A corrupted section is skipped and the rest still loads
+        file["correlations"] = serde_json::Value::String("corrupted".to_string());
+        std::fs::write(&path, file.to_string()).unwrap();
+        let (bootstrap, report) = UniverseBootstrap::import(&path, max_age).unwrap();
+        assert_eq!(report.skipped_sections.len(), 1);
+        assert_eq!(report.skipped_sections[0].0, CORRELATIONS_SECTION);
+        assert_eq!((report.tokens_imported, report.pairs_imported), (2, 0));
+        assert_eq!(bootstrap.price_history.prices("0xaaa").len(), 12);
+
+        // A newer schema is refused outright
+        file["schema_version"] = serde_json::json!(BOOTSTRAP_SCHEMA_VERSION + 1);
+        std::fs::write(&path, file.to_string()).unwrap();
+        assert!(UniverseBootstrap::import(&path, max_age).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,284 @@
+//! ============================================================
+//! EXECUTION FAILURE TAXONOMY
+//! ============================================================
+//! Failed executions are classified so that harmless misses do
+//! not pause trading the way a broken venue or a bad key should:
+//!
+//! - Transient (timeouts, rate limits) is retried with doubling
+//!   backoff until the class threshold, each execution's retry
+//!   budget; past it the failure counts toward
+//!   execution_failure_max.
+//! - MarketMoved (not filled at the limit) is counted only.
+//! - VenueError (5xx, maintenance) pauses entries on the venue
+//!   the failure was tagged with once the threshold is reached.
+//! - Fatal (auth, signing, insufficient funds) pauses all entries
+//!   on the first failure, until an operator Resume.
+//!
+//! Each class counts failures within its own decay window;
+//! older failures drop out of the count.
+//! ============================================================
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum FailureClass {
+    Transient,
+    MarketMoved,
+    VenueError,
+    Fatal,
+}
+
+impl FailureClass {
+    pub const ALL: [FailureClass; 4] =
+        [FailureClass::Transient, FailureClass::MarketMoved, FailureClass::VenueError, FailureClass::Fatal];
+}
+
+/// A failed execution, classified and tagged with the venue it was sent to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionFailure {
+    pub class: FailureClass,
+    pub venue: String, // As TokenSnapshot::venue reports it; matched case-insensitively
+    pub detail: String,
+    pub at: DateTime<Utc>,
+    #[serde(default)]
+    pub execution_id: Option<Uuid>, // Transient retries are budgeted per execution; None shares one budget
+}
+
+impl ExecutionFailure {
+    pub fn new(class: FailureClass, venue: &str, detail: impl Into<String>) -> Self {
+        Self { class, venue: venue.to_string(), detail: detail.into(), at: Utc::now(), execution_id: None }
+    }
+
+    pub fn for_execution(mut self, execution_id: Uuid) -> Self {
+        self.execution_id = Some(execution_id);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FailureClassPolicy {
+    pub threshold: u32, // Failures within the window that trigger the class's response
+    pub decay_window_seconds: u32,
+}
+
+impl FailureClassPolicy {
+    pub fn new(threshold: u32, decay_window_seconds: u32) -> Self {
+        Self { threshold, decay_window_seconds }
+    }
+
+    fn window(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.decay_window_seconds as i64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailurePolicyConfig {
+    pub transient: FailureClassPolicy, // Threshold is each execution's retry budget
+    pub market_moved: FailureClassPolicy, // Threshold only logs; never pauses
+    pub venue_error: FailureClassPolicy, // Counted per venue
+    pub fatal: FailureClassPolicy, // Counted only; the first fatal failure pauses
+    pub transient_backoff_ms: u64, // First retry's delay; doubles with each retry
+}
+
+impl Default for FailurePolicyConfig {
+    fn default() -> Self {
+        Self {
+            transient: FailureClassPolicy::new(3, 60),
+            market_moved: FailureClassPolicy::new(10, 300),
+            venue_error: FailureClassPolicy::new(2, 300),
+            fatal: FailureClassPolicy::new(1, 86_400),
+            transient_backoff_ms: 250,
+        }
+    }
+}
+
+impl FailurePolicyConfig {
+    pub fn for_class(&self, class: FailureClass) -> &FailureClassPolicy {
+        match class {
+            FailureClass::Transient => &self.transient,
+            FailureClass::MarketMoved => &self.market_moved,
+            FailureClass::VenueError => &self.venue_error,
+            FailureClass::Fatal => &self.fatal,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let errors: Vec<String> = FailureClass::ALL
+            .iter()
+            .filter(|class| {
+                let policy = self.for_class(**class);
+                policy.threshold == 0 || policy.decay_window_seconds == 0
+            })
+            .map(|class| format!("risk_controller.failure_policy {:?} threshold and decay window must be at least 1", class))
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// What the engine did about a recorded failure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailureResponse {
+    Retry { attempt: u32, backoff_ms: u64 },
+    RetryBudgetExhausted, // Counted toward execution_failure_max
+    Recorded,
+    VenuePaused { venue: String },
+    PausedAll, // Until an operator Resume
+}
+
+/// Failure counts within each class's decay window, for the Health report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureSummary {
+    pub counts: BTreeMap<FailureClass, u32>,
+    pub venue_errors: BTreeMap<String, u32>, // By lowercased venue
+    pub paused_venues: Vec<String>,
+}
+
+/// Recent failure times by class, by venue for venue errors, and by execution for transients
+#[derive(Debug, Clone, Default)]
+pub struct FailureTracker {
+    by_class: BTreeMap<FailureClass, VecDeque<DateTime<Utc>>>, // Oldest first
+    by_venue: BTreeMap<String, VecDeque<DateTime<Utc>>>, // Venue errors only
+    by_execution: BTreeMap<Uuid, VecDeque<DateTime<Utc>>>, // Transients tagged with an execution
+    paused_venues: BTreeSet<String>,
+}
+
+fn prune(times: &mut VecDeque<DateTime<Utc>>, window: chrono::Duration, now: DateTime<Utc>) {
+    while times.front().is_some_and(|at| now - *at >= window) {
+        times.pop_front();
+    }
+}
+
+fn count_within(times: &VecDeque<DateTime<Utc>>, window: chrono::Duration, now: DateTime<Utc>) -> u32 {
+    times.iter().filter(|at| now - **at < window).count() as u32
+}
+
+impl FailureTracker {
+    /// Records `failure` and returns how many of its class fall within the decay window,
+    /// counting venue errors for their venue only and tagged transients for their execution only
+    pub fn record(&mut self, failure: &ExecutionFailure, policy: &FailurePolicyConfig) -> u32 {
+        let window = policy.for_class(failure.class).window();
+        let class_times = self.by_class.entry(failure.class).or_default();
+        class_times.push_back(failure.at);
+        prune(class_times, window, failure.at);
+        let class_count = class_times.len() as u32;
+        match (failure.class, failure.execution_id) {
+            (FailureClass::VenueError, _) => {
+                let venue_times = self.by_venue.entry(failure.venue.to_lowercase()).or_default();
+                venue_times.push_back(failure.at);
+                prune(venue_times, window, failure.at);
+                venue_times.len() as u32
+            }
+            (FailureClass::Transient, Some(execution_id)) => {
+                // Drop executions whose attempts have all aged out, so the map stays bounded
+                self.by_execution.retain(|_, times| {
+                    prune(times, window, failure.at);
+                    !times.is_empty()
+                });
+                let attempts = self.by_execution.entry(execution_id).or_default();
+                attempts.push_back(failure.at);
+                attempts.len() as u32
+            }
+            _ => class_count,
+        }
+    }
+
+    /// Forgets `execution_id`'s transient attempts, once it has succeeded or given up
+    pub fn finish_execution(&mut self, execution_id: Uuid) {
+        self.by_execution.remove(&execution_id);
+    }
+
+    pub fn pause_venue(&mut self, venue: &str) {
+        self.paused_venues.insert(venue.to_lowercase());
+    }
+
+    pub fn is_venue_paused(&self, venue: &str) -> bool {
+        self.paused_venues.contains(&venue.to_lowercase())
+    }
+
+    pub fn has_paused_venues(&self) -> bool {
+        !self.paused_venues.is_empty()
+    }
+
+    /// Lifts every venue pause, returning the venues that were paused
+    pub fn resume_venues(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.paused_venues).into_iter().collect()
+    }
+
+    pub fn summary(&self, policy: &FailurePolicyConfig, now: DateTime<Utc>) -> FailureSummary {
+        let counts = FailureClass::ALL
+            .iter()
+            .map(|class| {
+                let count = self
+                    .by_class
+                    .get(class)
+                    .map(|times| count_within(times, policy.for_class(*class).window(), now))
+                    .unwrap_or(0);
+                (*class, count)
+            })
+            .collect();
+        let venue_errors = self
+            .by_venue
+            .iter()
+            .map(|(venue, times)| (venue.clone(), count_within(times, policy.venue_error.window(), now)))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        FailureSummary { counts, venue_errors, paused_venues: self.paused_venues.iter().cloned().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_decay_out_of_the_window() {
+        let policy = FailurePolicyConfig::default();
+        let mut tracker = FailureTracker::default();
+        let start = Utc::now();
+        let at = |seconds: i64, class: FailureClass, venue: &str| ExecutionFailure {
+            at: start + chrono::Duration::seconds(seconds),
+            ..ExecutionFailure::new(class, venue, "test")
+        };
+
+        assert_eq!(tracker.record(&at(0, FailureClass::Transient, "dex"), &policy), 1);
+        assert_eq!(tracker.record(&at(30, FailureClass::Transient, "dex"), &policy), 2);
+        // The first is 60s old, out of the transient window
+        assert_eq!(tracker.record(&at(60, FailureClass::Transient, "dex"), &policy), 2);
+
+        assert_eq!(tracker.record(&at(0, FailureClass::VenueError, "Kraken"), &policy), 1);
+        assert_eq!(tracker.record(&at(10, FailureClass::VenueError, "binance"), &policy), 1);
+        assert_eq!(tracker.record(&at(20, FailureClass::VenueError, "kraken"), &policy), 2);
+
+        let summary = tracker.summary(&policy, start + chrono::Duration::seconds(90));
+        assert_eq!(summary.counts[&FailureClass::Transient], 1);
+        assert_eq!(summary.counts[&FailureClass::VenueError], 3);
+        assert_eq!(summary.counts[&FailureClass::Fatal], 0);
+        assert_eq!(summary.venue_errors["kraken"], 2);
+    }
+
+    #[test]
+    fn test_transient_attempts_count_per_execution() {
+        let policy = FailurePolicyConfig::default();
+        let mut tracker = FailureTracker::default();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let timeout = |execution_id| ExecutionFailure::new(FailureClass::Transient, "dex", "timeout").for_execution(execution_id);
+
+        assert_eq!(tracker.record(&timeout(first), &policy), 1);
+        assert_eq!(tracker.record(&timeout(first), &policy), 2);
+        // Another execution starts its own budget, and untagged failures share the class count
+        assert_eq!(tracker.record(&timeout(second), &policy), 1);
+        assert_eq!(tracker.record(&ExecutionFailure::new(FailureClass::Transient, "dex", "timeout"), &policy), 4);
+
+        tracker.finish_execution(first);
+        assert_eq!(tracker.record(&timeout(first), &policy), 1);
+        assert_eq!(tracker.summary(&policy, Utc::now()).counts[&FailureClass::Transient], 5);
+    }
+}
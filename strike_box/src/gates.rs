@@ -0,0 +1,541 @@
+//! ============================================================
+//! ENTRY GATE REGISTRY
+//! ============================================================
+//! The entry gates validate_entry runs, by name and in order.
+//! Built-in gates register with hints 100, 200, ... in the order
+//! of BUILT_IN_GATES; a custom gate's hint places it among them
+//! (250 runs between data_staleness and liquidity_range). The
+//! first failing gate stops the run, a gate that does not apply
+//! to the snapshot or direction records nothing, and a disabled
+//! gate records Skipped without being evaluated.
+//! ============================================================
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::ActivityLevel;
+use crate::{
+    liquidation_distance_pct, Direction, GateResult, PositionBook, RiskValidation, SafetyScore, SnapshotSource,
+    StrikeBoxEngine, SystemState, TokenSnapshot,
+};
+
+/// What every gate, built-in or custom, sees of an entry being validated
+pub struct EvaluationContext<'a> {
+    pub engine: &'a StrikeBoxEngine,
+    pub token: &'a TokenSnapshot,
+    pub direction: Direction,
+    pub now: DateTime<Utc>, // The engine's evaluation time; gates read this, not the wall clock
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GateVerdict {
+    Pass(Option<String>), // With an optional note
+    Fail(String),
+    NotApplicable, // Records nothing, e.g. a DEX gate on a CEX snapshot
+}
+
+pub trait EntryGate: Send + Sync {
+    fn evaluate(&self, ctx: &EvaluationContext<'_>) -> GateVerdict;
+}
+
+impl<F> EntryGate for F
+where
+    F: Fn(&EvaluationContext<'_>) -> GateVerdict + Send + Sync,
+{
+    fn evaluate(&self, ctx: &EvaluationContext<'_>) -> GateVerdict {
+        self(ctx)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateRegistryError {
+    UnknownGate(String),
+    DuplicateGate(String),
+}
+
+impl std::fmt::Display for GateRegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GateRegistryError::UnknownGate(name) => write!(f, "No gate named {}", name),
+            GateRegistryError::DuplicateGate(name) => write!(f, "A gate named {} is already registered", name),
+        }
+    }
+}
+
+impl std::error::Error for GateRegistryError {}
+
+/// Built-in gates in their default order
+pub const BUILT_IN_GATES: [&str; 19] = [
+    "system_state",
+    "data_staleness",
+    "liquidity_range",
+    "safety_score",
+    "token_age",
+    "contract_verification",
+    "holder_distribution",
+    "cex_spread",
+    "cex_depth",
+    "cex_listing_age",
+    "book_capacity",
+    "no_stacking",
+    "cross_correlation",
+    "squeeze_risk",
+    "net_exposure",
+    "liquidation_distance",
+    "activity_schedule",
+    "venue_availability",
+    "borrow_availability",
+];
+
+/// Gates the recovery probe leaves out; it runs as if the system were active on fresh data
+pub(crate) const PROBE_EXCLUDED_GATES: [&str; 2] = ["system_state", "data_staleness"];
+
+struct RegisteredGate {
+    name: String,
+    order_hint: i32,
+    gate: Box<dyn EntryGate>,
+}
+
+pub struct GateRegistry {
+    gates: Vec<RegisteredGate>, // Run order, ascending by hint
+    disabled: HashSet<String>,
+}
+
+impl Default for GateRegistry {
+    fn default() -> Self {
+        let built_ins: [fn(&EvaluationContext<'_>) -> GateVerdict; 19] = [
+            system_state,
+            data_staleness,
+            liquidity_range,
+            safety_score,
+            token_age,
+            contract_verification,
+            holder_distribution,
+            cex_spread,
+            cex_depth,
+            cex_listing_age,
+            book_capacity,
+            no_stacking,
+            cross_correlation,
+            squeeze_risk,
+            net_exposure,
+            liquidation_distance,
+            activity_schedule,
+            venue_availability,
+            borrow_availability,
+        ];
+        let gates = BUILT_IN_GATES
+            .iter()
+            .zip(built_ins)
+            .enumerate()
+            .map(|(i, (name, gate))| RegisteredGate {
+                name: name.to_string(),
+                order_hint: (i as i32 + 1) * 100,
+                gate: Box::new(gate),
+            })
+            .collect();
+        Self { gates, disabled: HashSet::new() }
+    }
+}
+
+impl GateRegistry {
+    /// Adds a gate after every gate whose hint is at or below `order_hint`
+    pub fn register_gate(
+        &mut self,
+        name: impl Into<String>,
+        order_hint: i32,
+        gate: Box<dyn EntryGate>,
+    ) -> Result<(), GateRegistryError> {
+        let name = name.into();
+        if self.contains(&name) {
+            return Err(GateRegistryError::DuplicateGate(name));
+        }
+        let at = self.gates.partition_point(|g| g.order_hint <= order_hint);
+        self.gates.insert(at, RegisteredGate { name, order_hint, gate });
+        Ok(())
+    }
+
+    /// Runs `order` first, in that order, then the unnamed gates in their current order.
+    /// Hints are renumbered 100, 200, ... so later registrations place against the new order.
+    pub fn set_gate_order(&mut self, order: Vec<String>) -> Result<(), GateRegistryError> {
+        let mut seen = HashSet::new();
+        for name in &order {
+            if !self.contains(name) {
+                return Err(GateRegistryError::UnknownGate(name.clone()));
+            }
+            if !seen.insert(name.as_str()) {
+                return Err(GateRegistryError::DuplicateGate(name.clone()));
+            }
+        }
+        let mut remaining = std::mem::take(&mut self.gates);
+        for name in &order {
+            let at = remaining.iter().position(|g| g.name == *name).expect("checked above");
+            self.gates.push(remaining.remove(at));
+        }
+        self.gates.append(&mut remaining);
+        for (i, gate) in self.gates.iter_mut().enumerate() {
+            gate.order_hint = (i as i32 + 1) * 100;
+        }
+        Ok(())
+    }
+
+    /// Returns whether the gate was enabled before
+    pub fn disable_gate(&mut self, name: &str) -> Result<bool, GateRegistryError> {
+        if !self.contains(name) {
+            return Err(GateRegistryError::UnknownGate(name.to_string()));
+        }
+        Ok(self.disabled.insert(name.to_string()))
+    }
+
+    /// Returns whether the gate was disabled before
+    pub fn enable_gate(&mut self, name: &str) -> Result<bool, GateRegistryError> {
+        if !self.contains(name) {
+            return Err(GateRegistryError::UnknownGate(name.to_string()));
+        }
+        Ok(self.disabled.remove(name))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.gates.iter().any(|g| g.name == name)
+    }
+
+    pub fn is_disabled(&self, name: &str) -> bool {
+        self.disabled.contains(name)
+    }
+
+    /// Gate names in run order
+    pub fn gate_order(&self) -> Vec<&str> {
+        self.gates.iter().map(|g| g.name.as_str()).collect()
+    }
+
+    /// Evaluates the gates in order, except those named in `excluded`, into `validation`,
+    /// stopping at the first failure
+    pub(crate) fn run(&self, ctx: &EvaluationContext<'_>, excluded: &[&str], validation: &mut RiskValidation) {
+        for registered in self.gates.iter().filter(|g| !excluded.contains(&g.name.as_str())) {
+            if self.disabled.contains(&registered.name) {
+                validation.add_gate(&registered.name, GateResult::Skipped, Some("Gate disabled".to_string()));
+                continue;
+            }
+            let started = std::time::Instant::now();
+            let verdict = registered.gate.evaluate(ctx);
+            let elapsed_ms = started.elapsed().as_secs_f64() * 1_000.0;
+            match verdict {
+                GateVerdict::Pass(note) => {
+                    validation.add_timed_gate(&registered.name, GateResult::Passed, note, elapsed_ms)
+                }
+                GateVerdict::Fail(reason) => {
+                    validation.add_timed_gate(&registered.name, GateResult::Failed, Some(reason), elapsed_ms);
+                    return;
+                }
+                GateVerdict::NotApplicable => {}
+            }
+        }
+    }
+
+    /// Order and disabled set, for cache keys
+    pub(crate) fn fingerprint<H: std::hash::Hasher>(&self, hasher: &mut H) {
+        use std::hash::Hash;
+        for gate in &self.gates {
+            gate.name.hash(hasher);
+            self.disabled.contains(&gate.name).hash(hasher);
+        }
+    }
+}
+
+// Built-in gates
+
+fn system_state(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    let state = ctx.engine.portfolio.state;
+    match state {
+        SystemState::Active => GateVerdict::Pass(None),
+        SystemState::PausedLongs if ctx.direction == Direction::Short => GateVerdict::Pass(None),
+        SystemState::PausedShorts if ctx.direction == Direction::Long => GateVerdict::Pass(None),
+        SystemState::Recovering if ctx.engine.in_recovery_probation() => {
+            GateVerdict::Pass(Some("Recovery entry".to_string()))
+        }
+        _ => GateVerdict::Fail(format!("System state {:?} blocks {:?} entries", state, ctx.direction)),
+    }
+}
+
+fn data_staleness(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    let max_age = chrono::Duration::seconds(ctx.engine.config.risk_controller.data_feed_stale_seconds as i64);
+    let age = ctx.now - ctx.token.snapshot_timestamp;
+    if age > max_age {
+        return GateVerdict::Fail(format!(
+            "Snapshot is {}s old, limit {}s",
+            age.num_seconds(),
+            max_age.num_seconds()
+        ));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn liquidity_range(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if !matches!(ctx.token.source, SnapshotSource::Dex) {
+        return GateVerdict::NotApplicable;
+    }
+    let tv = &ctx.engine.config.token_validation;
+    if !ctx.token.liquidity_in_range(tv) {
+        return GateVerdict::Fail(format!(
+            "Liquidity ${} outside ${}-${} range",
+            ctx.token.liquidity_usd, tv.liquidity_min_usd, tv.liquidity_max_usd
+        ));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn safety_score(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if !matches!(ctx.token.source, SnapshotSource::Dex) {
+        return GateVerdict::NotApplicable;
+    }
+    let scoring = ctx.engine.safety_scoring();
+    let safety = SafetyScore::calculate(ctx.token, scoring, &ctx.engine.config.token_validation);
+    let score_ok = match ctx.direction {
+        Direction::Long => safety.qualifies_for_long(scoring),
+        Direction::Short => safety.qualifies_for_short(scoring),
+    };
+    if !score_ok {
+        return GateVerdict::Fail(format!("Score {:.2} below {:?} threshold", safety.total_score, ctx.direction));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn token_age(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if !matches!(ctx.token.source, SnapshotSource::Dex) {
+        return GateVerdict::NotApplicable;
+    }
+    let min_hours = ctx.engine.config.token_validation.token_age_min_hours;
+    if ctx.token.token_age_hours < min_hours {
+        return GateVerdict::Fail(format!(
+            "Token age {}h below {}h minimum",
+            ctx.token.token_age_hours, min_hours
+        ));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn contract_verification(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if !matches!(ctx.token.source, SnapshotSource::Dex) {
+        return GateVerdict::NotApplicable;
+    }
+    let tv = &ctx.engine.config.token_validation;
+    if tv.require_verified_contract && !ctx.token.contract_verified {
+        return GateVerdict::Fail("Contract not verified".to_string());
+    }
+    if ctx.token.is_proxy_contract && !tv.proxy_allowed(&ctx.token.token_address) {
+        return GateVerdict::Fail("Proxy contract not on the exception list".to_string());
+    }
+    GateVerdict::Pass(None)
+}
+
+fn holder_distribution(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if !matches!(ctx.token.source, SnapshotSource::Dex) {
+        return GateVerdict::NotApplicable;
+    }
+    if !ctx.token.holder_distribution_valid(&ctx.engine.config.token_validation) {
+        return GateVerdict::Fail(format!(
+            "Holders {} or concentration {:.1}% fails requirements",
+            ctx.token.holder_count, ctx.token.top_10_concentration_pct
+        ));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn cex_spread(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    let SnapshotSource::Cex { venue, spread_bps } = &ctx.token.source else {
+        return GateVerdict::NotApplicable;
+    };
+    let max_spread_bps = ctx.engine.config.cex_validation.max_spread_bps;
+    if *spread_bps > max_spread_bps {
+        return GateVerdict::Fail(format!(
+            "CEX spread {:.1}bps on {} above {}bps maximum",
+            spread_bps, venue, max_spread_bps
+        ));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn cex_depth(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    let SnapshotSource::Cex { venue, .. } = &ctx.token.source else {
+        return GateVerdict::NotApplicable;
+    };
+    let token = ctx.token;
+    let min_side_depth_usd = ctx.engine.config.cex_validation.min_side_depth_usd;
+    if token.bid_depth_usd.min(token.ask_depth_usd) < min_side_depth_usd {
+        return GateVerdict::Fail(format!(
+            "CEX depth ${:.0} bid / ${:.0} ask on {} below ${} per side",
+            token.bid_depth_usd, token.ask_depth_usd, venue, min_side_depth_usd
+        ));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn cex_listing_age(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    let SnapshotSource::Cex { venue, .. } = &ctx.token.source else {
+        return GateVerdict::NotApplicable;
+    };
+    let cex = &ctx.engine.config.cex_validation;
+    if cex.listings.is_empty() {
+        tracing::warn!("No CEX listings configured; {} on {} passes without a listing-age check", ctx.token.token_symbol, venue);
+        return GateVerdict::Pass(Some("listing registry not configured".to_string()));
+    }
+    let listing_age_hours = cex
+        .listings
+        .listed_at(venue, &ctx.token.token_symbol)
+        .map(|listed_at| (ctx.token.snapshot_timestamp - listed_at).num_hours());
+    match listing_age_hours {
+        None => GateVerdict::Fail(format!(
+            "CEX listing of {} on {} not in listing registry",
+            ctx.token.token_symbol, venue
+        )),
+        Some(hours) if hours < cex.listing_age_min_hours as i64 => GateVerdict::Fail(format!(
+            "CEX listing age {}h on {} below {}h minimum",
+            hours, venue, cex.listing_age_min_hours
+        )),
+        Some(_) => GateVerdict::Pass(None),
+    }
+}
+
+fn book<'a>(ctx: &EvaluationContext<'a>) -> &'a PositionBook {
+    match ctx.direction {
+        Direction::Long => &ctx.engine.portfolio.long_book,
+        Direction::Short => &ctx.engine.portfolio.short_book,
+    }
+}
+
+fn book_capacity(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    let book = book(ctx);
+    if book.slots_in_use() >= book.max_positions {
+        return GateVerdict::Fail(format!("{:?} book at max {} positions", ctx.direction, book.max_positions));
+    }
+    if book.is_over_capacity() {
+        return GateVerdict::Fail(format!(
+            "{:?} book allocation ${:.2} over limit ${:.2} - no new entries until under limit",
+            ctx.direction, book.total_allocation_usd, book.max_allocation_usd
+        ));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn no_stacking(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if book(ctx).has_position(&ctx.token.token_address) {
+        return GateVerdict::Fail("Position already exists for token".to_string());
+    }
+    GateVerdict::Pass(None)
+}
+
+// Correlated longs fall together, so a new long must not shadow one already held
+fn cross_correlation(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if ctx.direction != Direction::Long {
+        return GateVerdict::NotApplicable;
+    }
+    let max_correlation = ctx.engine.config.correlation.max_long_correlation;
+    if let Some((held, correlation)) = ctx.engine.max_long_correlation(&ctx.token.token_address) {
+        if correlation > max_correlation {
+            return GateVerdict::Fail(format!(
+                "Correlation {:.2} with open long {} above {}",
+                correlation, held, max_correlation
+            ));
+        }
+    }
+    GateVerdict::Pass(None)
+}
+
+// Wallet concentration only exists on-chain
+fn squeeze_risk(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if ctx.direction != Direction::Short || !ctx.token.has_onchain_data() {
+        return GateVerdict::NotApplicable;
+    }
+    if ctx.token.has_squeeze_risk(&ctx.engine.config.token_validation) {
+        return GateVerdict::Fail(format!(
+            "Largest wallet {:.1}% exceeds squeeze threshold",
+            ctx.token.largest_wallet_pct
+        ));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn net_exposure(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if !ctx.engine.net_exposure_valid() {
+        return GateVerdict::Fail("Net exposure outside bounds".to_string());
+    }
+    GateVerdict::Pass(None)
+}
+
+/// Never blocks; notes when the planned leverage liquidates the position before its stop
+fn liquidation_distance(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    let config = &ctx.engine.config;
+    let Some(liquidation_pct) =
+        liquidation_distance_pct(config.position_sizing.leverage, config.risk_controller.maintenance_margin_pct)
+    else {
+        return GateVerdict::NotApplicable;
+    };
+    // Stop distance as a share of entry, priced at an entry of 1
+    let stop_pct = match ctx.direction {
+        Direction::Long => {
+            let safety = SafetyScore::calculate(ctx.token, ctx.engine.safety_scoring(), &config.token_validation);
+            let (stop_price, _) = ctx.engine.long_stop(&ctx.token.token_address, Decimal::ONE, safety.total_score);
+            Decimal::ONE - stop_price
+        }
+        Direction::Short => config.stop_loss.short_stop_price(Decimal::ONE) - Decimal::ONE,
+    };
+    if stop_pct >= liquidation_pct {
+        return GateVerdict::Pass(Some(format!(
+            "Stop {:.2}% from entry is beyond liquidation at {:.2}% for {}x leverage",
+            stop_pct * Decimal::ONE_HUNDRED,
+            liquidation_pct * Decimal::ONE_HUNDRED,
+            config.position_sizing.leverage
+        )));
+    }
+    GateVerdict::Pass(None)
+}
+
+fn activity_schedule(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    let schedule = &ctx.engine.config.activity_schedule;
+    if schedule.grid(ctx.direction).is_empty() {
+        return GateVerdict::NotApplicable;
+    }
+    match schedule.level_at(ctx.direction, ctx.now) {
+        ActivityLevel::Normal => GateVerdict::Pass(None),
+        ActivityLevel::ReducedSize(factor) => GateVerdict::Pass(Some(format!("Size reduced to {} by activity schedule", factor))),
+        ActivityLevel::Blocked => GateVerdict::Fail(match schedule.next_change(ctx.direction, ctx.now) {
+            Some(at) => format!("{:?} entries blocked by activity schedule until {}", ctx.direction, at.to_rfc3339()),
+            None => format!("{:?} entries blocked by activity schedule", ctx.direction),
+        }),
+    }
+}
+
+fn venue_availability(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if !ctx.engine.failure_tracker.has_paused_venues() {
+        return GateVerdict::NotApplicable;
+    }
+    if ctx.engine.is_venue_paused(ctx.token.venue()) {
+        return GateVerdict::Fail(format!("Entries on {} paused after repeated venue errors", ctx.token.venue()));
+    }
+    GateVerdict::Pass(None)
+}
+
+// Only with a borrow provider configured; the quote comes from refresh_borrow_quote
+fn borrow_availability(ctx: &EvaluationContext<'_>) -> GateVerdict {
+    if ctx.direction != Direction::Short || !ctx.engine.has_borrow_provider() {
+        return GateVerdict::NotApplicable;
+    }
+    let Some(quote) = ctx.engine.fresh_borrow_quote(&ctx.token.token_address, ctx.now) else {
+        return GateVerdict::Fail("No fresh borrow quote for the token".to_string());
+    };
+    let required_usd = ctx.engine.calculate_position_size(ctx.token, ctx.direction)
+        * (Decimal::ONE + ctx.engine.config.risk_controller.short_borrow_buffer_pct);
+    if quote.info.available_usd < required_usd {
+        return GateVerdict::Fail(format!(
+            "Borrow available ${:.2} below ${:.2} needed",
+            quote.info.available_usd, required_usd
+        ));
+    }
+    let short_max_hours = ctx.engine.config.time_control.short_max_hours;
+    GateVerdict::Pass((quote.info.max_duration_hours < short_max_hours).then(|| {
+        format!("Borrow limited to {}h, short time stop assumes {}h", quote.info.max_duration_hours, short_max_hours)
+    }))
+}
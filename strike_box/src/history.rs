@@ -0,0 +1,231 @@
+//! ============================================================
+//! PORTFOLIO HISTORY
+//! ============================================================
+//! Compact equity and exposure samples for charting. The engine
+//! takes one sample per `sample(now)` call once the configured
+//! interval has passed (tick calls it with the wall clock), so
+//! the cadence is whatever the caller drives.
+//!
+//! Memory: samples live in a RingBuffer of `max_samples`; a sample
+//! is under 100 bytes, so the default week of one-minute samples
+//! stays below 1 MB. Older samples are dropped, or handed to the
+//! audit sink when `spill_evicted` is set.
+//! ============================================================
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{PortfolioState, RingBuffer};
+
+/// Cadence and retention of the sampled portfolio history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioHistoryConfig {
+    pub sample_interval_seconds: i64,
+    pub max_samples: usize,
+    #[serde(default)]
+    pub spill_evicted: bool, // Hand samples dropped for space to the audit sink
+}
+
+impl Default for PortfolioHistoryConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval_seconds: 60,
+            max_samples: 10_080,
+            spill_evicted: false,
+        }
+    }
+}
+
+/// Portfolio equity and exposure at one instant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioSample {
+    pub ts: DateTime<Utc>,
+    pub equity: Decimal,
+    pub gross_exposure_pct: Decimal,
+    pub net_exposure_pct: Decimal,
+    pub open_longs: u32,
+    pub open_shorts: u32,
+    pub daily_drawdown_pct: Decimal,
+}
+
+impl PortfolioSample {
+    pub fn from_portfolio(portfolio: &PortfolioState, ts: DateTime<Utc>) -> Self {
+        let open = |positions: &[crate::Position]| {
+            positions.iter().filter(|p| p.is_open()).count() as u32
+        };
+        Self {
+            ts,
+            equity: portfolio.equity_usd(),
+            gross_exposure_pct: portfolio.gross_exposure_pct,
+            net_exposure_pct: portfolio.net_exposure_pct,
+            open_longs: open(&portfolio.long_book.positions),
+            open_shorts: open(&portfolio.short_book.positions),
+            daily_drawdown_pct: portfolio.daily_drawdown_pct,
+        }
+    }
+}
+
+/// Equity over one bucket of a downsampled curve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityCandle {
+    pub bucket_start: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub samples: usize,
+}
+
+/// At least a second; an interval chrono can't hold never comes due
+fn sample_interval(config: &PortfolioHistoryConfig) -> chrono::Duration {
+    chrono::Duration::try_seconds(config.sample_interval_seconds.max(1)).unwrap_or(chrono::Duration::MAX)
+}
+
+/// Sampled portfolio history, oldest first
+#[derive(Debug, Clone)]
+pub struct PortfolioHistory {
+    samples: RingBuffer<PortfolioSample>,
+    sample_interval: chrono::Duration,
+}
+
+impl PortfolioHistory {
+    pub fn new(config: &PortfolioHistoryConfig) -> Self {
+        Self {
+            samples: RingBuffer::new(config.max_samples),
+            sample_interval: sample_interval(config),
+        }
+    }
+
+    /// Applies a new cadence and capacity, returning the oldest samples evicted to fit
+    pub fn reconfigure(&mut self, config: &PortfolioHistoryConfig) -> Vec<PortfolioSample> {
+        self.sample_interval = sample_interval(config);
+        self.samples.set_capacity(config.max_samples)
+    }
+
+    /// True when no sample has been taken yet or the interval has passed since the last
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.samples.last().is_none_or(|last| now - last.ts >= self.sample_interval)
+    }
+
+    /// Appends a sample, returning the oldest one when the buffer was full
+    pub fn record(&mut self, sample: PortfolioSample) -> Option<PortfolioSample> {
+        self.samples.push(sample)
+    }
+
+    pub fn samples(&self) -> &RingBuffer<PortfolioSample> {
+        &self.samples
+    }
+
+    /// Sampled equity with its timestamps, oldest first, for drawdown history
+    pub fn equity_points(&self) -> Vec<(DateTime<Utc>, Decimal)> {
+        self.samples.iter().map(|sample| (sample.ts, sample.equity)).collect()
+    }
+
+    /// Equity between `from` and `to` (inclusive) in buckets of `resolution`, aligned to the
+    /// Unix epoch so repeated queries chart consistently. Buckets without samples are omitted.
+    pub fn equity_curve(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        resolution: chrono::Duration,
+    ) -> Vec<EquityCandle> {
+        let width = resolution.num_seconds().max(1);
+        let mut candles: Vec<EquityCandle> = Vec::new();
+        for sample in self.samples.iter().filter(|s| s.ts >= from && s.ts <= to) {
+            let bucket = sample.ts.timestamp().div_euclid(width) * width;
+            let bucket_start = DateTime::from_timestamp(bucket, 0).unwrap_or(sample.ts);
+            match candles.last_mut() {
+                Some(candle) if candle.bucket_start == bucket_start => {
+                    candle.high = candle.high.max(sample.equity);
+                    candle.low = candle.low.min(sample.equity);
+                    candle.close = sample.equity;
+                    candle.samples += 1;
+                }
+                _ => candles.push(EquityCandle {
+                    bucket_start,
+                    open: sample.equity,
+                    high: sample.equity,
+                    low: sample.equity,
+                    close: sample.equity,
+                    samples: 1,
+                }),
+            }
+        }
+        candles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample_at(ts: DateTime<Utc>, equity: i64) -> PortfolioSample {
+        PortfolioSample {
+            ts,
+            equity: Decimal::new(equity, 0),
+            gross_exposure_pct: Decimal::ZERO,
+            net_exposure_pct: Decimal::ZERO,
+            open_longs: 0,
+            open_shorts: 0,
+            daily_drawdown_pct: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_equity_curve_downsamples_uneven_samples() {
+        let mut history = PortfolioHistory::new(&PortfolioHistoryConfig::default());
+        let base = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        // Three samples in the first hour, none in the second, two in the third
+        for (minutes, equity) in [(0, 100), (7, 120), (55, 90), (130, 95), (179, 110)] {
+            history.record(sample_at(base + chrono::Duration::minutes(minutes), equity));
+        }
+
+        let curve = history.equity_curve(base, base + chrono::Duration::hours(3), chrono::Duration::hours(1));
+        assert_eq!(curve.len(), 2);
+        let first = &curve[0];
+        assert_eq!(first.bucket_start, base);
+        assert_eq!(
+            (first.open, first.high, first.low, first.close, first.samples),
+            (Decimal::new(100, 0), Decimal::new(120, 0), Decimal::new(90, 0), Decimal::new(90, 0), 3)
+        );
+        let last = &curve[1];
+        assert_eq!(last.bucket_start, base + chrono::Duration::hours(2));
+        assert_eq!((last.open, last.close, last.samples), (Decimal::new(95, 0), Decimal::new(110, 0), 2));
+
+        // The window trims samples before bucketing
+        let trimmed = history.equity_curve(
+            base + chrono::Duration::minutes(5),
+            base + chrono::Duration::minutes(60),
+            chrono::Duration::hours(1),
+        );
+        assert_eq!(trimmed.len(), 1);
+        assert_eq!((trimmed[0].open, trimmed[0].samples), (Decimal::new(120, 0), 2));
+    }
+
+    #[test]
+    fn test_history_cadence_and_retention_eviction() {
+        let config = PortfolioHistoryConfig { sample_interval_seconds: 60, max_samples: 3, spill_evicted: true };
+        let mut history = PortfolioHistory::new(&config);
+        let base = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        assert!(history.is_due(base));
+
+        let mut evicted = Vec::new();
+        for i in 0..5 {
+            let now = base + chrono::Duration::minutes(i);
+            assert!(history.is_due(now));
+            evicted.extend(history.record(sample_at(now, 100 + i)));
+            assert!(!history.is_due(now + chrono::Duration::seconds(59)));
+        }
+
+        assert_eq!(history.samples().len(), 3);
+        assert_eq!(history.samples().evicted_count(), 2);
+        assert_eq!(evicted.iter().map(|s| s.equity).collect::<Vec<_>>(), vec![Decimal::new(100, 0), Decimal::new(101, 0)]);
+        assert_eq!(history.samples().first().map(|s| s.equity), Some(Decimal::new(102, 0)));
+
+        let shrunk = history.reconfigure(&PortfolioHistoryConfig { max_samples: 1, ..config });
+        assert_eq!(shrunk.len(), 2);
+        assert_eq!(history.samples().last().map(|s| s.equity), Some(Decimal::new(104, 0)));
+    }
+}
@@ -7,11 +7,31 @@
 //! Deployment: Binary Terminal Execution
 //! ============================================================
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, error, info, info_span, warn};
 use uuid::Uuid;
 
+pub mod attribution;
+pub mod batch;
+pub mod benchmark;
+pub mod bootstrap;
+pub mod conv;
+pub mod failures;
+pub mod gates;
+pub mod history;
+pub mod rescore;
+pub mod schedule;
+pub mod stress;
+pub mod trades;
+
 // ============================================================
 // SECTION 1: CORE ENUMERATIONS
 // ============================================================
@@ -32,6 +52,14 @@ pub enum ExitType {
     Manual,
     Emergency,
     SqueezeProtection,
+    Liquidation, // Force-closed by the venue
+}
+
+impl ExitType {
+    /// Filled at a take-profit level by a resting order
+    pub fn is_take_profit(self) -> bool {
+        matches!(self, Self::TakeProfit1 | Self::TakeProfit2 | Self::TakeProfit3)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -50,6 +78,7 @@ pub enum SystemState {
     PausedAll,
     EmergencyHalt,
     Recovering,
+    SoftHalt, // Entries paused on unrealized drawdown; clears by itself when marks recover
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -68,6 +97,13 @@ pub enum GateResult {
     Passed,
     Failed,
     Timeout,
+    Skipped, // Disabled in the gate registry; not evaluated
+}
+
+impl GateResult {
+    pub fn blocks_entry(self) -> bool {
+        matches!(self, GateResult::Failed | GateResult::Timeout)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -96,6 +132,8 @@ pub struct TokenValidationConfig {
     pub token_age_preferred_short_hours: u32,
     pub require_verified_contract: bool,
     pub reject_proxy_contracts: bool,
+    #[serde(default)]
+    pub proxy_contract_exceptions: HashSet<String>, // Lowercased addresses of known safe proxies
 }
 
 impl Default for TokenValidationConfig {
@@ -113,10 +151,69 @@ impl Default for TokenValidationConfig {
             token_age_preferred_short_hours: 48,
             require_verified_contract: true,
             reject_proxy_contracts: false,
+            proxy_contract_exceptions: HashSet::new(),
+        }
+    }
+}
+
+impl TokenValidationConfig {
+    /// Whether a proxy contract at `token_address` may be entered
+    pub fn proxy_allowed(&self, token_address: &str) -> bool {
+        !self.reject_proxy_contracts
+            || self.proxy_contract_exceptions.contains(&token_address.to_lowercase())
+    }
+}
+
+/// Gates for centralized-venue pairs, which have no holder, contract or deployment data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CexValidationConfig {
+    pub max_spread_bps: Decimal,
+    pub min_side_depth_usd: Decimal, // Each side, within ORDER_BOOK_DEPTH_BAND_PCT of mid
+    pub listing_age_min_hours: u32,
+    #[serde(default)]
+    pub listings: ListingRegistry, // Empty skips the listing-age check with a warning
+}
+
+impl Default for CexValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_spread_bps: Decimal::new(50, 0),
+            min_side_depth_usd: Decimal::new(100_000, 0),
+            listing_age_min_hours: 168,
+            listings: ListingRegistry::default(),
         }
     }
 }
 
+/// When each pair was listed on each venue
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListingRegistry {
+    listings: BTreeMap<String, DateTime<Utc>>, // Keyed by "venue:symbol"
+}
+
+impl ListingRegistry {
+    pub fn with_listing(mut self, venue: &str, symbol: &str, listed_at: DateTime<Utc>) -> Self {
+        self.insert(venue, symbol, listed_at);
+        self
+    }
+
+    pub fn insert(&mut self, venue: &str, symbol: &str, listed_at: DateTime<Utc>) {
+        self.listings.insert(Self::key(venue, symbol), listed_at);
+    }
+
+    pub fn listed_at(&self, venue: &str, symbol: &str) -> Option<DateTime<Utc>> {
+        self.listings.get(&Self::key(venue, symbol)).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listings.is_empty()
+    }
+
+    fn key(venue: &str, symbol: &str) -> String {
+        format!("{}:{}", venue.to_lowercase(), symbol.to_uppercase())
+    }
+}
+
 // ============================================================
 // SECTION 3: TOKEN DATA SNAPSHOT
 // ============================================================
@@ -136,9 +233,125 @@ pub struct TokenSnapshot {
     pub is_proxy_contract: bool,
     pub deployment_timestamp: DateTime<Utc>,
     pub snapshot_timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub source: SnapshotSource,
+    #[serde(default)]
+    pub categories: Option<Vec<String>>, // Sector tags from a metadata provider, broadest first
+}
+
+/// Where a snapshot's data came from. CEX snapshots carry no holder, contract or age data;
+/// those fields are zeroed and the CEX gate set replaces the gates that would read them.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum SnapshotSource {
+    #[default]
+    Dex,
+    Cex { venue: String, spread_bps: Decimal },
+}
+
+/// Order-book depth counts levels within this share of mid price (2%)
+pub const ORDER_BOOK_DEPTH_BAND_PCT: Decimal = Decimal::from_parts(2, 0, 0, false, 2);
+/// Spread recorded for a book with no two-sided quote, so the spread gate always fails it
+const UNQUOTED_SPREAD_BPS: Decimal = Decimal::from_parts(10_000, 0, 0, false, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: Decimal,
+    pub size: Decimal, // Base units
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+impl OrderBook {
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.iter().map(|l| l.price).max()
+    }
+
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.iter().map(|l| l.price).min()
+    }
+
+    pub fn mid_price(&self) -> Option<Decimal> {
+        Some((self.best_bid()? + self.best_ask()?) / Decimal::TWO)
+    }
+
+    /// (ask - bid) / mid in basis points; None without a two-sided, uncrossed quote
+    pub fn spread_bps(&self) -> Option<Decimal> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        let mid = self.mid_price()?;
+        if mid <= Decimal::ZERO || ask < bid {
+            return None;
+        }
+        Some((ask - bid) / mid * Decimal::new(10_000, 0))
+    }
+
+    /// USD value of `levels` priced within `band_pct` of `mid`. Levels are best first, so the
+    /// walk stops at the first one outside the band.
+    fn depth_within(levels: &[BookLevel], mid: Decimal, band_pct: Decimal) -> Decimal {
+        levels
+            .iter()
+            .take_while(|l| (l.price - mid).abs() <= mid * band_pct)
+            .map(|l| l.price * l.size)
+            .sum()
+    }
 }
 
 impl TokenSnapshot {
+    /// Snapshot of a centralized-venue pair: liquidity and side depths come from the book within
+    /// ORDER_BOOK_DEPTH_BAND_PCT of mid, and the DEX-only fields are left zeroed as not applicable.
+    /// A crossed book has no usable mid, so it shows no depth, as in the root api's orderbook_math.
+    pub fn from_order_book(symbol: &str, order_book: &OrderBook, venue: &str) -> Self {
+        let (bid_depth_usd, ask_depth_usd) = match order_book.spread_bps().and(order_book.mid_price()) {
+            Some(mid) => (
+                OrderBook::depth_within(&order_book.bids, mid, ORDER_BOOK_DEPTH_BAND_PCT),
+                OrderBook::depth_within(&order_book.asks, mid, ORDER_BOOK_DEPTH_BAND_PCT),
+            ),
+            None => (Decimal::ZERO, Decimal::ZERO),
+        };
+        let now = Utc::now();
+        Self {
+            token_address: symbol.to_string(),
+            token_symbol: symbol.to_string(),
+            liquidity_usd: bid_depth_usd + ask_depth_usd,
+            bid_depth_usd,
+            ask_depth_usd,
+            holder_count: 0,
+            top_10_concentration_pct: Decimal::ZERO,
+            largest_wallet_pct: Decimal::ZERO,
+            token_age_hours: 0,
+            contract_verified: false,
+            is_proxy_contract: false,
+            deployment_timestamp: now,
+            snapshot_timestamp: now,
+            source: SnapshotSource::Cex {
+                venue: venue.to_string(),
+                spread_bps: order_book.spread_bps().unwrap_or(UNQUOTED_SPREAD_BPS),
+            },
+            categories: None,
+        }
+    }
+
+    /// Sector for exposure limits: the first category, which providers list broadest first
+    pub fn sector(&self) -> Option<&str> {
+        self.categories.as_ref()?.first().map(String::as_str)
+    }
+
+    /// Venue an entry on this snapshot trades on, for its fee schedule
+    pub fn venue(&self) -> &str {
+        match &self.source {
+            SnapshotSource::Dex => DEX_VENUE,
+            SnapshotSource::Cex { venue, .. } => venue,
+        }
+    }
+
+    /// Whether holder, contract and token-age fields hold real data
+    pub fn has_onchain_data(&self) -> bool {
+        self.source == SnapshotSource::Dex
+    }
+
     pub fn liquidity_in_range(&self, config: &TokenValidationConfig) -> bool {
         self.liquidity_usd >= config.liquidity_min_usd
             && self.liquidity_usd <= config.liquidity_max_usd
@@ -152,8 +365,64 @@ impl TokenSnapshot {
     pub fn has_squeeze_risk(&self, config: &TokenValidationConfig) -> bool {
         self.largest_wallet_pct > config.single_wallet_max_pct
     }
+
+    /// |bid - ask| / (bid + ask): 0 = perfectly balanced, 1 = completely one-sided (or no depth)
+    pub fn liquidity_imbalance_ratio(&self) -> Decimal {
+        let total = self.bid_depth_usd + self.ask_depth_usd;
+        if total <= Decimal::ZERO {
+            return Decimal::ONE;
+        }
+        (self.bid_depth_usd - self.ask_depth_usd).abs() / total
+    }
+
+    /// Model input with every feature scaled to [0, 1] against `config`'s bounds; values past
+    /// a bound saturate at 1. Index with the *_IDX constants.
+    pub fn to_risk_features(&self, config: &TokenValidationConfig) -> RiskFeatureVector {
+        let ratio = |value: Decimal, bound: Decimal| {
+            if bound > Decimal::ZERO {
+                conv::decimal_to_f64(value / bound).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        let flag = |set: bool| if set { 1.0 } else { 0.0 };
+        let preferred_age = config.token_age_preferred_long_hours.max(config.token_age_preferred_short_hours);
+
+        let mut features = [0.0; RISK_FEATURE_COUNT];
+        features[LIQ_USD_IDX] = ratio(self.liquidity_usd, config.liquidity_max_usd);
+        features[LIQ_RANGE_RATIO_IDX] = ratio(
+            self.liquidity_usd - config.liquidity_min_usd,
+            config.liquidity_max_usd - config.liquidity_min_usd,
+        );
+        features[HOLDER_COUNT_IDX] = ratio(Decimal::from(self.holder_count), Decimal::from(config.holder_count_preferred));
+        features[CONCENTRATION_IDX] = ratio(self.top_10_concentration_pct, config.top_10_concentration_max_pct);
+        features[AGE_HOURS_IDX] = ratio(Decimal::from(self.token_age_hours), Decimal::from(preferred_age));
+        features[CONTRACT_VERIFIED_IDX] = flag(self.contract_verified);
+        features[PROXY_IDX] = flag(self.is_proxy_contract);
+        features[SINGLE_WALLET_PCT_IDX] = ratio(self.largest_wallet_pct, config.single_wallet_max_pct);
+        features
+    }
 }
 
+/// Liquidity over the configured maximum
+pub const LIQ_USD_IDX: usize = 0;
+/// Position within the [liquidity_min_usd, liquidity_max_usd] band
+pub const LIQ_RANGE_RATIO_IDX: usize = 1;
+/// Holders over holder_count_preferred
+pub const HOLDER_COUNT_IDX: usize = 2;
+/// Top-10 concentration over its maximum
+pub const CONCENTRATION_IDX: usize = 3;
+/// Age over the longer preferred age
+pub const AGE_HOURS_IDX: usize = 4;
+pub const CONTRACT_VERIFIED_IDX: usize = 5;
+pub const PROXY_IDX: usize = 6;
+/// Largest wallet over single_wallet_max_pct
+pub const SINGLE_WALLET_PCT_IDX: usize = 7;
+pub const RISK_FEATURE_COUNT: usize = 8;
+
+/// TokenSnapshot features for risk models, each in [0, 1]
+pub type RiskFeatureVector = [f64; RISK_FEATURE_COUNT];
+
 // ============================================================
 // SECTION 4: SAFETY SCORING SYSTEM
 // ============================================================
@@ -164,6 +433,8 @@ pub struct SafetyScoreConfig {
     pub holder_weight: Decimal,
     pub age_weight: Decimal,
     pub contract_weight: Decimal,
+    #[serde(default)]
+    pub liquidity_imbalance_weight: Decimal, // Configs from before order book scoring leave it out
     pub long_entry_min: Decimal,
     pub short_entry_min: Decimal,
     pub manual_review_min: Decimal,
@@ -173,10 +444,11 @@ pub struct SafetyScoreConfig {
 impl Default for SafetyScoreConfig {
     fn default() -> Self {
         Self {
-            liquidity_weight: Decimal::new(30, 2),
+            liquidity_weight: Decimal::new(25, 2),
             holder_weight: Decimal::new(25, 2),
-            age_weight: Decimal::new(15, 2),
+            age_weight: Decimal::new(10, 2),
             contract_weight: Decimal::new(30, 2),
+            liquidity_imbalance_weight: Decimal::new(10, 2),
             long_entry_min: Decimal::new(60, 2),
             short_entry_min: Decimal::new(50, 2),
             manual_review_min: Decimal::new(50, 2),
@@ -185,6 +457,80 @@ impl Default for SafetyScoreConfig {
     }
 }
 
+/// Weight moved between safety score components for each market condition that calls for it
+pub const REGIME_WEIGHT_SHIFT: Decimal = Decimal::from_parts(5, 0, 0, false, 2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VolatilityRegime {
+    Low,
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LiquidityCondition {
+    Deep,
+    Normal,
+    Illiquid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrendStrength {
+    Ranging,
+    Moderate,
+    Strong,
+}
+
+/// Market regime the safety score weights are calibrated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketConditions {
+    pub volatility_regime: VolatilityRegime,
+    pub liquidity_condition: LiquidityCondition,
+    pub market_trend: TrendStrength,
+}
+
+impl SafetyScoreConfig {
+    /// A copy with weights shifted for `conditions`, rescaled to the original weight total.
+    /// High volatility moves weight from age to liquidity, since old tokens gap as hard as new
+    /// ones when the book is thin. Illiquid markets add weight to holders, where a few wallets
+    /// can move the price. A strong trend adds weight to book imbalance, since one-sided books
+    /// run further. Entry thresholds are left as they are.
+    pub fn auto_calibrate(&self, conditions: &MarketConditions) -> SafetyScoreConfig {
+        let mut calibrated = self.clone();
+        if conditions.volatility_regime == VolatilityRegime::High {
+            let shift = REGIME_WEIGHT_SHIFT.min(calibrated.age_weight);
+            calibrated.age_weight -= shift;
+            calibrated.liquidity_weight += shift;
+        }
+        if conditions.liquidity_condition == LiquidityCondition::Illiquid {
+            calibrated.holder_weight += REGIME_WEIGHT_SHIFT;
+        }
+        if conditions.market_trend == TrendStrength::Strong {
+            calibrated.liquidity_imbalance_weight += REGIME_WEIGHT_SHIFT;
+        }
+
+        let original = self.weight_total();
+        let adjusted = calibrated.weight_total();
+        if adjusted > Decimal::ZERO && adjusted != original {
+            let scale = original / adjusted;
+            calibrated.liquidity_weight *= scale;
+            calibrated.holder_weight *= scale;
+            calibrated.age_weight *= scale;
+            calibrated.contract_weight *= scale;
+            calibrated.liquidity_imbalance_weight *= scale;
+        }
+        calibrated
+    }
+
+    fn weight_total(&self) -> Decimal {
+        self.liquidity_weight
+            + self.holder_weight
+            + self.age_weight
+            + self.contract_weight
+            + self.liquidity_imbalance_weight
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyScore {
     pub total_score: Decimal,
@@ -192,6 +538,7 @@ pub struct SafetyScore {
     pub holder_score: Decimal,
     pub age_score: Decimal,
     pub contract_score: Decimal,
+    pub liquidity_imbalance_score: Decimal,
     pub risk_level: RiskLevel,
     pub calculated_at: DateTime<Utc>,
 }
@@ -206,11 +553,25 @@ impl SafetyScore {
         let holder_score = Self::calc_holder_score(token, validation);
         let age_score = Self::calc_age_score(token);
         let contract_score = Self::calc_contract_score(token);
-
-        let total_score = (liquidity_score * config.liquidity_weight)
-            + (holder_score * config.holder_weight)
-            + (age_score * config.age_weight)
-            + (contract_score * config.contract_weight);
+        let liquidity_imbalance_score = Self::calc_liquidity_imbalance_score(token);
+
+        let book_score = (liquidity_score * config.liquidity_weight)
+            + (liquidity_imbalance_score * config.liquidity_imbalance_weight);
+        let total_score = if token.has_onchain_data() {
+            book_score
+                + (holder_score * config.holder_weight)
+                + (age_score * config.age_weight)
+                + (contract_score * config.contract_weight)
+        } else {
+            // CEX snapshots zero their holder, age and contract fields; score the book alone,
+            // rescaled to the full weight total so thresholds keep their meaning
+            let book_weight = config.liquidity_weight + config.liquidity_imbalance_weight;
+            if book_weight > Decimal::ZERO {
+                book_score * config.weight_total() / book_weight
+            } else {
+                Decimal::ZERO
+            }
+        };
 
         let risk_level = Self::classify_risk(total_score);
 
@@ -220,6 +581,7 @@ impl SafetyScore {
             holder_score,
             age_score,
             contract_score,
+            liquidity_imbalance_score,
             risk_level,
             calculated_at: Utc::now(),
         }
@@ -275,6 +637,17 @@ impl SafetyScore {
         }
     }
 
+    fn calc_liquidity_imbalance_score(token: &TokenSnapshot) -> Decimal {
+        let ratio = token.liquidity_imbalance_ratio();
+        if ratio < Decimal::new(1, 1) {
+            Decimal::ONE
+        } else if ratio < Decimal::new(3, 1) {
+            Decimal::new(5, 1)
+        } else {
+            Decimal::ZERO
+        }
+    }
+
     fn classify_risk(score: Decimal) -> RiskLevel {
         if score >= Decimal::new(70, 2) {
             RiskLevel::Safe
@@ -314,6 +687,12 @@ pub struct PositionSizingConfig {
     pub short_order_max_pool_pct: Decimal,
     pub scale_order_count: u32,
     pub scale_interval_seconds: u32,
+    #[serde(default = "default_leverage")]
+    pub leverage: Decimal, // Planned notional over margin for new positions; 1 is unlevered
+}
+
+fn default_leverage() -> Decimal {
+    Decimal::ONE
 }
 
 impl Default for PositionSizingConfig {
@@ -331,6 +710,7 @@ impl Default for PositionSizingConfig {
             short_order_max_pool_pct: Decimal::new(5, 3),
             scale_order_count: 4,
             scale_interval_seconds: 30,
+            leverage: default_leverage(),
         }
     }
 }
@@ -338,16 +718,21 @@ impl Default for PositionSizingConfig {
 pub struct LiquidityScaler;
 
 impl LiquidityScaler {
+    /// Liquidity floors and the position cap from each floor up, highest first; below the
+    /// last floor the cap is 0.5%
+    pub fn tiers() -> [(Decimal, Decimal); 3] {
+        [
+            (Decimal::new(900_000, 0), Decimal::new(2, 2)),
+            (Decimal::new(750_000, 0), Decimal::new(15, 3)),
+            (Decimal::new(600_000, 0), Decimal::new(1, 2)),
+        ]
+    }
+
     pub fn max_position_pct(liquidity_usd: Decimal) -> Decimal {
-        if liquidity_usd >= Decimal::new(900_000, 0) {
-            Decimal::new(2, 2)
-        } else if liquidity_usd >= Decimal::new(750_000, 0) {
-            Decimal::new(15, 3)
-        } else if liquidity_usd >= Decimal::new(600_000, 0) {
-            Decimal::new(1, 2)
-        } else {
-            Decimal::new(5, 3)
-        }
+        Self::tiers()
+            .into_iter()
+            .find(|(floor, _)| liquidity_usd >= *floor)
+            .map_or(Decimal::new(5, 3), |(_, pct)| pct)
     }
 
     pub fn max_position_usd(portfolio_value: Decimal, liquidity_usd: Decimal) -> Decimal {
@@ -378,6 +763,43 @@ pub struct StopLossConfig {
     pub short_fixed_pct: Decimal,
     pub short_squeeze_trigger_pct: Decimal,
     pub short_squeeze_window_seconds: u32,
+    #[serde(default)]
+    pub mode: StopMode, // How the initial long stop distance is chosen
+}
+
+/// How far below entry a long's initial stop sits
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum StopMode {
+    /// long_volatile_pct at or above long_volatile_min_safety, long_default_pct below it
+    #[default]
+    SafetyTiered,
+    /// atr_multiplier times the mean absolute return over the token's last atr_window price
+    /// observations, clamped to [min_pct, max_pct] and never wider than long_hard_floor_pct
+    VolatilityScaled {
+        atr_multiplier: Decimal,
+        atr_window: usize,
+        min_pct: Decimal,
+        max_pct: Decimal,
+    },
+}
+
+/// Why a long's initial stop sits where it does; recorded on the entry log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StopBasis {
+    SafetyTiered { stop_pct: Decimal },
+    VolatilityScaled { atr_pct: Decimal, stop_pct: Decimal },
+    /// VolatilityScaled was configured but the token had too few observations; safety tiers used
+    InsufficientHistory { observed_returns: usize, required_returns: usize, stop_pct: Decimal },
+}
+
+impl StopBasis {
+    pub fn stop_pct(&self) -> Decimal {
+        match self {
+            StopBasis::SafetyTiered { stop_pct }
+            | StopBasis::VolatilityScaled { stop_pct, .. }
+            | StopBasis::InsufficientHistory { stop_pct, .. } => *stop_pct,
+        }
+    }
 }
 
 impl Default for StopLossConfig {
@@ -392,18 +814,44 @@ impl Default for StopLossConfig {
             short_fixed_pct: Decimal::new(8, 2),
             short_squeeze_trigger_pct: Decimal::new(5, 2),
             short_squeeze_window_seconds: 3600,
+            mode: StopMode::SafetyTiered,
         }
     }
 }
 
 impl StopLossConfig {
+    /// Safety-tiered long stop, ignoring `mode`
     pub fn long_stop_price(&self, entry_price: Decimal, safety_score: Decimal) -> Decimal {
-        let stop_pct = if safety_score >= self.long_volatile_min_safety {
+        entry_price * (Decimal::ONE - self.safety_tiered_pct(safety_score))
+    }
+
+    pub fn safety_tiered_pct(&self, safety_score: Decimal) -> Decimal {
+        if safety_score >= self.long_volatile_min_safety {
             self.long_volatile_pct
         } else {
             self.long_default_pct
+        }
+    }
+
+    /// Long stop under `mode`, given the token's recent observed prices (oldest first)
+    pub fn long_stop(&self, entry_price: Decimal, safety_score: Decimal, recent_prices: &[Decimal]) -> (Decimal, StopBasis) {
+        let basis = match self.mode {
+            StopMode::SafetyTiered => StopBasis::SafetyTiered { stop_pct: self.safety_tiered_pct(safety_score) },
+            StopMode::VolatilityScaled { atr_multiplier, atr_window, min_pct, max_pct } => {
+                match realized_range_pct(recent_prices, atr_window) {
+                    Some(atr_pct) => StopBasis::VolatilityScaled {
+                        atr_pct,
+                        stop_pct: (atr_multiplier * atr_pct).max(min_pct).min(max_pct).min(self.long_hard_floor_pct),
+                    },
+                    None => StopBasis::InsufficientHistory {
+                        observed_returns: recent_prices.len().saturating_sub(1),
+                        required_returns: atr_window,
+                        stop_pct: self.safety_tiered_pct(safety_score),
+                    },
+                }
+            }
         };
-        entry_price * (Decimal::ONE - stop_pct)
+        (entry_price * (Decimal::ONE - basis.stop_pct()), basis)
     }
 
     pub fn short_stop_price(&self, entry_price: Decimal) -> Decimal {
@@ -420,76 +868,216 @@ impl StopLossConfig {
     }
 }
 
+/// ATR-like range from closes alone: mean absolute return over the last `window` returns.
+/// None until `prices` holds window + 1 observations.
+pub fn realized_range_pct(prices: &[Decimal], window: usize) -> Option<Decimal> {
+    if window == 0 || prices.len() <= window {
+        return None;
+    }
+    let recent = &prices[prices.len() - window - 1..];
+    let total: Decimal = recent
+        .windows(2)
+        .filter(|pair| pair[0] > Decimal::ZERO)
+        .map(|pair| ((pair[1] - pair[0]) / pair[0]).abs())
+        .sum();
+    Some(total / Decimal::from(window))
+}
+
+/// Which order to assume when one price update crosses both a take-profit and the stop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TriggerPathAssumption {
+    #[default]
+    Conservative, // Stop first; the update books the loss
+    Optimistic,   // Take-profits first, then the stop on whatever remains
+    Midpoint,     // Levels nearer the midpoint of the move than the stop fire before it
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TriggerResolutionConfig {
+    pub path_assumption: TriggerPathAssumption,
+}
+
 // ============================================================
 // SECTION 7: TAKE PROFIT CONFIGURATION
 // ============================================================
 
+/// One rung of a take-profit ladder: move from entry that triggers it, and share of the
+/// original position it exits
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TpLevel {
+    pub trigger_pct: Decimal,
+    pub exit_pct: Decimal,
+}
+
+impl TpLevel {
+    pub fn new(trigger_pct: Decimal, exit_pct: Decimal) -> Self {
+        Self { trigger_pct, exit_pct }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "TakeProfitConfigRepr")]
 pub struct TakeProfitConfig {
-    pub long_tp1_pct: Decimal,
-    pub long_tp1_exit_pct: Decimal,
-    pub long_tp2_pct: Decimal,
-    pub long_tp2_exit_pct: Decimal,
-    pub long_tp3_pct: Decimal,
-    pub long_tp3_exit_pct: Decimal,
-    pub short_tp1_pct: Decimal,
-    pub short_tp1_exit_pct: Decimal,
-    pub short_tp2_pct: Decimal,
-    pub short_tp2_exit_pct: Decimal,
-    pub short_tp3_pct: Decimal,
-    pub short_tp3_exit_pct: Decimal,
+    pub long_levels: Vec<TpLevel>,
+    pub short_levels: Vec<TpLevel>,
+}
+
+/// Accepts both the ladder format and the original fixed three-level fields
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TakeProfitConfigRepr {
+    Ladder {
+        long_levels: Vec<TpLevel>,
+        short_levels: Vec<TpLevel>,
+    },
+    Legacy {
+        long_tp1_pct: Decimal,
+        long_tp1_exit_pct: Decimal,
+        long_tp2_pct: Decimal,
+        long_tp2_exit_pct: Decimal,
+        long_tp3_pct: Decimal,
+        long_tp3_exit_pct: Decimal,
+        short_tp1_pct: Decimal,
+        short_tp1_exit_pct: Decimal,
+        short_tp2_pct: Decimal,
+        short_tp2_exit_pct: Decimal,
+        short_tp3_pct: Decimal,
+        short_tp3_exit_pct: Decimal,
+    },
+}
+
+impl From<TakeProfitConfigRepr> for TakeProfitConfig {
+    fn from(repr: TakeProfitConfigRepr) -> Self {
+        match repr {
+            TakeProfitConfigRepr::Ladder { long_levels, short_levels } => Self { long_levels, short_levels },
+            TakeProfitConfigRepr::Legacy {
+                long_tp1_pct,
+                long_tp1_exit_pct,
+                long_tp2_pct,
+                long_tp2_exit_pct,
+                long_tp3_pct,
+                long_tp3_exit_pct,
+                short_tp1_pct,
+                short_tp1_exit_pct,
+                short_tp2_pct,
+                short_tp2_exit_pct,
+                short_tp3_pct,
+                short_tp3_exit_pct,
+            } => Self {
+                long_levels: vec![
+                    TpLevel::new(long_tp1_pct, long_tp1_exit_pct),
+                    TpLevel::new(long_tp2_pct, long_tp2_exit_pct),
+                    TpLevel::new(long_tp3_pct, long_tp3_exit_pct),
+                ],
+                short_levels: vec![
+                    TpLevel::new(short_tp1_pct, short_tp1_exit_pct),
+                    TpLevel::new(short_tp2_pct, short_tp2_exit_pct),
+                    TpLevel::new(short_tp3_pct, short_tp3_exit_pct),
+                ],
+            },
+        }
+    }
 }
 
 impl Default for TakeProfitConfig {
     fn default() -> Self {
         Self {
-            long_tp1_pct: Decimal::new(15, 2),
-            long_tp1_exit_pct: Decimal::new(33, 2),
-            long_tp2_pct: Decimal::new(30, 2),
-            long_tp2_exit_pct: Decimal::new(33, 2),
-            long_tp3_pct: Decimal::new(50, 2),
-            long_tp3_exit_pct: Decimal::new(34, 2),
-            short_tp1_pct: Decimal::new(10, 2),
-            short_tp1_exit_pct: Decimal::new(33, 2),
-            short_tp2_pct: Decimal::new(20, 2),
-            short_tp2_exit_pct: Decimal::new(33, 2),
-            short_tp3_pct: Decimal::new(30, 2),
-            short_tp3_exit_pct: Decimal::new(34, 2),
+            long_levels: vec![
+                TpLevel::new(Decimal::new(15, 2), Decimal::new(33, 2)),
+                TpLevel::new(Decimal::new(30, 2), Decimal::new(33, 2)),
+                TpLevel::new(Decimal::new(50, 2), Decimal::new(34, 2)),
+            ],
+            short_levels: vec![
+                TpLevel::new(Decimal::new(10, 2), Decimal::new(33, 2)),
+                TpLevel::new(Decimal::new(20, 2), Decimal::new(33, 2)),
+                TpLevel::new(Decimal::new(30, 2), Decimal::new(34, 2)),
+            ],
         }
     }
 }
 
 impl TakeProfitConfig {
-    pub fn long_tp_prices(&self, entry_price: Decimal) -> [Decimal; 3] {
-        [
-            entry_price * (Decimal::ONE + self.long_tp1_pct),
-            entry_price * (Decimal::ONE + self.long_tp2_pct),
-            entry_price * (Decimal::ONE + self.long_tp3_pct),
-        ]
+    /// Builds a ladder, rejecting it if either direction fails validation
+    pub fn new(long_levels: Vec<TpLevel>, short_levels: Vec<TpLevel>) -> Result<Self, Vec<String>> {
+        let config = Self { long_levels, short_levels };
+        config.validate()?;
+        Ok(config)
     }
 
-    pub fn short_tp_prices(&self, entry_price: Decimal) -> [Decimal; 3] {
-        [
-            entry_price * (Decimal::ONE - self.short_tp1_pct),
-            entry_price * (Decimal::ONE - self.short_tp2_pct),
-            entry_price * (Decimal::ONE - self.short_tp3_pct),
-        ]
+    pub fn levels(&self, direction: Direction) -> &[TpLevel] {
+        match direction {
+            Direction::Long => &self.long_levels,
+            Direction::Short => &self.short_levels,
+        }
     }
 
-    pub fn exit_percentages(&self, direction: Direction) -> [Decimal; 3] {
-        match direction {
-            Direction::Long => [
-                self.long_tp1_exit_pct,
-                self.long_tp2_exit_pct,
-                self.long_tp3_exit_pct,
-            ],
-            Direction::Short => [
-                self.short_tp1_exit_pct,
-                self.short_tp2_exit_pct,
-                self.short_tp3_exit_pct,
-            ],
+    /// Each direction needs at least one level, strictly increasing positive triggers
+    /// and positive exit percentages summing to exactly 100%
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for direction in [Direction::Long, Direction::Short] {
+            let levels = self.levels(direction);
+            if levels.is_empty() {
+                errors.push(format!("take_profit {:?} ladder has no levels", direction));
+                continue;
+            }
+            if levels.iter().any(|l| l.trigger_pct <= Decimal::ZERO)
+                || levels.windows(2).any(|w| w[0].trigger_pct >= w[1].trigger_pct)
+            {
+                errors.push(format!("take_profit {:?} targets must be positive and strictly ascending", direction));
+            }
+            if direction == Direction::Short && levels.iter().any(|l| l.trigger_pct >= Decimal::ONE) {
+                errors.push("take_profit Short targets must be below 100%".to_string());
+            }
+            if levels.iter().any(|l| l.exit_pct <= Decimal::ZERO) {
+                errors.push(format!("take_profit {:?} exit percentages must be positive", direction));
+            }
+            let total: Decimal = levels.iter().map(|l| l.exit_pct).sum();
+            if total != Decimal::ONE {
+                errors.push(format!("take_profit {:?} exit percentages sum to {}, expected 1", direction, total));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
     }
+
+    pub fn long_tp_prices(&self, entry_price: Decimal) -> Vec<Decimal> {
+        self.long_levels
+            .iter()
+            .map(|l| entry_price * (Decimal::ONE + l.trigger_pct))
+            .collect()
+    }
+
+    pub fn short_tp_prices(&self, entry_price: Decimal) -> Vec<Decimal> {
+        self.short_levels
+            .iter()
+            .map(|l| entry_price * (Decimal::ONE - l.trigger_pct))
+            .collect()
+    }
+
+    pub fn exit_percentages(&self, direction: Direction) -> Vec<Decimal> {
+        self.levels(direction).iter().map(|l| l.exit_pct).collect()
+    }
+
+    /// Unhit targets for a new position entered at `entry_price`
+    pub fn targets(&self, direction: Direction, entry_price: Decimal) -> Vec<TpTarget> {
+        let prices = match direction {
+            Direction::Long => self.long_tp_prices(entry_price),
+            Direction::Short => self.short_tp_prices(entry_price),
+        };
+        prices
+            .into_iter()
+            .zip(self.levels(direction))
+            .map(|(price, level)| TpTarget {
+                price,
+                hit: false,
+                exit_pct: level.exit_pct,
+            })
+            .collect()
+    }
 }
 
 // ============================================================
@@ -502,6 +1090,20 @@ pub struct TimeControlConfig {
     pub long_flag_days: u32,
     pub short_max_hours: u32,
     pub long_no_movement_flag_hours: u32,
+    #[serde(default = "default_hold_review_threshold")]
+    pub hold_review_threshold: Decimal, // A rescored safety score below this flags the position for review
+    #[serde(default = "default_hold_review_max_drop")]
+    pub hold_review_max_drop: Decimal, // As does a fall of more than this from the entry score
+    #[serde(default)]
+    pub long_review_min_holder_growth_pct: Option<Decimal>, // Longs past review age growing holders slower than this fail review
+}
+
+fn default_hold_review_threshold() -> Decimal {
+    Decimal::new(50, 2)
+}
+
+fn default_hold_review_max_drop() -> Decimal {
+    Decimal::new(20, 2)
 }
 
 impl Default for TimeControlConfig {
@@ -511,14 +1113,56 @@ impl Default for TimeControlConfig {
             long_flag_days: 14,
             short_max_hours: 72,
             long_no_movement_flag_hours: 24,
+            hold_review_threshold: default_hold_review_threshold(),
+            hold_review_max_drop: default_hold_review_max_drop(),
+            long_review_min_holder_growth_pct: None,
         }
     }
 }
 
 impl TimeControlConfig {
     pub fn long_needs_review(&self, opened_at: DateTime<Utc>) -> bool {
-        let duration = Utc::now() - opened_at;
-        duration.num_days() >= self.long_review_days as i64
+        self.long_review_due(opened_at, Utc::now())
+    }
+
+    pub fn long_review_due(&self, opened_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        (now - opened_at).num_days() >= self.long_review_days as i64
+    }
+
+    /// Review of a long past `long_review_days`, judged on holder growth since entry when
+    /// `long_review_min_holder_growth_pct` is set. Without current holder data the position
+    /// stays in ordinary review with a note rather than failing.
+    pub fn review_long(&self, position: &Position, holder_count: Option<u32>, now: DateTime<Utc>) -> LongReview {
+        let holder_growth_pct = holder_count
+            .filter(|_| position.holder_count_at_entry > 0)
+            .map(|count| {
+                let entry = Decimal::from(position.holder_count_at_entry);
+                (Decimal::from(count) - entry) / entry
+            });
+        let outcome = match (self.long_review_min_holder_growth_pct, holder_growth_pct) {
+            (None, _) => LongReviewOutcome::NeedsReview { note: None },
+            (Some(min_growth_pct), Some(growth)) if growth < min_growth_pct => {
+                LongReviewOutcome::FailedReview { min_growth_pct, recommended_exit: ExitType::TimeStop }
+            }
+            (Some(_), Some(_)) => LongReviewOutcome::NeedsReview { note: None },
+            (Some(_), None) => LongReviewOutcome::NeedsReview {
+                note: Some(if holder_count.is_none() {
+                    "current holder data unavailable".to_string()
+                } else {
+                    "no holder count recorded at entry".to_string()
+                }),
+            },
+        };
+        LongReview {
+            execution_id: position.execution_id,
+            token_address: position.token_address.clone(),
+            token_symbol: position.token_symbol.clone(),
+            held_days: (now - position.opened_at).num_days(),
+            holder_count_at_entry: position.holder_count_at_entry,
+            holder_count,
+            holder_growth_pct,
+            outcome,
+        }
     }
 
     pub fn long_should_flag(&self, opened_at: DateTime<Utc>) -> bool {
@@ -536,13 +1180,41 @@ impl TimeControlConfig {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LongReviewOutcome {
+    NeedsReview { note: Option<String> }, // Note explains why holder growth couldn't be judged
+    FailedReview { min_growth_pct: Decimal, recommended_exit: ExitType },
+}
+
+/// A long held past `long_review_days`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LongReview {
+    pub execution_id: Uuid,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub held_days: i64,
+    pub holder_count_at_entry: u32,
+    pub holder_count: Option<u32>, // None without a fresh snapshot
+    pub holder_growth_pct: Option<Decimal>, // Since entry, as a share of the entry count
+    pub outcome: LongReviewOutcome,
+}
+
+impl LongReview {
+    pub fn failed(&self) -> bool {
+        matches!(self.outcome, LongReviewOutcome::FailedReview { .. })
+    }
+}
+
 // ============================================================
 // SECTION 9: RISK CONTROLLER CONFIGURATION
 // ============================================================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskControllerConfig {
-    pub daily_drawdown_halt_pct: Decimal,
+    #[serde(alias = "daily_drawdown_halt_pct")]
+    pub daily_realized_halt_pct: Decimal, // Realized loss from the daily high; pauses until resumed
+    #[serde(default = "default_daily_unrealized_soft_halt_pct")]
+    pub daily_unrealized_soft_halt_pct: Decimal, // Open loss as a share of realized equity
     pub weekly_drawdown_halt_pct: Decimal,
     pub monthly_review_pct: Decimal,
     pub net_exposure_min_pct: Decimal,
@@ -556,12 +1228,31 @@ pub struct RiskControllerConfig {
     pub slippage_pause_pct: Decimal,
     pub slippage_reduce_pct: Decimal,
     pub partial_fill_min_pct: Decimal,
+    #[serde(default = "default_short_borrow_buffer_pct")]
+    pub short_borrow_buffer_pct: Decimal, // Borrow needed beyond the short's size
+    #[serde(default = "default_maintenance_margin_pct")]
+    pub maintenance_margin_pct: Decimal, // Margin, as a share of notional, below which the venue liquidates
+    #[serde(default)]
+    pub failure_policy: failures::FailurePolicyConfig, // Thresholds and decay windows per failure class
+}
+
+fn default_maintenance_margin_pct() -> Decimal {
+    Decimal::new(5, 3)
+}
+
+fn default_short_borrow_buffer_pct() -> Decimal {
+    Decimal::new(10, 2)
+}
+
+fn default_daily_unrealized_soft_halt_pct() -> Decimal {
+    Decimal::new(5, 2)
 }
 
 impl Default for RiskControllerConfig {
     fn default() -> Self {
         Self {
-            daily_drawdown_halt_pct: Decimal::new(5, 2),
+            daily_realized_halt_pct: Decimal::new(5, 2),
+            daily_unrealized_soft_halt_pct: default_daily_unrealized_soft_halt_pct(),
             weekly_drawdown_halt_pct: Decimal::new(10, 2),
             monthly_review_pct: Decimal::new(15, 2),
             net_exposure_min_pct: Decimal::new(-30, 2),
@@ -575,862 +1266,10101 @@ impl Default for RiskControllerConfig {
             slippage_pause_pct: Decimal::new(5, 3),
             slippage_reduce_pct: Decimal::new(15, 3),
             partial_fill_min_pct: Decimal::new(80, 2),
+            short_borrow_buffer_pct: default_short_borrow_buffer_pct(),
+            maintenance_margin_pct: default_maintenance_margin_pct(),
+            failure_policy: failures::FailurePolicyConfig::default(),
         }
     }
 }
 
-// ============================================================
-// SECTION 10: RISK GATE VALIDATION
-// ============================================================
-
+/// What a venue will lend of a token for shorting
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RiskGateCheck {
-    pub gate_name: String,
-    pub result: GateResult,
-    pub reason: Option<String>,
-    pub checked_at: DateTime<Utc>,
+pub struct BorrowInfo {
+    pub available_usd: Decimal,
+    pub hourly_rate_pct: Decimal,
+    pub max_duration_hours: u32,
 }
 
+/// Hourly borrow cost and lendable size quoted for a token, and when
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RiskValidation {
-    pub validation_id: Uuid,
-    pub direction: Direction,
-    pub gates: Vec<RiskGateCheck>,
-    pub all_passed: bool,
-    pub validated_at: DateTime<Utc>,
+pub struct BorrowQuote {
+    pub info: BorrowInfo,
+    pub quoted_at: DateTime<Utc>,
 }
 
-impl RiskValidation {
-    pub fn new(direction: Direction) -> Self {
-        Self {
-            validation_id: Uuid::new_v4(),
-            direction,
-            gates: Vec::with_capacity(10),
-            all_passed: true,
-            validated_at: Utc::now(),
-        }
-    }
+pub type BorrowInfoFuture<'a> = std::pin::Pin<Box<dyn std::future::Future<Output = Result<BorrowInfo, String>> + Send + 'a>>;
 
-    pub fn add_gate(&mut self, name: &str, result: GateResult, reason: Option<String>) {
-        if result != GateResult::Passed {
-            self.all_passed = false;
-        }
-        self.gates.push(RiskGateCheck {
-            gate_name: name.to_string(),
-            result,
-            reason,
-            checked_at: Utc::now(),
-        });
+/// Boxed future rather than `async fn` so the engine can hold an `Arc<dyn BorrowAvailabilityProvider>`
+pub trait BorrowAvailabilityProvider: Send + Sync {
+    fn borrow_info<'a>(&'a self, token_address: &'a str) -> BorrowInfoFuture<'a>;
+}
+
+/// Fixed borrow terms per token address; tokens it doesn't know have nothing to borrow
+#[derive(Debug, Clone, Default)]
+pub struct StaticBorrowProvider {
+    pub tokens: BTreeMap<String, BorrowInfo>,
+}
+
+impl StaticBorrowProvider {
+    pub fn with_token(mut self, token_address: &str, info: BorrowInfo) -> Self {
+        self.tokens.insert(token_address.to_string(), info);
+        self
     }
+}
 
-    pub fn first_failure(&self) -> Option<&RiskGateCheck> {
-        self.gates.iter().find(|g| g.result == GateResult::Failed)
+impl BorrowAvailabilityProvider for StaticBorrowProvider {
+    fn borrow_info<'a>(&'a self, token_address: &'a str) -> BorrowInfoFuture<'a> {
+        let info = self
+            .tokens
+            .get(token_address)
+            .cloned()
+            .ok_or_else(|| format!("no borrow available for {}", token_address));
+        Box::pin(async move { info })
     }
+}
 
-    pub fn all_failures(&self) -> Vec<&RiskGateCheck> {
-        self.gates
-            .iter()
-            .filter(|g| g.result == GateResult::Failed)
-            .collect()
+/// How long a borrow quote backs a short entry before it has to be fetched again
+pub const BORROW_QUOTE_TTL_SECONDS: i64 = 60;
+/// Quotes kept beyond those backing open shorts; the oldest go first
+pub const MAX_BORROW_QUOTES: usize = 1_000;
+
+/// Pairwise correlation between held tokens, estimated from per-tick returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrelationConfig {
+    pub alpha: Decimal,                // Weight of the newest return product in the moving covariance
+    pub max_long_correlation: Decimal, // Long entries above this with any open long are rejected
+}
+
+impl Default for CorrelationConfig {
+    fn default() -> Self {
+        Self {
+            alpha: Decimal::new(1, 1),
+            max_long_correlation: Decimal::new(7, 1),
+        }
     }
 }
 
-// ============================================================
-// SECTION 11: POSITION STRUCTURES
-// ============================================================
+/// Venue key that DEX snapshots and their positions are charged under
+pub const DEX_VENUE: &str = "dex";
+
+/// Trading costs on one venue. `flat_usd` is charged per fill on top of the rate, for
+/// gas-inclusive DEX costs.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct VenueFees {
+    pub maker_bps: Decimal,
+    pub taker_bps: Decimal,
+    #[serde(default)]
+    pub flat_usd: Decimal,
+}
 
+impl VenueFees {
+    pub fn new(maker_bps: Decimal, taker_bps: Decimal) -> Self {
+        Self { maker_bps, taker_bps, flat_usd: Decimal::ZERO }
+    }
+
+    pub fn with_flat_usd(mut self, flat_usd: Decimal) -> Self {
+        self.flat_usd = flat_usd;
+        self
+    }
+
+    /// Fee on one fill of `notional_usd`, at the maker rate for resting orders
+    pub fn fee_usd(&self, notional_usd: Decimal, maker: bool) -> Decimal {
+        let bps = if maker { self.maker_bps } else { self.taker_bps };
+        notional_usd.abs() * bps / Decimal::new(10_000, 0) + self.flat_usd
+    }
+}
+
+/// Per-venue fee schedules; venues without one pay `default_fees`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Position {
-    pub execution_id: Uuid,
-    pub token_address: String,
-    pub token_symbol: String,
-    pub direction: Direction,
-    pub entry_price: Decimal,
-    pub current_price: Decimal,
-    pub position_size_tokens: Decimal,
-    pub position_size_usd: Decimal,
-    pub remaining_size_pct: Decimal,
-    pub liquidity_at_entry: Decimal,
-    pub safety_score_at_entry: Decimal,
-    pub holder_count_at_entry: u32,
-    pub stop_loss_price: Decimal,
-    pub take_profit_prices: [Decimal; 3],
-    pub take_profit_hit: [bool; 3],
-    pub risk_approval_id: Uuid,
-    pub opened_at: DateTime<Utc>,
-    pub time_stop_at: Option<DateTime<Utc>>,
-    pub status: PositionStatus,
-    pub trailing_stop_active: bool,
-    pub trailing_stop_high: Option<Decimal>,
-    pub unrealized_pnl_usd: Decimal,
-    pub unrealized_pnl_pct: Decimal,
+pub struct FeeModel {
+    pub default_fees: VenueFees,
+    #[serde(default)]
+    pub venues: BTreeMap<String, VenueFees>, // Keyed by lower-cased venue name
 }
 
-impl Position {
-    pub fn update_price(&mut self, new_price: Decimal) {
-        self.current_price = new_price;
-        self.unrealized_pnl_usd = match self.direction {
-            Direction::Long => {
-                (new_price - self.entry_price) * self.position_size_tokens * self.remaining_size_pct
-            }
-            Direction::Short => {
-                (self.entry_price - new_price) * self.position_size_tokens * self.remaining_size_pct
-            }
-        };
-        self.unrealized_pnl_pct = self.unrealized_pnl_usd / self.position_size_usd;
+impl Default for FeeModel {
+    fn default() -> Self {
+        Self::zero()
+            .with_venue(DEX_VENUE, VenueFees::new(Decimal::new(30, 0), Decimal::new(30, 0)))
+            .with_default_fees(VenueFees::new(Decimal::new(2, 0), Decimal::new(10, 0)))
+    }
+}
 
-        if self.trailing_stop_active && self.direction == Direction::Long {
-            if let Some(hwm) = self.trailing_stop_high {
-                if new_price > hwm {
-                    self.trailing_stop_high = Some(new_price);
-                }
-            }
-        }
+impl FeeModel {
+    /// Charges nothing anywhere
+    pub fn zero() -> Self {
+        Self { default_fees: VenueFees::default(), venues: BTreeMap::new() }
     }
 
-    pub fn stop_triggered(&self) -> bool {
-        match self.direction {
-            Direction::Long => self.current_price <= self.stop_loss_price,
-            Direction::Short => self.current_price >= self.stop_loss_price,
-        }
+    pub fn with_default_fees(mut self, fees: VenueFees) -> Self {
+        self.default_fees = fees;
+        self
     }
 
-    pub fn check_take_profits(&self) -> Option<usize> {
-        for (i, &tp_price) in self.take_profit_prices.iter().enumerate() {
-            if self.take_profit_hit[i] {
-                continue;
-            }
-            let triggered = match self.direction {
-                Direction::Long => self.current_price >= tp_price,
-                Direction::Short => self.current_price <= tp_price,
-            };
-            if triggered {
-                return Some(i);
-            }
-        }
-        None
+    pub fn with_venue(mut self, venue: &str, fees: VenueFees) -> Self {
+        self.venues.insert(venue.to_lowercase(), fees);
+        self
     }
 
-    pub fn trailing_stop_triggered(&self, config: &StopLossConfig) -> bool {
-        if !self.trailing_stop_active {
-            return false;
-        }
-        if let Some(hwm) = self.trailing_stop_high {
-            let trailing_price = config.trailing_stop_price(hwm);
-            self.current_price <= trailing_price
-        } else {
-            false
-        }
+    pub fn for_venue(&self, venue: &str) -> &VenueFees {
+        self.venues.get(&venue.to_lowercase()).unwrap_or(&self.default_fees)
     }
+
+    /// Taker fees on entering and leaving `notional_usd` at an unchanged price
+    pub fn round_trip_fee_usd(&self, venue: &str, notional_usd: Decimal) -> Decimal {
+        Decimal::TWO * self.for_venue(venue).fee_usd(notional_usd, false)
+    }
+}
+
+/// Market-wide signals fed to the engine from outside the token pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MarketEvent {
+    /// Reference index fell `pct` (0.16 = 16%) from its high within `window`
+    IndexDrop { pct: Decimal, window: std::time::Duration },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PositionExit {
-    pub execution_id: Uuid,
-    pub exit_price: Decimal,
-    pub exit_type: ExitType,
-    pub exit_size_pct: Decimal,
-    pub realized_pnl_tokens: Decimal,
-    pub realized_pnl_usd: Decimal,
-    pub slippage_bps: Decimal,
-    pub hold_duration_seconds: u64,
-    pub liquidity_at_exit: Decimal,
-    pub exited_at: DateTime<Utc>,
+pub struct MarketEventRecord {
+    pub event: MarketEvent,
+    pub state_before: SystemState,
+    pub state_after: SystemState,
+    pub received_at: DateTime<Utc>,
 }
 
 // ============================================================
-// SECTION 12: BOOK MANAGEMENT
+// SECTION 10: RISK GATE VALIDATION
 // ============================================================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PositionBook {
+pub struct RiskGateCheck {
+    pub gate_name: String,
+    pub result: GateResult,
+    pub reason: Option<String>,
+    pub checked_at: DateTime<Utc>,
+    #[serde(default)]
+    pub elapsed_ms: f64, // Time the gate took; 0 for checks recorded without timing
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskValidation {
+    pub validation_id: Uuid,
     pub direction: Direction,
-    pub positions: Vec<Position>,
-    pub total_allocation_usd: Decimal,
-    pub max_allocation_usd: Decimal,
-    pub max_positions: u32,
-    pub realized_pnl_usd: Decimal,
-    pub unrealized_pnl_usd: Decimal,
+    pub gates: Vec<RiskGateCheck>,
+    pub all_passed: bool,
+    pub validated_at: DateTime<Utc>,
 }
 
-impl PositionBook {
-    pub fn new(direction: Direction, max_allocation_usd: Decimal, max_positions: u32) -> Self {
+impl RiskValidation {
+    pub fn new(direction: Direction) -> Self {
         Self {
+            validation_id: Uuid::new_v4(),
             direction,
-            positions: Vec::with_capacity(max_positions as usize),
-            total_allocation_usd: Decimal::ZERO,
-            max_allocation_usd,
-            max_positions,
-            realized_pnl_usd: Decimal::ZERO,
-            unrealized_pnl_usd: Decimal::ZERO,
+            gates: Vec::with_capacity(10),
+            all_passed: true,
+            validated_at: Utc::now(),
         }
     }
 
-    pub fn can_add_position(&self, size_usd: Decimal) -> bool {
-        self.position_count() < self.max_positions
-            && (self.total_allocation_usd + size_usd) <= self.max_allocation_usd
+    pub fn add_gate(&mut self, name: &str, result: GateResult, reason: Option<String>) {
+        self.add_timed_gate(name, result, reason, 0.0);
     }
 
-    pub fn position_count(&self) -> u32 {
-        self.positions.len() as u32
+    pub fn add_timed_gate(&mut self, name: &str, result: GateResult, reason: Option<String>, elapsed_ms: f64) {
+        if result.blocks_entry() {
+            self.all_passed = false;
+            info!(gate = name, result = ?result, reason = reason.as_deref(), "risk gate rejected");
+        } else {
+            debug!(gate = name, result = ?result, "risk gate passed");
+        }
+        self.gates.push(RiskGateCheck {
+            gate_name: name.to_string(),
+            result,
+            reason,
+            checked_at: Utc::now(),
+            elapsed_ms,
+        });
     }
 
-    pub fn available_capacity_usd(&self) -> Decimal {
-        self.max_allocation_usd - self.total_allocation_usd
+    pub fn first_failure(&self) -> Option<&RiskGateCheck> {
+        self.gates.iter().find(|g| g.result == GateResult::Failed)
     }
 
-    pub fn update_unrealized_pnl(&mut self) {
-        self.unrealized_pnl_usd = self.positions.iter().map(|p| p.unrealized_pnl_usd).sum();
+    /// Time the gates took between them, as each timed itself
+    pub fn gate_elapsed_ms(&self) -> f64 {
+        self.gates.iter().map(|g| g.elapsed_ms).sum()
     }
 
-    pub fn has_position(&self, token_address: &str) -> bool {
-        self.positions
+    pub fn all_failures(&self) -> Vec<&RiskGateCheck> {
+        self.gates
             .iter()
-            .any(|p| p.token_address == token_address && p.status == PositionStatus::Open)
+            .filter(|g| g.result == GateResult::Failed)
+            .collect()
     }
 
-    pub fn get_position(&self, token_address: &str) -> Option<&Position> {
-        self.positions
-            .iter()
-            .find(|p| p.token_address == token_address && p.status == PositionStatus::Open)
+    /// Summary of the full gate sequence, pass or fail, for throughput analysis
+    pub fn to_audit_record(&self) -> ValidationAuditRecord {
+        let count = |result: GateResult| self.gates.iter().filter(|g| g.result == result).count() as u32;
+        let total_elapsed_ms = self
+            .gates
+            .last()
+            .map(|g| (g.checked_at - self.validated_at).num_milliseconds().max(0) as u64)
+            .unwrap_or(0);
+
+        ValidationAuditRecord {
+            validation_id: self.validation_id,
+            direction: self.direction,
+            all_passed: self.all_passed,
+            gate_count: self.gates.len() as u32,
+            pass_count: count(GateResult::Passed),
+            fail_count: count(GateResult::Failed),
+            timeout_count: count(GateResult::Timeout),
+            skip_count: count(GateResult::Skipped),
+            total_elapsed_ms,
+            gates: serde_json::to_value(&self.gates).unwrap_or(serde_json::Value::Null),
+        }
     }
+}
 
-    pub fn get_position_mut(&mut self, token_address: &str) -> Option<&mut Position> {
-        self.positions
-            .iter_mut()
-            .find(|p| p.token_address == token_address && p.status == PositionStatus::Open)
-    }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationAuditRecord {
+    pub validation_id: Uuid,
+    pub direction: Direction,
+    pub all_passed: bool,
+    pub gate_count: u32,
+    pub pass_count: u32,
+    pub fail_count: u32,
+    pub timeout_count: u32,
+    #[serde(default)]
+    pub skip_count: u32,
+    pub total_elapsed_ms: u64,
+    pub gates: serde_json::Value, // Array of RiskGateCheck in evaluation order
 }
 
-// ============================================================
-// SECTION 13: PORTFOLIO STATE
-// ============================================================
+/// A gate's inputs alongside what it requires; `result` is None when an earlier gate failed first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateDiagnostic {
+    pub gate_name: String,
+    pub result: Option<GateResult>,
+    pub observed: String,
+    pub required: String,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PortfolioState {
-    pub total_capital_usd: Decimal,
-    pub available_capital_usd: Decimal,
-    pub long_book: PositionBook,
-    pub short_book: PositionBook,
-    pub gross_exposure_pct: Decimal,
-    pub net_exposure_pct: Decimal,
-    pub gross_exposure_usd: Decimal,
-    pub net_exposure_usd: Decimal,
-    pub daily_pnl_usd: Decimal,
-    pub weekly_pnl_usd: Decimal,
-    pub monthly_pnl_usd: Decimal,
-    pub daily_drawdown_pct: Decimal,
-    pub weekly_drawdown_pct: Decimal,
-    pub monthly_drawdown_pct: Decimal,
-    pub daily_high_water_mark: Decimal,
-    pub weekly_high_water_mark: Decimal,
-    pub monthly_high_water_mark: Decimal,
-    pub state: SystemState,
-    pub consecutive_failures: u32,
-    pub last_updated: DateTime<Utc>,
+pub struct DryRunResult {
+    pub validation: RiskValidation,
+    pub safety_score: SafetyScore,
+    pub liquidity_percentile: Decimal, // Position of liquidity within the configured band, 0-1
+    pub position_size_usd: Decimal,
+    pub diagnostics: Vec<GateDiagnostic>,
 }
 
-impl PortfolioState {
-    pub fn calculate_exposure(&mut self) {
-        let long_exposure = self.long_book.total_allocation_usd;
-        let short_exposure = self.short_book.total_allocation_usd;
+/// Share of the original size the headline partial exit takes off (33%)
+pub const FIRST_PARTIAL_EXIT_PCT: Decimal = Decimal::from_parts(33, 0, 0, false, 2);
 
-        self.gross_exposure_usd = long_exposure + short_exposure;
-        self.net_exposure_usd = long_exposure - short_exposure;
+/// The position an entry would open, computed without booking anything
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedPosition {
+    pub token_address: String,
+    pub direction: Direction,
+    pub entry_price: Decimal,
+    pub position_size_usd: Decimal,
+    pub position_size_tokens: Decimal,
+    pub safety_score: SafetyScore,
+    pub stop_loss_price: Decimal,
+    pub take_profit_targets: Vec<TpTarget>,
+    pub first_partial_exit_level: Option<usize>, // Target at which FIRST_PARTIAL_EXIT_PCT of the size is out
+    pub time_stop_at: Option<DateTime<Utc>>,
+    pub validation: RiskValidation,
+}
 
-        if self.total_capital_usd > Decimal::ZERO {
-            self.gross_exposure_pct = self.gross_exposure_usd / self.total_capital_usd;
-            self.net_exposure_pct = self.net_exposure_usd / self.total_capital_usd;
-        }
+/// Which limit set an entry's size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SizingCap {
+    LiquidityTier, // Capital share allowed for the pool's liquidity tier
+    PoolDepth,     // Largest order the pool takes in this direction
+    Override,      // Size given by the caller
+}
 
-        self.available_capital_usd = self.total_capital_usd - self.gross_exposure_usd;
-        self.last_updated = Utc::now();
-    }
+/// How an entry's size was reached: the smaller of the two limits, scaled by the activity slot
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SizeDecision {
+    pub tier_max_usd: Decimal,   // Capital share allowed for the pool's liquidity tier
+    pub pool_limit_usd: Decimal, // Largest order the pool takes in this direction
+    pub cap: SizingCap,          // Which of the two is smaller
+    pub activity: schedule::ActivityLevel,
+    pub size_usd: Decimal,
+}
 
-    pub fn check_drawdown_limits(&self, config: &RiskControllerConfig) -> SystemState {
-        if self.monthly_drawdown_pct >= config.monthly_review_pct {
-            return SystemState::EmergencyHalt;
-        }
-        if self.weekly_drawdown_pct >= config.weekly_drawdown_halt_pct {
-            return SystemState::PausedAll;
-        }
-        if self.daily_drawdown_pct >= config.daily_drawdown_halt_pct {
-            return SystemState::PausedAll;
-        }
-        SystemState::Active
+/// What an entry would do to the portfolio if it were opened now and went straight to its stop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryImpact {
+    pub token_address: String,
+    pub direction: Direction,
+    pub validation: RiskValidation,
+    pub position_size_usd: Decimal,
+    pub sizing_cap: SizingCap,
+    pub pre_gross_exposure_pct: Decimal,
+    pub post_gross_exposure_pct: Decimal,
+    pub pre_net_exposure_pct: Decimal,
+    pub post_net_exposure_pct: Decimal,
+    pub post_available_capital_usd: Decimal,
+    pub book_slots_remaining: u32, // In the entry's book, after it
+    pub stop_loss_pct: Decimal,
+    pub stop_loss_usd: Decimal,
+    pub round_trip_fees_usd: Decimal, // Taker fees in and out at the entry notional
+    pub fee_drag_pct: Decimal,        // round_trip_fees_usd over position size
+    pub worst_case_daily_drawdown_pct: Decimal, // Daily drawdown with the stop hit immediately, fees included
+    pub crosses_daily_halt: bool,
+    pub max_long_correlation: Option<(String, Decimal)>, // Longs only, as the correlation gate sees it
+    pub correlation_utilization: Option<Decimal>, // max_long_correlation over its configured limit
+}
+
+/// What closing an open position at a price would realize and leave behind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitImpact {
+    pub execution_id: Uuid,
+    pub exit_price: Decimal,
+    pub exit_size_pct: Decimal,
+    pub realized_pnl_usd: Decimal, // Net of fees_usd
+    pub fees_usd: Decimal,
+    pub post_gross_exposure_pct: Decimal,
+    pub post_net_exposure_pct: Decimal,
+    pub post_available_capital_usd: Decimal,
+}
+
+/// Open positions repriced by a uniform move, set against the portfolio's own drawdown history
+#[derive(Debug, Clone)]
+pub struct StressTestResult {
+    pub price_shock_pct: Decimal,
+    pub scenario_pnl_usd: Decimal,      // Change in unrealized P&L from current marks
+    pub scenario_drawdown_pct: Decimal, // Scenario loss over current equity; zero for a gain
+    pub worst_historical_drawdown_pct: Decimal,
+    pub vs_worst_historical: Option<Decimal>, // Scenario drawdown as a multiple of the worst seen
+    pub historical_recovery_time: Option<chrono::Duration>, // Trough to recovery of the worst drawdown
+}
+
+/// Default lifetime of a cached validation; bounds drift in inputs the key does not cover,
+/// such as correlations and recovery probation
+pub const VALIDATION_CACHE_TTL_MS: i64 = 1_000;
+/// Default number of cached validations kept
+pub const VALIDATION_CACHE_CAPACITY: usize = 4_096;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64, // Expired or pushed out for capacity
+}
+
+/// Hasher that keeps every byte written to it, so `Hash` impls spell out an exact cache key
+#[derive(Debug, Default)]
+struct KeyBytes(Vec<u8>);
+
+impl std::hash::Hasher for KeyBytes {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
     }
 
-    pub fn net_exposure_valid(&self, config: &RiskControllerConfig) -> bool {
-        self.net_exposure_pct >= config.net_exposure_min_pct
-            && self.net_exposure_pct <= config.net_exposure_max_pct
+    fn finish(&self) -> u64 {
+        unreachable!("KeyBytes collects a key; it is never finished into a hash")
     }
+}
 
-    pub fn update_drawdowns(&mut self) {
-        let current_value = self.total_capital_usd
-            + self.long_book.unrealized_pnl_usd
-            + self.short_book.unrealized_pnl_usd;
+/// Everything a cached validation depends on, in full. `context` (config, session scoring and
+/// gate registry) is shared by every entry; `entry` is the snapshot, direction and portfolio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ValidationCacheKey {
+    context: Vec<u8>,
+    entry: Vec<u8>,
+}
 
-        if current_value > self.daily_high_water_mark {
-            self.daily_high_water_mark = current_value;
+/// Recent `validate_entry` results keyed by snapshot content, direction, config and a coarse
+/// portfolio fingerprint, so an unchanged snapshot re-validated every scan skips the gates.
+/// Keys are compared whole, never by hash alone.
+#[derive(Debug)]
+pub struct ValidationCache {
+    context: Vec<u8>, // Context of every entry; a different one empties the cache
+    entries: std::collections::HashMap<Vec<u8>, (RiskValidation, DateTime<Utc>)>, // Result and expiry
+    ttl: chrono::Duration,
+    capacity: usize,
+    stats: ValidationCacheStats,
+}
+
+impl ValidationCache {
+    pub fn new(ttl: chrono::Duration, capacity: usize) -> Self {
+        Self {
+            context: Vec::new(),
+            entries: std::collections::HashMap::new(),
+            ttl,
+            capacity: capacity.max(1),
+            stats: ValidationCacheStats::default(),
         }
-        self.daily_drawdown_pct = if self.daily_high_water_mark > Decimal::ZERO {
-            (self.daily_high_water_mark - current_value) / self.daily_high_water_mark
-        } else {
-            Decimal::ZERO
-        };
+    }
 
-        if current_value > self.weekly_high_water_mark {
-            self.weekly_high_water_mark = current_value;
+    pub fn stats(&self) -> ValidationCacheStats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drops every entry when `context` isn't the one they were stored under
+    fn switch_context(&mut self, context: &[u8]) {
+        if self.context != context {
+            self.stats.evictions += self.entries.len() as u64;
+            self.entries.clear();
+            self.context = context.to_vec();
         }
-        self.weekly_drawdown_pct = if self.weekly_high_water_mark > Decimal::ZERO {
-            (self.weekly_high_water_mark - current_value) / self.weekly_high_water_mark
-        } else {
-            Decimal::ZERO
-        };
+    }
 
-        if current_value > self.monthly_high_water_mark {
-            self.monthly_high_water_mark = current_value;
+    /// A copy of the cached result under a fresh id and timestamp, if one is live
+    fn get(&mut self, key: &ValidationCacheKey, now: DateTime<Utc>) -> Option<RiskValidation> {
+        self.switch_context(&key.context);
+        match self.entries.get(&key.entry) {
+            Some((validation, expires_at)) if *expires_at > now => {
+                self.stats.hits += 1;
+                let mut validation = validation.clone();
+                validation.validation_id = Uuid::new_v4();
+                validation.validated_at = now;
+                Some(validation)
+            }
+            Some(_) => {
+                self.entries.remove(&key.entry);
+                self.stats.evictions += 1;
+                self.stats.misses += 1;
+                None
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
         }
-        self.monthly_drawdown_pct = if self.monthly_high_water_mark > Decimal::ZERO {
-            (self.monthly_high_water_mark - current_value) / self.monthly_high_water_mark
-        } else {
-            Decimal::ZERO
-        };
+    }
+
+    /// Stores a result, dropping expired entries and then the soonest to expire when full
+    fn insert(&mut self, key: ValidationCacheKey, validation: RiskValidation, now: DateTime<Utc>) {
+        self.switch_context(&key.context);
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key.entry) {
+            let before = self.entries.len();
+            self.entries.retain(|_, (_, expires_at)| *expires_at > now);
+            self.stats.evictions += (before - self.entries.len()) as u64;
+            if self.entries.len() >= self.capacity {
+                let soonest = self.entries.iter().min_by_key(|(_, (_, expires_at))| *expires_at).map(|(k, _)| k.clone());
+                if let Some(soonest) = soonest {
+                    self.entries.remove(&soonest);
+                    self.stats.evictions += 1;
+                }
+            }
+        }
+        self.entries.insert(key.entry, (validation, now + self.ttl));
+    }
+}
+
+/// A value `StrikeBoxEngine::warmup` precomputes ahead of the first entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WarmupComputation {
+    IdealSafetyScore,
+    LiquidityTiers,
+    ExposureBounds,
+}
+
+/// Position cap from one liquidity floor up, in share of capital and in dollars
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiquidityTier {
+    pub min_liquidity_usd: Decimal,
+    pub max_position_pct: Decimal,
+    pub max_position_usd: Decimal,
+}
+
+/// Dollar limits on book allocation and exposure at the current capital
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExposureBounds {
+    pub long_max_usd: Decimal,
+    pub short_max_usd: Decimal,
+    pub gross_max_usd: Decimal,
+    pub net_min_usd: Decimal,
+    pub net_max_usd: Decimal,
+}
+
+/// Values computed by `warmup`; refreshed on config and capital changes once warmed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComputedCache {
+    pub ideal_safety_score: Option<SafetyScore>, // Score of a token sitting at every preferred value
+    pub liquidity_tiers: Option<Vec<LiquidityTier>>,
+    pub exposure_bounds: Option<ExposureBounds>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarmupResult {
+    pub elapsed_ms: u64,
+    pub succeeded: Vec<WarmupComputation>,
+    pub failed: Vec<(WarmupComputation, String)>,
+}
+
+impl WarmupResult {
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
     }
 }
 
 // ============================================================
-// SECTION 14: AUDIT LOGGING
+// SECTION 11: POSITION STRUCTURES
 // ============================================================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EntryLog {
+pub struct Position {
     pub execution_id: Uuid,
-    pub timestamp: DateTime<Utc>,
     pub token_address: String,
     pub token_symbol: String,
     pub direction: Direction,
     pub entry_price: Decimal,
+    pub current_price: Decimal,
     pub position_size_tokens: Decimal,
     pub position_size_usd: Decimal,
-    pub liquidity_depth_usd: Decimal,
-    pub safety_score: Decimal,
-    pub holder_count: u32,
+    pub remaining_size_pct: Decimal,
+    pub liquidity_at_entry: Decimal,
+    pub safety_score_at_entry: Decimal,
+    pub holder_count_at_entry: u32,
     pub stop_loss_price: Decimal,
-    pub take_profit_prices: [Decimal; 3],
+    pub take_profit_targets: Vec<TpTarget>,
     pub risk_approval_id: Uuid,
-    pub latency_ms: u32,
-    pub slippage_bps: Decimal,
+    pub opened_at: DateTime<Utc>,
+    pub time_stop_at: Option<DateTime<Utc>>,
+    pub status: PositionStatus,
+    #[serde(default)]
+    pub closed_at: Option<DateTime<Utc>>, // When the last of the size was exited
+    pub trailing_stop_active: bool,
+    pub trailing_stop_high: Option<Decimal>,
+    #[serde(default)]
+    pub trailing_activation_price: Option<Decimal>, // Long only; trailing arms at or above this
+    pub unrealized_pnl_usd: Decimal,
+    pub unrealized_pnl_pct: Decimal,
+    #[serde(default)]
+    pub max_favorable_excursion_pct: Decimal, // Largest marked move in the position's favor, over entry
+    #[serde(default)]
+    pub max_adverse_excursion_pct: Decimal, // Largest marked move against it, as a positive share
+    #[serde(default = "default_leverage")]
+    pub leverage: Decimal, // Notional over margin; 1 is unlevered and never liquidates
+    #[serde(default)]
+    pub venue: String, // Fee schedule key; empty for positions booked before it was recorded
+    #[serde(default)]
+    pub safety_breakdown_at_entry: Option<SafetyScore>, // Component scores behind the entry; None for older positions
+    #[serde(default)]
+    pub score_history: Vec<(DateTime<Utc>, Decimal)>, // Rescored safety totals, oldest first, at most SCORE_HISTORY_CAPACITY
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExitLog {
-    pub execution_id: Uuid,
-    pub timestamp: DateTime<Utc>,
-    pub exit_price: Decimal,
-    pub exit_type: ExitType,
-    pub exit_size_pct: Decimal,
-    pub realized_pnl_tokens: Decimal,
-    pub realized_pnl_usd: Decimal,
-    pub slippage_bps: Decimal,
-    pub hold_duration_seconds: u64,
-    pub liquidity_depth_exit_usd: Decimal,
-}
+/// Rescored safety totals kept per position
+pub const SCORE_HISTORY_CAPACITY: usize = 96;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RejectionLog {
-    pub timestamp: DateTime<Utc>,
-    pub token_address: String,
-    pub token_symbol: String,
-    pub direction: Direction,
-    pub rejection_reason: String,
-    pub failed_gate: String,
-    pub safety_score: Option<Decimal>,
-    pub liquidity_usd: Option<Decimal>,
+/// Adverse move from entry, as a share of entry, at which a position at `leverage` is
+/// liquidated: its margin of 1/leverage less the maintenance margin. None when unlevered.
+pub fn liquidation_distance_pct(leverage: Decimal, maintenance_margin_pct: Decimal) -> Option<Decimal> {
+    (leverage > Decimal::ONE).then(|| (Decimal::ONE / leverage - maintenance_margin_pct).max(Decimal::ZERO))
 }
 
-// ============================================================
-// SECTION 15: OPERATIONAL COMMANDS
-// ============================================================
+/// Current liquidity below this share of liquidity at entry raises LiquidityDegraded (70%)
+pub const LIQUIDITY_DEGRADED_RATIO: Decimal = Decimal::from_parts(7, 0, 0, false, 1);
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum OperationalCommand {
-    PauseLongs,
-    PauseShorts,
-    PauseAll,
-    CloseLongs,
-    CloseShorts,
-    CloseAll,
-    Resume,
-    Status,
-    Exposure,
-    Risk,
-    Health,
-    Position { token: String },
-    History { token: String },
-    Pnl { timeframe: String },
-    Rejects { timeframe: String },
+/// Something a mark-to-market changed or noticed on a position
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PositionEvent {
+    TrailingStopActivated { price: Decimal },
+    TrailingHighUpdated { high: Decimal },
+    LiquidityDegraded { ratio: Decimal }, // Current over entry liquidity
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CommandResponse {
-    pub command: String,
-    pub success: bool,
-    pub message: String,
-    pub data: Option<serde_json::Value>,
-    pub executed_at: DateTime<Utc>,
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarkToMarketResult {
+    pub unrealized_pnl_delta: Decimal,
+    pub events: Vec<PositionEvent>,
 }
 
-// ============================================================
-// SECTION 16: MASTER CONFIGURATION
-// ============================================================
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StrikeBoxConfig {
-    pub token_validation: TokenValidationConfig,
-    pub safety_scoring: SafetyScoreConfig,
-    pub position_sizing: PositionSizingConfig,
-    pub stop_loss: StopLossConfig,
-    pub take_profit: TakeProfitConfig,
-    pub time_control: TimeControlConfig,
-    pub risk_controller: RiskControllerConfig,
+/// An exit level a price update reached, filled at the level's price
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TriggerEvent {
+    TakeProfit { level: usize, price: Decimal },
+    StopLoss { price: Decimal },
+    Liquidation { price: Decimal }, // Leveraged positions, when the venue liquidates before the stop fires
 }
 
-impl Default for StrikeBoxConfig {
-    fn default() -> Self {
-        Self {
-            token_validation: TokenValidationConfig::default(),
-            safety_scoring: SafetyScoreConfig::default(),
-            position_sizing: PositionSizingConfig::default(),
-            stop_loss: StopLossConfig::default(),
-            take_profit: TakeProfitConfig::default(),
-            time_control: TimeControlConfig::default(),
-            risk_controller: RiskControllerConfig::default(),
+impl TriggerEvent {
+    pub fn price(&self) -> Decimal {
+        match self {
+            TriggerEvent::TakeProfit { price, .. }
+            | TriggerEvent::StopLoss { price }
+            | TriggerEvent::Liquidation { price } => *price,
+        }
+    }
+
+    pub fn exit_type(&self) -> ExitType {
+        match self {
+            TriggerEvent::TakeProfit { level: 0, .. } => ExitType::TakeProfit1,
+            TriggerEvent::TakeProfit { level: 1, .. } => ExitType::TakeProfit2,
+            TriggerEvent::TakeProfit { .. } => ExitType::TakeProfit3,
+            TriggerEvent::StopLoss { .. } => ExitType::StopLoss,
+            TriggerEvent::Liquidation { .. } => ExitType::Liquidation,
         }
     }
 }
 
-// ============================================================
-// SECTION 17: STRIKE BOX ENGINE
-// ============================================================
+/// Exit levels one price update reached, in the order they are taken. Nothing follows a stop
+/// or a liquidation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerResolution {
+    pub events: Vec<TriggerEvent>,
+    pub assumption: TriggerPathAssumption,
+    pub ambiguous: bool, // Both a take-profit and the stop were reached; `assumption` picked the order
+}
 
-pub struct StrikeBoxEngine {
-    pub config: StrikeBoxConfig,
-    pub portfolio: PortfolioState,
-    pub entry_logs: Vec<EntryLog>,
-    pub exit_logs: Vec<ExitLog>,
-    pub rejection_logs: Vec<RejectionLog>,
+/// A take-profit price on an open position and the share of the original size it exits
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TpTarget {
+    pub price: Decimal,
+    pub hit: bool,
+    pub exit_pct: Decimal,
 }
 
-impl StrikeBoxEngine {
-    pub fn new(config: StrikeBoxConfig, total_capital: Decimal) -> Self {
-        let long_max = total_capital * config.position_sizing.long_book_max_pct;
-        let short_max = total_capital * config.position_sizing.short_book_max_pct;
+#[derive(Debug, Clone, PartialEq)]
+pub enum PositionError {
+    NonPositiveSize(Decimal),
+    NonPositivePrice(Decimal),
+    NotApproved(Uuid),
+}
 
-        Self {
-            config: config.clone(),
-            portfolio: PortfolioState {
-                total_capital_usd: total_capital,
-                available_capital_usd: total_capital,
-                long_book: PositionBook::new(
-                    Direction::Long,
-                    long_max,
-                    config.position_sizing.long_book_max_positions,
-                ),
-                short_book: PositionBook::new(
-                    Direction::Short,
-                    short_max,
-                    config.position_sizing.short_book_max_positions,
-                ),
-                gross_exposure_pct: Decimal::ZERO,
-                net_exposure_pct: Decimal::ZERO,
-                gross_exposure_usd: Decimal::ZERO,
-                net_exposure_usd: Decimal::ZERO,
-                daily_pnl_usd: Decimal::ZERO,
-                weekly_pnl_usd: Decimal::ZERO,
-                monthly_pnl_usd: Decimal::ZERO,
-                daily_drawdown_pct: Decimal::ZERO,
-                weekly_drawdown_pct: Decimal::ZERO,
-                monthly_drawdown_pct: Decimal::ZERO,
-                daily_high_water_mark: total_capital,
-                weekly_high_water_mark: total_capital,
-                monthly_high_water_mark: total_capital,
-                state: SystemState::Active,
-                consecutive_failures: 0,
-                last_updated: Utc::now(),
-            },
-            entry_logs: Vec::new(),
-            exit_logs: Vec::new(),
-            rejection_logs: Vec::new(),
+impl std::fmt::Display for PositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionError::NonPositiveSize(size) => write!(f, "Position size must be positive, got {}", size),
+            PositionError::NonPositivePrice(price) => write!(f, "Entry price must be positive, got {}", price),
+            PositionError::NotApproved(id) => write!(f, "Risk validation {} did not pass", id),
         }
     }
+}
 
-    pub fn validate_entry(&self, token: &TokenSnapshot, direction: Direction) -> RiskValidation {
-        let mut validation = RiskValidation::new(direction);
+impl std::error::Error for PositionError {}
 
-        match self.portfolio.state {
-            SystemState::Active => {
-                validation.add_gate("system_state", GateResult::Passed, None);
-            }
-            SystemState::PausedLongs if direction == Direction::Short => {
-                validation.add_gate("system_state", GateResult::Passed, None);
-            }
-            SystemState::PausedShorts if direction == Direction::Long => {
-                validation.add_gate("system_state", GateResult::Passed, None);
-            }
-            _ => {
-                validation.add_gate(
-                    "system_state",
-                    GateResult::Failed,
-                    Some(format!("System state {:?} blocks {:?} entries", self.portfolio.state, direction)),
-                );
-                return validation;
-            }
-        }
+/// Span for one position's lifecycle; every event inside it carries the `execution_id`
+pub fn strike_span(execution_id: Uuid, token_symbol: &str, direction: Direction) -> tracing::Span {
+    info_span!("strike", %execution_id, token = token_symbol, direction = ?direction)
+}
 
-        if !token.liquidity_in_range(&self.config.token_validation) {
-            validation.add_gate(
-                "liquidity_range",
-                GateResult::Failed,
-                Some(format!(
-                    "Liquidity ${} outside ${}-${} range",
-                    token.liquidity_usd,
-                    self.config.token_validation.liquidity_min_usd,
-                    self.config.token_validation.liquidity_max_usd
-                )),
-            );
-            return validation;
+impl Position {
+    pub fn span(&self) -> tracing::Span {
+        strike_span(self.execution_id, &self.token_symbol, self.direction)
+    }
+
+    /// Opens a position from a passed risk validation, deriving stops, targets and the short time stop from config.
+    /// `safety` is the score the entry was validated on; both the entry score and its breakdown come from it.
+    /// `recent_prices` are the token's observed prices, oldest first, for a volatility-scaled long stop.
+    pub fn open(
+        token: &TokenSnapshot,
+        validation: &RiskValidation,
+        entry_price: Decimal,
+        position_size_usd: Decimal,
+        safety: SafetyScore,
+        recent_prices: &[Decimal],
+        config: &StrikeBoxConfig,
+    ) -> Result<Self, PositionError> {
+        if !validation.all_passed {
+            return Err(PositionError::NotApproved(validation.validation_id));
+        }
+        if position_size_usd <= Decimal::ZERO {
+            return Err(PositionError::NonPositiveSize(position_size_usd));
+        }
+        if entry_price <= Decimal::ZERO {
+            return Err(PositionError::NonPositivePrice(entry_price));
         }
-        validation.add_gate("liquidity_range", GateResult::Passed, None);
 
-        let safety = SafetyScore::calculate(
-            token,
-            &self.config.safety_scoring,
-            &self.config.token_validation,
-        );
-        let score_ok = match direction {
-            Direction::Long => safety.qualifies_for_long(&self.config.safety_scoring),
-            Direction::Short => safety.qualifies_for_short(&self.config.safety_scoring),
+        let opened_at = Utc::now();
+        let (stop_loss_price, time_stop_at, trailing_activation_price) = match validation.direction {
+            Direction::Long => (
+                config.stop_loss.long_stop(entry_price, safety.total_score, recent_prices).0,
+                None,
+                Some(entry_price * (Decimal::ONE + config.stop_loss.long_trailing_activation_pct)),
+            ),
+            Direction::Short => (
+                config.stop_loss.short_stop_price(entry_price),
+                Some(config.time_control.short_time_stop(opened_at)),
+                None,
+            ),
         };
-        if !score_ok {
-            validation.add_gate(
-                "safety_score",
-                GateResult::Failed,
-                Some(format!(
-                    "Score {:.2} below {:?} threshold",
-                    safety.total_score,
-                    direction
-                )),
-            );
-            return validation;
+
+        Ok(Self {
+            execution_id: Uuid::new_v4(),
+            token_address: token.token_address.clone(),
+            token_symbol: token.token_symbol.clone(),
+            direction: validation.direction,
+            entry_price,
+            current_price: entry_price,
+            position_size_tokens: position_size_usd / entry_price,
+            position_size_usd,
+            remaining_size_pct: Decimal::ONE,
+            liquidity_at_entry: token.liquidity_usd,
+            safety_score_at_entry: safety.total_score,
+            holder_count_at_entry: token.holder_count,
+            stop_loss_price,
+            take_profit_targets: config.take_profit.targets(validation.direction, entry_price),
+            risk_approval_id: validation.validation_id,
+            opened_at,
+            time_stop_at,
+            status: PositionStatus::Open,
+            closed_at: None,
+            trailing_stop_active: false,
+            trailing_stop_high: None,
+            trailing_activation_price,
+            unrealized_pnl_usd: Decimal::ZERO,
+            unrealized_pnl_pct: Decimal::ZERO,
+            max_favorable_excursion_pct: Decimal::ZERO,
+            max_adverse_excursion_pct: Decimal::ZERO,
+            leverage: config.position_sizing.leverage,
+            venue: token.venue().to_string(),
+            safety_breakdown_at_entry: Some(safety),
+            score_history: Vec::new(),
+        })
+    }
+
+    /// Revalues the position at `new_price`, arms or ratchets the long trailing stop, and flags
+    /// liquidity that has drained below LIQUIDITY_DEGRADED_RATIO of entry
+    pub fn mark_to_market(&mut self, new_price: Decimal, new_liquidity: Decimal) -> MarkToMarketResult {
+        let mut events = Vec::new();
+        let previous_pnl = self.unrealized_pnl_usd;
+
+        // Polled every 100ms by the bot loop; nothing to recompute at the same price
+        if new_price != self.current_price {
+            self.current_price = new_price;
+            self.refresh_pnl();
+            self.track_excursion();
+
+            if self.direction == Direction::Long {
+                if self.trailing_stop_active {
+                    if let Some(hwm) = self.trailing_stop_high {
+                        if new_price > hwm {
+                            self.trailing_stop_high = Some(new_price);
+                            events.push(PositionEvent::TrailingHighUpdated { high: new_price });
+                        }
+                    }
+                } else if self.trailing_activation_price.is_some_and(|activation| new_price >= activation) {
+                    self.trailing_stop_active = true;
+                    self.trailing_stop_high = Some(new_price);
+                    events.push(PositionEvent::TrailingStopActivated { price: new_price });
+                }
+            }
         }
-        validation.add_gate("safety_score", GateResult::Passed, None);
 
-        if token.token_age_hours < self.config.token_validation.token_age_min_hours {
-            validation.add_gate(
-                "token_age",
-                GateResult::Failed,
-                Some(format!(
-                    "Token age {}h below {}h minimum",
-                    token.token_age_hours, self.config.token_validation.token_age_min_hours
-                )),
-            );
-            return validation;
+        if self.liquidity_at_entry > Decimal::ZERO
+            && new_liquidity < self.liquidity_at_entry * LIQUIDITY_DEGRADED_RATIO
+        {
+            events.push(PositionEvent::LiquidityDegraded { ratio: new_liquidity / self.liquidity_at_entry });
         }
-        validation.add_gate("token_age", GateResult::Passed, None);
 
-        if self.config.token_validation.require_verified_contract && !token.contract_verified {
-            validation.add_gate(
-                "contract_verification",
-                GateResult::Failed,
-                Some("Contract not verified".to_string()),
-            );
-            return validation;
+        MarkToMarketResult {
+            unrealized_pnl_delta: self.unrealized_pnl_usd - previous_pnl,
+            events,
         }
-        validation.add_gate("contract_verification", GateResult::Passed, None);
+    }
 
-        if !token.holder_distribution_valid(&self.config.token_validation) {
-            validation.add_gate(
-                "holder_distribution",
-                GateResult::Failed,
-                Some(format!(
-                    "Holders {} or concentration {:.1}% fails requirements",
-                    token.holder_count, token.top_10_concentration_pct
-                )),
-            );
-            return validation;
+    /// Gives a long booked before the activation price was recorded one at
+    /// `long_trailing_activation_pct` over entry, so its trailing stop can still arm
+    pub fn backfill_trailing_activation(&mut self, stop_loss: &StopLossConfig) {
+        if self.direction == Direction::Long && self.trailing_activation_price.is_none() {
+            self.trailing_activation_price = Some(self.entry_price * (Decimal::ONE + stop_loss.long_trailing_activation_pct));
         }
-        validation.add_gate("holder_distribution", GateResult::Passed, None);
+    }
 
-        let book = match direction {
-            Direction::Long => &self.portfolio.long_book,
-            Direction::Short => &self.portfolio.short_book,
-        };
-        if book.position_count() >= book.max_positions {
-            validation.add_gate(
-                "book_capacity",
-                GateResult::Failed,
-                Some(format!("{:?} book at max {} positions", direction, book.max_positions)),
-            );
-            return validation;
+    fn track_excursion(&mut self) {
+        if self.entry_price <= Decimal::ZERO {
+            return;
         }
-        validation.add_gate("book_capacity", GateResult::Passed, None);
+        let favorable_move = match self.direction {
+            Direction::Long => (self.current_price - self.entry_price) / self.entry_price,
+            Direction::Short => (self.entry_price - self.current_price) / self.entry_price,
+        };
+        self.max_favorable_excursion_pct = self.max_favorable_excursion_pct.max(favorable_move);
+        self.max_adverse_excursion_pct = self.max_adverse_excursion_pct.max(-favorable_move);
+    }
 
-        if book.has_position(&token.token_address) {
+    /// Recomputes unrealized P&L at the current price; call after changing `remaining_size_pct`
+    pub fn refresh_pnl(&mut self) {
+        self.unrealized_pnl_usd = match self.direction {
+            Direction::Long => {
+                (self.current_price - self.entry_price) * self.position_size_tokens * self.remaining_size_pct
+            }
+            Direction::Short => {
+                (self.entry_price - self.current_price) * self.position_size_tokens * self.remaining_size_pct
+            }
+        };
+        self.unrealized_pnl_pct = self.return_on_remaining();
+    }
+
+    /// Unrealized P&L over the cost basis still held, i.e. after partial exits
+    pub fn return_on_remaining(&self) -> Decimal {
+        Self::guarded_ratio(self.unrealized_pnl_usd, self.position_size_usd * self.remaining_size_pct)
+    }
+
+    /// Unrealized P&L over the full original position size
+    pub fn return_on_initial(&self) -> Decimal {
+        Self::guarded_ratio(self.unrealized_pnl_usd, self.position_size_usd)
+    }
+
+    fn guarded_ratio(pnl: Decimal, basis: Decimal) -> Decimal {
+        if basis <= Decimal::ZERO {
+            Decimal::ZERO
+        } else {
+            pnl / basis
+        }
+    }
+
+    pub fn stop_triggered(&self) -> bool {
+        match self.direction {
+            Direction::Long => self.current_price <= self.stop_loss_price,
+            Direction::Short => self.current_price >= self.stop_loss_price,
+        }
+    }
+
+    /// Index of the first unhit target the current price has reached
+    pub fn check_take_profits(&self) -> Option<usize> {
+        self.take_profit_targets.iter().position(|target| {
+            !target.hit
+                && match self.direction {
+                    Direction::Long => self.current_price >= target.price,
+                    Direction::Short => self.current_price <= target.price,
+                }
+        })
+    }
+
+    /// Marks target `level` hit and exits its share of the original size, capped at what remains.
+    /// Returns the share exited, or None if the level does not exist or was already hit.
+    pub fn apply_take_profit(&mut self, level: usize) -> Option<Decimal> {
+        let target = self.take_profit_targets.get_mut(level).filter(|t| !t.hit)?;
+        target.hit = true;
+        let exit_pct = target.exit_pct;
+        Some(self.apply_partial_exit(exit_pct))
+    }
+
+    /// Takes `exit_pct` of the original size off the position, capped at what remains, and
+    /// revalues the rest. Returns the share actually exited.
+    pub fn apply_partial_exit(&mut self, exit_pct: Decimal) -> Decimal {
+        let exited = exit_pct.max(Decimal::ZERO).min(self.remaining_size_pct);
+        self.remaining_size_pct -= exited;
+        self.status = if self.remaining_size_pct <= Decimal::ZERO {
+            self.closed_at.get_or_insert_with(Utc::now);
+            PositionStatus::Closed
+        } else {
+            PositionStatus::PartialExit
+        };
+        self.refresh_pnl();
+        exited
+    }
+
+    /// Takes each resolved event in order at its level price. Returns one exit per event that
+    /// still had size to exit, each recording the path assumption that ordered it.
+    pub fn apply_triggers(&mut self, resolution: &TriggerResolution, liquidity_at_exit: Decimal) -> Vec<PositionExit> {
+        let mut exits = Vec::new();
+        for event in &resolution.events {
+            if self.remaining_size_pct <= Decimal::ZERO {
+                break;
+            }
+            let price = event.price();
+            let exited = match event {
+                TriggerEvent::TakeProfit { level, .. } => match self.apply_take_profit(*level) {
+                    Some(exited) => exited,
+                    None => continue,
+                },
+                TriggerEvent::StopLoss { .. } | TriggerEvent::Liquidation { .. } => {
+                    self.apply_partial_exit(self.remaining_size_pct)
+                }
+            };
+            if matches!(event, TriggerEvent::Liquidation { .. }) {
+                self.status = PositionStatus::Liquidated;
+            }
+            let mut exit = self.exit_record(price, exited, event.exit_type(), liquidity_at_exit);
+            exit.trigger_assumption = Some(resolution.assumption);
+            exits.push(exit);
+        }
+        exits
+    }
+
+    /// Takes everything that remains off at `price` as a manual exit
+    pub fn close_at(&mut self, price: Decimal, liquidity_at_exit: Decimal) -> Option<PositionExit> {
+        if self.remaining_size_pct <= Decimal::ZERO {
+            return None;
+        }
+        self.mark_to_market(price, liquidity_at_exit);
+        let exited = self.apply_partial_exit(self.remaining_size_pct);
+        Some(self.exit_record(price, exited, ExitType::Manual, liquidity_at_exit))
+    }
+
+    /// Takes everything that remains off at `price` as a liquidation, net of `fees_usd`
+    pub fn liquidate_at(&mut self, price: Decimal, liquidity_at_exit: Decimal, fees_usd: Decimal) -> Option<PositionExit> {
+        if self.remaining_size_pct <= Decimal::ZERO {
+            return None;
+        }
+        self.mark_to_market(price, liquidity_at_exit);
+        let exited = self.apply_partial_exit(self.remaining_size_pct);
+        self.status = PositionStatus::Liquidated;
+        let mut exit = self.exit_record(price, exited, ExitType::Liquidation, liquidity_at_exit);
+        exit.charge_fees(fees_usd);
+        Some(exit)
+    }
+
+    /// Takes this position's trading fees off `exit`: the exited share of the entry's taker fee,
+    /// plus the exit's own fee at the maker rate for a take-profit level and the taker rate
+    /// otherwise. A liquidation's exit fee is the venue's, passed to `liquidate_at`.
+    pub fn charge_fees(&self, exit: &mut PositionExit, fees: &FeeModel) {
+        let schedule = fees.for_venue(&self.venue);
+        let entry_fee = schedule.fee_usd(self.position_size_usd, false) * exit.exit_size_pct;
+        let exit_fee = match exit.exit_type {
+            ExitType::Liquidation => Decimal::ZERO,
+            exit_type => schedule.fee_usd(exit.exit_size_usd, exit_type.is_take_profit()),
+        };
+        exit.charge_fees(entry_fee + exit_fee);
+    }
+
+    /// Neither closed nor liquidated
+    pub fn is_open(&self) -> bool {
+        !matches!(self.status, PositionStatus::Closed | PositionStatus::Liquidated)
+    }
+
+    /// Price at which the venue liquidates the position; None when unlevered
+    pub fn liquidation_price(&self, maintenance_margin_pct: Decimal) -> Option<Decimal> {
+        let distance = liquidation_distance_pct(self.leverage, maintenance_margin_pct)?;
+        Some(match self.direction {
+            Direction::Long => self.entry_price * (Decimal::ONE - distance),
+            Direction::Short => self.entry_price * (Decimal::ONE + distance),
+        })
+    }
+
+    fn exit_record(&self, price: Decimal, exited: Decimal, exit_type: ExitType, liquidity_at_exit: Decimal) -> PositionExit {
+        let tokens = self.position_size_tokens * exited;
+        let per_token = match self.direction {
+            Direction::Long => price - self.entry_price,
+            Direction::Short => self.entry_price - price,
+        };
+        let exited_at = Utc::now();
+        PositionExit {
+            execution_id: self.execution_id,
+            exit_price: price,
+            exit_type,
+            exit_size_pct: exited,
+            exit_size_usd: tokens * price,
+            realized_pnl_tokens: if price > Decimal::ZERO { per_token * tokens / price } else { Decimal::ZERO },
+            realized_pnl_usd: per_token * tokens,
+            fees_usd: Decimal::ZERO,
+            slippage_bps: Decimal::ZERO,
+            hold_duration_seconds: (exited_at - self.opened_at).num_seconds().max(0) as u64,
+            liquidity_at_exit,
+            exited_at,
+            trigger_assumption: None,
+        }
+    }
+
+    pub fn take_profit_prices(&self) -> Vec<Decimal> {
+        self.take_profit_targets.iter().map(|t| t.price).collect()
+    }
+
+    /// Time left before the hard time stop, zero once it has passed; None when no time stop is set
+    pub fn time_remaining(&self) -> Option<chrono::Duration> {
+        self.time_stop_at
+            .map(|deadline| (deadline - Utc::now()).max(chrono::Duration::zero()))
+    }
+
+    pub fn is_past_time_stop(&self) -> bool {
+        self.time_stop_at.map(|deadline| Utc::now() >= deadline).unwrap_or(false)
+    }
+
+    pub fn trailing_stop_triggered(&self, config: &StopLossConfig) -> bool {
+        if !self.trailing_stop_active {
+            return false;
+        }
+        if let Some(hwm) = self.trailing_stop_high {
+            let trailing_price = config.trailing_stop_price(hwm);
+            self.current_price <= trailing_price
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionExit {
+    pub execution_id: Uuid,
+    pub exit_price: Decimal,
+    pub exit_type: ExitType,
+    pub exit_size_pct: Decimal,
+    #[serde(default)]
+    pub exit_size_usd: Decimal, // Exited tokens at the exit price
+    pub realized_pnl_tokens: Decimal,
+    pub realized_pnl_usd: Decimal, // Net of fees_usd
+    #[serde(default)]
+    pub fees_usd: Decimal,
+    pub slippage_bps: Decimal,
+    pub hold_duration_seconds: u64,
+    pub liquidity_at_exit: Decimal,
+    pub exited_at: DateTime<Utc>,
+    pub trigger_assumption: Option<TriggerPathAssumption>, // Set when a price update triggered the exit
+}
+
+impl PositionExit {
+    /// Dollars lost to slippage on this exit
+    pub fn slippage_cost_usd(&self) -> Decimal {
+        self.exit_size_usd * self.slippage_bps / Decimal::new(10_000, 0)
+    }
+
+    /// Takes `fees_usd` off realized P&L and adds it to the exit's fees
+    pub fn charge_fees(&mut self, fees_usd: Decimal) {
+        self.fees_usd += fees_usd;
+        self.realized_pnl_usd -= fees_usd;
+        if self.exit_price > Decimal::ZERO {
+            self.realized_pnl_tokens = self.realized_pnl_usd / self.exit_price;
+        }
+    }
+}
+
+// ============================================================
+// SECTION 12: BOOK MANAGEMENT
+// ============================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionBook {
+    pub direction: Direction,
+    pub positions: Vec<Position>,
+    pub total_allocation_usd: Decimal,
+    pub max_allocation_usd: Decimal,
+    pub max_positions: u32,
+    pub realized_pnl_usd: Decimal,
+    pub unrealized_pnl_usd: Decimal,
+    #[serde(default)]
+    pub reserved_usd: Decimal, // Planned size of entries reserved but not yet committed
+    #[serde(default)]
+    pub reserved_slots: u32,
+    #[serde(default)]
+    pub costs_usd: Decimal, // Slippage and fees, already taken out of realized_pnl_usd
+}
+
+impl PositionBook {
+    pub fn new(direction: Direction, max_allocation_usd: Decimal, max_positions: u32) -> Self {
+        Self {
+            direction,
+            positions: Vec::with_capacity(max_positions as usize),
+            total_allocation_usd: Decimal::ZERO,
+            max_allocation_usd,
+            max_positions,
+            realized_pnl_usd: Decimal::ZERO,
+            unrealized_pnl_usd: Decimal::ZERO,
+            reserved_usd: Decimal::ZERO,
+            reserved_slots: 0,
+            costs_usd: Decimal::ZERO,
+        }
+    }
+
+    pub fn can_add_position(&self, size_usd: Decimal) -> bool {
+        self.slots_in_use() < self.max_positions
+            && (self.total_allocation_usd + self.reserved_usd + size_usd) <= self.max_allocation_usd
+    }
+
+    pub fn position_count(&self) -> u32 {
+        self.positions.len() as u32
+    }
+
+    /// Positions neither closed nor liquidated
+    pub fn open_position_count(&self) -> u32 {
+        self.positions.iter().filter(|p| p.is_open()).count() as u32
+    }
+
+    /// Positions plus outstanding entry reservations
+    pub fn slots_in_use(&self) -> u32 {
+        self.position_count() + self.reserved_slots
+    }
+
+    pub fn available_capacity_usd(&self) -> Decimal {
+        self.max_allocation_usd - self.total_allocation_usd - self.reserved_usd
+    }
+
+    /// True when limits were lowered below current holdings; no new entries until back under
+    pub fn is_over_capacity(&self) -> bool {
+        self.position_count() > self.max_positions
+            || self.total_allocation_usd > self.max_allocation_usd
+    }
+
+    /// Realized and unrealized P&L with costs added back
+    pub fn gross_pnl_usd(&self) -> Decimal {
+        self.realized_pnl_usd + self.unrealized_pnl_usd + self.costs_usd
+    }
+
+    pub fn update_unrealized_pnl(&mut self) {
+        self.unrealized_pnl_usd = self.positions.iter().map(|p| p.unrealized_pnl_usd).sum();
+    }
+
+    pub fn has_position(&self, token_address: &str) -> bool {
+        self.positions
+            .iter()
+            .any(|p| p.token_address == token_address && p.status == PositionStatus::Open)
+    }
+
+    pub fn get_position(&self, token_address: &str) -> Option<&Position> {
+        self.positions
+            .iter()
+            .find(|p| p.token_address == token_address && p.status == PositionStatus::Open)
+    }
+
+    /// Open positions whose time stop falls within `within` of now, most urgent first
+    pub fn positions_approaching_time_stop(
+        &self,
+        within: chrono::Duration,
+    ) -> Vec<(&Position, chrono::Duration)> {
+        let mut approaching: Vec<(&Position, chrono::Duration)> = self
+            .positions
+            .iter()
+            .filter(|p| p.status == PositionStatus::Open)
+            .filter_map(|p| p.time_remaining().map(|remaining| (p, remaining)))
+            .filter(|(_, remaining)| *remaining <= within)
+            .collect();
+        approaching.sort_by_key(|(p, remaining)| (*remaining, p.time_stop_at));
+        approaching
+    }
+
+    pub fn get_position_mut(&mut self, token_address: &str) -> Option<&mut Position> {
+        self.positions
+            .iter_mut()
+            .find(|p| p.token_address == token_address && p.status == PositionStatus::Open)
+    }
+
+    /// Drops closed and liquidated positions that ended more than `retention` ago, returning
+    /// how many went. Partially exited positions stay. Realized P&L is already booked on the
+    /// book and is left as is; positions closed before `closed_at` was recorded age from entry.
+    pub fn compact(&mut self, retention: chrono::Duration) -> usize {
+        let cutoff = Utc::now() - retention;
+        let before = self.positions.len();
+        self.positions.retain(|p| {
+            !matches!(p.status, PositionStatus::Closed | PositionStatus::Liquidated)
+                || p.closed_at.unwrap_or(p.opened_at) >= cutoff
+        });
+        before - self.positions.len()
+    }
+
+    fn reserve(&mut self, size_usd: Decimal) {
+        self.reserved_usd += size_usd;
+        self.reserved_slots += 1;
+    }
+
+    fn release_reservation(&mut self, size_usd: Decimal) {
+        self.reserved_usd = (self.reserved_usd - size_usd).max(Decimal::ZERO);
+        self.reserved_slots = self.reserved_slots.saturating_sub(1);
+    }
+}
+
+/// Default lifetime of an entry reservation before its capacity is released
+pub const ENTRY_RESERVATION_TTL_SECONDS: i64 = 30;
+/// Default age past which closed positions are compacted out of the books
+pub const POSITION_RETENTION_HOURS: i64 = 24;
+/// How often tick compacts the books
+pub const BOOK_COMPACTION_INTERVAL_SECONDS: i64 = 3_600;
+
+pub type ReservationId = Uuid;
+
+/// One execution against a reserved entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub price: Decimal,
+    pub size_tokens: Decimal,
+    pub filled_at: DateTime<Utc>,
+}
+
+/// Book capacity held for a validated entry while its order works
+#[derive(Debug, Clone)]
+pub struct EntryReservation {
+    pub reservation_id: ReservationId,
+    pub token: TokenSnapshot,
+    pub validation: RiskValidation,
+    pub safety: SafetyScore, // Score the entry was validated on; the position opens with it
+    pub planned_size_usd: Decimal,
+    pub reserved_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ReservationError {
+    Rejected(RiskValidation),
+    NoCapacity { direction: Direction, planned_size_usd: Decimal },
+    NonPositiveSize(Decimal),
+    UnknownReservation(ReservationId), // Never made, already committed or cancelled, or expired
+    BelowMinFill { filled_pct: Decimal, min_pct: Decimal },
+    Overfilled { filled_usd: Decimal, planned_size_usd: Decimal, available_usd: Decimal }, // Excess over plan the book can't absorb
+    Position(PositionError),
+}
+
+impl std::fmt::Display for ReservationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReservationError::Rejected(validation) => {
+                let gate = validation.gates.iter().find(|g| g.result.blocks_entry());
+                match gate {
+                    Some(gate) => write!(f, "Entry rejected at {} gate", gate.gate_name),
+                    None => write!(f, "Entry rejected"),
+                }
+            }
+            ReservationError::NoCapacity { direction, planned_size_usd } => {
+                write!(f, "{:?} book has no room for ${:.2}", direction, planned_size_usd)
+            }
+            ReservationError::NonPositiveSize(size) => write!(f, "Planned size must be positive, got {}", size),
+            ReservationError::UnknownReservation(id) => write!(f, "No live reservation {}", id),
+            ReservationError::BelowMinFill { filled_pct, min_pct } => write!(
+                f,
+                "Filled {:.2}% of plan, below {:.2}% minimum - cancel the reservation and retry",
+                filled_pct * Decimal::ONE_HUNDRED,
+                min_pct * Decimal::ONE_HUNDRED
+            ),
+            ReservationError::Overfilled { filled_usd, planned_size_usd, available_usd } => write!(
+                f,
+                "Filled ${:.2} against ${:.2} planned, more than the ${:.2} the book has left - reduce the fill and retry",
+                filled_usd, planned_size_usd, available_usd
+            ),
+            ReservationError::Position(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReservationError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PositionSortKey {
+    #[default]
+    Age,
+    Pnl,  // Unrealized PnL pct
+    Size, // Remaining notional
+}
+
+/// Which positions `StrikeBoxEngine::query_positions` returns. Unset fields match everything;
+/// without a status, closed positions are left out.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PositionFilter {
+    pub direction: Option<Direction>,
+    pub status: Option<PositionStatus>,
+    pub token: Option<String>, // Substring of the address or symbol, ignoring ASCII case
+    pub min_pnl_pct: Option<Decimal>,
+    pub max_pnl_pct: Option<Decimal>,
+    pub opened_after: Option<DateTime<Utc>>,
+    pub opened_before: Option<DateTime<Utc>>,
+    pub trailing_active: Option<bool>,
+    pub pending_exit: Option<bool>, // Stop, trailing stop, take-profit or time stop reached on the last mark
+    pub sort_by: PositionSortKey,
+    pub descending: bool, // Ties keep book order (longs, then shorts) either way
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl PositionFilter {
+    fn matches(&self, position: &Position, pending_exit: bool) -> bool {
+        let in_range = |value: Decimal, min: Option<Decimal>, max: Option<Decimal>| {
+            min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+        };
+        self.direction.is_none_or(|d| position.direction == d)
+            && match self.status {
+                Some(status) => position.status == status,
+                None => position.is_open(),
+            }
+            && self.token.as_deref().is_none_or(|needle| {
+                contains_ignore_ascii_case(&position.token_address, needle)
+                    || contains_ignore_ascii_case(&position.token_symbol, needle)
+            })
+            && in_range(position.unrealized_pnl_pct, self.min_pnl_pct, self.max_pnl_pct)
+            && self.opened_after.is_none_or(|after| position.opened_at >= after)
+            && self.opened_before.is_none_or(|before| position.opened_at < before)
+            && self.trailing_active.is_none_or(|active| position.trailing_stop_active == active)
+            && self.pending_exit.is_none_or(|pending| pending_exit == pending)
+    }
+}
+
+fn contains_ignore_ascii_case(haystack: &str, needle: &str) -> bool {
+    needle.is_empty()
+        || haystack
+            .as_bytes()
+            .windows(needle.len())
+            .any(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// Read-only summary of one position with a few derived fields
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionView<'a> {
+    pub execution_id: Uuid,
+    pub token_address: &'a str,
+    pub token_symbol: &'a str,
+    pub direction: Direction,
+    pub status: PositionStatus,
+    pub entry_price: Decimal,
+    pub current_price: Decimal,
+    pub remaining_size_usd: Decimal,
+    pub unrealized_pnl_usd: Decimal,
+    pub unrealized_pnl_pct: Decimal,
+    pub opened_at: DateTime<Utc>,
+    pub age_seconds: i64,
+    pub distance_to_stop_pct: Decimal, // Adverse move from the current price to the stop; negative once through it
+    pub next_take_profit: Option<TpTarget>,
+    pub trailing_stop_active: bool,
+    pub pending_exit: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionPage<'a> {
+    pub items: Vec<PositionView<'a>>,
+    pub total: usize, // Matches before pagination
+    pub offset: usize,
+}
+
+impl PositionPage<'_> {
+    pub fn has_more(&self) -> bool {
+        self.offset + self.items.len() < self.total
+    }
+}
+
+// ============================================================
+// SECTION 13: PORTFOLIO STATE
+// ============================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioState {
+    pub total_capital_usd: Decimal,
+    pub available_capital_usd: Decimal,
+    pub long_book: PositionBook,
+    pub short_book: PositionBook,
+    pub gross_exposure_pct: Decimal,
+    pub net_exposure_pct: Decimal,
+    pub gross_exposure_usd: Decimal,
+    pub net_exposure_usd: Decimal,
+    pub daily_pnl_usd: Decimal,
+    pub weekly_pnl_usd: Decimal,
+    pub monthly_pnl_usd: Decimal,
+    pub daily_drawdown_pct: Decimal,
+    pub weekly_drawdown_pct: Decimal,
+    pub monthly_drawdown_pct: Decimal,
+    pub daily_high_water_mark: Decimal,
+    pub weekly_high_water_mark: Decimal,
+    pub monthly_high_water_mark: Decimal,
+    #[serde(default)]
+    pub daily_realized_drawdown_pct: Decimal, // From the daily high of capital plus realized P&L
+    #[serde(default)]
+    pub daily_unrealized_drawdown_pct: Decimal, // Open loss over capital plus realized P&L
+    #[serde(default)]
+    pub daily_realized_high_water_mark: Decimal,
+    pub state: SystemState,
+    pub consecutive_failures: u32,
+    #[serde(default)]
+    pub total_slippage_cost_usd: Decimal, // Summed over manual and automatic exits
+    #[serde(default)]
+    pub liquidation_count: u32, // Positions force-closed by the venue
+    #[serde(default)]
+    pub drawdown_baseline: DrawdownBaseline, // Books at the last marked-equity peak
+    pub last_updated: DateTime<Utc>,
+}
+
+/// Marked equity and each book's gross P&L and costs at the last marked-equity peak
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DrawdownBaseline {
+    pub marked_equity_usd: Decimal,
+    pub long_gross_pnl_usd: Decimal,
+    pub short_gross_pnl_usd: Decimal,
+    pub costs_usd: Decimal,
+}
+
+/// Shares of the decline from the marked-equity peak. A positive share added to the
+/// drawdown; a negative one offset it. The four sum to one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DrawdownAttribution {
+    pub drawdown_usd: Decimal, // Zero at or above the peak, when every share is zero too
+    pub long_book_contribution: Decimal,
+    pub short_book_contribution: Decimal,
+    pub fee_drag_contribution: Decimal,
+    pub unexplained: Decimal,
+}
+
+/// Largest peak-to-trough decline in an equity series
+struct DrawdownEpisode {
+    peak: Decimal,
+    trough_at: DateTime<Utc>,
+    drawdown_pct: Decimal,
+}
+
+fn worst_drawdown<'a>(equity: impl IntoIterator<Item = &'a (DateTime<Utc>, Decimal)>) -> Option<DrawdownEpisode> {
+    let mut peak: Option<Decimal> = None;
+    let mut worst: Option<DrawdownEpisode> = None;
+    for &(at, value) in equity {
+        let high = match peak {
+            Some(high) if high >= value => high,
+            _ => {
+                peak = Some(value);
+                continue;
+            }
+        };
+        if high <= Decimal::ZERO {
+            continue;
+        }
+        let drawdown_pct = (high - value) / high;
+        if worst.as_ref().is_none_or(|w| drawdown_pct > w.drawdown_pct) {
+            worst = Some(DrawdownEpisode { peak: high, trough_at: at, drawdown_pct });
+        }
+    }
+    worst
+}
+
+impl PortfolioState {
+    pub fn calculate_exposure(&mut self) {
+        let long_exposure = self.long_book.total_allocation_usd;
+        let short_exposure = self.short_book.total_allocation_usd;
+
+        self.gross_exposure_usd = long_exposure + short_exposure;
+        self.net_exposure_usd = long_exposure - short_exposure;
+
+        if self.total_capital_usd > Decimal::ZERO {
+            self.gross_exposure_pct = self.gross_exposure_usd / self.total_capital_usd;
+            self.net_exposure_pct = self.net_exposure_usd / self.total_capital_usd;
+        }
+
+        self.available_capital_usd = self.total_capital_usd - self.gross_exposure_usd;
+        self.last_updated = Utc::now();
+    }
+
+    /// The state drawdowns call for: past the monthly review limit halts, a weekly or realized
+    /// daily breach pauses all entries, and an unrealized daily breach alone soft-halts
+    pub fn check_drawdown_limits(&self, config: &RiskControllerConfig) -> SystemState {
+        if self.monthly_drawdown_pct >= config.monthly_review_pct {
+            return SystemState::EmergencyHalt;
+        }
+        if self.weekly_drawdown_pct >= config.weekly_drawdown_halt_pct {
+            return SystemState::PausedAll;
+        }
+        if self.daily_realized_drawdown_pct >= config.daily_realized_halt_pct {
+            return SystemState::PausedAll;
+        }
+        if self.daily_unrealized_drawdown_pct >= config.daily_unrealized_soft_halt_pct {
+            return SystemState::SoftHalt;
+        }
+        SystemState::Active
+    }
+
+    pub fn net_exposure_valid(&self, config: &RiskControllerConfig) -> bool {
+        self.net_exposure_pct >= config.net_exposure_min_pct
+            && self.net_exposure_pct <= config.net_exposure_max_pct
+    }
+
+    pub fn realized_pnl_usd(&self) -> Decimal {
+        self.long_book.realized_pnl_usd + self.short_book.realized_pnl_usd
+    }
+
+    /// Realized P&L before slippage: exits are booked at their fill prices, so the cost is
+    /// added back
+    pub fn gross_pnl_usd(&self) -> Decimal {
+        self.realized_pnl_usd() + self.total_slippage_cost_usd
+    }
+
+    /// Slippage cost as a share of gross realized P&L; None until gross P&L is positive
+    pub fn slippage_as_pct_of_pnl(&self) -> Option<Decimal> {
+        let gross = self.gross_pnl_usd();
+        (gross > Decimal::ZERO).then(|| self.total_slippage_cost_usd / gross)
+    }
+
+    pub fn portfolio_summary(&self) -> PortfolioSummary {
+        PortfolioSummary {
+            state: self.state,
+            total_capital_usd: self.total_capital_usd,
+            available_capital_usd: self.available_capital_usd,
+            gross_exposure_pct: self.gross_exposure_pct,
+            net_exposure_pct: self.net_exposure_pct,
+            realized_pnl_usd: self.realized_pnl_usd(),
+            unrealized_pnl_usd: self.long_book.unrealized_pnl_usd + self.short_book.unrealized_pnl_usd,
+            daily_drawdown_pct: self.daily_drawdown_pct,
+            total_slippage_cost_usd: self.total_slippage_cost_usd,
+            slippage_as_pct_of_pnl: self.slippage_as_pct_of_pnl(),
+        }
+    }
+
+    /// Tamper-evident snapshot: the state as JSON plus an HMAC-SHA256 under `signing_key`
+    /// covering both the JSON and the snapshot time
+    pub fn serialize_for_audit(&self, signing_key: &[u8]) -> AuditSnapshot {
+        let payload_json =
+            serde_json::to_string(self).expect("PortfolioState always serializes to JSON");
+        let timestamp = Utc::now();
+        let hmac_sha256 =
+            to_hex(&audit_mac(signing_key, timestamp, &payload_json).finalize().into_bytes());
+        AuditSnapshot {
+            payload_json,
+            timestamp,
+            hmac_sha256,
+        }
+    }
+
+    /// Largest peak-to-trough decline, as a fraction of the peak, in the points of
+    /// `equity_history` (oldest first) from `start` on
+    pub fn max_drawdown_since(start: DateTime<Utc>, equity_history: &[(DateTime<Utc>, Decimal)]) -> Decimal {
+        worst_drawdown(equity_history.iter().filter(|(at, _)| *at >= start))
+            .map_or(Decimal::ZERO, |worst| worst.drawdown_pct)
+    }
+
+    /// How long equity took to climb from the trough of the largest drawdown in
+    /// `equity_history` (oldest first) back to the prior peak. None with no drawdown, or while
+    /// still below that peak.
+    pub fn recovery_time_from_trough(equity_history: &[(DateTime<Utc>, Decimal)]) -> Option<chrono::Duration> {
+        let worst = worst_drawdown(equity_history)?;
+        equity_history
+            .iter()
+            .find(|(at, value)| *at > worst.trough_at && *value >= worst.peak)
+            .map(|(recovered_at, _)| *recovered_at - worst.trough_at)
+    }
+
+    /// Capital plus unrealized PnL on both books
+    pub fn equity_usd(&self) -> Decimal {
+        self.total_capital_usd + self.long_book.unrealized_pnl_usd + self.short_book.unrealized_pnl_usd
+    }
+
+    /// Capital plus realized P&L on both books
+    pub fn realized_equity_usd(&self) -> Decimal {
+        self.total_capital_usd + self.realized_pnl_usd()
+    }
+
+    /// Capital plus realized and unrealized P&L on both books
+    pub fn marked_equity_usd(&self) -> Decimal {
+        self.realized_equity_usd() + self.long_book.unrealized_pnl_usd + self.short_book.unrealized_pnl_usd
+    }
+
+    /// How much of the decline in marked equity since its last peak came from each book's
+    /// trading and how much from slippage and fees. Unlike `daily_drawdown_pct`, marked equity
+    /// counts realized P&L, so closed losses and their costs show up here.
+    pub fn drawdown_attribution(&self) -> DrawdownAttribution {
+        let baseline = &self.drawdown_baseline;
+        let drawdown_usd = baseline.marked_equity_usd - self.marked_equity_usd();
+        if drawdown_usd <= Decimal::ZERO {
+            return DrawdownAttribution::default();
+        }
+        let long_book_contribution = (baseline.long_gross_pnl_usd - self.long_book.gross_pnl_usd()) / drawdown_usd;
+        let short_book_contribution = (baseline.short_gross_pnl_usd - self.short_book.gross_pnl_usd()) / drawdown_usd;
+        let costs_usd = self.long_book.costs_usd + self.short_book.costs_usd;
+        let fee_drag_contribution = (costs_usd - baseline.costs_usd) / drawdown_usd;
+        DrawdownAttribution {
+            drawdown_usd,
+            long_book_contribution,
+            short_book_contribution,
+            fee_drag_contribution,
+            unexplained: Decimal::ONE - long_book_contribution - short_book_contribution - fee_drag_contribution,
+        }
+    }
+
+    pub fn update_drawdowns(&mut self) {
+        let marked_equity = self.marked_equity_usd();
+        if marked_equity >= self.drawdown_baseline.marked_equity_usd {
+            self.drawdown_baseline = DrawdownBaseline {
+                marked_equity_usd: marked_equity,
+                long_gross_pnl_usd: self.long_book.gross_pnl_usd(),
+                short_gross_pnl_usd: self.short_book.gross_pnl_usd(),
+                costs_usd: self.long_book.costs_usd + self.short_book.costs_usd,
+            };
+        }
+
+        let realized_equity = self.realized_equity_usd();
+        if realized_equity > self.daily_realized_high_water_mark {
+            self.daily_realized_high_water_mark = realized_equity;
+        }
+        self.daily_realized_drawdown_pct = if self.daily_realized_high_water_mark > Decimal::ZERO {
+            (self.daily_realized_high_water_mark - realized_equity) / self.daily_realized_high_water_mark
+        } else {
+            Decimal::ZERO
+        };
+        let unrealized = self.long_book.unrealized_pnl_usd + self.short_book.unrealized_pnl_usd;
+        self.daily_unrealized_drawdown_pct = if realized_equity > Decimal::ZERO && unrealized < Decimal::ZERO {
+            -unrealized / realized_equity
+        } else {
+            Decimal::ZERO
+        };
+
+        let current_value = self.equity_usd();
+
+        if current_value > self.daily_high_water_mark {
+            self.daily_high_water_mark = current_value;
+        }
+        self.daily_drawdown_pct = if self.daily_high_water_mark > Decimal::ZERO {
+            (self.daily_high_water_mark - current_value) / self.daily_high_water_mark
+        } else {
+            Decimal::ZERO
+        };
+
+        if current_value > self.weekly_high_water_mark {
+            self.weekly_high_water_mark = current_value;
+        }
+        self.weekly_drawdown_pct = if self.weekly_high_water_mark > Decimal::ZERO {
+            (self.weekly_high_water_mark - current_value) / self.weekly_high_water_mark
+        } else {
+            Decimal::ZERO
+        };
+
+        if current_value > self.monthly_high_water_mark {
+            self.monthly_high_water_mark = current_value;
+        }
+        self.monthly_drawdown_pct = if self.monthly_high_water_mark > Decimal::ZERO {
+            (self.monthly_high_water_mark - current_value) / self.monthly_high_water_mark
+        } else {
+            Decimal::ZERO
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CapitalEventKind {
+    Deposit,
+    Withdrawal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapitalEvent {
+    pub event_id: Uuid,
+    pub kind: CapitalEventKind,
+    pub amount_usd: Decimal,
+    pub total_capital_after: Decimal,
+    pub available_capital_after: Decimal,
+    pub long_book_max_after: Decimal,
+    pub short_book_max_after: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapitalError {
+    InvalidAmount(Decimal),
+    InsufficientLiquidity { available: Decimal, requested: Decimal },
+}
+
+impl std::fmt::Display for CapitalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CapitalError::InvalidAmount(amount) => {
+                write!(f, "Capital amount must be positive, got {}", amount)
+            }
+            CapitalError::InsufficientLiquidity { available, requested } => write!(
+                f,
+                "Insufficient liquidity: {} available, {} requested",
+                available, requested
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CapitalError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateTransitionError {
+    /// EmergencyHalt is only entered through `emergency_close_all`, Recovering only after a reset
+    /// halt or a passed recovery probe
+    Reserved(SystemState),
+    /// The halt must be cleared with `reset_emergency_halt` before any other state applies
+    HaltRequiresReset(SystemState),
+    /// Resume is only possible from a paused or recovering state
+    InvalidResume(SystemState),
+    NotHalted(SystemState),
+}
+
+impl std::fmt::Display for StateTransitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateTransitionError::Reserved(state) => write!(f, "{:?} cannot be set directly", state),
+            StateTransitionError::HaltRequiresReset(state) => {
+                write!(f, "Emergency halt requires a manual reset before moving to {:?}", state)
+            }
+            StateTransitionError::InvalidResume(state) => write!(f, "Cannot resume from {:?}", state),
+            StateTransitionError::NotHalted(state) => write!(f, "No emergency halt to reset (state is {:?})", state),
+        }
+    }
+}
+
+impl std::error::Error for StateTransitionError {}
+
+/// Where a probe-driven recovery from a consecutive-failure halt stands
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecoveryState {
+    /// The probe passed; the system is Recovering and takes entries until one succeeds
+    RecoveryAttempted,
+    /// A real entry went through during recovery and the system is Active again
+    RecoverySucceeded,
+    RecoveryFailed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryResult {
+    pub state: RecoveryState,
+    pub gates: Vec<RiskGateCheck>, // Probe validation; empty when the probe did not run
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSummary {
+    pub state: SystemState,
+    pub total_capital_usd: Decimal,
+    pub available_capital_usd: Decimal,
+    pub gross_exposure_pct: Decimal,
+    pub net_exposure_pct: Decimal,
+    pub realized_pnl_usd: Decimal,
+    pub unrealized_pnl_usd: Decimal,
+    pub daily_drawdown_pct: Decimal,
+    pub total_slippage_cost_usd: Decimal,
+    pub slippage_as_pct_of_pnl: Option<Decimal>,
+}
+
+/// Why the slippage controller paused entries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlippagePause {
+    pub slippage_cost_usd: Decimal,
+    pub gross_pnl_usd: Decimal,
+    pub slippage_pct: Decimal,
+    pub triggered_at: DateTime<Utc>,
+}
+
+/// Pauses entries once slippage eats more than `slippage_reduce_pct` of gross realized P&L.
+/// Stays tripped until an operator resumes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlippagePauseController {
+    pub triggered: Option<SlippagePause>,
+}
+
+impl SlippagePauseController {
+    /// Returns the pause when this check tripped the controller
+    pub fn check(&mut self, portfolio: &PortfolioState, config: &RiskControllerConfig) -> Option<SlippagePause> {
+        if self.triggered.is_some() {
+            return None;
+        }
+        let slippage_pct = portfolio.slippage_as_pct_of_pnl()?;
+        if slippage_pct <= config.slippage_reduce_pct {
+            return None;
+        }
+        let pause = SlippagePause {
+            slippage_cost_usd: portfolio.total_slippage_cost_usd,
+            gross_pnl_usd: portfolio.gross_pnl_usd(),
+            slippage_pct,
+            triggered_at: Utc::now(),
+        };
+        self.triggered = Some(pause.clone());
+        Some(pause)
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.triggered.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.triggered = None;
+    }
+}
+
+/// Exponentially weighted moments of one token pair, in pair-key order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PairMoments {
+    pub covariance: Decimal,
+    pub variance_a: Decimal,
+    pub variance_b: Decimal,
+    pub samples: u32,
+}
+
+impl PairMoments {
+    /// None until both tokens have moved
+    pub fn correlation(&self) -> Option<Decimal> {
+        let denominator = conv::decimal_to_f64(self.variance_a * self.variance_b).sqrt();
+        if denominator <= 0.0 {
+            return None;
+        }
+        conv::decimal_from_f64(conv::decimal_to_f64(self.covariance) / denominator).ok()
+    }
+}
+
+/// Pairs whose moments are kept; the least recently updated go first
+pub const MAX_CORRELATION_PAIRS: usize = 20_000;
+/// Unheld tokens observed within this many minutes are tracked against the held ones
+pub const CORRELATION_WATCH_MINUTES: i64 = 60;
+
+/// Moving covariance between each held token and every other held or recently watched token.
+/// Pairs outlive the positions, so a token entered later is checked against its history
+/// with the others.
+#[derive(Debug, Clone, Default)]
+pub struct CorrelationTracker {
+    pairs: BTreeMap<(String, String), PairMoments>,
+    last_prices: BTreeMap<String, Decimal>,
+    last_updated: BTreeMap<(String, String), u64>, // update sequence per pair; imported pairs have none
+    updates: u64,
+}
+
+impl CorrelationTracker {
+    fn key<'a>(token_a: &'a str, token_b: &'a str) -> ((&'a str, &'a str), bool) {
+        if token_a <= token_b {
+            ((token_a, token_b), false)
+        } else {
+            ((token_b, token_a), true)
+        }
+    }
+
+    /// cov = alpha * return_a * return_b + (1 - alpha) * prev_cov, with each variance
+    /// moving the same way
+    pub fn update(&mut self, token_a: &str, token_b: &str, return_a: Decimal, return_b: Decimal, alpha: Decimal) {
+        let ((first, second), swapped) = Self::key(token_a, token_b);
+        let (return_first, return_second) = if swapped { (return_b, return_a) } else { (return_a, return_b) };
+        let moments = self.pairs.entry((first.to_string(), second.to_string())).or_default();
+        let decay = Decimal::ONE - alpha;
+        moments.covariance = alpha * return_first * return_second + decay * moments.covariance;
+        moments.variance_a = alpha * return_first * return_first + decay * moments.variance_a;
+        moments.variance_b = alpha * return_second * return_second + decay * moments.variance_b;
+        moments.samples += 1;
+        self.updates += 1;
+        self.last_updated.insert((first.to_string(), second.to_string()), self.updates);
+    }
+
+    pub fn pair_count(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn moments(&self, token_a: &str, token_b: &str) -> Option<&PairMoments> {
+        let ((first, second), _) = Self::key(token_a, token_b);
+        self.pairs.get(&(first.to_string(), second.to_string()))
+    }
+
+    pub fn correlation(&self, token_a: &str, token_b: &str) -> Option<Decimal> {
+        self.moments(token_a, token_b).and_then(PairMoments::correlation)
+    }
+
+    /// Returns since the last observation for each token in `held` and `watched`, then feeds
+    /// every pair with at least one held side. Tokens missing from both are dropped so a later
+    /// observation starts fresh.
+    pub fn observe(&mut self, held: &BTreeMap<String, Decimal>, watched: &BTreeMap<String, Decimal>, alpha: Decimal) {
+        let return_of = |token: &String, price: &Decimal| {
+            self.last_prices
+                .get(token)
+                .filter(|last| **last > Decimal::ZERO)
+                .map(|last| (token.clone(), (*price - *last) / *last))
+        };
+        let held_returns: Vec<(String, Decimal)> = held.iter().filter_map(|(t, p)| return_of(t, p)).collect();
+        let watched_returns: Vec<(String, Decimal)> = watched
+            .iter()
+            .filter(|(token, _)| !held.contains_key(*token))
+            .filter_map(|(t, p)| return_of(t, p))
+            .collect();
+        for (i, (token_a, return_a)) in held_returns.iter().enumerate() {
+            for (token_b, return_b) in held_returns[i + 1..].iter().chain(&watched_returns) {
+                self.update(token_a, token_b, *return_a, *return_b, alpha);
+            }
+        }
+        self.last_prices = watched.clone();
+        self.last_prices.extend(held.iter().map(|(token, price)| (token.clone(), *price)));
+        self.evict_excess_pairs();
+    }
+
+    fn evict_excess_pairs(&mut self) {
+        let excess = self.pairs.len().saturating_sub(MAX_CORRELATION_PAIRS);
+        if excess == 0 {
+            return;
+        }
+        let mut by_recency: Vec<((String, String), u64)> = self
+            .pairs
+            .keys()
+            .map(|key| (key.clone(), self.last_updated.get(key).copied().unwrap_or(0)))
+            .collect();
+        by_recency.sort_by_key(|(_, updated)| *updated);
+        for (key, _) in by_recency.into_iter().take(excess) {
+            self.pairs.remove(&key);
+            self.last_updated.remove(&key);
+        }
+    }
+}
+
+/// Observations kept per token; bounds StopMode::VolatilityScaled's atr_window
+pub const PRICE_HISTORY_CAPACITY: usize = 256;
+/// Tokens whose prices are kept; the least recently observed goes first
+pub const PRICE_HISTORY_MAX_TOKENS: usize = 4_096;
+
+/// Recent prices per token address, oldest first, for volatility-scaled stops
+#[derive(Debug, Clone, Default)]
+pub struct PriceHistory {
+    prices: BTreeMap<String, VecDeque<Decimal>>,
+    last_observed: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl PriceHistory {
+    pub fn record(&mut self, token_address: &str, price: Decimal) {
+        if price <= Decimal::ZERO {
+            return;
+        }
+        if !self.prices.contains_key(token_address) && self.prices.len() >= PRICE_HISTORY_MAX_TOKENS {
+            self.evict_stalest();
+        }
+        let history = self.prices.entry(token_address.to_string()).or_default();
+        if history.len() == PRICE_HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back(price);
+        self.last_observed.insert(token_address.to_string(), Utc::now());
+    }
+
+    pub fn last_observed(&self, token_address: &str) -> Option<DateTime<Utc>> {
+        self.last_observed.get(token_address).copied()
+    }
+
+    pub fn token_count(&self) -> usize {
+        self.prices.len()
+    }
+
+    pub fn prices(&self, token_address: &str) -> Vec<Decimal> {
+        self.prices
+            .get(token_address)
+            .map(|history| history.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Latest price of each token observed at or after `since`
+    pub fn latest_since(&self, since: DateTime<Utc>) -> BTreeMap<String, Decimal> {
+        self.last_observed
+            .iter()
+            .filter(|(_, at)| **at >= since)
+            .filter_map(|(token_address, _)| {
+                self.prices.get(token_address)?.back().map(|price| (token_address.clone(), *price))
+            })
+            .collect()
+    }
+
+    fn evict_stalest(&mut self) {
+        let stalest = self
+            .last_observed
+            .iter()
+            .min_by_key(|(_, at)| **at)
+            .map(|(token_address, _)| token_address.clone());
+        if let Some(token_address) = stalest {
+            self.prices.remove(&token_address);
+            self.last_observed.remove(&token_address);
+        }
+    }
+}
+
+// ============================================================
+// SECTION 14: AUDIT LOGGING
+// ============================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryLog {
+    pub execution_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub direction: Direction,
+    pub entry_price: Decimal,
+    pub position_size_tokens: Decimal,
+    pub position_size_usd: Decimal,
+    pub liquidity_depth_usd: Decimal,
+    pub safety_score: Decimal,
+    pub holder_count: u32,
+    pub stop_loss_price: Decimal,
+    pub take_profit_prices: Vec<Decimal>, // Reads logs written with the old fixed [Decimal; 3]
+    pub risk_approval_id: Uuid,
+    pub latency_ms: u32,
+    pub slippage_bps: Decimal,
+    #[serde(default)]
+    pub stop_basis: Option<StopBasis>, // None for shorts and logs written before it was recorded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitLog {
+    pub execution_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub exit_price: Decimal,
+    pub exit_type: ExitType,
+    pub exit_size_pct: Decimal,
+    pub realized_pnl_tokens: Decimal,
+    pub realized_pnl_usd: Decimal,
+    #[serde(default)]
+    pub fees_usd: Decimal, // Already taken out of realized_pnl_usd
+    pub slippage_bps: Decimal,
+    pub hold_duration_seconds: u64,
+    pub liquidity_depth_exit_usd: Decimal,
+    #[serde(default)]
+    pub exit_execution_id: Option<Uuid>, // Exit order id; `execution_id` is the position's
+    #[serde(default)]
+    pub latency_ms: u32,
+    #[serde(default)]
+    pub trigger_assumption: Option<TriggerPathAssumption>,
+}
+
+impl From<&PositionExit> for ExitLog {
+    fn from(exit: &PositionExit) -> Self {
+        Self {
+            execution_id: exit.execution_id,
+            timestamp: exit.exited_at,
+            exit_price: exit.exit_price,
+            exit_type: exit.exit_type,
+            exit_size_pct: exit.exit_size_pct,
+            realized_pnl_tokens: exit.realized_pnl_tokens,
+            realized_pnl_usd: exit.realized_pnl_usd,
+            fees_usd: exit.fees_usd,
+            slippage_bps: exit.slippage_bps,
+            hold_duration_seconds: exit.hold_duration_seconds,
+            liquidity_depth_exit_usd: exit.liquidity_at_exit,
+            exit_execution_id: None,
+            latency_ms: 0,
+            trigger_assumption: exit.trigger_assumption,
+        }
+    }
+}
+
+/// Relative gap between booked and filled entry price or size that rebooks a position (0.1%)
+pub const FILL_ADJUSTMENT_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 3);
+/// Default wait for an entry or exit log before an execution report is orphaned
+pub const EXECUTION_REPORT_GRACE_SECONDS: i64 = 30;
+
+/// Venue-side outcome of one order, joined onto its entry or exit log by execution id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    pub execution_id: Uuid,
+    pub submitted_at: DateTime<Utc>,
+    pub acked_at: DateTime<Utc>,
+    pub first_fill_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub requested_price: Decimal,
+    pub achieved_avg_price: Decimal,
+    pub requested_size: Decimal, // Tokens
+    pub filled_size: Decimal,    // Tokens
+    pub fees_usd: Decimal,
+}
+
+impl ExecutionReport {
+    /// Submit-to-ack latency, saturating at zero for skewed clocks
+    pub fn latency_ms(&self) -> u32 {
+        let ms = (self.acked_at - self.submitted_at).num_milliseconds();
+        ms.clamp(0, u32::MAX as i64) as u32
+    }
+
+    /// Slippage in bps for an order on the `buying` side; positive is always adverse
+    pub fn slippage_bps(&self, buying: bool) -> Decimal {
+        if self.requested_price <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        let diff = if buying {
+            self.achieved_avg_price - self.requested_price
+        } else {
+            self.requested_price - self.achieved_avg_price
+        };
+        (diff / self.requested_price * Decimal::new(10_000, 0)).round_dp(2)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionLeg {
+    Entry,
+    Exit,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportAttachment {
+    Attached { leg: ExecutionLeg, position_adjusted: bool },
+    Queued, // No matching log yet; retried as logs are recorded until the grace period ends
+}
+
+/// Entry fill that differed from the booked position beyond FILL_ADJUSTMENT_TOLERANCE
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillAdjustment {
+    pub execution_id: Uuid,
+    pub old_entry_price: Decimal,
+    pub new_entry_price: Decimal,
+    pub old_size_tokens: Decimal,
+    pub new_size_tokens: Decimal,
+    pub adjusted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectionLog {
+    pub timestamp: DateTime<Utc>,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub direction: Direction,
+    pub rejection_reason: String,
+    pub failed_gate: String,
+    pub safety_score: Option<Decimal>,
+    pub liquidity_usd: Option<Decimal>,
+    #[serde(default)]
+    pub config_key: Option<String>, // Dotted config path of the threshold the gate failed on
+    #[serde(default)]
+    pub observed_value: Option<Decimal>, // The token's value compared against it
+}
+
+/// Rejection logs, newest first, that `StrikeBoxEngine::config_diagnostics` looks at
+pub const CONFIG_DIAGNOSTICS_WINDOW: usize = 500;
+/// Rejections on one threshold before a loosening is suggested
+pub const CONFIG_SUGGESTION_MIN_REJECTIONS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThresholdBound {
+    Min, // Observed values must be at least the config value
+    Max, // Observed values must be at most the config value
+}
+
+/// Config thresholds gates compare token values against
+const GATE_THRESHOLDS: [(&str, ThresholdBound); 11] = [
+    ("token_validation.liquidity_min_usd", ThresholdBound::Min),
+    ("token_validation.liquidity_max_usd", ThresholdBound::Max),
+    ("token_validation.token_age_min_hours", ThresholdBound::Min),
+    ("token_validation.holder_count_min", ThresholdBound::Min),
+    ("token_validation.top_10_concentration_max_pct", ThresholdBound::Max),
+    ("token_validation.single_wallet_max_pct", ThresholdBound::Max),
+    ("safety_scoring.long_entry_min", ThresholdBound::Min),
+    ("safety_scoring.short_entry_min", ThresholdBound::Min),
+    ("cex_validation.max_spread_bps", ThresholdBound::Max),
+    ("cex_validation.min_side_depth_usd", ThresholdBound::Min),
+    ("correlation.max_long_correlation", ThresholdBound::Max),
+];
+
+/// A gate that rejected entries, with the threshold behind it and what it turned away
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingConstraint {
+    pub gate: String,
+    pub config_key: Option<String>, // None for gates without a single threshold, e.g. book_capacity
+    pub config_value: Option<Decimal>,
+    pub bound: Option<ThresholdBound>,
+    pub rejections: usize,
+    pub observed_min: Option<Decimal>,
+    pub observed_median: Option<Decimal>,
+    pub observed_max: Option<Decimal>,
+    pub summary: String,
+}
+
+/// Loosening that would have admitted about half of a threshold's rejections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSuggestion {
+    pub config_key: String,
+    pub current_value: Decimal,
+    pub suggested_value: Decimal,
+    pub would_admit: usize,
+    pub rejections: usize,
+    pub rationale: String,
+}
+
+/// Market percentile of liquidity above which `liquidity_min_usd` blocks most tokens
+pub const LIQUIDITY_MIN_MARKET_PERCENTILE: Decimal = Decimal::from_parts(5, 0, 0, false, 1);
+/// Market percentile of liquidity below which `liquidity_max_usd` blocks too many tokens
+pub const LIQUIDITY_MAX_MARKET_PERCENTILE: Decimal = Decimal::from_parts(9, 0, 0, false, 1);
+
+/// A config threshold out of line with what recent snapshots show of the market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigMarketMismatch {
+    pub field: String,
+    pub config_value: Decimal,
+    pub market_percentile: Decimal, // Share of recent snapshots below config_value, 0-1
+    pub recommendation: String,
+}
+
+/// Nearest-rank `pct` percentile of sorted values: the first at or above which lies that
+/// share of them. None when empty.
+fn nearest_rank(sorted: &[Decimal], pct: Decimal) -> Option<Decimal> {
+    let count = Decimal::from(sorted.len());
+    sorted
+        .iter()
+        .enumerate()
+        .find(|(i, _)| Decimal::from(i + 1) / count >= pct)
+        .map(|(_, value)| *value)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigDiagnostics {
+    pub rejections_examined: usize,
+    pub binding_constraints: Vec<BindingConstraint>, // Most rejections first
+    pub suggested_adjustments: Vec<ConfigSuggestion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuditRecord {
+    Entry(EntryLog),
+    Exit(ExitLog),
+    Rejection(RejectionLog),
+    PortfolioSample(history::PortfolioSample),
+    Snapshot(AuditSnapshot),
+    Validation(ValidationAuditRecord),
+    Command(CommandAuditRecord),
+    ConfigUpdate(ConfigUpdateReport),
+    CapitalEvent(CapitalEvent),
+    MarketEvent(MarketEventRecord),
+    FillAdjustment(FillAdjustment),
+    OrphanedExecutionReport(ExecutionReport),
+}
+
+pub trait AuditSink: Send + Sync {
+    fn spill(&self, record: &AuditRecord) -> std::io::Result<()>;
+}
+
+/// Spills `record` to `sink`, logging and counting a failure in `failures`: by then the record
+/// has left memory, so a failed spill loses it
+fn spill_to(sink: &dyn AuditSink, record: &AuditRecord, failures: &std::sync::atomic::AtomicU64) {
+    if let Err(e) = sink.spill(record) {
+        failures.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        error!(error = %e, "audit record lost: spill to the audit sink failed");
+    }
+}
+
+/// Appends spilled records to a JSON-lines checkpoint file
+pub struct JsonlAuditSink {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl JsonlAuditSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+impl AuditSink for JsonlAuditSink {
+    fn spill(&self, record: &AuditRecord) -> std::io::Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let line = serde_json::to_string(record)?;
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", line)
+    }
+}
+
+/// Signed PortfolioState snapshot for the regulatory audit trail
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditSnapshot {
+    pub payload_json: String,
+    pub timestamp: DateTime<Utc>,
+    pub hmac_sha256: String, // Hex-encoded HMAC of timestamp and payload_json
+}
+
+impl AuditSnapshot {
+    pub fn verify(&self, signing_key: &[u8]) -> bool {
+        match from_hex(&self.hmac_sha256) {
+            Some(tag) => audit_mac(signing_key, self.timestamp, &self.payload_json)
+                .verify_slice(&tag)
+                .is_ok(),
+            None => false,
+        }
+    }
+}
+
+fn audit_mac(signing_key: &[u8], timestamp: DateTime<Utc>, payload: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(signing_key).expect("HMAC accepts keys of any length");
+    // Fixed-width nanosecond stamp, so the boundary with the payload is unambiguous
+    mac.update(&timestamp.timestamp_nanos_opt().unwrap_or_default().to_be_bytes());
+    mac.update(payload.as_bytes());
+    mac
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRetentionConfig {
+    pub max_entry_logs: usize,
+    pub max_exit_logs: usize,
+    pub max_rejection_logs: usize,
+    #[serde(default = "default_max_cycle_records")]
+    pub max_cycle_records: usize, // Completed array cycles kept in memory
+    #[serde(default = "default_max_audit_snapshots")]
+    pub max_audit_snapshots: usize, // Signed portfolio snapshots kept in memory
+    #[serde(default = "default_max_validation_records")]
+    pub max_validation_records: usize, // Gate-sequence audit records kept in memory
+    #[serde(default = "default_max_command_records")]
+    pub max_command_records: usize, // Operator command audit records kept in memory
+    #[serde(default = "default_max_config_reports")]
+    pub max_config_reports: usize, // Config update reports, applied or rejected, kept in memory
+    #[serde(default = "default_max_event_records")]
+    pub max_capital_events: usize, // Deposits and withdrawals kept in memory
+    #[serde(default = "default_max_event_records")]
+    pub max_market_events: usize, // Market events and the state changes they caused kept in memory
+    #[serde(default = "default_max_event_records")]
+    pub max_fill_adjustments: usize, // Entry fills that repriced a position kept in memory
+    #[serde(default = "default_max_orphaned_reports")]
+    pub max_orphaned_reports: usize, // Execution reports that never matched a position kept in memory
+}
+
+fn default_max_cycle_records() -> usize {
+    1_000
+}
+
+fn default_max_audit_snapshots() -> usize {
+    10_000
+}
+
+fn default_max_validation_records() -> usize {
+    50_000
+}
+
+fn default_max_command_records() -> usize {
+    10_000
+}
+
+fn default_max_config_reports() -> usize {
+    1_000
+}
+
+fn default_max_event_records() -> usize {
+    10_000
+}
+
+fn default_max_orphaned_reports() -> usize {
+    1_000
+}
+
+impl Default for LogRetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_entry_logs: 10_000,
+            max_exit_logs: 30_000,
+            max_rejection_logs: 50_000,
+            max_cycle_records: default_max_cycle_records(),
+            max_audit_snapshots: default_max_audit_snapshots(),
+            max_validation_records: default_max_validation_records(),
+            max_command_records: default_max_command_records(),
+            max_config_reports: default_max_config_reports(),
+            max_capital_events: default_max_event_records(),
+            max_market_events: default_max_event_records(),
+            max_fill_adjustments: default_max_event_records(),
+            max_orphaned_reports: default_max_orphaned_reports(),
+        }
+    }
+}
+
+/// Fixed-capacity FIFO buffer with O(1) eviction of the oldest item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    evicted: u64,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+            evicted: 0,
+        }
+    }
+
+    /// Pushes an item, returning the evicted oldest item when full
+    pub fn push(&mut self, item: T) -> Option<T> {
+        let evicted = if self.items.len() == self.capacity {
+            self.evicted += 1;
+            self.items.pop_front()
+        } else {
+            None
+        };
+        self.items.push_back(item);
+        evicted
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn evicted_count(&self) -> u64 {
+        self.evicted
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.items.get(index)
+    }
+
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::collections::vec_deque::IterMut<'_, T> {
+        self.items.iter_mut()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Removes and returns the oldest item matching `predicate`
+    pub fn remove_first(&mut self, predicate: impl FnMut(&T) -> bool) -> Option<T> {
+        let index = self.items.iter().position(predicate)?;
+        self.items.remove(index)
+    }
+
+    /// Changes the capacity, returning the oldest items evicted to fit
+    pub fn set_capacity(&mut self, capacity: usize) -> Vec<T> {
+        self.capacity = capacity.max(1);
+        let excess = self.items.len().saturating_sub(self.capacity);
+        self.evicted += excess as u64;
+        self.items.drain(..excess).collect()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = std::collections::vec_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionRecord {
+    pub position: Position,
+    pub entry_log: Option<EntryLog>,
+    pub exit_log: Option<ExitLog>,
+}
+
+// ============================================================
+// SECTION 15: OPERATIONAL COMMANDS
+// ============================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationalCommand {
+    PauseLongs,
+    PauseShorts,
+    PauseAll,
+    CloseLongs,
+    CloseShorts,
+    CloseAll,
+    Resume,
+    Status,
+    Exposure,
+    Risk,
+    Health,
+    Position { token: String },
+    History { token: String },
+    Pnl {
+        timeframe: PnlTimeframe,
+        #[serde(default)]
+        curve_resolution_seconds: Option<i64>, // Include the sampled equity curve at this bucket width
+    },
+    Rejects { timeframe: PnlTimeframe },
+    TimeStops { within_minutes: i64 },
+}
+
+impl std::str::FromStr for OperationalCommand {
+    type Err = ParseError;
+
+    /// CLI form: "pause longs", "close all", "status", "history <token>", "pnl 7d", "pnl 7d curve 1h",
+    /// "rejects 2024-01-01/2024-01-31", "timestops 60"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let command = words.first().map(|w| w.to_lowercase()).unwrap_or_default();
+        let scope = words.get(1).map(|w| w.to_lowercase());
+        let argument = || {
+            words.get(1).copied().ok_or_else(|| ParseError::new(s, format!("{} requires an argument", command)))
+        };
+        let parsed = match (command.as_str(), scope.as_deref(), words.len()) {
+            ("pause", Some("longs"), 2) => Self::PauseLongs,
+            ("pause", Some("shorts"), 2) => Self::PauseShorts,
+            ("pause", Some("all"), 2) => Self::PauseAll,
+            ("close", Some("longs"), 2) => Self::CloseLongs,
+            ("close", Some("shorts"), 2) => Self::CloseShorts,
+            ("close", Some("all"), 2) => Self::CloseAll,
+            ("resume", None, 1) => Self::Resume,
+            ("status", None, 1) => Self::Status,
+            ("exposure", None, 1) => Self::Exposure,
+            ("risk", None, 1) => Self::Risk,
+            ("health", None, 1) => Self::Health,
+            ("position", _, ..=2) => Self::Position { token: argument()?.to_string() },
+            ("history", _, ..=2) => Self::History { token: argument()?.to_string() },
+            ("pnl", _, ..=2) => Self::Pnl { timeframe: argument()?.parse()?, curve_resolution_seconds: None },
+            ("pnl", _, 4) if words[2].eq_ignore_ascii_case("curve") => Self::Pnl {
+                timeframe: argument()?.parse()?,
+                curve_resolution_seconds: Some(
+                    PnlTimeframe::parse_duration_seconds(words[3])
+                        .ok_or_else(|| ParseError::new(s, "curve resolution must be a duration such as 1h"))?,
+                ),
+            },
+            ("rejects", _, ..=2) => Self::Rejects { timeframe: argument()?.parse()? },
+            ("timestops", _, ..=2) => Self::TimeStops {
+                within_minutes: argument()?
+                    .parse()
+                    .map_err(|_| ParseError::new(s, "timestops takes whole minutes"))?,
+            },
+            _ => return Err(ParseError::new(s, "unknown command or wrong number of arguments")),
+        };
+        Ok(parsed)
+    }
+}
+
+/// Text that does not parse as a command or timeframe, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub input: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    fn new(input: &str, reason: impl Into<String>) -> Self {
+        Self { input: input.to_string(), reason: reason.into() }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cannot parse {:?}: {}", self.input, self.reason)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Reporting window for the Pnl and Rejects commands. Named periods are calendar-aligned in
+/// UTC (weeks start Monday); trailing windows end now; intervals cover whole days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PnlTimeframe {
+    Today,
+    Week,
+    Month,
+    All,
+    Trailing { seconds: i64 },
+    Between { start: DateTime<Utc>, end: DateTime<Utc> }, // End exclusive
+}
+
+impl PnlTimeframe {
+    pub fn start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let midnight = |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|t| t.and_utc()).unwrap_or(now);
+        let today = now.date_naive();
+        match self {
+            PnlTimeframe::Today => midnight(today),
+            PnlTimeframe::Week => midnight(today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64)),
+            PnlTimeframe::Month => midnight(today.with_day(1).unwrap_or(today)),
+            PnlTimeframe::All => DateTime::<Utc>::MIN_UTC,
+            // A window reaching past the earliest representable instant covers everything
+            PnlTimeframe::Trailing { seconds } => chrono::Duration::try_seconds(*seconds)
+                .and_then(|window| now.checked_sub_signed(window))
+                .unwrap_or(DateTime::<Utc>::MIN_UTC),
+            PnlTimeframe::Between { start, .. } => *start,
+        }
+    }
+
+    pub fn end(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            PnlTimeframe::Between { end, .. } => *end,
+            _ => now,
+        }
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        let end = self.end(now);
+        at >= self.start(now)
+            && match self {
+                PnlTimeframe::Between { .. } => at < end,
+                _ => at <= end,
+            }
+    }
+
+    /// "24h" or "30d" style, or an ISO-8601 duration limited to weeks, days, hours,
+    /// minutes and seconds ("P7D", "PT12H", "P1DT6H"); months and years have no fixed length.
+    /// None for anything chrono can't hold as a Duration.
+    fn parse_duration_seconds(s: &str) -> Option<i64> {
+        let unit_seconds = |unit: char| match unit.to_ascii_uppercase() {
+            'W' => Some(604_800),
+            'D' => Some(86_400),
+            'H' => Some(3_600),
+            'M' => Some(60),
+            'S' => Some(1),
+            _ => None,
+        };
+
+        let Some(iso) = s.strip_prefix('P').or_else(|| s.strip_prefix('p')) else {
+            let unit = s.chars().last()?;
+            if !matches!(unit.to_ascii_lowercase(), 'h' | 'd') {
+                return None;
+            }
+            let count: i64 = s[..s.len() - 1].parse().ok()?;
+            let seconds = count.checked_mul(unit_seconds(unit)?)?;
+            return (seconds > 0 && chrono::Duration::try_seconds(seconds).is_some()).then_some(seconds);
+        };
+
+        let (date_part, time_part) = match iso.split_once(['T', 't']) {
+            Some((date, time)) if !time.is_empty() => (date, time),
+            Some(_) => return None,
+            None => (iso, ""),
+        };
+        let mut total = 0i64;
+        for (part, allowed) in [(date_part, "WD"), (time_part, "HMS")] {
+            let mut digits = String::new();
+            for c in part.chars() {
+                if c.is_ascii_digit() {
+                    digits.push(c);
+                } else if allowed.contains(c.to_ascii_uppercase()) && !digits.is_empty() {
+                    total = total.checked_add(digits.parse::<i64>().ok()?.checked_mul(unit_seconds(c)?)?)?;
+                    digits.clear();
+                } else {
+                    return None;
+                }
+            }
+            if !digits.is_empty() {
+                return None;
+            }
+        }
+        (total > 0 && chrono::Duration::try_seconds(total).is_some()).then_some(total)
+    }
+
+    /// Interval bound: a date means its midnight, or an RFC 3339 timestamp as given
+    fn parse_instant(s: &str) -> Option<(DateTime<Utc>, bool)> {
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Some((date.and_hms_opt(0, 0, 0)?.and_utc(), true));
+        }
+        DateTime::parse_from_rfc3339(s).ok().map(|t| (t.with_timezone(&Utc), false))
+    }
+}
+
+impl std::str::FromStr for PnlTimeframe {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        match trimmed.to_lowercase().as_str() {
+            "today" => return Ok(PnlTimeframe::Today),
+            "week" => return Ok(PnlTimeframe::Week),
+            "month" => return Ok(PnlTimeframe::Month),
+            "all" => return Ok(PnlTimeframe::All),
+            _ => {}
+        }
+
+        if let Some((start, end)) = trimmed.split_once('/') {
+            let (start, _) = Self::parse_instant(start)
+                .ok_or_else(|| ParseError::new(s, "interval start must be YYYY-MM-DD or RFC 3339"))?;
+            let (end, end_is_date) = Self::parse_instant(end)
+                .ok_or_else(|| ParseError::new(s, "interval end must be YYYY-MM-DD or RFC 3339"))?;
+            // A closing date includes that whole day
+            let end = if end_is_date {
+                end.checked_add_signed(chrono::Duration::days(1))
+                    .ok_or_else(|| ParseError::new(s, "interval end is out of range"))?
+            } else {
+                end
+            };
+            if end <= start {
+                return Err(ParseError::new(s, "interval ends before it starts"));
+            }
+            return Ok(PnlTimeframe::Between { start, end });
+        }
+
+        Self::parse_duration_seconds(trimmed)
+            .map(|seconds| PnlTimeframe::Trailing { seconds })
+            .ok_or_else(|| {
+                ParseError::new(s, "expected today, week, month, all, a duration like 24h, 7d or P7D, or an interval like 2024-01-01/2024-01-31")
+            })
+    }
+}
+
+/// Realized results of exits logged in a timeframe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PnlReport {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub exits: usize,
+    pub winning_exits: usize,
+    pub losing_exits: usize,
+    pub realized_pnl_usd: Decimal,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equity_curve: Option<Vec<history::EquityCandle>>,
+}
+
+/// Entry rejections logged in a timeframe, counted by failed gate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectsReport {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub total: usize,
+    pub by_gate: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandResponse {
+    pub command: String,
+    pub success: bool,
+    pub message: String,
+    pub data: Option<serde_json::Value>,
+    pub executed_at: DateTime<Utc>,
+    #[serde(default)]
+    pub requires_confirmation: bool, // Nothing ran; resend the command with confirm_token
+    #[serde(default)]
+    pub confirm_token: Option<String>,
+    #[serde(default)]
+    pub confirm_token_expires_at: Option<DateTime<Utc>>,
+}
+
+/// How long a confirmation token for a destructive command stays valid
+pub const CONFIRMATION_TOKEN_TTL_SECONDS: i64 = 60;
+
+/// Processed request ids remembered for deduplicating retries
+pub const COMMAND_DEDUP_CAPACITY: usize = 1_024;
+
+/// An operator command as received from an ops channel. Resending the same `id` returns the
+/// first response without running the command again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandRequest {
+    pub id: Uuid,
+    pub issued_by: String,
+    pub command: OperationalCommand,
+    pub confirm_token: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingConfirmation {
+    command: OperationalCommand,
+    requested_by: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// What the command bus decided about a request before anything ran
+enum Admission {
+    Duplicate(CommandResponse), // Already answered; not recorded again
+    Respond(CommandResponse),   // Held for confirmation, or a bad token
+    Run { confirmation_requested_by: Option<String> },
+}
+
+/// Retry deduplication and confirmation holds in front of operator commands; the engine and
+/// the sleeve manager each run their commands through one
+#[derive(Debug, Clone)]
+struct CommandBus {
+    processed: VecDeque<(Uuid, CommandResponse)>, // Oldest first, for retry dedup
+    pending_confirmations: BTreeMap<String, PendingConfirmation>, // By confirmation token
+    confirmation_ttl: chrono::Duration,
+}
+
+impl CommandBus {
+    fn new() -> Self {
+        Self {
+            processed: VecDeque::new(),
+            pending_confirmations: BTreeMap::new(),
+            confirmation_ttl: chrono::Duration::seconds(CONFIRMATION_TOKEN_TTL_SECONDS),
+        }
+    }
+
+    fn admit(&mut self, request: &CommandRequest, needs_confirmation: bool, now: DateTime<Utc>) -> Admission {
+        if let Some((_, response)) = self.processed.iter().find(|(id, _)| *id == request.id) {
+            debug!(request_id = %request.id, "duplicate command request, returning original response");
+            return Admission::Duplicate(response.clone());
+        }
+        self.pending_confirmations.retain(|_, pending| pending.expires_at > now);
+
+        match (&request.confirm_token, needs_confirmation) {
+            (None, true) => {
+                let token = Uuid::new_v4().simple().to_string()[..8].to_string();
+                let expires_at = now + self.confirmation_ttl;
+                self.pending_confirmations.insert(token.clone(), PendingConfirmation {
+                    command: request.command.clone(),
+                    requested_by: request.issued_by.clone(),
+                    expires_at,
+                });
+                info!(command = ?request.command, issued_by = %request.issued_by, "command awaiting confirmation");
+                Admission::Respond(CommandResponse {
+                    command: format!("{:?}", request.command),
+                    success: false,
+                    message: format!("{:?} requires confirmation; resend with the token before {}", request.command, expires_at),
+                    data: None,
+                    executed_at: now,
+                    requires_confirmation: true,
+                    confirm_token: Some(token),
+                    confirm_token_expires_at: Some(expires_at),
+                })
+            }
+            (Some(token), _) => match self.pending_confirmations.get(token) {
+                Some(pending) if pending.command == request.command => {
+                    let requested_by = pending.requested_by.clone();
+                    self.pending_confirmations.remove(token);
+                    Admission::Run { confirmation_requested_by: Some(requested_by) }
+                }
+                Some(_) => Admission::Respond(rejected_command(request, "confirmation token was issued for a different command", now)),
+                None => Admission::Respond(rejected_command(request, "confirmation token unknown or expired", now)),
+            },
+            (None, false) => Admission::Run { confirmation_requested_by: None },
+        }
+    }
+
+    /// Remembers the response to `request_id` so a retry gets it back
+    fn record(&mut self, request_id: Uuid, response: CommandResponse) {
+        if self.processed.len() == COMMAND_DEDUP_CAPACITY {
+            self.processed.pop_front();
+        }
+        self.processed.push_back((request_id, response));
+    }
+}
+
+fn rejected_command(request: &CommandRequest, reason: &str, now: DateTime<Utc>) -> CommandResponse {
+    warn!(command = ?request.command, issued_by = %request.issued_by, reason, "command rejected");
+    CommandResponse {
+        command: format!("{:?}", request.command),
+        success: false,
+        message: format!("Not executed: {}", reason),
+        data: None,
+        executed_at: now,
+        requires_confirmation: false,
+        confirm_token: None,
+        confirm_token_expires_at: None,
+    }
+}
+
+impl CommandAuditRecord {
+    fn new(request: &CommandRequest, confirmation_requested_by: Option<String>, response: &CommandResponse, now: DateTime<Utc>) -> Self {
+        Self {
+            request_id: request.id,
+            issued_by: request.issued_by.clone(),
+            confirmation_requested_by,
+            command: response.command.clone(),
+            success: response.success,
+            message: response.message.clone(),
+            executed_at: now,
+        }
+    }
+}
+
+/// One command the engine ran on an operator's behalf
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandAuditRecord {
+    pub request_id: Uuid,
+    pub issued_by: String,
+    pub confirmation_requested_by: Option<String>, // Who asked for the token, for confirmed commands
+    pub command: String,
+    pub success: bool,
+    pub message: String,
+    pub executed_at: DateTime<Utc>,
+}
+
+// ============================================================
+// SECTION 16: MASTER CONFIGURATION
+// ============================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrikeBoxConfig {
+    pub token_validation: TokenValidationConfig,
+    pub safety_scoring: SafetyScoreConfig,
+    pub position_sizing: PositionSizingConfig,
+    pub stop_loss: StopLossConfig,
+    pub take_profit: TakeProfitConfig,
+    pub time_control: TimeControlConfig,
+    pub risk_controller: RiskControllerConfig,
+    #[serde(default)]
+    pub log_retention: LogRetentionConfig,
+    #[serde(default)]
+    pub cex_validation: CexValidationConfig,
+    #[serde(default)]
+    pub trigger_resolution: TriggerResolutionConfig,
+    #[serde(default)]
+    pub correlation: CorrelationConfig,
+    #[serde(default)]
+    pub portfolio_history: history::PortfolioHistoryConfig,
+    #[serde(default)]
+    pub fees: FeeModel,
+    #[serde(default)]
+    pub activity_schedule: schedule::ActivityScheduleConfig,
+    #[serde(default)]
+    pub bootstrap: bootstrap::BootstrapConfig,
+}
+
+impl Default for StrikeBoxConfig {
+    fn default() -> Self {
+        Self {
+            token_validation: TokenValidationConfig::default(),
+            safety_scoring: SafetyScoreConfig::default(),
+            position_sizing: PositionSizingConfig::default(),
+            stop_loss: StopLossConfig::default(),
+            take_profit: TakeProfitConfig::default(),
+            time_control: TimeControlConfig::default(),
+            risk_controller: RiskControllerConfig::default(),
+            log_retention: LogRetentionConfig::default(),
+            cex_validation: CexValidationConfig::default(),
+            trigger_resolution: TriggerResolutionConfig::default(),
+            correlation: CorrelationConfig::default(),
+            portfolio_history: history::PortfolioHistoryConfig::default(),
+            fees: FeeModel::default(),
+            activity_schedule: schedule::ActivityScheduleConfig::default(),
+            bootstrap: bootstrap::BootstrapConfig::default(),
+        }
+    }
+}
+
+impl StrikeBoxConfig {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let unit = |name: &str, value: Decimal, errors: &mut Vec<String>| {
+            if value <= Decimal::ZERO || value >= Decimal::ONE {
+                errors.push(format!("{} must be between 0 and 1, got {}", name, value));
+            }
+        };
+
+        let sl = &self.stop_loss;
+        unit("stop_loss.long_default_pct", sl.long_default_pct, &mut errors);
+        unit("stop_loss.long_volatile_pct", sl.long_volatile_pct, &mut errors);
+        unit("stop_loss.long_trailing_distance_pct", sl.long_trailing_distance_pct, &mut errors);
+        unit("stop_loss.long_hard_floor_pct", sl.long_hard_floor_pct, &mut errors);
+        unit("stop_loss.short_fixed_pct", sl.short_fixed_pct, &mut errors);
+        if let StopMode::VolatilityScaled { atr_multiplier, atr_window, min_pct, max_pct } = sl.mode {
+            unit("stop_loss.mode.min_pct", min_pct, &mut errors);
+            unit("stop_loss.mode.max_pct", max_pct, &mut errors);
+            if min_pct > max_pct {
+                errors.push(format!("stop_loss.mode.min_pct {} exceeds max_pct {}", min_pct, max_pct));
+            }
+            if atr_multiplier <= Decimal::ZERO {
+                errors.push(format!("stop_loss.mode.atr_multiplier must be positive, got {}", atr_multiplier));
+            }
+            if atr_window == 0 || atr_window >= PRICE_HISTORY_CAPACITY {
+                errors.push(format!(
+                    "stop_loss.mode.atr_window must be between 1 and {}, got {}",
+                    PRICE_HISTORY_CAPACITY - 1,
+                    atr_window
+                ));
+            }
+        }
+
+        if let Err(tp_errors) = self.take_profit.validate() {
+            errors.extend(tp_errors);
+        }
+
+        let sizing = &self.position_sizing;
+        unit("position_sizing.long_book_max_pct", sizing.long_book_max_pct, &mut errors);
+        unit("position_sizing.short_book_max_pct", sizing.short_book_max_pct, &mut errors);
+        if sizing.long_book_max_pct + sizing.short_book_max_pct > Decimal::ONE {
+            errors.push("position_sizing book allocations exceed 100% of capital".to_string());
+        }
+        if sizing.long_book_max_positions == 0 || sizing.short_book_max_positions == 0 {
+            errors.push("position_sizing book max positions must be at least 1".to_string());
+        }
+        if sizing.leverage < Decimal::ONE {
+            errors.push(format!("position_sizing.leverage must be at least 1, got {}", sizing.leverage));
+        }
+        unit("risk_controller.maintenance_margin_pct", self.risk_controller.maintenance_margin_pct, &mut errors);
+        if let Err(failure_errors) = self.risk_controller.failure_policy.validate() {
+            errors.extend(failure_errors);
+        }
+        unit("time_control.hold_review_threshold", self.time_control.hold_review_threshold, &mut errors);
+        unit("time_control.hold_review_max_drop", self.time_control.hold_review_max_drop, &mut errors);
+        if let Some(growth) = self.time_control.long_review_min_holder_growth_pct {
+            if growth < Decimal::ZERO {
+                errors.push(format!("time_control.long_review_min_holder_growth_pct must not be negative, got {}", growth));
+            }
+        }
+
+        let retention = &self.log_retention;
+        if retention.max_entry_logs == 0
+            || retention.max_exit_logs == 0
+            || retention.max_rejection_logs == 0
+            || retention.max_cycle_records == 0
+            || retention.max_audit_snapshots == 0
+            || retention.max_validation_records == 0
+            || retention.max_command_records == 0
+            || retention.max_config_reports == 0
+            || retention.max_capital_events == 0
+            || retention.max_market_events == 0
+            || retention.max_fill_adjustments == 0
+            || retention.max_orphaned_reports == 0
+        {
+            errors.push("log_retention capacities must be at least 1".to_string());
+        }
+        let history = &self.portfolio_history;
+        if history.sample_interval_seconds < 1 || history.max_samples == 0 {
+            errors.push("portfolio_history interval and capacity must be at least 1".to_string());
+        }
+
+        let cex = &self.cex_validation;
+        if cex.max_spread_bps <= Decimal::ZERO {
+            errors.push(format!("cex_validation.max_spread_bps must be positive, got {}", cex.max_spread_bps));
+        }
+        if cex.min_side_depth_usd < Decimal::ZERO {
+            errors.push(format!("cex_validation.min_side_depth_usd must not be negative, got {}", cex.min_side_depth_usd));
+        }
+
+        unit("correlation.alpha", self.correlation.alpha, &mut errors);
+        unit("correlation.max_long_correlation", self.correlation.max_long_correlation, &mut errors);
+        let schedules = std::iter::once(("default", &self.fees.default_fees))
+            .chain(self.fees.venues.iter().map(|(venue, fees)| (venue.as_str(), fees)));
+        for (venue, fees) in schedules {
+            if fees.maker_bps < Decimal::ZERO || fees.taker_bps < Decimal::ZERO || fees.flat_usd < Decimal::ZERO {
+                errors.push(format!("fees for {} must not be negative", venue));
+            }
+        }
+        if let Err(schedule_errors) = self.activity_schedule.validate() {
+            errors.extend(schedule_errors);
+        }
+        if self.bootstrap.max_age_hours <= 0 {
+            errors.push(format!("bootstrap.max_age_hours must be positive, got {}", self.bootstrap.max_age_hours));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Field-level differences, keyed by dotted path (e.g. "stop_loss.long_default_pct")
+    pub fn diff(&self, other: &StrikeBoxConfig) -> Vec<ConfigChange> {
+        let mut changes = Vec::new();
+        let old = serde_json::to_value(self).unwrap_or_default();
+        let new = serde_json::to_value(other).unwrap_or_default();
+        diff_values("", &old, &new, &mut changes);
+        changes
+    }
+
+    /// Current value at a dotted path such as "token_validation.liquidity_min_usd"
+    pub fn threshold(&self, key: &str) -> Option<Decimal> {
+        let config = serde_json::to_value(self).ok()?;
+        let value = config.pointer(&format!("/{}", key.replace('.', "/")))?;
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    pub fn load_json(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let raw = std::fs::read_to_string(path.as_ref()).map_err(|e| e.to_string())?;
+        serde_json::from_str(&raw).map_err(|e| e.to_string())
+    }
+
+    /// Writes through a synced temp file beside `path` and renames it over, so a crash or a
+    /// concurrent reader never sees a torn config
+    pub fn save_json(&self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let raw = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(format!(".{}.tmp", Uuid::new_v4().simple()));
+        let tmp = PathBuf::from(tmp);
+        let written = std::fs::File::create(&tmp).and_then(|mut file| {
+            std::io::Write::write_all(&mut file, raw.as_bytes())?;
+            file.sync_all()
+        });
+        written.and_then(|()| std::fs::rename(&tmp, path)).map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            e.to_string()
+        })
+    }
+
+    /// `base` with every section `overrides` sets replaced; use `diff` to audit the result
+    pub fn merge(base: &StrikeBoxConfig, overrides: &PartialStrikeBoxConfig) -> StrikeBoxConfig {
+        fn pick<T: Clone>(over: &Option<T>, base: &T) -> T {
+            over.clone().unwrap_or_else(|| base.clone())
+        }
+
+        StrikeBoxConfig {
+            token_validation: pick(&overrides.token_validation, &base.token_validation),
+            safety_scoring: pick(&overrides.safety_scoring, &base.safety_scoring),
+            position_sizing: pick(&overrides.position_sizing, &base.position_sizing),
+            stop_loss: pick(&overrides.stop_loss, &base.stop_loss),
+            take_profit: pick(&overrides.take_profit, &base.take_profit),
+            time_control: pick(&overrides.time_control, &base.time_control),
+            risk_controller: pick(&overrides.risk_controller, &base.risk_controller),
+            log_retention: pick(&overrides.log_retention, &base.log_retention),
+            cex_validation: pick(&overrides.cex_validation, &base.cex_validation),
+            trigger_resolution: pick(&overrides.trigger_resolution, &base.trigger_resolution),
+            correlation: pick(&overrides.correlation, &base.correlation),
+            portfolio_history: pick(&overrides.portfolio_history, &base.portfolio_history),
+            fees: pick(&overrides.fees, &base.fees),
+            activity_schedule: pick(&overrides.activity_schedule, &base.activity_schedule),
+            bootstrap: pick(&overrides.bootstrap, &base.bootstrap),
+        }
+    }
+}
+
+/// Prefix of the override variables read by `PartialStrikeBoxConfig::from_env`
+pub const CONFIG_ENV_PREFIX: &str = "STRIKE_BOX__";
+/// Separates the section and field names in an override variable
+pub const CONFIG_ENV_SEPARATOR: &str = "__";
+
+/// Environment-specific layer over a base StrikeBoxConfig: a Some section replaces the base's
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartialStrikeBoxConfig {
+    pub token_validation: Option<TokenValidationConfig>,
+    pub safety_scoring: Option<SafetyScoreConfig>,
+    pub position_sizing: Option<PositionSizingConfig>,
+    pub stop_loss: Option<StopLossConfig>,
+    pub take_profit: Option<TakeProfitConfig>,
+    pub time_control: Option<TimeControlConfig>,
+    pub risk_controller: Option<RiskControllerConfig>,
+    pub log_retention: Option<LogRetentionConfig>,
+    pub cex_validation: Option<CexValidationConfig>,
+    pub trigger_resolution: Option<TriggerResolutionConfig>,
+    pub correlation: Option<CorrelationConfig>,
+    pub portfolio_history: Option<history::PortfolioHistoryConfig>,
+    pub fees: Option<FeeModel>,
+    pub activity_schedule: Option<schedule::ActivityScheduleConfig>,
+    pub bootstrap: Option<bootstrap::BootstrapConfig>,
+}
+
+impl PartialStrikeBoxConfig {
+    /// Overrides from the environment, layered over `base`. STRIKE_BOX__<SECTION> holds a JSON
+    /// object with just the fields to change (e.g. STRIKE_BOX__STOP_LOSS='{"long_default_pct":
+    /// "0.06"}'); STRIKE_BOX__<SECTION>__<FIELD> sets one field, nesting with further `__`
+    /// (e.g. STRIKE_BOX__STOP_LOSS__LONG_DEFAULT_PCT=0.06). Field variables apply after the
+    /// section's object. Every variable that names an unknown section or field, or doesn't
+    /// parse, is reported and nothing is returned.
+    pub fn from_env(base: &StrikeBoxConfig) -> Result<Self, Vec<String>> {
+        let vars: BTreeMap<String, String> = std::env::vars().filter(|(name, _)| name.starts_with(CONFIG_ENV_PREFIX)).collect();
+        Self::from_vars(base, &vars)
+    }
+
+    fn from_vars(base: &StrikeBoxConfig, vars: &BTreeMap<String, String>) -> Result<Self, Vec<String>> {
+        let base_sections = match serde_json::to_value(base) {
+            Ok(serde_json::Value::Object(sections)) => sections,
+            _ => return Err(vec!["base config does not serialize to a JSON object".to_string()]),
+        };
+        let mut errors = Vec::new();
+        let mut layered = serde_json::Map::new();
+        let mut overrides: Vec<(&String, Vec<String>, &String)> = vars
+            .iter()
+            .filter_map(|(var, raw)| {
+                let path = var.strip_prefix(CONFIG_ENV_PREFIX)?;
+                Some((var, path.split(CONFIG_ENV_SEPARATOR).map(str::to_lowercase).collect(), raw))
+            })
+            .collect();
+        // Whole-section objects first, then fields, shallowest first
+        overrides.sort_by_key(|(_, path, _)| path.len());
+
+        for (var, path, raw) in overrides {
+            let Some(base_section) = base_sections.get(&path[0]) else {
+                errors.push(format!("{}: unknown config section {}", var, path[0]));
+                continue;
+            };
+            let section = layered.entry(path[0].clone()).or_insert_with(|| base_section.clone());
+            let result = match &path[1..] {
+                [] => serde_json::from_str(raw)
+                    .map_err(|e| e.to_string())
+                    .and_then(|patch| merge_json(section, patch, &path[0])),
+                fields => set_json_field(section, fields, raw),
+            };
+            if let Err(e) = result {
+                errors.push(format!("{}: {}", var, e));
+            }
+        }
+
+        for (name, section) in &layered {
+            let single = serde_json::Value::Object(serde_json::Map::from_iter([(name.clone(), section.clone())]));
+            if let Err(e) = serde_json::from_value::<PartialStrikeBoxConfig>(single) {
+                errors.push(format!("{}{}: {}", CONFIG_ENV_PREFIX, name.to_uppercase(), e));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        serde_json::from_value(serde_json::Value::Object(layered)).map_err(|e| vec![e.to_string()])
+    }
+}
+
+/// Overlays `patch` onto `target`: objects merge key by key, anything else replaces.
+/// Keys `target` doesn't have are rejected so a typo can't pass as an override.
+fn merge_json(target: &mut serde_json::Value, patch: serde_json::Value, path: &str) -> Result<(), String> {
+    match (target, patch) {
+        (serde_json::Value::Object(fields), serde_json::Value::Object(patch_fields)) => {
+            for (key, value) in patch_fields {
+                let field_path = format!("{}.{}", path, key);
+                match fields.get_mut(&key) {
+                    Some(field) => merge_json(field, value, &field_path)?,
+                    None => return Err(format!("unknown field {}", field_path)),
+                }
+            }
+            Ok(())
+        }
+        (target, patch) => {
+            *target = patch;
+            Ok(())
+        }
+    }
+}
+
+/// Sets the field at `fields` under `section`. Fields that are strings in the base take `raw`
+/// as is; others parse it as JSON.
+fn set_json_field(section: &mut serde_json::Value, fields: &[String], raw: &str) -> Result<(), String> {
+    let mut target = section;
+    for (depth, field) in fields.iter().enumerate() {
+        target = target
+            .as_object_mut()
+            .and_then(|object| object.get_mut(field))
+            .ok_or_else(|| format!("unknown field {}", fields[..=depth].join(".")))?;
+    }
+    *target = match target {
+        serde_json::Value::String(_) => serde_json::Value::String(raw.to_string()),
+        _ => serde_json::from_str(raw).map_err(|e| e.to_string())?,
+    };
+    Ok(())
+}
+
+fn diff_values(path: &str, old: &serde_json::Value, new: &serde_json::Value, changes: &mut Vec<ConfigChange>) {
+    match (old, new) {
+        (serde_json::Value::Object(old_fields), serde_json::Value::Object(new_fields)) => {
+            for (key, old_value) in old_fields {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                let new_value = new_fields.get(key).unwrap_or(&serde_json::Value::Null);
+                diff_values(&child, old_value, new_value, changes);
+            }
+        }
+        _ if old != new => changes.push(ConfigChange {
+            path: path.to_string(),
+            old_value: old.clone(),
+            new_value: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Settings copied onto a position at entry; changing them does not move existing orders
+const ENTRY_BAKED_SETTINGS: [&str; 6] = [
+    "stop_loss.long_default_pct",
+    "stop_loss.long_volatile_pct",
+    "stop_loss.long_volatile_min_safety",
+    "stop_loss.short_fixed_pct",
+    "take_profit.long_levels",
+    "take_profit.short_levels",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionRepricing {
+    pub execution_id: Uuid,
+    pub token_symbol: String,
+    pub direction: Direction,
+    pub old_stop_loss: Decimal,
+    pub new_stop_loss: Decimal,
+    pub old_take_profits: Vec<Decimal>,
+    pub new_take_profits: Vec<Decimal>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BookStatus {
+    WithinLimits,
+    NoNewEntriesUntilUnderLimit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookCapacityStatus {
+    pub direction: Direction,
+    pub positions: u32,
+    pub max_positions: u32,
+    pub allocation_usd: Decimal,
+    pub max_allocation_usd: Decimal,
+    pub status: BookStatus,
+}
+
+impl BookCapacityStatus {
+    fn of(book: &PositionBook) -> Self {
+        Self {
+            direction: book.direction,
+            positions: book.position_count(),
+            max_positions: book.max_positions,
+            allocation_usd: book.total_allocation_usd,
+            max_allocation_usd: book.max_allocation_usd,
+            status: if book.is_over_capacity() {
+                BookStatus::NoNewEntriesUntilUnderLimit
+            } else {
+                BookStatus::WithinLimits
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigUpdateReport {
+    pub applied: bool,
+    pub errors: Vec<String>,
+    pub changes: Vec<ConfigChange>,
+    pub warnings: Vec<String>,
+    pub repriced_positions: Vec<PositionRepricing>,
+    pub book_status: Vec<BookCapacityStatus>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "hot-reload")]
+pub use notify::RecommendedWatcher as ConfigWatcher;
+
+/// Watches a JSON config file and hands each parsed revision to `on_change`.
+/// The parent directory is watched so editors that replace the file are still seen.
+#[cfg(feature = "hot-reload")]
+pub fn watch_config_file<F>(
+    path: impl Into<PathBuf>,
+    on_change: F,
+) -> notify::Result<ConfigWatcher>
+where
+    F: Fn(Result<StrikeBoxConfig, String>) + Send + 'static,
+{
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let path: PathBuf = path.into();
+    let directory = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let file_name = path.file_name().map(|n| n.to_os_string());
+    let watched = path.clone();
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            return;
+        }
+        if event.paths.iter().any(|p| p.file_name().map(|n| n.to_os_string()) == file_name) {
+            on_change(StrikeBoxConfig::load_json(&watched));
+        }
+    })?;
+    watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+// ============================================================
+// SECTION 17: STRIKE BOX ENGINE
+// ============================================================
+
+pub struct StrikeBoxEngine {
+    pub config: StrikeBoxConfig,
+    pub portfolio: PortfolioState,
+    pub entry_logs: RingBuffer<EntryLog>,
+    pub exit_logs: RingBuffer<ExitLog>,
+    pub rejection_logs: RingBuffer<RejectionLog>,
+    pub capital_events: RingBuffer<CapitalEvent>,
+    pub config_audit: RingBuffer<ConfigUpdateReport>,
+    pub audit_snapshots: RingBuffer<AuditSnapshot>,
+    pub market_events: RingBuffer<MarketEventRecord>,
+    pub fill_adjustments: RingBuffer<FillAdjustment>,
+    pub validation_audit_log: RingBuffer<ValidationAuditRecord>,
+    pub orphaned_execution_reports: RingBuffer<ExecutionReport>,
+    pending_execution_reports: Vec<(ExecutionReport, DateTime<Utc>)>,
+    execution_report_grace: chrono::Duration,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    audit_spill_failures: std::sync::atomic::AtomicU64, // Records the sink failed to take, and so lost
+    audit_signing_key: Option<Vec<u8>>,
+    halt_reset: bool, // Halt cleared by an operator; the next tick moves to Recovering
+    recovery: Option<RecoveryState>, // Latest probe-driven recovery from a consecutive-failure halt
+    pub correlations: CorrelationTracker,
+    pub price_history: PriceHistory,
+    pub portfolio_history: history::PortfolioHistory,
+    pub slippage_pause: SlippagePauseController,
+    pub command_audit_log: RingBuffer<CommandAuditRecord>,
+    command_bus: CommandBus,
+    reservations: BTreeMap<ReservationId, EntryReservation>,
+    reservation_ttl: chrono::Duration,
+    position_retention: chrono::Duration,
+    last_compaction: Option<DateTime<Utc>>,
+    borrow_provider: Option<Arc<dyn BorrowAvailabilityProvider>>, // When set, every short entry needs a fresh quote
+    borrow_quotes: BTreeMap<String, BorrowQuote>, // Last quote by token, kept while fresh or backing an open short
+    config_path: Option<PathBuf>, // Where operator edits such as proxy exceptions are persisted
+    validation_cache: Option<Mutex<ValidationCache>>, // Opt-in; only validate_entry consults it
+    config_fingerprint: Vec<u8>, // Config and session scoring as cache key bytes, refreshed when either changes
+    pub gate_registry: gates::GateRegistry,
+    computed_cache: Option<ComputedCache>, // Set by warmup
+    outcome_attribution: attribution::OutcomeAttribution,
+    market_conditions: Option<MarketConditions>,
+    session_safety_scoring: Option<SafetyScoreConfig>, // Calibrated to market_conditions; never persisted
+    cold_validation_warned: std::sync::atomic::AtomicBool, // validate_entry warns once before warmup
+    batch_candidate_cost_ms: Mutex<Option<f64>>, // Moving average of one batch candidate's gate time
+    evaluation_time: Option<DateTime<Utc>>, // Clock gates and sizing read; the wall clock when None
+    last_rescore: Vec<rescore::RescoreResult>, // From the latest rescore_positions, for the TimeStops report
+    last_long_reviews: Vec<LongReview>, // From the latest review_long_holds, for the TimeStops report
+    failure_tracker: failures::FailureTracker, // Classified execution failures and the venues they paused
+}
+
+impl StrikeBoxEngine {
+    pub fn new(config: StrikeBoxConfig, total_capital: Decimal) -> Self {
+        let long_max = total_capital * config.position_sizing.long_book_max_pct;
+        let short_max = total_capital * config.position_sizing.short_book_max_pct;
+
+        Self {
+            config: config.clone(),
+            portfolio: PortfolioState {
+                total_capital_usd: total_capital,
+                available_capital_usd: total_capital,
+                long_book: PositionBook::new(
+                    Direction::Long,
+                    long_max,
+                    config.position_sizing.long_book_max_positions,
+                ),
+                short_book: PositionBook::new(
+                    Direction::Short,
+                    short_max,
+                    config.position_sizing.short_book_max_positions,
+                ),
+                gross_exposure_pct: Decimal::ZERO,
+                net_exposure_pct: Decimal::ZERO,
+                gross_exposure_usd: Decimal::ZERO,
+                net_exposure_usd: Decimal::ZERO,
+                daily_pnl_usd: Decimal::ZERO,
+                weekly_pnl_usd: Decimal::ZERO,
+                monthly_pnl_usd: Decimal::ZERO,
+                daily_drawdown_pct: Decimal::ZERO,
+                weekly_drawdown_pct: Decimal::ZERO,
+                monthly_drawdown_pct: Decimal::ZERO,
+                daily_high_water_mark: total_capital,
+                weekly_high_water_mark: total_capital,
+                monthly_high_water_mark: total_capital,
+                daily_realized_drawdown_pct: Decimal::ZERO,
+                daily_unrealized_drawdown_pct: Decimal::ZERO,
+                daily_realized_high_water_mark: total_capital,
+                state: SystemState::Active,
+                consecutive_failures: 0,
+                total_slippage_cost_usd: Decimal::ZERO,
+                liquidation_count: 0,
+                drawdown_baseline: DrawdownBaseline { marked_equity_usd: total_capital, ..Default::default() },
+                last_updated: Utc::now(),
+            },
+            entry_logs: RingBuffer::new(config.log_retention.max_entry_logs),
+            exit_logs: RingBuffer::new(config.log_retention.max_exit_logs),
+            rejection_logs: RingBuffer::new(config.log_retention.max_rejection_logs),
+            capital_events: RingBuffer::new(config.log_retention.max_capital_events),
+            config_audit: RingBuffer::new(config.log_retention.max_config_reports),
+            audit_snapshots: RingBuffer::new(config.log_retention.max_audit_snapshots),
+            market_events: RingBuffer::new(config.log_retention.max_market_events),
+            fill_adjustments: RingBuffer::new(config.log_retention.max_fill_adjustments),
+            validation_audit_log: RingBuffer::new(config.log_retention.max_validation_records),
+            orphaned_execution_reports: RingBuffer::new(config.log_retention.max_orphaned_reports),
+            pending_execution_reports: Vec::new(),
+            execution_report_grace: chrono::Duration::seconds(EXECUTION_REPORT_GRACE_SECONDS),
+            audit_sink: None,
+            audit_spill_failures: std::sync::atomic::AtomicU64::new(0),
+            audit_signing_key: None,
+            halt_reset: false,
+            recovery: None,
+            correlations: CorrelationTracker::default(),
+            price_history: PriceHistory::default(),
+            portfolio_history: history::PortfolioHistory::new(&config.portfolio_history),
+            slippage_pause: SlippagePauseController::default(),
+            command_audit_log: RingBuffer::new(config.log_retention.max_command_records),
+            command_bus: CommandBus::new(),
+            reservations: BTreeMap::new(),
+            borrow_provider: None,
+            borrow_quotes: BTreeMap::new(),
+            reservation_ttl: chrono::Duration::seconds(ENTRY_RESERVATION_TTL_SECONDS),
+            position_retention: chrono::Duration::hours(POSITION_RETENTION_HOURS),
+            last_compaction: None,
+            config_path: None,
+            validation_cache: None,
+            config_fingerprint: Vec::new(),
+            gate_registry: gates::GateRegistry::default(),
+            computed_cache: None,
+            outcome_attribution: attribution::OutcomeAttribution::default(),
+            market_conditions: None,
+            session_safety_scoring: None,
+            cold_validation_warned: std::sync::atomic::AtomicBool::new(false),
+            batch_candidate_cost_ms: Mutex::new(None),
+            evaluation_time: None,
+            last_rescore: Vec::new(),
+            last_long_reviews: Vec::new(),
+            failure_tracker: failures::FailureTracker::default(),
+        }
+    }
+
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Enables the "borrow_availability" gate: from then on no short enters without a fresh
+    /// quote from `provider`
+    pub fn with_borrow_provider(mut self, provider: Arc<dyn BorrowAvailabilityProvider>) -> Self {
+        self.borrow_provider = Some(provider);
+        self
+    }
+
+    /// Enables a signed portfolio snapshot on every tick
+    pub fn with_audit_signing_key(mut self, signing_key: impl Into<Vec<u8>>) -> Self {
+        self.audit_signing_key = Some(signing_key.into());
+        self
+    }
+
+    /// How long a confirmation token for a destructive command stays valid
+    pub fn with_confirmation_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.command_bus.confirmation_ttl = ttl;
+        self
+    }
+
+    /// How long a report for an unknown execution id waits for its log before being orphaned
+    pub fn with_execution_report_grace(mut self, grace: chrono::Duration) -> Self {
+        self.execution_report_grace = grace;
+        self
+    }
+
+    /// How long an entry reservation holds book capacity without being committed
+    pub fn with_reservation_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.reservation_ttl = ttl;
+        self
+    }
+
+    /// How long closed positions stay on their book before tick compacts them away
+    pub fn with_position_retention(mut self, retention: chrono::Duration) -> Self {
+        self.position_retention = retention;
+        self
+    }
+
+    /// Config file that operator edits are written back to, so they survive a restart
+    pub fn with_config_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_path = Some(path.into());
+        self
+    }
+
+    /// Reuse `validate_entry` results for up to `ttl` while the snapshot, config, gate registry
+    /// and coarse portfolio state are unchanged. The config is read as of this call and each
+    /// `apply_config_update`, proxy exception edit or market calibration; edit it through those, not
+    /// the field, while the cache is on. The cache is bypassed while `set_evaluation_time` pins
+    /// the clock, and `simulate_entry_impact` always runs the gates.
+    pub fn with_validation_cache(mut self, ttl: chrono::Duration, capacity: usize) -> Self {
+        self.validation_cache = Some(Mutex::new(ValidationCache::new(ttl, capacity)));
+        self.refresh_config_fingerprint();
+        self
+    }
+
+    /// Recomputes the config part of validation cache keys; a no-op without the cache
+    fn refresh_config_fingerprint(&mut self) {
+        use std::hash::Hash;
+        if self.validation_cache.is_none() {
+            return;
+        }
+        let mut fingerprint = KeyBytes::default();
+        serde_json::to_vec(&self.config).unwrap_or_default().hash(&mut fingerprint);
+        serde_json::to_vec(&self.session_safety_scoring).unwrap_or_default().hash(&mut fingerprint);
+        self.config_fingerprint = fingerprint.0;
+    }
+
+    /// Hits, misses and evictions of the validation cache; None when it is not enabled
+    pub fn validation_cache_stats(&self) -> Option<ValidationCacheStats> {
+        let cache = self.validation_cache.as_ref()?;
+        cache.lock().ok().map(|cache| cache.stats())
+    }
+
+    /// Precomputes the ideal-token safety score, the liquidity tiers in dollars and the
+    /// exposure bounds, so the first entry decision does not pay for them. A computation that
+    /// fails leaves its cache slot empty and is reported; the engine counts as warmed either way.
+    pub fn warmup(&mut self) -> WarmupResult {
+        let started = std::time::Instant::now();
+        let mut cache = ComputedCache::default();
+        let mut succeeded = Vec::new();
+        let mut failed = Vec::new();
+
+        let tv = &self.config.token_validation;
+        let ideal = TokenSnapshot {
+            token_address: String::new(),
+            token_symbol: "IDEAL".to_string(),
+            liquidity_usd: (tv.liquidity_min_usd + tv.liquidity_max_usd) / Decimal::TWO,
+            bid_depth_usd: tv.liquidity_max_usd / Decimal::TWO,
+            ask_depth_usd: tv.liquidity_max_usd / Decimal::TWO,
+            holder_count: tv.holder_count_preferred,
+            top_10_concentration_pct: Decimal::ZERO,
+            largest_wallet_pct: Decimal::ZERO,
+            token_age_hours: tv.token_age_preferred_long_hours.max(tv.token_age_preferred_short_hours),
+            contract_verified: true,
+            is_proxy_contract: false,
+            deployment_timestamp: Utc::now(),
+            snapshot_timestamp: Utc::now(),
+            source: SnapshotSource::Dex,
+            categories: None,
+        };
+        let score = SafetyScore::calculate(&ideal, self.safety_scoring(), tv);
+        if score.total_score >= self.config.safety_scoring.long_entry_min {
+            cache.ideal_safety_score = Some(score);
+            succeeded.push(WarmupComputation::IdealSafetyScore);
+        } else {
+            failed.push((
+                WarmupComputation::IdealSafetyScore,
+                format!(
+                    "ideal token scores {}, below the long entry minimum {}",
+                    score.total_score, self.config.safety_scoring.long_entry_min
+                ),
+            ));
+        }
+
+        let total = self.portfolio.total_capital_usd;
+        if tv.liquidity_min_usd <= tv.liquidity_max_usd {
+            cache.liquidity_tiers = Some(
+                LiquidityScaler::tiers()
+                    .into_iter()
+                    .map(|(floor, pct)| LiquidityTier {
+                        min_liquidity_usd: floor,
+                        max_position_pct: pct,
+                        max_position_usd: total * pct,
+                    })
+                    .collect(),
+            );
+            succeeded.push(WarmupComputation::LiquidityTiers);
+        } else {
+            failed.push((
+                WarmupComputation::LiquidityTiers,
+                format!("liquidity band is empty ({} to {})", tv.liquidity_min_usd, tv.liquidity_max_usd),
+            ));
+        }
+
+        let sizing = &self.config.position_sizing;
+        let risk = &self.config.risk_controller;
+        if total > Decimal::ZERO {
+            let long_max_usd = total * sizing.long_book_max_pct;
+            let short_max_usd = total * sizing.short_book_max_pct;
+            cache.exposure_bounds = Some(ExposureBounds {
+                long_max_usd,
+                short_max_usd,
+                gross_max_usd: long_max_usd + short_max_usd,
+                net_min_usd: total * risk.net_exposure_min_pct,
+                net_max_usd: total * risk.net_exposure_max_pct,
+            });
+            succeeded.push(WarmupComputation::ExposureBounds);
+        } else {
+            failed.push((WarmupComputation::ExposureBounds, format!("total capital is {}", total)));
+        }
+
+        self.computed_cache = Some(cache);
+        let result = WarmupResult { elapsed_ms: started.elapsed().as_millis() as u64, succeeded, failed };
+        for (computation, reason) in &result.failed {
+            warn!(?computation, %reason, "warmup computation failed");
+        }
+        info!(elapsed_ms = result.elapsed_ms, succeeded = result.succeeded.len(), "engine warmed up");
+        result
+    }
+
+    pub fn is_warmed_up(&self) -> bool {
+        self.computed_cache.is_some()
+    }
+
+    /// Calibrates safety score weights to `conditions` for this session. The persisted config
+    /// keeps its weights; config updates are recalibrated until `clear_market_calibration`.
+    pub fn calibrate_for_market(&mut self, conditions: MarketConditions) {
+        let calibrated = self.config.safety_scoring.auto_calibrate(&conditions);
+        info!(
+            ?conditions,
+            liquidity_weight = %calibrated.liquidity_weight,
+            holder_weight = %calibrated.holder_weight,
+            age_weight = %calibrated.age_weight,
+            "safety score weights calibrated"
+        );
+        self.market_conditions = Some(conditions);
+        self.session_safety_scoring = Some(calibrated);
+        self.refresh_config_fingerprint();
+        if self.is_warmed_up() {
+            self.warmup();
+        }
+    }
+
+    /// Returns to the configured safety score weights
+    pub fn clear_market_calibration(&mut self) {
+        self.market_conditions = None;
+        self.session_safety_scoring = None;
+        self.refresh_config_fingerprint();
+        if self.is_warmed_up() {
+            self.warmup();
+        }
+    }
+
+    pub fn market_conditions(&self) -> Option<&MarketConditions> {
+        self.market_conditions.as_ref()
+    }
+
+    /// Safety scoring in effect: the session calibration if any, else the config's
+    pub fn safety_scoring(&self) -> &SafetyScoreConfig {
+        self.session_safety_scoring.as_ref().unwrap_or(&self.config.safety_scoring)
+    }
+
+    /// Values precomputed by `warmup`; None before it runs
+    pub fn computed_cache(&self) -> Option<&ComputedCache> {
+        self.computed_cache.as_ref()
+    }
+
+    /// Allow the proxy contract at `token_address` even when proxies are rejected.
+    /// Returns whether it was newly added.
+    pub fn add_proxy_exception(&mut self, token_address: &str) -> Result<bool, String> {
+        let added = self
+            .config
+            .token_validation
+            .proxy_contract_exceptions
+            .insert(token_address.to_lowercase());
+        if added {
+            info!(token_address, "proxy contract exception added");
+            self.refresh_config_fingerprint();
+            self.persist_config()?;
+        }
+        Ok(added)
+    }
+
+    /// Returns whether `token_address` was on the exception list
+    pub fn remove_proxy_exception(&mut self, token_address: &str) -> Result<bool, String> {
+        let removed = self
+            .config
+            .token_validation
+            .proxy_contract_exceptions
+            .remove(&token_address.to_lowercase());
+        if removed {
+            info!(token_address, "proxy contract exception removed");
+            self.refresh_config_fingerprint();
+            self.persist_config()?;
+        }
+        Ok(removed)
+    }
+
+    fn persist_config(&self) -> Result<(), String> {
+        match &self.config_path {
+            Some(path) => self.config.save_json(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Exit levels a move from `prev_price` to `new_price` reached on `position`. Every level
+    /// the move spanned counts, since the price passed through it. When the move reached both
+    /// take-profits and the stop, `config.trigger_resolution.path_assumption` orders them.
+    pub fn resolve_triggers(
+        position: &Position,
+        prev_price: Decimal,
+        new_price: Decimal,
+        config: &StrikeBoxConfig,
+    ) -> TriggerResolution {
+        let assumption = config.trigger_resolution.path_assumption;
+        let (low, high) = (prev_price.min(new_price), prev_price.max(new_price));
+
+        // An armed trailing stop above the fixed stop is the one that fires
+        let stop_price = match (position.direction, position.trailing_stop_active, position.trailing_stop_high) {
+            (Direction::Long, true, Some(hwm)) => position.stop_loss_price.max(config.stop_loss.trailing_stop_price(hwm)),
+            _ => position.stop_loss_price,
+        };
+        let stop = match position.direction {
+            Direction::Long => low <= stop_price,
+            Direction::Short => high >= stop_price,
+        }
+        .then_some(TriggerEvent::StopLoss { price: stop_price });
+        // A leveraged position whose liquidation price sits short of its stop is liquidated by
+        // the venue before the stop can fire
+        let liquidation = position
+            .liquidation_price(config.risk_controller.maintenance_margin_pct)
+            .filter(|&price| match position.direction {
+                Direction::Long => price > stop_price && low <= price,
+                Direction::Short => price < stop_price && high >= price,
+            })
+            .map(|price| TriggerEvent::Liquidation { price });
+        let stop = liquidation.or(stop);
+        let take_profits: Vec<TriggerEvent> = position
+            .take_profit_targets
+            .iter()
+            .enumerate()
+            .filter(|(_, target)| {
+                !target.hit
+                    && match position.direction {
+                        Direction::Long => high >= target.price,
+                        Direction::Short => low <= target.price,
+                    }
+            })
+            .map(|(level, target)| TriggerEvent::TakeProfit { level, price: target.price })
+            .collect();
+
+        let ambiguous = stop.is_some() && !take_profits.is_empty();
+        let events = match stop {
+            None => take_profits,
+            Some(stop) if !ambiguous => vec![stop],
+            Some(stop) => {
+                let midpoint = (prev_price + new_price) / Decimal::TWO;
+                let distance = |price: Decimal| (price - midpoint).abs();
+                let mut events: Vec<TriggerEvent> = match assumption {
+                    TriggerPathAssumption::Conservative => Vec::new(),
+                    TriggerPathAssumption::Optimistic => take_profits,
+                    TriggerPathAssumption::Midpoint => take_profits
+                        .into_iter()
+                        .filter(|tp| distance(tp.price()) < distance(stop.price()))
+                        .collect(),
+                };
+                events.push(stop);
+                events
+            }
+        };
+
+        TriggerResolution { events, assumption, ambiguous }
+    }
+
+    /// Marks the open position `execution_id` at `new_price`, takes whatever exit levels the move
+    /// reached in resolved order, records each exit and releases the exited share of its allocation.
+    /// Returns the exits, or None if no open position has that id.
+    pub fn process_price_update(
+        &mut self,
+        execution_id: Uuid,
+        new_price: Decimal,
+        new_liquidity: Decimal,
+    ) -> Option<(MarkToMarketResult, Vec<PositionExit>)> {
+        let token_address = self
+            .portfolio
+            .long_book
+            .positions
+            .iter()
+            .chain(&self.portfolio.short_book.positions)
+            .find(|p| p.execution_id == execution_id && p.is_open())?
+            .token_address
+            .clone();
+        self.price_history.record(&token_address, new_price);
+        self.apply_price_update(execution_id, new_price, new_liquidity)
+    }
+
+    /// process_price_update for each open position whose token has a price in `prices`, keyed by
+    /// token address, recording each token's price once. Liquidity is taken as unchanged from
+    /// entry. Returns each updated position's id, mark and exits, longs first.
+    pub fn mark_all_books(&mut self, prices: &HashMap<String, Decimal>) -> Vec<(Uuid, MarkToMarketResult, Vec<PositionExit>)> {
+        let open: Vec<(Uuid, Decimal, Decimal)> = self
+            .portfolio
+            .long_book
+            .positions
+            .iter()
+            .chain(&self.portfolio.short_book.positions)
+            .filter(|p| p.is_open())
+            .filter_map(|p| prices.get(&p.token_address).map(|&price| (p.execution_id, price, p.liquidity_at_entry)))
+            .collect();
+        for (token_address, &price) in prices {
+            self.price_history.record(token_address, price);
+        }
+        let updates = open
+            .into_iter()
+            .filter_map(|(execution_id, price, liquidity)| {
+                let (mark, exits) = self.apply_price_update(execution_id, price, liquidity)?;
+                Some((execution_id, mark, exits))
+            })
+            .collect();
+        self.portfolio.long_book.update_unrealized_pnl();
+        self.portfolio.short_book.update_unrealized_pnl();
+        updates
+    }
+
+    /// process_price_update without recording the price
+    fn apply_price_update(
+        &mut self,
+        execution_id: Uuid,
+        new_price: Decimal,
+        new_liquidity: Decimal,
+    ) -> Option<(MarkToMarketResult, Vec<PositionExit>)> {
+        let book = [&mut self.portfolio.long_book, &mut self.portfolio.short_book]
+            .into_iter()
+            .find(|book| {
+                book.positions
+                    .iter()
+                    .any(|p| p.execution_id == execution_id && p.is_open())
+            })?;
+        let position = book.positions.iter_mut().find(|p| p.execution_id == execution_id)?;
+        let span = position.span();
+        let _entered = span.enter();
+
+        let prev_price = position.current_price;
+        let mark = position.mark_to_market(new_price, new_liquidity);
+        let resolution = Self::resolve_triggers(position, prev_price, new_price, &self.config);
+        if resolution.ambiguous {
+            debug!(assumption = ?resolution.assumption, "stop and take-profit both crossed");
+        }
+        let mut exits = position.apply_triggers(&resolution, new_liquidity);
+        for exit in &mut exits {
+            position.charge_fees(exit, &self.config.fees);
+        }
+        let released = position.position_size_usd * exits.iter().map(|e| e.exit_size_pct).sum::<Decimal>();
+        book.total_allocation_usd = (book.total_allocation_usd - released).max(Decimal::ZERO);
+        book.realized_pnl_usd += exits.iter().map(|e| e.realized_pnl_usd).sum::<Decimal>();
+        book.costs_usd += exits.iter().map(|e| e.slippage_cost_usd() + e.fees_usd).sum::<Decimal>();
+        debug!(%new_price, unrealized_pnl_usd = %position.unrealized_pnl_usd, "position marked");
+
+        for exit in &exits {
+            self.record_exit(ExitLog::from(exit));
+            if exit.exit_type == ExitType::Liquidation {
+                self.note_liquidation(exit, Decimal::ZERO);
+            }
+        }
+        if !exits.is_empty() {
+            self.portfolio.total_slippage_cost_usd += exits.iter().map(PositionExit::slippage_cost_usd).sum::<Decimal>();
+            self.portfolio.calculate_exposure();
+            self.check_slippage();
+        }
+        Some((mark, exits))
+    }
+
+    /// Feed a price for a token with no open position, e.g. a candidate being watched before entry
+    pub fn observe_token(&mut self, token_address: &str, price: Decimal) {
+        self.price_history.record(token_address, price);
+    }
+
+    /// Initial long stop for `token_address` under `config.stop_loss.mode`, from its observed prices
+    pub fn long_stop(&self, token_address: &str, entry_price: Decimal, safety_score: Decimal) -> (Decimal, StopBasis) {
+        let recent_prices = self.price_history.prices(token_address);
+        let (stop_loss_price, basis) = self.config.stop_loss.long_stop(entry_price, safety_score, &recent_prices);
+        if let StopBasis::InsufficientHistory { observed_returns, required_returns, .. } = basis {
+            debug!(token_address, observed_returns, required_returns, "too little history for a volatility-scaled stop, using safety tiers");
+        }
+        (stop_loss_price, basis)
+    }
+
+    /// Position::open on the token's observed prices, with the long stop's basis for the entry log
+    pub fn open_position(
+        &self,
+        token: &TokenSnapshot,
+        validation: &RiskValidation,
+        entry_price: Decimal,
+        position_size_usd: Decimal,
+        safety: SafetyScore,
+    ) -> Result<(Position, Option<StopBasis>), PositionError> {
+        let recent_prices = self.price_history.prices(&token.token_address);
+        let position = Position::open(token, validation, entry_price, position_size_usd, safety, &recent_prices, &self.config)?;
+        let basis = match position.direction {
+            Direction::Long => Some(self.long_stop(&token.token_address, entry_price, position.safety_score_at_entry).1),
+            Direction::Short => None,
+        };
+        Ok((position, basis))
+    }
+
+    /// Puts an opened position on its book and counts its size against the book's allocation
+    pub fn book_position(&mut self, mut position: Position) {
+        position.backfill_trailing_activation(&self.config.stop_loss);
+        let book = match position.direction {
+            Direction::Long => &mut self.portfolio.long_book,
+            Direction::Short => &mut self.portfolio.short_book,
+        };
+        book.total_allocation_usd += position.position_size_usd;
+        book.positions.push(position);
+        self.portfolio.calculate_exposure();
+    }
+
+    /// Manually closes what remains of a position at `exit_price`, net of its venue's fees, logs
+    /// the exit and releases the exited share of its allocation. The exit's slippage cost is added to the portfolio
+    /// total, which can trip the slippage pause. None when no open position has `execution_id`.
+    pub fn close_position(
+        &mut self,
+        execution_id: Uuid,
+        exit_price: Decimal,
+        liquidity_at_exit: Decimal,
+        slippage_bps: Decimal,
+    ) -> Option<PositionExit> {
+        let book = [&mut self.portfolio.long_book, &mut self.portfolio.short_book]
+            .into_iter()
+            .find(|book| {
+                book.positions
+                    .iter()
+                    .any(|p| p.execution_id == execution_id && p.is_open())
+            })?;
+        let position = book.positions.iter_mut().find(|p| p.execution_id == execution_id)?;
+        let _entered = position.span().entered();
+        let mut exit = position.close_at(exit_price, liquidity_at_exit)?;
+        exit.slippage_bps = slippage_bps;
+        position.charge_fees(&mut exit, &self.config.fees);
+        let released = position.position_size_usd * exit.exit_size_pct;
+        book.total_allocation_usd = (book.total_allocation_usd - released).max(Decimal::ZERO);
+        book.realized_pnl_usd += exit.realized_pnl_usd;
+        book.costs_usd += exit.slippage_cost_usd() + exit.fees_usd;
+        book.update_unrealized_pnl();
+        info!(%exit_price, realized_pnl_usd = %exit.realized_pnl_usd, "position closed manually");
+
+        self.record_exit(ExitLog::from(&exit));
+        self.portfolio.total_slippage_cost_usd += exit.slippage_cost_usd();
+        self.portfolio.calculate_exposure();
+        self.check_slippage();
+        Some(exit)
+    }
+
+    /// Books a venue liquidation of the open position `execution_id`: closes what remains at
+    /// `liquidation_price` with status Liquidated, takes `fees_usd` and its share of the entry fee
+    /// off its realized P&L and releases its allocation. None when no open position has `execution_id`.
+    pub fn mark_liquidated(
+        &mut self,
+        execution_id: Uuid,
+        liquidation_price: Decimal,
+        fees_usd: Decimal,
+    ) -> Option<PositionExit> {
+        let book = [&mut self.portfolio.long_book, &mut self.portfolio.short_book]
+            .into_iter()
+            .find(|book| book.positions.iter().any(|p| p.execution_id == execution_id && p.is_open()))?;
+        let position = book.positions.iter_mut().find(|p| p.execution_id == execution_id)?;
+        let _entered = position.span().entered();
+        // Filled by the venue; exit depth is unknown
+        let mut exit = position.liquidate_at(liquidation_price, Decimal::ZERO, fees_usd)?;
+        position.charge_fees(&mut exit, &self.config.fees);
+        let released = position.position_size_usd * exit.exit_size_pct;
+        book.total_allocation_usd = (book.total_allocation_usd - released).max(Decimal::ZERO);
+        book.realized_pnl_usd += exit.realized_pnl_usd;
+        book.costs_usd += exit.slippage_cost_usd() + exit.fees_usd;
+        book.update_unrealized_pnl();
+
+        self.record_exit(ExitLog::from(&exit));
+        self.note_liquidation(&exit, fees_usd);
+        self.portfolio.total_slippage_cost_usd += exit.slippage_cost_usd();
+        self.portfolio.calculate_exposure();
+        self.check_slippage();
+        Some(exit)
+    }
+
+    fn note_liquidation(&mut self, exit: &PositionExit, fees_usd: Decimal) {
+        self.portfolio.liquidation_count += 1;
+        self.position_span(exit.execution_id).in_scope(|| {
+            error!(
+                severity = ?RiskLevel::Critical,
+                liquidation_price = %exit.exit_price,
+                realized_pnl_usd = %exit.realized_pnl_usd,
+                %fees_usd,
+                liquidation_count = self.portfolio.liquidation_count,
+                "position liquidated"
+            );
+        });
+    }
+
+    /// Validates an entry and holds a slot and `planned_size_usd` of allocation on its book
+    /// until the order's fills are committed, the reservation is cancelled, or it expires.
+    /// Held capacity counts against later validations, so parallel entries can't oversubscribe.
+    pub fn reserve_entry(
+        &mut self,
+        token: &TokenSnapshot,
+        direction: Direction,
+        planned_size_usd: Decimal,
+    ) -> Result<ReservationId, ReservationError> {
+        let now = Utc::now();
+        self.expire_reservations(now);
+        if planned_size_usd <= Decimal::ZERO {
+            return Err(ReservationError::NonPositiveSize(planned_size_usd));
+        }
+        let validation = self.validate_entry(token, direction);
+        if !validation.all_passed {
+            return Err(ReservationError::Rejected(validation));
+        }
+        let book = match direction {
+            Direction::Long => &mut self.portfolio.long_book,
+            Direction::Short => &mut self.portfolio.short_book,
+        };
+        if !book.can_add_position(planned_size_usd) {
+            return Err(ReservationError::NoCapacity { direction, planned_size_usd });
+        }
+        book.reserve(planned_size_usd);
+
+        let reservation_id = Uuid::new_v4();
+        let safety = SafetyScore::calculate(token, self.safety_scoring(), &self.config.token_validation);
+        debug!(%reservation_id, token = %token.token_symbol, %planned_size_usd, "entry reserved");
+        self.reservations.insert(
+            reservation_id,
+            EntryReservation {
+                reservation_id,
+                token: token.clone(),
+                validation,
+                safety,
+                planned_size_usd,
+                reserved_at: now,
+                expires_at: now + self.reservation_ttl,
+            },
+        );
+        Ok(reservation_id)
+    }
+
+    /// Opens and books the reserved position at the fills' size and volume-weighted price.
+    /// Fills under `partial_fill_min_pct` of plan, or over plan by more than the book has
+    /// left, are refused and the reservation is kept, so the caller can cancel it and retry.
+    pub fn commit_entry(&mut self, reservation_id: ReservationId, fills: &[Fill]) -> Result<Uuid, ReservationError> {
+        self.expire_reservations(Utc::now());
+        let reservation = self
+            .reservations
+            .get(&reservation_id)
+            .ok_or(ReservationError::UnknownReservation(reservation_id))?;
+
+        let filled_tokens: Decimal = fills.iter().map(|f| f.size_tokens).sum();
+        let filled_usd: Decimal = fills.iter().map(|f| f.price * f.size_tokens).sum();
+        let filled_pct = filled_usd / reservation.planned_size_usd;
+        let min_pct = self.config.risk_controller.partial_fill_min_pct;
+        if filled_tokens <= Decimal::ZERO || filled_pct < min_pct {
+            return Err(ReservationError::BelowMinFill { filled_pct, min_pct });
+        }
+        // The reservation only held the plan; anything over it must fit in what the book has left
+        let book = match reservation.validation.direction {
+            Direction::Long => &self.portfolio.long_book,
+            Direction::Short => &self.portfolio.short_book,
+        };
+        let available_usd = book.available_capacity_usd();
+        if filled_usd - reservation.planned_size_usd > available_usd {
+            return Err(ReservationError::Overfilled {
+                filled_usd,
+                planned_size_usd: reservation.planned_size_usd,
+                available_usd,
+            });
+        }
+
+        let (position, _) = self
+            .open_position(
+                &reservation.token,
+                &reservation.validation,
+                filled_usd / filled_tokens,
+                filled_usd,
+                reservation.safety.clone(),
+            )
+            .map_err(ReservationError::Position)?;
+        let reservation = self.release_reservation(reservation_id)?;
+        let execution_id = position.execution_id;
+        info!(%reservation_id, %execution_id, planned_size_usd = %reservation.planned_size_usd, %filled_usd, "entry committed");
+        self.record_decision(&reservation.token, reservation.validation.direction, execution_id);
+        self.book_position(position);
+        Ok(execution_id)
+    }
+
+    /// Releases a reservation's capacity without opening anything
+    pub fn cancel_reservation(&mut self, reservation_id: ReservationId) -> Result<(), ReservationError> {
+        self.release_reservation(reservation_id)?;
+        debug!(%reservation_id, "entry reservation cancelled");
+        Ok(())
+    }
+
+    /// Drops reservations past their TTL, releasing their capacity
+    pub fn expire_reservations(&mut self, now: DateTime<Utc>) -> Vec<EntryReservation> {
+        let expired: Vec<ReservationId> = self
+            .reservations
+            .values()
+            .filter(|r| r.expires_at <= now)
+            .map(|r| r.reservation_id)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|id| self.release_reservation(id).ok())
+            .inspect(|r| {
+                warn!(
+                    reservation_id = %r.reservation_id,
+                    token = %r.token.token_symbol,
+                    planned_size_usd = %r.planned_size_usd,
+                    "entry reservation expired uncommitted"
+                );
+            })
+            .collect()
+    }
+
+    pub fn reservation(&self, reservation_id: ReservationId) -> Option<&EntryReservation> {
+        self.reservations.get(&reservation_id)
+    }
+
+    fn release_reservation(&mut self, reservation_id: ReservationId) -> Result<EntryReservation, ReservationError> {
+        let reservation = self
+            .reservations
+            .remove(&reservation_id)
+            .ok_or(ReservationError::UnknownReservation(reservation_id))?;
+        let book = match reservation.validation.direction {
+            Direction::Long => &mut self.portfolio.long_book,
+            Direction::Short => &mut self.portfolio.short_book,
+        };
+        book.release_reservation(reservation.planned_size_usd);
+        Ok(reservation)
+    }
+
+    fn check_slippage(&mut self) {
+        let Some(pause) = self.slippage_pause.check(&self.portfolio, &self.config.risk_controller) else {
+            return;
+        };
+        warn!(
+            slippage_cost_usd = %pause.slippage_cost_usd,
+            gross_pnl_usd = %pause.gross_pnl_usd,
+            slippage_pct = %pause.slippage_pct,
+            "slippage past limit, pausing entries"
+        );
+        if matches!(self.portfolio.state, SystemState::Active | SystemState::PausedLongs | SystemState::PausedShorts) {
+            let _ = self.set_state(SystemState::PausedAll);
+        }
+    }
+
+    /// Compacts both books, returning how many closed positions were removed
+    pub fn compact_books(&mut self, retention: chrono::Duration) -> usize {
+        let removed = self.portfolio.long_book.compact(retention) + self.portfolio.short_book.compact(retention);
+        if removed > 0 {
+            info!(removed, "closed positions compacted");
+        }
+        removed
+    }
+
+    /// Refresh portfolio aggregates and apply the drawdown limits to the state, returning the new
+    /// state if they changed it; with a signing key set, also append a signed snapshot
+    pub fn tick(&mut self) -> Option<SystemState> {
+        let _span = info_span!("cycle").entered();
+        if self.halt_reset && self.portfolio.state == SystemState::EmergencyHalt {
+            self.portfolio.state = SystemState::Recovering;
+            self.halt_reset = false;
+            info!("emergency halt reset, recovering");
+        }
+        self.expire_execution_reports(Utc::now());
+        self.expire_reservations(Utc::now());
+        let open_prices: BTreeMap<String, Decimal> = self
+            .portfolio
+            .long_book
+            .positions
+            .iter()
+            .chain(&self.portfolio.short_book.positions)
+            .filter(|p| p.is_open())
+            .map(|p| (p.token_address.clone(), p.current_price))
+            .collect();
+        let watched = self.price_history.latest_since(Utc::now() - chrono::Duration::minutes(CORRELATION_WATCH_MINUTES));
+        self.correlations.observe(&open_prices, &watched, self.config.correlation.alpha);
+        self.portfolio.long_book.update_unrealized_pnl();
+        self.portfolio.short_book.update_unrealized_pnl();
+        self.portfolio.calculate_exposure();
+        self.portfolio.update_drawdowns();
+        let state_change = self.enforce_drawdown_limits();
+        self.portfolio.last_updated = Utc::now();
+        self.sample(Utc::now());
+        if self
+            .last_compaction
+            .is_none_or(|last| Utc::now() - last >= chrono::Duration::seconds(BOOK_COMPACTION_INTERVAL_SECONDS))
+        {
+            self.compact_books(self.position_retention);
+            self.last_compaction = Some(Utc::now());
+        }
+        debug!(
+            gross_exposure_usd = %self.portfolio.gross_exposure_usd,
+            net_exposure_pct = %self.portfolio.net_exposure_pct,
+            daily_drawdown_pct = %self.portfolio.daily_drawdown_pct,
+            "portfolio aggregated"
+        );
+
+        if let Some(signing_key) = &self.audit_signing_key {
+            let snapshot = self.portfolio.serialize_for_audit(signing_key);
+            if let Some(evicted) = self.audit_snapshots.push(snapshot) {
+                self.spill(AuditRecord::Snapshot(evicted));
+            }
+        }
+        state_change
+    }
+
+    /// Write every retained audit snapshot to `path` as JSON lines, replacing the file;
+    /// older ones went to the audit sink
+    pub fn export_audit_trail(&self, path: &Path) -> std::io::Result<()> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        for snapshot in &self.audit_snapshots {
+            serde_json::to_writer(&mut file, snapshot)?;
+            writeln!(file)?;
+        }
+        file.flush()
+    }
+
+    pub fn record_entry(&mut self, log: EntryLog) {
+        let execution_id = log.execution_id;
+        strike_span(execution_id, &log.token_symbol, log.direction).in_scope(|| {
+            info!(
+                entry_price = %log.entry_price,
+                size_usd = %log.position_size_usd,
+                risk_approval_id = %log.risk_approval_id,
+                stop_basis = ?log.stop_basis,
+                "position opened"
+            );
+        });
+        if let Some(evicted) = self.entry_logs.push(log) {
+            self.spill(AuditRecord::Entry(evicted));
+        }
+        self.portfolio.consecutive_failures = 0;
+        if self.in_recovery_probation() {
+            self.portfolio.state = SystemState::Active;
+            self.recovery = Some(RecoveryState::RecoverySucceeded);
+            info!("recovery entry filled, system active");
+        }
+        self.retry_pending_report(execution_id);
+    }
+
+    pub fn record_exit(&mut self, log: ExitLog) {
+        let exit_execution_id = log.exit_execution_id;
+        self.position_span(log.execution_id).in_scope(|| {
+            info!(
+                exit_type = ?log.exit_type,
+                exit_price = %log.exit_price,
+                exit_size_pct = %log.exit_size_pct,
+                realized_pnl_usd = %log.realized_pnl_usd,
+                "position exit"
+            );
+        });
+        self.attribute_exit(&log);
+        if let Some(evicted) = self.exit_logs.push(log) {
+            self.spill(AuditRecord::Exit(evicted));
+        }
+        if let Some(execution_id) = exit_execution_id {
+            self.retry_pending_report(execution_id);
+        }
+    }
+
+    /// Join a venue execution report onto its entry log (by position execution id) or exit
+    /// log (by exit execution id), filling in latency and direction-aware slippage. An entry
+    /// fill off the booked price or size by more than FILL_ADJUSTMENT_TOLERANCE rebooks the
+    /// position at the achieved values. Reports that beat their log here are queued.
+    pub fn attach_execution_report(&mut self, report: ExecutionReport) -> ReportAttachment {
+        let now = Utc::now();
+        self.expire_execution_reports(now);
+        match self.try_attach(&report) {
+            Some(attachment) => attachment,
+            None => {
+                self.pending_execution_reports.push((report, now));
+                ReportAttachment::Queued
+            }
+        }
+    }
+
+    fn try_attach(&mut self, report: &ExecutionReport) -> Option<ReportAttachment> {
+        let latency_ms = report.latency_ms();
+
+        if let Some(entry) = self.entry_logs.iter_mut().find(|e| e.execution_id == report.execution_id) {
+            entry.latency_ms = latency_ms;
+            entry.slippage_bps = report.slippage_bps(entry.direction == Direction::Long);
+            let position_adjusted = self.adjust_position_to_fill(report);
+            return Some(ReportAttachment::Attached { leg: ExecutionLeg::Entry, position_adjusted });
+        }
+
+        let exit = self.exit_logs.iter().position(|e| e.exit_execution_id == Some(report.execution_id))?;
+        let position_id = self.exit_logs.get(exit)?.execution_id;
+        // Closing a long sells, closing a short buys
+        let direction = self.position_direction(position_id)?;
+        let exit = self.exit_logs.iter_mut().nth(exit)?;
+        exit.latency_ms = latency_ms;
+        let booked_bps = exit.slippage_bps;
+        exit.slippage_bps = report.slippage_bps(direction == Direction::Short);
+        let (exit_price, exit_size_pct, bps_change) = (exit.exit_price, exit.exit_size_pct, exit.slippage_bps - booked_bps);
+        self.charge_exit_slippage(position_id, exit_price, exit_size_pct, bps_change);
+        Some(ReportAttachment::Attached { leg: ExecutionLeg::Exit, position_adjusted: false })
+    }
+
+    /// Adds the cost of `bps_change` more slippage on an exit of `exit_size_pct` at `exit_price`
+    /// to its position's book and the portfolio total, which can trip the slippage pause; exits
+    /// are booked before their fill is reported, so this is where automatic exits learn their slippage
+    fn charge_exit_slippage(&mut self, execution_id: Uuid, exit_price: Decimal, exit_size_pct: Decimal, bps_change: Decimal) {
+        let Some(book) = [&mut self.portfolio.long_book, &mut self.portfolio.short_book]
+            .into_iter()
+            .find(|book| book.positions.iter().any(|p| p.execution_id == execution_id))
+        else {
+            debug!(%execution_id, "exit report for a compacted position, slippage not booked");
+            return;
+        };
+        let Some(position) = book.positions.iter().find(|p| p.execution_id == execution_id) else {
+            return;
+        };
+        let cost = position.position_size_tokens * exit_size_pct * exit_price * bps_change / Decimal::new(10_000, 0);
+        book.costs_usd += cost;
+        self.portfolio.total_slippage_cost_usd += cost;
+        self.check_slippage();
+    }
+
+    /// Strike span for a position by id, from the books or else its entry log
+    fn position_span(&self, execution_id: Uuid) -> tracing::Span {
+        let position = self.portfolio.long_book.positions.iter()
+            .chain(self.portfolio.short_book.positions.iter())
+            .find(|p| p.execution_id == execution_id);
+        if let Some(position) = position {
+            return position.span();
+        }
+        match self.entry_logs.iter().find(|e| e.execution_id == execution_id) {
+            Some(entry) => strike_span(execution_id, &entry.token_symbol, entry.direction),
+            None => info_span!("strike", %execution_id),
+        }
+    }
+
+    fn position_direction(&self, execution_id: Uuid) -> Option<Direction> {
+        self.portfolio.long_book.positions.iter()
+            .chain(self.portfolio.short_book.positions.iter())
+            .find(|p| p.execution_id == execution_id)
+            .map(|p| p.direction)
+            .or_else(|| {
+                self.entry_logs.iter()
+                    .find(|e| e.execution_id == execution_id)
+                    .map(|e| e.direction)
+            })
+    }
+
+    /// Rebooks a position whose fill missed its booked entry price or size by more than the
+    /// tolerance: its stops and unhit targets move with the entry price, keeping their distance
+    /// from it, and its book's allocation follows the new size
+    fn adjust_position_to_fill(&mut self, report: &ExecutionReport) -> bool {
+        if report.filled_size <= Decimal::ZERO || report.achieved_avg_price <= Decimal::ZERO {
+            return false;
+        }
+        let Some(book) = [&mut self.portfolio.long_book, &mut self.portfolio.short_book]
+            .into_iter()
+            .find(|book| book.positions.iter().any(|p| p.execution_id == report.execution_id))
+        else {
+            return false;
+        };
+        let Some(position) = book.positions.iter_mut().find(|p| p.execution_id == report.execution_id) else {
+            return false;
+        };
+
+        let off = |booked: Decimal, achieved: Decimal| {
+            booked > Decimal::ZERO && ((achieved - booked) / booked).abs() > FILL_ADJUSTMENT_TOLERANCE
+        };
+        if !off(position.entry_price, report.achieved_avg_price)
+            && !off(position.position_size_tokens, report.filled_size)
+        {
+            return false;
+        }
+
+        let evicted = self.fill_adjustments.push(FillAdjustment {
+            execution_id: position.execution_id,
+            old_entry_price: position.entry_price,
+            new_entry_price: report.achieved_avg_price,
+            old_size_tokens: position.position_size_tokens,
+            new_size_tokens: report.filled_size,
+            adjusted_at: Utc::now(),
+        });
+        if position.entry_price > Decimal::ZERO {
+            let shift = report.achieved_avg_price / position.entry_price;
+            if !position.trailing_stop_active {
+                position.stop_loss_price *= shift;
+            }
+            for target in position.take_profit_targets.iter_mut().filter(|t| !t.hit) {
+                target.price *= shift;
+            }
+            if let Some(activation) = &mut position.trailing_activation_price {
+                *activation *= shift;
+            }
+        }
+        let new_size_usd = report.filled_size * report.achieved_avg_price;
+        let allocation_delta = (new_size_usd - position.position_size_usd) * position.remaining_size_pct;
+        book.total_allocation_usd = (book.total_allocation_usd + allocation_delta).max(Decimal::ZERO);
+        position.entry_price = report.achieved_avg_price;
+        position.position_size_tokens = report.filled_size;
+        position.position_size_usd = new_size_usd;
+        position.refresh_pnl();
+        self.portfolio.calculate_exposure();
+        if let Some(evicted) = evicted {
+            self.spill(AuditRecord::FillAdjustment(evicted));
+        }
+        true
+    }
+
+    fn retry_pending_report(&mut self, execution_id: Uuid) {
+        let Some(index) = self.pending_execution_reports.iter().position(|(r, _)| r.execution_id == execution_id) else {
+            return;
+        };
+        let (report, queued_at) = self.pending_execution_reports.remove(index);
+        if self.try_attach(&report).is_none() {
+            self.pending_execution_reports.push((report, queued_at));
+        }
+    }
+
+    /// Move reports queued longer than the grace period to the orphaned list
+    fn expire_execution_reports(&mut self, now: DateTime<Utc>) {
+        let grace = self.execution_report_grace;
+        let (expired, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending_execution_reports)
+            .into_iter()
+            .partition(|(_, queued_at)| now - *queued_at > grace);
+        self.pending_execution_reports = pending;
+        for (report, _) in expired {
+            if let Some(evicted) = self.orphaned_execution_reports.push(report) {
+                self.spill(AuditRecord::OrphanedExecutionReport(evicted));
+            }
+        }
+    }
+
+    pub fn pending_execution_report_count(&self) -> usize {
+        self.pending_execution_reports.len()
+    }
+
+    fn record_rejection(&mut self, log: RejectionLog) {
+        if let Some(evicted) = self.rejection_logs.push(log) {
+            self.spill(AuditRecord::Rejection(evicted));
+        }
+    }
+
+    fn spill(&self, record: AuditRecord) {
+        if let Some(sink) = &self.audit_sink {
+            spill_to(sink.as_ref(), &record, &self.audit_spill_failures);
+        }
+    }
+
+    /// Evicted records the audit sink failed to take since the engine started
+    pub fn audit_spill_failures(&self) -> u64 {
+        self.audit_spill_failures.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Records a portfolio history sample if the configured interval has passed since the
+    /// last one; returns whether a sample was taken
+    pub fn sample(&mut self, now: DateTime<Utc>) -> bool {
+        if !self.portfolio_history.is_due(now) {
+            return false;
+        }
+        let sample = history::PortfolioSample::from_portfolio(&self.portfolio, now);
+        if let Some(evicted) = self.portfolio_history.record(sample) {
+            if self.config.portfolio_history.spill_evicted {
+                self.spill(AuditRecord::PortfolioSample(evicted));
+            }
+        }
+        true
+    }
+
+    /// Sampled equity between `from` and `to`, downsampled to one candle per `resolution`
+    pub fn equity_curve(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        resolution: chrono::Duration,
+    ) -> Vec<history::EquityCandle> {
+        self.portfolio_history.equity_curve(from, to, resolution)
+    }
+
+    pub fn deposit_capital(&mut self, amount: Decimal) -> Result<(), CapitalError> {
+        if amount <= Decimal::ZERO {
+            return Err(CapitalError::InvalidAmount(amount));
+        }
+
+        self.portfolio.total_capital_usd += amount;
+        self.apply_capital_change(CapitalEventKind::Deposit, amount);
+        Ok(())
+    }
+
+    /// Returns the available capital remaining after the withdrawal
+    pub fn withdraw_capital(&mut self, amount: Decimal) -> Result<Decimal, CapitalError> {
+        if amount <= Decimal::ZERO {
+            return Err(CapitalError::InvalidAmount(amount));
+        }
+
+        self.portfolio.calculate_exposure();
+        let available = self.portfolio.available_capital_usd;
+        if available < amount {
+            return Err(CapitalError::InsufficientLiquidity {
+                available,
+                requested: amount,
+            });
+        }
+
+        self.portfolio.total_capital_usd -= amount;
+        self.apply_capital_change(CapitalEventKind::Withdrawal, amount);
+        Ok(self.portfolio.available_capital_usd)
+    }
+
+    fn apply_capital_change(&mut self, kind: CapitalEventKind, amount: Decimal) {
+        let total = self.portfolio.total_capital_usd;
+        let sizing = &self.config.position_sizing;
+        self.portfolio.long_book.max_allocation_usd = total * sizing.long_book_max_pct;
+        self.portfolio.short_book.max_allocation_usd = total * sizing.short_book_max_pct;
+
+        // Shift high-water marks so flows don't register as P&L or drawdown
+        let shift = match kind {
+            CapitalEventKind::Deposit => amount,
+            CapitalEventKind::Withdrawal => -amount,
+        };
+        self.portfolio.daily_high_water_mark += shift;
+        self.portfolio.weekly_high_water_mark += shift;
+        self.portfolio.monthly_high_water_mark += shift;
+        self.portfolio.daily_realized_high_water_mark += shift;
+        self.portfolio.drawdown_baseline.marked_equity_usd += shift;
+
+        self.portfolio.calculate_exposure();
+        self.portfolio.update_drawdowns();
+        if self.is_warmed_up() {
+            self.warmup();
+        }
+
+        let evicted = self.capital_events.push(CapitalEvent {
+            event_id: Uuid::new_v4(),
+            kind,
+            amount_usd: amount,
+            total_capital_after: total,
+            available_capital_after: self.portfolio.available_capital_usd,
+            long_book_max_after: self.portfolio.long_book.max_allocation_usd,
+            short_book_max_after: self.portfolio.short_book.max_allocation_usd,
+            timestamp: Utc::now(),
+        });
+        if let Some(evicted) = evicted {
+            self.spill(AuditRecord::CapitalEvent(evicted));
+        }
+    }
+
+    /// Validates and swaps in a new config, returning what changed. Open positions keep their
+    /// stops and targets unless `reprice_open_positions` is set.
+    pub fn apply_config_update(
+        &mut self,
+        new: StrikeBoxConfig,
+        reprice_open_positions: bool,
+    ) -> ConfigUpdateReport {
+        let changes = self.config.diff(&new);
+        let mut report = ConfigUpdateReport {
+            applied: false,
+            errors: Vec::new(),
+            changes,
+            warnings: Vec::new(),
+            repriced_positions: Vec::new(),
+            book_status: Vec::new(),
+            updated_at: Utc::now(),
+        };
+
+        if let Err(errors) = new.validate() {
+            report.errors = errors;
+            self.record_config_report(&report);
+            return report;
+        }
+
+        let open_positions = self.portfolio.long_book.positions.len() + self.portfolio.short_book.positions.len();
+        let baked_changes: Vec<&ConfigChange> = report
+            .changes
+            .iter()
+            .filter(|c| ENTRY_BAKED_SETTINGS.contains(&c.path.as_str()))
+            .collect();
+        if !baked_changes.is_empty() && open_positions > 0 && !reprice_open_positions {
+            for change in baked_changes {
+                report.warnings.push(format!(
+                    "{} changed; {} open positions keep their existing stops and targets",
+                    change.path, open_positions
+                ));
+            }
+        }
+
+        self.config = new;
+        self.session_safety_scoring =
+            self.market_conditions.as_ref().map(|c| self.config.safety_scoring.auto_calibrate(c));
+        self.refresh_config_fingerprint();
+
+        let total = self.portfolio.total_capital_usd;
+        let sizing = &self.config.position_sizing;
+        self.portfolio.long_book.max_allocation_usd = total * sizing.long_book_max_pct;
+        self.portfolio.long_book.max_positions = sizing.long_book_max_positions;
+        self.portfolio.short_book.max_allocation_usd = total * sizing.short_book_max_pct;
+        self.portfolio.short_book.max_positions = sizing.short_book_max_positions;
+
+        let retention = self.config.log_retention.clone();
+        for evicted in self.entry_logs.set_capacity(retention.max_entry_logs) {
+            self.spill(AuditRecord::Entry(evicted));
+        }
+        for evicted in self.exit_logs.set_capacity(retention.max_exit_logs) {
+            self.spill(AuditRecord::Exit(evicted));
+        }
+        for evicted in self.rejection_logs.set_capacity(retention.max_rejection_logs) {
+            self.spill(AuditRecord::Rejection(evicted));
+        }
+        for evicted in self.audit_snapshots.set_capacity(retention.max_audit_snapshots) {
+            self.spill(AuditRecord::Snapshot(evicted));
+        }
+        for evicted in self.validation_audit_log.set_capacity(retention.max_validation_records) {
+            self.spill(AuditRecord::Validation(evicted));
+        }
+        for evicted in self.command_audit_log.set_capacity(retention.max_command_records) {
+            self.spill(AuditRecord::Command(evicted));
+        }
+        for evicted in self.config_audit.set_capacity(retention.max_config_reports) {
+            self.spill(AuditRecord::ConfigUpdate(evicted));
+        }
+        for evicted in self.capital_events.set_capacity(retention.max_capital_events) {
+            self.spill(AuditRecord::CapitalEvent(evicted));
+        }
+        for evicted in self.market_events.set_capacity(retention.max_market_events) {
+            self.spill(AuditRecord::MarketEvent(evicted));
+        }
+        for evicted in self.fill_adjustments.set_capacity(retention.max_fill_adjustments) {
+            self.spill(AuditRecord::FillAdjustment(evicted));
+        }
+        for evicted in self.orphaned_execution_reports.set_capacity(retention.max_orphaned_reports) {
+            self.spill(AuditRecord::OrphanedExecutionReport(evicted));
+        }
+        for evicted in self.portfolio_history.reconfigure(&self.config.portfolio_history) {
+            if self.config.portfolio_history.spill_evicted {
+                self.spill(AuditRecord::PortfolioSample(evicted));
+            }
+        }
+
+        if self.is_warmed_up() {
+            self.warmup();
+        }
+
+        if reprice_open_positions {
+            report.repriced_positions = self.reprice_open_positions();
+        }
+
+        for book in [&self.portfolio.long_book, &self.portfolio.short_book] {
+            let status = BookCapacityStatus::of(book);
+            if status.status == BookStatus::NoNewEntriesUntilUnderLimit {
+                report.warnings.push(format!(
+                    "{:?} book over new limit ({}/{} positions, ${:.2}/${:.2}) - no new entries until under limit",
+                    book.direction, status.positions, status.max_positions, status.allocation_usd, status.max_allocation_usd
+                ));
+            }
+            report.book_status.push(status);
+        }
+
+        report.applied = true;
+        self.record_config_report(&report);
+        report
+    }
+
+    fn record_config_report(&mut self, report: &ConfigUpdateReport) {
+        if let Some(evicted) = self.config_audit.push(report.clone()) {
+            self.spill(AuditRecord::ConfigUpdate(evicted));
+        }
+    }
+
+    fn reprice_open_positions(&mut self) -> Vec<PositionRepricing> {
+        let stop_loss = &self.config.stop_loss;
+        let price_history = &self.price_history;
+        let take_profit = &self.config.take_profit;
+        let mut repriced = Vec::new();
+
+        let positions = self
+            .portfolio
+            .long_book
+            .positions
+            .iter_mut()
+            .chain(self.portfolio.short_book.positions.iter_mut())
+            .filter(|p| p.status == PositionStatus::Open);
+        for position in positions {
+            let new_stop = match position.direction {
+                // An active trailing stop already supersedes the fixed stop
+                Direction::Long if position.trailing_stop_active => position.stop_loss_price,
+                Direction::Long => {
+                    let recent_prices = price_history.prices(&position.token_address);
+                    stop_loss.long_stop(position.entry_price, position.safety_score_at_entry, &recent_prices).0
+                }
+                Direction::Short => stop_loss.short_stop_price(position.entry_price),
+            };
+            let mut new_targets = take_profit.targets(position.direction, position.entry_price);
+            if position.take_profit_targets.iter().any(|t| t.hit) {
+                // Hit levels have already sold their share; only a same-shaped ladder can be
+                // repriced without changing how much of the position is left to exit
+                if new_targets.len() == position.take_profit_targets.len() {
+                    for (new, old) in new_targets.iter_mut().zip(&position.take_profit_targets) {
+                        new.hit = old.hit;
+                        new.exit_pct = old.exit_pct;
+                    }
+                } else {
+                    new_targets = position.take_profit_targets.clone();
+                }
+            }
+            if new_stop == position.stop_loss_price && new_targets == position.take_profit_targets {
+                continue;
+            }
+
+            repriced.push(PositionRepricing {
+                execution_id: position.execution_id,
+                token_symbol: position.token_symbol.clone(),
+                direction: position.direction,
+                old_stop_loss: position.stop_loss_price,
+                new_stop_loss: new_stop,
+                old_take_profits: position.take_profit_prices(),
+                new_take_profits: new_targets.iter().map(|t| t.price).collect(),
+            });
+            position.stop_loss_price = new_stop;
+            position.take_profit_targets = new_targets;
+        }
+        repriced
+    }
+
+    /// Applies a market-wide event. An index drop at or beyond `market_crash_trigger_pct`
+    /// halts the system; smaller drops are recorded only. Returns the resulting state.
+    pub fn ingest_market_event(&mut self, event: MarketEvent) -> SystemState {
+        let state_before = self.portfolio.state;
+        match &event {
+            MarketEvent::IndexDrop { pct, .. } => {
+                if *pct >= self.config.risk_controller.market_crash_trigger_pct {
+                    self.emergency_close_all();
+                }
+            }
+        }
+        let evicted = self.market_events.push(MarketEventRecord {
+            event,
+            state_before,
+            state_after: self.portfolio.state,
+            received_at: Utc::now(),
+        });
+        if let Some(evicted) = evicted {
+            self.spill(AuditRecord::MarketEvent(evicted));
+        }
+        self.portfolio.state
+    }
+
+    /// Moves to `new_state` and returns the previous state. Resume (Active) is only possible
+    /// from a paused or recovering state; nothing leaves EmergencyHalt without a manual reset.
+    /// EmergencyHalt and Recovering are reserved for `emergency_close_all`, `tick` and
+    /// `attempt_recovery`.
+    pub fn set_state(&mut self, new_state: SystemState) -> Result<SystemState, StateTransitionError> {
+        let old_state = self.portfolio.state;
+        match (old_state, new_state) {
+            (_, SystemState::EmergencyHalt | SystemState::Recovering | SystemState::SoftHalt) => {
+                return Err(StateTransitionError::Reserved(new_state));
+            }
+            (SystemState::EmergencyHalt, _) => return Err(StateTransitionError::HaltRequiresReset(new_state)),
+            (SystemState::Active, SystemState::Active) => return Err(StateTransitionError::InvalidResume(old_state)),
+            _ => {}
+        }
+        self.portfolio.state = new_state;
+        info!(from = ?old_state, to = ?new_state, "system state changed");
+        Ok(old_state)
+    }
+
+    /// Moves the state to what `check_drawdown_limits` calls for. A monthly breach halts and
+    /// closes everything; weekly and realized daily breaches pause all entries until an operator
+    /// resumes; an unrealized breach soft-halts an active system and the soft halt clears here
+    /// once marks recover. Operator pauses are left alone. Returns the new state if it changed.
+    pub fn enforce_drawdown_limits(&mut self) -> Option<SystemState> {
+        let limit_state = self.portfolio.check_drawdown_limits(&self.config.risk_controller);
+        let old_state = self.portfolio.state;
+        let new_state = match (limit_state, old_state) {
+            (_, SystemState::EmergencyHalt) => return None,
+            (SystemState::EmergencyHalt, _) => {
+                self.emergency_close_all();
+                return Some(SystemState::EmergencyHalt);
+            }
+            (SystemState::Active, SystemState::SoftHalt) => SystemState::Active,
+            (SystemState::SoftHalt, SystemState::Active) => SystemState::SoftHalt,
+            (SystemState::Active | SystemState::SoftHalt, _) | (_, SystemState::PausedAll) => return None,
+            (state, _) => state,
+        };
+        self.portfolio.state = new_state;
+        info!(
+            from = ?old_state,
+            to = ?new_state,
+            realized_drawdown_pct = %self.portfolio.daily_realized_drawdown_pct,
+            unrealized_drawdown_pct = %self.portfolio.daily_unrealized_drawdown_pct,
+            "system state changed on drawdown"
+        );
+        Some(new_state)
+    }
+
+    /// Halts the system and returns how many open positions need closing
+    pub fn emergency_close_all(&mut self) -> u32 {
+        self.portfolio.state = SystemState::EmergencyHalt;
+        self.halt_reset = false;
+        // Probation from an earlier recovery doesn't carry over into a new halt
+        self.recovery = None;
+        let open = self.portfolio.long_book.position_count() + self.portfolio.short_book.position_count();
+        warn!(open_positions = open, "emergency halt");
+        open
+    }
+
+    /// Counts a failed execution; at `execution_failure_max` in a row the system halts.
+    /// Returns the resulting state.
+    pub fn record_execution_failure(&mut self) -> SystemState {
+        self.portfolio.consecutive_failures += 1;
+        let failures = self.portfolio.consecutive_failures;
+        warn!(consecutive_failures = failures, "execution failed");
+        if failures >= self.config.risk_controller.execution_failure_max
+            && self.portfolio.state != SystemState::EmergencyHalt
+        {
+            let failed_recovery = self.in_recovery_probation();
+            self.emergency_close_all();
+            if failed_recovery {
+                self.recovery = Some(RecoveryState::RecoveryFailed("execution failed during recovery".to_string()));
+            }
+        }
+        self.portfolio.state
+    }
+
+    /// Applies the failure class's response. Transient failures return a retry with doubling
+    /// backoff until their execution's retry budget is spent, then count as an execution failure
+    /// toward `execution_failure_max`. MarketMoved is only counted. VenueError pauses entries on
+    /// the failure's venue at its threshold. The first Fatal failure pauses all entries and ends
+    /// any recovery in progress; an operator Resume lifts it, along with any venue pauses.
+    pub fn record_failure(&mut self, failure: failures::ExecutionFailure) -> failures::FailureResponse {
+        use failures::{FailureClass, FailureResponse};
+        let policy = self.config.risk_controller.failure_policy.clone();
+        let count = self.failure_tracker.record(&failure, &policy);
+        let threshold = policy.for_class(failure.class).threshold;
+        warn!(class = ?failure.class, venue = %failure.venue, detail = %failure.detail, count, "execution failed");
+        match failure.class {
+            FailureClass::Transient if count <= threshold => FailureResponse::Retry {
+                attempt: count,
+                backoff_ms: policy.transient_backoff_ms.saturating_mul(1u64.checked_shl(count - 1).unwrap_or(u64::MAX)),
+            },
+            FailureClass::Transient => {
+                if let Some(execution_id) = failure.execution_id {
+                    self.failure_tracker.finish_execution(execution_id);
+                }
+                self.record_execution_failure();
+                FailureResponse::RetryBudgetExhausted
+            }
+            FailureClass::MarketMoved => {
+                if count == threshold {
+                    info!(count, "repeated market-moved misses; not pausing");
+                }
+                FailureResponse::Recorded
+            }
+            FailureClass::VenueError if count >= threshold => {
+                self.failure_tracker.pause_venue(&failure.venue);
+                warn!(venue = %failure.venue, count, "venue errors past threshold, pausing entries on venue");
+                FailureResponse::VenuePaused { venue: failure.venue.to_lowercase() }
+            }
+            FailureClass::Fatal => {
+                if self.portfolio.state != SystemState::EmergencyHalt {
+                    let old_state = self.portfolio.state;
+                    // A recovery probe can't succeed past a fatal failure; Resume starts afresh
+                    self.recovery = None;
+                    self.portfolio.state = SystemState::PausedAll;
+                    warn!(from = ?old_state, to = ?SystemState::PausedAll, detail = %failure.detail, "fatal execution failure, pausing all entries");
+                }
+                FailureResponse::PausedAll
+            }
+            FailureClass::VenueError => FailureResponse::Recorded,
+        }
+    }
+
+    /// Failures per class within their decay windows, and the venues paused
+    pub fn failure_summary(&self) -> failures::FailureSummary {
+        self.failure_tracker.summary(&self.config.risk_controller.failure_policy, self.evaluation_time())
+    }
+
+    /// Whether venue errors have paused entries on `venue`
+    pub fn is_venue_paused(&self, venue: &str) -> bool {
+        self.failure_tracker.is_venue_paused(venue)
+    }
+
+    /// Tries to leave a halt caused by consecutive execution failures. The entry gates run on
+    /// `probe_token` as if the system were active, and nothing is opened. If they pass, the
+    /// system moves to Recovering and takes entries; the first successful one makes it Active.
+    pub fn attempt_recovery(&mut self, probe_token: &TokenSnapshot) -> RecoveryResult {
+        let _span = info_span!("recovery", probe = %probe_token.token_symbol).entered();
+        let failed = |reason: String| {
+            warn!(%reason, "recovery not attempted");
+            RecoveryResult { state: RecoveryState::RecoveryFailed(reason), gates: Vec::new() }
+        };
+        if self.in_recovery_probation() {
+            return failed("recovery already in progress, waiting for an entry".to_string());
+        }
+        if self.portfolio.state != SystemState::EmergencyHalt {
+            return failed(format!("not halted (state is {:?})", self.portfolio.state));
+        }
+        if self.portfolio.consecutive_failures < self.config.risk_controller.execution_failure_max {
+            return failed("halt was not caused by execution failures and needs an operator reset".to_string());
+        }
+
+        let mut probe = RiskValidation::new(Direction::Long);
+        let ctx = gates::EvaluationContext {
+            engine: self,
+            token: probe_token,
+            direction: Direction::Long,
+            now: self.evaluation_time(),
+        };
+        self.gate_registry.run(&ctx, &gates::PROBE_EXCLUDED_GATES, &mut probe);
+        let state = match probe.first_failure() {
+            None if probe.all_passed => {
+                self.portfolio.state = SystemState::Recovering;
+                self.halt_reset = false;
+                info!("recovery probe passed, recovering");
+                RecoveryState::RecoveryAttempted
+            }
+            failure => {
+                let reason = failure
+                    .map(|gate| format!("probe failed {}: {}", gate.gate_name, gate.reason.as_deref().unwrap_or("")))
+                    .unwrap_or_else(|| "probe did not pass every gate".to_string());
+                warn!(%reason, "recovery probe failed");
+                RecoveryState::RecoveryFailed(reason)
+            }
+        };
+        self.recovery = Some(state.clone());
+        RecoveryResult { state, gates: probe.gates }
+    }
+
+    pub fn recovery_state(&self) -> Option<&RecoveryState> {
+        self.recovery.as_ref()
+    }
+
+    fn in_recovery_probation(&self) -> bool {
+        self.portfolio.state == SystemState::Recovering && self.recovery == Some(RecoveryState::RecoveryAttempted)
+    }
+
+    /// Operator reset of an emergency halt. The system stays halted until the next tick moves
+    /// it to Recovering, from which it can be resumed.
+    pub fn reset_emergency_halt(&mut self) -> Result<(), StateTransitionError> {
+        if self.portfolio.state != SystemState::EmergencyHalt {
+            return Err(StateTransitionError::NotHalted(self.portfolio.state));
+        }
+        self.halt_reset = true;
+        Ok(())
+    }
+
+    /// Time entry gates and sizing evaluate at: the time set by `set_evaluation_time`, or now
+    pub fn evaluation_time(&self) -> DateTime<Utc> {
+        self.evaluation_time.unwrap_or_else(Utc::now)
+    }
+
+    /// Pins the clock entry gates and sizing read, e.g. to each bar's time in a backtest;
+    /// None goes back to the wall clock
+    pub fn set_evaluation_time(&mut self, at: Option<DateTime<Utc>>) {
+        self.evaluation_time = at;
+    }
+
+    pub fn validate_entry(&self, token: &TokenSnapshot, direction: Direction) -> RiskValidation {
+        self.validate_entry_cached(token, direction).0
+    }
+
+    /// validate_entry, also saying whether the validation came from the cache. A pinned
+    /// evaluation time always runs the gates: replayed bars must not reuse each other's results.
+    pub(crate) fn validate_entry_cached(&self, token: &TokenSnapshot, direction: Direction) -> (RiskValidation, bool) {
+        if !self.is_warmed_up() && !self.cold_validation_warned.swap(true, std::sync::atomic::Ordering::Relaxed) {
+            warn!(token = %token.token_symbol, "validate_entry called before warmup");
+        }
+        let cache = match &self.validation_cache {
+            Some(cache) if self.evaluation_time.is_none() => cache,
+            _ => return (self.evaluate_entry(token, direction), false),
+        };
+        let now = self.evaluation_time();
+        let key = self.validation_cache_key(token, direction, now);
+        if let Some(cached) = cache.lock().ok().and_then(|mut cache| cache.get(&key, now)) {
+            debug!(token = %token.token_symbol, direction = ?direction, "validation cache hit");
+            return (cached, true);
+        }
+        let validation = self.evaluate_entry(token, direction);
+        if let Ok(mut cache) = cache.lock() {
+            cache.insert(key, validation.clone(), now);
+        }
+        (validation, false)
+    }
+
+    /// Snapshot content (its timestamp only as fresh or stale), direction, the config fingerprint
+    /// and a coarse portfolio fingerprint: state, capital, each book's slots, allocation and open
+    /// tokens, and net exposure in 1% buckets; plus the gate registry's order and disabled set.
+    /// Opening or closing a position, a state change, a config update or a gate registry change
+    /// each change the key, so earlier entries miss.
+    fn validation_cache_key(&self, token: &TokenSnapshot, direction: Direction, now: DateTime<Utc>) -> ValidationCacheKey {
+        use std::hash::Hash;
+        let mut context = KeyBytes(self.config_fingerprint.clone());
+        self.gate_registry.fingerprint(&mut context);
+
+        let mut hasher = KeyBytes::default();
+        token.token_address.hash(&mut hasher);
+        token.token_symbol.hash(&mut hasher);
+        token.liquidity_usd.hash(&mut hasher);
+        token.bid_depth_usd.hash(&mut hasher);
+        token.ask_depth_usd.hash(&mut hasher);
+        token.holder_count.hash(&mut hasher);
+        token.top_10_concentration_pct.hash(&mut hasher);
+        token.largest_wallet_pct.hash(&mut hasher);
+        token.token_age_hours.hash(&mut hasher);
+        token.contract_verified.hash(&mut hasher);
+        token.is_proxy_contract.hash(&mut hasher);
+        token.deployment_timestamp.hash(&mut hasher);
+        match &token.source {
+            SnapshotSource::Dex => 0u8.hash(&mut hasher),
+            SnapshotSource::Cex { venue, spread_bps } => {
+                1u8.hash(&mut hasher);
+                venue.hash(&mut hasher);
+                spread_bps.hash(&mut hasher);
+            }
+        }
+        let max_age = chrono::Duration::seconds(self.config.risk_controller.data_feed_stale_seconds as i64);
+        (now - token.snapshot_timestamp > max_age).hash(&mut hasher);
+        direction.hash(&mut hasher);
+        self.config.activity_schedule.level_at(direction, now).hash(&mut hasher);
+        self.failure_tracker.is_venue_paused(token.venue()).hash(&mut hasher);
+        if direction == Direction::Short && self.has_borrow_provider() {
+            let quote = self.fresh_borrow_quote(&token.token_address, now);
+            quote.map(|q| (q.info.available_usd, q.info.max_duration_hours, q.quoted_at)).hash(&mut hasher);
+        }
+
+        let portfolio = &self.portfolio;
+        portfolio.state.hash(&mut hasher);
+        portfolio.total_capital_usd.hash(&mut hasher);
+        for book in [&portfolio.long_book, &portfolio.short_book] {
+            book.slots_in_use().hash(&mut hasher);
+            book.max_positions.hash(&mut hasher);
+            book.is_over_capacity().hash(&mut hasher);
+            for position in book.positions.iter().filter(|p| p.is_open()) {
+                position.token_address.hash(&mut hasher);
+            }
+        }
+        (portfolio.net_exposure_pct * Decimal::ONE_HUNDRED).floor().hash(&mut hasher);
+        ValidationCacheKey { context: context.0, entry: hasher.0 }
+    }
+
+    /// Every entry gate, run afresh
+    fn evaluate_entry(&self, token: &TokenSnapshot, direction: Direction) -> RiskValidation {
+        let _span = info_span!("validation", token = %token.token_symbol, direction = ?direction).entered();
+        let validation = self.run_entry_gates(token, direction);
+        info!(
+            validation_id = %validation.validation_id,
+            all_passed = validation.all_passed,
+            gates = validation.gates.len(),
+            "entry validated"
+        );
+        validation
+    }
+
+    fn run_entry_gates(&self, token: &TokenSnapshot, direction: Direction) -> RiskValidation {
+        let mut validation = RiskValidation::new(direction);
+        let ctx = gates::EvaluationContext { engine: self, token, direction, now: self.evaluation_time() };
+        self.gate_registry.run(&ctx, &[], &mut validation);
+        validation
+    }
+
+    /// Positions in both books matching `filter`, sorted and paginated. Views borrow from
+    /// the books, so nothing is cloned per position.
+    pub fn query_positions(&self, filter: PositionFilter) -> PositionPage<'_> {
+        let now = Utc::now();
+        let mut items: Vec<PositionView<'_>> = self
+            .portfolio
+            .long_book
+            .positions
+            .iter()
+            .chain(&self.portfolio.short_book.positions)
+            .filter_map(|p| {
+                let pending_exit = p.stop_triggered()
+                    || p.trailing_stop_triggered(&self.config.stop_loss)
+                    || p.check_take_profits().is_some()
+                    || p.time_stop_at.is_some_and(|deadline| now >= deadline);
+                filter.matches(p, pending_exit).then(|| PositionView {
+                    execution_id: p.execution_id,
+                    token_address: &p.token_address,
+                    token_symbol: &p.token_symbol,
+                    direction: p.direction,
+                    status: p.status,
+                    entry_price: p.entry_price,
+                    current_price: p.current_price,
+                    remaining_size_usd: p.position_size_usd * p.remaining_size_pct,
+                    unrealized_pnl_usd: p.unrealized_pnl_usd,
+                    unrealized_pnl_pct: p.unrealized_pnl_pct,
+                    opened_at: p.opened_at,
+                    age_seconds: (now - p.opened_at).num_seconds(),
+                    distance_to_stop_pct: if p.current_price > Decimal::ZERO {
+                        match p.direction {
+                            Direction::Long => (p.current_price - p.stop_loss_price) / p.current_price,
+                            Direction::Short => (p.stop_loss_price - p.current_price) / p.current_price,
+                        }
+                    } else {
+                        Decimal::ZERO
+                    },
+                    next_take_profit: p.take_profit_targets.iter().find(|t| !t.hit).copied(),
+                    trailing_stop_active: p.trailing_stop_active,
+                    pending_exit,
+                })
+            })
+            .collect();
+
+        // Stable sort; flipping the comparison rather than the result keeps ties in book order
+        items.sort_by(|a, b| {
+            let ordering = match filter.sort_by {
+                PositionSortKey::Age => b.opened_at.cmp(&a.opened_at),
+                PositionSortKey::Pnl => a.unrealized_pnl_pct.cmp(&b.unrealized_pnl_pct),
+                PositionSortKey::Size => a.remaining_size_usd.cmp(&b.remaining_size_usd),
+            };
+            if filter.descending { ordering.reverse() } else { ordering }
+        });
+
+        let total = items.len();
+        let items = items
+            .into_iter()
+            .skip(filter.offset)
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect();
+        PositionPage { items, total, offset: filter.offset }
+    }
+
+    /// Highest known correlation between `token_address` and an open long, with that long's token
+    pub fn max_long_correlation(&self, token_address: &str) -> Option<(String, Decimal)> {
+        self.portfolio
+            .long_book
+            .positions
+            .iter()
+            .filter(|p| p.is_open() && p.token_address != token_address)
+            .filter_map(|p| {
+                self.correlations
+                    .correlation(token_address, &p.token_address)
+                    .map(|correlation| (p.token_address.clone(), correlation))
+            })
+            .max_by_key(|(_, correlation)| *correlation)
+    }
+
+    /// validate_entry, recording the gate sequence in the validation audit log and any
+    /// failure in the rejection log
+    pub fn validate_and_record_entry(&mut self, token: &TokenSnapshot, direction: Direction) -> RiskValidation {
+        let validation = self.validate_entry(token, direction);
+        if let Some(evicted) = self.validation_audit_log.push(validation.to_audit_record()) {
+            self.spill(AuditRecord::Validation(evicted));
+        }
+        if !validation.all_passed {
+            self.log_rejection(token, direction, &validation);
+        }
+        validation
+    }
+
+    /// Fetches a fresh borrow quote for `token_address` from the borrow provider. A failed
+    /// fetch drops any earlier quote, so the "borrow_availability" gate fails until one succeeds.
+    pub async fn refresh_borrow_quote(&mut self, token_address: &str) -> Result<BorrowInfo, String> {
+        let provider = self.borrow_provider.clone().ok_or_else(|| "No borrow provider configured".to_string())?;
+        match provider.borrow_info(token_address).await {
+            Ok(info) => {
+                let quote = BorrowQuote { info: info.clone(), quoted_at: self.evaluation_time() };
+                self.borrow_quotes.insert(token_address.to_string(), quote);
+                self.prune_borrow_quotes();
+                Ok(info)
+            }
+            Err(e) => {
+                warn!(token = %token_address, error = %e, "borrow info unavailable");
+                self.borrow_quotes.remove(token_address);
+                Err(e)
+            }
+        }
+    }
+
+    /// Drops stale quotes that no open short depends on, then the oldest of those left over
+    /// MAX_BORROW_QUOTES
+    fn prune_borrow_quotes(&mut self) {
+        let cutoff = self.evaluation_time() - chrono::Duration::seconds(BORROW_QUOTE_TTL_SECONDS);
+        let short_book = &self.portfolio.short_book;
+        let held = |token: &str| short_book.positions.iter().any(|p| p.is_open() && p.token_address == token);
+        self.borrow_quotes.retain(|token, quote| quote.quoted_at >= cutoff || held(token));
+        let mut evictable: Vec<(DateTime<Utc>, String)> = self
+            .borrow_quotes
+            .iter()
+            .filter(|(token, _)| !held(token))
+            .map(|(token, quote)| (quote.quoted_at, token.clone()))
+            .collect();
+        let excess = self.borrow_quotes.len().saturating_sub(MAX_BORROW_QUOTES).min(evictable.len());
+        evictable.sort();
+        for (_, token) in evictable.into_iter().take(excess) {
+            self.borrow_quotes.remove(&token);
+        }
+    }
+
+    /// The quote the "borrow_availability" gate reads: the last one for `token_address`, if
+    /// no older than BORROW_QUOTE_TTL_SECONDS at `now`
+    pub(crate) fn fresh_borrow_quote(&self, token_address: &str, now: DateTime<Utc>) -> Option<&BorrowQuote> {
+        self.borrow_quotes
+            .get(token_address)
+            .filter(|quote| now - quote.quoted_at <= chrono::Duration::seconds(BORROW_QUOTE_TTL_SECONDS))
+    }
+
+    pub(crate) fn has_borrow_provider(&self) -> bool {
+        self.borrow_provider.is_some()
+    }
+
+    /// Refreshes the borrow quote for a short, then runs `validate_entry`, whose
+    /// "borrow_availability" gate reads that quote
+    pub async fn validate_entry_with_borrow(&mut self, token: &TokenSnapshot, direction: Direction) -> RiskValidation {
+        if direction == Direction::Short && self.has_borrow_provider() {
+            // A failure leaves no quote, which the gate reports
+            let _ = self.refresh_borrow_quote(&token.token_address).await;
+        }
+        self.validate_entry(token, direction)
+    }
+
+    /// Hourly borrow cost last quoted for `token_address`, which takes precedence over any
+    /// default rate when accruing short carry
+    pub fn borrow_rate(&self, token_address: &str) -> Option<Decimal> {
+        self.borrow_quotes.get(token_address).map(|quote| quote.info.hourly_rate_pct)
+    }
+
+    /// Runs the registered entry gates and reports their inputs, in run order, without logging a
+    /// rejection or touching state
+    pub fn dry_run_validate_entry(&self, token: &TokenSnapshot, direction: Direction) -> DryRunResult {
+        let validation = self.validate_entry(token, direction);
+        let tv = &self.config.token_validation;
+        let safety = SafetyScore::calculate(token, self.safety_scoring(), tv);
+        let book = match direction {
+            Direction::Long => &self.portfolio.long_book,
+            Direction::Short => &self.portfolio.short_book,
+        };
+
+        let band = tv.liquidity_max_usd - tv.liquidity_min_usd;
+        let liquidity_percentile = if band > Decimal::ZERO {
+            ((token.liquidity_usd - tv.liquidity_min_usd) / band).clamp(Decimal::ZERO, Decimal::ONE)
+        } else {
+            Decimal::ZERO
+        };
+        let score_threshold = match direction {
+            Direction::Long => self.config.safety_scoring.long_entry_min,
+            Direction::Short => self.config.safety_scoring.short_entry_min,
+        };
+        let risk = &self.config.risk_controller;
+
+        let mut inputs = vec![
+            ("system_state", format!("{:?}", self.portfolio.state), format!("allows {:?} entries", direction)),
+        ];
+        match &token.source {
+            SnapshotSource::Dex => inputs.extend([
+                (
+                    "liquidity_range",
+                    format!("${}", token.liquidity_usd),
+                    format!("${}-${}", tv.liquidity_min_usd, tv.liquidity_max_usd),
+                ),
+                ("safety_score", format!("{:.4}", safety.total_score), format!(">= {}", score_threshold)),
+                ("token_age", format!("{}h", token.token_age_hours), format!(">= {}h", tv.token_age_min_hours)),
+                (
+                    "contract_verification",
+                    format!("verified={}, proxy={}", token.contract_verified, token.is_proxy_contract),
+                    format!(
+                        "verified required={}, proxies rejected={}",
+                        tv.require_verified_contract,
+                        !tv.proxy_allowed(&token.token_address)
+                    ),
+                ),
+                (
+                    "holder_distribution",
+                    format!("{} holders, top 10 {}", token.holder_count, token.top_10_concentration_pct),
+                    format!(">= {} holders, top 10 <= {}", tv.holder_count_min, tv.top_10_concentration_max_pct),
+                ),
+            ]),
+            SnapshotSource::Cex { venue, spread_bps } => {
+                let cex = &self.config.cex_validation;
+                let listed_at = cex.listings.listed_at(venue, &token.token_symbol);
+                inputs.extend([
+                    ("cex_spread", format!("{:.1}bps", spread_bps), format!("<= {}bps", cex.max_spread_bps)),
+                    (
+                        "cex_depth",
+                        format!("${} bid, ${} ask", token.bid_depth_usd, token.ask_depth_usd),
+                        format!(">= ${} per side", cex.min_side_depth_usd),
+                    ),
+                    (
+                        "cex_listing_age",
+                        match listed_at {
+                            Some(at) => format!("{}h", (token.snapshot_timestamp - at).num_hours()),
+                            None if cex.listings.is_empty() => "listing registry not configured".to_string(),
+                            None => "not listed".to_string(),
+                        },
+                        format!(">= {}h", cex.listing_age_min_hours),
+                    ),
+                ]);
+            }
+        }
+        inputs.extend([
+            (
+                "book_capacity",
+                format!(
+                    "{}/{} positions, ${}/${}",
+                    book.position_count(),
+                    book.max_positions,
+                    book.total_allocation_usd,
+                    book.max_allocation_usd
+                ),
+                "below position and allocation limits".to_string(),
+            ),
+            (
+                "no_stacking",
+                format!("open position={}", book.has_position(&token.token_address)),
+                "no open position in token".to_string(),
+            ),
+        ]);
+        if direction == Direction::Long {
+            inputs.push((
+                "cross_correlation",
+                self.max_long_correlation(&token.token_address)
+                    .map_or("no correlated open long".to_string(), |(held, correlation)| {
+                        format!("{:.4} with {}", correlation, held)
+                    }),
+                format!("<= {}", self.config.correlation.max_long_correlation),
+            ));
+        }
+        if direction == Direction::Short && token.has_onchain_data() {
+            inputs.push((
+                "squeeze_risk",
+                format!("largest wallet {}", token.largest_wallet_pct),
+                format!("<= {}", tv.single_wallet_max_pct),
+            ));
+        }
+        inputs.push((
+            "net_exposure",
+            format!("{}", self.portfolio.net_exposure_pct),
+            format!("{} to {}", risk.net_exposure_min_pct, risk.net_exposure_max_pct),
+        ));
+
+        // Every registered gate in run order: built-ins with the inputs above whether or not the
+        // run reached them, anything else (custom gates, inputs not listed above) as it recorded
+        let diagnostics = self
+            .gate_registry
+            .gate_order()
+            .into_iter()
+            .filter_map(|gate_name| {
+                let check = validation.gates.iter().find(|g| g.gate_name == gate_name);
+                let (observed, required) = match inputs.iter().position(|(name, _, _)| *name == gate_name) {
+                    Some(at) => {
+                        let (_, observed, required) = inputs.swap_remove(at);
+                        (observed, required)
+                    }
+                    None => (check?.reason.clone().unwrap_or_default(), String::new()),
+                };
+                Some(GateDiagnostic {
+                    gate_name: gate_name.to_string(),
+                    result: check.map(|g| g.result),
+                    observed,
+                    required,
+                })
+            })
+            .collect();
+
+        DryRunResult {
+            validation,
+            safety_score: safety,
+            liquidity_percentile,
+            position_size_usd: self.calculate_position_size(token, direction),
+            diagnostics,
+        }
+    }
+
+    /// The position an entry at `entry_price` would open: size, safety-scored stop, take-profit
+    /// ladder and time stop, plus the validation it would face. Books and logs nothing.
+    pub fn simulate_entry(&self, token: &TokenSnapshot, direction: Direction, entry_price: Decimal) -> SimulatedPosition {
+        let validation = self.evaluate_entry(token, direction);
+        let safety_score = SafetyScore::calculate(token, self.safety_scoring(), &self.config.token_validation);
+        let position_size_usd = self.calculate_position_size(token, direction);
+        let position_size_tokens = if entry_price > Decimal::ZERO {
+            position_size_usd / entry_price
+        } else {
+            Decimal::ZERO
+        };
+
+        let (stop_loss_price, time_stop_at) = match direction {
+            Direction::Long => (self.long_stop(&token.token_address, entry_price, safety_score.total_score).0, None),
+            Direction::Short => (
+                self.config.stop_loss.short_stop_price(entry_price),
+                Some(self.config.time_control.short_time_stop(Utc::now())),
+            ),
+        };
+        let take_profit_targets = self.config.take_profit.targets(direction, entry_price);
+
+        let mut exited = Decimal::ZERO;
+        let first_partial_exit_level = take_profit_targets.iter().position(|target| {
+            exited += target.exit_pct;
+            exited >= FIRST_PARTIAL_EXIT_PCT
+        });
+
+        SimulatedPosition {
+            token_address: token.token_address.clone(),
+            direction,
+            entry_price,
+            position_size_usd,
+            position_size_tokens,
+            safety_score,
+            stop_loss_price,
+            take_profit_targets,
+            first_partial_exit_level,
+            time_stop_at,
+            validation,
+        }
+    }
+
+    /// Portfolio impact of an entry sized by `calculate_position_size`, or at `size_override`.
+    /// Runs the same gates as `validate_entry` on the engine as it stands, so a backtest replaying
+    /// state into an engine gets the same answer; never served from the validation cache.
+    /// Books and logs nothing.
+    pub fn simulate_entry_impact(
+        &self,
+        token: &TokenSnapshot,
+        direction: Direction,
+        size_override: Option<Decimal>,
+    ) -> EntryImpact {
+        let validation = self.evaluate_entry(token, direction);
+        let (position_size_usd, sizing_cap) = match size_override {
+            Some(size) => (size, SizingCap::Override),
+            None => self.position_size_with_cap(token, direction),
+        };
+        let portfolio = &self.portfolio;
+        let capital = portfolio.total_capital_usd;
+        let ratio = |usd: Decimal| if capital > Decimal::ZERO { usd / capital } else { Decimal::ZERO };
+
+        let signed_size = match direction {
+            Direction::Long => position_size_usd,
+            Direction::Short => -position_size_usd,
+        };
+        let post_gross_usd = portfolio.long_book.total_allocation_usd
+            + portfolio.short_book.total_allocation_usd
+            + position_size_usd;
+        let post_net_usd = portfolio.long_book.total_allocation_usd - portfolio.short_book.total_allocation_usd + signed_size;
+        let book = match direction {
+            Direction::Long => &portfolio.long_book,
+            Direction::Short => &portfolio.short_book,
+        };
+
+        // Stops are a fixed share of entry, so any reference price gives the same percentage
+        let reference_price = self.price_history.prices(&token.token_address).last().copied().unwrap_or(Decimal::ONE);
+        let stop_loss_pct = match direction {
+            Direction::Long => {
+                let safety_score = SafetyScore::calculate(token, self.safety_scoring(), &self.config.token_validation);
+                let (stop_price, _) = self.long_stop(&token.token_address, reference_price, safety_score.total_score);
+                (reference_price - stop_price) / reference_price
+            }
+            Direction::Short => {
+                (self.config.stop_loss.short_stop_price(reference_price) - reference_price) / reference_price
+            }
+        };
+        let stop_loss_usd = position_size_usd * stop_loss_pct;
+        let round_trip_fees_usd = self.config.fees.round_trip_fee_usd(token.venue(), position_size_usd);
+        let fee_drag_pct = if position_size_usd > Decimal::ZERO {
+            round_trip_fees_usd / position_size_usd
+        } else {
+            Decimal::ZERO
+        };
+        let current_value = capital + portfolio.long_book.unrealized_pnl_usd + portfolio.short_book.unrealized_pnl_usd;
+        let high_water_mark = portfolio.daily_high_water_mark.max(current_value);
+        let worst_case_daily_drawdown_pct = if high_water_mark > Decimal::ZERO {
+            (high_water_mark - (current_value - stop_loss_usd - round_trip_fees_usd)) / high_water_mark
+        } else {
+            Decimal::ZERO
+        };
+
+        let max_long_correlation = match direction {
+            Direction::Long => self.max_long_correlation(&token.token_address),
+            Direction::Short => None,
+        };
+        let correlation_limit = self.config.correlation.max_long_correlation;
+        let correlation_utilization = max_long_correlation
+            .as_ref()
+            .filter(|_| correlation_limit > Decimal::ZERO)
+            .map(|(_, correlation)| *correlation / correlation_limit);
+
+        EntryImpact {
+            token_address: token.token_address.clone(),
+            direction,
+            validation,
+            position_size_usd,
+            sizing_cap,
+            pre_gross_exposure_pct: portfolio.gross_exposure_pct,
+            post_gross_exposure_pct: ratio(post_gross_usd),
+            pre_net_exposure_pct: portfolio.net_exposure_pct,
+            post_net_exposure_pct: ratio(post_net_usd),
+            post_available_capital_usd: capital - post_gross_usd,
+            book_slots_remaining: book.max_positions.saturating_sub(book.position_count() + 1),
+            stop_loss_pct,
+            stop_loss_usd,
+            round_trip_fees_usd,
+            fee_drag_pct,
+            worst_case_daily_drawdown_pct,
+            crosses_daily_halt: worst_case_daily_drawdown_pct >= self.config.risk_controller.daily_realized_halt_pct,
+            max_long_correlation,
+            correlation_utilization,
+        }
+    }
+
+    /// What `close_position` at `exit_price` would realize and leave behind, without closing.
+    /// None when no open position has `execution_id`.
+    pub fn simulate_exit(&self, execution_id: Uuid, exit_price: Decimal) -> Option<ExitImpact> {
+        let mut position = self
+            .portfolio
+            .long_book
+            .positions
+            .iter()
+            .chain(&self.portfolio.short_book.positions)
+            .find(|p| p.execution_id == execution_id && p.is_open())?
+            .clone();
+        let mut exit = position.close_at(exit_price, position.liquidity_at_entry)?;
+        position.charge_fees(&mut exit, &self.config.fees);
+        let released = position.position_size_usd * exit.exit_size_pct;
+
+        let mut portfolio = self.portfolio.clone();
+        let book = match position.direction {
+            Direction::Long => &mut portfolio.long_book,
+            Direction::Short => &mut portfolio.short_book,
+        };
+        book.total_allocation_usd = (book.total_allocation_usd - released).max(Decimal::ZERO);
+        portfolio.calculate_exposure();
+
+        Some(ExitImpact {
+            execution_id,
+            exit_price,
+            exit_size_pct: exit.exit_size_pct,
+            realized_pnl_usd: exit.realized_pnl_usd,
+            fees_usd: exit.fees_usd,
+            post_gross_exposure_pct: portfolio.gross_exposure_pct,
+            post_net_exposure_pct: portfolio.net_exposure_pct,
+            post_available_capital_usd: portfolio.available_capital_usd,
+        })
+    }
+
+    /// Recomputes the safety score of every open position from `snapshots`, keyed by token
+    /// address, and flags a hold review where it has deteriorated. Advisory only: nothing is
+    /// exited. Positions without a snapshot come back stale. The results are kept for the
+    /// TimeStops report.
+    pub fn rescore_positions(&mut self, snapshots: &HashMap<String, TokenSnapshot>) -> Vec<rescore::RescoreResult> {
+        let weights = self.safety_scoring().clone();
+        let now = self.evaluation_time();
+        let results: Vec<rescore::RescoreResult> = self
+            .portfolio
+            .long_book
+            .positions
+            .iter_mut()
+            .chain(self.portfolio.short_book.positions.iter_mut())
+            .filter(|p| p.is_open())
+            .map(|p| {
+                let snapshot = snapshots.get(&p.token_address);
+                rescore::rescore_position(
+                    p,
+                    snapshot,
+                    &weights,
+                    &self.config.token_validation,
+                    &self.config.time_control,
+                    now,
+                )
+            })
+            .collect();
+        info!(
+            rescored = results.iter().filter(|r| !r.is_stale()).count(),
+            flagged = results.iter().filter(|r| r.needs_review()).count(),
+            stale = results.iter().filter(|r| r.is_stale()).count(),
+            "open positions rescored"
+        );
+        self.last_rescore = results.clone();
+        results
+    }
+
+    /// Results of the latest `rescore_positions`
+    pub fn last_rescore(&self) -> &[rescore::RescoreResult] {
+        &self.last_rescore
+    }
+
+    /// Reviews every open long past `time_control.long_review_days`, reading current holder
+    /// counts from `snapshots`, keyed by token address; CEX snapshots carry no holder data and
+    /// count as unavailable. A long whose holders grew less than
+    /// `long_review_min_holder_growth_pct` fails review with a recommended time-stop exit; it
+    /// is not exited here. The results are kept for the TimeStops report.
+    pub fn review_long_holds(&mut self, snapshots: &HashMap<String, TokenSnapshot>) -> Vec<LongReview> {
+        let now = self.evaluation_time();
+        let time_control = &self.config.time_control;
+        let reviews: Vec<LongReview> = self
+            .portfolio
+            .long_book
+            .positions
+            .iter()
+            .filter(|p| p.is_open() && time_control.long_review_due(p.opened_at, now))
+            .map(|p| {
+                let holder_count = snapshots
+                    .get(&p.token_address)
+                    .filter(|s| s.has_onchain_data())
+                    .map(|s| s.holder_count);
+                time_control.review_long(p, holder_count, now)
+            })
+            .collect();
+        info!(
+            reviewed = reviews.len(),
+            failed = reviews.iter().filter(|r| r.failed()).count(),
+            "long holds reviewed"
+        );
+        self.last_long_reviews = reviews.clone();
+        reviews
+    }
+
+    pub fn calculate_position_size(&self, token: &TokenSnapshot, direction: Direction) -> Decimal {
+        self.position_size_with_cap(token, direction).0
+    }
+
+    /// Entry size and the limit that set it
+    pub fn position_size_with_cap(&self, token: &TokenSnapshot, direction: Direction) -> (Decimal, SizingCap) {
+        let decision = self.size_decision(token, direction);
+        (decision.size_usd, decision.cap)
+    }
+
+    /// Entry size with each limit that went into it, at the evaluation time's activity slot
+    pub fn size_decision(&self, token: &TokenSnapshot, direction: Direction) -> SizeDecision {
+        let tier_max_usd = self.tier_max_usd(token.liquidity_usd);
+        let pool_limit_usd = LiquidityScaler::max_order_vs_pool(token.liquidity_usd, direction);
+        let (capped_usd, cap) = if pool_limit_usd < tier_max_usd {
+            (pool_limit_usd, SizingCap::PoolDepth)
+        } else {
+            (tier_max_usd, SizingCap::LiquidityTier)
+        };
+        let activity = self.config.activity_schedule.level_at(direction, self.evaluation_time());
+        SizeDecision {
+            tier_max_usd,
+            pool_limit_usd,
+            cap,
+            activity,
+            size_usd: capped_usd * activity.size_factor(),
+        }
+    }
+
+    /// Dollar cap of the liquidity tier `liquidity_usd` falls in, from the warmed tiers when present
+    fn tier_max_usd(&self, liquidity_usd: Decimal) -> Decimal {
+        let cached = self.computed_cache.as_ref().and_then(|cache| cache.liquidity_tiers.as_ref());
+        match cached.and_then(|tiers| tiers.iter().find(|tier| liquidity_usd >= tier.min_liquidity_usd)) {
+            Some(tier) => tier.max_position_usd,
+            None => self.portfolio.total_capital_usd * LiquidityScaler::max_position_pct(liquidity_usd),
+        }
+    }
+
+    /// Whether net exposure is inside its limits, in dollars against the warmed bounds when present
+    pub fn net_exposure_valid(&self) -> bool {
+        match self.computed_cache.as_ref().and_then(|cache| cache.exposure_bounds.as_ref()) {
+            Some(bounds) => {
+                let net = self.portfolio.net_exposure_usd;
+                net >= bounds.net_min_usd && net <= bounds.net_max_usd
+            }
+            None => self.portfolio.net_exposure_valid(&self.config.risk_controller),
+        }
+    }
+
+    /// Closes, and a resume out of an emergency halt, run only once confirmed
+    pub fn requires_confirmation(&self, command: &OperationalCommand) -> bool {
+        match command {
+            OperationalCommand::CloseAll | OperationalCommand::CloseLongs | OperationalCommand::CloseShorts => true,
+            OperationalCommand::Resume => self.portfolio.state == SystemState::EmergencyHalt,
+            _ => false,
+        }
+    }
+
+    /// Entry point for operator channels: deduplicates retries by request id, holds destructive
+    /// commands until they come back with a confirmation token, and audits what runs
+    pub fn handle_command(&mut self, request: CommandRequest) -> CommandResponse {
+        self.handle_command_at(request, Utc::now())
+    }
+
+    pub fn handle_command_at(&mut self, request: CommandRequest, now: DateTime<Utc>) -> CommandResponse {
+        let needs_confirmation = self.requires_confirmation(&request.command);
+        let response = match self.command_bus.admit(&request, needs_confirmation, now) {
+            Admission::Duplicate(response) => return response,
+            Admission::Respond(response) => response,
+            Admission::Run { confirmation_requested_by } => self.run_audited(&request, confirmation_requested_by, now),
+        };
+        self.command_bus.record(request.id, response.clone());
+        response
+    }
+
+    fn run_audited(&mut self, request: &CommandRequest, confirmation_requested_by: Option<String>, now: DateTime<Utc>) -> CommandResponse {
+        let response = self.execute_command(request.command.clone());
+        if let Some(evicted) = self.command_audit_log.push(CommandAuditRecord::new(request, confirmation_requested_by, &response, now)) {
+            self.spill(AuditRecord::Command(evicted));
+        }
+        response
+    }
+
+    /// Runs `command` immediately, with no confirmation, deduplication or command audit;
+    /// operator channels should go through `handle_command`
+    pub fn execute_command(&mut self, command: OperationalCommand) -> CommandResponse {
+        let mut data = None;
+        let (success, message) = match &command {
+            OperationalCommand::PauseLongs => match self.set_state(SystemState::PausedLongs) {
+                Ok(_) => (true, "Long entries paused".to_string()),
+                Err(e) => (false, e.to_string()),
+            },
+            OperationalCommand::PauseShorts => match self.set_state(SystemState::PausedShorts) {
+                Ok(_) => (true, "Short entries paused".to_string()),
+                Err(e) => (false, e.to_string()),
+            },
+            OperationalCommand::PauseAll => match self.set_state(SystemState::PausedAll) {
+                Ok(_) => (true, "All entries paused".to_string()),
+                Err(e) => (false, e.to_string()),
+            },
+            OperationalCommand::Resume => {
+                let drawdown_state = self.portfolio.check_drawdown_limits(&self.config.risk_controller);
+                match drawdown_state {
+                    // Venue pauses leave the system Active; lifting them is the whole resume
+                    SystemState::Active
+                        if self.portfolio.state == SystemState::Active && self.failure_tracker.has_paused_venues() =>
+                    {
+                        let venues = self.failure_tracker.resume_venues();
+                        info!(venues = ?venues, "venue pauses lifted");
+                        (true, format!("Venue pauses lifted: {}", venues.join(", ")))
+                    }
+                    SystemState::Active => match self.set_state(SystemState::Active) {
+                        Ok(_) => {
+                            self.slippage_pause.clear();
+                            self.failure_tracker.resume_venues();
+                            (true, "System resumed".to_string())
+                        }
+                        Err(e) => (false, e.to_string()),
+                    },
+                    // Open losses alone: hand over to the soft halt, which reopens entries on recovery
+                    SystemState::SoftHalt if self.portfolio.state == SystemState::EmergencyHalt => {
+                        (false, StateTransitionError::HaltRequiresReset(SystemState::Active).to_string())
+                    }
+                    SystemState::SoftHalt => {
+                        let old_state = self.portfolio.state;
+                        self.portfolio.state = SystemState::SoftHalt;
+                        self.slippage_pause.clear();
+                        self.failure_tracker.resume_venues();
+                        info!(from = ?old_state, to = ?SystemState::SoftHalt, "system state changed");
+                        (true, "Resumed into soft halt - entries reopen when unrealized drawdown recovers".to_string())
+                    }
+                    _ => (false, format!("Cannot resume - drawdown limits require {:?}", drawdown_state)),
+                }
+            }
+            OperationalCommand::Status => {
+                let now = self.evaluation_time();
+                let schedule = &self.config.activity_schedule;
+                let next_change = [Direction::Long, Direction::Short]
+                    .into_iter()
+                    .filter_map(|direction| schedule.next_change(direction, now))
+                    .min()
+                    .map(|at| at.to_rfc3339())
+                    .unwrap_or_else(|| "none".to_string());
+                let msg = format!(
+                    "State: {:?} | Longs: {}/{} | Shorts: {}/{} | Gross: {:.1}% | Net: {:.1}% | Activity: longs {:?}, shorts {:?}, next change {}",
+                    self.portfolio.state,
+                    self.portfolio.long_book.position_count(),
+                    self.portfolio.long_book.max_positions,
+                    self.portfolio.short_book.position_count(),
+                    self.portfolio.short_book.max_positions,
+                    self.portfolio.gross_exposure_pct * Decimal::new(100, 0),
+                    self.portfolio.net_exposure_pct * Decimal::new(100, 0),
+                    schedule.level_at(Direction::Long, now),
+                    schedule.level_at(Direction::Short, now),
+                    next_change
+                );
+                data = serde_json::to_value(self.portfolio.portfolio_summary()).ok();
+                (true, msg)
+            }
+            OperationalCommand::Exposure => {
+                let msg = format!(
+                    "Gross: ${:.2} ({:.1}%) | Net: ${:.2} ({:.1}%)",
+                    self.portfolio.gross_exposure_usd,
+                    self.portfolio.gross_exposure_pct * Decimal::new(100, 0),
+                    self.portfolio.net_exposure_usd,
+                    self.portfolio.net_exposure_pct * Decimal::new(100, 0)
+                );
+                (true, msg)
+            }
+            OperationalCommand::Risk => {
+                let msg = format!(
+                    "Daily DD: {:.2}% (realized {:.2}%, unrealized {:.2}%) | Weekly DD: {:.2}% | Monthly DD: {:.2}%",
+                    self.portfolio.daily_drawdown_pct * Decimal::new(100, 0),
+                    self.portfolio.daily_realized_drawdown_pct * Decimal::new(100, 0),
+                    self.portfolio.daily_unrealized_drawdown_pct * Decimal::new(100, 0),
+                    self.portfolio.weekly_drawdown_pct * Decimal::new(100, 0),
+                    self.portfolio.monthly_drawdown_pct * Decimal::new(100, 0)
+                );
+                data = serde_json::to_value(self.portfolio.drawdown_attribution()).ok();
+                (true, msg)
+            }
+            OperationalCommand::Health => {
+                let failures = self.failure_summary();
+                let count = |class| failures.counts.get(&class).copied().unwrap_or(0);
+                let mut msg = format!(
+                    "State: {:?} | Capital: ${:.2} | Available: ${:.2} | Liquidations: {} | \
+                     Failures: {} transient, {} market moved, {} venue, {} fatal",
+                    self.portfolio.state,
+                    self.portfolio.total_capital_usd,
+                    self.portfolio.available_capital_usd,
+                    self.portfolio.liquidation_count,
+                    count(failures::FailureClass::Transient),
+                    count(failures::FailureClass::MarketMoved),
+                    count(failures::FailureClass::VenueError),
+                    count(failures::FailureClass::Fatal)
+                );
+                if !failures.paused_venues.is_empty() {
+                    msg.push_str(&format!(" | Paused venues: {}", failures.paused_venues.join(", ")));
+                }
+                data = serde_json::to_value(&failures).ok();
+                (true, msg)
+            }
+            OperationalCommand::CloseLongs => {
+                let count = self.portfolio.long_book.position_count();
+                (true, format!("Close {} long positions - MANUAL EXECUTION REQUIRED", count))
+            }
+            OperationalCommand::CloseShorts => {
+                let count = self.portfolio.short_book.position_count();
+                (true, format!("Close {} short positions - MANUAL EXECUTION REQUIRED", count))
+            }
+            OperationalCommand::CloseAll => {
+                let total = self.emergency_close_all();
+                (true, format!("EMERGENCY: Close {} total positions", total))
+            }
+            OperationalCommand::History { token } => {
+                let history = self.position_history_for_token(token);
+                let avg_hold = self
+                    .average_holding_duration_hours(token)
+                    .map(|h| format!("{:.1}h", h))
+                    .unwrap_or_else(|| "n/a".to_string());
+                let msg = format!(
+                    "Token: {} | Positions: {} | Avg Hold: {} | Realized PnL: ${:.2}",
+                    token,
+                    history.len(),
+                    avg_hold,
+                    self.total_realized_pnl_for_token(token)
+                );
+                data = serde_json::to_value(&history).ok();
+                (true, msg)
+            }
+            OperationalCommand::Pnl { timeframe, curve_resolution_seconds } => {
+                let now = Utc::now();
+                let mut report = self.pnl_report(*timeframe, now);
+                if let Some(resolution) = curve_resolution_seconds.and_then(chrono::Duration::try_seconds) {
+                    report.equity_curve = Some(self.equity_curve(report.start, report.end, resolution));
+                }
+                let msg = format!(
+                    "Exits: {} ({} won, {} lost) | Realized PnL: ${:.2}",
+                    report.exits, report.winning_exits, report.losing_exits, report.realized_pnl_usd
+                );
+                data = serde_json::to_value(&report).ok();
+                (true, msg)
+            }
+            OperationalCommand::Rejects { timeframe } => {
+                let report = self.rejects_report(*timeframe, Utc::now());
+                let top_gate = report
+                    .by_gate
+                    .iter()
+                    .max_by_key(|(_, count)| **count)
+                    .map(|(gate, count)| format!("{} ({})", gate, count))
+                    .unwrap_or_else(|| "n/a".to_string());
+                let msg = format!("Rejections: {} | Top gate: {}", report.total, top_gate);
+                data = serde_json::to_value(&report).ok();
+                (true, msg)
+            }
+            OperationalCommand::Position { token } => {
+                let page = self.query_positions(PositionFilter {
+                    token: Some(token.clone()),
+                    ..PositionFilter::default()
+                });
+                let msg = format!(
+                    "Token: {} | Positions: {} | Unrealized PnL: ${:.2}",
+                    token,
+                    page.total,
+                    page.items.iter().map(|v| v.unrealized_pnl_usd).sum::<Decimal>()
+                );
+                data = serde_json::to_value(&page).ok();
+                (true, msg)
+            }
+            OperationalCommand::TimeStops { within_minutes } => {
+                let within = chrono::Duration::minutes(*within_minutes);
+                let approaching: Vec<serde_json::Value> = self
+                    .portfolio
+                    .short_book
+                    .positions_approaching_time_stop(within)
+                    .into_iter()
+                    .chain(self.portfolio.long_book.positions_approaching_time_stop(within))
+                    .map(|(p, remaining)| {
+                        serde_json::json!({
+                            "execution_id": p.execution_id,
+                            "token_symbol": p.token_symbol,
+                            "direction": p.direction,
+                            "time_stop_at": p.time_stop_at,
+                            "minutes_remaining": remaining.num_minutes(),
+                            "unrealized_pnl_pct": p.unrealized_pnl_pct,
+                        })
+                    })
+                    .collect();
+                let hold_reviews: Vec<&rescore::RescoreResult> =
+                    self.last_rescore.iter().filter(|r| r.needs_review()).collect();
+                let unable_to_rescore: Vec<&rescore::RescoreResult> =
+                    self.last_rescore.iter().filter(|r| r.is_stale()).collect();
+                let (failed_review, needs_review): (Vec<&LongReview>, Vec<&LongReview>) =
+                    self.last_long_reviews.iter().partition(|r| r.failed());
+                let msg = format!(
+                    "{} position(s) within {}m of time stop | {} flagged for hold review | {} stale, unable to rescore | \
+                     {} long(s) need review, {} failed review",
+                    approaching.len(),
+                    within_minutes,
+                    hold_reviews.len(),
+                    unable_to_rescore.len(),
+                    needs_review.len(),
+                    failed_review.len()
+                );
+                data = Some(serde_json::json!({
+                    "time_stops": approaching,
+                    "hold_reviews": hold_reviews,
+                    "unable_to_rescore": unable_to_rescore,
+                    "needs_review": needs_review,
+                    "failed_review": failed_review,
+                }));
+                (true, msg)
+            }
+        };
+
+        info!(command = ?command, success, %message, "operational command");
+        CommandResponse {
+            command: format!("{:?}", command),
+            success,
+            message,
+            data,
+            executed_at: Utc::now(),
+            requires_confirmation: false,
+            confirm_token: None,
+            confirm_token_expires_at: None,
+        }
+    }
+
+    pub fn log_rejection(
+        &mut self,
+        token: &TokenSnapshot,
+        direction: Direction,
+        validation: &RiskValidation,
+    ) {
+        if let Some(failure) = validation.first_failure() {
+            let safety = SafetyScore::calculate(token, self.safety_scoring(), &self.config.token_validation);
+            let binding = self.binding_input(token, direction, &failure.gate_name, safety.total_score);
+
+            self.record_rejection(RejectionLog {
+                timestamp: Utc::now(),
+                token_address: token.token_address.clone(),
+                token_symbol: token.token_symbol.clone(),
+                direction,
+                rejection_reason: failure.reason.clone().unwrap_or_default(),
+                failed_gate: failure.gate_name.clone(),
+                safety_score: token.has_onchain_data().then_some(safety.total_score),
+                liquidity_usd: Some(token.liquidity_usd),
+                config_key: binding.map(|(key, _)| key.to_string()),
+                observed_value: binding.map(|(_, observed)| observed),
+            });
+        }
+    }
+
+    /// Config threshold a failed gate compared against, and the token's value for it
+    fn binding_input(
+        &self,
+        token: &TokenSnapshot,
+        direction: Direction,
+        gate: &str,
+        safety_score: Decimal,
+    ) -> Option<(&'static str, Decimal)> {
+        let tv = &self.config.token_validation;
+        match gate {
+            "liquidity_range" if token.liquidity_usd < tv.liquidity_min_usd => {
+                Some(("token_validation.liquidity_min_usd", token.liquidity_usd))
+            }
+            "liquidity_range" => Some(("token_validation.liquidity_max_usd", token.liquidity_usd)),
+            "safety_score" => match direction {
+                Direction::Long => Some(("safety_scoring.long_entry_min", safety_score)),
+                Direction::Short => Some(("safety_scoring.short_entry_min", safety_score)),
+            },
+            "token_age" => Some(("token_validation.token_age_min_hours", Decimal::from(token.token_age_hours))),
+            "holder_distribution" if token.holder_count < tv.holder_count_min => {
+                Some(("token_validation.holder_count_min", Decimal::from(token.holder_count)))
+            }
+            "holder_distribution" => Some(("token_validation.top_10_concentration_max_pct", token.top_10_concentration_pct)),
+            "squeeze_risk" => Some(("token_validation.single_wallet_max_pct", token.largest_wallet_pct)),
+            "cex_spread" => match &token.source {
+                SnapshotSource::Cex { spread_bps, .. } => Some(("cex_validation.max_spread_bps", *spread_bps)),
+                SnapshotSource::Dex => None,
+            },
+            "cex_depth" => Some(("cex_validation.min_side_depth_usd", token.bid_depth_usd.min(token.ask_depth_usd))),
+            "cross_correlation" => self
+                .max_long_correlation(&token.token_address)
+                .map(|(_, correlation)| ("correlation.max_long_correlation", correlation)),
+            _ => None,
+        }
+    }
+
+    /// Which gates rejected the most of the last CONFIG_DIAGNOSTICS_WINDOW entries, the
+    /// config value behind each and the spread of values it turned away, with a loosening
+    /// for thresholds that rejected at least CONFIG_SUGGESTION_MIN_REJECTIONS
+    pub fn config_diagnostics(&self) -> ConfigDiagnostics {
+        let recent: Vec<&RejectionLog> = self.rejection_logs.iter().rev().take(CONFIG_DIAGNOSTICS_WINDOW).collect();
+
+        // (rejections, observed values) by gate and threshold
+        let mut by_threshold: BTreeMap<(String, Option<String>), (usize, Vec<Decimal>)> = BTreeMap::new();
+        for log in &recent {
+            let entry = by_threshold.entry((log.failed_gate.clone(), log.config_key.clone())).or_default();
+            entry.0 += 1;
+            entry.1.extend(log.observed_value);
+        }
+
+        let mut binding_constraints = Vec::new();
+        let mut suggested_adjustments = Vec::new();
+        for ((gate, config_key), (rejections, mut observed)) in by_threshold {
+            observed.sort();
+            let observed_min = observed.first().copied();
+            let observed_max = observed.last().copied();
+            let observed_median = observed.get(observed.len().saturating_sub(1) / 2).copied();
+            let config_value = config_key.as_deref().and_then(|key| self.config.threshold(key));
+            let bound = config_key
+                .as_deref()
+                .and_then(|key| GATE_THRESHOLDS.iter().find(|(k, _)| *k == key))
+                .map(|(_, bound)| *bound);
+
+            let summary = match (&config_key, config_value, bound) {
+                (Some(key), Some(value), Some(bound)) => {
+                    let (side, extreme) = match bound {
+                        ThresholdBound::Min => ("min", observed_min),
+                        ThresholdBound::Max => ("max", observed_max),
+                    };
+                    match extreme {
+                        Some(extreme) => format!(
+                            "{} = {} caused {} rejections; observed {} was {}",
+                            key, value, rejections, side, extreme
+                        ),
+                        None => format!("{} = {} caused {} rejections", key, value, rejections),
+                    }
+                }
+                _ => format!("{} gate caused {} rejections", gate, rejections),
+            };
+
+            if let (Some(key), Some(current_value), Some(bound), Some(median)) =
+                (&config_key, config_value, bound, observed_median)
+            {
+                if rejections >= CONFIG_SUGGESTION_MIN_REJECTIONS {
+                    let would_admit = observed
+                        .iter()
+                        .filter(|v| match bound {
+                            ThresholdBound::Min => **v >= median,
+                            ThresholdBound::Max => **v <= median,
+                        })
+                        .count();
+                    let verb = match bound {
+                        ThresholdBound::Min => "lowering",
+                        ThresholdBound::Max => "raising",
+                    };
+                    suggested_adjustments.push(ConfigSuggestion {
+                        config_key: key.clone(),
+                        current_value,
+                        suggested_value: median,
+                        would_admit,
+                        rejections,
+                        rationale: format!(
+                            "{} {} from {} to {} would have admitted {} of {} rejections",
+                            verb, key, current_value, median, would_admit, rejections
+                        ),
+                    });
+                }
+            }
+
+            binding_constraints.push(BindingConstraint {
+                gate,
+                config_key,
+                config_value,
+                bound,
+                rejections,
+                observed_min,
+                observed_median,
+                observed_max,
+                summary,
+            });
+        }
+        binding_constraints.sort_by_key(|c| std::cmp::Reverse(c.rejections));
+        suggested_adjustments.sort_by_key(|s| std::cmp::Reverse(s.rejections));
+
+        ConfigDiagnostics {
+            rejections_examined: recent.len(),
+            binding_constraints,
+            suggested_adjustments,
+        }
+    }
+
+    /// Liquidity thresholds that recent DEX snapshots suggest are too tight: a
+    /// `liquidity_min_usd` above the market median blocks most tokens, and a
+    /// `liquidity_max_usd` below the 90th percentile blocks more than a tenth of them.
+    /// Empty when there are no DEX snapshots.
+    pub fn validate_config_against_market(&self, recent_snapshots: &[TokenSnapshot]) -> Vec<ConfigMarketMismatch> {
+        let mut liquidity: Vec<Decimal> = recent_snapshots
+            .iter()
+            .filter(|s| s.source == SnapshotSource::Dex)
+            .map(|s| s.liquidity_usd)
+            .collect();
+        if liquidity.is_empty() {
+            return Vec::new();
+        }
+        liquidity.sort();
+        let share_below = |value: Decimal| {
+            Decimal::from(liquidity.iter().filter(|l| **l < value).count()) / Decimal::from(liquidity.len())
+        };
+
+        let tv = &self.config.token_validation;
+        let mut mismatches = Vec::new();
+        let min_percentile = share_below(tv.liquidity_min_usd);
+        if min_percentile > LIQUIDITY_MIN_MARKET_PERCENTILE {
+            if let Some(median) = nearest_rank(&liquidity, LIQUIDITY_MIN_MARKET_PERCENTILE) {
+                mismatches.push(ConfigMarketMismatch {
+                    field: "token_validation.liquidity_min_usd".to_string(),
+                    config_value: tv.liquidity_min_usd,
+                    market_percentile: min_percentile,
+                    recommendation: format!(
+                        "lower to the market median of ${} or below; {:.0}% of recent tokens are under ${}",
+                        median,
+                        min_percentile * Decimal::ONE_HUNDRED,
+                        tv.liquidity_min_usd
+                    ),
+                });
+            }
+        }
+        let max_percentile = share_below(tv.liquidity_max_usd);
+        if max_percentile < LIQUIDITY_MAX_MARKET_PERCENTILE {
+            if let Some(p90) = nearest_rank(&liquidity, LIQUIDITY_MAX_MARKET_PERCENTILE) {
+                mismatches.push(ConfigMarketMismatch {
+                    field: "token_validation.liquidity_max_usd".to_string(),
+                    config_value: tv.liquidity_max_usd,
+                    market_percentile: max_percentile,
+                    recommendation: format!(
+                        "raise to the market 90th percentile of ${} or above; {:.0}% of recent tokens are at or over ${}",
+                        p90,
+                        (Decimal::ONE - max_percentile) * Decimal::ONE_HUNDRED,
+                        tv.liquidity_max_usd
+                    ),
+                });
+            }
+        }
+        for mismatch in &mismatches {
+            warn!(
+                field = %mismatch.field,
+                config_value = %mismatch.config_value,
+                market_percentile = %mismatch.market_percentile,
+                "config out of line with market"
+            );
+        }
+        mismatches
+    }
+
+    pub fn position_history_for_token(&self, token_address: &str) -> Vec<PositionRecord> {
+        let mut records: Vec<PositionRecord> = self
+            .portfolio
+            .long_book
+            .positions
+            .iter()
+            .chain(self.portfolio.short_book.positions.iter())
+            .filter(|p| p.token_address == token_address)
+            .map(|p| PositionRecord {
+                position: p.clone(),
+                entry_log: self
+                    .entry_logs
+                    .iter()
+                    .find(|e| e.execution_id == p.execution_id)
+                    .cloned(),
+                exit_log: self
+                    .exit_logs
+                    .iter()
+                    .rev()
+                    .find(|e| e.execution_id == p.execution_id)
+                    .cloned(),
+            })
+            .collect();
+        records.sort_by_key(|r| r.position.opened_at);
+        records
+    }
+
+    pub fn average_holding_duration_hours(&self, token_address: &str) -> Option<f64> {
+        let mut durations: Vec<u64> = Vec::new();
+        for execution_id in self.execution_ids_for_token(token_address) {
+            let max_hold = self
+                .exit_logs
+                .iter()
+                .filter(|e| e.execution_id == execution_id)
+                .map(|e| e.hold_duration_seconds)
+                .max();
+            if let Some(seconds) = max_hold {
+                durations.push(seconds);
+            }
+        }
+        if durations.is_empty() {
+            return None;
+        }
+        let total_seconds: u64 = durations.iter().sum();
+        Some(total_seconds as f64 / durations.len() as f64 / 3600.0)
+    }
+
+    pub fn pnl_report(&self, timeframe: PnlTimeframe, now: DateTime<Utc>) -> PnlReport {
+        let exits: Vec<&ExitLog> = self.exit_logs.iter().filter(|e| timeframe.contains(e.timestamp, now)).collect();
+        PnlReport {
+            start: timeframe.start(now),
+            end: timeframe.end(now),
+            exits: exits.len(),
+            winning_exits: exits.iter().filter(|e| e.realized_pnl_usd > Decimal::ZERO).count(),
+            losing_exits: exits.iter().filter(|e| e.realized_pnl_usd < Decimal::ZERO).count(),
+            realized_pnl_usd: exits.iter().map(|e| e.realized_pnl_usd).sum(),
+            equity_curve: None,
+        }
+    }
+
+    pub fn rejects_report(&self, timeframe: PnlTimeframe, now: DateTime<Utc>) -> RejectsReport {
+        let mut by_gate = BTreeMap::new();
+        let mut total = 0;
+        for rejection in self.rejection_logs.iter().filter(|r| timeframe.contains(r.timestamp, now)) {
+            *by_gate.entry(rejection.failed_gate.clone()).or_insert(0) += 1;
+            total += 1;
+        }
+        RejectsReport {
+            start: timeframe.start(now),
+            end: timeframe.end(now),
+            total,
+            by_gate,
+        }
+    }
+
+    pub fn total_realized_pnl_for_token(&self, token_address: &str) -> Decimal {
+        let execution_ids = self.execution_ids_for_token(token_address);
+        self.exit_logs
+            .iter()
+            .filter(|e| execution_ids.contains(&e.execution_id))
+            .map(|e| e.realized_pnl_usd)
+            .sum()
+    }
+
+    fn execution_ids_for_token(&self, token_address: &str) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = self
+            .entry_logs
+            .iter()
+            .filter(|e| e.token_address == token_address)
+            .map(|e| e.execution_id)
+            .chain(
+                self.portfolio
+                    .long_book
+                    .positions
+                    .iter()
+                    .chain(self.portfolio.short_book.positions.iter())
+                    .filter(|p| p.token_address == token_address)
+                    .map(|p| p.execution_id),
+            )
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+}
+
+// ============================================================
+// SECTION 18: SLEEVE MANAGER
+// ============================================================
+
+/// Constraints that hold across every sleeve of a `SleeveManager`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleeveManagerConfig {
+    pub max_gross_exposure_pct: Decimal, // Summed gross exposure over summed capital
+    pub allow_opposite_directions: bool, // Let one sleeve short a token another holds long
+    #[serde(default = "default_max_command_records")]
+    pub max_command_records: usize, // Cross-sleeve command audit records kept in memory
+}
+
+impl Default for SleeveManagerConfig {
+    fn default() -> Self {
+        Self {
+            max_gross_exposure_pct: Decimal::ONE,
+            allow_opposite_directions: false,
+            max_command_records: default_max_command_records(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SleeveError {
+    UnknownSleeve(String),
+    DuplicateSleeve(String),
+    SameSleeve(String),
+    Position(PositionError),
+    Capital(CapitalError),
+}
+
+impl std::fmt::Display for SleeveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SleeveError::UnknownSleeve(name) => write!(f, "No sleeve named {}", name),
+            SleeveError::DuplicateSleeve(name) => write!(f, "Sleeve {} already exists", name),
+            SleeveError::SameSleeve(name) => write!(f, "Cannot rebalance sleeve {} into itself", name),
+            SleeveError::Position(e) => write!(f, "{}", e),
+            SleeveError::Capital(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SleeveError {}
+
+/// One sleeve's line in the combined view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleeveSummary {
+    pub name: String,
+    pub state: SystemState,
+    pub total_capital_usd: Decimal,
+    pub equity_usd: Decimal,
+    pub gross_exposure_usd: Decimal,
+    pub net_exposure_usd: Decimal,
+    pub daily_drawdown_pct: Decimal,
+}
+
+/// Every sleeve as one portfolio. Drawdowns run over the summed equity curve, so a loss in one
+/// sleeve offset by gains in another does not count.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombinedPortfolioView {
+    pub total_capital_usd: Decimal,
+    pub available_capital_usd: Decimal,
+    pub equity_usd: Decimal, // Capital plus unrealized P&L
+    pub gross_exposure_usd: Decimal,
+    pub net_exposure_usd: Decimal,
+    pub gross_exposure_pct: Decimal,
+    pub net_exposure_pct: Decimal,
+    pub daily_drawdown_pct: Decimal,
+    pub weekly_drawdown_pct: Decimal,
+    pub monthly_drawdown_pct: Decimal,
+    pub sleeves: Vec<SleeveSummary>,
+}
+
+/// High-water marks of the summed equity curve
+#[derive(Debug, Clone, Copy, Default)]
+struct CombinedHighWaterMarks {
+    daily: Decimal,
+    weekly: Decimal,
+    monthly: Decimal,
+}
+
+impl CombinedHighWaterMarks {
+    fn raise(&mut self, equity: Decimal) {
+        self.daily = self.daily.max(equity);
+        self.weekly = self.weekly.max(equity);
+        self.monthly = self.monthly.max(equity);
+    }
+
+    fn shift(&mut self, amount: Decimal) {
+        self.daily += amount;
+        self.weekly += amount;
+        self.monthly += amount;
+    }
+}
+
+fn drawdown_from(high_water_mark: Decimal, equity: Decimal) -> Decimal {
+    if high_water_mark > Decimal::ZERO && equity < high_water_mark {
+        (high_water_mark - equity) / high_water_mark
+    } else {
+        Decimal::ZERO
+    }
+}
+
+/// Independent engines over one capital base, each with its own config, book and drawdown
+/// limits, under a cross-sleeve layer for gross exposure and opposing directions
+pub struct SleeveManager {
+    pub config: SleeveManagerConfig,
+    sleeves: BTreeMap<String, StrikeBoxEngine>,
+    high_water_marks: CombinedHighWaterMarks,
+    command_bus: CommandBus,
+    pub command_audit_log: RingBuffer<CommandAuditRecord>, // Every command run across sleeves; each sleeve also audits its share
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    audit_spill_failures: std::sync::atomic::AtomicU64,
+}
+
+impl SleeveManager {
+    pub fn new(config: SleeveManagerConfig) -> Self {
+        Self {
+            command_audit_log: RingBuffer::new(config.max_command_records),
+            config,
+            sleeves: BTreeMap::new(),
+            high_water_marks: CombinedHighWaterMarks::default(),
+            command_bus: CommandBus::new(),
+            audit_sink: None,
+            audit_spill_failures: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Spill command audit records evicted from memory to `sink`
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Evicted command records the audit sink failed to take
+    pub fn audit_spill_failures(&self) -> u64 {
+        self.audit_spill_failures.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Adds a sleeve running `config` with `capital` allocated to it
+    pub fn add_sleeve(
+        &mut self,
+        name: impl Into<String>,
+        config: StrikeBoxConfig,
+        capital: Decimal,
+    ) -> Result<&mut StrikeBoxEngine, SleeveError> {
+        let name = name.into();
+        if self.sleeves.contains_key(&name) {
+            return Err(SleeveError::DuplicateSleeve(name));
+        }
+        if capital <= Decimal::ZERO {
+            return Err(SleeveError::Capital(CapitalError::InvalidAmount(capital)));
+        }
+        self.high_water_marks.shift(capital);
+        info!(sleeve = %name, %capital, "sleeve added");
+        Ok(self.sleeves.entry(name.clone()).or_insert_with(|| StrikeBoxEngine::new(config, capital)))
+    }
+
+    pub fn sleeve(&self, name: &str) -> Option<&StrikeBoxEngine> {
+        self.sleeves.get(name)
+    }
+
+    pub fn sleeve_mut(&mut self, name: &str) -> Option<&mut StrikeBoxEngine> {
+        self.sleeves.get_mut(name)
+    }
+
+    pub fn sleeve_names(&self) -> Vec<&str> {
+        self.sleeves.keys().map(String::as_str).collect()
+    }
+
+    fn engine(&self, name: &str) -> Result<&StrikeBoxEngine, SleeveError> {
+        self.sleeves.get(name).ok_or_else(|| SleeveError::UnknownSleeve(name.to_string()))
+    }
+
+    fn engine_mut(&mut self, name: &str) -> Result<&mut StrikeBoxEngine, SleeveError> {
+        self.sleeves.get_mut(name).ok_or_else(|| SleeveError::UnknownSleeve(name.to_string()))
+    }
+
+    /// The sleeve's own entry gates, then the cross-sleeve ones when those pass
+    pub fn validate_entry(
+        &self,
+        sleeve: &str,
+        token: &TokenSnapshot,
+        direction: Direction,
+    ) -> Result<RiskValidation, SleeveError> {
+        let engine = self.engine(sleeve)?;
+        let mut validation = engine.validate_entry(token, direction);
+        if !validation.all_passed {
+            return Ok(validation);
+        }
+
+        let opposing = self.sleeves.iter().find(|(name, other)| {
+            name.as_str() != sleeve
+                && other
+                    .portfolio
+                    .long_book
+                    .positions
+                    .iter()
+                    .chain(&other.portfolio.short_book.positions)
+                    .any(|p| {
+                        p.token_address == token.token_address
+                            && p.direction != direction
+                            && p.is_open()
+                    })
+        });
+        match opposing {
+            Some((name, _)) if !self.config.allow_opposite_directions => validation.add_gate(
+                "cross_sleeve_direction",
+                GateResult::Failed,
+                Some(format!("Sleeve {} holds {} in the opposite direction", name, token.token_symbol)),
+            ),
+            _ => validation.add_gate("cross_sleeve_direction", GateResult::Passed, None),
+        }
+
+        let view = self.combined_view();
+        let size = engine.calculate_position_size(token, direction);
+        let limit = view.total_capital_usd * self.config.max_gross_exposure_pct;
+        if view.gross_exposure_usd + size > limit {
             validation.add_gate(
-                "no_stacking",
+                "combined_gross_exposure",
                 GateResult::Failed,
-                Some("Position already exists for token".to_string()),
+                Some(format!(
+                    "Gross exposure across sleeves ${:.2} + ${:.2} exceeds ${:.2}",
+                    view.gross_exposure_usd, size, limit
+                )),
+            );
+        } else {
+            validation.add_gate("combined_gross_exposure", GateResult::Passed, None);
+        }
+        Ok(validation)
+    }
+
+    /// Opens and books a position in `sleeve`; `validation` should come from `validate_entry`
+    /// here so the cross-sleeve gates were part of the approval
+    pub fn open_position(
+        &mut self,
+        sleeve: &str,
+        token: &TokenSnapshot,
+        validation: &RiskValidation,
+        entry_price: Decimal,
+        position_size_usd: Decimal,
+        safety: SafetyScore,
+    ) -> Result<(Position, Option<StopBasis>), SleeveError> {
+        let engine = self.engine_mut(sleeve)?;
+        let (position, basis) = engine
+            .open_position(token, validation, entry_price, position_size_usd, safety)
+            .map_err(SleeveError::Position)?;
+        engine.book_position(position.clone());
+        Ok((position, basis))
+    }
+
+    pub fn close_position(
+        &mut self,
+        sleeve: &str,
+        execution_id: Uuid,
+        exit_price: Decimal,
+        liquidity_at_exit: Decimal,
+        slippage_bps: Decimal,
+    ) -> Result<Option<PositionExit>, SleeveError> {
+        let engine = self.engine_mut(sleeve)?;
+        let Some(exit) = engine.close_position(execution_id, exit_price, liquidity_at_exit, slippage_bps) else {
+            return Ok(None);
+        };
+        // The realized P&L moves the sleeve's drawdowns and limits now rather than at the next tick
+        engine.portfolio.update_drawdowns();
+        let state_change = engine.enforce_drawdown_limits();
+        log_sleeve_state_change(sleeve, state_change);
+        let equity = self.combined_view().equity_usd;
+        self.high_water_marks.raise(equity);
+        Ok(Some(exit))
+    }
+
+    /// Moves capital between sleeves; the source must have `amount` unallocated
+    pub fn rebalance(&mut self, from: &str, to: &str, amount: Decimal) -> Result<(), SleeveError> {
+        if from == to {
+            return Err(SleeveError::SameSleeve(from.to_string()));
+        }
+        self.engine(to)?;
+        self.engine_mut(from)?.withdraw_capital(amount).map_err(SleeveError::Capital)?;
+        self.engine_mut(to)?.deposit_capital(amount).map_err(SleeveError::Capital)?;
+        info!(from, to, %amount, "sleeve capital rebalanced");
+        Ok(())
+    }
+
+    /// Ticks every sleeve, pauses or halts any sleeve past its own drawdown limits, then moves
+    /// the combined high-water marks
+    pub fn tick(&mut self) {
+        for (name, engine) in &mut self.sleeves {
+            log_sleeve_state_change(name, engine.tick());
+        }
+        let equity = self.combined_view().equity_usd;
+        self.high_water_marks.raise(equity);
+    }
+
+    pub fn combined_view(&self) -> CombinedPortfolioView {
+        let sleeves: Vec<SleeveSummary> = self
+            .sleeves
+            .iter()
+            .map(|(name, engine)| {
+                let portfolio = &engine.portfolio;
+                SleeveSummary {
+                    name: name.clone(),
+                    state: portfolio.state,
+                    total_capital_usd: portfolio.total_capital_usd,
+                    equity_usd: portfolio.marked_equity_usd(),
+                    gross_exposure_usd: portfolio.long_book.total_allocation_usd
+                        + portfolio.short_book.total_allocation_usd,
+                    net_exposure_usd: portfolio.long_book.total_allocation_usd
+                        - portfolio.short_book.total_allocation_usd,
+                    daily_drawdown_pct: portfolio.daily_drawdown_pct,
+                }
+            })
+            .collect();
+
+        let total_capital_usd: Decimal = sleeves.iter().map(|s| s.total_capital_usd).sum();
+        let equity_usd: Decimal = sleeves.iter().map(|s| s.equity_usd).sum();
+        let gross_exposure_usd: Decimal = sleeves.iter().map(|s| s.gross_exposure_usd).sum();
+        let net_exposure_usd: Decimal = sleeves.iter().map(|s| s.net_exposure_usd).sum();
+        let (gross_exposure_pct, net_exposure_pct) = if total_capital_usd > Decimal::ZERO {
+            (gross_exposure_usd / total_capital_usd, net_exposure_usd / total_capital_usd)
+        } else {
+            (Decimal::ZERO, Decimal::ZERO)
+        };
+        // Not yet ticked equity can already stand above the recorded marks
+        let mut marks = self.high_water_marks;
+        marks.raise(equity_usd);
+
+        CombinedPortfolioView {
+            total_capital_usd,
+            available_capital_usd: total_capital_usd - gross_exposure_usd,
+            equity_usd,
+            gross_exposure_usd,
+            net_exposure_usd,
+            gross_exposure_pct,
+            net_exposure_pct,
+            daily_drawdown_pct: drawdown_from(marks.daily, equity_usd),
+            weekly_drawdown_pct: drawdown_from(marks.weekly, equity_usd),
+            monthly_drawdown_pct: drawdown_from(marks.monthly, equity_usd),
+            sleeves,
+        }
+    }
+
+    /// Entry point for operator channels, deduplicating retries and holding a command for
+    /// confirmation when any sleeve would. Status, Exposure, Risk and Health answer from the
+    /// combined view; every other command runs, and is audited, on each sleeve in turn.
+    pub fn handle_command(&mut self, request: CommandRequest) -> CommandResponse {
+        self.handle_command_at(request, Utc::now())
+    }
+
+    pub fn handle_command_at(&mut self, request: CommandRequest, now: DateTime<Utc>) -> CommandResponse {
+        let needs_confirmation = self.sleeves.values().any(|engine| engine.requires_confirmation(&request.command));
+        let response = match self.command_bus.admit(&request, needs_confirmation, now) {
+            Admission::Duplicate(response) => return response,
+            Admission::Respond(response) => response,
+            Admission::Run { confirmation_requested_by } => {
+                let response = self.run_command(&request, confirmation_requested_by.clone(), now);
+                let record = CommandAuditRecord::new(&request, confirmation_requested_by, &response, now);
+                if let (Some(evicted), Some(sink)) = (self.command_audit_log.push(record), &self.audit_sink) {
+                    spill_to(sink.as_ref(), &AuditRecord::Command(evicted), &self.audit_spill_failures);
+                }
+                response
+            }
+        };
+        self.command_bus.record(request.id, response.clone());
+        response
+    }
+
+    fn run_command(&mut self, request: &CommandRequest, confirmation_requested_by: Option<String>, now: DateTime<Utc>) -> CommandResponse {
+        let command = &request.command;
+        let hundred = Decimal::new(100, 0);
+        let view = self.combined_view();
+        let message = match command {
+            OperationalCommand::Status => Some(format!(
+                "Sleeves: {} | {} | Gross: {:.1}% | Net: {:.1}%",
+                view.sleeves.len(),
+                view.sleeves
+                    .iter()
+                    .map(|s| format!("{}: {:?}", s.name, s.state))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                view.gross_exposure_pct * hundred,
+                view.net_exposure_pct * hundred
+            )),
+            OperationalCommand::Exposure => Some(format!(
+                "Gross: ${:.2} ({:.1}%) | Net: ${:.2} ({:.1}%)",
+                view.gross_exposure_usd,
+                view.gross_exposure_pct * hundred,
+                view.net_exposure_usd,
+                view.net_exposure_pct * hundred
+            )),
+            OperationalCommand::Risk => Some(format!(
+                "Combined Daily DD: {:.2}% | Weekly DD: {:.2}% | Monthly DD: {:.2}%",
+                view.daily_drawdown_pct * hundred,
+                view.weekly_drawdown_pct * hundred,
+                view.monthly_drawdown_pct * hundred
+            )),
+            OperationalCommand::Health => Some(format!(
+                "Sleeves active: {}/{} | Capital: ${:.2} | Equity: ${:.2} | Available: ${:.2}",
+                view.sleeves.iter().filter(|s| s.state == SystemState::Active).count(),
+                view.sleeves.len(),
+                view.total_capital_usd,
+                view.equity_usd,
+                view.available_capital_usd
+            )),
+            _ => None,
+        };
+        if let Some(message) = message {
+            return CommandResponse {
+                command: format!("{:?}", command),
+                success: true,
+                message,
+                data: serde_json::to_value(&view).ok(),
+                executed_at: now,
+                requires_confirmation: false,
+                confirm_token: None,
+                confirm_token_expires_at: None,
+            };
+        }
+
+        let responses: Vec<(String, CommandResponse)> = self
+            .sleeves
+            .iter_mut()
+            .map(|(name, engine)| (name.clone(), engine.run_audited(request, confirmation_requested_by.clone(), now)))
+            .collect();
+        CommandResponse {
+            command: format!("{:?}", command),
+            success: responses.iter().all(|(_, r)| r.success),
+            message: responses
+                .iter()
+                .map(|(name, r)| format!("{}: {}", name, r.message))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            data: Some(serde_json::Value::Object(
+                responses
+                    .into_iter()
+                    .filter_map(|(name, r)| Some((name, r.data?)))
+                    .collect(),
+            )),
+            executed_at: now,
+            requires_confirmation: false,
+            confirm_token: None,
+            confirm_token_expires_at: None,
+        }
+    }
+}
+
+fn log_sleeve_state_change(sleeve: &str, state_change: Option<SystemState>) {
+    match state_change {
+        None => {}
+        Some(SystemState::EmergencyHalt) => warn!(sleeve, "sleeve drawdown past monthly review limit"),
+        Some(SystemState::Active) => info!(sleeve, "sleeve soft halt cleared"),
+        Some(state) => warn!(sleeve, to = ?state, "sleeve paused on drawdown"),
+    }
+}
+
+// ============================================================
+// SECTION 19: UNIT TESTS
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trades::{TradeFilter, TradeStatus};
+
+    fn create_test_token() -> TokenSnapshot {
+        TokenSnapshot {
+            token_address: "0x1234567890abcdef".to_string(),
+            token_symbol: "TEST".to_string(),
+            liquidity_usd: Decimal::new(750_000, 0),
+            bid_depth_usd: Decimal::new(375_000, 0),
+            ask_depth_usd: Decimal::new(375_000, 0),
+            holder_count: 60,
+            top_10_concentration_pct: Decimal::new(45, 2),
+            largest_wallet_pct: Decimal::new(12, 2),
+            token_age_hours: 48,
+            contract_verified: true,
+            is_proxy_contract: false,
+            deployment_timestamp: Utc::now(),
+            snapshot_timestamp: Utc::now(),
+            source: SnapshotSource::Dex,
+            categories: None,
+        }
+    }
+
+    /// Default config without trading fees, for tests that check gross P&L
+    fn fee_free_config() -> StrikeBoxConfig {
+        StrikeBoxConfig { fees: FeeModel::zero(), ..StrikeBoxConfig::default() }
+    }
+
+    fn test_safety(token: &TokenSnapshot) -> SafetyScore {
+        SafetyScore::calculate(token, &SafetyScoreConfig::default(), &TokenValidationConfig::default())
+    }
+
+    fn create_test_position(token: &TokenSnapshot, direction: Direction, opened_at: DateTime<Utc>) -> Position {
+        let entry_price = Decimal::new(100, 0);
+        let safety = test_safety(token);
+        Position {
+            execution_id: Uuid::new_v4(),
+            token_address: token.token_address.clone(),
+            token_symbol: token.token_symbol.clone(),
+            direction,
+            entry_price,
+            current_price: entry_price,
+            position_size_tokens: Decimal::new(100, 0),
+            position_size_usd: Decimal::new(10_000, 0),
+            remaining_size_pct: Decimal::ONE,
+            liquidity_at_entry: token.liquidity_usd,
+            safety_score_at_entry: safety.total_score,
+            holder_count_at_entry: token.holder_count,
+            stop_loss_price: Decimal::new(95, 0),
+            take_profit_targets: [(115, 33), (130, 33), (150, 34)]
+                .into_iter()
+                .map(|(price, exit)| TpTarget {
+                    price: Decimal::new(price, 0),
+                    hit: false,
+                    exit_pct: Decimal::new(exit, 2),
+                })
+                .collect(),
+            risk_approval_id: Uuid::new_v4(),
+            opened_at,
+            time_stop_at: None,
+            status: PositionStatus::Open,
+            closed_at: None,
+            trailing_stop_active: false,
+            trailing_stop_high: None,
+            trailing_activation_price: (direction == Direction::Long).then(|| Decimal::new(115, 0)),
+            unrealized_pnl_usd: Decimal::ZERO,
+            unrealized_pnl_pct: Decimal::ZERO,
+            max_favorable_excursion_pct: Decimal::ZERO,
+            max_adverse_excursion_pct: Decimal::ZERO,
+            leverage: Decimal::ONE,
+            venue: token.venue().to_string(),
+            safety_breakdown_at_entry: Some(safety),
+            score_history: Vec::new(),
+        }
+    }
+
+    fn create_test_exit(execution_id: Uuid, pnl_usd: Decimal, hold_seconds: u64) -> ExitLog {
+        ExitLog {
+            execution_id,
+            timestamp: Utc::now(),
+            exit_price: Decimal::new(110, 0),
+            exit_type: ExitType::Manual,
+            exit_size_pct: Decimal::ONE,
+            realized_pnl_tokens: Decimal::ZERO,
+            realized_pnl_usd: pnl_usd,
+            fees_usd: Decimal::ZERO,
+            slippage_bps: Decimal::ZERO,
+            hold_duration_seconds: hold_seconds,
+            liquidity_depth_exit_usd: Decimal::new(750_000, 0),
+            exit_execution_id: None,
+            latency_ms: 0,
+            trigger_assumption: None,
+        }
+    }
+
+    fn create_test_entry(position: &Position) -> EntryLog {
+        EntryLog {
+            execution_id: position.execution_id,
+            timestamp: position.opened_at,
+            token_address: position.token_address.clone(),
+            token_symbol: position.token_symbol.clone(),
+            direction: position.direction,
+            entry_price: position.entry_price,
+            position_size_tokens: position.position_size_tokens,
+            position_size_usd: position.position_size_usd,
+            liquidity_depth_usd: position.liquidity_at_entry,
+            safety_score: position.safety_score_at_entry,
+            holder_count: position.holder_count_at_entry,
+            stop_loss_price: position.stop_loss_price,
+            take_profit_prices: position.take_profit_prices(),
+            risk_approval_id: position.risk_approval_id,
+            latency_ms: 0,
+            slippage_bps: Decimal::ZERO,
+            stop_basis: None,
+        }
+    }
+
+    fn create_test_report(execution_id: Uuid, requested_price: Decimal, achieved_avg_price: Decimal) -> ExecutionReport {
+        let submitted_at = Utc::now();
+        ExecutionReport {
+            execution_id,
+            submitted_at,
+            acked_at: submitted_at + chrono::Duration::milliseconds(120),
+            first_fill_at: submitted_at + chrono::Duration::milliseconds(150),
+            completed_at: submitted_at + chrono::Duration::milliseconds(400),
+            requested_price,
+            achieved_avg_price,
+            requested_size: Decimal::new(100, 0),
+            filled_size: Decimal::new(100, 0),
+            fees_usd: Decimal::new(5, 0),
+        }
+    }
+
+    #[test]
+    fn test_default_config_values() {
+        let config = StrikeBoxConfig::default();
+        assert_eq!(config.token_validation.liquidity_min_usd, Decimal::new(500_000, 0));
+        assert_eq!(config.token_validation.liquidity_max_usd, Decimal::new(1_000_000, 0));
+        assert_eq!(config.token_validation.holder_count_min, 25);
+        assert_eq!(config.token_validation.token_age_min_hours, 24);
+        assert_eq!(config.position_sizing.long_book_max_positions, 10);
+        assert_eq!(config.position_sizing.short_book_max_positions, 3);
+        assert_eq!(config.stop_loss.short_fixed_pct, Decimal::new(8, 2));
+    }
+
+    #[test]
+    fn test_safety_score_calculation() {
+        let config = SafetyScoreConfig::default();
+        let validation = TokenValidationConfig::default();
+        let token = create_test_token();
+        let score = SafetyScore::calculate(&token, &config, &validation);
+        assert!(score.total_score > Decimal::new(60, 2));
+        assert!(score.qualifies_for_long(&config));
+        assert!(score.qualifies_for_short(&config));
+    }
+
+    #[test]
+    fn test_liquidity_scaler() {
+        assert_eq!(LiquidityScaler::max_position_pct(Decimal::new(550_000, 0)), Decimal::new(5, 3));
+        assert_eq!(LiquidityScaler::max_position_pct(Decimal::new(700_000, 0)), Decimal::new(1, 2));
+        assert_eq!(LiquidityScaler::max_position_pct(Decimal::new(800_000, 0)), Decimal::new(15, 3));
+        assert_eq!(LiquidityScaler::max_position_pct(Decimal::new(950_000, 0)), Decimal::new(2, 2));
+    }
+
+    #[test]
+    fn test_engine_validation_passing() {
+        let config = StrikeBoxConfig::default();
+        let engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let validation = engine.validate_entry(&token, Direction::Long);
+        assert!(validation.all_passed);
+    }
+
+    #[test]
+    fn test_proxy_exceptions_are_allowed_and_persisted() {
+        let path = std::env::temp_dir().join(format!("strike_box_config_{}.json", Uuid::new_v4()));
+        let mut config = StrikeBoxConfig::default();
+        config.token_validation.reject_proxy_contracts = true;
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0)).with_config_path(&path);
+        let mut token = create_test_token();
+        token.is_proxy_contract = true;
+
+        let validation = engine.validate_entry(&token, Direction::Long);
+        assert!(!validation.all_passed);
+        let gate = validation.gates.last().unwrap();
+        assert_eq!(gate.gate_name, "contract_verification");
+        assert_eq!(gate.result, GateResult::Failed);
+
+        assert_eq!(engine.add_proxy_exception("0x1234567890ABCDEF"), Ok(true));
+        assert!(engine.validate_entry(&token, Direction::Long).all_passed);
+
+        // A restart from the config file keeps the exception
+        let reloaded = StrikeBoxConfig::load_json(&path).unwrap();
+        assert!(reloaded.token_validation.proxy_contract_exceptions.contains("0x1234567890abcdef"));
+
+        assert_eq!(engine.remove_proxy_exception(&token.token_address), Ok(true));
+        assert!(!engine.validate_entry(&token, Direction::Long).all_passed);
+        let reloaded = StrikeBoxConfig::load_json(&path).unwrap();
+        assert!(reloaded.token_validation.proxy_contract_exceptions.is_empty());
+
+        // Each save renames its temp file over the config, leaving nothing beside it
+        let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let leftovers = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&file_name))
+            .count();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(leftovers, 1);
+    }
+
+    fn create_test_fill(price: i64, size_tokens: i64) -> Fill {
+        Fill { price: Decimal::new(price, 0), size_tokens: Decimal::new(size_tokens, 0), filled_at: Utc::now() }
+    }
+
+    #[test]
+    fn test_reservations_hold_the_last_slot() {
+        let mut config = StrikeBoxConfig::default();
+        config.position_sizing.long_book_max_positions = 1;
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let mut other = create_test_token();
+        other.token_address = "0xfedcba0987654321".to_string();
+
+        let first = engine.reserve_entry(&token, Direction::Long, Decimal::new(15_000, 0)).unwrap();
+        let second = engine.reserve_entry(&other, Direction::Long, Decimal::new(15_000, 0));
+        assert!(matches!(second, Err(ReservationError::Rejected(_))));
+        assert_eq!(engine.portfolio.long_book.reserved_slots, 1);
+
+        // Planned $15k, $12k filled over two prints at a $100 average
+        let execution_id = engine
+            .commit_entry(first, &[create_test_fill(99, 60), create_test_fill(101, 60)])
+            .unwrap();
+        let position = &engine.portfolio.long_book.positions[0];
+        assert_eq!(position.execution_id, execution_id);
+        assert_eq!(position.entry_price, Decimal::new(100, 0));
+        assert_eq!(position.position_size_usd, Decimal::new(12_000, 0));
+        assert_eq!(engine.portfolio.long_book.total_allocation_usd, Decimal::new(12_000, 0));
+        assert_eq!(engine.portfolio.long_book.reserved_usd, Decimal::ZERO);
+        assert_eq!(engine.portfolio.long_book.reserved_slots, 0);
+        assert!(matches!(engine.commit_entry(first, &[]), Err(ReservationError::UnknownReservation(_))));
+    }
+
+    #[test]
+    fn test_reservations_expire_after_ttl() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0))
+            .with_reservation_ttl(chrono::Duration::seconds(5));
+        let token = create_test_token();
+        let id = engine.reserve_entry(&token, Direction::Long, Decimal::new(15_000, 0)).unwrap();
+        assert!(engine.expire_reservations(Utc::now()).is_empty());
+        assert_eq!(engine.portfolio.long_book.reserved_usd, Decimal::new(15_000, 0));
+
+        let expired = engine.expire_reservations(Utc::now() + chrono::Duration::seconds(6));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].reservation_id, id);
+        assert_eq!(engine.portfolio.long_book.reserved_usd, Decimal::ZERO);
+        assert_eq!(engine.portfolio.long_book.reserved_slots, 0);
+        assert!(engine.reservation(id).is_none());
+        assert!(matches!(engine.cancel_reservation(id), Err(ReservationError::UnknownReservation(_))));
+    }
+
+    #[test]
+    fn test_commit_below_min_fill_is_refused() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let id = engine.reserve_entry(&token, Direction::Long, Decimal::new(15_000, 0)).unwrap();
+
+        // $9k of $15k is 60%, under the 80% default
+        let refused = engine.commit_entry(id, &[create_test_fill(100, 90)]);
+        assert!(matches!(
+            refused,
+            Err(ReservationError::BelowMinFill { filled_pct, .. }) if filled_pct == Decimal::new(6, 1)
+        ));
+        assert!(engine.portfolio.long_book.positions.is_empty());
+        assert!(engine.reservation(id).is_some());
+
+        engine.cancel_reservation(id).unwrap();
+        assert_eq!(engine.portfolio.long_book.reserved_slots, 0);
+        assert_eq!(engine.portfolio.long_book.available_capacity_usd(), engine.portfolio.long_book.max_allocation_usd);
+    }
+
+    #[test]
+    fn test_commit_over_plan_must_fit_the_book() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        let token = create_test_token();
+        let max_allocation = engine.portfolio.long_book.max_allocation_usd;
+        let planned = max_allocation - Decimal::new(1_000, 0);
+        let id = engine.reserve_entry(&token, Direction::Long, planned).unwrap();
+
+        // $2k over plan with $1k left in the book
+        let overfill = Fill { price: Decimal::ONE, size_tokens: planned + Decimal::new(2_000, 0), filled_at: Utc::now() };
+        let refused = engine.commit_entry(id, &[overfill]);
+        assert!(matches!(
+            refused,
+            Err(ReservationError::Overfilled { available_usd, .. }) if available_usd == Decimal::new(1_000, 0)
+        ));
+        assert!(engine.portfolio.long_book.positions.is_empty());
+        assert!(engine.reservation(id).is_some());
+
+        // $500 over fits
+        let fill = Fill { price: Decimal::ONE, size_tokens: planned + Decimal::new(500, 0), filled_at: Utc::now() };
+        engine.commit_entry(id, &[fill]).unwrap();
+        assert_eq!(engine.portfolio.long_book.total_allocation_usd, planned + Decimal::new(500, 0));
+        assert!(engine.portfolio.long_book.total_allocation_usd <= max_allocation);
+    }
+
+    #[test]
+    fn test_drawdown_history_and_recovery() {
+        let start = Utc::now() - chrono::Duration::hours(10);
+        let at = |hours: i64| start + chrono::Duration::hours(hours);
+        let history: Vec<(DateTime<Utc>, Decimal)> = [100, 110, 88, 99, 112, 106]
+            .iter()
+            .enumerate()
+            .map(|(i, &equity)| (at(i as i64), Decimal::new(equity, 0)))
+            .collect();
+        // 110 -> 88 is the worst decline; from hour 3 on it's only 112 -> 106
+        assert_eq!(PortfolioState::max_drawdown_since(start, &history), Decimal::new(2, 1));
+        assert_eq!(
+            PortfolioState::max_drawdown_since(at(3), &history),
+            Decimal::new(6, 0) / Decimal::new(112, 0)
+        );
+
+        // Trough at hour 2, back above the 110 peak at hour 4
+        assert_eq!(PortfolioState::recovery_time_from_trough(&history), Some(chrono::Duration::hours(2)));
+
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        for &(ts, equity) in &history {
+            let mut sample = history::PortfolioSample::from_portfolio(&engine.portfolio, ts);
+            sample.equity = equity;
+            engine.portfolio_history.record(sample);
+        }
+
+        let token = create_test_token();
+        let position = create_test_position(&token, Direction::Long, Utc::now());
+        engine.book_position(position);
+        let result = engine.stress_test(Decimal::new(-3, 1));
+        assert_eq!(result.scenario_pnl_usd, Decimal::new(-3_000, 0));
+        assert_eq!(result.scenario_drawdown_pct, Decimal::new(3, 3));
+        assert_eq!(result.worst_historical_drawdown_pct, Decimal::new(2, 1));
+        assert_eq!(result.vs_worst_historical, Some(Decimal::new(15, 3)));
+        assert_eq!(result.historical_recovery_time, Some(chrono::Duration::hours(2)));
+        // The same AllTokens scenario through run_stress
+        let crash = stress::StressScenario::new("crash", vec![stress::Shock::AllTokens { price_pct: Decimal::new(-3, 1) }]);
+        assert_eq!(engine.run_stress(&[crash])[0].pnl_usd, result.scenario_pnl_usd);
+    }
+
+    fn create_test_borrow(available_usd: i64, max_duration_hours: u32) -> BorrowInfo {
+        BorrowInfo {
+            available_usd: Decimal::new(available_usd, 0),
+            hourly_rate_pct: Decimal::new(2, 4),
+            max_duration_hours,
+        }
+    }
+
+    fn borrow_engine(provider: StaticBorrowProvider) -> StrikeBoxEngine {
+        StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0)).with_borrow_provider(Arc::new(provider))
+    }
+
+    #[tokio::test]
+    async fn test_short_fails_without_enough_borrow() {
+        let token = create_test_token();
+        let mut engine = borrow_engine(
+            StaticBorrowProvider::default().with_token(&token.token_address, create_test_borrow(1_000, 96)),
+        );
+        let size = engine.calculate_position_size(&token, Direction::Short);
+
+        let validation = engine.validate_entry_with_borrow(&token, Direction::Short).await;
+        assert!(!validation.all_passed);
+        let gate = validation.gates.last().unwrap();
+        assert_eq!((gate.gate_name.as_str(), gate.result), ("borrow_availability", GateResult::Failed));
+
+        // Exactly the size isn't enough; the buffer has to be there too
+        let mut exact = borrow_engine(
+            StaticBorrowProvider::default()
+                .with_token(&token.token_address, BorrowInfo { available_usd: size, ..create_test_borrow(0, 96) }),
+        );
+        assert!(!exact.validate_entry_with_borrow(&token, Direction::Short).await.all_passed);
+        // Longs never ask
+        let mut nothing = borrow_engine(StaticBorrowProvider::default());
+        assert!(nothing.validate_entry_with_borrow(&token, Direction::Long).await.all_passed);
+        assert!(!nothing.validate_entry_with_borrow(&token, Direction::Short).await.all_passed);
+        assert!(nothing.borrow_rate(&token.token_address).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_short_borrow_duration_warning() {
+        let token = create_test_token();
+        let mut engine = borrow_engine(
+            StaticBorrowProvider::default().with_token(&token.token_address, create_test_borrow(1_000_000, 24)),
+        );
+
+        let validation = engine.validate_entry_with_borrow(&token, Direction::Short).await;
+        assert!(validation.all_passed);
+        let gate = validation.gates.last().unwrap();
+        assert_eq!(gate.gate_name, "borrow_availability");
+        assert!(gate.reason.as_deref().unwrap().contains("24h"));
+        assert_eq!(engine.borrow_rate(&token.token_address), Some(Decimal::new(2, 4)));
+
+        let mut long_borrow = borrow_engine(
+            StaticBorrowProvider::default().with_token(&token.token_address, create_test_borrow(1_000_000, 96)),
+        );
+        let validation = long_borrow.validate_entry_with_borrow(&token, Direction::Short).await;
+        assert!(validation.gates.last().unwrap().reason.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_short_entry_paths_need_a_fresh_borrow_quote() {
+        let token = create_test_token();
+        let mut engine = borrow_engine(
+            StaticBorrowProvider::default().with_token(&token.token_address, create_test_borrow(1_000_000, 96)),
+        );
+        let start = Utc::now();
+        engine.set_evaluation_time(Some(start));
+
+        // The sync paths run the same gate, so skipping the fetch doesn't get a short in
+        let validation = engine.validate_entry(&token, Direction::Short);
+        assert_eq!(validation.first_failure().unwrap().gate_name, "borrow_availability");
+        assert!(engine.reserve_entry(&token, Direction::Short, Decimal::new(1_000, 0)).is_err());
+
+        engine.refresh_borrow_quote(&token.token_address).await.unwrap();
+        assert!(engine.validate_entry(&token, Direction::Short).all_passed);
+
+        // A quote past its TTL no longer backs an entry
+        let later = start + chrono::Duration::seconds(BORROW_QUOTE_TTL_SECONDS + 1);
+        engine.set_evaluation_time(Some(later));
+        let validation = engine.validate_entry(&TokenSnapshot { snapshot_timestamp: later, ..token }, Direction::Short);
+        assert_eq!(validation.first_failure().unwrap().gate_name, "borrow_availability");
+    }
+
+    #[tokio::test]
+    async fn test_borrow_quotes_are_bounded() {
+        let provider = (0..MAX_BORROW_QUOTES + 10).fold(StaticBorrowProvider::default(), |provider, i| {
+            provider.with_token(&format!("0x{:040x}", i), create_test_borrow(1_000_000, 96))
+        });
+        let mut engine = borrow_engine(provider);
+        let start = Utc::now();
+        for i in 0..MAX_BORROW_QUOTES + 10 {
+            engine.set_evaluation_time(Some(start + chrono::Duration::milliseconds(i as i64)));
+            engine.refresh_borrow_quote(&format!("0x{:040x}", i)).await.unwrap();
+        }
+        assert_eq!(engine.borrow_quotes.len(), MAX_BORROW_QUOTES);
+        assert!(engine.borrow_rate(&format!("0x{:040x}", 0)).is_none());
+
+        // Stale quotes go on the next refresh
+        engine.set_evaluation_time(Some(start + chrono::Duration::seconds(BORROW_QUOTE_TTL_SECONDS * 2)));
+        engine.refresh_borrow_quote(&format!("0x{:040x}", 0)).await.unwrap();
+        assert_eq!(engine.borrow_quotes.len(), 1);
+    }
+
+    #[test]
+    fn test_correlation_tracker_moving_covariance() {
+        let mut tracker = CorrelationTracker::default();
+        let alpha = Decimal::new(5, 1);
+        tracker.update("A", "B", Decimal::new(2, 2), Decimal::new(4, 2), alpha);
+        tracker.update("B", "A", Decimal::new(-2, 2), Decimal::new(-1, 2), alpha);
+
+        // Pair order doesn't matter; A's returns stay A's
+        let moments = tracker.moments("B", "A").unwrap();
+        assert_eq!(moments.covariance, Decimal::new(3, 4)); // 0.5 * 0.0002 + 0.5 * 0.0004
+        assert_eq!(moments.variance_a, Decimal::new(15, 5)); // 0.5 * 0.0001 + 0.5 * 0.0002
+        assert_eq!(moments.variance_b, Decimal::new(6, 4)); // 0.5 * 0.0004 + 0.5 * 0.0008
+        assert_eq!(moments.samples, 2);
+        // B moved exactly twice A both times
+        assert_eq!(tracker.correlation("A", "B"), Some(Decimal::ONE));
+        assert!(tracker.correlation("A", "C").is_none());
+    }
+
+    #[test]
+    fn test_cross_correlation_gate_blocks_correlated_long() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token_for = |address: &str| TokenSnapshot { token_address: address.to_string(), ..create_test_token() };
+        let (held, twin, hedge) = (token_for("0xheld"), token_for("0xtwin"), token_for("0xhedge"));
+        engine.portfolio.long_book.positions.push(create_test_position(&held, Direction::Long, Utc::now()));
+        engine.portfolio.short_book.positions.push(create_test_position(&twin, Direction::Short, Utc::now()));
+        engine.portfolio.short_book.positions.push(create_test_position(&hedge, Direction::Short, Utc::now()));
+
+        // The twin moves with the held long, the hedge against it
+        for step in 0..20 {
+            let price = Decimal::new(if step % 2 == 0 { 100 } else { 104 }, 0);
+            let mirrored = Decimal::new(204, 0) - price;
+            for position in engine.portfolio.long_book.positions.iter_mut().chain(&mut engine.portfolio.short_book.positions) {
+                position.current_price = if position.token_address == "0xhedge" { mirrored } else { price };
+            }
+            engine.tick();
+        }
+        assert!(engine.correlations.correlation("0xheld", "0xtwin").unwrap() > Decimal::new(99, 2));
+        assert!(engine.correlations.correlation("0xheld", "0xhedge").unwrap() < Decimal::new(-99, 2));
+
+        // Take the shorts off so only the correlation gate stands in the way
+        engine.portfolio.short_book.positions.clear();
+        let rejected = engine.validate_entry(&twin, Direction::Long);
+        assert!(!rejected.all_passed);
+        let gate = rejected.gates.last().unwrap();
+        assert_eq!((gate.gate_name.as_str(), gate.result), ("cross_correlation", GateResult::Failed));
+        assert!(engine.validate_entry(&hedge, Direction::Long).all_passed);
+        assert!(engine.validate_entry(&twin, Direction::Short).all_passed);
+
+        let diagnostic = engine.dry_run_validate_entry(&twin, Direction::Long).diagnostics
+            .into_iter()
+            .find(|d| d.gate_name == "cross_correlation")
+            .unwrap();
+        assert_eq!(diagnostic.result, Some(GateResult::Failed));
+        assert!(diagnostic.observed.ends_with("with 0xheld"));
+    }
+
+    #[test]
+    fn test_cross_correlation_gate_tracks_unheld_candidate() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token_for = |address: &str| TokenSnapshot { token_address: address.to_string(), ..create_test_token() };
+        let (held, candidate) = (token_for("0xheld"), token_for("0xcandidate"));
+        engine.portfolio.long_book.positions.push(create_test_position(&held, Direction::Long, Utc::now()));
+
+        // The candidate is only watched, never held, and moves with the held long
+        for step in 0..20 {
+            let price = Decimal::new(if step % 2 == 0 { 100 } else { 104 }, 0);
+            engine.portfolio.long_book.positions[0].current_price = price;
+            engine.observe_token("0xcandidate", price);
+            engine.tick();
+        }
+        assert!(engine.correlations.correlation("0xheld", "0xcandidate").unwrap() > Decimal::new(99, 2));
+
+        let rejected = engine.validate_entry(&candidate, Direction::Long);
+        let gate = rejected.gates.last().unwrap();
+        assert_eq!((gate.gate_name.as_str(), gate.result), ("cross_correlation", GateResult::Failed));
+    }
+
+    #[test]
+    fn test_correlation_pairs_evict_least_recently_updated() {
+        let mut tracker = CorrelationTracker::default();
+        let alpha = Decimal::new(5, 1);
+        let held = BTreeMap::from([("0xheld".to_string(), Decimal::new(100, 0))]);
+        let watched: BTreeMap<String, Decimal> =
+            (0..MAX_CORRELATION_PAIRS).map(|i| (format!("0x{i:06}"), Decimal::new(50, 0))).collect();
+        tracker.observe(&held, &watched, alpha);
+        let moved = |prices: &BTreeMap<String, Decimal>| prices.iter().map(|(t, p)| (t.clone(), *p + Decimal::ONE)).collect();
+        tracker.observe(&moved(&held), &moved(&watched), alpha);
+        assert_eq!(tracker.pair_count(), MAX_CORRELATION_PAIRS);
+
+        // Two fresh tokens push out the two pairs updated first
+        let fresh = BTreeMap::from([("0xnew_a".to_string(), Decimal::new(10, 0)), ("0xnew_b".to_string(), Decimal::new(10, 0))]);
+        let mut both = moved(&held);
+        tracker.observe(&both, &fresh, alpha);
+        both = moved(&both);
+        tracker.observe(&both, &moved(&fresh), alpha);
+        assert_eq!(tracker.pair_count(), MAX_CORRELATION_PAIRS);
+        assert!(tracker.moments("0xheld", "0x000000").is_none());
+        assert!(tracker.moments("0xheld", "0x000001").is_none());
+        assert!(tracker.moments("0xheld", "0x000002").is_some());
+        assert!(tracker.moments("0xheld", "0xnew_b").is_some());
+    }
+
+    #[test]
+    fn test_every_validation_is_audited() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let mut thin = create_test_token();
+        thin.liquidity_usd = Decimal::new(100_000, 0);
+
+        assert!(engine.validate_and_record_entry(&token, Direction::Long).all_passed);
+        let rejected = engine.validate_and_record_entry(&thin, Direction::Long);
+        assert!(!rejected.all_passed);
+
+        assert_eq!(engine.validation_audit_log.len(), 2);
+        assert_eq!(engine.rejection_logs.len(), 1);
+
+        let passed = engine.validation_audit_log.get(0).unwrap();
+        assert!(passed.all_passed);
+        assert_eq!(passed.pass_count, passed.gate_count);
+        assert_eq!((passed.fail_count, passed.timeout_count), (0, 0));
+        assert_eq!(passed.gates.as_array().unwrap().len() as u32, passed.gate_count);
+
+        let failed = engine.validation_audit_log.get(1).unwrap();
+        assert_eq!(failed.validation_id, rejected.validation_id);
+        assert_eq!((failed.gate_count, failed.pass_count, failed.fail_count), (3, 2, 1));
+        assert_eq!(failed.gates[2]["gate_name"], "liquidity_range");
+        assert_eq!(failed.gates[2]["result"], "Failed");
+    }
+
+    #[test]
+    fn test_validation_audit_log_is_bounded_and_spills_the_oldest() {
+        let sink = Arc::new(CollectingSink::default());
+        let mut config = StrikeBoxConfig::default();
+        config.log_retention.max_validation_records = 1;
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0)).with_audit_sink(sink.clone());
+        let first = engine.validate_and_record_entry(&create_test_token(), Direction::Long);
+        let second = engine.validate_and_record_entry(&create_test_token(), Direction::Long);
+
+        assert_eq!(engine.validation_audit_log.len(), 1);
+        assert_eq!(engine.validation_audit_log.last().unwrap().validation_id, second.validation_id);
+        let spilled = sink.records.lock().unwrap();
+        assert!(matches!(&spilled[..], [AuditRecord::Validation(record)] if record.validation_id == first.validation_id));
+    }
+
+    #[test]
+    fn test_command_audit_log_is_bounded_and_spills_the_oldest() {
+        let sink = Arc::new(CollectingSink::default());
+        let mut config = StrikeBoxConfig::default();
+        config.log_retention.max_command_records = 1;
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0)).with_audit_sink(sink.clone());
+        let now = Utc::now();
+        engine.handle_command_at(command_request("alice", OperationalCommand::Status, None), now);
+        engine.handle_command_at(command_request("bob", OperationalCommand::Status, None), now);
+
+        assert_eq!(engine.command_audit_log.len(), 1);
+        assert_eq!(engine.command_audit_log.last().unwrap().issued_by, "bob");
+        let spilled = sink.records.lock().unwrap();
+        assert!(matches!(&spilled[..], [AuditRecord::Command(record)] if record.issued_by == "alice"));
+    }
+
+    #[test]
+    fn test_config_audit_is_bounded_and_spills_the_oldest() {
+        let sink = Arc::new(CollectingSink::default());
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0)).with_audit_sink(sink.clone());
+        let mut retained = StrikeBoxConfig::default();
+        retained.log_retention.max_config_reports = 1;
+        assert!(engine.apply_config_update(retained.clone(), false).applied);
+        let mut invalid = retained;
+        invalid.log_retention.max_config_reports = 0;
+        assert!(!engine.apply_config_update(invalid, false).applied);
+
+        assert_eq!(engine.config_audit.len(), 1);
+        assert!(!engine.config_audit.last().unwrap().applied);
+        let spilled = sink.records.lock().unwrap();
+        assert!(matches!(&spilled[..], [AuditRecord::ConfigUpdate(report)] if report.applied));
+    }
+
+    #[test]
+    fn test_capital_events_are_bounded_and_spill_the_oldest() {
+        let sink = Arc::new(CollectingSink::default());
+        let mut config = StrikeBoxConfig::default();
+        config.log_retention.max_capital_events = 1;
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0)).with_audit_sink(sink.clone());
+        engine.deposit_capital(Decimal::new(1_000, 0)).unwrap();
+        engine.deposit_capital(Decimal::new(2_000, 0)).unwrap();
+
+        assert_eq!(engine.capital_events.len(), 1);
+        assert_eq!(engine.capital_events.last().unwrap().amount_usd, Decimal::new(2_000, 0));
+        let spilled = sink.records.lock().unwrap();
+        assert!(matches!(&spilled[..], [AuditRecord::CapitalEvent(event)] if event.amount_usd == Decimal::new(1_000, 0)));
+    }
+
+    #[test]
+    fn test_stale_snapshots_fail_data_staleness() {
+        let engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let mut token = create_test_token();
+        token.snapshot_timestamp = Utc::now() - chrono::Duration::seconds(10);
+        assert!(engine.validate_entry(&token, Direction::Long).all_passed);
+
+        token.snapshot_timestamp = Utc::now() - chrono::Duration::seconds(31);
+        let validation = engine.validate_entry(&token, Direction::Long);
+        assert!(!validation.all_passed);
+        let last = validation.gates.last().unwrap();
+        assert_eq!((last.gate_name.as_str(), last.result), ("data_staleness", GateResult::Failed));
+    }
+
+    #[test]
+    fn test_custom_gate_blocks_entries_and_reaches_rejection_analytics() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let default_gates: Vec<String> =
+            engine.validate_entry(&token, Direction::Long).gates.into_iter().map(|g| g.gate_name).collect();
+
+        let blacklist = |ctx: &gates::EvaluationContext<'_>| {
+            gates::GateVerdict::Fail(format!("{} is blacklisted", ctx.token.token_symbol))
+        };
+        engine.gate_registry.register_gate("blacklist", 250, Box::new(blacklist)).unwrap();
+        assert_eq!(
+            engine.gate_registry.register_gate("blacklist", 0, Box::new(blacklist)),
+            Err(gates::GateRegistryError::DuplicateGate("blacklist".to_string()))
+        );
+
+        let validation = engine.validate_and_record_entry(&token, Direction::Long);
+        assert!(!validation.all_passed);
+        let names: Vec<&str> = validation.gates.iter().map(|g| g.gate_name.as_str()).collect();
+        assert_eq!(names, vec!["system_state", "data_staleness", "blacklist"]);
+        assert_eq!(validation.first_failure().unwrap().reason.as_deref(), Some("TEST is blacklisted"));
+        let rejects = engine.rejects_report(PnlTimeframe::Today, Utc::now());
+        assert_eq!(rejects.by_gate.get("blacklist"), Some(&1));
+
+        // Moved last, the built-ins run as before ahead of it
+        let mut order: Vec<String> = gates::BUILT_IN_GATES.iter().map(|g| g.to_string()).collect();
+        order.push("blacklist".to_string());
+        engine.gate_registry.set_gate_order(order).unwrap();
+        let validation = engine.validate_entry(&token, Direction::Long);
+        let names: Vec<String> = validation.gates.into_iter().map(|g| g.gate_name).collect();
+        assert_eq!(names[..names.len() - 1], default_gates[..]);
+        assert_eq!(names.last().map(String::as_str), Some("blacklist"));
+        assert!(engine.gate_registry.set_gate_order(vec!["missing".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_disabled_gate_is_skipped() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let mut token = create_test_token();
+        token.token_age_hours = 6;
+        assert_eq!(
+            engine.validate_entry(&token, Direction::Long).first_failure().map(|g| g.gate_name.as_str()),
+            Some("token_age")
+        );
+
+        assert_eq!(engine.gate_registry.disable_gate("token_age"), Ok(true));
+        let validation = engine.validate_entry(&token, Direction::Long);
+        assert!(validation.all_passed);
+        let gate = validation.gates.iter().find(|g| g.gate_name == "token_age").unwrap();
+        assert_eq!(gate.result, GateResult::Skipped);
+        assert_eq!(validation.to_audit_record().skip_count, 1);
+
+        assert_eq!(engine.gate_registry.enable_gate("token_age"), Ok(true));
+        assert!(!engine.validate_entry(&token, Direction::Long).all_passed);
+        assert!(engine.gate_registry.disable_gate("sentiment").is_err());
+    }
+
+    #[test]
+    fn test_warmup_precomputes_and_refreshes_on_capital_change() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        assert!(!engine.is_warmed_up());
+        assert!(engine.computed_cache().is_none());
+
+        let result = engine.warmup();
+        assert!(result.is_complete());
+        assert_eq!(
+            result.succeeded,
+            vec![WarmupComputation::IdealSafetyScore, WarmupComputation::LiquidityTiers, WarmupComputation::ExposureBounds]
+        );
+        assert!(engine.is_warmed_up());
+        let cache = engine.computed_cache().unwrap();
+        assert!(cache.ideal_safety_score.as_ref().unwrap().total_score >= engine.config.safety_scoring.long_entry_min);
+        let top = &cache.liquidity_tiers.as_ref().unwrap()[0];
+        assert_eq!((top.max_position_pct, top.max_position_usd), (Decimal::new(2, 2), Decimal::new(2_000, 0)));
+        let bounds = cache.exposure_bounds.as_ref().unwrap();
+        assert_eq!(bounds.long_max_usd, Decimal::new(70_000, 0));
+        assert_eq!(bounds.net_min_usd, Decimal::new(-30_000, 0));
+
+        engine.deposit_capital(Decimal::new(100_000, 0)).unwrap();
+        let bounds = engine.computed_cache().unwrap().exposure_bounds.as_ref().unwrap();
+        assert_eq!(bounds.long_max_usd, Decimal::new(140_000, 0));
+
+        // Sizing and the net exposure gate read the warmed values
+        let token = TokenSnapshot { liquidity_usd: Decimal::new(950_000, 0), ..create_test_token() };
+        let (size, _) = engine.position_size_with_cap(&token, Direction::Long);
+        engine.computed_cache.as_mut().unwrap().liquidity_tiers.as_mut().unwrap()[0].max_position_usd = size / Decimal::TWO;
+        assert_eq!(engine.position_size_with_cap(&token, Direction::Long).0, size / Decimal::TWO);
+        assert!(engine.net_exposure_valid());
+        engine.computed_cache.as_mut().unwrap().exposure_bounds.as_mut().unwrap().net_min_usd = Decimal::ONE;
+        assert!(!engine.net_exposure_valid());
+
+        // An unreachable long minimum is reported but still leaves the engine warmed
+        let mut config = StrikeBoxConfig::default();
+        config.safety_scoring.long_entry_min = Decimal::new(2, 0);
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(100_000, 0));
+        let result = engine.warmup();
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, WarmupComputation::IdealSafetyScore);
+        assert!(engine.is_warmed_up());
+        assert!(engine.computed_cache().unwrap().ideal_safety_score.is_none());
+    }
+
+    #[test]
+    fn test_validation_cache_hits_until_snapshot_or_portfolio_changes() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0))
+            .with_validation_cache(chrono::Duration::seconds(60), VALIDATION_CACHE_CAPACITY);
+        let token = create_test_token();
+
+        let first = engine.validate_entry(&token, Direction::Long);
+        let second = engine.validate_entry(&token, Direction::Long);
+        assert!(first.all_passed && second.all_passed);
+        assert_ne!(first.validation_id, second.validation_id);
+        assert_eq!(second.gates.len(), first.gates.len());
+        let stats = engine.validation_cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+
+        let mut thinner = token.clone();
+        thinner.liquidity_usd = Decimal::new(600_000, 0);
+        assert!(engine.validate_entry(&thinner, Direction::Long).all_passed);
+        assert_eq!(engine.validation_cache_stats().unwrap().misses, 2);
+
+        engine.book_position(create_test_position(&token, Direction::Long, Utc::now()));
+        let stacked = engine.validate_entry(&token, Direction::Long);
+        assert!(!stacked.all_passed);
+        assert_eq!(stacked.gates.last().unwrap().gate_name, "no_stacking");
+        assert_eq!(engine.validation_cache_stats().unwrap(), ValidationCacheStats { hits: 1, misses: 3, evictions: 0 });
+
+        // Simulation never reads or fills the cache
+        engine.simulate_entry_impact(&token, Direction::Long, None);
+        assert_eq!(engine.validation_cache_stats().unwrap().misses, 3);
+
+        // Neither does a pinned clock, which could otherwise reuse another bar's result
+        engine.set_evaluation_time(Some(Utc::now()));
+        engine.validate_entry(&thinner, Direction::Long);
+        engine.validate_entry(&thinner, Direction::Long);
+        assert_eq!(engine.validation_cache_stats().unwrap(), ValidationCacheStats { hits: 1, misses: 3, evictions: 0 });
+        engine.set_evaluation_time(None);
+
+        // Applying a config update changes the key
+        engine.validate_entry(&thinner, Direction::Long);
+        let mut stricter = engine.config.clone();
+        stricter.token_validation.liquidity_min_usd = Decimal::new(700_000, 0);
+        assert!(engine.apply_config_update(stricter, false).applied);
+        assert!(!engine.validate_entry(&thinner, Direction::Long).all_passed);
+        assert_eq!(engine.validation_cache_stats().unwrap().misses, 5);
+
+        // Only an identical key hits; a new config context empties the cache
+        let now = Utc::now();
+        let key = |context: &[u8], entry: &[u8]| ValidationCacheKey { context: context.to_vec(), entry: entry.to_vec() };
+        let mut cache = ValidationCache::new(chrono::Duration::seconds(60), 8);
+        cache.insert(key(b"config", b"token-a"), first.clone(), now);
+        assert!(cache.get(&key(b"config", b"token-b"), now).is_none());
+        assert!(cache.get(&key(b"config", b"token-a"), now).is_some());
+        assert!(cache.get(&key(b"other config", b"token-a"), now).is_none());
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats(), ValidationCacheStats { hits: 1, misses: 2, evictions: 1 });
+        assert!(StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::ONE).validation_cache_stats().is_none());
+    }
+
+    #[test]
+    fn test_stop_loss_calculations() {
+        let config = StopLossConfig::default();
+        let entry_price = Decimal::new(100, 0);
+        let long_stop = config.long_stop_price(entry_price, Decimal::new(60, 2));
+        assert_eq!(long_stop, Decimal::new(95, 0));
+        let short_stop = config.short_stop_price(entry_price);
+        assert_eq!(short_stop, Decimal::new(108, 0));
+    }
+
+    #[test]
+    fn test_merge_layers_env_overrides_over_base() {
+        let base = StrikeBoxConfig::default();
+        let vars: BTreeMap<String, String> = [
+            ("STRIKE_BOX__STOP_LOSS", r#"{"long_default_pct": "0.06"}"#),
+            ("STRIKE_BOX__STOP_LOSS__SHORT_FIXED_PCT", "0.09"),
+            ("STRIKE_BOX__CORRELATION__ALPHA", "0.2"),
+            ("STRIKE_BOX_BOOTSTRAP", "/var/lib/strike_box/bootstrap.json"), // Not an override
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+        let overrides = PartialStrikeBoxConfig::from_vars(&base, &vars).unwrap();
+        assert!(overrides.take_profit.is_none());
+        assert!(overrides.bootstrap.is_none());
+
+        let merged = StrikeBoxConfig::merge(&base, &overrides);
+        assert_eq!(merged.stop_loss.long_default_pct, Decimal::new(6, 2));
+        assert_eq!(merged.stop_loss.short_fixed_pct, Decimal::new(9, 2));
+        assert_eq!(merged.correlation.alpha, Decimal::new(2, 1));
+        assert_eq!(merged.take_profit.long_levels, base.take_profit.long_levels);
+        let paths: Vec<String> = base.diff(&merged).into_iter().map(|c| c.path).collect();
+        assert_eq!(paths, vec!["correlation.alpha", "stop_loss.long_default_pct", "stop_loss.short_fixed_pct"]);
+
+        // An empty layer is the base
+        assert!(base.diff(&StrikeBoxConfig::merge(&base, &PartialStrikeBoxConfig::default())).is_empty());
+    }
+
+    #[test]
+    fn test_env_overrides_report_every_bad_variable() {
+        let base = StrikeBoxConfig::default();
+        let vars: BTreeMap<String, String> = [
+            ("STRIKE_BOX__CORRELATION", "not json"),
+            ("STRIKE_BOX__STOP_LOSS", r#"{"long_default_pctt": "0.06"}"#),
+            ("STRIKE_BOX__TAKE_PROFIT__LONG_TP1_PCT", "0.1"),
+            ("STRIKE_BOX__RISK_CONTROLER", "{}"),
+            ("STRIKE_BOX__POSITION_SIZING__LONG_BOOK_MAX_POSITIONS", "many"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+        let errors = PartialStrikeBoxConfig::from_vars(&base, &vars).unwrap_err();
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.contains("unknown field stop_loss.long_default_pctt")));
+        assert!(errors.iter().any(|e| e.contains("unknown field long_tp1_pct")));
+        assert!(errors.iter().any(|e| e.contains("unknown config section risk_controler")));
+    }
+
+    fn volatility_scaled_engine(max_pct: Decimal) -> StrikeBoxEngine {
+        let mut config = StrikeBoxConfig::default();
+        config.stop_loss.mode = StopMode::VolatilityScaled {
+            atr_multiplier: Decimal::TWO,
+            atr_window: 10,
+            min_pct: Decimal::new(5, 3),
+            max_pct,
+        };
+        assert!(config.validate().is_ok());
+        StrikeBoxEngine::new(config, Decimal::new(100_000, 0))
+    }
+
+    #[test]
+    fn test_volatility_scaled_stop_is_narrow_for_calm_series() {
+        let mut engine = volatility_scaled_engine(Decimal::new(9, 2));
+        for i in 0..12 {
+            let price = if i % 2 == 0 { Decimal::new(100, 0) } else { Decimal::new(1004, 1) };
+            engine.observe_token("0xcalm", price);
+        }
+
+        let (stop, basis) = engine.long_stop("0xcalm", Decimal::new(100, 0), Decimal::new(80, 2));
+        match basis {
+            StopBasis::VolatilityScaled { atr_pct, stop_pct } => {
+                assert!(atr_pct > Decimal::new(39, 4) && atr_pct < Decimal::new(4, 3), "atr {}", atr_pct);
+                assert_eq!(stop_pct, Decimal::TWO * atr_pct);
+            }
+            other => panic!("expected a volatility-scaled stop, got {:?}", other),
+        }
+        // Tighter than either safety tier, rather than the 8% the high safety score would get
+        assert!(stop > Decimal::new(99, 0), "stop {}", stop);
+    }
+
+    #[test]
+    fn test_volatility_scaled_stop_clamps_for_choppy_series() {
+        let mut engine = volatility_scaled_engine(Decimal::new(9, 2));
+        for i in 0..12 {
+            let price = if i % 2 == 0 { Decimal::new(100, 0) } else { Decimal::new(110, 0) };
+            engine.observe_token("0xchop", price);
+        }
+        let (stop, basis) = engine.long_stop("0xchop", Decimal::new(100, 0), Decimal::new(50, 2));
+        assert_eq!(stop, Decimal::new(91, 0));
+        assert!(matches!(basis, StopBasis::VolatilityScaled { atr_pct, .. } if atr_pct > Decimal::new(9, 2)));
+
+        // Position::open places the same stop from the same prices
+        let token = create_test_token();
+        let validation = engine.validate_entry(&token, Direction::Long);
+        let safety = SafetyScore { total_score: Decimal::new(50, 2), ..test_safety(&token) };
+        let recent_prices = engine.price_history.prices("0xchop");
+        let position = Position::open(&token, &validation, Decimal::new(100, 0), Decimal::new(1_000, 0), safety, &recent_prices, &engine.config)
+            .unwrap();
+        assert_eq!(position.stop_loss_price, stop);
+
+        // A max_pct past the hard floor still stops at the floor
+        let mut engine = volatility_scaled_engine(Decimal::new(20, 2));
+        for i in 0..12 {
+            let price = if i % 2 == 0 { Decimal::new(100, 0) } else { Decimal::new(110, 0) };
+            engine.observe_token("0xchop", price);
+        }
+        let (stop, basis) = engine.long_stop("0xchop", Decimal::new(100, 0), Decimal::new(50, 2));
+        assert_eq!(stop, Decimal::new(90, 0));
+        assert_eq!(basis.stop_pct(), engine.config.stop_loss.long_hard_floor_pct);
+    }
+
+    #[test]
+    fn test_price_history_evicts_the_stalest_token() {
+        let mut history = PriceHistory::default();
+        for i in 0..PRICE_HISTORY_MAX_TOKENS {
+            history.record(&format!("0x{}", i), Decimal::ONE);
+        }
+        history.last_observed.insert("0x7".to_string(), Utc::now() - chrono::Duration::hours(1));
+
+        history.record("0x7", Decimal::TWO); // Known tokens never evict
+        history.last_observed.insert("0x7".to_string(), Utc::now() - chrono::Duration::hours(1));
+        assert_eq!(history.token_count(), PRICE_HISTORY_MAX_TOKENS);
+        history.record("0xnew", Decimal::ONE);
+        assert_eq!(history.token_count(), PRICE_HISTORY_MAX_TOKENS);
+        assert!(history.prices("0x7").is_empty());
+        assert_eq!(history.prices("0xnew"), vec![Decimal::ONE]);
+    }
+
+    #[test]
+    fn test_volatility_scaled_stop_falls_back_without_history() {
+        let mut engine = volatility_scaled_engine(Decimal::new(9, 2));
+        for _ in 0..4 {
+            engine.observe_token("0xnew", Decimal::new(100, 0));
+        }
+        let token = create_test_token();
+        let validation = engine.validate_entry(&token, Direction::Long);
+        let safety = SafetyScore { total_score: Decimal::new(60, 2), ..test_safety(&token) };
+        let (position, basis) = engine
+            .open_position(&token, &validation, Decimal::new(100, 0), Decimal::new(1_000, 0), safety)
+            .unwrap();
+        assert_eq!(
+            basis,
+            Some(StopBasis::InsufficientHistory {
+                observed_returns: 0,
+                required_returns: 10,
+                stop_pct: Decimal::new(5, 2),
+            })
+        );
+        assert_eq!(position.stop_loss_price, Decimal::new(95, 0));
+
+        let (_, watched) = engine.long_stop("0xnew", Decimal::new(100, 0), Decimal::new(60, 2));
+        assert!(matches!(watched, StopBasis::InsufficientHistory { observed_returns: 3, .. }));
+
+        let mut log = create_test_entry(&position);
+        log.stop_basis = basis;
+        engine.record_entry(log);
+        assert!(matches!(
+            engine.entry_logs.iter().last().and_then(|l| l.stop_basis.as_ref()),
+            Some(StopBasis::InsufficientHistory { .. })
+        ));
+    }
+
+    #[test]
+    fn test_take_profit_calculations() {
+        let config = TakeProfitConfig::default();
+        let entry_price = Decimal::new(100, 0);
+        let long_tps = config.long_tp_prices(entry_price);
+        assert_eq!(long_tps[0], Decimal::new(115, 0));
+        assert_eq!(long_tps[1], Decimal::new(130, 0));
+        assert_eq!(long_tps[2], Decimal::new(150, 0));
+    }
+
+    #[test]
+    fn test_custom_ladders_exit_exactly_full_position() {
+        let pct = |n| Decimal::new(n, 2);
+        let config = TakeProfitConfig::new(
+            vec![
+                TpLevel::new(pct(5), pct(10)),
+                TpLevel::new(pct(10), pct(15)),
+                TpLevel::new(pct(20), pct(20)),
+                TpLevel::new(pct(40), pct(25)),
+                TpLevel::new(pct(80), pct(30)),
+            ],
+            vec![TpLevel::new(pct(10), pct(60)), TpLevel::new(pct(25), pct(40))],
+        )
+        .unwrap();
+
+        let token = create_test_token();
+        for (direction, final_price, levels) in [
+            (Direction::Long, Decimal::new(200, 0), 5),
+            (Direction::Short, Decimal::new(50, 0), 2),
+        ] {
+            let mut position = create_test_position(&token, direction, Utc::now());
+            position.take_profit_targets = config.targets(direction, position.entry_price);
+            assert_eq!(position.take_profit_targets.len(), levels);
+
+            position.mark_to_market(final_price, position.liquidity_at_entry);
+            let mut exited = Decimal::ZERO;
+            while let Some(level) = position.check_take_profits() {
+                exited += position.apply_take_profit(level).unwrap();
+            }
+            assert_eq!(exited, Decimal::ONE);
+            assert_eq!(position.remaining_size_pct, Decimal::ZERO);
+            assert_eq!(position.status, PositionStatus::Closed);
+            assert!(position.take_profit_targets.iter().all(|t| t.hit));
+            assert_eq!(position.apply_take_profit(0), None);
+        }
+
+        let unordered = TakeProfitConfig::new(
+            vec![TpLevel::new(pct(20), pct(50)), TpLevel::new(pct(10), pct(50))],
+            vec![TpLevel::new(pct(10), pct(90))],
+        )
+        .unwrap_err();
+        assert_eq!(unordered.len(), 2);
+    }
+
+    #[test]
+    fn test_config_without_log_retention_loads_with_defaults() {
+        let mut config = serde_json::to_value(StrikeBoxConfig::default()).unwrap();
+        config.as_object_mut().unwrap().remove("log_retention");
+        let loaded: StrikeBoxConfig = serde_json::from_value(config).unwrap();
+        assert_eq!(loaded.log_retention.max_rejection_logs, LogRetentionConfig::default().max_rejection_logs);
+    }
+
+    #[test]
+    fn test_config_without_liquidity_imbalance_weight_loads() {
+        let mut config = serde_json::to_value(StrikeBoxConfig::default()).unwrap();
+        config["safety_scoring"].as_object_mut().unwrap().remove("liquidity_imbalance_weight");
+        let loaded: StrikeBoxConfig = serde_json::from_value(config).unwrap();
+        assert_eq!(loaded.safety_scoring.liquidity_imbalance_weight, Decimal::ZERO);
+        assert_eq!(loaded.safety_scoring.contract_weight, SafetyScoreConfig::default().contract_weight);
+    }
+
+    #[test]
+    fn test_legacy_take_profit_config_still_loads() {
+        let legacy = r#"{
+            "long_tp1_pct": "0.15", "long_tp1_exit_pct": "0.33",
+            "long_tp2_pct": "0.30", "long_tp2_exit_pct": "0.33",
+            "long_tp3_pct": "0.50", "long_tp3_exit_pct": "0.34",
+            "short_tp1_pct": "0.10", "short_tp1_exit_pct": "0.33",
+            "short_tp2_pct": "0.20", "short_tp2_exit_pct": "0.33",
+            "short_tp3_pct": "0.30", "short_tp3_exit_pct": "0.34"
+        }"#;
+        let config: TakeProfitConfig = serde_json::from_str(legacy).unwrap();
+        let default = TakeProfitConfig::default();
+        assert_eq!(config.long_levels, default.long_levels);
+        assert_eq!(config.short_levels, default.short_levels);
+
+        // Re-serialized configs use the ladder format and round-trip
+        let ladder: TakeProfitConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(ladder.long_levels, default.long_levels);
+
+        // Entry logs written with a fixed three-price array still deserialize
+        let mut log = serde_json::to_value(EntryLog {
+            execution_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            token_address: "0xtest".to_string(),
+            token_symbol: "TEST".to_string(),
+            direction: Direction::Long,
+            entry_price: Decimal::new(100, 0),
+            position_size_tokens: Decimal::new(100, 0),
+            position_size_usd: Decimal::new(10_000, 0),
+            liquidity_depth_usd: Decimal::new(500_000, 0),
+            safety_score: Decimal::new(80, 2),
+            holder_count: 1_000,
+            stop_loss_price: Decimal::new(95, 0),
+            take_profit_prices: Vec::new(),
+            risk_approval_id: Uuid::new_v4(),
+            latency_ms: 10,
+            slippage_bps: Decimal::new(5, 0),
+            stop_basis: Some(StopBasis::SafetyTiered { stop_pct: Decimal::new(5, 2) }),
+        })
+        .unwrap();
+        log["take_profit_prices"] = serde_json::json!(["115", "130", "150"]);
+        log.as_object_mut().unwrap().remove("stop_basis");
+        let log: EntryLog = serde_json::from_value(log).unwrap();
+        assert_eq!(log.take_profit_prices[2], Decimal::new(150, 0));
+        assert_eq!(log.stop_basis, None);
+    }
+
+    #[test]
+    fn test_index_drop_beyond_crash_trigger_halts() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        let hour = std::time::Duration::from_secs(3_600);
+
+        let state = engine.ingest_market_event(MarketEvent::IndexDrop { pct: Decimal::new(8, 2), window: hour });
+        assert_eq!(state, SystemState::Active);
+
+        let state = engine.ingest_market_event(MarketEvent::IndexDrop { pct: Decimal::new(16, 2), window: hour });
+        assert_eq!(state, SystemState::EmergencyHalt);
+        assert_eq!(engine.market_events.len(), 2);
+        assert_eq!(engine.market_events.get(1).unwrap().state_before, SystemState::Active);
+        assert_eq!(engine.market_events.get(1).unwrap().state_after, SystemState::EmergencyHalt);
+    }
+
+    #[test]
+    fn test_execution_report_slippage_sign_by_direction() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let long = create_test_position(&token, Direction::Long, Utc::now());
+        let short = create_test_position(&token, Direction::Short, Utc::now());
+        engine.record_entry(create_test_entry(&long));
+        engine.record_entry(create_test_entry(&short));
+        engine.portfolio.long_book.positions.push(long.clone());
+        engine.portfolio.short_book.positions.push(short.clone());
+
+        // Buying the long 0.05% above the quote is adverse; selling the short below it too.
+        // Both stay inside the tolerance, so neither position is rebooked.
+        let attached = engine.attach_execution_report(
+            create_test_report(long.execution_id, Decimal::new(100, 0), Decimal::new(10005, 2)),
+        );
+        assert_eq!(attached, ReportAttachment::Attached { leg: ExecutionLeg::Entry, position_adjusted: false });
+        let attached = engine.attach_execution_report(
+            create_test_report(short.execution_id, Decimal::new(100, 0), Decimal::new(9995, 2)),
+        );
+        assert_eq!(attached, ReportAttachment::Attached { leg: ExecutionLeg::Entry, position_adjusted: false });
+        assert_eq!(engine.entry_logs.get(0).unwrap().slippage_bps, Decimal::new(5, 0));
+        assert_eq!(engine.entry_logs.get(1).unwrap().slippage_bps, Decimal::new(5, 0));
+        assert_eq!(engine.entry_logs.get(0).unwrap().latency_ms, 120);
+
+        // Closing the long sells: a fill above the quote is price improvement
+        let exit_id = Uuid::new_v4();
+        let mut exit = create_test_exit(long.execution_id, Decimal::new(1_000, 0), 3_600);
+        exit.exit_execution_id = Some(exit_id);
+        engine.record_exit(exit);
+        engine.attach_execution_report(create_test_report(exit_id, Decimal::new(110, 0), Decimal::new(11022, 2)));
+        assert_eq!(engine.exit_logs.get(0).unwrap().slippage_bps, Decimal::new(-20, 0));
+        assert_eq!(engine.portfolio.total_slippage_cost_usd, Decimal::new(-22, 0));
+
+        // Closing the short buys: a fill above the quote is adverse
+        let exit_id = Uuid::new_v4();
+        let mut exit = create_test_exit(short.execution_id, Decimal::new(-1_000, 0), 3_600);
+        exit.exit_execution_id = Some(exit_id);
+        engine.record_exit(exit);
+        engine.attach_execution_report(create_test_report(exit_id, Decimal::new(110, 0), Decimal::new(11022, 2)));
+        assert_eq!(engine.exit_logs.get(1).unwrap().slippage_bps, Decimal::new(20, 0));
+        assert!(engine.fill_adjustments.is_empty());
+
+        // Each exit's reported slippage lands on its book's costs: 100 tokens at 110, ∓20bps
+        assert_eq!(engine.portfolio.long_book.costs_usd, Decimal::new(-22, 0));
+        assert_eq!(engine.portfolio.short_book.costs_usd, Decimal::new(22, 0));
+        assert_eq!(engine.portfolio.total_slippage_cost_usd, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_execution_report_before_entry_log_is_queued() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0))
+            .with_execution_report_grace(chrono::Duration::seconds(30));
+        let token = create_test_token();
+        let position = create_test_position(&token, Direction::Long, Utc::now());
+
+        // The fill lands 1% worse and 10% short of the booked size, before the entry is logged
+        let mut report = create_test_report(position.execution_id, Decimal::new(100, 0), Decimal::new(101, 0));
+        report.filled_size = Decimal::new(90, 0);
+        assert_eq!(engine.attach_execution_report(report), ReportAttachment::Queued);
+        assert_eq!(engine.pending_execution_report_count(), 1);
+
+        engine.book_position(position.clone());
+        engine.record_entry(create_test_entry(&position));
+        assert_eq!(engine.pending_execution_report_count(), 0);
+        assert_eq!(engine.entry_logs.get(0).unwrap().slippage_bps, Decimal::new(100, 0));
+
+        let booked = &engine.portfolio.long_book.positions[0];
+        assert_eq!(booked.entry_price, Decimal::new(101, 0));
+        assert_eq!(booked.position_size_tokens, Decimal::new(90, 0));
+        assert_eq!(booked.position_size_usd, Decimal::new(9_090, 0));
+        assert_eq!(engine.portfolio.long_book.total_allocation_usd, Decimal::new(9_090, 0));
+        // Stops and targets keep their distance from the achieved entry
+        let shift = Decimal::new(101, 2);
+        assert_eq!(booked.stop_loss_price, position.stop_loss_price * shift);
+        assert_eq!(booked.take_profit_prices(), position.take_profit_prices().iter().map(|p| p * shift).collect::<Vec<_>>());
+        assert_eq!(engine.fill_adjustments.len(), 1);
+        assert_eq!(engine.fill_adjustments.first().unwrap().old_entry_price, Decimal::new(100, 0));
+
+        // A report nobody claims is orphaned once the grace period passes
+        let mut engine = engine.with_execution_report_grace(chrono::Duration::zero());
+        engine.attach_execution_report(create_test_report(Uuid::new_v4(), Decimal::ONE, Decimal::ONE));
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        engine.tick();
+        assert_eq!(engine.pending_execution_report_count(), 0);
+        assert_eq!(engine.orphaned_execution_reports.len(), 1);
+    }
+
+    #[test]
+    fn test_operational_commands() {
+        let config = StrikeBoxConfig::default();
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
+        let response = engine.execute_command(OperationalCommand::PauseAll);
+        assert!(response.success);
+        assert_eq!(engine.portfolio.state, SystemState::PausedAll);
+    }
+
+    #[test]
+    fn test_consecutive_failure_halt_recovers_through_probe() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+
+        // An operator halt is not ours to undo
+        engine.emergency_close_all();
+        assert!(matches!(engine.attempt_recovery(&token).state, RecoveryState::RecoveryFailed(_)));
+
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        assert_eq!(engine.record_execution_failure(), SystemState::Active);
+        assert_eq!(engine.record_execution_failure(), SystemState::Active);
+        assert_eq!(engine.record_execution_failure(), SystemState::EmergencyHalt);
+        assert!(!engine.validate_entry(&token, Direction::Long).all_passed);
+
+        // A probe that fails its gates leaves the halt in place
+        let mut thin = create_test_token();
+        thin.liquidity_usd = Decimal::new(1_000, 0);
+        let result = engine.attempt_recovery(&thin);
+        assert!(matches!(result.state, RecoveryState::RecoveryFailed(ref reason) if reason.contains("probe failed")));
+        assert!(result.gates.iter().any(|g| g.result == GateResult::Failed));
+        assert_eq!(engine.portfolio.state, SystemState::EmergencyHalt);
+
+        let result = engine.attempt_recovery(&token);
+        assert_eq!(result.state, RecoveryState::RecoveryAttempted);
+        assert!(!result.gates.is_empty() && result.gates.iter().all(|g| g.result == GateResult::Passed));
+        assert_eq!(engine.portfolio.state, SystemState::Recovering);
+        assert_eq!(engine.portfolio.long_book.position_count(), 0);
+
+        // The next real entry completes the recovery
+        let validation = engine.validate_entry(&token, Direction::Long);
+        assert!(validation.all_passed);
+        let (position, _) = engine
+            .open_position(&token, &validation, Decimal::new(100, 0), Decimal::new(1_000, 0), test_safety(&token))
+            .unwrap();
+        engine.record_entry(create_test_entry(&position));
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+        assert_eq!(engine.recovery_state(), Some(&RecoveryState::RecoverySucceeded));
+        assert_eq!(engine.portfolio.consecutive_failures, 0);
+
+        // A later halt starts with no recovery on record
+        engine.emergency_close_all();
+        assert_eq!(engine.recovery_state(), None);
+
+        // Failing during probation halts again and says why
+        engine.portfolio.consecutive_failures = 3;
+        assert_eq!(engine.attempt_recovery(&token).state, RecoveryState::RecoveryAttempted);
+        assert_eq!(engine.record_execution_failure(), SystemState::EmergencyHalt);
+        assert!(matches!(engine.recovery_state(), Some(RecoveryState::RecoveryFailed(reason)) if reason.contains("during recovery")));
+    }
+
+    fn command_request(issued_by: &str, command: OperationalCommand, confirm_token: Option<String>) -> CommandRequest {
+        CommandRequest { id: Uuid::new_v4(), issued_by: issued_by.to_string(), command, confirm_token }
+    }
+
+    #[test]
+    fn test_command_bus_confirms_destructive_commands_once() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let now = Utc::now();
+
+        // First step hands back a token and changes nothing
+        let request = command_request("alice", OperationalCommand::CloseAll, None);
+        let pending = engine.handle_command_at(request.clone(), now);
+        assert!(pending.requires_confirmation);
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+        assert!(engine.command_audit_log.is_empty());
+        let token = pending.confirm_token.clone().unwrap();
+        assert_eq!(engine.handle_command_at(request, now).confirm_token, Some(token.clone()));
+
+        // Confirmed, then the same confirmation retried: executes once
+        let confirm = command_request("bob", OperationalCommand::CloseAll, Some(token.clone()));
+        let executed = engine.handle_command_at(confirm.clone(), now + chrono::Duration::seconds(5));
+        assert!(executed.success);
+        assert_eq!(engine.portfolio.state, SystemState::EmergencyHalt);
+        let retried = engine.handle_command_at(confirm, now + chrono::Duration::seconds(6));
+        assert_eq!(retried.executed_at, executed.executed_at);
+        assert_eq!(engine.command_audit_log.len(), 1);
+        let audit = engine.command_audit_log.first().unwrap();
+        assert_eq!(audit.issued_by, "bob");
+        assert_eq!(audit.confirmation_requested_by.as_deref(), Some("alice"));
+        assert_eq!(audit.command, "CloseAll");
+
+        // A used token cannot run a second close
+        let reused = engine.handle_command_at(command_request("bob", OperationalCommand::CloseAll, Some(token)), now);
+        assert!(!reused.success);
+        assert_eq!(engine.command_audit_log.len(), 1);
+
+        // Read-only commands run straight away and are audited with their issuer
+        assert!(engine.handle_command_at(command_request("carol", OperationalCommand::Status, None), now).success);
+        assert_eq!(engine.command_audit_log.get(1).unwrap().issued_by, "carol");
+        assert!(engine.requires_confirmation(&OperationalCommand::Resume));
+    }
+
+    #[test]
+    fn test_command_bus_rejects_expired_confirmation() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0))
+            .with_confirmation_ttl(chrono::Duration::seconds(30));
+        let now = Utc::now();
+        let pending = engine.handle_command_at(command_request("alice", OperationalCommand::CloseLongs, None), now);
+        let token = pending.confirm_token.unwrap();
+        assert_eq!(pending.confirm_token_expires_at, Some(now + chrono::Duration::seconds(30)));
+
+        let late = engine.handle_command_at(
+            command_request("alice", OperationalCommand::CloseLongs, Some(token.clone())),
+            now + chrono::Duration::seconds(31),
+        );
+        assert!(!late.success);
+        assert!(!late.requires_confirmation);
+        assert!(late.message.contains("expired"));
+        assert!(engine.command_audit_log.is_empty());
+
+        // A live token only confirms the command it was issued for
+        let pending = engine.handle_command_at(command_request("alice", OperationalCommand::CloseShorts, None), now);
+        let mismatched = engine.handle_command_at(
+            command_request("alice", OperationalCommand::CloseAll, pending.confirm_token),
+            now + chrono::Duration::seconds(1),
+        );
+        assert!(!mismatched.success);
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+    }
+
+    #[test]
+    fn test_unrealized_drawdown_soft_halts_and_realized_loss_hard_halts() {
+        let mut engine = StrikeBoxEngine::new(fee_free_config(), Decimal::new(100_000, 0));
+        let token = create_test_token();
+        let position = create_test_position(&token, Direction::Long, Utc::now());
+        let execution_id = position.execution_id;
+        engine.book_position(position);
+        let mark = |engine: &mut StrikeBoxEngine, pnl: i64| {
+            engine.portfolio.long_book.positions[0].unrealized_pnl_usd = Decimal::new(pnl, 0);
+            engine.tick()
+        };
+
+        // $6,000 open loss on $100,000 is 6% unrealized, none realized
+        assert_eq!(mark(&mut engine, -6_000), Some(SystemState::SoftHalt));
+        assert_eq!(engine.portfolio.daily_unrealized_drawdown_pct, Decimal::new(6, 2));
+        assert_eq!(engine.portfolio.daily_realized_drawdown_pct, Decimal::ZERO);
+        assert!(!engine.validate_entry(&create_test_token(), Direction::Short).all_passed);
+        assert!(engine.execute_command(OperationalCommand::Risk).message.contains("unrealized 6.00%"));
+        assert_eq!(engine.set_state(SystemState::SoftHalt), Err(StateTransitionError::Reserved(SystemState::SoftHalt)));
+
+        // The mark recovers and entries reopen with no operator action
+        assert_eq!(mark(&mut engine, -1_000), Some(SystemState::Active));
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+        assert_eq!(mark(&mut engine, -6_000), Some(SystemState::SoftHalt));
+
+        // Selling 100 tokens at $40 realizes the $6,000: a pause only an operator lifts
+        let exit = engine.close_position(execution_id, Decimal::new(40, 0), token.liquidity_usd, Decimal::ZERO).unwrap();
+        assert_eq!(exit.realized_pnl_usd, Decimal::new(-6_000, 0));
+        assert_eq!(engine.tick(), Some(SystemState::PausedAll));
+        assert_eq!(engine.portfolio.daily_realized_drawdown_pct, Decimal::new(6, 2));
+        assert_eq!(engine.portfolio.daily_unrealized_drawdown_pct, Decimal::ZERO);
+        assert_eq!(engine.tick(), None);
+        let resume = engine.execute_command(OperationalCommand::Resume);
+        assert!(!resume.success);
+        assert!(resume.message.contains("PausedAll"));
+    }
+
+    #[test]
+    fn test_state_transitions_are_validated() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        assert_eq!(engine.set_state(SystemState::Active), Err(StateTransitionError::InvalidResume(SystemState::Active)));
+        assert_eq!(engine.set_state(SystemState::PausedLongs), Ok(SystemState::Active));
+        assert_eq!(engine.set_state(SystemState::Active), Ok(SystemState::PausedLongs));
+        for reserved in [SystemState::EmergencyHalt, SystemState::Recovering] {
+            assert_eq!(engine.set_state(reserved), Err(StateTransitionError::Reserved(reserved)));
+        }
+
+        // Only a manual reset leaves the halt, and only via Recovering on the next tick
+        assert!(engine.execute_command(OperationalCommand::CloseAll).success);
+        assert_eq!(engine.portfolio.state, SystemState::EmergencyHalt);
+        assert!(!engine.execute_command(OperationalCommand::Resume).success);
+        assert!(!engine.execute_command(OperationalCommand::PauseAll).success);
+        assert_eq!(engine.portfolio.state, SystemState::EmergencyHalt);
+
+        engine.reset_emergency_halt().unwrap();
+        assert_eq!(engine.portfolio.state, SystemState::EmergencyHalt);
+        engine.tick();
+        assert_eq!(engine.portfolio.state, SystemState::Recovering);
+        assert_eq!(engine.reset_emergency_halt(), Err(StateTransitionError::NotHalted(SystemState::Recovering)));
+        assert!(engine.execute_command(OperationalCommand::Resume).success);
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+    }
+
+    #[test]
+    fn test_position_history_for_token() {
+        let config = StrikeBoxConfig::default();
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+
+        let mut closed = create_test_position(&token, Direction::Long, Utc::now() - chrono::Duration::days(3));
+        closed.status = PositionStatus::Closed;
+        let open = create_test_position(&token, Direction::Long, Utc::now());
+        let mut liquidated = create_test_position(&token, Direction::Short, Utc::now() - chrono::Duration::days(1));
+        liquidated.status = PositionStatus::Liquidated;
+        engine.record_exit(create_test_exit(closed.execution_id, Decimal::new(500, 0), 7200));
+        engine.record_exit(create_test_exit(liquidated.execution_id, Decimal::new(-200, 0), 3600));
+        engine.portfolio.long_book.positions.push(open);
+        engine.portfolio.long_book.positions.push(closed);
+        engine.portfolio.short_book.positions.push(liquidated);
+
+        let history = engine.position_history_for_token(&token.token_address);
+        assert_eq!(history.len(), 3);
+        assert!(history.windows(2).all(|w| w[0].position.opened_at <= w[1].position.opened_at));
+        assert_eq!(history[0].position.status, PositionStatus::Closed);
+        assert!(history[0].exit_log.is_some());
+        assert!(history[2].exit_log.is_none());
+
+        assert_eq!(engine.total_realized_pnl_for_token(&token.token_address), Decimal::new(300, 0));
+        assert_eq!(engine.average_holding_duration_hours(&token.token_address), Some(1.5));
+        assert_eq!(engine.average_holding_duration_hours("0xunknown"), None);
+
+        let response = engine.execute_command(OperationalCommand::History {
+            token: token.token_address.clone(),
+        });
+        assert!(response.success);
+        assert!(response.data.is_some());
+    }
+
+    #[test]
+    fn test_ring_buffer_eviction_preserves_order() {
+        let mut buffer = RingBuffer::new(3);
+        for i in 0..3 {
+            assert_eq!(buffer.push(i), None);
+        }
+        assert_eq!(buffer.push(3), Some(0));
+        assert_eq!(buffer.push(4), Some(1));
+
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.capacity(), 3);
+        assert_eq!(buffer.evicted_count(), 2);
+        assert_eq!(buffer.first(), Some(&2));
+        assert_eq!(buffer.last(), Some(&4));
+    }
+
+    #[derive(Default)]
+    struct CollectingSink {
+        records: Mutex<Vec<AuditRecord>>,
+    }
+
+    impl AuditSink for CollectingSink {
+        fn spill(&self, record: &AuditRecord) -> std::io::Result<()> {
+            self.records.lock().unwrap().push(record.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_evicted_logs_spill_to_audit_sink() {
+        let mut config = StrikeBoxConfig::default();
+        config.log_retention.max_exit_logs = 2;
+        let sink = Arc::new(CollectingSink::default());
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0))
+            .with_audit_sink(sink.clone());
+
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        for (i, id) in ids.iter().enumerate() {
+            engine.record_exit(create_test_exit(*id, Decimal::new(i as i64, 0), 60));
+        }
+
+        let spilled: Vec<Uuid> = sink
+            .records
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| match r {
+                AuditRecord::Exit(exit) => exit.execution_id,
+                other => panic!("unexpected record {:?}", other),
+            })
+            .collect();
+        assert_eq!(spilled, ids[..2].to_vec());
+        let retained: Vec<Uuid> = engine.exit_logs.iter().map(|e| e.execution_id).collect();
+        assert_eq!(retained, ids[2..].to_vec());
+        assert_eq!(engine.exit_logs.evicted_count(), 2);
+    }
+
+    #[test]
+    fn test_jsonl_audit_sink_appends_records() {
+        let path = std::env::temp_dir().join(format!("strike_box_audit_{}.jsonl", Uuid::new_v4()));
+        let sink = JsonlAuditSink::new(&path);
+        for pnl in [100, -50] {
+            sink.spill(&AuditRecord::Exit(create_test_exit(Uuid::new_v4(), Decimal::new(pnl, 0), 60))).unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let records: Vec<AuditRecord> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert!(matches!(&records[1], AuditRecord::Exit(exit) if exit.realized_pnl_usd == Decimal::new(-50, 0)));
+    }
+
+    #[test]
+    fn test_failed_spills_are_counted() {
+        let missing_dir = std::env::temp_dir().join(format!("strike_box_missing_{}", Uuid::new_v4()));
+        let sink = Arc::new(JsonlAuditSink::new(missing_dir.join("audit.jsonl")));
+        let exit = AuditRecord::Exit(create_test_exit(Uuid::new_v4(), Decimal::ONE, 60));
+        assert!(sink.spill(&exit).is_err());
+
+        let mut config = StrikeBoxConfig::default();
+        config.log_retention.max_exit_logs = 1;
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0)).with_audit_sink(sink);
+        for _ in 0..3 {
+            engine.record_exit(create_test_exit(Uuid::new_v4(), Decimal::ONE, 60));
+        }
+        assert_eq!(engine.audit_spill_failures(), 2);
+        assert_eq!(engine.exit_logs.evicted_count(), 2);
+    }
+
+    #[test]
+    fn test_signed_audit_snapshots_detect_tampering() {
+        let key = b"compliance-key";
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0))
+            .with_audit_signing_key(key.to_vec());
+        engine.tick();
+        engine.tick();
+        assert_eq!(engine.audit_snapshots.len(), 2);
+
+        let snapshot = engine.audit_snapshots.first().unwrap();
+        assert_eq!(snapshot.hmac_sha256.len(), 64);
+        assert!(snapshot.verify(key));
+        assert!(!snapshot.verify(b"wrong-key"));
+        let mut tampered = snapshot.clone();
+        tampered.payload_json = tampered.payload_json.replace("100000", "900000");
+        assert!(!tampered.verify(key));
+        let mut backdated = snapshot.clone();
+        backdated.timestamp -= chrono::Duration::hours(1);
+        assert!(!backdated.verify(key));
+
+        let path = std::env::temp_dir().join(format!("strike_box_trail_{}.jsonl", Uuid::new_v4()));
+        engine.export_audit_trail(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let exported: Vec<AuditSnapshot> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(exported, engine.audit_snapshots.iter().cloned().collect::<Vec<_>>());
+        assert!(exported.iter().all(|s| s.verify(key)));
+
+        // Equity history stays on the engine, so snapshots don't grow as it fills
+        for _ in 0..100 {
+            engine.portfolio.update_drawdowns();
+            engine.tick();
+        }
+        let latest = engine.audit_snapshots.last().unwrap();
+        assert!(!latest.payload_json.contains("equity_history"));
+        assert!(latest.payload_json.len() <= engine.audit_snapshots.first().unwrap().payload_json.len() + 16);
+    }
+
+    #[test]
+    fn test_audit_snapshots_are_bounded_and_spill_the_oldest() {
+        let sink = Arc::new(CollectingSink::default());
+        let mut config = StrikeBoxConfig::default();
+        config.log_retention.max_audit_snapshots = 2;
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(100_000, 0))
+            .with_audit_signing_key(b"compliance-key".to_vec())
+            .with_audit_sink(sink.clone());
+        for _ in 0..3 {
+            engine.tick();
+        }
+
+        assert_eq!(engine.audit_snapshots.len(), 2);
+        let spilled = sink.records.lock().unwrap();
+        assert_eq!(spilled.len(), 1);
+        assert!(matches!(&spilled[0], AuditRecord::Snapshot(snapshot) if snapshot.verify(b"compliance-key")));
+    }
+
+    #[test]
+    fn test_capital_deposit_rescales_books() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+
+        engine.deposit_capital(Decimal::new(50_000, 0)).unwrap();
+
+        assert_eq!(engine.portfolio.total_capital_usd, Decimal::new(150_000, 0));
+        assert_eq!(engine.portfolio.available_capital_usd, Decimal::new(150_000, 0));
+        assert_eq!(engine.portfolio.long_book.max_allocation_usd, Decimal::new(105_000, 0));
+        assert_eq!(engine.portfolio.short_book.max_allocation_usd, Decimal::new(45_000, 0));
+        assert_eq!(engine.portfolio.daily_drawdown_pct, Decimal::ZERO);
+        assert_eq!(engine.capital_events.len(), 1);
+        assert_eq!(engine.capital_events.first().unwrap().kind, CapitalEventKind::Deposit);
+        assert_eq!(
+            engine.deposit_capital(Decimal::ZERO),
+            Err(CapitalError::InvalidAmount(Decimal::ZERO))
+        );
+    }
+
+    #[test]
+    fn test_capital_withdrawal_limited_to_available() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        let token = create_test_token();
+        engine.portfolio.long_book.positions.push(create_test_position(&token, Direction::Long, Utc::now()));
+        engine.portfolio.long_book.total_allocation_usd = Decimal::new(60_000, 0);
+
+        let err = engine.withdraw_capital(Decimal::new(50_000, 0)).unwrap_err();
+        assert_eq!(
+            err,
+            CapitalError::InsufficientLiquidity {
+                available: Decimal::new(40_000, 0),
+                requested: Decimal::new(50_000, 0),
+            }
+        );
+        assert!(engine.capital_events.is_empty());
+
+        let remaining = engine.withdraw_capital(Decimal::new(30_000, 0)).unwrap();
+        assert_eq!(remaining, Decimal::new(10_000, 0));
+        assert_eq!(engine.portfolio.total_capital_usd, Decimal::new(70_000, 0));
+        assert_eq!(engine.portfolio.long_book.max_allocation_usd, Decimal::new(49_000, 0));
+        assert_eq!(engine.portfolio.daily_drawdown_pct, Decimal::ZERO);
+        assert_eq!(engine.capital_events.first().unwrap().kind, CapitalEventKind::Withdrawal);
+    }
+
+    #[test]
+    fn test_config_update_reports_field_diff() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        let token = create_test_token();
+        engine.portfolio.long_book.positions.push(create_test_position(&token, Direction::Long, Utc::now()));
+
+        let mut new_config = StrikeBoxConfig::default();
+        new_config.stop_loss.long_default_pct = Decimal::new(6, 2);
+        new_config.safety_scoring.long_entry_min = Decimal::new(75, 2);
+        let report = engine.apply_config_update(new_config, false);
+
+        assert!(report.applied);
+        let paths: Vec<&str> = report.changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["safety_scoring.long_entry_min", "stop_loss.long_default_pct"]);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("stop_loss.long_default_pct"));
+        assert!(report.repriced_positions.is_empty());
+        assert_eq!(engine.portfolio.long_book.positions[0].stop_loss_price, Decimal::new(95, 0));
+        assert_eq!(engine.config.stop_loss.long_default_pct, Decimal::new(6, 2));
+
+        let mut invalid = engine.config.clone();
+        invalid.take_profit.long_levels[1].trigger_pct = Decimal::new(10, 2);
+        let rejected = engine.apply_config_update(invalid, false);
+        assert!(!rejected.applied);
+        assert!(!rejected.errors.is_empty());
+        assert_eq!(engine.config.take_profit.long_levels[1].trigger_pct, Decimal::new(30, 2));
+        assert_eq!(engine.config_audit.len(), 2);
+    }
+
+    #[test]
+    fn test_config_update_reprices_open_positions() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        let token = create_test_token();
+        engine.portfolio.long_book.positions.push(create_test_position(&token, Direction::Long, Utc::now()));
+        let mut trailing = create_test_position(&token, Direction::Long, Utc::now());
+        trailing.trailing_stop_active = true;
+        engine.portfolio.long_book.positions.push(trailing);
+
+        let mut new_config = StrikeBoxConfig::default();
+        new_config.stop_loss.long_volatile_pct = Decimal::new(10, 2);
+        new_config.take_profit.long_levels[0].trigger_pct = Decimal::new(20, 2);
+        let report = engine.apply_config_update(new_config, true);
+
+        assert!(report.applied);
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.repriced_positions.len(), 2);
+        // Safety 0.80 at entry uses the volatile stop width
+        let repriced = &engine.portfolio.long_book.positions[0];
+        assert_eq!(repriced.stop_loss_price, Decimal::new(90, 0));
+        assert_eq!(repriced.take_profit_targets[0].price, Decimal::new(120, 0));
+        assert_eq!(report.repriced_positions[0].old_stop_loss, Decimal::new(95, 0));
+        // Trailing stop is left alone; targets still move
+        let trailing = &engine.portfolio.long_book.positions[1];
+        assert_eq!(trailing.stop_loss_price, Decimal::new(95, 0));
+        assert_eq!(trailing.take_profit_targets[0].price, Decimal::new(120, 0));
+    }
+
+    #[test]
+    fn test_config_update_flags_over_capacity_book() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        let token = create_test_token();
+        for _ in 0..3 {
+            engine.portfolio.short_book.positions.push(create_test_position(&token, Direction::Short, Utc::now()));
+        }
+
+        let mut new_config = StrikeBoxConfig::default();
+        new_config.position_sizing.short_book_max_positions = 2;
+        let report = engine.apply_config_update(new_config, false);
+
+        assert!(report.applied);
+        let short = report.book_status.iter().find(|b| b.direction == Direction::Short).unwrap();
+        assert_eq!(short.status, BookStatus::NoNewEntriesUntilUnderLimit);
+        assert_eq!(short.positions, 3);
+        assert!(report.warnings.iter().any(|w| w.contains("no new entries until under limit")));
+        let long = report.book_status.iter().find(|b| b.direction == Direction::Long).unwrap();
+        assert_eq!(long.status, BookStatus::WithinLimits);
+
+        let mut fresh = create_test_token();
+        fresh.token_address = "0xfresh".to_string();
+        let validation = engine.validate_entry(&fresh, Direction::Short);
+        assert_eq!(validation.first_failure().map(|f| f.gate_name.as_str()), Some("book_capacity"));
+    }
+
+    #[test]
+    fn test_positions_approaching_time_stop() {
+        let token = create_test_token();
+        let mut book = PositionBook::new(Direction::Short, Decimal::new(30_000, 0), 3);
+        for minutes in [90, 20, -5] {
+            let mut position = create_test_position(&token, Direction::Short, Utc::now());
+            position.time_stop_at = Some(Utc::now() + chrono::Duration::minutes(minutes));
+            book.positions.push(position);
+        }
+        book.positions.push(create_test_position(&token, Direction::Short, Utc::now()));
+
+        let approaching = book.positions_approaching_time_stop(chrono::Duration::minutes(30));
+        assert_eq!(approaching.len(), 2);
+        assert!(approaching[0].0.is_past_time_stop());
+        assert_eq!(approaching[0].1, chrono::Duration::zero());
+        assert!(!approaching[1].0.is_past_time_stop());
+        assert!(approaching[1].1 > chrono::Duration::minutes(19));
+        assert!(approaching[1].1 <= chrono::Duration::minutes(20));
+
+        assert_eq!(book.positions[3].time_remaining(), None);
+        assert!(!book.positions[3].is_past_time_stop());
+
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        engine.portfolio.short_book = book;
+        let response = engine.execute_command(OperationalCommand::TimeStops { within_minutes: 120 });
+        assert_eq!(response.data.unwrap()["time_stops"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_long_review_fails_on_stagnant_holder_growth() {
+        let token = create_test_token();
+        let mut config = StrikeBoxConfig::default();
+        config.time_control.long_review_min_holder_growth_pct = Some(Decimal::new(10, 2));
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(100_000, 0));
+        let eight_days_ago = Utc::now() - chrono::Duration::days(8);
+        let mut snapshots = HashMap::new();
+        // (address, holders now); the last has no fresh snapshot
+        for (address, holders) in [("0xstagnant", Some(102)), ("0xgrowing", Some(115)), ("0xcex", None), ("0xunknown", None)] {
+            let snapshot = TokenSnapshot { token_address: address.to_string(), ..token.clone() };
+            let mut position = create_test_position(&snapshot, Direction::Long, eight_days_ago);
+            position.holder_count_at_entry = 100;
+            engine.book_position(position);
+            if let Some(holder_count) = holders {
+                snapshots.insert(address.to_string(), TokenSnapshot { holder_count, ..snapshot });
+            }
+        }
+        // A CEX listing reports no holders; that is missing data, not zero growth
+        let cex = TokenSnapshot::from_order_book("0xcex", &create_test_order_book(100, 10, 500_000), "binance");
+        snapshots.insert("0xcex".to_string(), cex);
+        // Too young for review
+        engine.book_position(create_test_position(&token, Direction::Long, Utc::now()));
+
+        let reviews = engine.review_long_holds(&snapshots);
+        assert_eq!(reviews.len(), 4);
+        assert_eq!(reviews[0].holder_growth_pct, Some(Decimal::new(2, 2)));
+        assert_eq!(
+            reviews[0].outcome,
+            LongReviewOutcome::FailedReview { min_growth_pct: Decimal::new(10, 2), recommended_exit: ExitType::TimeStop }
+        );
+        assert_eq!(reviews[1].outcome, LongReviewOutcome::NeedsReview { note: None });
+        for review in &reviews[2..] {
+            assert_eq!(
+                review.outcome,
+                LongReviewOutcome::NeedsReview { note: Some("current holder data unavailable".to_string()) }
             );
-            return validation;
         }
-        validation.add_gate("no_stacking", GateResult::Passed, None);
 
-        if direction == Direction::Short && token.has_squeeze_risk(&self.config.token_validation) {
-            validation.add_gate(
-                "squeeze_risk",
-                GateResult::Failed,
-                Some(format!(
-                    "Largest wallet {:.1}% exceeds squeeze threshold",
-                    token.largest_wallet_pct
-                )),
-            );
-            return validation;
+        let response = engine.execute_command(OperationalCommand::TimeStops { within_minutes: 60 });
+        assert!(response.message.ends_with("3 long(s) need review, 1 failed review"));
+        let data = response.data.unwrap();
+        assert_eq!(data["failed_review"][0]["token_address"], "0xstagnant");
+        assert_eq!(data["needs_review"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_rescore_flags_position_whose_liquidity_halved() {
+        let token = create_test_token();
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        let position = create_test_position(&token, Direction::Long, Utc::now());
+        let flagged_id = position.execution_id;
+        engine.book_position(position);
+        let other = TokenSnapshot { token_address: "0xother".to_string(), ..create_test_token() };
+        engine.book_position(create_test_position(&other, Direction::Short, Utc::now()));
+
+        // Unchanged token: holding
+        let results = engine.rescore_positions(&HashMap::from([(token.token_address.clone(), token.clone())]));
+        assert_eq!(results[0].outcome, rescore::RescoreOutcome::Holding);
+
+        let drained = TokenSnapshot {
+            liquidity_usd: token.liquidity_usd / Decimal::TWO,
+            bid_depth_usd: token.bid_depth_usd / Decimal::TWO,
+            ask_depth_usd: token.ask_depth_usd / Decimal::TWO,
+            ..token.clone()
+        };
+        let results = engine.rescore_positions(&HashMap::from([(token.token_address.clone(), drained)]));
+        assert_eq!(results.len(), 2);
+        let flagged = &results[0];
+        assert_eq!(flagged.execution_id, flagged_id);
+        assert_eq!(flagged.score, Some(Decimal::new(72, 2)));
+        match &flagged.outcome {
+            rescore::RescoreOutcome::ReviewExit { reasons, driver } => {
+                assert_eq!(*driver, Some(rescore::SafetyComponent::Liquidity));
+                assert!(matches!(reasons[..], [rescore::HoldReviewReason::DroppedSinceEntry { .. }]));
+            }
+            other => panic!("expected a hold review, got {:?}", other),
+        }
+        let liquidity = &flagged.components[0];
+        assert_eq!((liquidity.at_entry, liquidity.current), (Some(Decimal::ONE), Decimal::ZERO));
+        assert_eq!(engine.portfolio.long_book.positions[0].score_history.len(), 2);
+        // Still open: the review is advisory
+        assert!(engine.portfolio.long_book.positions[0].is_open());
+
+        assert!(results[1].is_stale());
+        assert!(engine.portfolio.short_book.positions[0].score_history.is_empty());
+
+        let response = engine.execute_command(OperationalCommand::TimeStops { within_minutes: 60 });
+        let data = response.data.unwrap();
+        assert_eq!(data["hold_reviews"][0]["outcome"]["ReviewExit"]["driver"], "Liquidity");
+        assert_eq!(data["unable_to_rescore"].as_array().unwrap().len(), 1);
+        assert!(response.message.contains("1 flagged for hold review | 1 stale, unable to rescore"));
+    }
+
+    fn create_query_engine() -> StrikeBoxEngine {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let now = Utc::now();
+        // (symbol, direction, hours old, mark, size usd)
+        for (symbol, direction, hours, mark, size) in [
+            ("PEPE", Direction::Long, 5, 110, 10_000),
+            ("WIF", Direction::Long, 3, 96, 20_000),
+            ("BONK", Direction::Long, 1, 110, 10_000),
+            ("PEPE2", Direction::Short, 4, 90, 15_000),
+            ("MOG", Direction::Short, 2, 109, 10_000),
+        ] {
+            let token = TokenSnapshot {
+                token_address: format!("0x{}", symbol.to_lowercase()),
+                token_symbol: symbol.to_string(),
+                ..create_test_token()
+            };
+            let mut position = create_test_position(&token, direction, now - chrono::Duration::hours(hours));
+            position.position_size_usd = Decimal::new(size, 0);
+            position.position_size_tokens = Decimal::new(size / 100, 0);
+            if direction == Direction::Short {
+                position.stop_loss_price = Decimal::new(108, 0);
+                position.take_profit_targets = TakeProfitConfig::default().targets(Direction::Short, position.entry_price);
+            }
+            position.mark_to_market(Decimal::new(mark, 0), token.liquidity_usd);
+            match direction {
+                Direction::Long => engine.portfolio.long_book.positions.push(position),
+                Direction::Short => engine.portfolio.short_book.positions.push(position),
+            }
+        }
+        engine
+    }
+
+    #[test]
+    fn test_query_positions_combined_filters() {
+        let mut engine = create_query_engine();
+        let symbols = |page: PositionPage<'_>| page.items.iter().map(|v| v.token_symbol.to_string()).collect::<Vec<_>>();
+
+        // Token matches address or symbol, ignoring case
+        let pepe = PositionFilter { token: Some("pEpE".to_string()), ..Default::default() };
+        assert_eq!(symbols(engine.query_positions(pepe.clone())), vec!["PEPE2", "PEPE"]);
+        let profitable_pepe_longs = PositionFilter {
+            direction: Some(Direction::Long),
+            min_pnl_pct: Some(Decimal::new(5, 2)),
+            ..pepe
+        };
+        assert_eq!(symbols(engine.query_positions(profitable_pepe_longs)), vec!["PEPE"]);
+
+        let older_losers = PositionFilter {
+            max_pnl_pct: Some(Decimal::ZERO),
+            opened_before: Some(Utc::now() - chrono::Duration::minutes(150)),
+            ..Default::default()
+        };
+        assert_eq!(symbols(engine.query_positions(older_losers)), vec!["WIF"]);
+
+        // MOG's mark is past its 108 stop; the rest are above TP1 or inside the range
+        let pending = engine.query_positions(PositionFilter { pending_exit: Some(true), ..Default::default() });
+        let pending_symbols: Vec<&str> = pending.items.iter().map(|v| v.token_symbol).collect();
+        assert_eq!(pending_symbols, vec!["MOG", "PEPE2"]);
+        let mog = &pending.items[0];
+        assert_eq!(mog.distance_to_stop_pct.round_dp(4), Decimal::new(-92, 4));
+        assert_eq!(mog.next_take_profit.unwrap().price, Decimal::new(90, 0));
+
+        let wif = &engine.query_positions(PositionFilter { token: Some("wif".to_string()), ..Default::default() }).items[0];
+        assert_eq!(wif.distance_to_stop_pct, Decimal::ONE / Decimal::new(96, 0));
+        assert_eq!(wif.age_seconds / 3_600, 3);
+
+        // Closed positions only show up when asked for
+        engine.portfolio.long_book.positions[0].status = PositionStatus::Closed;
+        assert_eq!(engine.query_positions(PositionFilter::default()).total, 4);
+        let closed = PositionFilter { status: Some(PositionStatus::Closed), ..Default::default() };
+        assert_eq!(symbols(engine.query_positions(closed)), vec!["PEPE"]);
+
+        let response = engine.execute_command(OperationalCommand::Position { token: "bonk".to_string() });
+        assert!(response.message.contains("Positions: 1"));
+        assert_eq!(response.data.unwrap()["items"][0]["token_symbol"], "BONK");
+    }
+
+    #[test]
+    fn test_query_positions_sort_and_pagination() {
+        let engine = create_query_engine();
+        let page = |filter: PositionFilter| {
+            engine.query_positions(filter).items.iter().map(|v| v.token_symbol).collect::<Vec<_>>()
+        };
+
+        assert_eq!(page(PositionFilter::default()), vec!["BONK", "MOG", "WIF", "PEPE2", "PEPE"]);
+        assert_eq!(
+            page(PositionFilter { descending: true, ..Default::default() }),
+            vec!["PEPE", "PEPE2", "WIF", "MOG", "BONK"]
+        );
+
+        // PEPE, BONK and PEPE2 all sit at +10%; ties keep book order in both directions
+        let by_pnl = PositionFilter { sort_by: PositionSortKey::Pnl, ..Default::default() };
+        assert_eq!(page(by_pnl.clone()), vec!["MOG", "WIF", "PEPE", "BONK", "PEPE2"]);
+        assert_eq!(
+            page(PositionFilter { descending: true, ..by_pnl }),
+            vec!["PEPE", "BONK", "PEPE2", "WIF", "MOG"]
+        );
+        let by_size = PositionFilter { sort_by: PositionSortKey::Size, descending: true, ..Default::default() };
+        assert_eq!(page(by_size.clone()), vec!["WIF", "PEPE2", "PEPE", "BONK", "MOG"]);
+
+        let window = |offset, limit| engine.query_positions(PositionFilter { offset, limit, ..by_size.clone() });
+        let first = window(0, Some(2));
+        assert_eq!((first.items.len(), first.total, first.has_more()), (2, 5, true));
+        let last = window(4, Some(2));
+        assert_eq!((last.items[0].token_symbol, last.has_more()), ("MOG", false));
+        let exact = window(3, Some(2));
+        assert_eq!((exact.items.len(), exact.has_more()), (2, false));
+        let beyond = window(9, Some(2));
+        assert_eq!((beyond.items.len(), beyond.total, beyond.offset), (0, 5, 9));
+        assert!(window(0, Some(0)).items.is_empty());
+        assert_eq!(window(1, None).items.len(), 4);
+    }
+
+    #[test]
+    fn test_pnl_pct_uses_remaining_cost_basis() {
+        let token = create_test_token();
+        let mut position = create_test_position(&token, Direction::Long, Utc::now());
+
+        // TP1 took 33% off
+        position.apply_partial_exit(Decimal::new(33, 2));
+        assert_eq!(position.remaining_size_pct, Decimal::new(67, 2));
+        assert_eq!(position.status, PositionStatus::PartialExit);
+        position.mark_to_market(Decimal::new(115, 0), position.liquidity_at_entry);
+
+        assert_eq!(position.unrealized_pnl_usd, Decimal::new(1005, 0));
+        assert_eq!(position.return_on_remaining(), Decimal::new(15, 2));
+        assert_eq!(position.unrealized_pnl_pct, Decimal::new(15, 2));
+        assert_eq!(position.return_on_initial(), Decimal::new(1005, 4));
+
+        // Same price is a no-op; a further exit needs an explicit refresh
+        position.remaining_size_pct = Decimal::new(34, 2);
+        let unchanged = position.mark_to_market(Decimal::new(115, 0), position.liquidity_at_entry);
+        assert_eq!(unchanged.unrealized_pnl_delta, Decimal::ZERO);
+        assert_eq!(position.unrealized_pnl_usd, Decimal::new(1005, 0));
+        position.refresh_pnl();
+        assert_eq!(position.unrealized_pnl_usd, Decimal::new(510, 0));
+        assert_eq!(position.return_on_remaining(), Decimal::new(15, 2));
+    }
+
+    #[test]
+    fn test_mark_to_market_reports_pnl_delta_and_events() {
+        let token = create_test_token();
+        let liquidity = token.liquidity_usd;
+        let mut position = create_test_position(&token, Direction::Long, Utc::now());
+
+        let result = position.mark_to_market(Decimal::new(110, 0), liquidity);
+        assert_eq!(result.unrealized_pnl_delta, Decimal::new(1_000, 0));
+        assert!(result.events.is_empty());
+        assert!(!position.trailing_stop_active);
+
+        let result = position.mark_to_market(Decimal::new(116, 0), liquidity);
+        assert_eq!(result.unrealized_pnl_delta, Decimal::new(600, 0));
+        assert_eq!(result.events, vec![PositionEvent::TrailingStopActivated { price: Decimal::new(116, 0) }]);
+        assert_eq!(position.trailing_stop_high, Some(Decimal::new(116, 0)));
+
+        // Half the size gone: the next move counts on what remains
+        position.apply_partial_exit(Decimal::new(50, 2));
+        let result = position.mark_to_market(Decimal::new(120, 0), liquidity / Decimal::TWO);
+        assert_eq!(result.unrealized_pnl_delta, Decimal::new(200, 0));
+        assert_eq!(result.events, vec![
+            PositionEvent::TrailingHighUpdated { high: Decimal::new(120, 0) },
+            PositionEvent::LiquidityDegraded { ratio: Decimal::new(5, 1) },
+        ]);
+
+        // A dip keeps the high and, at 70% liquidity exactly, raises nothing
+        let result = position.mark_to_market(Decimal::new(118, 0), liquidity * LIQUIDITY_DEGRADED_RATIO);
+        assert_eq!(result.unrealized_pnl_delta, Decimal::new(-100, 0));
+        assert!(result.events.is_empty());
+        assert_eq!(position.trailing_stop_high, Some(Decimal::new(120, 0)));
+
+        // A long from before the activation price was recorded gets the engine's configured
+        // activation when booked; marked on its own, it never arms
+        let mut legacy: Position = serde_json::from_value({
+            let mut value = serde_json::to_value(create_test_position(&token, Direction::Long, Utc::now())).unwrap();
+            value.as_object_mut().unwrap().remove("trailing_activation_price");
+            value
+        })
+        .unwrap();
+        let mut config = StrikeBoxConfig::default();
+        config.stop_loss.long_trailing_activation_pct = Decimal::new(20, 2);
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(100_000, 0));
+        engine.book_position(legacy.clone());
+        assert_eq!(engine.portfolio.long_book.positions[0].trailing_activation_price, Some(Decimal::new(120, 0)));
+        assert!(legacy.mark_to_market(Decimal::new(130, 0), liquidity).events.is_empty());
+        assert!(legacy.trailing_activation_price.is_none());
+
+        // Shorts never arm the long trailing stop
+        let mut short = create_test_position(&token, Direction::Short, Utc::now());
+        let result = short.mark_to_market(Decimal::new(80, 0), liquidity);
+        assert_eq!(result.unrealized_pnl_delta, Decimal::new(2_000, 0));
+        assert!(result.events.is_empty());
+    }
+
+    #[test]
+    fn test_zero_size_position_is_rejected_and_guarded() {
+        let config = StrikeBoxConfig::default();
+        let token = create_test_token();
+        let validation = RiskValidation::new(Direction::Short);
+
+        let err = Position::open(&token, &validation, Decimal::new(100, 0), Decimal::ZERO, test_safety(&token), &[], &config)
+            .unwrap_err();
+        assert_eq!(err, PositionError::NonPositiveSize(Decimal::ZERO));
+
+        let position = Position::open(&token, &validation, Decimal::new(100, 0), Decimal::new(5_000, 0), test_safety(&token), &[], &config)
+            .unwrap();
+        assert_eq!(position.position_size_tokens, Decimal::new(50, 0));
+        assert_eq!(position.stop_loss_price, Decimal::new(108, 0));
+        let breakdown = position.safety_breakdown_at_entry.as_ref().unwrap();
+        assert_eq!(position.safety_score_at_entry, breakdown.total_score);
+        assert!(position.time_stop_at.is_some());
+
+        // A hand-built degenerate position must not panic on price updates
+        let mut degenerate = create_test_position(&token, Direction::Long, Utc::now());
+        degenerate.position_size_usd = Decimal::ZERO;
+        degenerate.mark_to_market(Decimal::new(120, 0), Decimal::ZERO);
+        assert_eq!(degenerate.unrealized_pnl_pct, Decimal::ZERO);
+        assert_eq!(degenerate.return_on_initial(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_dry_run_reports_gates_without_logging() {
+        let engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let mut token = create_test_token();
+        token.token_age_hours = 6;
+
+        let result = engine.dry_run_validate_entry(&token, Direction::Short);
+
+        assert!(!result.validation.all_passed);
+        assert_eq!(result.liquidity_percentile, Decimal::new(5, 1));
+        assert!(result.safety_score.total_score > Decimal::ZERO);
+        let gate = |name: &str| result.diagnostics.iter().find(|d| d.gate_name == name).unwrap();
+        assert_eq!(gate("safety_score").result, Some(GateResult::Passed));
+        assert_eq!(gate("token_age").result, Some(GateResult::Failed));
+        assert_eq!(gate("token_age").observed, "6h");
+        assert_eq!(gate("squeeze_risk").result, None);
+        assert_eq!(gate("data_staleness").result, Some(GateResult::Passed));
+        assert_eq!(result.diagnostics.len(), 11);
+
+        assert!(engine.rejection_logs.is_empty());
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+    }
+
+    #[test]
+    fn test_dry_run_follows_the_gate_registry() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let blacklist = |ctx: &gates::EvaluationContext<'_>| {
+            gates::GateVerdict::Fail(format!("{} is blacklisted", ctx.token.token_symbol))
+        };
+        engine.gate_registry.register_gate("blacklist", 250, Box::new(blacklist)).unwrap();
+        engine.gate_registry.disable_gate("data_staleness").unwrap();
+
+        let result = engine.dry_run_validate_entry(&token, Direction::Long);
+        let names: Vec<&str> = result.diagnostics.iter().map(|d| d.gate_name.as_str()).collect();
+        assert_eq!(&names[..4], ["system_state", "data_staleness", "blacklist", "liquidity_range"]);
+        let gate = |name: &str| result.diagnostics.iter().find(|d| d.gate_name == name).unwrap();
+        assert_eq!(gate("data_staleness").result, Some(GateResult::Skipped));
+        assert_eq!(gate("blacklist").result, Some(GateResult::Failed));
+        assert_eq!(gate("blacklist").observed, format!("{} is blacklisted", token.token_symbol));
+        assert_eq!(gate("liquidity_range").result, None);
+        assert_eq!(result.validation.first_failure().unwrap().gate_name, "blacklist");
+    }
+
+    #[test]
+    fn test_simulate_entry_matches_config_without_booking() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let entry_price = Decimal::new(2, 0);
+
+        let long = engine.simulate_entry(&token, Direction::Long, entry_price);
+        assert_eq!(long.position_size_usd, engine.calculate_position_size(&token, Direction::Long));
+        assert_eq!(long.position_size_tokens, long.position_size_usd / entry_price);
+        assert_eq!(
+            long.stop_loss_price,
+            engine.config.stop_loss.long_stop_price(entry_price, long.safety_score.total_score)
+        );
+        assert_eq!(long.take_profit_targets, engine.config.take_profit.targets(Direction::Long, entry_price));
+        assert_eq!(long.first_partial_exit_level, Some(0));
+        assert!(long.time_stop_at.is_none());
+
+        let short = engine.simulate_entry(&token, Direction::Short, entry_price);
+        assert_eq!(short.stop_loss_price, engine.config.stop_loss.short_stop_price(entry_price));
+        assert!(short.time_stop_at.is_some());
+
+        // A 20/40/40 ladder only has a third of the size out at the second target
+        let pct = |n| Decimal::new(n, 2);
+        engine.config.take_profit = TakeProfitConfig::new(
+            vec![TpLevel::new(pct(10), pct(20)), TpLevel::new(pct(20), pct(40)), TpLevel::new(pct(30), pct(40))],
+            vec![TpLevel::new(pct(10), pct(100))],
+        )
+        .unwrap();
+        let laddered = engine.simulate_entry(&token, Direction::Long, entry_price);
+        assert_eq!(laddered.first_partial_exit_level, Some(1));
+
+        assert_eq!(engine.portfolio.long_book.position_count(), 0);
+        assert_eq!(engine.portfolio.short_book.position_count(), 0);
+        assert!(engine.entry_logs.is_empty());
+        assert!(engine.rejection_logs.is_empty());
+        assert!(engine.validation_audit_log.is_empty());
+    }
+
+    fn create_test_order_book(mid: i64, spread_bps: i64, side_depth_usd: i64) -> OrderBook {
+        let mid = Decimal::new(mid, 0);
+        let half_spread = mid * Decimal::new(spread_bps, 4) / Decimal::TWO;
+        let level = |price: Decimal| BookLevel { price, size: Decimal::new(side_depth_usd, 0) / price };
+        OrderBook {
+            // The 10% levels sit outside the depth band and must not count
+            bids: vec![level(mid - half_spread), level(mid * Decimal::new(9, 1))],
+            asks: vec![level(mid + half_spread), level(mid * Decimal::new(11, 1))],
+        }
+    }
+
+    #[test]
+    fn test_activity_schedule_blocks_and_reduces_entries() {
+        use chrono::TimeZone;
+        use schedule::{ActivityLevel, ActivityRange, HOURS_PER_WEEK};
+
+        // Sunday 02:00-06:00 blocked, Tuesday 02:00-06:00 at half size
+        let half = ActivityLevel::ReducedSize(Decimal::new(5, 1));
+        let mut config = StrikeBoxConfig::default();
+        config.activity_schedule.ranges = vec![
+            ActivityRange::new(0, 26, ActivityLevel::Normal),
+            ActivityRange::new(26, 30, half),
+            ActivityRange::new(30, 146, ActivityLevel::Normal),
+            ActivityRange::new(146, 150, ActivityLevel::Blocked),
+            ActivityRange::new(150, HOURS_PER_WEEK, ActivityLevel::Normal),
+        ];
+        assert!(config.validate().is_ok());
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
+        let full_size = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0))
+            .calculate_position_size(&create_test_token(), Direction::Long);
+        let token_at = |at: DateTime<Utc>| TokenSnapshot { snapshot_timestamp: at, ..create_test_token() };
+
+        let sunday = Utc.with_ymd_and_hms(2026, 10, 18, 3, 0, 0).unwrap();
+        engine.set_evaluation_time(Some(sunday));
+        let validation = engine.validate_entry(&token_at(sunday), Direction::Long);
+        let failure = validation.first_failure().unwrap();
+        assert_eq!(failure.gate_name, "activity_schedule");
+        assert!(failure.reason.as_deref().unwrap().ends_with("until 2026-10-18T06:00:00+00:00"));
+        let status = engine.execute_command(OperationalCommand::Status);
+        assert!(status.message.contains("Activity: longs Blocked, shorts Blocked, next change 2026-10-18T06:00:00+00:00"));
+
+        let monday = Utc.with_ymd_and_hms(2026, 10, 19, 14, 0, 0).unwrap();
+        engine.set_evaluation_time(Some(monday));
+        assert!(engine.validate_entry(&token_at(monday), Direction::Long).all_passed);
+        let decision = engine.size_decision(&token_at(monday), Direction::Long);
+        assert_eq!((decision.activity, decision.size_usd), (ActivityLevel::Normal, full_size));
+
+        let tuesday = Utc.with_ymd_and_hms(2026, 10, 20, 3, 0, 0).unwrap();
+        engine.set_evaluation_time(Some(tuesday));
+        let validation = engine.validate_entry(&token_at(tuesday), Direction::Long);
+        assert!(validation.all_passed);
+        let decision = engine.size_decision(&token_at(tuesday), Direction::Long);
+        assert_eq!(decision.activity, half);
+        assert_eq!(decision.size_usd, full_size / Decimal::TWO);
+        assert_eq!(engine.calculate_position_size(&token_at(tuesday), Direction::Long), decision.size_usd);
+    }
+
+    #[test]
+    fn test_cex_snapshots_use_venue_gates() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let book = create_test_order_book(100, 10, 200_000);
+        let cex = TokenSnapshot::from_order_book("PEPE/USDT", &book, "binance");
+
+        // Without configured listings the age check fails open, noting why
+        let validation = engine.validate_entry(&cex, Direction::Long);
+        assert!(validation.all_passed);
+        let listing = validation.gates.iter().find(|g| g.gate_name == "cex_listing_age").unwrap();
+        assert_eq!(listing.reason.as_deref(), Some("listing registry not configured"));
+
+        // Scored on the book alone: a tight, balanced book is not dragged down by missing holders
+        let safety = SafetyScore::calculate(&cex, engine.safety_scoring(), &engine.config.token_validation);
+        let scoring = engine.safety_scoring();
+        let book_weight = scoring.liquidity_weight + scoring.liquidity_imbalance_weight;
+        let expected = (safety.liquidity_score * scoring.liquidity_weight
+            + safety.liquidity_imbalance_score * scoring.liquidity_imbalance_weight)
+            * scoring.weight_total()
+            / book_weight;
+        assert_eq!(safety.total_score, expected);
+
+        engine.config.cex_validation.listings = ListingRegistry::default()
+            .with_listing("Binance", "PEPE/USDT", Utc::now() - chrono::Duration::days(30))
+            .with_listing("binance", "NEW/USDT", Utc::now() - chrono::Duration::hours(12));
+
+        assert_eq!(cex.bid_depth_usd, Decimal::new(200_000, 0));
+        assert_eq!(cex.liquidity_usd, Decimal::new(400_000, 0));
+        assert_eq!(cex.holder_count, 0);
+        assert!(!cex.has_onchain_data());
+
+        // No holder data and liquidity under the DEX band, yet every CEX gate passes
+        let validation = engine.validate_and_record_entry(&cex, Direction::Short);
+        assert!(validation.all_passed);
+        let gates: Vec<&str> = validation.gates.iter().map(|g| g.gate_name.as_str()).collect();
+        assert_eq!(gates, ["system_state", "data_staleness", "cex_spread", "cex_depth", "cex_listing_age", "book_capacity", "no_stacking", "net_exposure"]);
+
+        let wide = TokenSnapshot::from_order_book("PEPE/USDT", &create_test_order_book(100, 80, 200_000), "binance");
+        let shallow = TokenSnapshot::from_order_book("PEPE/USDT", &create_test_order_book(100, 10, 50_000), "binance");
+        let fresh = TokenSnapshot::from_order_book("NEW/USDT", &book, "binance");
+        let unlisted = TokenSnapshot::from_order_book("PEPE/USDT", &book, "okx");
+        let empty = TokenSnapshot::from_order_book("PEPE/USDT", &OrderBook::default(), "binance");
+        let mut crossed_book = book.clone();
+        crossed_book.bids[0].price = crossed_book.best_ask().unwrap() + Decimal::ONE;
+        let crossed = TokenSnapshot::from_order_book("PEPE/USDT", &crossed_book, "binance");
+        assert_eq!((crossed.bid_depth_usd, crossed.liquidity_usd), (Decimal::ZERO, Decimal::ZERO));
+        for (snapshot, gate) in [
+            (&wide, "cex_spread"),
+            (&shallow, "cex_depth"),
+            (&fresh, "cex_listing_age"),
+            (&unlisted, "cex_listing_age"),
+            (&empty, "cex_spread"),
+        ] {
+            let validation = engine.validate_and_record_entry(snapshot, Direction::Long);
+            assert_eq!(validation.first_failure().unwrap().gate_name, gate);
+            let rejection = engine.rejection_logs.iter().last().unwrap();
+            assert_eq!(rejection.failed_gate, gate);
+            assert!(rejection.rejection_reason.starts_with("CEX "));
+            assert_eq!(rejection.safety_score, None);
+        }
+
+        // A DEX snapshot still has to show its holders
+        let mut dex = create_test_token();
+        dex.holder_count = 10;
+        let validation = engine.validate_and_record_entry(&dex, Direction::Long);
+        assert_eq!(validation.first_failure().unwrap().gate_name, "holder_distribution");
+        let rejection = engine.rejection_logs.iter().last().unwrap();
+        assert!(rejection.rejection_reason.starts_with("Holders 10"));
+        assert!(rejection.safety_score.is_some());
+
+        let dry_run = engine.dry_run_validate_entry(&wide, Direction::Short);
+        let names: Vec<&str> = dry_run.diagnostics.iter().map(|d| d.gate_name.as_str()).collect();
+        assert!(names.contains(&"cex_spread") && !names.contains(&"holder_distribution") && !names.contains(&"squeeze_risk"));
+    }
+
+    #[test]
+    fn test_pnl_timeframe_parsing_and_bounds() {
+        // Thursday
+        let now = DateTime::parse_from_rfc3339("2024-03-14T15:30:00Z").unwrap().with_timezone(&Utc);
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let parse = |s: &str| s.parse::<PnlTimeframe>().unwrap();
+
+        assert_eq!(parse("today").start(now), at("2024-03-14T00:00:00Z"));
+        assert_eq!(parse("Week").start(now), at("2024-03-11T00:00:00Z"));
+        assert_eq!(parse("month").start(now), at("2024-03-01T00:00:00Z"));
+        assert_eq!(parse("all").start(now), DateTime::<Utc>::MIN_UTC);
+        assert_eq!(parse("today").end(now), now);
+
+        assert_eq!(parse("24h"), PnlTimeframe::Trailing { seconds: 86_400 });
+        assert_eq!(parse("90d").start(now), now - chrono::Duration::days(90));
+        assert_eq!(parse("P7D"), parse("7d"));
+        assert_eq!(parse("PT12H"), PnlTimeframe::Trailing { seconds: 43_200 });
+        assert_eq!(parse("P1W2DT6H30M"), PnlTimeframe::Trailing { seconds: 9 * 86_400 + 6 * 3_600 + 1_800 });
+
+        let january = parse("2024-01-01/2024-01-31");
+        assert_eq!(january.start(now), at("2024-01-01T00:00:00Z"));
+        assert_eq!(january.end(now), at("2024-02-01T00:00:00Z"));
+        assert!(january.contains(at("2024-01-31T23:59:59Z"), now));
+        assert!(!january.contains(at("2024-02-01T00:00:00Z"), now));
+        assert_eq!(
+            parse("2024-01-01T06:00:00Z/2024-01-01T18:00:00Z").end(now),
+            at("2024-01-01T18:00:00Z")
+        );
+
+        for bad in ["", "yesterday", "0d", "7x", "P1M", "P1Y", "PT", "P", "2024-01-31/2024-01-01", "2024-13-01/2024-12-31"] {
+            assert!(bad.parse::<PnlTimeframe>().is_err(), "{:?} should not parse", bad);
+        }
+
+        // Too long for a chrono Duration is a parse error, not a panic
+        for overflow in ["9999999999999d", "99999999999999999999h", "P9999999999999W", "P9999999999999DT1H"] {
+            assert!(overflow.parse::<PnlTimeframe>().is_err(), "{:?} should not parse", overflow);
+        }
+        assert!("pnl 9999999999999d".parse::<OperationalCommand>().is_err());
+        assert!("pnl today curve 9999999999999d".parse::<OperationalCommand>().is_err());
+        // Representable, but reaching past the earliest instant
+        assert_eq!(parse("100000000d").start(now), DateTime::<Utc>::MIN_UTC);
+        assert_eq!(PnlTimeframe::Trailing { seconds: i64::MAX }.start(now), DateTime::<Utc>::MIN_UTC);
+    }
+
+    #[test]
+    fn test_operational_commands_parse_and_report_timeframes() {
+        let parse = |s: &str| s.parse::<OperationalCommand>();
+        assert_eq!(parse("pause longs"), Ok(OperationalCommand::PauseLongs));
+        assert_eq!(parse("CLOSE all"), Ok(OperationalCommand::CloseAll));
+        assert_eq!(parse(" status "), Ok(OperationalCommand::Status));
+        assert_eq!(parse("history PEPE"), Ok(OperationalCommand::History { token: "PEPE".to_string() }));
+        assert_eq!(
+            parse("pnl 7d"),
+            Ok(OperationalCommand::Pnl { timeframe: PnlTimeframe::Trailing { seconds: 604_800 }, curve_resolution_seconds: None })
+        );
+        assert_eq!(
+            parse("pnl today curve 1h"),
+            Ok(OperationalCommand::Pnl { timeframe: PnlTimeframe::Today, curve_resolution_seconds: Some(3_600) })
+        );
+        assert!(parse("pnl 7d curve").is_err());
+        assert!(parse("pnl 7d curve soon").is_err());
+        assert_eq!(parse("timestops 60"), Ok(OperationalCommand::TimeStops { within_minutes: 60 }));
+        assert!(parse("pnl").is_err());
+        assert!(parse("pnl soon").is_err());
+        assert!(parse("pause everything").is_err());
+        assert!(parse("status now").is_err());
+
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let mut recent_win = create_test_exit(Uuid::new_v4(), Decimal::new(300, 0), 60);
+        recent_win.timestamp = Utc::now() - chrono::Duration::hours(2);
+        let mut recent_loss = create_test_exit(Uuid::new_v4(), Decimal::new(-100, 0), 60);
+        recent_loss.timestamp = Utc::now() - chrono::Duration::hours(3);
+        let mut old_win = create_test_exit(Uuid::new_v4(), Decimal::new(1_000, 0), 60);
+        old_win.timestamp = Utc::now() - chrono::Duration::days(3);
+        for exit in [recent_win, recent_loss, old_win] {
+            engine.record_exit(exit);
+        }
+
+        let day = engine.pnl_report("24h".parse().unwrap(), Utc::now());
+        assert_eq!((day.exits, day.winning_exits, day.losing_exits), (2, 1, 1));
+        assert_eq!(day.realized_pnl_usd, Decimal::new(200, 0));
+        assert_eq!(engine.pnl_report(PnlTimeframe::All, Utc::now()).realized_pnl_usd, Decimal::new(1_200, 0));
+
+        let mut token = create_test_token();
+        token.token_age_hours = 6;
+        engine.validate_and_record_entry(&token, Direction::Long);
+        let rejects = engine.rejects_report(PnlTimeframe::Today, Utc::now());
+        assert_eq!(rejects.total, 1);
+        assert_eq!(rejects.by_gate.get("token_age"), Some(&1));
+
+        let response = engine.execute_command(parse("pnl all").unwrap());
+        assert!(response.message.contains("Realized PnL: $1200"));
+        assert_eq!(response.data.unwrap()["exits"], 3);
+    }
+
+    #[test]
+    fn test_trigger_resolution_orders_gaps_through_take_profit_and_stop() {
+        use TriggerPathAssumption::*;
+        let token = create_test_token();
+        let d = |n| Decimal::new(n, 0);
+        let tp = |level, price| TriggerEvent::TakeProfit { level, price: d(price) };
+        let stop = |price| TriggerEvent::StopLoss { price: d(price) };
+        let resolve = |position: &Position, prev, new, assumption| {
+            let mut config = StrikeBoxConfig::default();
+            config.trigger_resolution.path_assumption = assumption;
+            StrikeBoxEngine::resolve_triggers(position, d(prev), d(new), &config)
+        };
+
+        // Long: stop 95, targets 115/130/150; a wick from 140 down to 90 spans TP1, TP2 and the stop
+        let long = create_test_position(&token, Direction::Long, Utc::now());
+        let cases = [
+            (140, 90, Conservative, vec![stop(95)]),
+            (140, 90, Optimistic, vec![tp(0, 115), tp(1, 130), stop(95)]),
+            (140, 90, Midpoint, vec![tp(0, 115), tp(1, 130), stop(95)]),
+            (120, 60, Optimistic, vec![tp(0, 115), stop(95)]),
+            (120, 60, Midpoint, vec![stop(95)]),
+        ];
+        for (prev, new, assumption, expected) in cases {
+            let resolution = resolve(&long, prev, new, assumption);
+            assert!(resolution.ambiguous);
+            assert_eq!(resolution.assumption, assumption);
+            assert_eq!(resolution.events, expected, "long {}->{} {:?}", prev, new, assumption);
+        }
+
+        // Short: stop 108, targets 90/80/70; a squeeze from 75 up to 110 spans TP1, TP2 and the stop
+        let mut short = create_test_position(&token, Direction::Short, Utc::now());
+        short.stop_loss_price = d(108);
+        short.take_profit_targets = TakeProfitConfig::default().targets(Direction::Short, d(100));
+        let cases = [
+            (75, 110, Conservative, vec![stop(108)]),
+            (75, 110, Optimistic, vec![tp(0, 90), tp(1, 80), stop(108)]),
+            (75, 110, Midpoint, vec![tp(0, 90), tp(1, 80), stop(108)]),
+            (85, 125, Midpoint, vec![stop(108)]),
+        ];
+        for (prev, new, assumption, expected) in cases {
+            let resolution = resolve(&short, prev, new, assumption);
+            assert!(resolution.ambiguous);
+            assert_eq!(resolution.events, expected, "short {}->{} {:?}", prev, new, assumption);
+        }
+
+        // One-sided moves are not ambiguous under any assumption
+        for assumption in [Conservative, Optimistic, Midpoint] {
+            let up = resolve(&long, 100, 120, assumption);
+            assert_eq!((up.events, up.ambiguous), (vec![tp(0, 115)], false));
+            let down = resolve(&long, 100, 94, assumption);
+            assert_eq!((down.events, down.ambiguous), (vec![stop(95)], false));
+        }
+    }
+
+    #[test]
+    fn test_price_update_books_resolved_exits_with_assumption() {
+        let mut config = fee_free_config();
+        config.trigger_resolution.path_assumption = TriggerPathAssumption::Optimistic;
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let mut position = create_test_position(&token, Direction::Long, Utc::now());
+        position.current_price = Decimal::new(140, 0);
+        let execution_id = position.execution_id;
+        engine.book_position(position);
+
+        let (_, exits) = engine
+            .process_price_update(execution_id, Decimal::new(90, 0), token.liquidity_usd)
+            .unwrap();
+        let booked: Vec<(ExitType, Decimal)> = exits.iter().map(|e| (e.exit_type, e.realized_pnl_usd)).collect();
+        assert_eq!(booked, vec![
+            (ExitType::TakeProfit1, Decimal::new(495, 0)),
+            (ExitType::TakeProfit2, Decimal::new(990, 0)),
+            (ExitType::StopLoss, Decimal::new(-170, 0)),
+        ]);
+        assert_eq!(engine.portfolio.long_book.realized_pnl_usd, Decimal::new(1_315, 0));
+        assert_eq!(engine.portfolio.long_book.positions[0].status, PositionStatus::Closed);
+        assert_eq!(engine.portfolio.long_book.total_allocation_usd, Decimal::ZERO);
+        assert_eq!(engine.exit_logs.len(), 3);
+        assert!(engine
+            .exit_logs
+            .iter()
+            .all(|log| log.trigger_assumption == Some(TriggerPathAssumption::Optimistic)));
+
+        // Closed positions take no further updates
+        assert!(engine.process_price_update(execution_id, Decimal::new(80, 0), token.liquidity_usd).is_none());
+    }
+
+    #[test]
+    fn test_trade_record_joins_partial_and_trailing_exits() {
+        let mut engine = StrikeBoxEngine::new(fee_free_config(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let position = create_test_position(&token, Direction::Long, Utc::now());
+        let execution_id = position.execution_id;
+        engine.record_entry(create_test_entry(&position));
+        engine.portfolio.long_book.positions.push(position);
+        let open = create_test_position(&token, Direction::Short, Utc::now());
+        engine.record_entry(create_test_entry(&open));
+        engine.portfolio.short_book.positions.push(open);
+
+        // Dip 3%, TP1 at 115 arms the trailing stop, TP2 at 130, peak 140, trail out at 126.
+        // Each step stays above the trail of the one before so only the last crosses it.
+        for price in [97, 110, 116, 124, 132, 140, 125] {
+            engine.process_price_update(execution_id, Decimal::new(price, 0), token.liquidity_usd).unwrap();
         }
-        if direction == Direction::Short {
-            validation.add_gate("squeeze_risk", GateResult::Passed, None);
+
+        let records = engine.trade_records(&TradeFilter { direction: Some(Direction::Long), ..TradeFilter::default() });
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.status, TradeStatus::Closed);
+        let slices: Vec<(ExitType, Decimal)> = record.exits.iter().map(|e| (e.exit_type, e.exit_size_pct)).collect();
+        assert_eq!(slices, vec![
+            (ExitType::TakeProfit1, Decimal::new(33, 2)),
+            (ExitType::TakeProfit2, Decimal::new(33, 2)),
+            (ExitType::StopLoss, Decimal::new(34, 2)),
+        ]);
+        assert_eq!(record.exited_size_pct(), Decimal::ONE);
+        assert_eq!(record.realized_pnl_usd, Decimal::new(2_369, 0));
+        assert_eq!(record.weighted_avg_exit_price, Some(Decimal::new(12_369, 2)));
+        assert_eq!(record.max_favorable_excursion_pct, Some(Decimal::new(40, 2)));
+        assert_eq!(record.max_adverse_excursion_pct, Some(Decimal::new(3, 2)));
+        assert!(record.provisional_exit.is_none());
+
+        let open = engine.trade_records(&TradeFilter { status: Some(TradeStatus::Open), ..TradeFilter::default() });
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].entry.direction, Direction::Short);
+        assert!(open[0].exits.is_empty());
+        let provisional = open[0].provisional_exit.as_ref().unwrap();
+        assert_eq!((provisional.mark_price, provisional.remaining_size_pct), (Decimal::new(100, 0), Decimal::ONE));
+    }
+
+    #[test]
+    fn test_liquidation_fires_before_a_stop_beyond_it() {
+        let token = create_test_token();
+        let d = |n| Decimal::new(n, 0);
+        let config = fee_free_config(); // 0.5% maintenance margin
+        let mut long = create_test_position(&token, Direction::Long, Utc::now()); // Stop 95
+        assert_eq!(long.liquidation_price(config.risk_controller.maintenance_margin_pct), None);
+
+        // 10x liquidates at 90.5, past the stop, so the stop fires first
+        long.leverage = d(10);
+        assert_eq!(long.liquidation_price(config.risk_controller.maintenance_margin_pct), Some(Decimal::new(905, 1)));
+        let resolution = StrikeBoxEngine::resolve_triggers(&long, d(100), d(89), &config);
+        assert_eq!(resolution.events, vec![TriggerEvent::StopLoss { price: d(95) }]);
+
+        // 25x liquidates at 96.5, before the stop is reached
+        long.leverage = d(25);
+        assert!(StrikeBoxEngine::resolve_triggers(&long, d(100), d(97), &config).events.is_empty());
+        let resolution = StrikeBoxEngine::resolve_triggers(&long, d(100), d(89), &config);
+        assert_eq!(resolution.events, vec![TriggerEvent::Liquidation { price: Decimal::new(965, 1) }]);
+
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
+        let execution_id = long.execution_id;
+        engine.book_position(long);
+        let (_, exits) = engine.process_price_update(execution_id, d(96), token.liquidity_usd).unwrap();
+        assert_eq!(exits.len(), 1);
+        assert_eq!(exits[0].exit_type, ExitType::Liquidation);
+        assert_eq!(exits[0].realized_pnl_usd, Decimal::new(-350, 0));
+        let position = &engine.portfolio.long_book.positions[0];
+        assert_eq!(position.status, PositionStatus::Liquidated);
+        assert!(!position.is_open());
+        assert!(engine.process_price_update(execution_id, d(90), token.liquidity_usd).is_none());
+
+        // A liquidation the venue reports is booked with its fees
+        let short = create_test_position(&token, Direction::Short, Utc::now());
+        let short_id = short.execution_id;
+        engine.book_position(short);
+        let exit = engine.mark_liquidated(short_id, d(104), d(12)).unwrap();
+        assert_eq!(exit.realized_pnl_usd, Decimal::new(-412, 0));
+        assert_eq!(engine.portfolio.short_book.realized_pnl_usd, Decimal::new(-412, 0));
+        assert_eq!(engine.portfolio.short_book.positions[0].status, PositionStatus::Liquidated);
+        assert!(engine.mark_liquidated(short_id, d(104), d(12)).is_none());
+        assert_eq!(engine.portfolio.liquidation_count, 2);
+        assert!(engine.execute_command(OperationalCommand::Health).message.contains("Liquidations: 2"));
+    }
+
+    #[test]
+    fn test_validation_notes_a_stop_beyond_liquidation() {
+        let token = create_test_token();
+        let note = |engine: &StrikeBoxEngine, direction| {
+            let validation = engine.validate_entry(&token, direction);
+            assert!(validation.all_passed);
+            validation.gates.into_iter().find(|g| g.gate_name == "liquidation_distance").map(|g| g.reason)
+        };
+
+        // Unlevered positions never liquidate, so the gate records nothing
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        assert_eq!(note(&engine, Direction::Long), None);
+
+        // 5x liquidates 19.5% from entry, well past either stop
+        engine.config.position_sizing.leverage = Decimal::new(5, 0);
+        assert_eq!(note(&engine, Direction::Long), Some(None));
+
+        // 12.5x liquidates 7.5% from entry, short of the 8% stops either way; entries still pass
+        engine.config.position_sizing.leverage = Decimal::new(125, 1);
+        for direction in [Direction::Long, Direction::Short] {
+            let warning = note(&engine, direction).flatten().unwrap();
+            assert!(warning.contains("Stop 8.00% from entry is beyond liquidation at 7.50%"), "{}", warning);
         }
+    }
 
-        if !self.portfolio.net_exposure_valid(&self.config.risk_controller) {
-            validation.add_gate(
-                "net_exposure",
-                GateResult::Failed,
-                Some("Net exposure outside bounds".to_string()),
-            );
-            return validation;
+    #[test]
+    fn test_gate_benchmark_times_every_gate_run() {
+        let engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let report = engine.gate_benchmark(1_000);
+        assert_eq!(report.samples, 1_000);
+        let calls = |name: &str| report.gates.iter().find(|g| g.gate_name == name).map_or(0, |g| g.calls_count);
+        assert_eq!(calls("system_state"), 1_000);
+        assert_eq!(calls("data_staleness"), 1_000);
+        assert!(calls("liquidity_range") < 1_000); // Stale snapshots stop at data_staleness
+        assert!(report.gates.windows(2).all(|w| w[0].p95_ms >= w[1].p95_ms));
+        assert!(report.gates.iter().all(|g| g.p50_ms <= g.p95_ms && g.p95_ms <= g.p99_ms));
+        assert!(report.bottleneck.is_some());
+        assert!(report.parallel_mean_ms <= report.sequential_mean_ms);
+        assert!(report.to_string().starts_with("gate "));
+        assert!(engine.rejection_logs.is_empty());
+    }
+
+    #[test]
+    fn test_compact_books_drops_only_old_closed_positions() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0))
+            .with_position_retention(chrono::Duration::hours(24));
+        let token = create_test_token();
+        let long_ago = Utc::now() - chrono::Duration::days(3);
+        let mut positions: Vec<Position> = (0..4).map(|_| create_test_position(&token, Direction::Long, long_ago)).collect();
+        positions[0].status = PositionStatus::Closed;
+        positions[0].closed_at = Some(Utc::now() - chrono::Duration::days(2));
+        positions[1].status = PositionStatus::Liquidated; // Closed before closed_at existed; ages from entry
+        positions[2].apply_partial_exit(Decimal::ONE);
+        positions[3].apply_partial_exit(Decimal::new(33, 2));
+        assert_eq!(positions[2].status, PositionStatus::Closed);
+        assert!(positions[2].closed_at.is_some());
+        let kept = [positions[2].execution_id, positions[3].execution_id];
+        engine.portfolio.long_book.positions = positions;
+        engine.portfolio.long_book.realized_pnl_usd = Decimal::new(1_234, 0);
+        assert_eq!(engine.portfolio.long_book.open_position_count(), 1);
+
+        assert_eq!(engine.compact_books(chrono::Duration::hours(24)), 2);
+        let remaining: Vec<Uuid> = engine.portfolio.long_book.positions.iter().map(|p| p.execution_id).collect();
+        assert_eq!(remaining, kept);
+        assert_eq!(engine.portfolio.long_book.realized_pnl_usd, Decimal::new(1_234, 0));
+
+        // Tick compacts on its first run and then hourly
+        engine.position_retention = chrono::Duration::zero();
+        engine.tick();
+        assert_eq!(engine.portfolio.long_book.positions.len(), 1);
+        engine.portfolio.long_book.positions[0].apply_partial_exit(Decimal::ONE);
+        engine.tick();
+        assert_eq!(engine.portfolio.long_book.positions.len(), 1);
+    }
+
+    #[test]
+    fn test_threshold_report_attributes_closed_trades_to_margins() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let marginal = TokenSnapshot { holder_count: 26, ..create_test_token() }; // 4% above the minimum of 25
+        let comfortable = create_test_token();
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        engine.record_decision(&marginal, Direction::Long, ids[0]);
+        engine.record_decision(&comfortable, Direction::Long, ids[1]);
+        engine.record_decision(&comfortable, Direction::Long, ids[2]);
+        let margins = &engine.outcome_attribution().pending_decision(ids[0]).unwrap().margins;
+        assert_eq!(margins["token_validation.holder_count_min"], Decimal::new(4, 2));
+        assert!(!margins.contains_key("token_validation.single_wallet_max_pct")); // Shorts only
+
+        engine.record_exit(create_test_exit(ids[0], Decimal::new(-500, 0), 60));
+        engine.record_exit(create_test_exit(ids[1], Decimal::new(800, 0), 60));
+        // Partial exits settle only once they cover the full size
+        let partial = ExitLog { exit_size_pct: Decimal::new(5, 1), ..create_test_exit(ids[2], Decimal::new(300, 0), 60) };
+        engine.record_exit(partial.clone());
+        assert_eq!(engine.threshold_report().open_decisions, 1);
+        engine.record_exit(partial);
+
+        engine.rejection_logs.push(RejectionLog {
+            timestamp: Utc::now(),
+            token_address: "near".to_string(),
+            token_symbol: "NEAR".to_string(),
+            direction: Direction::Long,
+            rejection_reason: "too few holders".to_string(),
+            failed_gate: "holder_distribution".to_string(),
+            safety_score: None,
+            liquidity_usd: None,
+            config_key: Some("token_validation.holder_count_min".to_string()),
+            observed_value: Some(Decimal::from(24)),
+        });
+
+        let report = engine.threshold_report();
+        assert_eq!(report.open_decisions, 0);
+        let holders = &report.thresholds[0];
+        assert_eq!(holders.config_key, "token_validation.holder_count_min");
+        assert!(holders.marginal_losing);
+        assert_eq!(holders.marginal_accepted.expectancy_usd(), Some(Decimal::new(-500, 0)));
+        assert_eq!(holders.comfortably_accepted.trades, 2);
+        assert_eq!(holders.comfortably_accepted.win_rate(), Some(Decimal::ONE));
+        assert_eq!(holders.comfortably_accepted.expectancy_usd(), Some(Decimal::new(700, 0)));
+        assert_eq!(holders.near_miss_rejections, 1);
+
+        let total: u32 = report.by_safety_decile.values().map(|s| s.trades).sum();
+        assert_eq!(total, 3);
+        assert_eq!(report.by_liquidity_tier["750000"].total_pnl_usd, Decimal::new(900, 0));
+    }
+
+    #[test]
+    fn test_pending_decisions_are_bounded() {
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0))
+            .with_outcome_attribution(attribution::OutcomeAttribution::with_max_pending(3));
+        let token = create_test_token();
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        for id in &ids {
+            engine.record_decision(&token, Direction::Long, *id);
         }
-        validation.add_gate("net_exposure", GateResult::Passed, None);
+        // Recording the same entry again replaces its decision
+        engine.record_decision(&token, Direction::Long, ids[1]);
+
+        let attribution = engine.outcome_attribution();
+        assert_eq!(attribution.pending.len(), 3);
+        assert!(attribution.pending_decision(ids[0]).is_none());
+        assert!(attribution.pending_decision(ids[1]).is_some());
+        assert_eq!(attribution.pending.last().unwrap().execution_id, ids[1]);
+
+        // An exit for a dropped decision is ignored
+        engine.record_exit(create_test_exit(ids[0], Decimal::new(100, 0), 60));
+        assert_eq!(engine.threshold_report().by_safety_decile.values().map(|s| s.trades).sum::<u32>(), 0);
+    }
 
-        validation
+    #[test]
+    fn test_risk_features_scaled_to_config_bounds() {
+        let config = TokenValidationConfig::default();
+        let features = create_test_token().to_risk_features(&config);
+        assert_eq!(features[LIQ_USD_IDX], 0.75);
+        assert_eq!(features[LIQ_RANGE_RATIO_IDX], 0.5);
+        assert_eq!(features[HOLDER_COUNT_IDX], 1.0); // 60 holders saturates at the preferred 50
+        assert_eq!(features[CONCENTRATION_IDX], 0.75);
+        assert_eq!(features[AGE_HOURS_IDX], 1.0);
+        assert_eq!((features[CONTRACT_VERIFIED_IDX], features[PROXY_IDX]), (1.0, 0.0));
+        assert_eq!(features[SINGLE_WALLET_PCT_IDX], 0.6);
+
+        let thin = TokenSnapshot { liquidity_usd: Decimal::new(100_000, 0), ..create_test_token() };
+        let features = thin.to_risk_features(&config);
+        assert_eq!((features[LIQ_USD_IDX], features[LIQ_RANGE_RATIO_IDX]), (0.1, 0.0));
+        assert!(features.iter().all(|f| (0.0..=1.0).contains(f)));
     }
 
-    pub fn calculate_position_size(&self, token: &TokenSnapshot, direction: Direction) -> Decimal {
-        let base_max_pct = LiquidityScaler::max_position_pct(token.liquidity_usd);
-        let max_usd = self.portfolio.total_capital_usd * base_max_pct;
-        let pool_limit_usd = LiquidityScaler::max_order_vs_pool(token.liquidity_usd, direction);
-        max_usd.min(pool_limit_usd)
+    #[test]
+    fn test_liquidity_imbalance_scoring() {
+        let config = SafetyScoreConfig::default();
+        let validation = TokenValidationConfig::default();
+        let balanced = create_test_token();
+        assert_eq!(balanced.liquidity_imbalance_ratio(), Decimal::ZERO);
+
+        let mut skewed = create_test_token();
+        skewed.bid_depth_usd = Decimal::new(600_000, 0);
+        skewed.ask_depth_usd = Decimal::new(150_000, 0);
+        assert_eq!(skewed.liquidity_imbalance_ratio(), Decimal::new(6, 1));
+
+        let mut leaning = create_test_token();
+        leaning.bid_depth_usd = Decimal::new(450_000, 0);
+        leaning.ask_depth_usd = Decimal::new(300_000, 0);
+        assert_eq!(leaning.liquidity_imbalance_ratio(), Decimal::new(2, 1));
+
+        let balanced_score = SafetyScore::calculate(&balanced, &config, &validation);
+        let leaning_score = SafetyScore::calculate(&leaning, &config, &validation);
+        let skewed_score = SafetyScore::calculate(&skewed, &config, &validation);
+        assert_eq!(balanced_score.liquidity_imbalance_score, Decimal::ONE);
+        assert_eq!(leaning_score.liquidity_imbalance_score, Decimal::new(5, 1));
+        assert_eq!(skewed_score.liquidity_imbalance_score, Decimal::ZERO);
+        assert_eq!(
+            balanced_score.total_score - skewed_score.total_score,
+            config.liquidity_imbalance_weight
+        );
+
+        let mut empty = create_test_token();
+        empty.bid_depth_usd = Decimal::ZERO;
+        empty.ask_depth_usd = Decimal::ZERO;
+        assert_eq!(empty.liquidity_imbalance_ratio(), Decimal::ONE);
     }
 
-    pub fn execute_command(&mut self, command: OperationalCommand) -> CommandResponse {
-        let (success, message) = match command {
-            OperationalCommand::PauseLongs => {
-                self.portfolio.state = SystemState::PausedLongs;
-                (true, "Long entries paused".to_string())
-            }
-            OperationalCommand::PauseShorts => {
-                self.portfolio.state = SystemState::PausedShorts;
-                (true, "Short entries paused".to_string())
-            }
-            OperationalCommand::PauseAll => {
-                self.portfolio.state = SystemState::PausedAll;
-                (true, "All entries paused".to_string())
-            }
-            OperationalCommand::Resume => {
-                let drawdown_state = self.portfolio.check_drawdown_limits(&self.config.risk_controller);
-                if drawdown_state == SystemState::Active {
-                    self.portfolio.state = SystemState::Active;
-                    (true, "System resumed".to_string())
-                } else {
-                    (false, format!("Cannot resume - drawdown limits require {:?}", drawdown_state))
-                }
-            }
-            OperationalCommand::Status => {
-                let msg = format!(
-                    "State: {:?} | Longs: {}/{} | Shorts: {}/{} | Gross: {:.1}% | Net: {:.1}%",
-                    self.portfolio.state,
-                    self.portfolio.long_book.position_count(),
-                    self.portfolio.long_book.max_positions,
-                    self.portfolio.short_book.position_count(),
-                    self.portfolio.short_book.max_positions,
-                    self.portfolio.gross_exposure_pct * Decimal::new(100, 0),
-                    self.portfolio.net_exposure_pct * Decimal::new(100, 0)
-                );
-                (true, msg)
-            }
-            OperationalCommand::Exposure => {
-                let msg = format!(
-                    "Gross: ${:.2} ({:.1}%) | Net: ${:.2} ({:.1}%)",
-                    self.portfolio.gross_exposure_usd,
-                    self.portfolio.gross_exposure_pct * Decimal::new(100, 0),
-                    self.portfolio.net_exposure_usd,
-                    self.portfolio.net_exposure_pct * Decimal::new(100, 0)
-                );
-                (true, msg)
-            }
-            OperationalCommand::Risk => {
-                let msg = format!(
-                    "Daily DD: {:.2}% | Weekly DD: {:.2}% | Monthly DD: {:.2}%",
-                    self.portfolio.daily_drawdown_pct * Decimal::new(100, 0),
-                    self.portfolio.weekly_drawdown_pct * Decimal::new(100, 0),
-                    self.portfolio.monthly_drawdown_pct * Decimal::new(100, 0)
-                );
-                (true, msg)
-            }
-            OperationalCommand::Health => {
-                let msg = format!(
-                    "State: {:?} | Capital: ${:.2} | Available: ${:.2}",
-                    self.portfolio.state,
-                    self.portfolio.total_capital_usd,
-                    self.portfolio.available_capital_usd
-                );
-                (true, msg)
-            }
-            OperationalCommand::CloseLongs => {
-                let count = self.portfolio.long_book.position_count();
-                (true, format!("Close {} long positions - MANUAL EXECUTION REQUIRED", count))
-            }
-            OperationalCommand::CloseShorts => {
-                let count = self.portfolio.short_book.position_count();
-                (true, format!("Close {} short positions - MANUAL EXECUTION REQUIRED", count))
-            }
-            OperationalCommand::CloseAll => {
-                let total = self.portfolio.long_book.position_count()
-                    + self.portfolio.short_book.position_count();
-                self.portfolio.state = SystemState::EmergencyHalt;
-                (true, format!("EMERGENCY: Close {} total positions", total))
-            }
-            _ => (true, "Command acknowledged".to_string()),
+    #[test]
+    fn test_auto_calibrate_shifts_weights_for_market_regime() {
+        let base = SafetyScoreConfig::default();
+        let calm = MarketConditions {
+            volatility_regime: VolatilityRegime::Normal,
+            liquidity_condition: LiquidityCondition::Normal,
+            market_trend: TrendStrength::Ranging,
         };
+        let unchanged = base.auto_calibrate(&calm);
+        assert_eq!((unchanged.liquidity_weight, unchanged.age_weight), (base.liquidity_weight, base.age_weight));
+
+        let volatile = base.auto_calibrate(&MarketConditions { volatility_regime: VolatilityRegime::High, ..calm });
+        assert_eq!(volatile.liquidity_weight, Decimal::new(30, 2));
+        assert_eq!(volatile.age_weight, Decimal::new(5, 2));
+        assert_eq!(volatile.weight_total(), Decimal::ONE);
+
+        let illiquid = base.auto_calibrate(&MarketConditions { liquidity_condition: LiquidityCondition::Illiquid, ..calm });
+        assert!(illiquid.holder_weight > base.holder_weight);
+        assert!(illiquid.contract_weight < base.contract_weight);
+        assert_eq!(illiquid.weight_total().round_dp(10), Decimal::ONE);
+        assert_eq!(illiquid.long_entry_min, base.long_entry_min);
+
+        // Session calibration leaves the config's weights alone and survives config updates
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        engine.calibrate_for_market(MarketConditions { volatility_regime: VolatilityRegime::High, ..calm });
+        assert_eq!(engine.safety_scoring().liquidity_weight, Decimal::new(30, 2));
+        assert_eq!(engine.config.safety_scoring.liquidity_weight, Decimal::new(25, 2));
+        let mut new_config = engine.config.clone();
+        new_config.safety_scoring.age_weight = Decimal::new(20, 2);
+        new_config.safety_scoring.contract_weight = Decimal::new(20, 2);
+        engine.apply_config_update(new_config, false);
+        assert_eq!(engine.safety_scoring().age_weight, Decimal::new(15, 2));
+        engine.clear_market_calibration();
+        assert_eq!(engine.safety_scoring().age_weight, Decimal::new(20, 2));
+        assert!(engine.market_conditions().is_none());
+    }
 
-        CommandResponse {
-            command: format!("{:?}", command),
-            success,
-            message,
-            data: None,
-            executed_at: Utc::now(),
+    #[derive(Clone, Default)]
+    struct CapturedLog(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLog {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
         }
     }
 
-    pub fn log_rejection(
-        &mut self,
-        token: &TokenSnapshot,
-        direction: Direction,
-        validation: &RiskValidation,
-    ) {
-        if let Some(failure) = validation.first_failure() {
-            let safety = SafetyScore::calculate(
-                token,
-                &self.config.safety_scoring,
-                &self.config.token_validation,
-            );
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CapturedLog {
+        type Writer = Self;
 
-            self.rejection_logs.push(RejectionLog {
-                timestamp: Utc::now(),
-                token_address: token.token_address.clone(),
-                token_symbol: token.token_symbol.clone(),
-                direction,
-                rejection_reason: failure.reason.clone().unwrap_or_default(),
-                failed_gate: failure.gate_name.clone(),
-                safety_score: Some(safety.total_score),
-                liquidity_usd: Some(token.liquidity_usd),
-            });
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
         }
     }
-}
 
-// ============================================================
-// SECTION 18: UNIT TESTS
-// ============================================================
+    #[test]
+    fn test_strike_lifecycle_json_events_carry_execution_id() {
+        let captured = CapturedLog::default();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(captured.clone())
+            .finish();
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let position = create_test_position(&token, Direction::Long, Utc::now());
+        let execution_id = position.execution_id;
+
+        tracing::subscriber::with_default(subscriber, || {
+            engine.validate_and_record_entry(&token, Direction::Long);
+            engine.record_entry(create_test_entry(&position));
+            engine.portfolio.long_book.positions.push(position);
+            engine.process_price_update(execution_id, Decimal::new(110, 0), token.liquidity_usd).unwrap();
+            engine.process_price_update(execution_id, Decimal::new(90, 0), token.liquidity_usd).unwrap();
+            engine.tick();
+        });
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("one JSON object per line"))
+            .collect();
+        let in_span = |event: &serde_json::Value, name: &str| {
+            event["spans"].as_array().is_some_and(|spans| spans.iter().any(|s| s["name"] == name))
+        };
+        let message = |event: &serde_json::Value| event["fields"]["message"].as_str().unwrap_or_default().to_string();
+
+        let strike_events: Vec<&serde_json::Value> = events.iter().filter(|e| in_span(e, "strike")).collect();
+        assert!(strike_events.iter().all(|e| e["span"]["execution_id"] == execution_id.to_string()));
+        let strike_messages: Vec<String> = strike_events.iter().map(|e| message(e)).collect();
+        assert_eq!(strike_messages.iter().filter(|m| *m == "position opened").count(), 1);
+        assert_eq!(strike_messages.iter().filter(|m| *m == "position exit").count(), 1);
+        assert_eq!(strike_messages.iter().filter(|m| *m == "position marked").count(), 2);
+        // Lifecycle events never escape the strike span
+        assert!(events
+            .iter()
+            .filter(|e| ["position opened", "position exit", "position marked"].contains(&message(e).as_str()))
+            .all(|e| in_span(e, "strike")));
+
+        let validation = events.iter().find(|e| message(e) == "entry validated").unwrap();
+        assert_eq!(validation["span"]["name"], "validation");
+        assert_eq!(validation["span"]["token"], "TEST");
+        assert!(events.iter().any(|e| in_span(e, "validation") && e["fields"]["gate"] == "system_state"));
+        assert!(events.iter().any(|e| in_span(e, "cycle") && message(e) == "portfolio aggregated"));
+    }
 
-    fn create_test_token() -> TokenSnapshot {
-        TokenSnapshot {
-            token_address: "0x1234567890abcdef".to_string(),
-            token_symbol: "TEST".to_string(),
-            liquidity_usd: Decimal::new(750_000, 0),
-            bid_depth_usd: Decimal::new(375_000, 0),
-            ask_depth_usd: Decimal::new(375_000, 0),
-            holder_count: 60,
-            top_10_concentration_pct: Decimal::new(45, 2),
-            largest_wallet_pct: Decimal::new(12, 2),
-            token_age_hours: 48,
-            contract_verified: true,
-            is_proxy_contract: false,
-            deployment_timestamp: Utc::now(),
-            snapshot_timestamp: Utc::now(),
+    fn two_sleeve_manager(config: SleeveManagerConfig) -> SleeveManager {
+        let mut manager = SleeveManager::new(config);
+        manager.add_sleeve("conservative", fee_free_config(), Decimal::new(1_000_000, 0)).unwrap();
+        manager.add_sleeve("aggressive", fee_free_config(), Decimal::new(1_000_000, 0)).unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_sleeve_drawdown_pauses_only_that_sleeve() {
+        let mut manager = two_sleeve_manager(SleeveManagerConfig::default());
+        let token = create_test_token();
+        let validation = manager.validate_entry("aggressive", &token, Direction::Long).unwrap();
+        assert!(validation.all_passed);
+        let (position, _) = manager
+            .open_position("aggressive", &token, &validation, Decimal::new(100, 0), Decimal::new(10_000, 0), test_safety(&token))
+            .unwrap();
+        assert_eq!(manager.sleeve("aggressive").unwrap().portfolio.long_book.total_allocation_usd, Decimal::new(10_000, 0));
+
+        // 6% down on the aggressive sleeve's capital, past its 5% unrealized daily limit
+        let aggressive = manager.sleeve_mut("aggressive").unwrap();
+        aggressive.portfolio.long_book.positions[0].unrealized_pnl_usd = Decimal::new(-60_000, 0);
+        manager.tick();
+
+        assert_eq!(manager.sleeve("aggressive").unwrap().portfolio.state, SystemState::SoftHalt);
+        assert_eq!(manager.sleeve("conservative").unwrap().portfolio.state, SystemState::Active);
+        assert!(!manager.validate_entry("aggressive", &token, Direction::Long).unwrap().all_passed);
+        assert!(manager.validate_entry("conservative", &token, Direction::Long).unwrap().all_passed);
+
+        // Closing releases the allocation and logs a manual exit
+        let exit = manager
+            .close_position("aggressive", position.execution_id, Decimal::new(90, 0), token.liquidity_usd, Decimal::ZERO)
+            .unwrap()
+            .unwrap();
+        assert_eq!(exit.exit_type, ExitType::Manual);
+        assert_eq!(exit.realized_pnl_usd, Decimal::new(-1_000, 0));
+        let aggressive = manager.sleeve("aggressive").unwrap();
+        assert_eq!(aggressive.portfolio.long_book.total_allocation_usd, Decimal::ZERO);
+        assert_eq!(aggressive.exit_logs.len(), 1);
+        // The realized loss counts toward equity and drawdown without waiting for a tick
+        assert_eq!(aggressive.portfolio.daily_realized_drawdown_pct, Decimal::new(1, 3));
+        let view = manager.combined_view();
+        assert_eq!(view.sleeves[0].equity_usd, Decimal::new(999_000, 0));
+        assert_eq!(view.equity_usd, Decimal::new(1_999_000, 0));
+        assert!(manager.close_position("aggressive", position.execution_id, Decimal::new(90, 0), token.liquidity_usd, Decimal::ZERO).unwrap().is_none());
+        assert_eq!(
+            manager.close_position("missing", position.execution_id, Decimal::new(90, 0), token.liquidity_usd, Decimal::ZERO).unwrap_err(),
+            SleeveError::UnknownSleeve("missing".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sleeve_commands_go_through_the_command_bus() {
+        let mut manager = two_sleeve_manager(SleeveManagerConfig::default());
+        let now = Utc::now();
+
+        // Closing every sleeve waits for a confirmation token and runs nothing until then
+        let request = command_request("alice", OperationalCommand::CloseAll, None);
+        let pending = manager.handle_command_at(request.clone(), now);
+        assert!(pending.requires_confirmation && !pending.success);
+        assert_eq!(manager.handle_command_at(request, now).confirm_token, pending.confirm_token);
+        assert!(manager.command_audit_log.is_empty());
+        assert_eq!(manager.sleeve("aggressive").unwrap().portfolio.state, SystemState::Active);
+
+        let confirm = command_request("bob", OperationalCommand::CloseAll, pending.confirm_token.clone());
+        let response = manager.handle_command_at(confirm.clone(), now);
+        assert!(response.success);
+        for name in ["conservative", "aggressive"] {
+            let sleeve = manager.sleeve(name).unwrap();
+            assert_eq!(sleeve.portfolio.state, SystemState::EmergencyHalt);
+            assert_eq!(sleeve.command_audit_log.len(), 1);
+            assert_eq!(sleeve.command_audit_log.first().unwrap().confirmation_requested_by.as_deref(), Some("alice"));
         }
+        assert_eq!(manager.command_audit_log.len(), 1);
+        assert_eq!(manager.command_audit_log.first().unwrap().issued_by, "bob");
+
+        // A retried request gets the first answer back without running again
+        assert_eq!(manager.handle_command_at(confirm, now).message, response.message);
+        assert_eq!(manager.command_audit_log.len(), 1);
+        assert_eq!(manager.sleeve("aggressive").unwrap().command_audit_log.len(), 1);
+
+        // Combined-view commands are audited once, by the manager
+        manager.handle_command_at(command_request("carol", OperationalCommand::Status, None), now);
+        assert_eq!(manager.command_audit_log.len(), 2);
+        assert_eq!(manager.sleeve("aggressive").unwrap().command_audit_log.len(), 1);
     }
 
     #[test]
-    fn test_default_config_values() {
-        let config = StrikeBoxConfig::default();
-        assert_eq!(config.token_validation.liquidity_min_usd, Decimal::new(500_000, 0));
-        assert_eq!(config.token_validation.liquidity_max_usd, Decimal::new(1_000_000, 0));
-        assert_eq!(config.token_validation.holder_count_min, 25);
-        assert_eq!(config.token_validation.token_age_min_hours, 24);
-        assert_eq!(config.position_sizing.long_book_max_positions, 10);
-        assert_eq!(config.position_sizing.short_book_max_positions, 3);
-        assert_eq!(config.stop_loss.short_fixed_pct, Decimal::new(8, 2));
+    fn test_sleeve_command_audit_log_is_bounded_and_spills_the_oldest() {
+        let sink = Arc::new(CollectingSink::default());
+        let config = SleeveManagerConfig { max_command_records: 1, ..SleeveManagerConfig::default() };
+        let mut manager = two_sleeve_manager(config).with_audit_sink(sink.clone());
+        let now = Utc::now();
+        manager.handle_command_at(command_request("alice", OperationalCommand::Status, None), now);
+        manager.handle_command_at(command_request("bob", OperationalCommand::Status, None), now);
+
+        assert_eq!(manager.command_audit_log.len(), 1);
+        assert_eq!(manager.command_audit_log.last().unwrap().issued_by, "bob");
+        let spilled = sink.records.lock().unwrap();
+        assert!(matches!(&spilled[..], [AuditRecord::Command(record)] if record.issued_by == "alice"));
     }
 
     #[test]
-    fn test_safety_score_calculation() {
-        let config = SafetyScoreConfig::default();
-        let validation = TokenValidationConfig::default();
+    fn test_cross_sleeve_opposite_direction_blocked() {
+        let mut manager = two_sleeve_manager(SleeveManagerConfig::default());
         let token = create_test_token();
-        let score = SafetyScore::calculate(&token, &config, &validation);
-        assert!(score.total_score > Decimal::new(60, 2));
-        assert!(score.qualifies_for_long(&config));
-        assert!(score.qualifies_for_short(&config));
+        manager
+            .sleeve_mut("conservative")
+            .unwrap()
+            .book_position(create_test_position(&token, Direction::Long, Utc::now()));
+
+        let rejected = manager.validate_entry("aggressive", &token, Direction::Short).unwrap();
+        assert!(!rejected.all_passed);
+        let gate = rejected.first_failure().unwrap();
+        assert_eq!(gate.gate_name, "cross_sleeve_direction");
+        assert!(gate.reason.as_deref().unwrap().contains("conservative"));
+        assert!(manager
+            .open_position("aggressive", &token, &rejected, Decimal::new(100, 0), Decimal::new(10_000, 0), test_safety(&token))
+            .is_err());
+
+        let other = TokenSnapshot { token_address: "0xother".to_string(), ..create_test_token() };
+        assert!(manager.validate_entry("aggressive", &other, Direction::Short).unwrap().all_passed);
+
+        let mut hedging = two_sleeve_manager(SleeveManagerConfig { allow_opposite_directions: true, ..SleeveManagerConfig::default() });
+        hedging
+            .sleeve_mut("conservative")
+            .unwrap()
+            .book_position(create_test_position(&token, Direction::Long, Utc::now()));
+        assert!(hedging.validate_entry("aggressive", &token, Direction::Short).unwrap().all_passed);
+
+        // Combined gross exposure caps entries even when each sleeve has room
+        let mut capped = two_sleeve_manager(SleeveManagerConfig {
+            max_gross_exposure_pct: Decimal::new(5, 3),
+            ..SleeveManagerConfig::default()
+        });
+        capped.sleeve_mut("conservative").unwrap().book_position(create_test_position(&token, Direction::Long, Utc::now()));
+        let rejected = capped.validate_entry("aggressive", &other, Direction::Long).unwrap();
+        assert_eq!(rejected.first_failure().unwrap().gate_name, "combined_gross_exposure");
     }
 
     #[test]
-    fn test_liquidity_scaler() {
-        assert_eq!(LiquidityScaler::max_position_pct(Decimal::new(550_000, 0)), Decimal::new(5, 3));
-        assert_eq!(LiquidityScaler::max_position_pct(Decimal::new(700_000, 0)), Decimal::new(1, 2));
-        assert_eq!(LiquidityScaler::max_position_pct(Decimal::new(800_000, 0)), Decimal::new(15, 3));
-        assert_eq!(LiquidityScaler::max_position_pct(Decimal::new(950_000, 0)), Decimal::new(2, 2));
+    fn test_combined_drawdown_over_summed_equity() {
+        let mut manager = two_sleeve_manager(SleeveManagerConfig::default());
+        let token = create_test_token();
+        for name in ["conservative", "aggressive"] {
+            manager.sleeve_mut(name).unwrap().book_position(create_test_position(&token, Direction::Long, Utc::now()));
+        }
+        manager.tick();
+        let mark = |manager: &mut SleeveManager, name: &str, pnl: i64| {
+            manager.sleeve_mut(name).unwrap().portfolio.long_book.positions[0].unrealized_pnl_usd = Decimal::new(pnl, 0);
+            manager.tick();
+        };
+
+        // 2,000,000 -> 1,960,000: 2% combined though the sleeve itself is 4% down
+        mark(&mut manager, "aggressive", -40_000);
+        let view = manager.combined_view();
+        assert_eq!(view.equity_usd, Decimal::new(1_960_000, 0));
+        assert_eq!(view.daily_drawdown_pct, Decimal::new(2, 2));
+        assert_eq!(view.sleeves[0].name, "aggressive");
+        assert_eq!(view.sleeves[0].daily_drawdown_pct, Decimal::new(4, 2));
+
+        // The other sleeve's gain sets a new summed high: no combined drawdown, sleeve still down 4%
+        mark(&mut manager, "conservative", 50_000);
+        let view = manager.combined_view();
+        assert_eq!(view.equity_usd, Decimal::new(2_010_000, 0));
+        assert_eq!(view.monthly_drawdown_pct, Decimal::ZERO);
+
+        // Off that high: 2,010,000 -> 1,909,500 is 5%
+        mark(&mut manager, "conservative", -50_500);
+        let view = manager.combined_view();
+        assert_eq!(view.weekly_drawdown_pct, Decimal::new(5, 2));
+        assert_eq!(view.gross_exposure_usd, Decimal::new(20_000, 0));
+
+        let response = manager.handle_command(command_request("ops", OperationalCommand::Risk, None));
+        assert!(response.message.starts_with("Combined Daily DD: 5.00%"));
+        assert_eq!(response.data.unwrap()["sleeves"].as_array().unwrap().len(), 2);
+
+        // Rebalancing needs unallocated capital in the source
+        assert!(matches!(
+            manager.rebalance("conservative", "aggressive", Decimal::new(995_000, 0)),
+            Err(SleeveError::Capital(CapitalError::InsufficientLiquidity { .. }))
+        ));
+        manager.rebalance("conservative", "aggressive", Decimal::new(100_000, 0)).unwrap();
+        assert_eq!(manager.sleeve("aggressive").unwrap().portfolio.total_capital_usd, Decimal::new(1_100_000, 0));
+        assert_eq!(manager.combined_view().weekly_drawdown_pct, Decimal::new(5, 2));
     }
 
     #[test]
-    fn test_engine_validation_passing() {
-        let config = StrikeBoxConfig::default();
-        let engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
+    fn test_exit_fees_come_out_of_realized_pnl() {
         let token = create_test_token();
-        let validation = engine.validate_entry(&token, Direction::Long);
-        assert!(validation.all_passed);
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        let dex_fees = *engine.config.fees.for_venue(DEX_VENUE);
+        assert_eq!(dex_fees.taker_bps, Decimal::new(30, 0));
+
+        // $10,000 in at 100; out at p pays 30 bps on $10,000 and on 100 * p, so the gross
+        // gain 100 * (p - 100) covers both exactly at p = 10,030 / 99.7
+        let position = create_test_position(&token, Direction::Long, Utc::now());
+        let execution_id = position.execution_id;
+        engine.book_position(position);
+        let break_even = Decimal::new(10_030, 0) / Decimal::new(997, 1);
+        let exit = engine.close_position(execution_id, break_even, token.liquidity_usd, Decimal::ZERO).unwrap();
+        let gross = Decimal::new(100, 0) * (break_even - Decimal::new(100, 0));
+        assert!((exit.fees_usd - gross).abs() < Decimal::new(1, 12));
+        assert!(exit.realized_pnl_usd.abs() < Decimal::new(1, 12));
+        assert_eq!(engine.portfolio.long_book.realized_pnl_usd, exit.realized_pnl_usd);
+        assert_eq!(engine.portfolio.long_book.costs_usd, exit.fees_usd);
+        assert_eq!(engine.exit_logs.iter().last().unwrap().fees_usd, exit.fees_usd);
+
+        // Take-profit levels rest on the book and pay the maker rate; a CEX venue falls back to
+        // the default schedule
+        let binance = VenueFees::new(Decimal::new(2, 0), Decimal::new(10, 0)).with_flat_usd(Decimal::ONE);
+        let config = StrikeBoxConfig { fees: FeeModel::zero().with_venue("binance", binance), ..fee_free_config() };
+        let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
+        let mut position = create_test_position(&token, Direction::Long, Utc::now());
+        position.venue = "Binance".to_string();
+        let execution_id = position.execution_id;
+        engine.book_position(position);
+        engine.process_price_update(execution_id, Decimal::new(114, 0), token.liquidity_usd).unwrap();
+        let (_, exits) = engine.process_price_update(execution_id, Decimal::new(116, 0), token.liquidity_usd).unwrap();
+        // 33% of the $11 entry fee, plus 2 bps on 33 tokens at 115 and $1 flat
+        assert_eq!(exits[0].fees_usd, Decimal::new(363, 2) + Decimal::new(1_759, 3));
+        assert_eq!(exits[0].realized_pnl_usd, Decimal::new(495, 0) - exits[0].fees_usd);
+
+        let impact = engine.simulate_entry_impact(&token, Direction::Long, Some(Decimal::new(10_000, 0)));
+        assert_eq!(impact.round_trip_fees_usd, Decimal::ZERO);
+        engine.config.fees = FeeModel::default();
+        let impact = engine.simulate_entry_impact(&token, Direction::Long, Some(Decimal::new(10_000, 0)));
+        assert_eq!(impact.round_trip_fees_usd, Decimal::new(60, 0));
+        assert_eq!(impact.fee_drag_pct, Decimal::new(6, 3));
     }
 
     #[test]
-    fn test_stop_loss_calculations() {
-        let config = StopLossConfig::default();
-        let entry_price = Decimal::new(100, 0);
-        let long_stop = config.long_stop_price(entry_price, Decimal::new(60, 2));
-        assert_eq!(long_stop, Decimal::new(95, 0));
-        let short_stop = config.short_stop_price(entry_price);
-        assert_eq!(short_stop, Decimal::new(108, 0));
+    fn test_drawdown_attribution_splits_books_and_costs() {
+        let mut engine = StrikeBoxEngine::new(fee_free_config(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let long = create_test_position(&token, Direction::Long, Utc::now());
+        let long_id = long.execution_id;
+        engine.book_position(long);
+        engine.book_position(create_test_position(&token, Direction::Short, Utc::now()));
+        engine.portfolio.update_drawdowns();
+        assert_eq!(engine.portfolio.drawdown_attribution(), DrawdownAttribution::default());
+
+        // Long marked down $1,000, short $500 against
+        engine.portfolio.long_book.positions[0].mark_to_market(Decimal::new(90, 0), token.liquidity_usd);
+        engine.portfolio.short_book.positions[0].mark_to_market(Decimal::new(105, 0), token.liquidity_usd);
+        engine.portfolio.long_book.update_unrealized_pnl();
+        engine.portfolio.short_book.update_unrealized_pnl();
+        // The long closes at 90 with 50 bps of slippage on $9,000: $45 of the $1,000 loss
+        engine.close_position(long_id, Decimal::new(90, 0), token.liquidity_usd, Decimal::new(50, 0)).unwrap();
+        engine.portfolio.update_drawdowns();
+
+        let attribution = engine.portfolio.drawdown_attribution();
+        assert_eq!(attribution.drawdown_usd, Decimal::new(1_500, 0));
+        assert_eq!(attribution.long_book_contribution, Decimal::new(955, 0) / Decimal::new(1_500, 0));
+        assert_eq!(attribution.short_book_contribution, Decimal::new(500, 0) / Decimal::new(1_500, 0));
+        assert_eq!(attribution.fee_drag_contribution, Decimal::new(45, 0) / Decimal::new(1_500, 0));
+        assert!(attribution.unexplained.abs() < Decimal::new(1, 20));
+
+        // A deposit moves the peak with it rather than reading as recovery
+        engine.deposit_capital(Decimal::new(100_000, 0)).unwrap();
+        assert_eq!(engine.portfolio.drawdown_attribution().drawdown_usd, Decimal::new(1_500, 0));
+        let risk = engine.execute_command(OperationalCommand::Risk);
+        assert_eq!(risk.data.unwrap()["drawdown_usd"], "1500");
     }
 
     #[test]
-    fn test_take_profit_calculations() {
-        let config = TakeProfitConfig::default();
-        let entry_price = Decimal::new(100, 0);
-        let long_tps = config.long_tp_prices(entry_price);
-        assert_eq!(long_tps[0], Decimal::new(115, 0));
-        assert_eq!(long_tps[1], Decimal::new(130, 0));
-        assert_eq!(long_tps[2], Decimal::new(150, 0));
+    fn test_slippage_cost_accumulates_and_pauses_entries() {
+        let mut engine = StrikeBoxEngine::new(fee_free_config(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let winners: Vec<Uuid> = (0..2)
+            .map(|_| {
+                let position = create_test_position(&token, Direction::Long, Utc::now());
+                let execution_id = position.execution_id;
+                engine.book_position(position);
+                execution_id
+            })
+            .collect();
+
+        // 100 tokens out at 120: $12,000 exited, 10 bps is $12 against $2,000 of P&L
+        let exit = engine
+            .close_position(winners[0], Decimal::new(120, 0), token.liquidity_usd, Decimal::new(10, 0))
+            .unwrap();
+        assert_eq!(exit.exit_size_usd, Decimal::new(12_000, 0));
+        assert_eq!(exit.slippage_cost_usd(), Decimal::new(12, 0));
+        assert_eq!(engine.portfolio.total_slippage_cost_usd, Decimal::new(12, 0));
+        assert_eq!(engine.portfolio.slippage_as_pct_of_pnl(), Some(Decimal::new(12, 0) / Decimal::new(2_012, 0)));
+        assert!(!engine.slippage_pause.is_triggered());
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+
+        // $240 more takes slippage past 1.5% of gross P&L
+        engine
+            .close_position(winners[1], Decimal::new(120, 0), token.liquidity_usd, Decimal::new(200, 0))
+            .unwrap();
+        let summary = engine.portfolio.portfolio_summary();
+        assert_eq!(summary.total_slippage_cost_usd, Decimal::new(252, 0));
+        assert_eq!(summary.realized_pnl_usd, Decimal::new(4_000, 0));
+        assert!(summary.slippage_as_pct_of_pnl.unwrap() > Decimal::new(15, 3));
+        assert_eq!(engine.slippage_pause.triggered.as_ref().unwrap().gross_pnl_usd, Decimal::new(4_252, 0));
+        assert_eq!(engine.portfolio.state, SystemState::PausedAll);
+
+        let status = engine.execute_command(OperationalCommand::Status);
+        assert_eq!(status.data.unwrap()["total_slippage_cost_usd"], "252");
+        assert!(engine.execute_command(OperationalCommand::Resume).success);
+        assert!(!engine.slippage_pause.is_triggered());
     }
 
     #[test]
-    fn test_operational_commands() {
-        let config = StrikeBoxConfig::default();
+    fn test_entry_impact_flags_stop_crossing_daily_halt() {
+        let mut engine = StrikeBoxEngine::new(fee_free_config(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let loser = TokenSnapshot { token_address: "0xloser".to_string(), ..create_test_token() };
+        let mut held = create_test_position(&loser, Direction::Long, Utc::now());
+        held.unrealized_pnl_usd = Decimal::new(-49_700, 0);
+        let held_id = held.execution_id;
+        engine.book_position(held);
+        engine.tick();
+        assert_eq!(engine.portfolio.daily_drawdown_pct, Decimal::new(497, 4));
+
+        let impact = engine.simulate_entry_impact(&token, Direction::Long, None);
+        assert!(impact.validation.all_passed);
+        // 1% of a $750k pool binds before the 1% capital tier on $1M
+        assert_eq!((impact.position_size_usd, impact.sizing_cap), (Decimal::new(7_500, 0), SizingCap::PoolDepth));
+        assert_eq!(impact.stop_loss_usd, impact.position_size_usd * impact.stop_loss_pct);
+        assert!(impact.worst_case_daily_drawdown_pct > Decimal::new(5, 2));
+        assert!(impact.crosses_daily_halt);
+        assert_eq!(impact.pre_gross_exposure_pct, Decimal::new(1, 2));
+        assert_eq!(impact.post_gross_exposure_pct, Decimal::new(175, 4));
+        assert_eq!(impact.post_available_capital_usd, Decimal::new(982_500, 0));
+        assert_eq!(impact.book_slots_remaining, 8);
+
+        let small = engine.simulate_entry_impact(&token, Direction::Long, Some(Decimal::new(1_000, 0)));
+        assert_eq!(small.sizing_cap, SizingCap::Override);
+        assert!(!small.crosses_daily_halt);
+        assert_eq!(engine.portfolio.long_book.position_count(), 1);
+
+        // Closing the loser at 90 realizes $1,000 and frees its $10,000
+        let exit = engine.simulate_exit(held_id, Decimal::new(90, 0)).unwrap();
+        assert_eq!(exit.realized_pnl_usd, Decimal::new(-1_000, 0));
+        assert_eq!(exit.post_gross_exposure_pct, Decimal::ZERO);
+        assert_eq!(exit.post_available_capital_usd, Decimal::new(1_000_000, 0));
+        assert_eq!(engine.portfolio.long_book.positions[0].status, PositionStatus::Open);
+        assert!(engine.simulate_exit(Uuid::new_v4(), Decimal::new(90, 0)).is_none());
+    }
+
+    #[test]
+    fn test_failure_classes_pause_by_their_own_rules() {
+        use failures::{ExecutionFailure, FailureClass, FailureResponse};
+
+        // Missed fills are counted, never paused on
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(1_000_000, 0));
+        for _ in 0..5 {
+            let response = engine.record_failure(ExecutionFailure::new(FailureClass::MarketMoved, DEX_VENUE, "not filled at limit"));
+            assert_eq!(response, FailureResponse::Recorded);
+        }
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+        assert_eq!(engine.portfolio.consecutive_failures, 0);
+        assert_eq!(engine.failure_summary().counts[&FailureClass::MarketMoved], 5);
+        assert_eq!(
+            engine.record_failure(ExecutionFailure::new(FailureClass::Transient, DEX_VENUE, "timeout")),
+            FailureResponse::Retry { attempt: 1, backoff_ms: 250 }
+        );
+
+        // Venue errors pause only the venue they came from
+        engine.config.cex_validation.listings = ListingRegistry::default()
+            .with_listing("kraken", "PEPE/USDT", Utc::now() - chrono::Duration::days(30))
+            .with_listing("binance", "PEPE/USDT", Utc::now() - chrono::Duration::days(30));
+        let book = create_test_order_book(100, 10, 200_000);
+        let kraken = TokenSnapshot::from_order_book("PEPE/USDT", &book, "kraken");
+        let binance = TokenSnapshot::from_order_book("PEPE/USDT", &book, "binance");
+        assert!(engine.validate_entry(&kraken, Direction::Short).all_passed);
+        assert_eq!(
+            engine.record_failure(ExecutionFailure::new(FailureClass::VenueError, "Kraken", "503 Service Unavailable")),
+            FailureResponse::Recorded
+        );
+        assert_eq!(
+            engine.record_failure(ExecutionFailure::new(FailureClass::VenueError, "Kraken", "maintenance")),
+            FailureResponse::VenuePaused { venue: "kraken".to_string() }
+        );
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+        let validation = engine.validate_entry(&kraken, Direction::Short);
+        assert_eq!(validation.first_failure().unwrap().gate_name, "venue_availability");
+        assert!(engine.validate_entry(&binance, Direction::Short).all_passed);
+        assert!(engine.validate_entry(&create_test_token(), Direction::Long).all_passed);
+
+        let health = engine.execute_command(OperationalCommand::Health);
+        assert!(health.message.contains("Failures: 1 transient, 5 market moved, 2 venue, 0 fatal | Paused venues: kraken"));
+        assert_eq!(health.data.unwrap()["venue_errors"]["kraken"], 2);
+
+        // The system is still Active, yet Resume lifts the venue pause
+        let resumed = engine.execute_command(OperationalCommand::Resume);
+        assert!(resumed.success, "{}", resumed.message);
+        assert_eq!(resumed.message, "Venue pauses lifted: kraken");
+        assert!(engine.validate_entry(&kraken, Direction::Short).all_passed);
+        assert!(!engine.execute_command(OperationalCommand::Resume).success);
+        engine.record_failure(ExecutionFailure::new(FailureClass::VenueError, "kraken", "maintenance"));
+        assert!(engine.is_venue_paused("kraken"));
+
+        // A single fatal failure pauses everything until Resume
+        assert_eq!(
+            engine.record_failure(ExecutionFailure::new(FailureClass::Fatal, "binance", "invalid API key")),
+            FailureResponse::PausedAll
+        );
+        assert_eq!(engine.portfolio.state, SystemState::PausedAll);
+        assert_eq!(engine.validate_entry(&binance, Direction::Short).first_failure().unwrap().gate_name, "system_state");
+
+        assert!(engine.execute_command(OperationalCommand::Resume).success);
+        assert_eq!(engine.portfolio.state, SystemState::Active);
+        assert!(!engine.is_venue_paused("kraken"));
+        assert!(engine.validate_entry(&kraken, Direction::Short).all_passed);
+    }
+
+    #[test]
+    fn test_fatal_failure_pauses_at_once_and_ends_recovery() {
+        use failures::{ExecutionFailure, FailureClass, FailureResponse};
+
+        let mut config = StrikeBoxConfig::default();
+        config.risk_controller.failure_policy.fatal.threshold = 5;
         let mut engine = StrikeBoxEngine::new(config, Decimal::new(1_000_000, 0));
-        let response = engine.execute_command(OperationalCommand::PauseAll);
-        assert!(response.success);
+        let token = create_test_token();
+
+        // Each execution gets its own retry budget
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let timeout = |execution_id| ExecutionFailure::new(FailureClass::Transient, DEX_VENUE, "timeout").for_execution(execution_id);
+        for attempt in 1..=3 {
+            assert!(matches!(engine.record_failure(timeout(first)), FailureResponse::Retry { attempt: a, .. } if a == attempt));
+        }
+        assert_eq!(engine.record_failure(timeout(second)), FailureResponse::Retry { attempt: 1, backoff_ms: 250 });
+        assert_eq!(engine.record_failure(timeout(first)), FailureResponse::RetryBudgetExhausted);
+        assert_eq!(engine.portfolio.consecutive_failures, 1);
+
+        for _ in 0..3 {
+            engine.record_execution_failure();
+        }
+        assert_eq!(engine.attempt_recovery(&token).state, RecoveryState::RecoveryAttempted);
+        assert_eq!(engine.portfolio.state, SystemState::Recovering);
+
+        // Whatever the threshold, the first fatal failure pauses and drops the recovery
+        assert_eq!(
+            engine.record_failure(ExecutionFailure::new(FailureClass::Fatal, DEX_VENUE, "insufficient funds")),
+            FailureResponse::PausedAll
+        );
         assert_eq!(engine.portfolio.state, SystemState::PausedAll);
+        assert_eq!(engine.recovery_state(), None);
     }
-}
 
+    #[test]
+    fn test_mark_all_books_resolves_triggers_and_skips_unpriced_tokens() {
+        let mut engine = StrikeBoxEngine::new(fee_free_config(), Decimal::new(1_000_000, 0));
+        let token = create_test_token();
+        let other = TokenSnapshot { token_address: "0xother".to_string(), ..create_test_token() };
+        engine.book_position(create_test_position(&token, Direction::Long, Utc::now()));
+        engine.book_position(create_test_position(&token, Direction::Long, Utc::now()));
+        engine.book_position(create_test_position(&other, Direction::Long, Utc::now()));
+        engine.book_position(create_test_position(&other, Direction::Short, Utc::now()));
+        let allocation = engine.portfolio.long_book.total_allocation_usd;
+
+        // 110 marks both positions in the token without reaching an exit level
+        let prices = HashMap::from([(token.token_address.clone(), Decimal::new(110, 0))]);
+        let updates = engine.mark_all_books(&prices);
+        assert_eq!(updates.len(), 2);
+        assert!(updates.iter().all(|(_, _, exits)| exits.is_empty()));
+        let marked = &engine.portfolio.long_book.positions[0];
+        assert!(marked.unrealized_pnl_usd > Decimal::ZERO);
+        assert_eq!(engine.portfolio.long_book.unrealized_pnl_usd, marked.unrealized_pnl_usd * Decimal::TWO);
+
+        // 94 crosses the stop on both, which exit, log and release their allocation
+        let prices = HashMap::from([(token.token_address.clone(), Decimal::new(94, 0))]);
+        let updates = engine.mark_all_books(&prices);
+        assert_eq!(updates.len(), 2);
+        for (execution_id, _, exits) in &updates {
+            assert_eq!(exits.len(), 1);
+            assert_eq!(exits[0].exit_type, ExitType::StopLoss);
+            assert_eq!(exits[0].execution_id, *execution_id);
+        }
+        assert_eq!(engine.exit_logs.len(), 2);
+        assert_eq!(engine.price_history.prices(&token.token_address), vec![Decimal::new(110, 0), Decimal::new(94, 0)]);
+        assert_eq!(engine.portfolio.long_book.total_allocation_usd, allocation - Decimal::new(20_000, 0));
+        assert_eq!(engine.portfolio.long_book.positions[2].current_price, Decimal::new(100, 0));
+        assert_eq!(engine.portfolio.short_book.unrealized_pnl_usd, Decimal::ZERO);
+    }
+}
@@ -0,0 +1,195 @@
+//! ============================================================
+//! OPEN POSITION RESCORING
+//! ============================================================
+//! The safety score behind an entry is frozen on the position,
+//! but liquidity drains and holders concentrate while it is held.
+//! Rescoring recomputes the score from a fresh snapshot, appends
+//! it to the position's score history and flags a hold review
+//! when the score is below time_control.hold_review_threshold or
+//! more than time_control.hold_review_max_drop under the entry
+//! score. A review is advisory: nothing is exited.
+//!
+//! Each component's weighted change against the entry breakdown
+//! names the factor that deteriorated most. Positions booked
+//! before breakdowns were recorded are measured against full
+//! marks instead. Positions with no fresh snapshot are reported
+//! as stale rather than rescored.
+//! ============================================================
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    Direction, Position, SafetyScore, SafetyScoreConfig, TimeControlConfig, TokenSnapshot, TokenValidationConfig,
+    SCORE_HISTORY_CAPACITY,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SafetyComponent {
+    Liquidity,
+    Holders,
+    Age,
+    Contract,
+    LiquidityImbalance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ComponentChange {
+    pub component: SafetyComponent,
+    pub weight: Decimal,
+    pub at_entry: Option<Decimal>, // None when the position has no entry breakdown
+    pub current: Decimal,
+    pub weighted_change: Decimal, // Points this component added to the total since entry; negative when it fell
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HoldReviewReason {
+    BelowThreshold { threshold: Decimal },
+    DroppedSinceEntry { drop: Decimal, max_drop: Decimal },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RescoreOutcome {
+    Holding,
+    ReviewExit {
+        reasons: Vec<HoldReviewReason>,
+        driver: Option<SafetyComponent>, // Component with the largest weighted fall
+    },
+    Stale, // No fresh snapshot; unable to rescore
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RescoreResult {
+    pub execution_id: Uuid,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub direction: Direction,
+    pub score_at_entry: Decimal,
+    pub score: Option<Decimal>, // None when stale
+    pub components: Vec<ComponentChange>,
+    pub outcome: RescoreOutcome,
+    pub rescored_at: DateTime<Utc>,
+}
+
+impl RescoreResult {
+    pub fn needs_review(&self) -> bool {
+        matches!(self.outcome, RescoreOutcome::ReviewExit { .. })
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.outcome == RescoreOutcome::Stale
+    }
+}
+
+/// Weighted change of each component of `current` against `at_entry`, or against full
+/// marks when there is no entry breakdown
+pub fn component_changes(
+    current: &SafetyScore,
+    at_entry: Option<&SafetyScore>,
+    weights: &SafetyScoreConfig,
+) -> Vec<ComponentChange> {
+    let parts = |score: &SafetyScore| {
+        [
+            (SafetyComponent::Liquidity, score.liquidity_score),
+            (SafetyComponent::Holders, score.holder_score),
+            (SafetyComponent::Age, score.age_score),
+            (SafetyComponent::Contract, score.contract_score),
+            (SafetyComponent::LiquidityImbalance, score.liquidity_imbalance_score),
+        ]
+    };
+    let component_weights = [
+        weights.liquidity_weight,
+        weights.holder_weight,
+        weights.age_weight,
+        weights.contract_weight,
+        weights.liquidity_imbalance_weight,
+    ];
+    let entry_parts = at_entry.map(parts);
+    parts(current)
+        .into_iter()
+        .zip(component_weights)
+        .enumerate()
+        .map(|(i, ((component, now), weight))| {
+            let at_entry = entry_parts.map(|entry| entry[i].1);
+            ComponentChange {
+                component,
+                weight,
+                at_entry,
+                current: now,
+                weighted_change: weight * (now - at_entry.unwrap_or(Decimal::ONE)),
+            }
+        })
+        .collect()
+}
+
+/// Component whose weighted contribution fell furthest; None when none fell
+pub fn deterioration_driver(changes: &[ComponentChange]) -> Option<SafetyComponent> {
+    changes
+        .iter()
+        .filter(|c| c.weighted_change < Decimal::ZERO)
+        .min_by_key(|c| c.weighted_change)
+        .map(|c| c.component)
+}
+
+/// Rescores `position` from `snapshot`, recording the new total in its score history.
+/// A missing snapshot leaves the position untouched and reports it stale.
+pub fn rescore_position(
+    position: &mut Position,
+    snapshot: Option<&TokenSnapshot>,
+    weights: &SafetyScoreConfig,
+    validation: &TokenValidationConfig,
+    time_control: &TimeControlConfig,
+    now: DateTime<Utc>,
+) -> RescoreResult {
+    let mut result = RescoreResult {
+        execution_id: position.execution_id,
+        token_address: position.token_address.clone(),
+        token_symbol: position.token_symbol.clone(),
+        direction: position.direction,
+        score_at_entry: position.safety_score_at_entry,
+        score: None,
+        components: Vec::new(),
+        outcome: RescoreOutcome::Stale,
+        rescored_at: now,
+    };
+    let Some(snapshot) = snapshot else {
+        return result;
+    };
+
+    let score = SafetyScore::calculate(snapshot, weights, validation);
+    position.score_history.push((now, score.total_score));
+    if position.score_history.len() > SCORE_HISTORY_CAPACITY {
+        let excess = position.score_history.len() - SCORE_HISTORY_CAPACITY;
+        position.score_history.drain(..excess);
+    }
+
+    let mut reasons = Vec::new();
+    if score.total_score < time_control.hold_review_threshold {
+        reasons.push(HoldReviewReason::BelowThreshold { threshold: time_control.hold_review_threshold });
+    }
+    let drop = position.safety_score_at_entry - score.total_score;
+    if drop > time_control.hold_review_max_drop {
+        reasons.push(HoldReviewReason::DroppedSinceEntry { drop, max_drop: time_control.hold_review_max_drop });
+    }
+
+    result.score = Some(score.total_score);
+    result.components = component_changes(&score, position.safety_breakdown_at_entry.as_ref(), weights);
+    result.outcome = if reasons.is_empty() {
+        RescoreOutcome::Holding
+    } else {
+        let driver = deterioration_driver(&result.components);
+        warn!(
+            execution_id = %position.execution_id,
+            token = %position.token_symbol,
+            score = %score.total_score,
+            score_at_entry = %position.safety_score_at_entry,
+            driver = ?driver,
+            "safety score deteriorated, hold review advised"
+        );
+        RescoreOutcome::ReviewExit { reasons, driver }
+    };
+    result
+}
@@ -0,0 +1,219 @@
+//! ============================================================
+//! ACTIVITY SCHEDULE
+//! ============================================================
+//! A weekly policy for when entries may open. The week is 168
+//! hourly slots counted from 00:00 UTC Monday; a grid of ranges
+//! gives each slot a level: Normal, ReducedSize(factor) or
+//! Blocked. The activity_schedule gate rejects entries in Blocked
+//! slots and position sizing applies the ReducedSize factor.
+//!
+//! Either direction can override the shared grid. An empty grid
+//! is no policy: every slot is Normal. A non-empty grid must
+//! cover the whole week with no gaps or overlaps. Slots are read
+//! at the engine's evaluation time, so a backtest that sets it
+//! replays the schedule too.
+//! ============================================================
+
+use chrono::{DateTime, Datelike, Duration, DurationRound, Timelike, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::Direction;
+
+/// Hourly slots in the schedule's week
+pub const HOURS_PER_WEEK: u16 = 168;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ActivityLevel {
+    Normal,
+    ReducedSize(Decimal), // Share of the normal size, in (0, 1]
+    Blocked,
+}
+
+impl ActivityLevel {
+    /// Multiplier on the computed entry size
+    pub fn size_factor(self) -> Decimal {
+        match self {
+            ActivityLevel::Normal => Decimal::ONE,
+            ActivityLevel::ReducedSize(factor) => factor,
+            ActivityLevel::Blocked => Decimal::ZERO,
+        }
+    }
+}
+
+/// Hours `start_hour` up to, not including, `end_hour` of the week
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ActivityRange {
+    pub start_hour: u16,
+    pub end_hour: u16,
+    pub level: ActivityLevel,
+}
+
+impl ActivityRange {
+    pub fn new(start_hour: u16, end_hour: u16, level: ActivityLevel) -> Self {
+        Self { start_hour, end_hour, level }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActivityScheduleConfig {
+    #[serde(default)]
+    pub ranges: Vec<ActivityRange>, // Shared by both directions
+    #[serde(default)]
+    pub long_ranges: Option<Vec<ActivityRange>>, // Replaces `ranges` for longs
+    #[serde(default)]
+    pub short_ranges: Option<Vec<ActivityRange>>, // Replaces `ranges` for shorts
+}
+
+/// Hour of the week `at` falls in, 0 at 00:00 UTC Monday
+pub fn hour_of_week(at: DateTime<Utc>) -> u16 {
+    (at.weekday().num_days_from_monday() * 24 + at.hour()) as u16
+}
+
+impl ActivityScheduleConfig {
+    /// Grid for `direction`: its override if set, the shared ranges otherwise
+    pub fn grid(&self, direction: Direction) -> &[ActivityRange] {
+        let own = match direction {
+            Direction::Long => &self.long_ranges,
+            Direction::Short => &self.short_ranges,
+        };
+        own.as_deref().unwrap_or(&self.ranges)
+    }
+
+    pub fn level_at(&self, direction: Direction, at: DateTime<Utc>) -> ActivityLevel {
+        Self::level_in(self.grid(direction), hour_of_week(at))
+    }
+
+    /// Start of the first hour after `at` whose level differs from the level at `at`;
+    /// None when the whole week has one level
+    pub fn next_change(&self, direction: Direction, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let grid = self.grid(direction);
+        let hour = hour_of_week(at);
+        let current = Self::level_in(grid, hour);
+        let hour_start = at.duration_trunc(Duration::hours(1)).ok()?;
+        (1..HOURS_PER_WEEK)
+            .find(|ahead| Self::level_in(grid, (hour + ahead) % HOURS_PER_WEEK) != current)
+            .map(|ahead| hour_start + Duration::hours(ahead as i64))
+    }
+
+    fn level_in(grid: &[ActivityRange], hour: u16) -> ActivityLevel {
+        grid.iter()
+            .find(|range| range.start_hour <= hour && hour < range.end_hour)
+            .map(|range| range.level)
+            .unwrap_or(ActivityLevel::Normal)
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let grids = [
+            ("activity_schedule.ranges", Some(&self.ranges)),
+            ("activity_schedule.long_ranges", self.long_ranges.as_ref()),
+            ("activity_schedule.short_ranges", self.short_ranges.as_ref()),
+        ];
+        for (name, grid) in grids {
+            if let Some(grid) = grid {
+                Self::validate_grid(name, grid, &mut errors);
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_grid(name: &str, grid: &[ActivityRange], errors: &mut Vec<String>) {
+        if grid.is_empty() {
+            return;
+        }
+        let mut sorted = grid.to_vec();
+        sorted.sort_by_key(|range| range.start_hour);
+        let mut covered_to = 0;
+        for range in &sorted {
+            if range.start_hour >= range.end_hour || range.end_hour > HOURS_PER_WEEK {
+                errors.push(format!(
+                    "{} range {}..{} must end after it starts and by hour {}",
+                    name, range.start_hour, range.end_hour, HOURS_PER_WEEK
+                ));
+                continue;
+            }
+            if let ActivityLevel::ReducedSize(factor) = range.level {
+                if factor <= Decimal::ZERO || factor > Decimal::ONE {
+                    errors.push(format!(
+                        "{} range {}..{} size factor must be in (0, 1], got {}",
+                        name, range.start_hour, range.end_hour, factor
+                    ));
+                }
+            }
+            if range.start_hour < covered_to {
+                errors.push(format!(
+                    "{} range {}..{} overlaps hours {}..{}",
+                    name,
+                    range.start_hour,
+                    range.end_hour,
+                    range.start_hour,
+                    covered_to.min(range.end_hour)
+                ));
+            } else if range.start_hour > covered_to {
+                errors.push(format!("{} leaves hours {}..{} unscheduled", name, covered_to, range.start_hour));
+            }
+            covered_to = covered_to.max(range.end_hour);
+        }
+        if covered_to < HOURS_PER_WEEK {
+            errors.push(format!("{} leaves hours {}..{} unscheduled", name, covered_to, HOURS_PER_WEEK));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_validation_rejects_gaps_and_overlaps() {
+        let normal = ActivityLevel::Normal;
+        let week = ActivityScheduleConfig { ranges: vec![ActivityRange::new(0, HOURS_PER_WEEK, normal)], ..Default::default() };
+        assert!(week.validate().is_ok());
+        assert!(ActivityScheduleConfig::default().validate().is_ok());
+
+        let gapped = ActivityScheduleConfig {
+            ranges: vec![ActivityRange::new(0, 10, normal), ActivityRange::new(12, HOURS_PER_WEEK, normal)],
+            ..Default::default()
+        };
+        assert_eq!(gapped.validate(), Err(vec!["activity_schedule.ranges leaves hours 10..12 unscheduled".to_string()]));
+
+        let overlapping = ActivityScheduleConfig {
+            long_ranges: Some(vec![ActivityRange::new(0, 100, normal), ActivityRange::new(90, HOURS_PER_WEEK, normal)]),
+            ..week.clone()
+        };
+        assert_eq!(overlapping.validate(), Err(vec!["activity_schedule.long_ranges range 90..168 overlaps hours 90..100".to_string()]));
+
+        let short_week = ActivityScheduleConfig {
+            ranges: vec![ActivityRange::new(0, 100, ActivityLevel::ReducedSize(Decimal::TWO))],
+            ..Default::default()
+        };
+        assert_eq!(short_week.validate().unwrap_err().len(), 2);
+    }
+
+    #[test]
+    fn test_next_change_wraps_around_the_week() {
+        // Blocked 02:00-06:00 every day, Normal otherwise
+        let mut ranges = Vec::new();
+        for day in 0..7 {
+            let start = day * 24;
+            ranges.push(ActivityRange::new(start, start + 2, ActivityLevel::Normal));
+            ranges.push(ActivityRange::new(start + 2, start + 6, ActivityLevel::Blocked));
+            ranges.push(ActivityRange::new(start + 6, start + 24, ActivityLevel::Normal));
+        }
+        let schedule = ActivityScheduleConfig { ranges, ..Default::default() };
+        assert!(schedule.validate().is_ok());
+
+        // Sunday 2026-10-18 22:30 is Normal until Monday 02:00
+        let sunday_night = Utc.with_ymd_and_hms(2026, 10, 18, 22, 30, 0).unwrap();
+        assert_eq!(hour_of_week(sunday_night), 166);
+        assert_eq!(schedule.level_at(Direction::Long, sunday_night), ActivityLevel::Normal);
+        assert_eq!(schedule.next_change(Direction::Long, sunday_night), Some(Utc.with_ymd_and_hms(2026, 10, 19, 2, 0, 0).unwrap()));
+        assert_eq!(ActivityScheduleConfig::default().next_change(Direction::Long, sunday_night), None);
+    }
+}
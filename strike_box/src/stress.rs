@@ -0,0 +1,374 @@
+//! ============================================================
+//! PORTFOLIO STRESS SCENARIOS
+//! ============================================================
+//! Instantaneous shocks applied to a copy of the portfolio. Price
+//! shocks compose multiplicatively per position; a stop the shock
+//! crosses fills at the shocked price (the move gaps through it)
+//! less exit slippage, and that loss is booked as realized before
+//! drawdowns are measured, so the result ties out with
+//! `check_drawdown_limits` on the same state.
+//!
+//! Exit slippage comes from a constant-product pool with half of
+//! the position's entry liquidity on the side it sells into:
+//! selling x into depth d moves the fill by x / (d + x).
+//! LiquidityHaircut shrinks d, widening it.
+//! ============================================================
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Direction, PortfolioState, PositionStatus, RiskControllerConfig, StrikeBoxEngine, StressTestResult, SystemState};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Shock {
+    AllTokens { price_pct: Decimal },
+    ByDirection { direction: Direction, price_pct: Decimal },
+    ByToken { address: String, price_pct: Decimal },
+    LiquidityHaircut { pct: Decimal }, // Share of pool depth gone at exit
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StressScenario {
+    pub name: String,
+    pub shocks: Vec<Shock>,
+}
+
+impl StressScenario {
+    pub fn new(name: impl Into<String>, shocks: Vec<Shock>) -> Self {
+        Self { name: name.into(), shocks }
+    }
+
+    /// Every token down by `market_crash_trigger_pct`
+    pub fn crash(config: &RiskControllerConfig) -> Self {
+        Self::new("crash", vec![Shock::AllTokens { price_pct: -config.market_crash_trigger_pct }])
+    }
+
+    /// Shorted tokens up by `market_crash_trigger_pct`
+    pub fn squeeze(config: &RiskControllerConfig) -> Self {
+        Self::new(
+            "squeeze",
+            vec![Shock::ByDirection { direction: Direction::Short, price_pct: config.market_crash_trigger_pct }],
+        )
+    }
+
+    /// The crash move with `liquidity_crisis_trigger_pct` of depth gone
+    pub fn liquidity_crisis(config: &RiskControllerConfig) -> Self {
+        Self::new(
+            "liquidity_crisis",
+            vec![
+                Shock::AllTokens { price_pct: -config.market_crash_trigger_pct },
+                Shock::LiquidityHaircut { pct: config.liquidity_crisis_trigger_pct },
+            ],
+        )
+    }
+
+    pub fn built_in(config: &RiskControllerConfig) -> Vec<Self> {
+        vec![Self::crash(config), Self::squeeze(config), Self::liquidity_crisis(config)]
+    }
+
+    /// Combined price move for a position in `token_address` held in `direction`
+    pub fn price_pct_for(&self, token_address: &str, direction: Direction) -> Decimal {
+        let factor = self.shocks.iter().fold(Decimal::ONE, |factor, shock| {
+            let pct = match shock {
+                Shock::AllTokens { price_pct } => *price_pct,
+                Shock::ByDirection { direction: d, price_pct } if *d == direction => *price_pct,
+                Shock::ByToken { address, price_pct } if address.eq_ignore_ascii_case(token_address) => *price_pct,
+                _ => Decimal::ZERO,
+            };
+            factor * (Decimal::ONE + pct)
+        });
+        factor - Decimal::ONE
+    }
+
+    /// Share of pool depth lost across all haircuts
+    pub fn liquidity_haircut_pct(&self) -> Decimal {
+        let remaining = self.shocks.iter().fold(Decimal::ONE, |remaining, shock| match shock {
+            Shock::LiquidityHaircut { pct } => remaining * (Decimal::ONE - (*pct).clamp(Decimal::ZERO, Decimal::ONE)),
+            _ => remaining,
+        });
+        Decimal::ONE - remaining
+    }
+}
+
+/// Fractional fill-price slippage selling `exit_usd` into a pool of `liquidity_usd` after
+/// `haircut_pct` of it is gone
+pub fn exit_slippage_pct(exit_usd: Decimal, liquidity_usd: Decimal, haircut_pct: Decimal) -> Decimal {
+    let depth = liquidity_usd / Decimal::TWO * (Decimal::ONE - haircut_pct);
+    if exit_usd <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    if depth <= Decimal::ZERO {
+        return Decimal::ONE;
+    }
+    exit_usd / (depth + exit_usd)
+}
+
+/// A position whose stop the scenario crosses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopBreach {
+    pub execution_id: Uuid,
+    pub token_address: String,
+    pub direction: Direction,
+    pub stop_loss_price: Decimal,
+    pub shocked_price: Decimal,
+    pub fill_price: Decimal, // Shocked price less exit slippage
+    pub exit_slippage_pct: Decimal,
+    pub realized_pnl_usd: Decimal,
+}
+
+/// Post-shock drawdown against one halt threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HaltCheck {
+    pub limit: String, // RiskControllerConfig field
+    pub drawdown_pct: Decimal,
+    pub threshold_pct: Decimal,
+    pub breached: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StressResult {
+    pub scenario: String,
+    pub pnl_usd: Decimal, // Change in open P&L at shocked marks, before stops fill
+    pub stop_breaches: Vec<StopBreach>,
+    pub stop_slippage_usd: Decimal, // Extra loss from filling breached stops below the shocked mark
+    pub halt_checks: Vec<HaltCheck>,
+    pub resulting_state: SystemState, // What check_drawdown_limits calls for after the shock
+    pub post_net_exposure_pct: Decimal,
+    pub post_gross_exposure_pct: Decimal,
+}
+
+impl StrikeBoxEngine {
+    /// Each scenario applied to a copy of the current portfolio; nothing here mutates the engine
+    pub fn run_stress(&self, scenarios: &[StressScenario]) -> Vec<StressResult> {
+        scenarios.iter().map(|scenario| self.stress_scenario(scenario)).collect()
+    }
+
+    /// An AllTokens scenario of `price_shock_pct` (-0.2 is a 20% fall), its open P&L change
+    /// compared with the worst drawdown and the recovery time in the sampled portfolio history
+    pub fn stress_test(&self, price_shock_pct: Decimal) -> StressTestResult {
+        let scenario = StressScenario::new("price_shock", vec![Shock::AllTokens { price_pct: price_shock_pct }]);
+        let scenario_pnl_usd = self.stress_scenario(&scenario).pnl_usd;
+        let portfolio = &self.portfolio;
+        let equity = portfolio.total_capital_usd
+            + portfolio.long_book.unrealized_pnl_usd
+            + portfolio.short_book.unrealized_pnl_usd;
+        let scenario_drawdown_pct = if equity > Decimal::ZERO && scenario_pnl_usd < Decimal::ZERO {
+            -scenario_pnl_usd / equity
+        } else {
+            Decimal::ZERO
+        };
+
+        let history = self.portfolio_history.equity_points();
+        let worst_historical_drawdown_pct = history
+            .first()
+            .map_or(Decimal::ZERO, |(start, _)| PortfolioState::max_drawdown_since(*start, &history));
+        let vs_worst_historical = (worst_historical_drawdown_pct > Decimal::ZERO)
+            .then(|| scenario_drawdown_pct / worst_historical_drawdown_pct);
+
+        StressTestResult {
+            price_shock_pct,
+            scenario_pnl_usd,
+            scenario_drawdown_pct,
+            worst_historical_drawdown_pct,
+            vs_worst_historical,
+            historical_recovery_time: PortfolioState::recovery_time_from_trough(&history),
+        }
+    }
+
+    fn stress_scenario(&self, scenario: &StressScenario) -> StressResult {
+        let haircut = scenario.liquidity_haircut_pct();
+        let mut portfolio = self.portfolio.clone();
+        let mut pnl_usd = Decimal::ZERO;
+        let mut stop_slippage_usd = Decimal::ZERO;
+        let mut stop_breaches = Vec::new();
+
+        for book in [&mut portfolio.long_book, &mut portfolio.short_book] {
+            let mut realized = Decimal::ZERO;
+            let mut released = Decimal::ZERO;
+            for position in book.positions.iter_mut().filter(|p| p.is_open()) {
+                let before = position.unrealized_pnl_usd;
+                let shocked_price =
+                    position.current_price * (Decimal::ONE + scenario.price_pct_for(&position.token_address, position.direction));
+                position.current_price = shocked_price;
+                position.refresh_pnl();
+                pnl_usd += position.unrealized_pnl_usd - before;
+                if !position.stop_triggered() {
+                    continue;
+                }
+
+                let tokens = position.position_size_tokens * position.remaining_size_pct;
+                let slippage = exit_slippage_pct(tokens * shocked_price, position.liquidity_at_entry, haircut);
+                let fill_price = match position.direction {
+                    Direction::Long => shocked_price * (Decimal::ONE - slippage),
+                    Direction::Short => shocked_price * (Decimal::ONE + slippage),
+                };
+                let realized_pnl_usd = match position.direction {
+                    Direction::Long => (fill_price - position.entry_price) * tokens,
+                    Direction::Short => (position.entry_price - fill_price) * tokens,
+                };
+                stop_slippage_usd += position.unrealized_pnl_usd - realized_pnl_usd;
+                stop_breaches.push(StopBreach {
+                    execution_id: position.execution_id,
+                    token_address: position.token_address.clone(),
+                    direction: position.direction,
+                    stop_loss_price: position.stop_loss_price,
+                    shocked_price,
+                    fill_price,
+                    exit_slippage_pct: slippage,
+                    realized_pnl_usd,
+                });
+                realized += realized_pnl_usd;
+                released += position.position_size_usd * position.remaining_size_pct;
+                position.remaining_size_pct = Decimal::ZERO;
+                position.unrealized_pnl_usd = Decimal::ZERO;
+                position.status = PositionStatus::Closed;
+            }
+            book.realized_pnl_usd += realized;
+            book.total_allocation_usd = (book.total_allocation_usd - released).max(Decimal::ZERO);
+            book.update_unrealized_pnl();
+        }
+        portfolio.calculate_exposure();
+        portfolio.update_drawdowns();
+
+        let rc = &self.config.risk_controller;
+        let halt_checks = [
+            ("daily_realized_halt_pct", portfolio.daily_realized_drawdown_pct, rc.daily_realized_halt_pct),
+            ("daily_unrealized_soft_halt_pct", portfolio.daily_unrealized_drawdown_pct, rc.daily_unrealized_soft_halt_pct),
+            ("weekly_drawdown_halt_pct", portfolio.weekly_drawdown_pct, rc.weekly_drawdown_halt_pct),
+            ("monthly_review_pct", portfolio.monthly_drawdown_pct, rc.monthly_review_pct),
+        ]
+        .into_iter()
+        .map(|(limit, drawdown_pct, threshold_pct)| HaltCheck {
+            limit: limit.to_string(),
+            drawdown_pct,
+            threshold_pct,
+            breached: drawdown_pct >= threshold_pct,
+        })
+        .collect();
+
+        StressResult {
+            scenario: scenario.name.clone(),
+            pnl_usd,
+            stop_breaches,
+            stop_slippage_usd,
+            halt_checks,
+            resulting_state: portfolio.check_drawdown_limits(rc),
+            post_net_exposure_pct: portfolio.net_exposure_pct,
+            post_gross_exposure_pct: portfolio.gross_exposure_pct,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StrikeBoxConfig;
+
+    fn book_position(engine: &mut StrikeBoxEngine, address: &str, direction: Direction, stop: i64) {
+        let position = crate::Position {
+            execution_id: Uuid::new_v4(),
+            token_address: address.to_string(),
+            token_symbol: address.to_uppercase(),
+            direction,
+            entry_price: Decimal::new(100, 0),
+            current_price: Decimal::new(100, 0),
+            position_size_tokens: Decimal::new(100, 0),
+            position_size_usd: Decimal::new(10_000, 0),
+            remaining_size_pct: Decimal::ONE,
+            liquidity_at_entry: Decimal::new(200_000, 0),
+            safety_score_at_entry: Decimal::new(80, 2),
+            holder_count_at_entry: 60,
+            stop_loss_price: Decimal::new(stop, 0),
+            take_profit_targets: Vec::new(),
+            risk_approval_id: Uuid::new_v4(),
+            opened_at: chrono::Utc::now(),
+            time_stop_at: None,
+            status: PositionStatus::Open,
+            closed_at: None,
+            trailing_stop_active: false,
+            trailing_stop_high: None,
+            trailing_activation_price: None,
+            unrealized_pnl_usd: Decimal::ZERO,
+            unrealized_pnl_pct: Decimal::ZERO,
+            max_favorable_excursion_pct: Decimal::ZERO,
+            max_adverse_excursion_pct: Decimal::ZERO,
+            leverage: Decimal::ONE,
+            venue: crate::DEX_VENUE.to_string(),
+            safety_breakdown_at_entry: None,
+            score_history: Vec::new(),
+        };
+        engine.book_position(position);
+    }
+
+    fn two_position_engine() -> StrikeBoxEngine {
+        // $100,000 capital: a long and a short, 100 tokens each at $100 in $200,000 pools
+        let mut engine = StrikeBoxEngine::new(StrikeBoxConfig::default(), Decimal::new(100_000, 0));
+        book_position(&mut engine, "0xlong", Direction::Long, 90);
+        book_position(&mut engine, "0xshort", Direction::Short, 120);
+        engine.tick();
+        engine
+    }
+
+    #[test]
+    fn test_crash_breaches_long_stop_with_exit_slippage() {
+        let engine = two_position_engine();
+        let results = engine.run_stress(&StressScenario::built_in(&engine.config.risk_controller));
+        let [crash, squeeze, crisis] = [&results[0], &results[1], &results[2]];
+
+        // -15%: the long loses $1,500 and the short gains $1,500 at the shocked marks
+        assert_eq!(crash.pnl_usd, Decimal::ZERO);
+        // Long stop at 90 crossed at 85; $8,500 sold into $100,000 of depth slips 8500/108500
+        assert_eq!(crash.stop_breaches.len(), 1);
+        let breach = &crash.stop_breaches[0];
+        assert_eq!((breach.token_address.as_str(), breach.shocked_price), ("0xlong", Decimal::new(85, 0)));
+        let slippage = Decimal::new(8_500, 0) / Decimal::new(108_500, 0);
+        assert_eq!(breach.exit_slippage_pct, slippage);
+        assert_eq!(breach.realized_pnl_usd, (Decimal::new(85, 0) * (Decimal::ONE - slippage) - Decimal::new(100, 0)) * Decimal::new(100, 0));
+        assert_eq!(crash.stop_slippage_usd, Decimal::new(-1_500, 0) - breach.realized_pnl_usd);
+        // The long's $10,000 is released: only the short remains, net -10%
+        assert_eq!(crash.post_net_exposure_pct, Decimal::new(-1, 1));
+        assert_eq!(crash.post_gross_exposure_pct, Decimal::new(1, 1));
+        // About $2,166 realized on $100,000 stays under the 5% realized halt
+        assert_eq!(crash.resulting_state, SystemState::Active);
+
+        // +15% on shorts only: $1,500 open loss, short stop at 120 not reached
+        assert_eq!(squeeze.pnl_usd, Decimal::new(-1_500, 0));
+        assert!(squeeze.stop_breaches.is_empty());
+        assert_eq!(squeeze.halt_checks[1].drawdown_pct, Decimal::new(15, 3));
+
+        // Half the depth gone: the same crash fills worse
+        let crisis_slippage = Decimal::new(8_500, 0) / Decimal::new(58_500, 0);
+        assert_eq!(crisis.stop_breaches[0].exit_slippage_pct, crisis_slippage);
+        assert!(crisis.stop_breaches[0].realized_pnl_usd < breach.realized_pnl_usd);
+
+        // Nothing was mutated
+        assert_eq!(engine.portfolio.long_book.positions[0].current_price, Decimal::new(100, 0));
+        assert_eq!(engine.portfolio.long_book.realized_pnl_usd, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_token_shock_composes_and_trips_halts() {
+        let engine = two_position_engine();
+        let scenario = StressScenario::new(
+            "short squeeze on one name",
+            vec![
+                Shock::ByToken { address: "0xSHORT".to_string(), price_pct: Decimal::new(50, 2) },
+                Shock::ByDirection { direction: Direction::Short, price_pct: Decimal::new(10, 2) },
+            ],
+        );
+        assert_eq!(scenario.price_pct_for("0xshort", Direction::Short), Decimal::new(65, 2));
+        assert_eq!(scenario.price_pct_for("0xlong", Direction::Long), Decimal::ZERO);
+
+        let result = &engine.run_stress(&[scenario])[0];
+        // 100 * 1.65 = 165, bought back into $100,000 of depth: 16,500 / 116,500 slippage
+        let slippage = Decimal::new(16_500, 0) / Decimal::new(116_500, 0);
+        let fill = Decimal::new(165, 0) * (Decimal::ONE + slippage);
+        assert_eq!(result.pnl_usd, Decimal::new(-6_500, 0));
+        assert_eq!(result.stop_breaches[0].realized_pnl_usd, (Decimal::new(100, 0) - fill) * Decimal::new(100, 0));
+        let realized_check = &result.halt_checks[0];
+        assert_eq!(realized_check.limit, "daily_realized_halt_pct");
+        assert!(realized_check.breached);
+        assert_eq!(result.resulting_state, SystemState::PausedAll);
+    }
+}
@@ -0,0 +1,131 @@
+//! ============================================================
+//! TRADE RECORDS
+//! ============================================================
+//! One record per entry, joining its EntryLog with the ExitLogs
+//! that share its execution id (partial take-profits, then the
+//! final stop, time stop or manual exit) and with the booked
+//! position for status, mark and excursions. Reports that tell a
+//! trade's story should read these instead of re-joining logs.
+//!
+//! Trades are built from the retained entry logs, so a trade
+//! whose entry was evicted drops out even if exits remain.
+//! ============================================================
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{Direction, EntryLog, ExitLog, PositionStatus, StrikeBoxEngine};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeStatus {
+    Open, // Size remains, possibly after partial exits
+    Closed,
+    Liquidated,
+}
+
+/// Which trades `trade_records` returns; the default matches every trade
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradeFilter {
+    pub token_address: Option<String>,
+    pub direction: Option<Direction>,
+    pub status: Option<TradeStatus>,
+    pub opened_since: Option<DateTime<Utc>>,
+}
+
+impl TradeFilter {
+    fn matches_entry(&self, entry: &EntryLog) -> bool {
+        self.token_address.as_ref().is_none_or(|a| *a == entry.token_address)
+            && self.direction.is_none_or(|d| d == entry.direction)
+            && self.opened_since.is_none_or(|since| entry.timestamp >= since)
+    }
+}
+
+/// What remains of an open trade, valued at its current mark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProvisionalExit {
+    pub mark_price: Decimal,
+    pub remaining_size_pct: Decimal,
+    pub unrealized_pnl_usd: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub entry: EntryLog,
+    pub exits: Vec<ExitLog>, // Oldest first
+    pub provisional_exit: Option<ProvisionalExit>, // Open trades with a booked position only
+    pub realized_pnl_usd: Decimal,
+    pub weighted_avg_exit_price: Option<Decimal>, // Over realized exits by size; None before the first
+    pub hold_duration_seconds: u64, // To the final exit, or to now while open
+    pub max_favorable_excursion_pct: Option<Decimal>, // None when the position is no longer booked
+    pub max_adverse_excursion_pct: Option<Decimal>,
+    pub status: TradeStatus,
+}
+
+impl TradeRecord {
+    /// Share of the original size exited so far
+    pub fn exited_size_pct(&self) -> Decimal {
+        self.exits.iter().map(|e| e.exit_size_pct).sum()
+    }
+}
+
+impl StrikeBoxEngine {
+    /// Every retained trade matching `filter`, oldest entry first
+    pub fn trade_records(&self, filter: &TradeFilter) -> Vec<TradeRecord> {
+        let now = Utc::now();
+        let mut records: Vec<TradeRecord> = self
+            .entry_logs
+            .iter()
+            .filter(|entry| filter.matches_entry(entry))
+            .map(|entry| self.trade_record(entry, now))
+            .filter(|record| filter.status.is_none_or(|s| s == record.status))
+            .collect();
+        records.sort_by_key(|r| r.entry.timestamp);
+        records
+    }
+
+    fn trade_record(&self, entry: &EntryLog, now: DateTime<Utc>) -> TradeRecord {
+        let mut exits: Vec<ExitLog> =
+            self.exit_logs.iter().filter(|e| e.execution_id == entry.execution_id).cloned().collect();
+        exits.sort_by_key(|e| e.timestamp);
+        let position = self
+            .portfolio
+            .long_book
+            .positions
+            .iter()
+            .chain(self.portfolio.short_book.positions.iter())
+            .find(|p| p.execution_id == entry.execution_id);
+
+        let exited_pct: Decimal = exits.iter().map(|e| e.exit_size_pct).sum();
+        let status = match position.map(|p| p.status) {
+            Some(PositionStatus::Liquidated) => TradeStatus::Liquidated,
+            Some(PositionStatus::Closed) => TradeStatus::Closed,
+            Some(_) => TradeStatus::Open,
+            None if exited_pct >= Decimal::ONE => TradeStatus::Closed,
+            None => TradeStatus::Open,
+        };
+        let provisional_exit = position.filter(|_| status == TradeStatus::Open).map(|p| ProvisionalExit {
+            mark_price: p.current_price,
+            remaining_size_pct: p.remaining_size_pct,
+            unrealized_pnl_usd: p.unrealized_pnl_usd,
+        });
+        let weighted_avg_exit_price = (exited_pct > Decimal::ZERO)
+            .then(|| exits.iter().map(|e| e.exit_price * e.exit_size_pct).sum::<Decimal>() / exited_pct);
+        let hold_duration_seconds = match (status, exits.last()) {
+            (TradeStatus::Open, _) | (_, None) => (now - entry.timestamp).num_seconds().max(0) as u64,
+            (_, Some(last)) => last.hold_duration_seconds,
+        };
+
+        TradeRecord {
+            entry: entry.clone(),
+            realized_pnl_usd: exits.iter().map(|e| e.realized_pnl_usd).sum(),
+            weighted_avg_exit_price,
+            hold_duration_seconds,
+            max_favorable_excursion_pct: position.map(|p| p.max_favorable_excursion_pct),
+            max_adverse_excursion_pct: position.map(|p| p.max_adverse_excursion_pct),
+            provisional_exit,
+            status,
+            exits,
+        }
+    }
+}
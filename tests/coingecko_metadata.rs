@@ -0,0 +1,138 @@
+// CoinGecko token metadata tests against a local mock server
+// Fixtures under tests/fixtures/coingecko are recorded CoinGecko responses
+
+use chrono::{TimeZone, Utc};
+use macro_strike_bot_fixed::api::coingecko::CoinGeckoClient;
+use macro_strike_bot_fixed::api::enrichment::TokenEnricher;
+use macro_strike_bot_fixed::api::{ApiConfig, ApiError};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use strike_box::{SnapshotSource, TokenSnapshot};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const LINK_ETHEREUM: &str = "0x514910771af9ca656af840dff83e8264ecf986ca";
+const UNLISTED: &str = "0x000000000000000000000000000000000000dead";
+
+fn fixture(name: &str) -> serde_json::Value {
+    let raw = match name {
+        "coin_chainlink" => include_str!("fixtures/coingecko/coin_chainlink.json"),
+        "coin_not_found" => include_str!("fixtures/coingecko/coin_not_found.json"),
+        other => panic!("unknown fixture {}", other),
+    };
+    serde_json::from_str(raw).unwrap()
+}
+
+fn client(server: &MockServer) -> CoinGeckoClient {
+    CoinGeckoClient::new(ApiConfig {
+        api_key: "test-key".to_string(),
+        api_secret: String::new(),
+        testnet: true,
+        rate_limit_per_minute: 60_000,
+    })
+    .with_base_url(server.uri())
+}
+
+fn dex_snapshot(token_address: &str) -> TokenSnapshot {
+    let now = Utc::now();
+    TokenSnapshot {
+        token_address: token_address.to_string(),
+        token_symbol: "LINK".to_string(),
+        liquidity_usd: Decimal::new(750_000, 0),
+        bid_depth_usd: Decimal::new(375_000, 0),
+        ask_depth_usd: Decimal::new(375_000, 0),
+        holder_count: 60,
+        top_10_concentration_pct: Decimal::new(45, 2),
+        largest_wallet_pct: Decimal::new(12, 2),
+        token_age_hours: 0,
+        contract_verified: true,
+        is_proxy_contract: false,
+        deployment_timestamp: now,
+        snapshot_timestamp: now,
+        source: SnapshotSource::Dex,
+        categories: None,
+    }
+}
+
+#[tokio::test]
+async fn test_multi_chain_metadata_is_parsed_and_cached() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/coins/chainlink"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("coin_chainlink")))
+        .expect(1) // Second lookup is served from the cache
+        .mount(&server)
+        .await;
+    let coingecko = client(&server);
+
+    let metadata = coingecko.get_token_metadata("chainlink").await.unwrap();
+    assert_eq!(metadata.symbol, "LINK");
+    // The empty Solana entry is not a deployment
+    assert_eq!(metadata.contract_addresses.len(), 4);
+    assert_eq!(metadata.contract_addresses["ethereum"], LINK_ETHEREUM);
+    assert_eq!(metadata.contract_addresses["arbitrum-one"], "0xf97f4df75117a78c1a5a0dbb814af92458539fb4");
+    assert_eq!(metadata.listed_at, Some(Utc.with_ymd_and_hms(2017, 9, 16, 0, 0, 0).unwrap()));
+    assert_eq!(metadata.categories[0], "Oracle");
+    assert_eq!(metadata.market_cap_rank, Some(14));
+
+    assert_eq!(coingecko.get_token_metadata("chainlink").await.unwrap(), metadata);
+}
+
+#[tokio::test]
+async fn test_contract_lookup_enriches_snapshot() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!("/coins/ethereum/contract/{}", LINK_ETHEREUM)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("coin_chainlink")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let enricher = TokenEnricher::new(Arc::new(client(&server)), "ethereum");
+
+    // Checksummed addresses resolve to the same lowercase lookup
+    let mut snapshot = dex_snapshot("0x514910771AF9Ca656af840dff83E8264EcF986CA");
+    let metadata = enricher.enrich(&mut snapshot).await.unwrap();
+    assert_eq!(metadata.id, "chainlink");
+    assert!(snapshot.token_age_hours > 24 * 365 * 8);
+    assert_eq!(Some(snapshot.deployment_timestamp), metadata.listed_at);
+    assert_eq!(snapshot.sector(), Some("Oracle"));
+    assert_eq!(snapshot.categories.as_ref().map(Vec::len), Some(4));
+}
+
+#[tokio::test]
+async fn test_unlisted_token_is_typed_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!("/coins/ethereum/contract/{}", UNLISTED)))
+        .respond_with(ResponseTemplate::new(404).set_body_json(fixture("coin_not_found")))
+        .mount(&server)
+        .await;
+    let enricher = TokenEnricher::new(Arc::new(client(&server)), "ethereum");
+
+    let mut snapshot = dex_snapshot(UNLISTED);
+    let error = enricher.enrich(&mut snapshot).await.unwrap_err();
+    assert!(matches!(error.downcast_ref::<ApiError>(), Some(ApiError::NotFound(_))));
+    assert!(TokenEnricher::is_not_found(error.as_ref()));
+    // Nothing is fabricated for the caller to mistake for real data
+    assert_eq!(snapshot.token_age_hours, 0);
+    assert_eq!(snapshot.categories, None);
+}
+
+#[tokio::test]
+async fn test_metadata_cache_persists_across_clients() {
+    let cache_path = std::env::temp_dir().join(format!("coingecko_metadata_{}.json", uuid::Uuid::new_v4()));
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/coins/chainlink"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("coin_chainlink")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let fetched = client(&server).with_metadata_cache(&cache_path).get_token_metadata("chainlink").await.unwrap();
+
+    // A fresh client against a server with no routes answers from the file
+    let empty = MockServer::start().await;
+    let cached = client(&empty).with_metadata_cache(&cache_path).get_token_metadata("chainlink").await.unwrap();
+    assert_eq!(cached, fetched);
+    std::fs::remove_file(&cache_path).ok();
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod elite_quant_tests {
-    use macro_strk_bot::elite_quant_framework::*;
+    use macro_strike_bot_fixed::elite_quant_framework::*;
     use tokio;
 
     #[tokio::test]
@@ -300,7 +300,7 @@ mod elite_quant_tests {
 
 #[cfg(test)]
 mod integration_tests {
-    use macro_strk_bot::elite_quant_framework::*;
+    use macro_strike_bot_fixed::elite_quant_framework::*;
     use std::time::Duration;
     use tokio;
 